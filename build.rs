@@ -1,26 +1,43 @@
 use std::env;
-use std::fs::{File, metadata};
-use std::process::Command;
+use std::fs;
+use std::path::Path;
 
-// Change the filename below if you use a different format
-const PRELOADED_FORMAT: &str = "plain.fmt";
+// The formats embedded in the binary, separated by commas. Another list
+// can be given with the environment variable TEX_RUST_FORMATS, the first
+// format being the one loaded when the program name is not a format name.
+const EMBEDDED_FORMATS: &str = "plain.fmt";
 
 fn main() {
-    let path = env::var("OUT_DIR").unwrap() + "/preloaded_format.fmt";
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let (list, required) = match env::var("TEX_RUST_FORMATS") {
+        Ok(list) => (list, true),
+        Err(_) => (EMBEDDED_FORMATS.to_string(), false)
+    };
 
-    println!("cargo:rustc-env=PRELOADED_FORMAT={PRELOADED_FORMAT}");
-    match metadata(PRELOADED_FORMAT) {
-        Ok(_) => {
-            _ = Command::new("cp")
-                .args([PRELOADED_FORMAT, &path])
-                .status()
-                .unwrap();
-        },
-        Err(_) => {
-            _ = File::create(path)
-                .expect("cannot create empty file")
+    let mut names = vec![];
+    let mut entries = String::new();
+    for file in list.split(',').map(str::trim).filter(|file| !file.is_empty()) {
+        println!("cargo:rerun-if-changed={file}");
+        let path = Path::new(file);
+        if !path.exists() {
+            // The default format is only embedded if it has been dumped.
+            if required {
+                panic!("cannot find the format file {file}");
+            }
+            continue;
         }
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        if names.contains(&name) {
+            panic!("two embedded formats are named {name}");
+        }
+        let copy = format!("{out_dir}/{name}.fmt");
+        fs::copy(path, &copy).expect("cannot copy the format file");
+        entries.push_str(&format!("    ({name:?}, include_bytes!({copy:?})),\n"));
+        names.push(name);
     }
+    fs::write(format!("{out_dir}/embedded_formats.rs"), format!("&[\n{entries}]\n"))
+        .expect("cannot write the list of embedded formats");
+
+    println!("cargo:rerun-if-env-changed=TEX_RUST_FORMATS");
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=plain.fmt");
 }
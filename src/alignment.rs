@@ -1,16 +1,6 @@
 
 use crate::constants::*; 
-use crate::datastructures::{
-    mem, mem_mut, Status, depth, depth_mut, display_indent, every_cr, global_defs,
-    glue_order, glue_order_mut, glue_ptr, glue_ptr_mut, glue_ref_count_mut,
-    glue_set, glue_set_mut, glue_shrink, glue_shrink_mut, glue_sign,
-    glue_sign_mut, glue_stretch, glue_stretch_mut, height, height_mut,
-    info, info_mut, link, link_mut, list_ptr, llink, llink_mut, overfull_rule,
-    overfull_rule_mut, post_display_penalty, pre_display_penalty, rlink,
-    rlink_mut, shift_amount_mut, shrink, shrink_order, span_count,
-    span_count_mut, stretch, stretch_order, subtype_mut, r#type, type_mut,
-    width, width_mut
-};
+use crate::datastructures::Status;
 use crate::error::{TeXResult, TeXError};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Real, Scaled, SmallNumber,
@@ -22,52 +12,54 @@ use std::cmp::Ordering::{Equal, Greater, Less};
 
 // Part 37: Alignment
 
-// Section 769
-fn u_part(p: HalfWord) -> Integer {
-    mem((p + HEIGHT_OFFSET) as usize).int()
-}
+impl Global {
+    // Section 769
+    fn u_part(&self, p: HalfWord) -> Integer {
+        self.mem((p + HEIGHT_OFFSET) as usize).int()
+    }
 
-fn u_part_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + HEIGHT_OFFSET) as usize).int_mut()
-}
+    fn u_part_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + HEIGHT_OFFSET) as usize).int_mut()
+    }
 
-pub(crate) fn v_part(p: HalfWord) -> Integer {
-    mem((p + DEPTH_OFFSET) as usize).int()
-}
+    pub(crate) fn v_part(&self, p: HalfWord) -> Integer {
+        self.mem((p + DEPTH_OFFSET) as usize).int()
+    }
 
-fn v_part_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + DEPTH_OFFSET) as usize).int_mut()
-}
+    fn v_part_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + DEPTH_OFFSET) as usize).int_mut()
+    }
 
-pub(crate) fn extra_info(p: HalfWord) -> HalfWord {
-    info(p + LIST_OFFSET)
-}
+    pub(crate) fn extra_info(&self, p: HalfWord) -> HalfWord {
+        self.info(p + LIST_OFFSET)
+    }
 
-pub(crate) fn extra_info_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + LIST_OFFSET)
-}
+    pub(crate) fn extra_info_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + LIST_OFFSET)
+    }
 
-// Section 770
-fn preamble() -> HalfWord {
-    link(ALIGN_HEAD)
-}
+    // Section 770
+    fn preamble(&self) -> HalfWord {
+        self.link(self.align_head())
+    }
 
-fn preamble_mut() -> &'static mut HalfWord {
-    link_mut(ALIGN_HEAD)
+    fn preamble_mut(&mut self) -> &mut HalfWord {
+        self.link_mut(self.align_head())
+    }
 }
 
 impl Global {
     // Section 772
     fn push_alignment(&mut self) -> TeXResult<()> {
         let p = self.get_node(ALIGN_STACK_NODE_SIZE)?;
-        *link_mut(p) = self.align_ptr;
-        *info_mut(p) = self.cur_align;
-        *llink_mut(p) = preamble();
-        *rlink_mut(p) = self.cur_span;
-        *mem_mut((p + 2) as usize).int_mut() = self.cur_loop;
-        *mem_mut((p + 3) as usize).int_mut() = self.align_state;
-        *info_mut(p + 4) = self.cur_head;
-        *link_mut(p + 4) = self.cur_tail;
+        *self.link_mut(p) = self.align_ptr;
+        *self.info_mut(p) = self.cur_align;
+        *self.llink_mut(p) = self.preamble();
+        *self.rlink_mut(p) = self.cur_span;
+        *self.mem_mut((p + 2) as usize).int_mut() = self.cur_loop;
+        *self.mem_mut((p + 3) as usize).int_mut() = self.align_state;
+        *self.info_mut(p + 4) = self.cur_head;
+        *self.link_mut(p + 4) = self.cur_tail;
         self.align_ptr = p;
         self.cur_head = self.get_avail()?;
         Ok(())
@@ -76,14 +68,14 @@ impl Global {
     fn pop_alignment(&mut self) {
         free_avail!(self, self.cur_head);
         let p = self.align_ptr;
-        self.cur_tail = link(p + 4);
-        self.cur_head = info(p + 4);
-        self.align_state = mem((p + 3) as usize).int();
-        self.cur_loop = mem((p + 2) as usize).int();
-        self.cur_span = rlink(p);
-        *preamble_mut() = llink(p);
-        self.cur_align = info(p);
-        self.align_ptr = link(p);
+        self.cur_tail = self.link(p + 4);
+        self.cur_head = self.info(p + 4);
+        self.align_state = self.mem((p + 3) as usize).int();
+        self.cur_loop = self.mem((p + 2) as usize).int();
+        self.cur_span = self.rlink(p);
+        *self.preamble_mut() = self.llink(p);
+        self.cur_align = self.info(p);
+        self.align_ptr = self.link(p);
         self.free_node(p, ALIGN_STACK_NODE_SIZE);
     }
 
@@ -97,7 +89,8 @@ impl Global {
         if self.mode() == MMODE
             && (self.tail() != self.head() || self.incomplete_noad() != NULL)
         {
-            return Err(TeXError::ImproperHalignDisplay);
+            self.error(TeXError::ImproperHalignDisplay)?;
+            self.flush_math()?;
         }
         // End section 776
 
@@ -116,16 +109,16 @@ impl Global {
         self.scan_spec(ALIGN_GROUP, false)?;
 
         // Section 777
-        *preamble_mut() = NULL;
-        self.cur_align = ALIGN_HEAD;
+        *self.preamble_mut() = NULL;
+        self.cur_align = self.align_head();
         self.cur_loop = NULL;
         self.scanner_status = Status::Aligning;
         self.warning_index = save_cs_ptr;
         self.align_state = -1_000_000;
         'sec777: loop {
             // Section 778
-            *link_mut(self.cur_align) = self.new_param_glue(TAB_SKIP_CODE as SmallNumber)?;
-            self.cur_align = link(self.cur_align);
+            *self.link_mut(self.cur_align) = self.new_param_glue(TAB_SKIP_CODE as SmallNumber)?;
+            self.cur_align = self.link(self.cur_align);
             // End section 778
 
             if self.cur_cmd == CAR_RET {
@@ -134,8 +127,8 @@ impl Global {
 
             // Section 779
             // Section 783
-            let mut p = HOLD_HEAD;
-            *link_mut(p) = NULL;
+            let mut p = self.hold_head();
+            *self.link_mut(p) = NULL;
             'sec783: loop {
                 self.get_preamble_token()?;
                 if self.cur_cmd == MAC_PARAM {
@@ -145,34 +138,35 @@ impl Global {
                     && self.cur_cmd >= TAB_MARK
                     && self.align_state == -1_000_000
                 {
-                    if p == HOLD_HEAD
+                    if p == self.hold_head()
                         && self.cur_loop == NULL
                         && self.cur_cmd == TAB_MARK
                     {
                         self.cur_loop = self.cur_align;
                     }
                     else {
-                        return Err(TeXError::MissingCroisillonAlign);
+                        self.back_error(TeXError::MissingCroisillonAlign)?;
+                        break 'sec783; // Goto done1
                     }
                 }
-                else if self.cur_cmd != SPACER || p != HOLD_HEAD {
-                    *link_mut(p) = self.get_avail()?;
-                    p = link(p);
-                    *info_mut(p) = self.cur_tok;
+                else if self.cur_cmd != SPACER || p != self.hold_head() {
+                    *self.link_mut(p) = self.get_avail()?;
+                    p = self.link(p);
+                    *self.info_mut(p) = self.cur_tok;
                 }
             }
             // done1:
             // End section 783
             
-            *link_mut(self.cur_align) = self.new_null_box()?;
-            self.cur_align = link(self.cur_align);
-            *info_mut(self.cur_align) = END_SPAN;
-            *width_mut(self.cur_align) = NULL_FLAG;
-            *u_part_mut(self.cur_align) = link(HOLD_HEAD);
+            *self.link_mut(self.cur_align) = self.new_null_box()?;
+            self.cur_align = self.link(self.cur_align);
+            *self.info_mut(self.cur_align) = self.end_span();
+            *self.width_mut(self.cur_align) = NULL_FLAG;
+            *self.u_part_mut(self.cur_align) = self.link(self.hold_head());
 
             // Section 784
-            p = HOLD_HEAD;
-            *link_mut(p) = NULL;
+            p = self.hold_head();
+            *self.link_mut(p) = NULL;
             'sec784: loop {
                 self.get_preamble_token()?;
                 if self.cur_cmd <= CAR_RET
@@ -182,19 +176,20 @@ impl Global {
                     break 'sec784; // Goto done2
                 }
                 if self.cur_cmd == MAC_PARAM {
-                    return Err(TeXError::OnlyOneCroisillonAllowed);
+                    self.error(TeXError::OnlyOneCroisillonAllowed)?;
+                    continue 'sec784; // Goto continue
                 }
-                *link_mut(p) = self.get_avail()?;
-                p = link(p);
-                *info_mut(p) = self.cur_tok;
+                *self.link_mut(p) = self.get_avail()?;
+                p = self.link(p);
+                *self.info_mut(p) = self.cur_tok;
             }
             // done2:
-            *link_mut(p) = self.get_avail()?;
-            p = link(p);
-            *info_mut(p) = END_TEMPLATE_TOKEN;
+            *self.link_mut(p) = self.get_avail()?;
+            p = self.link(p);
+            *self.info_mut(p) = END_TEMPLATE_TOKEN;
             // End section 784
 
-            *v_part_mut(self.cur_align) = link(HOLD_HEAD);
+            *self.v_part_mut(self.cur_align) = self.link(self.hold_head());
             // End section 779
         }
 
@@ -203,8 +198,8 @@ impl Global {
         // End section 777
 
         self.new_save_level(ALIGN_GROUP)?;
-        if every_cr() != NULL {
-            self.begin_token_list(every_cr(), EVERY_CR_TEXT)?;
+        if self.every_cr() != NULL {
+            self.begin_token_list(self.every_cr(), EVERY_CR_TEXT)?;
         }
         self.align_peek()?;
         Ok(())
@@ -230,7 +225,7 @@ impl Global {
             {
                 self.scan_optional_equals()?;
                 self.scan_glue(GLUE_VAL as QuarterWord)?;
-                if global_defs() > 0 {
+                if self.global_defs() > 0 {
                     self.geq_define(GLUE_BASE + TAB_SKIP_CODE, GLUE_REF, self.cur_val)?;
                 }
                 else {
@@ -285,9 +280,9 @@ impl Global {
         else {
             *self.prev_depth_mut() = 0;
         }
-        tail_append!(self, self.new_glue(glue_ptr(preamble()))?);
-        *subtype_mut(self.tail()) = (TAB_SKIP_CODE + 1) as QuarterWord;
-        self.cur_align = link(preamble());
+        tail_append!(self, self.new_glue(self.glue_ptr(self.preamble()))?);
+        *self.subtype_mut(self.tail()) = (TAB_SKIP_CODE + 1) as QuarterWord;
+        self.cur_align = self.link(self.preamble());
         self.cur_tail = self.cur_head;
         self.init_span(self.cur_align)?;
         Ok(())
@@ -309,13 +304,13 @@ impl Global {
 
     // Section 788
     fn init_col(&mut self) -> TeXResult<()> {
-        *extra_info_mut(self.cur_align) = self.cur_cmd as HalfWord;
+        *self.extra_info_mut(self.cur_align) = self.cur_cmd as HalfWord;
         if self.cur_cmd == OMIT {
             self.align_state = 0;
         }
         else {
             self.back_input()?;
-            self.begin_token_list(u_part(self.cur_align), U_TEMPLATE)?;
+            self.begin_token_list(self.u_part(self.cur_align), U_TEMPLATE)?;
         }
         Ok(())
     }
@@ -326,7 +321,7 @@ impl Global {
             return Err(TeXError::Confusion("endv"));
         }
 
-        let mut q = link(self.cur_align);
+        let mut q = self.link(self.cur_align);
         if q == NULL {
             return Err(TeXError::Confusion("endv"));
         }
@@ -335,68 +330,69 @@ impl Global {
             return Err(TeXError::Fatal("(interwoven alignment preambles are not allowed)"));
         }
 
-        let mut p = link(q);
+        let mut p = self.link(q);
 
         // Section 792
-        if p == NULL && extra_info(self.cur_align) < CR_CODE {
+        if p == NULL && self.extra_info(self.cur_align) < CR_CODE {
             if self.cur_loop != NULL {
                 // Section 793
-                *link_mut(q) = self.new_null_box()?;
-                p = link(q);
-                *info_mut(p) = END_SPAN;
-                *width_mut(p) = NULL_FLAG;
-                self.cur_loop = link(self.cur_loop);
+                *self.link_mut(q) = self.new_null_box()?;
+                p = self.link(q);
+                *self.info_mut(p) = self.end_span();
+                *self.width_mut(p) = NULL_FLAG;
+                self.cur_loop = self.link(self.cur_loop);
 
                 // Setion 794
-                q = HOLD_HEAD;
-                let mut r = u_part(self.cur_loop);
+                q = self.hold_head();
+                let mut r = self.u_part(self.cur_loop);
                 while r != NULL {
-                    *link_mut(q) = self.get_avail()?;
-                    q = link(q);
-                    *info_mut(q) = info(r);
-                    r = link(r);
+                    *self.link_mut(q) = self.get_avail()?;
+                    q = self.link(q);
+                    *self.info_mut(q) = self.info(r);
+                    r = self.link(r);
                 }
-                *link_mut(q) = NULL;
-                *u_part_mut(p) = link(HOLD_HEAD);
-                q = HOLD_HEAD;
-                r = v_part(self.cur_loop);
+                *self.link_mut(q) = NULL;
+                *self.u_part_mut(p) = self.link(self.hold_head());
+                q = self.hold_head();
+                r = self.v_part(self.cur_loop);
                 while r != NULL {
-                    *link_mut(q) = self.get_avail()?;
-                    q = link(q);
-                    *info_mut(q) = info(r);
-                    r = link(r);
+                    *self.link_mut(q) = self.get_avail()?;
+                    q = self.link(q);
+                    *self.info_mut(q) = self.info(r);
+                    r = self.link(r);
                 }
-                *link_mut(q) = NULL;
-                *v_part_mut(p) = link(HOLD_HEAD);
+                *self.link_mut(q) = NULL;
+                *self.v_part_mut(p) = self.link(self.hold_head());
                 // End section 794
 
-                self.cur_loop = link(self.cur_loop);
-                *link_mut(p) = self.new_glue(glue_ptr(self.cur_loop))?;
-                *subtype_mut(link(p)) = (TAB_SKIP_CODE + 1) as QuarterWord;
+                self.cur_loop = self.link(self.cur_loop);
+                *self.link_mut(p) = self.new_glue(self.glue_ptr(self.cur_loop))?;
+                *self.subtype_mut(self.link(p)) = (TAB_SKIP_CODE + 1) as QuarterWord;
                 // End section 793
             }
             else {
-                return Err(TeXError::ExtraAlignmentTab);
+                *self.extra_info_mut(self.cur_align) = CR_CODE;
+                self.error(TeXError::ExtraAlignmentTab)?;
             }
         }
         // End section 792
 
-        if extra_info(self.cur_align) != SPAN_CODE {
+        if self.extra_info(self.cur_align) != SPAN_CODE {
             self.unsave()?;
             self.new_save_level(ALIGN_GROUP)?;
 
             // Section 796
             let (u, w) = if self.mode() == -HMODE {
                 self.adjust_tail = self.cur_tail;
-                let u = hpack!(self, link(self.head()), NATURAL)?;
-                let w = width(u);
+                let u = hpack!(self, self.link(self.head()), NATURAL)?;
+                let w = self.width(u);
                 self.cur_tail = self.adjust_tail;
                 self.adjust_tail = NULL;
                 (u, w)
             }
             else {
-                let u = self.vpackage(link(self.head()), 0, ADDITIONAL, 0)?;
-                let w = height(u);
+                let u = self.vpackage(self.link(self.head()), 0, ADDITIONAL, 0)?;
+                let w = self.height(u);
                 (u, w)
             };
 
@@ -406,7 +402,7 @@ impl Global {
                 q = self.cur_span;
                 loop {
                     n += 1;
-                    q = link(link(q));
+                    q = self.link(self.link(q));
                     if q == self.cur_align {
                         break;
                     }
@@ -415,26 +411,26 @@ impl Global {
                     return Err(TeXError::Confusion("65536 spans"));
                 }
                 q = self.cur_span;
-                while link(info(q)) < n {
-                    q = info(q);
+                while self.link(self.info(q)) < n {
+                    q = self.info(q);
                 }
-                if link(info(q)) > n {
+                if self.link(self.info(q)) > n {
                     let s = self.get_node(SPAN_NODE_SIZE)?;
-                    *info_mut(s) = info(q);
-                    *link_mut(s) = n;
-                    *info_mut(q) = s;
-                    *width_mut(s) = w;
+                    *self.info_mut(s) = self.info(q);
+                    *self.link_mut(s) = n;
+                    *self.info_mut(q) = s;
+                    *self.width_mut(s) = w;
                 }
-                else if width(info(q)) < w {
-                    *width_mut(info(q)) = w;
+                else if self.width(self.info(q)) < w {
+                    *self.width_mut(self.info(q)) = w;
                 }
                 // End section 798
             }
-            else if w > width(self.cur_align) {
-                *width_mut(self.cur_align) = w;
+            else if w > self.width(self.cur_align) {
+                *self.width_mut(self.cur_align) = w;
             }
-            *type_mut(u) = UNSET_NODE;
-            *span_count_mut(u) = n as QuarterWord;
+            *self.type_mut(u) = UNSET_NODE;
+            *self.span_count_mut(u) = n as QuarterWord;
 
             // Section 659
             let mut o = if self.total_stretch[FILLL as usize] != 0 {
@@ -451,8 +447,8 @@ impl Global {
             };
             // End section 659
 
-            *glue_order_mut(u) = o;
-            *glue_stretch_mut(u) = self.total_stretch[o as usize];
+            *self.glue_order_mut(u) = o;
+            *self.glue_stretch_mut(u) = self.total_stretch[o as usize];
 
             // Section 665
             o = if self.total_shrink[FILLL as usize] != 0 {
@@ -469,19 +465,19 @@ impl Global {
             };
             // End section 665
 
-            *glue_sign_mut(u) = o;
-            *glue_shrink_mut(u) = self.total_shrink[o as usize];
+            *self.glue_sign_mut(u) = o;
+            *self.glue_shrink_mut(u) = self.total_shrink[o as usize];
             self.pop_nest();
-            *link_mut(self.tail()) = u;
+            *self.link_mut(self.tail()) = u;
             *self.tail_mut() = u;
             // End section 796
 
             // Section 795
-            tail_append!(self, self.new_glue(glue_ptr(link(self.cur_align)))?);
-            *subtype_mut(self.tail()) = (TAB_SKIP_CODE + 1) as QuarterWord;
+            tail_append!(self, self.new_glue(self.glue_ptr(self.link(self.cur_align)))?);
+            *self.subtype_mut(self.tail()) = (TAB_SKIP_CODE + 1) as QuarterWord;
             // End section 795
 
-            if extra_info(self.cur_align) >= CR_CODE {
+            if self.extra_info(self.cur_align) >= CR_CODE {
                 return Ok(true);
             }
             self.init_span(p)?;
@@ -496,28 +492,28 @@ impl Global {
     // Section 799
     pub(crate) fn fin_row(&mut self) -> TeXResult<()> {
         let p = if self.mode() == -HMODE {
-            let p = hpack!(self, link(self.head()), NATURAL)?;
+            let p = hpack!(self, self.link(self.head()), NATURAL)?;
             self.pop_nest();
             self.append_to_vlist(p)?;
             if self.cur_head != self.cur_tail {
-                *link_mut(self.tail()) = link(self.cur_head);
+                *self.link_mut(self.tail()) = self.link(self.cur_head);
                 *self.tail_mut() = self.cur_tail;
             }
             p
         }
         else {
-            let p = vpack!(self, link(self.head()), NATURAL)?;
+            let p = vpack!(self, self.link(self.head()), NATURAL)?;
             self.pop_nest();
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             *self.tail_mut() = p;
             *self.space_factor_mut() = 1000;
             p
         };
 
-        *type_mut(p) = UNSET_NODE;
-        *glue_stretch_mut(p) = 0;
-        if every_cr() != NULL {
-            self.begin_token_list(every_cr(), EVERY_CR_TEXT)?;
+        *self.type_mut(p) = UNSET_NODE;
+        *self.glue_stretch_mut(p) = 0;
+        if self.every_cr() != NULL {
+            self.begin_token_list(self.every_cr(), EVERY_CR_TEXT)?;
         }
         self.align_peek()
     }
@@ -534,71 +530,71 @@ impl Global {
         self.unsave()?;
 
         let o = if self.nest[self.nest_ptr - 1].mode_field == MMODE {
-            display_indent()
+            self.display_indent()
         }
         else {
             0
         };
 
         // Section 801
-        let mut q = link(preamble());
+        let mut q = self.link(self.preamble());
         'sec801: loop {
-            self.flush_list(u_part(q));
-            self.flush_list(v_part(q));
-            let p = link(link(q));
-            if width(q) == NULL_FLAG {
+            self.flush_list(self.u_part(q));
+            self.flush_list(self.v_part(q));
+            let p = self.link(self.link(q));
+            if self.width(q) == NULL_FLAG {
                 // Section 802
-                *width_mut(q) = 0;
-                let r = link(q);
-                let s = glue_ptr(r);
+                *self.width_mut(q) = 0;
+                let r = self.link(q);
+                let s = self.glue_ptr(r);
                 if s != ZERO_GLUE {
-                    add_glue_ref!(ZERO_GLUE);
+                    add_glue_ref!(self, ZERO_GLUE);
                     self.delete_glue_ref(s);
-                    *glue_ptr_mut(r) = ZERO_GLUE;
+                    *self.glue_ptr_mut(r) = ZERO_GLUE;
                 }
                 // End section 802
             }
-            if info(q) != END_SPAN {
+            if self.info(q) != self.end_span() {
                 // Section 803
-                let t = width(q) + width(glue_ptr(link(q)));
-                let mut r = info(q);
-                let mut s = END_SPAN;
-                *info_mut(s) = p;
+                let t = self.width(q) + self.width(self.glue_ptr(self.link(q)));
+                let mut r = self.info(q);
+                let mut s = self.end_span();
+                *self.info_mut(s) = p;
                 let mut n = (MIN_QUARTERWORD + 1) as HalfWord;
                 'sec803: loop {
-                    *width_mut(r) -= t;
-                    let u = info(r);
-                    while link(r) > n {
-                        s = info(s);
-                        n = link(info(s)) + 1;
+                    *self.width_mut(r) -= t;
+                    let u = self.info(r);
+                    while self.link(r) > n {
+                        s = self.info(s);
+                        n = self.link(self.info(s)) + 1;
                     }
-                    if link(r) < n {
-                        *info_mut(r) = info(s);
-                        *info_mut(s) = r;
-                        *link_mut(r) -= 1;
+                    if self.link(r) < n {
+                        *self.info_mut(r) = self.info(s);
+                        *self.info_mut(s) = r;
+                        *self.link_mut(r) -= 1;
                         s = r;
                     }
                     else {
-                        if width(r) > width(info(s)) {
-                            *width_mut(info(s)) = width(r);
+                        if self.width(r) > self.width(self.info(s)) {
+                            *self.width_mut(self.info(s)) = self.width(r);
                         }
                         self.free_node(r, SPAN_NODE_SIZE);
                     }
                     r = u;
-                    if r == END_SPAN {
+                    if r == self.end_span() {
                         break 'sec803;
                     }
                 }
                 // End section 803
             }
-            *type_mut(q) = UNSET_NODE;
-            *span_count_mut(q) = MIN_QUARTERWORD;
-            *height_mut(q) = 0;
-            *depth_mut(q) = 0;
-            *glue_order_mut(q) = NORMAL;
-            *glue_sign_mut(q) = NORMAL;
-            *glue_stretch_mut(q) = 0;
-            *glue_shrink_mut(q) = 0;
+            *self.type_mut(q) = UNSET_NODE;
+            *self.span_count_mut(q) = MIN_QUARTERWORD;
+            *self.height_mut(q) = 0;
+            *self.depth_mut(q) = 0;
+            *self.glue_order_mut(q) = NORMAL;
+            *self.glue_sign_mut(q) = NORMAL;
+            *self.glue_stretch_mut(q) = 0;
+            *self.glue_shrink_mut(q) = 0;
             q = p;
             if q == NULL {
                 break 'sec801;
@@ -611,30 +607,30 @@ impl Global {
         self.pack_begin_line = -self.mode_line();
 
         let mut p = if self.mode() == -VMODE {
-            let rule_save = overfull_rule();
-            *overfull_rule_mut() = 0;
-            let p = hpack!(self, preamble(), self.saved(1), self.saved(0) as QuarterWord)?;
-            *overfull_rule_mut() = rule_save;
+            let rule_save = self.overfull_rule();
+            *self.overfull_rule_mut() = 0;
+            let p = hpack!(self, self.preamble(), self.saved(1), self.saved(0) as QuarterWord)?;
+            *self.overfull_rule_mut() = rule_save;
             p
         }
         else {
-            q = link(preamble());
+            q = self.link(self.preamble());
             loop {
-                *height_mut(q) = width(q);
-                *width_mut(q) = 0;
-                q = link(link(q));
+                *self.height_mut(q) = self.width(q);
+                *self.width_mut(q) = 0;
+                q = self.link(self.link(q));
                 if q == NULL {
                     break;
                 }
             }
             
-            let p = vpack!(self, preamble(), self.saved(1), self.saved(0) as QuarterWord)?;
+            let p = vpack!(self, self.preamble(), self.saved(1), self.saved(0) as QuarterWord)?;
             
-            q = link(preamble());
+            q = self.link(self.preamble());
             loop {
-                *width_mut(q) = height(q);
-                *height_mut(q) = 0;
-                q = link(link(q));
+                *self.width_mut(q) = self.height(q);
+                *self.height_mut(q) = 0;
+                q = self.link(self.link(q));
                 if q == NULL {
                     break;
                 }
@@ -646,191 +642,191 @@ impl Global {
         // End setion 804
 
         // Section 805
-        q = link(self.head());
+        q = self.link(self.head());
         let mut s = self.head();
         while q != NULL {
             if !self.is_char_node(q) {
-                if r#type(q) == UNSET_NODE {
+                if self.r#type(q) == UNSET_NODE {
                     // Section 807
                     if self.mode() == -VMODE {
-                        *type_mut(q) = HLIST_NODE;
-                        *width_mut(q) = width(p);
+                        *self.type_mut(q) = HLIST_NODE;
+                        *self.width_mut(q) = self.width(p);
                     }
                     else {
-                        *type_mut(q) = VLIST_NODE;
-                        *height_mut(q) = height(p);
+                        *self.type_mut(q) = VLIST_NODE;
+                        *self.height_mut(q) = self.height(p);
                     }
-                    *glue_order_mut(q) = glue_order(p);
-                    *glue_sign_mut(q) = glue_sign(p);
-                    *glue_set_mut(q) = glue_set(p);
-                    *shift_amount_mut(q) = o;
-                    let mut r = link(list_ptr(q));
-                    s = link(list_ptr(p));
+                    *self.glue_order_mut(q) = self.glue_order(p);
+                    *self.glue_sign_mut(q) = self.glue_sign(p);
+                    *self.glue_set_mut(q) = self.glue_set(p);
+                    *self.shift_amount_mut(q) = o;
+                    let mut r = self.link(self.list_ptr(q));
+                    s = self.link(self.list_ptr(p));
                     'sec807: loop {
                         // Section 808
-                        let mut n = span_count(r) as HalfWord;
-                        let mut t = width(s);
+                        let mut n = self.span_count(r) as HalfWord;
+                        let mut t = self.width(s);
                         let w = t;
-                        let mut u = HOLD_HEAD;
+                        let mut u = self.hold_head();
                         while n > (MIN_QUARTERWORD as HalfWord) {
                             n -= 1;
                             // Section 809
-                            s = link(s);
-                            let v = glue_ptr(s);
-                            *link_mut(u) = self.new_glue(v)?;
-                            u = link(u);
-                            *subtype_mut(u) = (TAB_SKIP_CODE + 1) as QuarterWord;
-                            t += width(v);
-                            match glue_sign(p) {
+                            s = self.link(s);
+                            let v = self.glue_ptr(s);
+                            *self.link_mut(u) = self.new_glue(v)?;
+                            u = self.link(u);
+                            *self.subtype_mut(u) = (TAB_SKIP_CODE + 1) as QuarterWord;
+                            t += self.width(v);
+                            match self.glue_sign(p) {
                                 STRETCHING => {
-                                    if stretch_order(v) == glue_order(p) {
-                                        t += (glue_set(p)*(stretch(v) as Real)).round() as Scaled;
+                                    if self.stretch_order(v) == self.glue_order(p) {
+                                        t += (self.glue_set(p)*(self.stretch(v) as Real)).round() as Scaled;
                                     }
                                 },
 
                                 SHRINKING => {
-                                    if shrink_order(v) == glue_order(p) {
-                                        t -= (glue_set(p)*(shrink(v) as Real)).round() as Scaled;
+                                    if self.shrink_order(v) == self.glue_order(p) {
+                                        t -= (self.glue_set(p)*(self.shrink(v) as Real)).round() as Scaled;
                                     }
                                 },
 
                                 _ => ()
                             }
-                            s = link(s);
-                            *link_mut(u) = self.new_null_box()?;
-                            u = link(u);
-                            t += width(s);
+                            s = self.link(s);
+                            *self.link_mut(u) = self.new_null_box()?;
+                            u = self.link(u);
+                            t += self.width(s);
                             if self.mode() == -VMODE {
-                                *width_mut(u) = width(s);
+                                *self.width_mut(u) = self.width(s);
                             }
                             else {
-                                *type_mut(u) = VLIST_NODE;
-                                *height_mut(u) = width(s);
+                                *self.type_mut(u) = VLIST_NODE;
+                                *self.height_mut(u) = self.width(s);
                             }
                             // End section 809
                         }
 
                         if self.mode() == -VMODE {
                             // Section 810
-                            *height_mut(r) = height(q);
-                            *depth_mut(r) = depth(q);
+                            *self.height_mut(r) = self.height(q);
+                            *self.depth_mut(r) = self.depth(q);
 
-                            match t.cmp(&width(r)) {
+                            match t.cmp(&self.width(r)) {
                                 Equal => {
-                                    *glue_sign_mut(r) = NORMAL;
-                                    *glue_order_mut(r) = NORMAL;
-                                    *glue_set_mut(r) = 0.0;
+                                    *self.glue_sign_mut(r) = NORMAL;
+                                    *self.glue_order_mut(r) = NORMAL;
+                                    *self.glue_set_mut(r) = 0.0;
                                 },
 
                                 Greater => {
-                                    *glue_sign_mut(r) = STRETCHING;
-                                    if glue_stretch(r) == 0 {
-                                        *glue_set_mut(r) = 0.0;
+                                    *self.glue_sign_mut(r) = STRETCHING;
+                                    if self.glue_stretch(r) == 0 {
+                                        *self.glue_set_mut(r) = 0.0;
                                     }
                                     else {
-                                        *glue_set_mut(r) = ((t - width(r)) as Real) / (glue_stretch(r) as Real);
+                                        *self.glue_set_mut(r) = ((t - self.width(r)) as Real) / (self.glue_stretch(r) as Real);
                                     }
                                 },
 
                                 Less => {
-                                    *glue_order_mut(r) = glue_sign(r);
-                                    *glue_sign_mut(r) = SHRINKING;
-                                    if glue_shrink(r) == 0 {
-                                        *glue_set_mut(r) = 0.0;
+                                    *self.glue_order_mut(r) = self.glue_sign(r);
+                                    *self.glue_sign_mut(r) = SHRINKING;
+                                    if self.glue_shrink(r) == 0 {
+                                        *self.glue_set_mut(r) = 0.0;
                                     }
-                                    else if glue_order(r) == NORMAL && (width(r) - t) > glue_shrink(r) {
-                                        *glue_set_mut(r) = 1.0;
+                                    else if self.glue_order(r) == NORMAL && (self.width(r) - t) > self.glue_shrink(r) {
+                                        *self.glue_set_mut(r) = 1.0;
                                     }
                                     else {
-                                        *glue_set_mut(r) = ((width(r) - t) as Real) / (glue_shrink(r) as Real);
+                                        *self.glue_set_mut(r) = ((self.width(r) - t) as Real) / (self.glue_shrink(r) as Real);
                                     }
                                 },
                             }
 
-                            *width_mut(r) = w;
-                            *type_mut(r) = HLIST_NODE;
+                            *self.width_mut(r) = w;
+                            *self.type_mut(r) = HLIST_NODE;
                             // End section 810
                         }
                         else {
                             // Section 811
-                            *width_mut(r) = width(q);
+                            *self.width_mut(r) = self.width(q);
                             
-                            match t.cmp(&height(r)) {
+                            match t.cmp(&self.height(r)) {
                                 Equal => {
-                                    *glue_sign_mut(r) = NORMAL;
-                                    *glue_order_mut(r) = NORMAL;
-                                    *glue_set_mut(r) = 0.0;
+                                    *self.glue_sign_mut(r) = NORMAL;
+                                    *self.glue_order_mut(r) = NORMAL;
+                                    *self.glue_set_mut(r) = 0.0;
                                 },
 
                                 Greater => {
-                                    *glue_sign_mut(r) = STRETCHING;
-                                    if glue_stretch(r) == 0 {
-                                        *glue_set_mut(r) = 0.0;
+                                    *self.glue_sign_mut(r) = STRETCHING;
+                                    if self.glue_stretch(r) == 0 {
+                                        *self.glue_set_mut(r) = 0.0;
                                     }
                                     else {
-                                        *glue_set_mut(r) = ((t - height(r)) as Real) / (glue_stretch(r) as Real);
+                                        *self.glue_set_mut(r) = ((t - self.height(r)) as Real) / (self.glue_stretch(r) as Real);
                                     }
                                 },
 
                                 Less => {
-                                    *glue_order_mut(r) = glue_sign(r);
-                                    *glue_sign_mut(r) = SHRINKING;
-                                    if glue_shrink(r) == 0 {
-                                        *glue_set_mut(r) = 0.0;
+                                    *self.glue_order_mut(r) = self.glue_sign(r);
+                                    *self.glue_sign_mut(r) = SHRINKING;
+                                    if self.glue_shrink(r) == 0 {
+                                        *self.glue_set_mut(r) = 0.0;
                                     }
-                                    else if glue_order(r) == NORMAL && (height(r) - t) > glue_shrink(r) {
-                                        *glue_set_mut(r) = 1.0;
+                                    else if self.glue_order(r) == NORMAL && (self.height(r) - t) > self.glue_shrink(r) {
+                                        *self.glue_set_mut(r) = 1.0;
                                     }
                                     else {
-                                        *glue_set_mut(r) = ((height(r) - t) as Real) / (glue_shrink(r) as Real);
+                                        *self.glue_set_mut(r) = ((self.height(r) - t) as Real) / (self.glue_shrink(r) as Real);
                                     }
                                 },
                             }
 
-                            *height_mut(r) = w;
-                            *type_mut(r) = VLIST_NODE;
+                            *self.height_mut(r) = w;
+                            *self.type_mut(r) = VLIST_NODE;
                             // End section 811
                         }
-                        *shift_amount_mut(r) = 0;
-                        if u != HOLD_HEAD {
-                            *link_mut(u) = link(r);
-                            *link_mut(r) = link(HOLD_HEAD);
+                        *self.shift_amount_mut(r) = 0;
+                        if u != self.hold_head() {
+                            *self.link_mut(u) = self.link(r);
+                            *self.link_mut(r) = self.link(self.hold_head());
                             r = u;
                         }
                         // End section 808
 
-                        r = link(link(r));
-                        s = link(link(s));
+                        r = self.link(self.link(r));
+                        s = self.link(self.link(s));
                         if r == NULL {
                             break 'sec807;
                         }
                     }
                     // End section 807
                 }
-                else if r#type(q) == RULE_NODE {
+                else if self.r#type(q) == RULE_NODE {
                     // Section 806
-                    if is_running!(width(q)) {
-                        *width_mut(q) = width(p);
+                    if is_running!(self.width(q)) {
+                        *self.width_mut(q) = self.width(p);
                     }
-                    if is_running!(height(q)) {
-                        *height_mut(q) = height(p);
+                    if is_running!(self.height(q)) {
+                        *self.height_mut(q) = self.height(p);
                     }
-                    if is_running!(depth(q)) {
-                        *depth_mut(q) = depth(p);
+                    if is_running!(self.depth(q)) {
+                        *self.depth_mut(q) = self.depth(p);
                     }
                     if o != 0 {
-                        let r = link(q);
-                        *link_mut(q) = NULL;
+                        let r = self.link(q);
+                        *self.link_mut(q) = NULL;
                         q = hpack!(self, q, NATURAL)?;
-                        *shift_amount_mut(q) = o;
-                        *link_mut(q) = r;
-                        *link_mut(s) = q;
+                        *self.shift_amount_mut(q) = o;
+                        *self.link_mut(q) = r;
+                        *self.link_mut(s) = q;
                     }
                     // End section 806
                 }
             }
             s = q;
-            q = link(q);
+            q = self.link(q);
         }
         // End section 805
 
@@ -839,31 +835,33 @@ impl Global {
 
         // Section 812
         let aux_save = self.aux();
-        p = link(self.head());
+        p = self.link(self.head());
         q = self.tail();
         self.pop_nest();
         if self.mode() == MMODE {
             // Section 1206
             self.do_assignments()?;
             if self.cur_cmd != MATH_SHIFT {
-                return Err(TeXError::MissingDollarDollar);
+                // Section 1207
+                self.back_error(TeXError::MissingDollarDollar)?;
             }
-
-            // Section 1197
-            self.get_x_token()?;
-            if self.cur_cmd != MATH_SHIFT {
-                return Err(TeXError::DisplayMathEndsWithDollars);
+            else {
+                // Section 1197
+                self.get_x_token()?;
+                if self.cur_cmd != MATH_SHIFT {
+                    self.back_error(TeXError::DisplayMathEndsWithDollars)?;
+                }
+                // End section 1197
             }
-            // End section 1197
 
             self.pop_nest();
-            tail_append!(self, self.new_penalty(pre_display_penalty())?);
+            tail_append!(self, self.new_penalty(self.pre_display_penalty())?);
             tail_append!(self, self.new_param_glue(ABOVE_DISPLAY_SKIP_CODE as SmallNumber)?);
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             if p != NULL {
                 *self.tail_mut() = q;
             }
-            tail_append!(self, self.new_penalty(post_display_penalty())?);
+            tail_append!(self, self.new_penalty(self.post_display_penalty())?);
             tail_append!(self, self.new_param_glue(BELOW_DISPLAY_SKIP_CODE as SmallNumber)?);
             *self.prev_depth_mut() = aux_save.sc();
             self.resume_after_display()?;
@@ -871,7 +869,7 @@ impl Global {
         }
         else {
             *self.aux_mut() = aux_save;
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             if p != NULL {
                 *self.tail_mut() = q;
             }
@@ -1,5 +1,4 @@
 use crate::constants::{INF_BAD, TWO};
-use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, Scaled
 };
@@ -43,80 +42,86 @@ impl Global {
 // Section 105
 #[macro_export]
 macro_rules! nx_plus_y {
-    ($($args:expr),*) => {
-        mult_and_add($($args),*, 0x3fff_ffff)?
+    ($s:ident, $($args:expr),*) => {
+        $s.mult_and_add($($args),*, 0x3fff_ffff)
     };
 }
 
 #[macro_export]
 macro_rules! mult_integers {
-    ($($args:expr),*) => {
-        mult_and_add($($args),*, 0, 0x7fff_ffff)?
+    ($s:ident, $($args:expr),*) => {
+        $s.mult_and_add($($args),*, 0, 0x7fff_ffff)
     };
 }
 
-pub(crate) fn mult_and_add(mut n: Integer, mut x: Scaled, y: Scaled, max_answer: Scaled) -> TeXResult<Scaled> {
-    if n < 0 {
-        x = -x;
-        n = -n;
-    }
-    match n {
-        0 => Ok(y),
-        _ => {
-            if (x <= (max_answer - y) / n) && (-x <= (max_answer + y) / n) {
-                Ok(x*n + y)
-            }
-            else {
-                Err(TeXError::Arith)
+impl Global {
+    pub(crate) fn mult_and_add(&mut self, mut n: Integer, mut x: Scaled, y: Scaled, max_answer: Scaled) -> Scaled {
+        if n < 0 {
+            x = -x;
+            n = -n;
+        }
+        match n {
+            0 => y,
+            _ => {
+                if (x <= (max_answer - y) / n) && (-x <= (max_answer + y) / n) {
+                    x*n + y
+                }
+                else {
+                    self.arith_error = true;
+                    0
+                }
             }
         }
     }
-}
 
-// Section 106
-// Remainder is not a global variable in this implementation.
-pub(crate) fn x_over_n(mut x: Scaled, mut n: Integer) -> TeXResult<(Scaled, Scaled)> {
-    let mut negative = false;
-    if n == 0 {
-        return Err(TeXError::Arith);
-    }
-    if n < 0 {
-        x = -x;
-        n = -n;
-        negative = true
-    }
-    let (quo, mut rem) = match x.cmp(&0) {
-        Greater | Equal => (x / n, x % n),
-        Less => (-((-x) / n), -((-x) % n))
-    };
+    // Section 106
+    // Remainder is not a global variable in this implementation.
+    pub(crate) fn x_over_n(&mut self, mut x: Scaled, mut n: Integer) -> (Scaled, Scaled) {
+        let mut negative = false;
+        if n == 0 {
+            self.arith_error = true;
+            return (0, x);
+        }
+        if n < 0 {
+            x = -x;
+            n = -n;
+            negative = true
+        }
+        let (quo, mut rem) = match x.cmp(&0) {
+            Greater | Equal => (x / n, x % n),
+            Less => (-((-x) / n), -((-x) % n))
+        };
+
+        if negative {
+            rem = -rem;
+        }
 
-    if negative {
-        rem = -rem;
+        (quo, rem)
     }
 
-    Ok((quo, rem))
-}
+    // Section 107
+    pub(crate) fn xn_over_d(&mut self, mut x: Scaled, n: Integer, d: Integer) -> (Scaled, Scaled) {
+        let positive = match x.cmp(&0) {
+            Greater | Equal => true,
+            Less => {
+                x = -x;
+                false
+            }
+        };
 
-// Section 107
-pub(crate) fn xn_over_d(mut x: Scaled, n: Integer, d: Integer) -> TeXResult<(Scaled, Scaled)> {
-    let positive = match x.cmp(&0) {
-        Greater | Equal => true,
-        Less => {
-            x = -x;
-            false
+        let t = (x % 32768) * n;
+        let mut u = (x / 32768) * n + (t / 32768);
+        let v = (u % d) * 32768 + (t % 32768);
+        if u / d >= 32768 {
+            self.arith_error = true;
+        }
+        else {
+            u = 32768 * (u / d) + (v / d);
+        }
+        match positive {
+            true => (u, v % d),
+            false => (-u, -(v % d))
         }
-    };
-
-    let t = (x % 32768) * n;
-    let mut u = (x / 32768) * n + (t / 32768);
-    let v = (u % d) * 32768 + (t % 32768);
-    if u / d >= 32768 {
-        return Err(TeXError::Arith);
-    }
-    u = 32768 * (u / d) + (v / d);
-    match positive {
-        true => Ok((u, v % d)),
-        false => Ok((-u, -(v % d)))
     }
 }
 
@@ -147,3 +152,13 @@ pub(crate) fn badness(t: Scaled, s: Scaled) -> HalfWord {
         }
     }
 }
+
+// Reals of the PDF and SVG output, with at most `decimals` decimals.
+pub(crate) fn real(x: f64, decimals: usize) -> String {
+    let s = format!("{x:.decimals$}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" => "0".to_string(),
+        _ => s.to_string()
+    }
+}
@@ -2,4 +2,4 @@ mod hyphenation;
 mod line_break;
 mod page_break;
 
-pub(crate) use line_break::{Array1to6, HyfArray, line_number_mut};
+pub(crate) use line_break::{Array1to6, HyfArray};
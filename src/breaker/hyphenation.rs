@@ -1,13 +1,5 @@
 use crate::constants::*;
-use crate::datastructures::{
-    character, character_mut, font, font_mut, info, lig_ptr, lig_ptr_mut,
-    link, link_mut, post_break_mut, pre_break_mut, r#type, replace_count_mut,
-    subtype, subtype_mut, type_mut
-};
 use crate::error::TeXResult;
-use crate::strings::{
-    str_pool, length, str_start
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, SmallNumber,
     free_avail, lig_char, odd
@@ -34,19 +26,19 @@ impl Global {
             'sec930: loop {
                 // Section 931
                 let k = self.hyph_word[h as usize];
-                if k == 0 || length(k) < self.hn as usize {
+                if k == 0 || self.length(k) < self.hn as usize {
                     break 'sec930; // Goto not_found
                 }
                 'innerblock: {
-                    if length(k) == self.hn as usize {
+                    if self.length(k) == self.hn as usize {
                         let mut j = 1;
-                        let mut u = str_start(k);
+                        let mut u = self.str_start(k);
                         
                         'sec931: loop {
-                            if str_pool(u) < self.hc[j] as u8 {
+                            if self.str_pool(u) < self.hc[j] as u8 {
                                 break 'sec930; // Goto not_found
                             }
-                            if str_pool(u) > self.hc[j] as u8 {
+                            if self.str_pool(u) > self.hc[j] as u8 {
                                 break 'innerblock; // Goto done
                             }
                             j += 1;
@@ -58,8 +50,8 @@ impl Global {
                         // Section 932
                         let mut s = self.hyph_list[h as usize];
                         while s != NULL {
-                            self.hyf[info(s) as usize] = 1;
-                            s = link(s);
+                            self.hyf[self.info(s) as usize] = 1;
+                            s = self.link(s);
                         }
                         // End section 932
                         self.hn -= 1;
@@ -132,29 +124,29 @@ impl Global {
         // End section 902
         
         // Section 903
-        let q = link(self.hb);
-        *link_mut(self.hb) = NULL;
-        let mut r = link(self.ha);
-        *link_mut(self.ha) = NULL;
+        let q = self.link(self.hb);
+        *self.link_mut(self.hb) = NULL;
+        let mut r = self.link(self.ha);
+        *self.link_mut(self.ha) = NULL;
         let bchar = self.hyf_bchar;
 
         let found2 = 'block: {
             if self.is_char_node(self.ha) {
-                if font(self.ha) != self.hf {
+                if self.font(self.ha) != self.hf {
                     break 'block None; // Goto found2
                 }
                 self.init_list = self.ha;
                 self.init_lig = false;
-                self.hu[0] = character(self.ha);
+                self.hu[0] = self.character(self.ha);
             }
-            else if r#type(self.ha) == LIGATURE_NODE {
-                if font(lig_char!(self.ha)) != self.hf {
+            else if self.r#type(self.ha) == LIGATURE_NODE {
+                if self.font(lig_char!(self.ha)) != self.hf {
                     break 'block None; // Goto found2
                 }
-                self.init_list = lig_ptr(self.ha);
+                self.init_list = self.lig_ptr(self.ha);
                 self.init_lig = true;
-                self.init_lft = subtype(self.ha) > 1;
-                self.hu[0] = character(lig_char!(self.ha));
+                self.init_lft = self.subtype(self.ha) > 1;
+                self.hu[0] = self.character(lig_char!(self.ha));
                 if self.init_list == NULL && self.init_lft {
                     self.hu[0] = 256;
                     self.init_lig = false;
@@ -163,8 +155,8 @@ impl Global {
             }
             else {
                 if !self.is_char_node(r)
-                    && r#type(r) == LIGATURE_NODE
-                    && subtype(r) > 1
+                    && self.r#type(r) == LIGATURE_NODE
+                    && self.subtype(r) > 1
                 {
                     break 'block None; // Goto found2
                 }
@@ -172,8 +164,8 @@ impl Global {
                 break 'block Some((1, self.ha)); // Goto common_ending
             }
             let mut s = self.cur_p;
-            while link(s) != self.ha {
-                s = link(s);
+            while self.link(s) != self.ha {
+                s = self.link(s);
             }
             Some((0, s))
         };
@@ -197,14 +189,14 @@ impl Global {
             let mut l = j;
             j = self.reconstitute(j, self.hn, bchar, self.hyf_char as HalfWord)? + 1;
             if self.hyphen_passed == 0 {
-                *link_mut(s) = link(HOLD_HEAD);
-                while link(s) > NULL {
-                    s = link(s);
+                *self.link_mut(s) = self.link(self.hold_head());
+                while self.link(s) > NULL {
+                    s = self.link(s);
                 }
                 if odd!(self.hyf[(j - 1) as usize]) {
                     l = j;
                     self.hyphen_passed = j - 1;
-                    *link_mut(HOLD_HEAD) = NULL;
+                    *self.link_mut(self.hold_head()) = NULL;
                 }
             }
             if self.hyphen_passed > 0 {
@@ -212,13 +204,13 @@ impl Global {
                 let mut c = 0;
                 'sec914: loop {
                     r = self.get_node(SMALL_NODE_SIZE)?;
-                    *link_mut(r) = link(HOLD_HEAD);
-                    *type_mut(r) = DISC_NODE;
+                    *self.link_mut(r) = self.link(self.hold_head());
+                    *self.type_mut(r) = DISC_NODE;
                     let mut major_tail = r;
                     let mut r_count = 0;
-                    while link(major_tail) > NULL {
+                    while self.link(major_tail) > NULL {
                         // advance_major_tail:
-                        major_tail = link(major_tail);
+                        major_tail = self.link(major_tail);
                         r_count += 1;
                     }
                     let mut i = self.hyphen_passed;
@@ -226,7 +218,7 @@ impl Global {
                     
                     // Section 915
                     let mut minor_tail = NULL;
-                    *pre_break_mut(r) = NULL;
+                    *self.pre_break_mut(r) = NULL;
                     let hyf_node = self.new_character(self.hf, self.hyf_char as u8)?;
                     if hyf_node != NULL {
                         i += 1;
@@ -236,16 +228,16 @@ impl Global {
                     }
                     while l <= i {
                         l = self.reconstitute(l, i, self.font_bchar[self.hf as usize] as HalfWord, NON_CHAR)? + 1;
-                        if link(HOLD_HEAD) > NULL {
+                        if self.link(self.hold_head()) > NULL {
                             if minor_tail == NULL {
-                                *pre_break_mut(r) = link(HOLD_HEAD);
+                                *self.pre_break_mut(r) = self.link(self.hold_head());
                             }
                             else {
-                                *link_mut(minor_tail) = link(HOLD_HEAD);
+                                *self.link_mut(minor_tail) = self.link(self.hold_head());
                             }
-                            minor_tail = link(HOLD_HEAD);
-                            while link(minor_tail) > NULL {
-                                minor_tail = link(minor_tail);
+                            minor_tail = self.link(self.hold_head());
+                            while self.link(minor_tail) > NULL {
+                                minor_tail = self.link(minor_tail);
                             }
                         }
                     }
@@ -259,7 +251,7 @@ impl Global {
 
                     // Section 916
                     minor_tail = NULL;
-                    *post_break_mut(r) = NULL;
+                    *self.post_break_mut(r) = NULL;
                     let mut c_loc = 0;
                     if self.bchar_label[self.hf as usize] != NON_ADDRESS as usize {
                         l -= 1;
@@ -274,16 +266,16 @@ impl Global {
                                 self.hu[c_loc as usize] = c;
                                 c_loc = 0;
                             }
-                            if link(HOLD_HEAD) > NULL {
+                            if self.link(self.hold_head()) > NULL {
                                 if minor_tail == NULL {
-                                    *post_break_mut(r) = link(HOLD_HEAD);
+                                    *self.post_break_mut(r) = self.link(self.hold_head());
                                 }
                                 else {
-                                    *link_mut(minor_tail) = link(HOLD_HEAD);
+                                    *self.link_mut(minor_tail) = self.link(self.hold_head());
                                 }
-                                minor_tail = link(HOLD_HEAD);
-                                while link(minor_tail) > NULL {
-                                    minor_tail = link(minor_tail);
+                                minor_tail = self.link(self.hold_head());
+                                while self.link(minor_tail) > NULL {
+                                    minor_tail = self.link(minor_tail);
                                 }
                             }
                             if l >= j {
@@ -293,10 +285,10 @@ impl Global {
                         while l > j {
                             // Section 917
                             j = self.reconstitute(j, self.hn, bchar, NON_CHAR)? + 1;
-                            *link_mut(major_tail) = link(HOLD_HEAD);
-                            while link(major_tail) > NULL {
+                            *self.link_mut(major_tail) = self.link(self.hold_head());
+                            while self.link(major_tail) > NULL {
                                 // advance_major_tail:
-                                major_tail = link(major_tail);
+                                major_tail = self.link(major_tail);
                                 r_count += 1;
                             }
                             // End section 917
@@ -306,19 +298,19 @@ impl Global {
                     
                     // Section 918
                     if r_count > 127 {
-                        *link_mut(s) = link(r);
-                        *link_mut(r) = NULL;
+                        *self.link_mut(s) = self.link(r);
+                        *self.link_mut(r) = NULL;
                         self.flush_node_list(r)?;
                     }
                     else {
-                        *link_mut(s) = r;
-                        *replace_count_mut(r) = r_count as QuarterWord;
+                        *self.link_mut(s) = r;
+                        *self.replace_count_mut(r) = r_count as QuarterWord;
                     }
                     s = major_tail;
                     // End section 918
 
                     self.hyphen_passed = j - 1;
-                    *link_mut(HOLD_HEAD) = NULL;
+                    *self.link_mut(self.hold_head()) = NULL;
                     if !odd!(self.hyf[(j - 1) as usize]) {
                         break 'sec914;
                     }
@@ -329,7 +321,7 @@ impl Global {
                 break 'sec913;
             }
         }
-        *link_mut(s) = q;
+        *self.link_mut(s) = q;
         // End section 913
 
         self.flush_list(self.init_list);
@@ -341,17 +333,17 @@ impl Global {
     fn reconstitute(&mut self, mut j: SmallNumber, n: SmallNumber, mut bchar: HalfWord, mut hchar: HalfWord) -> TeXResult<SmallNumber> {
         self.hyphen_passed = 0;
         let mut cur_rh: HalfWord;
-        let mut t = HOLD_HEAD;
+        let mut t = self.hold_head();
         let mut w = 0;
-        *link_mut(HOLD_HEAD) = NULL;
+        *self.link_mut(self.hold_head()) = NULL;
 
         // Section 908
         macro_rules! append_charnode_to_t {
             ($p:expr) => {
-                *link_mut(t) = self.get_avail()?;
-                t = link(t);
-                *font_mut(t) = self.hf;
-                *character_mut(t) = $p;
+                *self.link_mut(t) = self.get_avail()?;
+                t = self.link(t);
+                *self.font_mut(t) = self.hf;
+                *self.character_mut(t) = $p;
             };
         }
 
@@ -381,8 +373,8 @@ impl Global {
                 self.lft_hit = self.init_lft;
             }
             while p > NULL {
-                append_charnode_to_t!(character(p));
-                p = link(p);
+                append_charnode_to_t!(self.character(p));
+                p = self.link(p);
             }
         }
         else if self.cur_l < NON_CHAR {
@@ -396,16 +388,16 @@ impl Global {
         macro_rules! wrap_lig {
             ($b:expr) => {
                 if self.ligature_present {
-                    let p = self.new_ligature(self.hf, self.cur_l as QuarterWord, link(self.cur_q))?;
+                    let p = self.new_ligature(self.hf, self.cur_l as QuarterWord, self.link(self.cur_q))?;
                     if self.lft_hit {
-                        *subtype_mut(p) = 2;
+                        *self.subtype_mut(p) = 2;
                         self.lft_hit = false;
                     }
                     if $b && self.lig_stack == NULL {
-                        *subtype_mut(p) += 1;
+                        *self.subtype_mut(p) += 1;
                         self.rt_hit = false;
                     }
-                    *link_mut(self.cur_q) = p;
+                    *self.link_mut(self.cur_q) = p;
                     t = p;
                     self.ligature_present = false;
                 }
@@ -414,19 +406,19 @@ impl Global {
 
         macro_rules! pop_lig_stack {
             () => {
-                if lig_ptr(self.lig_stack) > NULL {
-                    *link_mut(t) = lig_ptr(self.lig_stack);
-                    t = link(t);
+                if self.lig_ptr(self.lig_stack) > NULL {
+                    *self.link_mut(t) = self.lig_ptr(self.lig_stack);
+                    t = self.link(t);
                     j += 1;
                 }
                 let p = self.lig_stack;
-                self.lig_stack = link(p);
+                self.lig_stack = self.link(p);
                 self.free_node(p, SMALL_NODE_SIZE);
                 if self.lig_stack == NULL {
                     set_cur_r!();
                 }
                 else {
-                    self.cur_r = character(self.lig_stack) as HalfWord;
+                    self.cur_r = self.character(self.lig_stack) as HalfWord;
                 }
             };
         }        
@@ -497,7 +489,7 @@ impl Global {
                                 2 | 6 => {
                                     self.cur_r = q.rem_byte() as HalfWord;
                                     if self.lig_stack > NULL {
-                                        *character_mut(self.lig_stack) = self.cur_r as QuarterWord;
+                                        *self.character_mut(self.lig_stack) = self.cur_r as QuarterWord;
                                     }
                                     else {
                                         self.lig_stack = self.new_lig_item(self.cur_r as QuarterWord)?;
@@ -506,9 +498,9 @@ impl Global {
                                         }
                                         else {
                                             let p = self.get_avail()?;
-                                            *lig_ptr_mut(self.lig_stack) = p;
-                                            *character_mut(p) = self.hu[(j + 1) as usize];
-                                            *font_mut(p) = self.hf;
+                                            *self.lig_ptr_mut(self.lig_stack) = p;
+                                            *self.character_mut(p) = self.hu[(j + 1) as usize];
+                                            *self.font_mut(p) = self.hf;
                                         }
                                     }
                                 },
@@ -517,7 +509,7 @@ impl Global {
                                     self.cur_r = q.rem_byte() as HalfWord;
                                     let p = self.lig_stack;
                                     self.lig_stack = self.new_lig_item(self.cur_r as QuarterWord)?;
-                                    *link_mut(self.lig_stack) = p;
+                                    *self.link_mut(self.lig_stack) = p;
                                 },
 
                                 7 | 11 => {
@@ -571,13 +563,13 @@ impl Global {
             // Section 910
             wrap_lig!(self.rt_hit);
             if w != 0 {
-                *link_mut(t) = self.new_kern(w)?;
-                t = link(t);
+                *self.link_mut(t) = self.new_kern(w)?;
+                t = self.link(t);
                 w = 0;
             }
             if self.lig_stack > NULL {
                 self.cur_q = t;
-                self.cur_l = character(self.lig_stack) as HalfWord;
+                self.cur_l = self.character(self.lig_stack) as HalfWord;
                 self.ligature_present = true;
                 pop_lig_stack!();
                 // Goto continue
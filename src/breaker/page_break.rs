@@ -1,10 +1,5 @@
 use crate::arithmetic::badness;
 use crate::constants::*;
-use crate::datastructures::{
-    box_mut, depth, glue_ptr, height, link, link_mut, list_ptr, list_ptr_mut,
-    mark_ptr, penalty, r#box, r#type, shrink, shrink_order, split_max_depth,
-    stretch, stretch_order, token_ref_count_mut, width, width_mut
-};
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Scaled, SmallNumber,
@@ -16,19 +11,19 @@ use crate::{
 impl Global {
     // Section 968
     pub(crate) fn prune_page_top(&mut self, mut p: HalfWord) -> TeXResult<HalfWord> {
-        let mut prev_p = TEMP_HEAD;
-        *link_mut(TEMP_HEAD) = p;
+        let mut prev_p = self.temp_head();
+        *self.link_mut(self.temp_head()) = p;
         while p != NULL {
-            match r#type(p) {
+            match self.r#type(p) {
                 HLIST_NODE
                 | VLIST_NODE
                 | RULE_NODE => {
                     // Section 969
                     let q = self.new_skip_param(SPLIT_TOP_SKIP_CODE as SmallNumber)?;
-                    *link_mut(prev_p) = q;
-                    *link_mut(q) = p;
-                    *width_mut(self.temp_ptr) = if width(self.temp_ptr) > height(p) {
-                        width(self.temp_ptr) - height(p)
+                    *self.link_mut(prev_p) = q;
+                    *self.link_mut(q) = p;
+                    *self.width_mut(self.temp_ptr) = if self.width(self.temp_ptr) > self.height(p) {
+                        self.width(self.temp_ptr) - self.height(p)
                     }
                     else {
                         0
@@ -41,23 +36,23 @@ impl Global {
                 | MARK_NODE
                 | INS_NODE => {
                     prev_p = p;
-                    p = link(prev_p);
+                    p = self.link(prev_p);
                 },
 
                 GLUE_NODE
                 | KERN_NODE
                 | PENALTY_NODE => {
                     let q = p;
-                    p = link(q);
-                    *link_mut(q) = NULL;
-                    *link_mut(prev_p) = p;
+                    p = self.link(q);
+                    *self.link_mut(q) = NULL;
+                    *self.link_mut(prev_p) = p;
                     self.flush_node_list(q)?;
                 },
 
                 _ => return Err(TeXError::Confusion("pruning"))
             }
         }
-        Ok(link(TEMP_HEAD))
+        Ok(self.link(self.temp_head()))
     }
 
     // Section 970
@@ -94,12 +89,12 @@ impl Global {
                 }
                 else {
                     // Section 973
-                    match r#type(p) {
+                    match self.r#type(p) {
                         HLIST_NODE
                         | VLIST_NODE
                         | RULE_NODE => {
-                            cur_height!() += prev_dp + height(p);
-                            prev_dp = depth(p);
+                            cur_height!() += prev_dp + self.height(p);
+                            prev_dp = self.depth(p);
                             break 'sec972 true; // Goto not_found
                         },
 
@@ -110,7 +105,7 @@ impl Global {
                         },
 
                         GLUE_NODE => {
-                            if precedes_break!(prev_p) {
+                            if precedes_break!(self, prev_p) {
                                 0
                             }
                             else {
@@ -119,11 +114,11 @@ impl Global {
                         },
 
                         KERN_NODE => {
-                            let t = if link(p) == NULL {
+                            let t = if self.link(p) == NULL {
                                 PENALTY_NODE
                             }
                             else {
-                                r#type(link(p))
+                                self.r#type(self.link(p))
                             };
 
                             if t == GLUE_NODE {
@@ -134,7 +129,7 @@ impl Global {
                             }
                         },
 
-                        PENALTY_NODE => penalty(p),
+                        PENALTY_NODE => self.penalty(p),
 
                         MARK_NODE
                         | INS_NODE => {
@@ -192,7 +187,7 @@ impl Global {
                 }
                 // End section 974
 
-                if r#type(p) < GLUE_NODE || r#type(p) > KERN_NODE {
+                if self.r#type(p) < GLUE_NODE || self.r#type(p) > KERN_NODE {
                     true // Goto not_found
                 }
                 else {
@@ -203,21 +198,27 @@ impl Global {
             // update_heights:
             if !not_found {
                 // Section 976
-                let q = match r#type(p) {
+                let q = match self.r#type(p) {
                     KERN_NODE => p,
 
                     _ => {
-                        let q = glue_ptr(p);
-                        active_height![2 + stretch_order(q) as usize] += stretch(q);
-                        active_height![6] += shrink(q);
-                        if shrink_order(q) != NORMAL && shrink(q) != 0 {
-                            return Err(TeXError::InfiniteGlueShrinkageInBoxBeingSplit);
+                        let mut q = self.glue_ptr(p);
+                        let o = self.stretch_order(q);
+                        active_height![2 + o as usize] += self.stretch(q);
+                        active_height![6] += self.shrink(q);
+                        if self.shrink_order(q) != NORMAL && self.shrink(q) != 0 {
+                            self.error(TeXError::InfiniteGlueShrinkageInBoxBeingSplit)?;
+                            let r = self.new_spec(q)?;
+                            *self.shrink_order_mut(r) = NORMAL;
+                            self.delete_glue_ref(q);
+                            *self.glue_ptr_mut(p) = r;
+                            q = r;
                         }
                         q
                     }
                 };
 
-                cur_height!() += prev_dp + width(q);
+                cur_height!() += prev_dp + self.width(q);
                 prev_dp = 0;
                 // End section 976
             }
@@ -230,13 +231,13 @@ impl Global {
             // End section 972
 
             prev_p = p;
-            p = link(prev_p);
+            p = self.link(prev_p);
         }
     }
 
     // Section 977
     pub(crate) fn vsplit(&mut self, n: u8, h: Scaled) -> TeXResult<HalfWord> {
-        let v = r#box(n as HalfWord);
+        let v = self.r#box(n as HalfWord);
         if self.split_first_mark() != NULL {
             self.delete_token_ref(self.split_first_mark());
             *self.split_first_mark_mut() = NULL;
@@ -248,51 +249,52 @@ impl Global {
         if v == NULL {
             return Ok(NULL);
         }
-        if r#type(v) != VLIST_NODE {
-            return Err(TeXError::VsplitNeedsAVbox);
+        if self.r#type(v) != VLIST_NODE {
+            self.error(TeXError::VsplitNeedsAVbox)?;
+            return Ok(NULL);
         }
         // End section 978
 
-        let mut q = self.vert_break(list_ptr(v), h, split_max_depth())?;
+        let mut q = self.vert_break(self.list_ptr(v), h, self.split_max_depth())?;
         
         // Section 979
-        let mut p = list_ptr(v);
+        let mut p = self.list_ptr(v);
         if p == q {
-            *list_ptr_mut(v) = NULL;
+            *self.list_ptr_mut(v) = NULL;
         }
         else {
             loop {
-                if r#type(p) == MARK_NODE {
+                if self.r#type(p) == MARK_NODE {
                     if self.split_first_mark() == NULL {
-                        *self.split_first_mark_mut() = mark_ptr(p);
+                        *self.split_first_mark_mut() = self.mark_ptr(p);
                         *self.split_bot_mark_mut() = self.split_first_mark();
-                        *token_ref_count_mut(self.split_first_mark()) += 2;
+                        *self.token_ref_count_mut(self.split_first_mark()) += 2;
                     }
                     else {
                         self.delete_token_ref(self.split_bot_mark());
-                        *self.split_bot_mark_mut() = mark_ptr(p);
-                        add_token_ref!(self.split_bot_mark());
+                        *self.split_bot_mark_mut() = self.mark_ptr(p);
+                        add_token_ref!(self, self.split_bot_mark());
                     }
                 }
-                if link(p) == q {
-                    *link_mut(p) = NULL;
+                if self.link(p) == q {
+                    *self.link_mut(p) = NULL;
                     break; // Goto done
                 }
-                p = link(p);
+                p = self.link(p);
             }
         }
         // done:
         // End section 979
         
         q = self.prune_page_top(q)?;
-        p = list_ptr(v);
+        p = self.list_ptr(v);
         self.free_node(v, BOX_NODE_SIZE);
-        *box_mut(n as HalfWord) = if q == NULL {
+        *self.box_mut(n as HalfWord) = if q == NULL {
             NULL
         }
         else {
             vpack!(self, q, NATURAL)?
         };
-        self.vpackage(p, h, EXACTLY, split_max_depth())
+        self.vpackage(p, h, EXACTLY, self.split_max_depth())
     }
 }
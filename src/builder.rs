@@ -5,4 +5,3 @@ mod packaging;
 mod page_builder;
 
 pub(crate) use boxes_and_lists::norm_min;
-pub(crate) use page_builder::broken_ins;
@@ -1,20 +1,8 @@
 use crate::constants::*;
-use crate::datastructures::{
-    mem, mem_mut, adjust_ptr_mut, box_max_depth, box_mut, character, cur_font,
-    depth, depth_mut, every_hbox, every_par, every_vbox, float_cost_mut,
-    floating_penalty, font, glue_ref_count_mut, hang_after, hang_indent,
-    height, height_mut, info_mut, ins_ptr_mut, leader_ptr_mut,
-    left_hyphen_min, link, link_mut, list_ptr, looseness, mark_ptr_mut,
-    par_indent, par_shape_ptr, post_break_mut, pre_break_mut, r#box, r#type,
-    replace_count, replace_count_mut, right_hyphen_min, shift_amount_mut,
-    split_max_depth, split_top_ptr_mut, split_top_skip, subtype_mut, type_mut,
-    widow_penalty, width_mut
-};
 use crate::error::{TeXError, TeXResult};
-use crate::math::{math_type, math_type_mut};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Real, Scaled, SmallNumber,
-    add_glue_ref, contrib_tail, hpack, lig_char, nucleus,
+    add_glue_ref, contrib_tail, hpack, ins_list, lig_char, nucleus,
     sec404_get_next_nonblank_nonrelax_noncall_token, subscr, supscr,
     tail_append, vpack
 };
@@ -36,9 +24,9 @@ impl Global {
         }
         tail_append!(self, self.new_glue(self.cur_val)?);
         if s >= SKIP_CODE {
-            *glue_ref_count_mut(self.cur_val) -= 1;
+            *self.glue_ref_count_mut(self.cur_val) -= 1;
             if s > SKIP_CODE {
-                *subtype_mut(self.tail()) = MU_GLUE;
+                *self.subtype_mut(self.tail()) = MU_GLUE;
             }
         }
         Ok(())
@@ -49,23 +37,44 @@ impl Global {
         let s = self.cur_chr;
         self.scan_dimen(s == (MU_GLUE as HalfWord), false, false)?;
         tail_append!(self, self.new_kern(self.cur_val)?);
-        *subtype_mut(self.tail()) = s as QuarterWord;
+        *self.subtype_mut(self.tail()) = s as QuarterWord;
         Ok(())
     }
 
     // Section 1064
     pub(crate) fn off_save(&mut self) -> TeXResult<()> {
         if self.cur_group == BOTTOM_LEVEL {
-            Err(TeXError::Extra)
+            // Section 1066
+            self.error(TeXError::Extra)
         }
         else {
             // Section 1065
-            match self.cur_group {
-                SEMI_SIMPLE_GROUP => Err(TeXError::MissingEndGroup),
-                MATH_SHIFT_GROUP => Err(TeXError::MissingDollar),
-                MATH_LEFT_GROUP => Err(TeXError::MissingMathRight),
-                _ => Err(TeXError::MissingRightBrace),
-            }
+            self.back_input()?;
+            let mut p = self.get_avail()?;
+            *self.link_mut(self.temp_head()) = p;
+            let texerror = match self.cur_group {
+                SEMI_SIMPLE_GROUP => {
+                    *self.info_mut(p) = CS_TOKEN_FLAG + FROZEN_END_GROUP;
+                    TeXError::MissingEndGroup
+                },
+                MATH_SHIFT_GROUP => {
+                    *self.info_mut(p) = MATH_SHIFT_TOKEN + b'$' as HalfWord;
+                    TeXError::MissingDollar2
+                },
+                MATH_LEFT_GROUP => {
+                    *self.info_mut(p) = CS_TOKEN_FLAG + FROZEN_RIGHT;
+                    *self.link_mut(p) = self.get_avail()?;
+                    p = self.link(p);
+                    *self.info_mut(p) = OTHER_TOKEN + b'.' as HalfWord;
+                    TeXError::MissingMathRight
+                },
+                _ => {
+                    *self.info_mut(p) = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
+                    TeXError::MissingRightBrace
+                }
+            };
+            ins_list!(self, self.link(self.temp_head()));
+            self.error(texerror)
             // End section 1065
         }
     }
@@ -75,17 +84,21 @@ impl Global {
         match self.cur_group {
             SIMPLE_GROUP => self.unsave()?,
 
-            BOTTOM_LEVEL => return Err(TeXError::TooManyRightBraces),
+            BOTTOM_LEVEL => self.error(TeXError::TooManyRightBraces)?,
 
             SEMI_SIMPLE_GROUP
             | MATH_SHIFT_GROUP
-            | MATH_LEFT_GROUP => return Err(TeXError::ExtraRightBraceOrForgotten),
+            | MATH_LEFT_GROUP => {
+                // Section 1069
+                self.error(TeXError::ExtraRightBraceOrForgotten)?;
+                self.align_state += 1;
+            },
             
             // Section 1085
             HBOX_GROUP => self.package(0)?,
 
             ADJUSTED_HBOX_GROUP => {
-                self.adjust_tail = ADJUST_HEAD;
+                self.adjust_tail = self.adjust_head();
                 self.package(0)?;
             },
 
@@ -102,29 +115,29 @@ impl Global {
             // Section 1100
             INSERT_GROUP => {
                 self.end_graf()?;
-                let q = split_top_skip();
-                add_glue_ref!(q);
-                let d = split_max_depth();
-                let f = floating_penalty();
+                let q = self.split_top_skip();
+                add_glue_ref!(self, q);
+                let d = self.split_max_depth();
+                let f = self.floating_penalty();
                 self.unsave()?;
                 self.save_ptr -= 1;
-                let p = vpack!(self, link(self.head()), NATURAL)?;
+                let p = vpack!(self, self.link(self.head()), NATURAL)?;
                 self.pop_nest();
                 if self.saved(0) < 255 {
                     tail_append!(self, self.get_node(INS_NODE_SIZE)?);
-                    *type_mut(self.tail()) = INS_NODE;
-                    *subtype_mut(self.tail()) = self.saved(0) as QuarterWord;
-                    *height_mut(self.tail()) = height(p) + depth(p);
-                    *ins_ptr_mut(self.tail()) = list_ptr(p);
-                    *split_top_ptr_mut(self.tail()) = q;
-                    *depth_mut(self.tail()) = d;
-                    *float_cost_mut(self.tail()) = f;
+                    *self.type_mut(self.tail()) = INS_NODE;
+                    *self.subtype_mut(self.tail()) = self.saved(0) as QuarterWord;
+                    *self.height_mut(self.tail()) = self.height(p) + self.depth(p);
+                    *self.ins_ptr_mut(self.tail()) = self.list_ptr(p);
+                    *self.split_top_ptr_mut(self.tail()) = q;
+                    *self.depth_mut(self.tail()) = d;
+                    *self.float_cost_mut(self.tail()) = f;
                 }
                 else {
                     tail_append!(self, self.get_node(SMALL_NODE_SIZE)?);
-                    *type_mut(self.tail()) = ADJUST_NODE;
-                    *subtype_mut(self.tail()) = 0;
-                    *adjust_ptr_mut(self.tail()) = list_ptr(p);
+                    *self.type_mut(self.tail()) = ADJUST_NODE;
+                    *self.subtype_mut(self.tail()) = 0;
+                    *self.adjust_ptr_mut(self.tail()) = self.list_ptr(p);
                     self.delete_glue_ref(q);
                 }
                 self.free_node(p, BOX_NODE_SIZE);
@@ -136,7 +149,15 @@ impl Global {
             OUTPUT_GROUP => {
                 // Section 1026
                 if self.loc() != NULL || (self.token_type() != OUTPUT_TEXT && self.token_type() != BACKED_UP) {
-                    return Err(TeXError::UnbalancedOutputRoutine);
+                    // Section 1027
+                    self.error(TeXError::UnbalancedOutputRoutine)?;
+                    loop {
+                        self.get_token()?;
+                        if self.loc() == NULL {
+                            break;
+                        }
+                    }
+                    // End section 1027
                 }
                 self.end_token_list()?;
                 self.end_graf()?;
@@ -145,23 +166,23 @@ impl Global {
                 self.insert_penalties = 0;
 
                 // Section 1028
-                if r#box(255) != NULL {
-                    return Err(TeXError::OutputRoutineDidntUseAllOfBox255);
+                if self.r#box(255) != NULL {
+                    self.box_error(TeXError::OutputRoutineDidntUseAllOfBox255, 255)?;
                 }
                 // End section 1028
 
                 if self.tail() != self.head() {
-                    *link_mut(self.page_tail) = link(self.head());
+                    *self.link_mut(self.page_tail) = self.link(self.head());
                     self.page_tail = self.tail();
                 }
-                if link(PAGE_HEAD) != NULL {
-                    if link(CONTRIB_HEAD) == NULL {
+                if self.link(self.page_head()) != NULL {
+                    if self.link(self.contrib_head()) == NULL {
                         contrib_tail!(self) = self.page_tail;
                     }
-                    *link_mut(self.page_tail) = link(CONTRIB_HEAD);
-                    *link_mut(CONTRIB_HEAD) = link(PAGE_HEAD);
-                    *link_mut(PAGE_HEAD) = NULL;
-                    self.page_tail = PAGE_HEAD;
+                    *self.link_mut(self.page_tail) = self.link(self.contrib_head());
+                    *self.link_mut(self.contrib_head()) = self.link(self.page_head());
+                    *self.link_mut(self.page_head()) = NULL;
+                    self.page_tail = self.page_head();
                 }
                 self.pop_nest();
                 self.build_page()?;
@@ -174,7 +195,11 @@ impl Global {
             // End section 1118
 
             // Section 1132
-            ALIGN_GROUP => return Err(TeXError::MissingCr),
+            ALIGN_GROUP => {
+                self.back_input()?;
+                self.cur_tok = CS_TOKEN_FLAG + FROZEN_CR;
+                self.ins_error(TeXError::MissingCr)?;
+            },
             // End section 1132
 
             // Section 1133
@@ -190,12 +215,12 @@ impl Global {
                 self.end_graf()?;
                 self.unsave()?;
                 self.save_ptr -= 2;
-                let p = vpack!(self, link(self.head()), self.saved(1), self.saved(0) as QuarterWord)?;
+                let p = vpack!(self, self.link(self.head()), self.saved(1), self.saved(0) as QuarterWord)?;
                 self.pop_nest();
                 tail_append!(self, self.new_noad()?);
-                *type_mut(self.tail()) = VCENTER_NOAD;
-                *math_type_mut(nucleus!(self.tail())) = SUB_BOX;
-                *info_mut(nucleus!(self.tail())) = p;
+                *self.type_mut(self.tail()) = VCENTER_NOAD;
+                *self.math_type_mut(nucleus!(self.tail())) = SUB_BOX;
+                *self.info_mut(nucleus!(self.tail())) = p;
             },
             // End section 1168
 
@@ -207,23 +232,23 @@ impl Global {
             MATH_GROUP => {
                 self.unsave()?;
                 self.save_ptr -= 1;
-                *math_type_mut(self.saved(0)) = SUB_MLIST;
+                *self.math_type_mut(self.saved(0)) = SUB_MLIST;
                 let p = self.fin_mlist(NULL)?;
-                *info_mut(self.saved(0)) = p;
-                if p != NULL && link(p) == NULL {
-                    if r#type(p) == ORD_NOAD {
-                        if math_type(subscr!(p)) == EMPTY && math_type(supscr!(p)) == EMPTY {
-                            *mem_mut(self.saved(0) as usize) = mem(nucleus!(p) as usize);
+                *self.info_mut(self.saved(0)) = p;
+                if p != NULL && self.link(p) == NULL {
+                    if self.r#type(p) == ORD_NOAD {
+                        if self.math_type(subscr!(p)) == EMPTY && self.math_type(supscr!(p)) == EMPTY {
+                            *self.mem_mut(self.saved(0) as usize) = self.mem(nucleus!(p) as usize);
                             self.free_node(p, NOAD_SIZE);
                         }
                     }
-                    else if r#type(p) == ACCENT_NOAD && self.saved(0) == nucleus!(self.tail()) && r#type(self.tail()) == ORD_NOAD {
+                    else if self.r#type(p) == ACCENT_NOAD && self.saved(0) == nucleus!(self.tail()) && self.r#type(self.tail()) == ORD_NOAD {
                         // Section 1187
                         let mut q = self.head();
-                        while link(q) != self.tail() {
-                            q = link(q);
+                        while self.link(q) != self.tail() {
+                            q = self.link(q);
                         }
-                        *link_mut(q) = p;
+                        *self.link_mut(q) = p;
                         self.free_node(self.tail(), NOAD_SIZE);
                         *self.tail_mut() = p;
                         // End section 1187
@@ -240,16 +265,16 @@ impl Global {
 
     // Section 1070
     pub(crate) fn normal_paragraph(&mut self) -> TeXResult<()> {
-        if looseness() != 0 {
+        if self.looseness() != 0 {
             self.eq_word_define(INT_BASE + LOOSENESS_CODE, 0)?;
         }
-        if hang_indent() != 0 {
+        if self.hang_indent() != 0 {
             self.eq_word_define(DIMEN_BASE + HANG_INDENT_CODE, 0)?;
         }
-        if hang_after() != 1 {
+        if self.hang_after() != 1 {
             self.eq_word_define(INT_BASE + HANG_AFTER_CODE, 1)?;
         }
-        if par_shape_ptr() != NULL {
+        if self.par_shape_ptr() != NULL {
             self.eq_define(PAR_SHAPE_LOC, SHAPE_REF, NULL)?;
         }
         Ok(())
@@ -260,12 +285,12 @@ impl Global {
         if box_content < BOX_FLAG {
             // Section 1076
             if self.cur_box != NULL {
-                *shift_amount_mut(self.cur_box) = box_content;
+                *self.shift_amount_mut(self.cur_box) = box_content;
                 if self.mode().abs() == VMODE {
                     self.append_to_vlist(self.cur_box)?;
                     if self.adjust_tail != NULL {
-                        if ADJUST_HEAD != self.adjust_tail {
-                            *link_mut(self.tail()) = link(ADJUST_HEAD);
+                        if self.adjust_head() != self.adjust_tail {
+                            *self.link_mut(self.tail()) = self.link(self.adjust_head());
                             *self.tail_mut() = self.adjust_tail;
                         }
                         self.adjust_tail = NULL;
@@ -280,11 +305,11 @@ impl Global {
                     }
                     else {
                         let p = self.new_noad()?;
-                        *math_type_mut(nucleus!(p)) = SUB_BOX;
-                        *info_mut(nucleus!(p)) = self.cur_box;
+                        *self.math_type_mut(nucleus!(p)) = SUB_BOX;
+                        *self.info_mut(nucleus!(p)) = self.cur_box;
                         self.cur_box = p;
                     }
-                    *link_mut(self.tail()) = self.cur_box;
+                    *self.link_mut(self.tail()) = self.cur_box;
                     *self.tail_mut() = self.cur_box;
                 }
             }
@@ -308,11 +333,12 @@ impl Global {
                     || self.cur_cmd == VSKIP && self.mode().abs() == VMODE
                 {
                     self.append_glue()?;
-                    *subtype_mut(self.tail()) = (box_content - (LEADER_FLAG - (A_LEADERS as Integer))) as QuarterWord;
-                    *leader_ptr_mut(self.tail()) = self.cur_box;
+                    *self.subtype_mut(self.tail()) = (box_content - (LEADER_FLAG - (A_LEADERS as Integer))) as QuarterWord;
+                    *self.leader_ptr_mut(self.tail()) = self.cur_box;
                 }
                 else {
-                    return Err(TeXError::LeadersNotFollowedByProperGlue);
+                    self.back_error(TeXError::LeadersNotFollowedByProperGlue)?;
+                    self.flush_node_list(self.cur_box)?;
                 }
                 // End section 1078
             }
@@ -328,45 +354,45 @@ impl Global {
         match self.cur_chr {
             BOX_CODE => {
                 self.scan_eight_bit_int()?;
-                self.cur_box = r#box(self.cur_val);
-                *box_mut(self.cur_val) = NULL;
+                self.cur_box = self.r#box(self.cur_val);
+                *self.box_mut(self.cur_val) = NULL;
             },
 
             COPY_CODE => {
                 self.scan_eight_bit_int()?;
-                self.cur_box = self.copy_node_list(r#box(self.cur_val))?;
+                self.cur_box = self.copy_node_list(self.r#box(self.cur_val))?;
             },
 
             LAST_BOX_CODE => {
                 // Section 1080
                 self.cur_box = NULL;
                 if self.mode().abs() == MMODE {
-                    return Err(TeXError::CantUseIn);
+                    self.error(TeXError::CantUseIn)?;
                 }
-                if self.mode() == VMODE && self.head() == self.tail() {
-                    return Err(TeXError::CantUseIn2);
+                else if self.mode() == VMODE && self.head() == self.tail() {
+                    self.error(TeXError::CantUseIn2)?;
                 }
-                if !self.is_char_node(self.tail())
-                    && (r#type(self.tail()) == HLIST_NODE || r#type(self.tail()) == VLIST_NODE)
+                else if !self.is_char_node(self.tail())
+                    && (self.r#type(self.tail()) == HLIST_NODE || self.r#type(self.tail()) == VLIST_NODE)
                 {
                     // Section 1081
                     let mut q = self.head();
                     loop {
                         let mut p = q;
-                        if !self.is_char_node(q) && r#type(q) == DISC_NODE {
-                            for _ in 1..=replace_count(q) {
-                                p = link(p);
+                        if !self.is_char_node(q) && self.r#type(q) == DISC_NODE {
+                            for _ in 1..=self.replace_count(q) {
+                                p = self.link(p);
                             }
                             if p == self.tail() {
                                 break; // Goto done
                             }
                         }
-                        q = link(p);
+                        q = self.link(p);
                         if q == self.tail() {
                             self.cur_box = self.tail();
-                            *shift_amount_mut(self.cur_box) = 0;
+                            *self.shift_amount_mut(self.cur_box) = 0;
                             *self.tail_mut() = p;
-                            *link_mut(p) = NULL;    
+                            *self.link_mut(p) = NULL;    
                             break;
                         }
                     }
@@ -381,7 +407,7 @@ impl Global {
                 self.scan_eight_bit_int()?;
                 let n = self.cur_val;
                 if !self.scan_keyword(b"to")? {
-                    return Err(TeXError::MissingTo);
+                    self.error(TeXError::MissingTo)?;
                 }
                 self.scan_dimen(false, false, false)?;
                 self.cur_box = self.vsplit(n as u8, self.cur_val)?;
@@ -414,14 +440,14 @@ impl Global {
                 *self.mode_mut() = -k;
                 if k == VMODE {
                     *self.prev_depth_mut() = IGNORE_DEPTH;
-                    if every_vbox() != NULL {
-                        self.begin_token_list(every_vbox(), EVERY_VBOX_TEXT)?;
+                    if self.every_vbox() != NULL {
+                        self.begin_token_list(self.every_vbox(), EVERY_VBOX_TEXT)?;
                     }
                 }
                 else {
                     *self.space_factor_mut() = 1000;
-                    if every_hbox() != NULL {
-                        self.begin_token_list(every_hbox(), EVERY_HBOX_TEXT)?;
+                    if self.every_hbox() != NULL {
+                        self.begin_token_list(self.every_hbox(), EVERY_HBOX_TEXT)?;
                     }
                 }
                 return Ok(());
@@ -442,32 +468,32 @@ impl Global {
             self.box_end(box_content)
         }
         else {
-            Err(TeXError::BoxWasSupposedToBeHere)
+            self.back_error(TeXError::BoxWasSupposedToBeHere)
         }
     }
 
     // Section 1086
     pub(crate) fn package(&mut self, c: SmallNumber) -> TeXResult<()> {
-        let d = box_max_depth();
+        let d = self.box_max_depth();
         self.unsave()?;
         self.save_ptr -= 3;
         if self.mode() == -HMODE {
-            self.cur_box = hpack!(self, link(self.head()), self.saved(2), self.saved(1) as SmallNumber)?;
+            self.cur_box = hpack!(self, self.link(self.head()), self.saved(2), self.saved(1) as SmallNumber)?;
         }
         else {
-            self.cur_box = self.vpackage(link(self.head()), self.saved(2), self.saved(1) as SmallNumber, d)?;
+            self.cur_box = self.vpackage(self.link(self.head()), self.saved(2), self.saved(1) as SmallNumber, d)?;
             if c == VTOP_CODE as SmallNumber {
                 // Section 1087
-                let p = list_ptr(self.cur_box);
-                let h = if p != NULL && r#type(p) <= RULE_NODE {
-                    height(p)
+                let p = self.list_ptr(self.cur_box);
+                let h = if p != NULL && self.r#type(p) <= RULE_NODE {
+                    self.height(p)
                 }
                 else {
                     0
                 };
 
-                *depth_mut(self.cur_box) += - h + height(self.cur_box);
-                *height_mut(self.cur_box) = h;
+                *self.depth_mut(self.cur_box) += - h + self.height(self.cur_box);
+                *self.height_mut(self.cur_box) = h;
                 // End section 1087
             }
         }
@@ -501,14 +527,14 @@ impl Global {
         *self.space_factor_mut() = 1000;
         self.set_cur_lang();
         *self.clang_mut() = self.cur_lang as HalfWord;
-        *self.prev_graf_mut() = (norm_min(left_hyphen_min())*64 + norm_min(right_hyphen_min()))*65536 + self.cur_lang as Integer;
+        *self.prev_graf_mut() = (norm_min(self.left_hyphen_min())*64 + norm_min(self.right_hyphen_min()))*65536 + self.cur_lang as Integer;
         if indented {
             *self.tail_mut() = self.new_null_box()?;
-            *link_mut(self.head()) = self.tail();
-            *width_mut(self.tail()) = par_indent();
+            *self.link_mut(self.head()) = self.tail();
+            *self.width_mut(self.tail()) = self.par_indent();
         }
-        if every_par() != NULL {
-            self.begin_token_list(every_par(), EVERY_PAR_TEXT)?;
+        if self.every_par() != NULL {
+            self.begin_token_list(self.every_par(), EVERY_PAR_TEXT)?;
         }
         if self.nest_ptr == 1 {
             self.build_page()?
@@ -520,14 +546,14 @@ impl Global {
     pub(crate) fn indent_in_hmode(&mut self) -> TeXResult<()> {
         if self.cur_chr > 0 {
             let mut p = self.new_null_box()?;
-            *width_mut(p) = par_indent();
+            *self.width_mut(p) = self.par_indent();
             if self.mode().abs() == HMODE {
                 *self.space_factor_mut() = 1000;
             }
             else {
                 let q = self.new_noad()?;
-                *math_type_mut(nucleus!(q)) = SUB_BOX;
-                *info_mut(nucleus!(q)) = p;
+                *self.math_type_mut(nucleus!(q)) = SUB_BOX;
+                *self.info_mut(nucleus!(q)) = p;
                 p = q;
             }
             tail_append!(self, p);
@@ -542,7 +568,7 @@ impl Global {
                 self.off_save()
             }
             else {
-                Err(TeXError::CantUseHrule)
+                self.error(TeXError::CantUseHrule)
             }
         }
         else {
@@ -561,10 +587,10 @@ impl Global {
                 self.pop_nest();
             }
             else {
-                self.line_break(widow_penalty())?;
+                self.line_break(self.widow_penalty())?;
             }
             self.normal_paragraph()?;
-            // no error_count
+            self.error_count = 0;
         }
         Ok(())
     }
@@ -577,7 +603,8 @@ impl Global {
         else {
             self.scan_eight_bit_int()?;
             if self.cur_val == 255 {
-                return Err(TeXError::CantInsert255);
+                self.error(TeXError::CantInsert255)?;
+                self.cur_val = 0;
             }
         }
         *self.saved_mut(0) = self.cur_val;
@@ -595,10 +622,10 @@ impl Global {
     pub(crate) fn make_mark(&mut self) -> TeXResult<()> {
         let _ = self.scan_toks(false, true)?;
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = MARK_NODE;
-        *subtype_mut(p) = 0;
-        *mark_ptr_mut(p) = self.def_ref;
-        *link_mut(self.tail()) = p;
+        *self.type_mut(p) = MARK_NODE;
+        *self.subtype_mut(p) = 0;
+        *self.mark_ptr_mut(p) = self.def_ref;
+        *self.link_mut(self.tail()) = p;
         *self.tail_mut() = p;
         Ok(())
     }
@@ -618,31 +645,31 @@ impl Global {
         if self.mode() == VMODE && self.tail() == self.head() {
             // Section 1106
             if self.cur_chr != (GLUE_NODE as HalfWord) || self.last_glue != MAX_HALFWORD {
-                return Err(TeXError::CantTakeThings);
+                self.error(TeXError::CantTakeThings)?;
             }
             // End section 1106
         }
         else if !self.is_char_node(self.tail())
-            && (r#type(self.tail()) as HalfWord) == self.cur_chr
+            && (self.r#type(self.tail()) as HalfWord) == self.cur_chr
         {
             let mut q = self.head();
             let mut p: HalfWord;
             loop {
                 p = q;
-                if !self.is_char_node(q) && r#type(q) == DISC_NODE {
-                    for _ in 1..=replace_count(q) {
-                        p = link(p);
+                if !self.is_char_node(q) && self.r#type(q) == DISC_NODE {
+                    for _ in 1..=self.replace_count(q) {
+                        p = self.link(p);
                     }
                     if p == self.tail() {
                         return Ok(())
                     }
                 }
-                q = link(p);
+                q = self.link(p);
                 if q == self.tail() {
                     break;
                 }
             }
-            *link_mut(p) = NULL;
+            *self.link_mut(p) = NULL;
             self.flush_node_list(self.tail())?;
             *self.tail_mut() = p;
         }
@@ -653,26 +680,27 @@ impl Global {
     pub(crate) fn unpackage(&mut self) -> TeXResult<()> {
         let c = self.cur_chr;
         self.scan_eight_bit_int()?;
-        let p = r#box(self.cur_val);
+        let p = self.r#box(self.cur_val);
         if p == NULL {
             return Ok(())
         }
         if self.mode().abs() == MMODE
-            || (self.mode().abs() == VMODE && r#type(p) != VLIST_NODE)
-            || (self.mode().abs() == HMODE && r#type(p) != HLIST_NODE)
+            || (self.mode().abs() == VMODE && self.r#type(p) != VLIST_NODE)
+            || (self.mode().abs() == HMODE && self.r#type(p) != HLIST_NODE)
         {
-            return Err(TeXError::IncompatibleListCantBeUnboxed);
+            self.error(TeXError::IncompatibleListCantBeUnboxed)?;
+            return Ok(());
         }
         if c == COPY_CODE {
-            *link_mut(self.tail()) = self.copy_node_list(list_ptr(p))?;
+            *self.link_mut(self.tail()) = self.copy_node_list(self.list_ptr(p))?;
         }
         else {
-            *link_mut(self.tail()) = list_ptr(p);
-            *box_mut(self.cur_val) = NULL;
+            *self.link_mut(self.tail()) = self.list_ptr(p);
+            *self.box_mut(self.cur_val) = NULL;
             self.free_node(p, BOX_NODE_SIZE);
         }
-        while link(self.tail()) != NULL {
-            *self.tail_mut() = link(self.tail());
+        while self.link(self.tail()) != NULL {
+            *self.tail_mut() = self.link(self.tail());
         }
         Ok(())
     }
@@ -683,17 +711,17 @@ impl Global {
             let p = if self.is_char_node(self.tail()) {
                 self.tail()
             }
-            else if r#type(self.tail()) == LIGATURE_NODE {
+            else if self.r#type(self.tail()) == LIGATURE_NODE {
                 lig_char!(self.tail())
             }
             else {
                 return Ok(())
             };
 
-            let f = font(p);
-            let tmp = self.char_italic(f, self.char_info(f, character(p)));
+            let f = self.font(p);
+            let tmp = self.char_italic(f, self.char_info(f, self.character(p)));
             tail_append!(self, self.new_kern(tmp)?);
-            *subtype_mut(self.tail()) = EXPLICIT;
+            *self.subtype_mut(self.tail()) = EXPLICIT;
         }
         Ok(())
     }
@@ -702,9 +730,9 @@ impl Global {
     pub(crate) fn append_discretionary(&mut self) -> TeXResult<()> {
         tail_append!(self, self.new_disc()?);
         if self.cur_chr == 1 {
-            let c = self.hyphen_char[cur_font() as usize];
+            let c = self.hyphen_char[self.cur_font() as usize];
             if (0..=255).contains(&c) {
-                *pre_break_mut(self.tail()) = self.new_character(cur_font() as QuarterWord, c as u8)?;
+                *self.pre_break_mut(self.tail()) = self.new_character(self.cur_font() as QuarterWord, c as u8)?;
             }
         }
         else {
@@ -725,40 +753,51 @@ impl Global {
         
         // Section 1121
         let mut q = self.head();
-        let mut p = link(q);
+        let mut p = self.link(q);
         let mut n = 0;
         while p != NULL {
             if !self.is_char_node(p)
-                && r#type(p) > RULE_NODE
-                && r#type(p) != KERN_NODE
-                && r#type(p) != LIGATURE_NODE
+                && self.r#type(p) > RULE_NODE
+                && self.r#type(p) != KERN_NODE
+                && self.r#type(p) != LIGATURE_NODE
             {
-                return Err(TeXError::ImproperDiscList);
+                self.error(TeXError::ImproperDiscList)?;
+                self.begin_diagnostic();
+                self.print_nl("The following discretionary sublist has been deleted:");
+                self.show_box(p);
+                self.end_diagnostic(true);
+                self.flush_node_list(p)?;
+                *self.link_mut(q) = NULL;
+                break;
             }
             q = p;
-            p = link(q);
+            p = self.link(q);
             n += 1;
         }
         // End section 1121
         
-        p = link(self.head());
+        p = self.link(self.head());
         self.pop_nest();
         match self.saved(-1) {
-            0 => *pre_break_mut(self.tail()) = p,
+            0 => *self.pre_break_mut(self.tail()) = p,
 
-            1 => *post_break_mut(self.tail()) = p,
+            1 => *self.post_break_mut(self.tail()) = p,
 
             _ /* 2 */ => {
                 // Section 1120
                 if n > 0 && self.mode().abs() == MMODE {
-                    return Err(TeXError::IllegalMathDisc);
+                    self.flush_node_list(p)?;
+                    n = 0;
+                    self.error(TeXError::IllegalMathDisc)?;
+                }
+                else {
+                    *self.link_mut(self.tail()) = p;
                 }
-                *link_mut(self.tail()) = p;
                 if n <= MAX_QUARTERWORD as Integer {
-                    *replace_count_mut(self.tail()) = n as QuarterWord;
+                    *self.replace_count_mut(self.tail()) = n as QuarterWord;
                 }
                 else {
-                    return Err(TeXError::DiscListTooLong);
+                    self.error(TeXError::DiscListTooLong)?;
                 }
                 if n > 0 {
                     *self.tail_mut() = q;
@@ -780,17 +819,17 @@ impl Global {
     // Section 1123
     pub(crate) fn make_accent(&mut self) -> TeXResult<()> {
         self.scan_char_num()?;
-        let mut f = cur_font() as QuarterWord;
+        let mut f = self.cur_font() as QuarterWord;
         let mut p = self.new_character(f, self.cur_val as u8)?;
         if p != NULL {
             let x = self.x_height(f);
             let s = self.slant(f) as Real / 65536.0;
-            let a = self.char_width(f, self.char_info(f, character(p)));
+            let a = self.char_width(f, self.char_info(f, self.character(p)));
             self.do_assignments()?;
 
             // Section 1124
             let mut q = NULL;
-            f = cur_font() as QuarterWord;
+            f = self.cur_font() as QuarterWord;
             if self.cur_cmd == LETTER
                 || self.cur_cmd == OTHER_CHAR
                 || self.cur_cmd == CHAR_GIVEN
@@ -809,25 +848,25 @@ impl Global {
             if q != NULL {
                 // Section 1125
                 let t = self.slant(f) as Real / 65536.0;
-                let i = self.char_info(f, character(q));
+                let i = self.char_info(f, self.character(q));
                 let w = self.char_width(f, i);
                 let h = self.char_height(f, i.height_depth());
                 if h != x {
                     p = hpack!(self, p, NATURAL)?;
-                    *shift_amount_mut(p) = x - h;
+                    *self.shift_amount_mut(p) = x - h;
                 }
                 let delta = ((w - a) as Real / 2.0 + (h as Real)*t - (x as Real)*s).round() as Scaled;
                 let r = self.new_kern(delta)?;
-                *subtype_mut(r) = ACC_KERN;
-                *link_mut(self.tail()) = r;
-                *link_mut(r) = p;
+                *self.subtype_mut(r) = ACC_KERN;
+                *self.link_mut(self.tail()) = r;
+                *self.link_mut(r) = p;
                 *self.tail_mut() = self.new_kern(-a - delta)?;
-                *subtype_mut(self.tail()) = ACC_KERN;
-                *link_mut(p) = self.tail();
+                *self.subtype_mut(self.tail()) = ACC_KERN;
+                *self.link_mut(p) = self.tail();
                 p = q;
                 // End section 1125
             }
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             *self.tail_mut() = p;
             *self.space_factor_mut() = 1000;
         }
@@ -837,15 +876,19 @@ impl Global {
     // Section 1127
     pub(crate) fn align_error(&mut self) -> TeXResult<()> {
         if self.align_state.abs() > 2 {
-            Err(TeXError::MisplacedTabMark)
+            self.error(TeXError::MisplacedTabMark)
         }
         else {
             self.back_input()?;
             if self.align_state < 0 {
-                Err(TeXError::MissingLeftBrace3)
+                self.align_state += 1;
+                self.cur_tok = LEFT_BRACE_TOKEN + b'{' as HalfWord;
+                self.ins_error(TeXError::MissingLeftBrace3)
             }
             else {
-                Err(TeXError::MissingRightBrace2)
+                self.align_state -= 1;
+                self.cur_tok = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
+                self.ins_error(TeXError::MissingRightBrace2)
             }
         }
     }
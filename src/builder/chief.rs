@@ -1,11 +1,4 @@
-use crate::arithmetic::xn_over_d;
 use crate::constants::*;
-use crate::datastructures::{
-    character, character_mut, cur_font, every_job, every_vbox, font_mut,
-    glue_ref_count_mut, hsize, language, lig_ptr, lig_ptr_mut, link, link_mut,
-    math_code, sf_code, shrink, shrink_mut, space_skip, stretch, stretch_mut,
-    subtype_mut, tracing_commands, type_mut, width_mut, xspace_skip
-};
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, fast_get_avail, free_avail,
@@ -30,8 +23,8 @@ enum Goto {
 impl Global {
     // Section 1030
     pub fn main_control(&mut self) -> TeXResult<()> {
-        if every_job() != NULL {
-            self.begin_token_list(every_job(), EVERY_JOB_TEXT)?;
+        if self.every_job() != NULL {
+            self.begin_token_list(self.every_job(), EVERY_JOB_TEXT)?;
         }
 
         'big_switch: loop {
@@ -48,7 +41,7 @@ impl Global {
                 if self.panicking {
                     self.check_mem(false);
                 }
-                if tracing_commands() > 0 {
+                if self.tracing_commands() > 0 {
                     self.show_cur_cmd_chr();
                 }
                 // End section 1031
@@ -116,7 +109,7 @@ impl Global {
                     | (VMODE, ITAL_CORR) // Section 1111
                     | (VMODE, EQ_NO) // Section 1144
                     | (HMODE, EQ_NO) // End section 1048
-                    | (_, MAC_PARAM) => return Err(TeXError::ReportIllegalCase),
+                    | (_, MAC_PARAM) => self.report_illegal_case()?,
 
                     // Section 1046 (math only cases in non-math mode, or vice-versa)
                     (VMODE, SUP_MARK)
@@ -159,7 +152,7 @@ impl Global {
                     | (MMODE, VSKIP)
                     | (MMODE, UN_VBOX)
                     | (MMODE, VALIGN)
-                    | (MMODE, HRULE) => return Err(TeXError::MissingDollar),
+                    | (MMODE, HRULE) => self.insert_dollar_sign()?,
                     // End section 1046
 
                     // Section 1056
@@ -315,9 +308,9 @@ impl Global {
                     (_, CAR_RET)
                     | (_, TAB_MARK) => self.align_error()?,
 
-                    (_, NO_ALIGN) => return Err(TeXError::MisplacedNoalign),
+                    (_, NO_ALIGN) => self.error(TeXError::MisplacedNoalign)?,
 
-                    (_, OMIT) => return Err(TeXError::MisplacedOmit),
+                    (_, OMIT) => self.error(TeXError::MisplacedOmit)?,
                     // End section 1126
 
                     // Section 1130
@@ -325,12 +318,13 @@ impl Global {
                     | (HMODE, VALIGN) => self.init_align()?,
 
                     (MMODE, HALIGN) => {
-                        self.privileged()?;
-                        if self.cur_group == MATH_SHIFT_GROUP {
-                            self.init_align()?;
-                        }
-                        else {
-                            self.off_save()?;
+                        if self.privileged()? {
+                            if self.cur_group == MATH_SHIFT_GROUP {
+                                self.init_align()?;
+                            }
+                            else {
+                                self.off_save()?;
+                            }
                         }
                     },
 
@@ -339,7 +333,7 @@ impl Global {
                     // End section 1130
 
                     // Section 1134
-                    (_, END_CS_NAME) => return Err(TeXError::ExtraEndcsname),
+                    (_, END_CS_NAME) => self.error(TeXError::ExtraEndcsname)?,
                     // End section 1134
 
                     // Section 1137
@@ -348,12 +342,13 @@ impl Global {
 
                     // Section 1140
                     (MMODE, EQ_NO) => {
-                        self.privileged()?;
-                        if self.cur_group == MATH_SHIFT_GROUP {
-                            self.start_eq_no()?;
-                        }
-                        else {
-                            self.off_save()?;
+                        if self.privileged()? {
+                            if self.cur_group == MATH_SHIFT_GROUP {
+                                self.start_eq_no()?;
+                            }
+                            else {
+                                self.off_save()?;
+                            }
                         }
                     },
                     // End section 1140
@@ -369,12 +364,12 @@ impl Global {
                     // Section 1154
                     (MMODE, LETTER)
                     | (MMODE, OTHER_CHAR)
-                    | (MMODE, CHAR_GIVEN) => self.set_math_char(ho!(math_code(self.cur_chr)))?,
+                    | (MMODE, CHAR_GIVEN) => self.set_math_char(ho!(self.math_code(self.cur_chr)))?,
 
                     (MMODE, CHAR_NUM) => {
                         self.scan_char_num()?;
                         self.cur_chr = self.cur_val;
-                        self.set_math_char(ho!(math_code(self.cur_chr)))?;
+                        self.set_math_char(ho!(self.math_code(self.cur_chr)))?;
                     },
 
                     (MMODE, MATH_CHAR_NUM) => {
@@ -393,7 +388,7 @@ impl Global {
                     // Section 1158
                     (MMODE, MATH_COMP) => {
                         tail_append!(self, self.new_noad()?);
-                        *type_mut(self.tail()) = self.cur_chr as QuarterWord;
+                        *self.type_mut(self.tail()) = self.cur_chr as QuarterWord;
                         self.scan_math(nucleus!(self.tail()))?;
                     },
 
@@ -416,8 +411,8 @@ impl Global {
                         self.push_nest()?;
                         *self.mode_mut() = -VMODE;
                         *self.prev_depth_mut() = IGNORE_DEPTH;
-                        if every_vbox() != NULL {
-                            self.begin_token_list(every_vbox(), EVERY_VBOX_TEXT)?;
+                        if self.every_vbox() != NULL {
+                            self.begin_token_list(self.every_vbox(), EVERY_VBOX_TEXT)?;
                         }
                     },
                     // End section 1167
@@ -427,7 +422,7 @@ impl Global {
 
                     (MMODE, NON_SCRIPT) => {
                         tail_append!(self, self.new_glue(ZERO_GLUE)?);
-                        *subtype_mut(self.tail()) = COND_MATH_GLUE;
+                        *self.subtype_mut(self.tail()) = COND_MATH_GLUE;
                     },
 
                     (MMODE, MATH_CHOICE) => self.append_choices()?,
@@ -536,16 +531,16 @@ impl Global {
                 if goto == Goto::MainLoop {
                     // Section 1034
                     self.adjust_space_factor();
-                    self.main_f = cur_font() as QuarterWord;
+                    self.main_f = self.cur_font() as QuarterWord;
                     self.bchar = self.font_bchar[self.main_f as usize] as HalfWord;
                     self.false_bchar = self.font_false_bchar[self.main_f as usize] as HalfWord;
-                    if self.mode() > 0 && language() != self.clang() {
+                    if self.mode() > 0 && self.language() != self.clang() {
                         self.fix_language()?;
                     }
                     fast_get_avail!(self, self.lig_stack);
-                    *font_mut(self.lig_stack) = self.main_f;
+                    *self.font_mut(self.lig_stack) = self.main_f;
                     self.cur_l = self.cur_chr;
-                    *character_mut(self.lig_stack) = self.cur_l as QuarterWord;
+                    *self.character_mut(self.lig_stack) = self.cur_l as QuarterWord;
                     self.cur_q = self.tail();
                     self.main_k = if self.cancel_boundary {
                         self.cancel_boundary = false;
@@ -585,16 +580,17 @@ impl Global {
 
                 // append_normal_space:
                 // Section 1041
-                self.temp_ptr = if space_skip() == ZERO_GLUE {
+                self.temp_ptr = if self.space_skip() == ZERO_GLUE {
                     // Section 1042
-                    self.main_p = self.font_glue[cur_font() as usize];
+                    self.main_p = self.font_glue[self.cur_font() as usize];
                     if self.main_p == NULL {
                         self.main_p = self.new_spec(ZERO_GLUE)?;
-                        self.main_k = self.param_base[cur_font() as usize] + SPACE_CODE;
-                        *width_mut(self.main_p) = self.font_info[self.main_k as usize].sc();
-                        *stretch_mut(self.main_p) = self.font_info[(self.main_k + 1) as usize].sc();
-                        *shrink_mut(self.main_p) = self.font_info[(self.main_k + 2) as usize].sc();
-                        self.font_glue[cur_font() as usize] = self.main_p;
+                        self.main_k = self.param_base[self.cur_font() as usize] + SPACE_CODE;
+                        *self.width_mut(self.main_p) = self.font_info[self.main_k as usize].sc();
+                        *self.stretch_mut(self.main_p) = self.font_info[(self.main_k + 1) as usize].sc();
+                        *self.shrink_mut(self.main_p) = self.font_info[(self.main_k + 2) as usize].sc();
+                        let f = self.cur_font() as usize;
+                        self.font_glue[f] = self.main_p;
                     }
                     // End section 1042
                     self.new_glue(self.main_p)?
@@ -603,7 +599,7 @@ impl Global {
                     self.new_param_glue(SPACE_SKIP_CODE as QuarterWord)?
                 };
 
-                *link_mut(self.tail()) = self.temp_ptr;
+                *self.link_mut(self.tail()) = self.temp_ptr;
                 *self.tail_mut() = self.temp_ptr;
                 continue 'big_switch;
                 // End section 1041
@@ -613,7 +609,7 @@ impl Global {
 
     // Section 1034
     fn adjust_space_factor(&mut self) {
-        self.main_s = sf_code(self.cur_chr);
+        self.main_s = self.sf_code(self.cur_chr);
          if self.main_s == 1000 {
             *self.space_factor_mut() = 1000;
         }
@@ -632,16 +628,16 @@ impl Global {
 
     // Section 1035
     fn pack_lig(&mut self, b: bool) -> TeXResult<()> {
-        self.main_p = self.new_ligature(self.main_f, self.cur_l as QuarterWord, link(self.cur_q))?;
+        self.main_p = self.new_ligature(self.main_f, self.cur_l as QuarterWord, self.link(self.cur_q))?;
         if self.lft_hit {
-            *subtype_mut(self.main_p) = 2;
+            *self.subtype_mut(self.main_p) = 2;
             self.lft_hit = false;
         }
         if b && self.lig_stack == NULL {
-            *subtype_mut(self.main_p) += 1;
+            *self.subtype_mut(self.main_p) += 1;
             self.rt_hit = false;
         }
-        *link_mut(self.cur_q) = self.main_p;
+        *self.link_mut(self.cur_q) = self.main_p;
         *self.tail_mut() = self.main_p;
         self.ligature_present = false;
         Ok(())
@@ -649,8 +645,8 @@ impl Global {
 
     fn wrapup(&mut self, b: bool) -> TeXResult<()> {
         if self.cur_l < NON_CHAR {
-            if link(self.cur_q) > NULL
-                && (character(self.tail()) as Integer) == self.hyphen_char[self.main_f as usize]
+            if self.link(self.cur_q) > NULL
+                && (self.character(self.tail()) as Integer) == self.hyphen_char[self.main_f as usize]
             {
                 self.ins_disc = true;
             }
@@ -674,7 +670,7 @@ impl Global {
                 return Goto::ReSwitch;
             }
             self.cur_q = self.tail();
-            self.cur_l = character(self.lig_stack) as HalfWord;
+            self.cur_l = self.character(self.lig_stack) as HalfWord;
         }
 
         // main_loop_move + 1:
@@ -696,19 +692,19 @@ impl Global {
             free_avail!(self, self.lig_stack);
             return Goto::BigSwitch;
         }
-        *link_mut(self.tail()) = self.lig_stack;
+        *self.link_mut(self.tail()) = self.lig_stack;
         *self.tail_mut() = self.lig_stack;
         Goto::MainLoopLookAhead // main_loop_lookahead is next
     }
 
     // Section 1037
     fn sec1037_move_the_cursor_past(&mut self) -> Goto {
-        self.main_p = lig_ptr(self.lig_stack);
+        self.main_p = self.lig_ptr(self.lig_stack);
         if self.main_p > NULL {
             tail_append!(self, self.main_p);
         }
         self.temp_ptr = self.lig_stack;
-        self.lig_stack = link(self.temp_ptr);
+        self.lig_stack = self.link(self.temp_ptr);
         self.free_node(self.temp_ptr, SMALL_NODE_SIZE);
         self.main_i = self.char_info(self.main_f, self.cur_l as QuarterWord);
         self.ligature_present = true;
@@ -719,7 +715,7 @@ impl Global {
             self.cur_r = self.bchar;
         }
         else {
-            self.cur_r = character(self.lig_stack) as HalfWord;
+            self.cur_r = self.character(self.lig_stack) as HalfWord;
         }
         Goto::MainLigLoop(0)
     }
@@ -761,9 +757,9 @@ impl Global {
         // main_loop_lookahead + 1:
         self.adjust_space_factor();
         fast_get_avail!(self, self.lig_stack);
-        *font_mut(self.lig_stack) = self.main_f;
+        *self.font_mut(self.lig_stack) = self.main_f;
         self.cur_r = self.cur_chr;
-        *character_mut(self.lig_stack) = self.cur_r as QuarterWord;
+        *self.character_mut(self.lig_stack) = self.cur_r as QuarterWord;
         if self.cur_r == self.false_bchar {
             self.cur_r = NON_CHAR;
         }
@@ -823,10 +819,10 @@ impl Global {
                     else if self.is_char_node(self.lig_stack) {
                         self.main_p = self.lig_stack;
                         self.lig_stack = self.new_lig_item(self.cur_r as QuarterWord)?;
-                        *lig_ptr_mut(self.lig_stack) = self.main_p;
+                        *self.lig_ptr_mut(self.lig_stack) = self.main_p;
                     }
                     else {
-                        *character_mut(self.lig_stack) = self.cur_r as QuarterWord;
+                        *self.character_mut(self.lig_stack) = self.cur_r as QuarterWord;
                     }
                 },
 
@@ -834,7 +830,7 @@ impl Global {
                     self.cur_r = self.main_j.rem_byte() as HalfWord;
                     self.main_p = self.lig_stack;
                     self.lig_stack = self.new_lig_item(self.cur_r as QuarterWord)?;
-                    *link_mut(self.lig_stack) = self.main_p;
+                    *self.link_mut(self.lig_stack) = self.main_p;
                 },
 
                 7 | 11 => {
@@ -880,23 +876,24 @@ impl Global {
 
     // Section 1043
     fn app_space(&mut self) -> TeXResult<()> {
-        let q = if self.space_factor() >= 2000 && xspace_skip() != ZERO_GLUE {
+        let q = if self.space_factor() >= 2000 && self.xspace_skip() != ZERO_GLUE {
             self.new_param_glue(XSPACE_SKIP_CODE as QuarterWord)?
         }
         else {
-            if space_skip() != ZERO_GLUE {
-                self.main_p = space_skip();
+            if self.space_skip() != ZERO_GLUE {
+                self.main_p = self.space_skip();
             }
             else {
                 // Section 1042
-                self.main_p = self.font_glue[cur_font() as usize];
+                self.main_p = self.font_glue[self.cur_font() as usize];
                 if self.main_p == NULL {
                     self.main_p = self.new_spec(ZERO_GLUE)?;
-                    self.main_k = self.param_base[cur_font() as usize] + SPACE_CODE;
-                    *width_mut(self.main_p) = self.font_info[self.main_k as usize].sc();
-                    *stretch_mut(self.main_p) = self.font_info[(self.main_k + 1) as usize].sc();
-                    *shrink_mut(self.main_p) = self.font_info[(self.main_k + 2) as usize].sc();
-                    self.font_glue[cur_font() as usize] = self.main_p;
+                    self.main_k = self.param_base[self.cur_font() as usize] + SPACE_CODE;
+                    *self.width_mut(self.main_p) = self.font_info[self.main_k as usize].sc();
+                    *self.stretch_mut(self.main_p) = self.font_info[(self.main_k + 1) as usize].sc();
+                    *self.shrink_mut(self.main_p) = self.font_info[(self.main_k + 2) as usize].sc();
+                    let f = self.cur_font() as usize;
+                    self.font_glue[f] = self.main_p;
                 }
                 // End section 1042
             }
@@ -904,43 +901,57 @@ impl Global {
 
             // Section 1044
             if self.space_factor() >= 2000 {
-                *width_mut(self.main_p) += self.extra_space(cur_font() as QuarterWord);
+                *self.width_mut(self.main_p) += self.extra_space(self.cur_font() as QuarterWord);
             }
-            *stretch_mut(self.main_p) = xn_over_d(stretch(self.main_p), self.space_factor(), 1000)?.0;
-            *shrink_mut(self.main_p) = xn_over_d(shrink(self.main_p), 1000, self.space_factor())?.0;
+            *self.stretch_mut(self.main_p) = self.xn_over_d(self.stretch(self.main_p), self.space_factor(), 1000).0;
+            *self.shrink_mut(self.main_p) = self.xn_over_d(self.shrink(self.main_p), 1000, self.space_factor()).0;
             // End section 1044
 
             let q = self.new_glue(self.main_p)?;
-            *glue_ref_count_mut(self.main_p) = NULL;
+            *self.glue_ref_count_mut(self.main_p) = NULL;
             q
         };
 
-        *link_mut(self.tail()) = q;
+        *self.link_mut(self.tail()) = q;
         *self.tail_mut() = q;
         Ok(())
     }
 
+    // Section 1047
+    fn insert_dollar_sign(&mut self) -> TeXResult<()> {
+        self.back_input()?;
+        self.cur_tok = MATH_SHIFT_TOKEN + b'$' as HalfWord;
+        self.ins_error(TeXError::MissingDollar)
+    }
+
+    // Section 1050
+    pub(crate) fn report_illegal_case(&mut self) -> TeXResult<()> {
+        self.error(TeXError::ReportIllegalCase)
+    }
+
     // Section 1051
-    // Does not output a boolean, use TeXResult instead.
-    fn privileged(&self) -> TeXResult<()> {
+    fn privileged(&mut self) -> TeXResult<bool> {
         if self.mode() > 0 {
-            Ok(())
+            Ok(true)
         }
         else {
-            Err(TeXError::ReportIllegalCase)
+            self.report_illegal_case()?;
+            Ok(false)
         }
     }
 
     // Section 1054
     fn its_all_over(&mut self) -> TeXResult<bool> {
-        self.privileged()?;
-        if PAGE_HEAD == self.page_tail && self.head() == self.tail() && self.dead_cycles == 0 {
+        if !self.privileged()? {
+            return Ok(false);
+        }
+        if self.page_head() == self.page_tail && self.head() == self.tail() && self.dead_cycles == 0 {
             Ok(true)
         }
         else {
             self.back_input()?;
             tail_append!(self, self.new_null_box()?);
-            *width_mut(self.tail()) = hsize();
+            *self.width_mut(self.tail()) = self.hsize();
             tail_append!(self, self.new_glue(FILL_GLUE)?);
             tail_append!(self, self.new_penalty(-0x4000_0000)?);
             self.build_page()?;
@@ -1,25 +1,11 @@
-use crate::arithmetic::{
-    mult_and_add, x_over_n, xn_over_d
-};
 use crate::constants::*;
-use crate::datastructures::{
-    eqtb, eqtb_mut, mem_mut, r#box, equiv, equiv_mut, font_id_text_mut, geq_word_define,
-    global_defs, glue_ref_count_mut, info, info_mut, link, link_mut,
-    shrink, shrink_mut, shrink_order, shrink_order_mut, stretch,
-    stretch_mut, stretch_order, stretch_order_mut, text, token_ref_count_mut,
-    width, width_mut
-};
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileInSelector;
-use crate::strings::{
-    str_room, make_string, str_eq_str, flush_string, str_ptr, length
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, SmallNumber, StrNum,
     add_glue_ref, add_token_ref, back_list, free_avail, hi, mult_integers, nx_plus_y,
     odd, sec404_get_next_nonblank_nonrelax_noncall_token, update_terminal
 };
-use std::io::Write;
 
 // Part 49: Mode-independent processing
 
@@ -33,13 +19,14 @@ impl Global {
             }
             sec404_get_next_nonblank_nonrelax_noncall_token!(self);
             if self.cur_cmd <= MAX_NON_PREFIXED_COMMAND {
-                return Err(TeXError::CantUsePrefix);
+                // Section 1212
+                return self.back_error(TeXError::CantUsePrefix);
             }
         }
 
         // Section 1213
         if self.cur_cmd != DEF && a % 4 != 0 {
-            return Err(TeXError::CantUseLongOuter);
+            self.error(TeXError::CantUseLongOuter)?;
         }
         // End section 1213
 
@@ -64,7 +51,7 @@ impl Global {
         macro_rules! word_define {
             ($($args:expr),*) => {
                 if global!() {
-                    geq_word_define($($args),*);
+                    self.geq_word_define($($args),*);
                 }
                 else {
                     self.eq_word_define($($args),*)?;
@@ -72,8 +59,8 @@ impl Global {
             };
         }
 
-        if global_defs() != 0 {
-            if global_defs() < 0 {
+        if self.global_defs() != 0 {
+            if self.global_defs() < 0 {
                 if global!() {
                     a -= 4;
                 }
@@ -90,7 +77,7 @@ impl Global {
 
             // Section 1218
             DEF => {
-                if odd!(self.cur_chr) && !global!() && global_defs() >= 0 {
+                if odd!(self.cur_chr) && !global!() && self.global_defs() >= 0 {
                     a += 4;
                 }
                 let e = self.cur_chr >= 2;
@@ -129,7 +116,7 @@ impl Global {
                     self.back_input()?;
                 }
                 if self.cur_cmd >= CALL {
-                    add_token_ref!(self.cur_chr);
+                    add_token_ref!(self, self.cur_chr);
                 }
                 define!(p, self.cur_cmd, self.cur_chr);
             }
@@ -172,7 +159,7 @@ impl Global {
                 self.scan_int()?;
                 let n = self.cur_val;
                 if !self.scan_keyword(b"to")? {
-                    return Err(TeXError::MissingTo2);
+                    self.error(TeXError::MissingTo2)?;
                 }
                 self.get_r_token()?;
                 let p = self.cur_cs;
@@ -203,12 +190,12 @@ impl Global {
                         self.cur_chr = TOKS_BASE + self.cur_val;
                     }
                     if self.cur_cmd == ASSIGN_TOKS {
-                        q = equiv(self.cur_chr);
+                        q = self.equiv(self.cur_chr);
                         if q == NULL {
                             define!(p, UNDEFINED_CS, NULL);
                         }
                         else {
-                            add_token_ref!(q);
+                            add_token_ref!(self, q);
                             define!(p, CALL, q);
                         }
                         break 'toks; // Goto done
@@ -218,19 +205,19 @@ impl Global {
                 self.back_input()?;
                 self.cur_cs = q;
                 q = self.scan_toks(false, false)?;
-                if link(self.def_ref) == NULL {
+                if self.link(self.def_ref) == NULL {
                     define!(p, UNDEFINED_CS, NULL);
                     free_avail!(self, self.def_ref);
                 }
                 else {
                     if p == OUTPUT_ROUTINE_LOC {
-                        *link_mut(q) = self.get_avail()?;
-                        q = link(q);
-                        *info_mut(q) = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
+                        *self.link_mut(q) = self.get_avail()?;
+                        q = self.link(q);
+                        *self.info_mut(q) = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
                         q = self.get_avail()?;
-                        *info_mut(q) = LEFT_BRACE_TOKEN + b'{' as HalfWord;
-                        *link_mut(q) = link(self.def_ref);
-                        *link_mut(self.def_ref) = q;
+                        *self.info_mut(q) = LEFT_BRACE_TOKEN + b'{' as HalfWord;
+                        *self.link_mut(q) = self.link(self.def_ref);
+                        *self.link_mut(self.def_ref) = q;
                     }
                     define!(p, CALL, self.def_ref);
                 }
@@ -286,7 +273,8 @@ impl Global {
                 self.scan_optional_equals()?;
                 self.scan_int()?;
                 if self.cur_val < 0 && p < DEL_CODE_BASE || self.cur_val > n {
-                    return Err(TeXError::InvalidCode(n, p));
+                    self.error(TeXError::InvalidCode(n, p))?;
+                    self.cur_val = 0;
                 }
                 if p < MATH_CODE_BASE {
                     define!(p, DATA, self.cur_val);
@@ -333,7 +321,7 @@ impl Global {
                     self.scan_box(BOX_FLAG + n)?;
                 }
                 else {
-                    return Err(TeXError::ImproperSetbox);
+                    self.error(TeXError::ImproperSetbox)?;
                 }
             },
             // End section 1241
@@ -360,12 +348,12 @@ impl Global {
                 }
                 else {
                     let p = self.get_node(2*n + 1)?;
-                    *info_mut(p) = n;
+                    *self.info_mut(p) = n;
                     for j in 1..=n {
                         self.scan_dimen(false, false, false)?;
-                        *mem_mut((p + 2*j - 1) as usize).sc_mut() = self.cur_val;
+                        *self.mem_mut((p + 2*j - 1) as usize).sc_mut() = self.cur_val;
                         self.scan_dimen(false, false, false)?;
-                        *mem_mut((p + 2*j) as usize).sc_mut() = self.cur_val;
+                        *self.mem_mut((p + 2*j) as usize).sc_mut() = self.cur_val;
                     }
                     p
                 };
@@ -380,7 +368,14 @@ impl Global {
                         self.new_patterns()?;
                     }
                     else {
-                        return Err(TeXError::PatternsOnlyIniTeX);
+                        self.error(TeXError::PatternsOnlyIniTeX)?;
+                        loop {
+                            self.get_token()?;
+                            if self.cur_cmd == RIGHT_BRACE {
+                                break;
+                            }
+                        }
+                        return Ok(());
                     }
                 }
                 else {
@@ -439,27 +434,32 @@ impl Global {
 
     // Section 1215
     fn get_r_token(&mut self) -> TeXResult<()> {
-        loop {
-            self.get_token()?;
-            if self.cur_tok != SPACE_TOKEN {
-                break;
+        'restart: loop {
+            loop {
+                self.get_token()?;
+                if self.cur_tok != SPACE_TOKEN {
+                    break;
+                }
             }
-        }
-        if self.cur_cs == 0 || self.cur_cs > FROZEN_CONTROL_SEQUENCE {
-            Err(TeXError::MissingControlSequence)
-        }
-        else {
-            Ok(())
+            if self.cur_cs == 0 || (self.cur_cs > FROZEN_CONTROL_SEQUENCE && self.cur_cs <= EQTB_SIZE) {
+                if self.cur_cs == 0 {
+                    self.back_input()?;
+                }
+                self.cur_tok = CS_TOKEN_FLAG + FROZEN_PROTECTION;
+                self.ins_error(TeXError::MissingControlSequence)?;
+                continue 'restart;
+            }
+            return Ok(());
         }
     }
 
     // Section 1229
     fn trap_zero_glue(&mut self) {
-        if width(self.cur_val) == 0
-            && stretch(self.cur_val) == 0
-            && shrink(self.cur_val) == 0
+        if self.width(self.cur_val) == 0
+            && self.stretch(self.cur_val) == 0
+            && self.shrink(self.cur_val) == 0
         {
-            add_glue_ref!(ZERO_GLUE);
+            add_glue_ref!(self, ZERO_GLUE);
             self.delete_glue_ref(self.cur_val);
             self.cur_val = ZERO_GLUE;
         }
@@ -487,7 +487,7 @@ impl Global {
         macro_rules! word_define {
             ($($args:expr),*) => {
                 if global!() {
-                    geq_word_define($($args),*);
+                    self.geq_word_define($($args),*);
                 }
                 else {
                     self.eq_word_define($($args),*)?;
@@ -506,7 +506,7 @@ impl Global {
                     break 'sec1237 (self.cur_chr, (self.cur_cmd as Integer) - (ASSIGN_INT as Integer));
                 }
                 if self.cur_cmd != REGISTER {
-                    return Err(TeXError::CantUseAfterCmd(q as QuarterWord));
+                    return self.error(TeXError::CantUseAfterCmd(q as QuarterWord));
                 }
             }
             let p = self.cur_chr;
@@ -528,6 +528,7 @@ impl Global {
             // optional 'by'
             _ = self.scan_keyword(b"by")?;
         }
+        self.arith_error = false;
         if q < MULTIPLY {
             // Section 1238
             if p < GLUE_VAL {
@@ -538,7 +539,7 @@ impl Global {
                     self.scan_dimen(false, false, false)?;
                 }
                 if q == ADVANCE {
-                    self.cur_val += eqtb(l as usize).int()
+                    self.cur_val += self.eqtb(l as usize).int()
                 }
             }
             else {
@@ -546,28 +547,28 @@ impl Global {
                 if q == ADVANCE {
                     // Section 1239
                     let q = self.new_spec(self.cur_val)?;
-                    let r = equiv(l);
+                    let r = self.equiv(l);
                     self.delete_glue_ref(self.cur_val);
-                    *width_mut(q) += width(r);
-                    if stretch(q) == 0 {
-                        *stretch_order_mut(q) = NORMAL;
+                    *self.width_mut(q) += self.width(r);
+                    if self.stretch(q) == 0 {
+                        *self.stretch_order_mut(q) = NORMAL;
                     }
-                    if stretch_order(q) == stretch_order(r) {
-                        *stretch_mut(q) += stretch(r);
+                    if self.stretch_order(q) == self.stretch_order(r) {
+                        *self.stretch_mut(q) += self.stretch(r);
                     }
-                    else if stretch_order(q) < stretch_order(r) && stretch(r) != 0 {
-                        *stretch_mut(q) = stretch(r);
-                        *stretch_order_mut(q) = stretch_order(r);
+                    else if self.stretch_order(q) < self.stretch_order(r) && self.stretch(r) != 0 {
+                        *self.stretch_mut(q) = self.stretch(r);
+                        *self.stretch_order_mut(q) = self.stretch_order(r);
                     }
-                    if shrink(q) == 0 {
-                        *shrink_order_mut(q) = NORMAL;
+                    if self.shrink(q) == 0 {
+                        *self.shrink_order_mut(q) = NORMAL;
                     }
-                    if shrink_order(q) == shrink_order(r) {
-                        *shrink_mut(q) += shrink(r);
+                    if self.shrink_order(q) == self.shrink_order(r) {
+                        *self.shrink_mut(q) += self.shrink(r);
                     }
-                    else if shrink_order(q) < shrink_order(r) && shrink(r) != 0 {
-                        *shrink_mut(q) = shrink(r);
-                        *shrink_order_mut(q) = shrink_order(r);
+                    else if self.shrink_order(q) < self.shrink_order(r) && self.shrink(r) != 0 {
+                        *self.shrink_mut(q) = self.shrink(r);
+                        *self.shrink_order_mut(q) = self.shrink_order(r);
                     }
                     self.cur_val = q;
                     // End section 1239
@@ -581,41 +582,47 @@ impl Global {
             self.cur_val = if p < GLUE_VAL {
                 if q == MULTIPLY {
                     if p == INT_VAL {
-                        mult_integers!(eqtb(l as usize).int(), self.cur_val)
+                        mult_integers!(self, self.eqtb(l as usize).int(), self.cur_val)
                     }
                     else {
-                        nx_plus_y!(eqtb(l as usize).int(), self.cur_val, 0)
+                        nx_plus_y!(self, self.eqtb(l as usize).int(), self.cur_val, 0)
                     }
                 }
                 else {
-                    x_over_n(eqtb(l as usize).int(), self.cur_val)?.0
+                    self.x_over_n(self.eqtb(l as usize).int(), self.cur_val).0
                 }
             }
             else {
-                let s = equiv(l);
+                let s = self.equiv(l);
                 let r = self.new_spec(s)?;
                 (
-                    *width_mut(r),
-                    *stretch_mut(r),
-                    *shrink_mut(r)
+                    *self.width_mut(r),
+                    *self.stretch_mut(r),
+                    *self.shrink_mut(r)
                 ) = if q == MULTIPLY {
                     (
-                        nx_plus_y!(width(s), self.cur_val, 0),
-                        nx_plus_y!(stretch(s), self.cur_val, 0),
-                        nx_plus_y!(shrink(s), self.cur_val, 0),
+                        nx_plus_y!(self, self.width(s), self.cur_val, 0),
+                        nx_plus_y!(self, self.stretch(s), self.cur_val, 0),
+                        nx_plus_y!(self, self.shrink(s), self.cur_val, 0),
                     )
                 }
                 else {
                     (
-                        x_over_n(width(s), self.cur_val)?.0,
-                        x_over_n(stretch(s), self.cur_val)?.0,
-                        x_over_n(shrink(s), self.cur_val)?.0,
+                        self.x_over_n(self.width(s), self.cur_val).0,
+                        self.x_over_n(self.stretch(s), self.cur_val).0,
+                        self.x_over_n(self.shrink(s), self.cur_val).0,
                     )
                 };
                 r
             };
             // End section 1240
         }
+        if self.arith_error {
+            if p >= GLUE_VAL {
+                self.delete_glue_ref(self.cur_val);
+            }
+            return self.error(TeXError::Arith);
+        }
         if p < GLUE_VAL {
             word_define!(l, self.cur_val);
         }
@@ -629,7 +636,7 @@ impl Global {
     // Section 1243
     fn alter_aux(&mut self) -> TeXResult<()> {
         if self.cur_chr != self.mode().abs() {
-            return Err(TeXError::ReportIllegalCase);
+            return self.report_illegal_case();
         }
         let c = self.cur_chr;
         self.scan_optional_equals()?;
@@ -640,9 +647,11 @@ impl Global {
         else {
             self.scan_int()?;
             if self.cur_val <= 0 || self.cur_val > 32767 {
-                return Err(TeXError::BadSpaceFactor);
+                self.error(TeXError::BadSpaceFactor)?; // int_error
+            }
+            else {
+                *self.space_factor_mut() = self.cur_val;
             }
-            *self.space_factor_mut() = self.cur_val;
         }
         Ok(())
     }
@@ -657,10 +666,12 @@ impl Global {
         self.scan_optional_equals()?;
         self.scan_int()?;
         if self.cur_val < 0 {
-            return Err(TeXError::BadPrevGraf);
+            self.error(TeXError::BadPrevGraf)?; // int_error
+        }
+        else {
+            self.nest[p].pg_field = self.cur_val;
+            self.cur_list = self.nest[self.nest_ptr];
         }
-        self.nest[p].pg_field = self.cur_val;
-        self.cur_list = self.nest[self.nest_ptr];
         Ok(())
     }
 
@@ -694,8 +705,8 @@ impl Global {
         let b = self.cur_val;
         self.scan_optional_equals()?;
         self.scan_dimen(false, false, false)?;
-        if r#box(b) != NULL {
-            *mem_mut((r#box(b) + c) as usize).sc_mut() = self.cur_val;
+        if self.r#box(b) != NULL {
+            *self.mem_mut((self.r#box(b) + c) as usize).sc_mut() = self.cur_val;
         }
         Ok(())
     }
@@ -725,7 +736,7 @@ impl Global {
         self.get_r_token()?;
         let u = self.cur_cs;
         let t = if u >= HASH_BASE {
-            text(u)
+            self.text(u)
         }
         else if u >= SINGLE_BASE {
             if u == NULL_CS {
@@ -741,8 +752,8 @@ impl Global {
             self.print("FONT");
             self.print_strnumber((u - ACTIVE_BASE) as StrNum);
             self.selector = old_setting;
-            str_room(1)?;
-            make_string()? as HalfWord
+            self.str_room(1)?;
+            self.make_string()? as HalfWord
         };
 
         define!(u, SET_FONT, NULL_FONT);
@@ -756,18 +767,23 @@ impl Global {
             self.scan_dimen(false, false, false)?;
             let s = self.cur_val;
             if s <= 0 || s >= 0x800_0000 {
-                return Err(TeXError::ImproperAt(s));
+                self.error(TeXError::ImproperAt(s))?;
+                10*UNITY
+            }
+            else {
+                s
             }
             // End section 1259
-            s
         }
         else if self.scan_keyword(b"scaled")? {
             self.scan_int()?;
-            let s = -self.cur_val;
             if self.cur_val <= 0 || self.cur_val >= 32768 {
-                return Err(TeXError::IllegalMag(self.cur_val));
+                self.error(TeXError::IllegalMag(self.cur_val))?; // int_error
+                -1000
+            }
+            else {
+                -self.cur_val
             }
-            s
         }
         else {
             -1000
@@ -778,11 +794,11 @@ impl Global {
 
         let f = 'block: {
             // Section 1260
-            let flushable_string = str_ptr() - 1;
+            let flushable_string = self.str_ptr() - 1;
             for f in (FONT_BASE as usize + 1)..=(self.font_ptr as usize) {
-                if str_eq_str(self.cur_name, self.font_name[f]) && str_eq_str(self.font_area[f], self.cur_area) {
+                if self.str_eq_str(self.cur_name, self.font_name[f]) && self.str_eq_str(self.font_area[f], self.cur_area) {
                     if self.cur_name == flushable_string {
-                        flush_string();
+                        self.flush_string();
                         self.cur_name = self.font_name[f];
                     }
                     if s > 0 {
@@ -790,7 +806,7 @@ impl Global {
                             break 'block f; // Goto common_ending
                         }
                     }
-                    else if self.font_size[f] == xn_over_d(self.font_dsize[f], -s, 1000)?.0 {
+                    else if self.font_size[f] == self.xn_over_d(self.font_dsize[f], -s, 1000).0 {
                         break 'block f; // Goto common_ending
                     }
                 }
@@ -801,9 +817,9 @@ impl Global {
         };
     
         // common_ending:
-        *equiv_mut(u) = f as HalfWord;
-        *eqtb_mut(FONT_ID_BASE as usize + f) = eqtb(u as usize);
-        *font_id_text_mut(f as QuarterWord) = t;
+        *self.equiv_mut(u) = f as HalfWord;
+        *self.eqtb_mut(FONT_ID_BASE as usize + f) = self.eqtb(u as usize);
+        *self.font_id_text_mut(f as QuarterWord) = t;
         Ok(())
     }
 
@@ -847,7 +863,7 @@ impl Global {
                 self.cur_ext = EXT_TEX;
             }
             self.pack_cur_name();
-            if self.a_open_in(AlphaFileInSelector::ReadFile(n as usize)) {
+            if self.a_open_in_path(AlphaFileInSelector::ReadFile(n as usize)) {
                 self.read_open[n as usize] = JUST_OPEN;
             }
         }
@@ -857,30 +873,32 @@ impl Global {
     // Section 1279
     pub(crate) fn issue_message(&mut self) -> TeXResult<()> {
         let c = self.cur_chr;
-        *link_mut(GARBAGE) = self.scan_toks(false, true)?;
+        *self.link_mut(self.garbage()) = self.scan_toks(false, true)?;
         let old_setting = self.selector;
         self.selector = NEW_STRING;
         self.token_show(self.def_ref);
         self.selector = old_setting;
         self.flush_list(self.def_ref);
-        str_room(1)?;
-        let s = make_string()? as StrNum;
+        self.str_room(1)?;
+        let s = self.make_string()? as StrNum;
         if c == 0 {
             // Section 1280
-            if self.term_offset + length(s) as Integer > MAX_PRINT_LINE - 2 {
+            if self.term_offset + self.length(s) as Integer > MAX_PRINT_LINE - 2 {
                 self.print_ln();
             }
             else if self.term_offset > 0 || self.file_offset > 0 {
                 self.print_char(b' ');
             }
             self.slow_print(s);
-            update_terminal!();
+            update_terminal!(self);
             // End section 1280
         }
         else {
-            return Err(TeXError::ErrMessage(s));
+            // Section 1283
+            self.error(TeXError::ErrMessage(s))?;
+            self.use_err_help = false;
         }
-        flush_string();
+        self.flush_string();
         Ok(())
     }
 
@@ -888,20 +906,20 @@ impl Global {
     pub(crate) fn shift_case(&mut self) -> TeXResult<()> {
         let b = self.cur_chr;
         let _ = self.scan_toks(false, false)?;
-        let mut p = link(self.def_ref);
+        let mut p = self.link(self.def_ref);
         while p != NULL {
             // Section 1289
-            let t = info(p);
+            let t = self.info(p);
             if t < CS_TOKEN_FLAG + SINGLE_BASE {
                 let c = t % 256;
-                if equiv(b + c) != 0 {
-                    *info_mut(p) = t - c + equiv(b + c);
+                if self.equiv(b + c) != 0 {
+                    *self.info_mut(p) = t - c + self.equiv(b + c);
                 }
             }
             // End section 1289
-            p = link(p);
+            p = self.link(p);
         }
-        back_list!(self, link(self.def_ref));
+        back_list!(self, self.link(self.def_ref));
         free_avail!(self, self.def_ref);
         Ok(())
     }
@@ -1,14 +1,6 @@
 use crate::arithmetic::badness;
 use crate::constants::*;
 use crate::error::{TeXError, TeXResult};
-use crate::datastructures::{
-    mem, mem_mut, adjust_ptr, baseline_skip, character, depth, depth_mut, font,
-    glue_order_mut, glue_ptr, glue_set_mut, glue_sign_mut, hbadness, height,
-    height_mut, hfuzz, leader_ptr, line_skip_limit, link, link_mut, list_ptr,
-    list_ptr_mut, overfull_rule, shift_amount, shift_amount_mut, shrink,
-    shrink_order, stretch, stretch_order, subtype, subtype_mut, r#type,
-    type_mut, vbadness, vfuzz, width, width_mut
-};
 use crate::{
     Global, HalfWord, QuarterWord, Real, Scaled, SmallNumber, lig_char
 };
@@ -31,11 +23,11 @@ impl Global {
     pub(crate) fn hpack(&mut self, mut p: HalfWord, mut w: Scaled, m: SmallNumber) -> TeXResult<HalfWord> {
         self.last_badness = 0;
         let r = self.get_node(BOX_NODE_SIZE)?;
-        *type_mut(r) = HLIST_NODE;
-        *subtype_mut(r) = MIN_QUARTERWORD;
-        *shift_amount_mut(r) = 0;
+        *self.type_mut(r) = HLIST_NODE;
+        *self.subtype_mut(r) = MIN_QUARTERWORD;
+        *self.shift_amount_mut(r) = 0;
         let mut q = r + (LIST_OFFSET);
-        *link_mut(q) = p;
+        *self.link_mut(q) = p;
         let mut h = 0;
 
         // Section 650
@@ -50,8 +42,8 @@ impl Global {
             'reswitch: loop {
                 while self.is_char_node(p) {
                     // Section 654
-                    let f = font(p);
-                    let i = self.char_info(f, character(p));
+                    let f = self.font(p);
+                    let i = self.char_info(f, self.character(p));
                     let hd = i.height_depth();
                     x += self.char_width(f, i);
                     let mut s = self.char_height(f, hd);
@@ -62,30 +54,30 @@ impl Global {
                     if s > d {
                         d = s;
                     }
-                    p = link(p);
+                    p = self.link(p);
                     // End section 654
                 }
 
                 if p != NULL {
-                    match r#type(p) {
+                    match self.r#type(p) {
                         HLIST_NODE
                         | VLIST_NODE
                         | RULE_NODE
                         | UNSET_NODE => {
                             // Section 653
-                            x += width(p);
-                            let s = if r#type(p) >= RULE_NODE {
+                            x += self.width(p);
+                            let s = if self.r#type(p) >= RULE_NODE {
                                 0
                             }
                             else {
-                                shift_amount(p)
+                                self.shift_amount(p)
                             };
 
-                            if height(p) - s > h {
-                                h = height(p) - s;
+                            if self.height(p) - s > h {
+                                h = self.height(p) - s;
                             }
-                            if depth(p) + s > d {
-                                d = depth(p) + s;
+                            if self.depth(p) + s > d {
+                                d = self.depth(p) + s;
                             }
                             // End section 653
                         },
@@ -95,23 +87,23 @@ impl Global {
                         | ADJUST_NODE => {
                             if self.adjust_tail != NULL {
                                 // Section 655
-                                while link(q) != p {
-                                    q = link(q);
+                                while self.link(q) != p {
+                                    q = self.link(q);
                                 }
-                                if r#type(p) == ADJUST_NODE {
-                                    *link_mut(self.adjust_tail) = adjust_ptr(p);
-                                    while link(self.adjust_tail) != NULL {
-                                        self.adjust_tail = link(self.adjust_tail);
+                                if self.r#type(p) == ADJUST_NODE {
+                                    *self.link_mut(self.adjust_tail) = self.adjust_ptr(p);
+                                    while self.link(self.adjust_tail) != NULL {
+                                        self.adjust_tail = self.link(self.adjust_tail);
                                     }
-                                    p = link(p);
-                                    self.free_node(link(q), SMALL_NODE_SIZE);
+                                    p = self.link(p);
+                                    self.free_node(self.link(q), SMALL_NODE_SIZE);
                                 }
                                 else {
-                                    *link_mut(self.adjust_tail) = p;
+                                    *self.link_mut(self.adjust_tail) = p;
                                     self.adjust_tail = p;
-                                    p = link(p);
+                                    p = self.link(p);
                                 }
-                                *link_mut(q) = p;
+                                *self.link_mut(q) = p;
                                 p = q;
                                 // End section 655
                             }
@@ -121,39 +113,39 @@ impl Global {
 
                         GLUE_NODE => {
                             // Section 656
-                            let mut g = glue_ptr(p);
-                            x += width(g);
-                            let mut o = stretch_order(g);
-                            self.total_stretch[o as usize] += stretch(g);
-                            o = shrink_order(g);
-                            self.total_shrink[o as usize] += shrink(g);
-                            if subtype(p) >= A_LEADERS {
-                                g = leader_ptr(p);
-                                if height(g) > h {
-                                    h = height(g);
+                            let mut g = self.glue_ptr(p);
+                            x += self.width(g);
+                            let mut o = self.stretch_order(g);
+                            self.total_stretch[o as usize] += self.stretch(g);
+                            o = self.shrink_order(g);
+                            self.total_shrink[o as usize] += self.shrink(g);
+                            if self.subtype(p) >= A_LEADERS {
+                                g = self.leader_ptr(p);
+                                if self.height(g) > h {
+                                    h = self.height(g);
                                 }
-                                if depth(g) > d {
-                                    d = depth(g);
+                                if self.depth(g) > d {
+                                    d = self.depth(g);
                                 }
                             }
                             // End section 656
                         },
 
                         KERN_NODE
-                        | MATH_NODE => x += width(p),
+                        | MATH_NODE => x += self.width(p),
 
                         LIGATURE_NODE => {
                             // Section 652
-                            *mem_mut(LIG_TRICK as usize) = mem(lig_char!(p) as usize);
-                            *link_mut(LIG_TRICK) = link(p);
-                            p = LIG_TRICK;
+                            *self.mem_mut(self.lig_trick() as usize) = self.mem(lig_char!(p) as usize);
+                            *self.link_mut(self.lig_trick()) = self.link(p);
+                            p = self.lig_trick();
                             continue 'reswitch;
                             // End section 652
                         },
                         
                         _ => (),
                     }
-                    p = link(p);
+                    p = self.link(p);
                 }
                 break 'reswitch;
                 // End section 651
@@ -161,21 +153,21 @@ impl Global {
         }
 
         if self.adjust_tail != NULL {
-            *link_mut(self.adjust_tail) = NULL;
+            *self.link_mut(self.adjust_tail) = NULL;
         }
-        *height_mut(r) = h;
-        *depth_mut(r) = d;
+        *self.height_mut(r) = h;
+        *self.depth_mut(r) = d;
 
         // Section 657
         if m == ADDITIONAL {
             w += x;
         }
-        *width_mut(r) = w;
+        *self.width_mut(r) = w;
         x = w - x;
         if x == 0 {
-            *glue_sign_mut(r) = NORMAL;
-            *glue_order_mut(r) = NORMAL;
-            *glue_set_mut(r) = 0.0;
+            *self.glue_sign_mut(r) = NORMAL;
+            *self.glue_order_mut(r) = NORMAL;
+            *self.glue_set_mut(r) = 0.0;
             return Ok(r);
         }
 
@@ -197,21 +189,22 @@ impl Global {
                 };
                 // End section 659
                 
-                *glue_order_mut(r) = o;
-                *glue_sign_mut(r) = STRETCHING;
+                *self.glue_order_mut(r) = o;
+                *self.glue_sign_mut(r) = STRETCHING;
                 if self.total_stretch[o as usize] != 0 {
-                    *glue_set_mut(r) = (x as Real) / (self.total_stretch[o as usize] as Real);
+                    *self.glue_set_mut(r) = (x as Real) / (self.total_stretch[o as usize] as Real);
                 }
                 else {
-                    *glue_sign_mut(r) = NORMAL;
-                    *glue_set_mut(r) = 0.0;
+                    *self.glue_sign_mut(r) = NORMAL;
+                    *self.glue_set_mut(r) = 0.0;
                 }
 
-                if o == NORMAL && list_ptr(r) != NULL {
+                if o == NORMAL && self.list_ptr(r) != NULL {
                     // Section 660
                     self.last_badness = badness(x, self.total_stretch[NORMAL as usize]);
-                    if self.last_badness > hbadness() {
+                    if self.last_badness > self.hbadness() {
                         self.print_ln();
+                        self.begin_warning();
                         if self.last_badness > 100 {
                             self.print_nl("Underfull");
                         }
@@ -243,29 +236,30 @@ impl Global {
             };
             // End section 665
 
-            *glue_order_mut(r) = o;
-            *glue_sign_mut(r) = SHRINKING;
+            *self.glue_order_mut(r) = o;
+            *self.glue_sign_mut(r) = SHRINKING;
             if self.total_shrink[o as usize] != 0 {
-                *glue_set_mut(r) = (-x as Real) / (self.total_shrink[o as usize] as Real);
+                *self.glue_set_mut(r) = (-x as Real) / (self.total_shrink[o as usize] as Real);
             }
             else {
-                *glue_sign_mut(r) = NORMAL;
-                *glue_set_mut(r) = 0.0;
+                *self.glue_sign_mut(r) = NORMAL;
+                *self.glue_set_mut(r) = 0.0;
             }
-            if self.total_shrink[o as usize] < -x && o == NORMAL && list_ptr(r) != NULL {
+            if self.total_shrink[o as usize] < -x && o == NORMAL && self.list_ptr(r) != NULL {
                 self.last_badness = 1_000_000;
-                *glue_set_mut(r) = 1.0;
+                *self.glue_set_mut(r) = 1.0;
 
                 // Section 666
-                if -x -self.total_shrink[NORMAL as usize] > hfuzz() || hbadness() < 100 {
-                    if overfull_rule() > 0 && -x - self.total_shrink[NORMAL as usize] > hfuzz() {
-                        while link(q) != NULL {
-                            q = link(q);
+                if -x -self.total_shrink[NORMAL as usize] > self.hfuzz() || self.hbadness() < 100 {
+                    if self.overfull_rule() > 0 && -x - self.total_shrink[NORMAL as usize] > self.hfuzz() {
+                        while self.link(q) != NULL {
+                            q = self.link(q);
                         }
-                        *link_mut(q) = self.new_rule()?;
-                        *width_mut(link(q)) = overfull_rule();
+                        *self.link_mut(q) = self.new_rule()?;
+                        *self.width_mut(self.link(q)) = self.overfull_rule();
                     }
                     self.print_ln();
+                    self.begin_warning();
                     self.print_nl("Overfull \\hbox (");
                     self.print_scaled(-x - self.total_shrink[NORMAL as usize]);
                     self.print("pt too wide");
@@ -273,11 +267,12 @@ impl Global {
                 }
                 // End section 666
             }
-            else if o == NORMAL && list_ptr(r) != NULL {
+            else if o == NORMAL && self.list_ptr(r) != NULL {
                 // Section 667
                 self.last_badness = badness(-x, self.total_shrink[NORMAL as usize]);
-                if self.last_badness > hbadness() {
+                if self.last_badness > self.hbadness() {
                     self.print_ln();
+                    self.begin_warning();
                     self.print_nl("Tight \\hbox (badness ");
                     self.print_int(self.last_badness);
                     break 'block; // Goto common_ending
@@ -310,9 +305,10 @@ impl Global {
             }
             self.print_int(self.line);
         }
+        self.end_warning("box");
         self.print_ln();
         self.font_in_short_display = NULL_FONT as QuarterWord;
-        self.short_display(list_ptr(r));
+        self.short_display(self.list_ptr(r));
         self.print_ln();
         self.begin_diagnostic();
         self.show_box(r);
@@ -340,10 +336,10 @@ impl Global {
     pub(crate) fn vpackage(&mut self, mut p: HalfWord, mut h: Scaled, m: QuarterWord, l: Scaled) -> TeXResult<HalfWord> {
         self.last_badness = 0;
         let r = self.get_node(BOX_NODE_SIZE)?;
-        *type_mut(r) = VLIST_NODE;
-        *subtype_mut(r) = MIN_QUARTERWORD;
-        *shift_amount_mut(r) = 0;
-        *list_ptr_mut(r) = p;
+        *self.type_mut(r) = VLIST_NODE;
+        *self.subtype_mut(r) = MIN_QUARTERWORD;
+        *self.shift_amount_mut(r) = 0;
+        *self.list_ptr_mut(r) = p;
         let mut w = 0;
 
         // Section 650
@@ -359,23 +355,23 @@ impl Global {
                 return Err(TeXError::Confusion("vpack"));
             }
 
-            match r#type(p) {
+            match self.r#type(p) {
                 HLIST_NODE
                 | VLIST_NODE
                 | RULE_NODE
                 | UNSET_NODE => {
                     // Section 670
-                    x += d + height(p);
-                    d = depth(p);
-                    let s = if r#type(p) >= RULE_NODE {
+                    x += d + self.height(p);
+                    d = self.depth(p);
+                    let s = if self.r#type(p) >= RULE_NODE {
                         0
                     }
                     else {
-                        shift_amount(p)
+                        self.shift_amount(p)
                     };
 
-                    if width(p) + s > w {
-                        w = width(p) + s;
+                    if self.width(p) + s > w {
+                        w = self.width(p) + s;
                     }
                     // End section 670
                 },
@@ -386,51 +382,51 @@ impl Global {
                     // Section 671
                     x += d;
                     d = 0;
-                    let mut g = glue_ptr(p);
-                    x += width(g);
-                    let mut o = stretch_order(g);
-                    self.total_stretch[o as usize] += stretch(g);
-                    o = shrink_order(g);
-                    self.total_shrink[o as usize] += shrink(g);
-                    if subtype(p) >= A_LEADERS {
-                        g = leader_ptr(p);
-                        if width(g) > w {
-                            w = width(g);
+                    let mut g = self.glue_ptr(p);
+                    x += self.width(g);
+                    let mut o = self.stretch_order(g);
+                    self.total_stretch[o as usize] += self.stretch(g);
+                    o = self.shrink_order(g);
+                    self.total_shrink[o as usize] += self.shrink(g);
+                    if self.subtype(p) >= A_LEADERS {
+                        g = self.leader_ptr(p);
+                        if self.width(g) > w {
+                            w = self.width(g);
                         }
                     }
                     // End section 671
                 },
 
                 KERN_NODE => {
-                    x += d + width(p);
+                    x += d + self.width(p);
                     d = 0;
                 },
 
                 _ => (), // Do nothing
             }
-            p = link(p);
+            p = self.link(p);
             // End section 669
         }
 
-        *width_mut(r) = w;
+        *self.width_mut(r) = w;
         if d > l {
             x += d - l;
-            *depth_mut(r) = l;
+            *self.depth_mut(r) = l;
         }
         else {
-            *depth_mut(r) = d;
+            *self.depth_mut(r) = d;
         }
 
         // Section 672
         if m == ADDITIONAL {
             h += x;
         }
-        *height_mut(r) = h;
+        *self.height_mut(r) = h;
         x = h - x;
         if x == 0 {
-            *glue_sign_mut(r) = NORMAL;
-            *glue_order_mut(r) = NORMAL;
-            *glue_set_mut(r) = 0.0;
+            *self.glue_sign_mut(r) = NORMAL;
+            *self.glue_order_mut(r) = NORMAL;
+            *self.glue_set_mut(r) = 0.0;
             return Ok(r);
         }
 
@@ -452,20 +448,21 @@ impl Global {
                 };
                 // End section 659
 
-                *glue_order_mut(r) = o;
-                *glue_sign_mut(r) = STRETCHING;
+                *self.glue_order_mut(r) = o;
+                *self.glue_sign_mut(r) = STRETCHING;
                 if self.total_stretch[o as usize] != 0 {
-                    *glue_set_mut(r) = (x as Real) / (self.total_stretch[o as usize] as Real);
+                    *self.glue_set_mut(r) = (x as Real) / (self.total_stretch[o as usize] as Real);
                 }
                 else {
-                    *glue_sign_mut(r) = NORMAL;
-                    *glue_set_mut(r) = 0.0;
+                    *self.glue_sign_mut(r) = NORMAL;
+                    *self.glue_set_mut(r) = 0.0;
                 }
-                if o == NORMAL && list_ptr(r) != NULL {
+                if o == NORMAL && self.list_ptr(r) != NULL {
                     // Section 674
                     self.last_badness = badness(x, self.total_stretch[NORMAL as usize]);
-                    if self.last_badness > vbadness() {
+                    if self.last_badness > self.vbadness() {
                         self.print_ln();
+                        self.begin_warning();
                         if self.last_badness > 100 {
                             self.print_nl("Underfull");
                         }
@@ -498,21 +495,22 @@ impl Global {
                 };
                 // End section 665
 
-                *glue_order_mut(r) = o;
-                *glue_sign_mut(r) = SHRINKING;
+                *self.glue_order_mut(r) = o;
+                *self.glue_sign_mut(r) = SHRINKING;
                 if self.total_shrink[o as usize] != 0 {
-                    *glue_set_mut(r) = (-x as Real) / (self.total_shrink[o as usize] as Real);
+                    *self.glue_set_mut(r) = (-x as Real) / (self.total_shrink[o as usize] as Real);
                 }
                 else {
-                    *glue_sign_mut(r) = NORMAL;
-                    *glue_set_mut(r) = 0.0;
+                    *self.glue_sign_mut(r) = NORMAL;
+                    *self.glue_set_mut(r) = 0.0;
                 }
-                if self.total_shrink[o as usize] < -x && o == NORMAL && list_ptr(r) != NULL {
+                if self.total_shrink[o as usize] < -x && o == NORMAL && self.list_ptr(r) != NULL {
                     self.last_badness = 1_000_000;
-                    *glue_set_mut(r) = 1.0;
+                    *self.glue_set_mut(r) = 1.0;
                     // Section 677
-                    if -x - self.total_shrink[NORMAL as usize] > vfuzz() || vbadness() < 100 {
+                    if -x - self.total_shrink[NORMAL as usize] > self.vfuzz() || self.vbadness() < 100 {
                         self.print_ln();
+                        self.begin_warning();
                         self.print_nl("Overfull \\vbox (");
                         self.print_scaled(-x - self.total_shrink[NORMAL as usize]);
                         self.print("pt too high");
@@ -520,11 +518,12 @@ impl Global {
                     }
                     // End section 677
                 }
-                else if o == NORMAL && list_ptr(r) != NULL {
+                else if o == NORMAL && self.list_ptr(r) != NULL {
                     // Section 678
                     self.last_badness = badness(-x, self.total_shrink[NORMAL as usize]);
-                    if self.last_badness > vbadness() {
+                    if self.last_badness > self.vbadness() {
                         self.print_ln();
+                        self.begin_warning();
                         self.print_nl("Tight \\vbox (badness ");
                         self.print_int(self.last_badness);
                         break 'block; // Goto common_ending
@@ -541,6 +540,7 @@ impl Global {
         // Section 675
         if self.output_active {
             self.print(") has occured while \\output is active");
+            self.end_warning("box");
         }
         else {
             if self.pack_begin_line != 0 {
@@ -552,6 +552,7 @@ impl Global {
                 self.print(") detected at line ");
             }
             self.print_int(self.line);
+            self.end_warning("box");
             self.print_ln();
         }
         self.begin_diagnostic();
@@ -565,24 +566,24 @@ impl Global {
     // Section 679
     pub(crate) fn append_to_vlist(&mut self, b: HalfWord) -> TeXResult<()> {
         if self.prev_depth() > IGNORE_DEPTH {
-            let d = width(baseline_skip()) - self.prev_depth() - height(b);
+            let d = self.width(self.baseline_skip()) - self.prev_depth() - self.height(b);
 
-            let p = if d < line_skip_limit() {
+            let p = if d < self.line_skip_limit() {
                 self.new_param_glue(LINE_SKIP_CODE as SmallNumber)?
             }
             else {
                 let p = self.new_skip_param(BASELINE_SKIP_CODE as SmallNumber)?;
-                *width_mut(self.temp_ptr) = d;
+                *self.width_mut(self.temp_ptr) = d;
                 p
             };
 
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             *self.tail_mut() = p;
         }
 
-        *link_mut(self.tail()) = b;
+        *self.link_mut(self.tail()) = b;
         *self.tail_mut() = b;
-        *self.prev_depth_mut() = depth(b);
+        *self.prev_depth_mut() = self.depth(b);
         Ok(())
     }
 }
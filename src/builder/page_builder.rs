@@ -1,56 +1,47 @@
-use crate::arithmetic::{badness, x_over_n};
+use crate::arithmetic::badness;
 use crate::constants::*;
-use crate::datastructures::{
-    r#box, box_mut, count, depth, dimen, float_cost, geq_word_define, glue_ptr,
-    glue_ref_count_mut, height, height_mut, holding_inserts, info, info_mut,
-    ins_ptr, ins_ptr_mut, link, link_mut, list_ptr, mark_ptr, max_dead_cycles,
-    max_depth, output_routine, penalty, penalty_mut, shrink, shrink_order,
-    skip, split_top_ptr, split_top_skip, split_top_skip_mut, stretch,
-    stretch_order, subtype, subtype_mut, token_ref_count_mut, r#type, type_mut,
-    vbadness, vbadness_mut, vfuzz, vfuzz_mut, vsize, width, width_mut
-};
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, SmallNumber,
     add_glue_ref, add_token_ref, precedes_break, vpack
 };
 
-#[cfg(feature = "stat")]
-use crate::datastructures::tracing_pages;
 
 // Part 45: The page builder
 
-// Section 981
-fn broken_ptr(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+impl Global {
+    // Section 981
+    fn broken_ptr(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-fn broken_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
-}
+    fn broken_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 
-pub(crate) fn broken_ins(p: HalfWord) -> HalfWord {
-    info(p + 1)
-}
+    pub(crate) fn broken_ins(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 1)
+    }
 
-fn broken_ins_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 1)
-}
+    fn broken_ins_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 1)
+    }
 
-fn last_ins_ptr(p: HalfWord) -> HalfWord {
-    link(p + 2)
-}
+    fn last_ins_ptr(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 2)
+    }
 
-fn last_ins_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 2)
-}
+    fn last_ins_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 2)
+    }
 
-fn best_ins_ptr(p: HalfWord) -> HalfWord {
-    info(p + 2)
-}
+    fn best_ins_ptr(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 2)
+    }
 
-fn best_ins_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 2)
+    fn best_ins_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 2)
+    }
 }
 
 // Section 982
@@ -86,13 +77,13 @@ impl Global {
     // Section 987
     fn freeze_page_specs(&mut self, s: SmallNumber) {
         self.page_contents = s;
-        page_goal!(self) = vsize();
-        self.page_max_depth = max_depth();
+        page_goal!(self) = self.vsize();
+        self.page_max_depth = self.max_depth();
         page_depth!(self) = 0;
         self.page_so_far[1..=6].fill(0);
         self.least_page_cost = AWFUL_BAD;
         #[cfg(feature = "stat")]
-        if tracing_pages() > 0 {
+        if self.tracing_pages() > 0 {
             self.begin_diagnostic();
             self.print_nl("%% goal height=");
             self.print_scaled(page_goal!(self));
@@ -103,14 +94,16 @@ impl Global {
     }
 }
 
-// Section 993
-fn ensure_vbox(n: u8) -> TeXResult<()> {
-    let p = r#box(n as HalfWord);
-    if p != NULL && r#type(p) == HLIST_NODE {
-        Err(TeXError::InsertionCanOnlyBeAddedToVbox(n as HalfWord))
-    }
-    else {
-        Ok(())
+impl Global {
+    // Section 993
+    fn ensure_vbox(&mut self, n: u8) -> TeXResult<()> {
+        let p = self.r#box(n as HalfWord);
+        if p != NULL && self.r#type(p) == HLIST_NODE {
+            self.box_error(TeXError::InsertionCanOnlyBeAddedToVbox(n as HalfWord), n as HalfWord)
+        }
+        else {
+            Ok(())
+        }
     }
 }
 
@@ -132,13 +125,13 @@ enum Goto {
 impl Global {
     // Section 994
     pub(crate) fn build_page(&mut self) -> TeXResult<()> {
-        if link(CONTRIB_HEAD) == NULL || self.output_active {
+        if self.link(self.contrib_head()) == NULL || self.output_active {
             return Ok(())
         }
 
         // continue:
         'sec994: loop {
-            let p = link(CONTRIB_HEAD);
+            let p = self.link(self.contrib_head());
 
             // Section 996
             if self.last_glue != MAX_HALFWORD {
@@ -146,17 +139,17 @@ impl Global {
             }
             self.last_penalty = 0;
             self.last_kern = 0;
-            if r#type(p) == GLUE_NODE {
-                self.last_glue = glue_ptr(p);
-                add_glue_ref!(self.last_glue);
+            if self.r#type(p) == GLUE_NODE {
+                self.last_glue = self.glue_ptr(p);
+                add_glue_ref!(self, self.last_glue);
             }
             else {
                 self.last_glue = MAX_HALFWORD;
-                if r#type(p) == PENALTY_NODE {
-                    self.last_penalty = penalty(p);
+                if self.r#type(p) == PENALTY_NODE {
+                    self.last_penalty = self.penalty(p);
                 }
-                else if r#type(p) == KERN_NODE {
-                    self.last_kern = width(p);
+                else if self.r#type(p) == KERN_NODE {
+                    self.last_kern = self.width(p);
                 }
             }
             // End section 996
@@ -164,7 +157,7 @@ impl Global {
             // Section 997
             let mut goto = 'block: {
                 // Section 1000
-                let pi = match r#type(p) {
+                let pi = match self.r#type(p) {
                     HLIST_NODE
                     | VLIST_NODE
                     | RULE_NODE => {
@@ -177,22 +170,22 @@ impl Global {
                                 self.page_contents = BOX_THERE;
                             }
                             let q = self.new_skip_param(TOP_SKIP_CODE as SmallNumber)?;
-                            *width_mut(self.temp_ptr) = if width(self.temp_ptr) > height(p) {
-                                width(self.temp_ptr) - height(p)
+                            *self.width_mut(self.temp_ptr) = if self.width(self.temp_ptr) > self.height(p) {
+                                self.width(self.temp_ptr) - self.height(p)
                             }
                             else {
                                 0
                             };
 
-                            *link_mut(q) = p;
-                            *link_mut(CONTRIB_HEAD) = q;
+                            *self.link_mut(q) = p;
+                            *self.link_mut(self.contrib_head()) = q;
                             continue 'sec994; // Goto continue
                             // End section 1001
                         }
                         
                         // Section 1002
-                        page_total!(self) += page_depth!(self) + height(p);
-                        page_depth!(self) = depth(p);
+                        page_total!(self) += page_depth!(self) + self.height(p);
+                        page_depth!(self) = self.depth(p);
                         break 'block Goto::Contribute;
                         // End section 1002
                     },
@@ -203,7 +196,7 @@ impl Global {
                         if self.page_contents < BOX_THERE {
                             break 'block Goto::Done1;
                         }
-                        if precedes_break!(self.page_tail) {
+                        if precedes_break!(self, self.page_tail) {
                             0
                         }
                         else {
@@ -215,10 +208,10 @@ impl Global {
                         if self.page_contents < BOX_THERE {
                             break 'block Goto::Done1;
                         }
-                        if link(p) == NULL {
+                        if self.link(p) == NULL {
                             return Ok(());
                         }
-                        if r#type(link(p)) == GLUE_NODE {
+                        if self.r#type(self.link(p)) == GLUE_NODE {
                             0
                         }
                         else {
@@ -230,7 +223,7 @@ impl Global {
                         if self.page_contents < BOX_THERE {
                             break 'block Goto::Done1;
                         }
-                        penalty(p)
+                        self.penalty(p)
                     },
 
                     MARK_NODE => break 'block Goto::Contribute,
@@ -240,86 +233,86 @@ impl Global {
                         if self.page_contents == EMPTY as SmallNumber {
                             self.freeze_page_specs(INSERTS_ONLY);
                         }
-                        let n = subtype(p);
-                        let mut r = PAGE_INS_HEAD;
-                        while n >= subtype(link(r)) {
-                            r = link(r);
+                        let n = self.subtype(p);
+                        let mut r = self.page_ins_head();
+                        while n >= self.subtype(self.link(r)) {
+                            r = self.link(r);
                         }
-                        if subtype(r) != n {
+                        if self.subtype(r) != n {
                             // Section 1009
                             let mut q = self.get_node(PAGE_INS_NODE_SIZE)?;
-                            *link_mut(q) = link(r);
-                            *link_mut(r) = q;
+                            *self.link_mut(q) = self.link(r);
+                            *self.link_mut(r) = q;
                             r = q;
-                            *subtype_mut(r) = n;
-                            *type_mut(r) = INSERTING;
-                            ensure_vbox(n as u8)?;
+                            *self.subtype_mut(r) = n;
+                            *self.type_mut(r) = INSERTING;
+                            self.ensure_vbox(n as u8)?;
 
-                            *height_mut(r) = if r#box(n as HalfWord) == NULL {
+                            *self.height_mut(r) = if self.r#box(n as HalfWord) == NULL {
                                 0
                             }
                             else {
-                                height(r#box(n as HalfWord)) + depth(r#box(n as HalfWord))
+                                self.height(self.r#box(n as HalfWord)) + self.depth(self.r#box(n as HalfWord))
                             };
 
-                            *best_ins_ptr_mut(r) = NULL;
-                            q = skip(n as HalfWord);
+                            *self.best_ins_ptr_mut(r) = NULL;
+                            q = self.skip(n as HalfWord);
 
-                            let h = if count(n as HalfWord) == 1000 {
-                                height(r)
+                            let h = if self.count(n as HalfWord) == 1000 {
+                                self.height(r)
                             }
                             else {
-                                x_over_n(height(r), 1000)?.0*count(n as HalfWord)
+                                self.x_over_n(self.height(r), 1000).0*self.count(n as HalfWord)
                             };
 
-                            page_goal!(self) -= h + width(q);
-                            self.page_so_far[2 + stretch_order(q) as usize] += stretch(q);
-                            page_shrink!(self) += shrink(q);
-                            if shrink_order(q) != NORMAL && shrink(q) != 0 {
-                                return Err(TeXError::InfiniteGlueShrinkageInsertedFrom(n as Integer));
+                            page_goal!(self) -= h + self.width(q);
+                            self.page_so_far[2 + self.stretch_order(q) as usize] += self.stretch(q);
+                            page_shrink!(self) += self.shrink(q);
+                            if self.shrink_order(q) != NORMAL && self.shrink(q) != 0 {
+                                self.error(TeXError::InfiniteGlueShrinkageInsertedFrom(n as Integer))?;
                             }
                             // End section 1009
                         }
 
-                        if r#type(r) == SPLIT_UP {
-                            self.insert_penalties += float_cost(p);
+                        if self.r#type(r) == SPLIT_UP {
+                            self.insert_penalties += self.float_cost(p);
                         }
                         else {
-                            *last_ins_ptr_mut(r) = p;
+                            *self.last_ins_ptr_mut(r) = p;
                             let delta = page_goal!(self) - page_total!(self) - page_depth!(self) + page_shrink!(self);
 
-                            let h = if count(n as HalfWord) == 1000 {
-                                height(p)
+                            let h = if self.count(n as HalfWord) == 1000 {
+                                self.height(p)
                             }
                             else {
-                                x_over_n(height(p), 1000)?.0*count(n as HalfWord)
+                                self.x_over_n(self.height(p), 1000).0*self.count(n as HalfWord)
                             };
 
-                            if (h <= 0 || h <= delta) && (height(p) + height(r) <= dimen(n as HalfWord)) {
+                            if (h <= 0 || h <= delta) && (self.height(p) + self.height(r) <= self.dimen(n as HalfWord)) {
                                 page_goal!(self) -= h;
-                                *height_mut(r) += height(p);
+                                *self.height_mut(r) += self.height(p);
                             }
                             else {
                                 // Section 1010
-                                let mut w = if count(n as HalfWord) <= 0 {
+                                let mut w = if self.count(n as HalfWord) <= 0 {
                                     MAX_DIMEN
                                 }
                                 else {
                                     let mut w = page_goal!(self) - page_total!(self) - page_depth!(self);
-                                    if count(n as HalfWord) != 1000 {
-                                        w = x_over_n(w, count(n as HalfWord))?.0 * 1000;
+                                    if self.count(n as HalfWord) != 1000 {
+                                        w = self.x_over_n(w, self.count(n as HalfWord)).0 * 1000;
                                     }
                                     w
                                 };
 
-                                if w > dimen(n as HalfWord) - height(r) {
-                                    w = dimen(n as HalfWord) - height(r);
+                                if w > self.dimen(n as HalfWord) - self.height(r) {
+                                    w = self.dimen(n as HalfWord) - self.height(r);
                                 }
-                                let q = self.vert_break(ins_ptr(p), w, depth(p))?;
-                                *height_mut(r) += self.best_height_plus_depth;
+                                let q = self.vert_break(self.ins_ptr(p), w, self.depth(p))?;
+                                *self.height_mut(r) += self.best_height_plus_depth;
 
                                 #[cfg(feature = "stat")]
-                                if tracing_pages() > 0 {
+                                if self.tracing_pages() > 0 {
                                     // Section 1011
                                     self.begin_diagnostic();
                                     self.print_nl("% split");
@@ -332,8 +325,8 @@ impl Global {
                                     if q == NULL {
                                         self.print_int(EJECT_PENALTY);
                                     }
-                                    else if r#type(q) == PENALTY_NODE {
-                                        self.print_int(penalty(q));
+                                    else if self.r#type(q) == PENALTY_NODE {
+                                        self.print_int(self.penalty(q));
                                     }
                                     else {
                                         self.print_char(b'0');
@@ -342,18 +335,18 @@ impl Global {
                                     // End section 1011
                                 }
 
-                                if count(n as HalfWord) != 1000 {
-                                    self.best_height_plus_depth = x_over_n(self.best_height_plus_depth, 1000)?.0 * count(n as HalfWord);
+                                if self.count(n as HalfWord) != 1000 {
+                                    self.best_height_plus_depth = self.x_over_n(self.best_height_plus_depth, 1000).0 * self.count(n as HalfWord);
                                 }
                                 page_goal!(self) -= self.best_height_plus_depth;
-                                *type_mut(r) = SPLIT_UP;
-                                *broken_ptr_mut(r) = q;
-                                *broken_ins_mut(r) = p;
+                                *self.type_mut(r) = SPLIT_UP;
+                                *self.broken_ptr_mut(r) = q;
+                                *self.broken_ins_mut(r) = p;
                                 if q == NULL {
                                     self.insert_penalties += EJECT_PENALTY;
                                 }
-                                else if r#type(q) == PENALTY_NODE {
-                                    self.insert_penalties += penalty(q);
+                                else if self.r#type(q) == PENALTY_NODE {
+                                    self.insert_penalties += self.penalty(q);
                                 }
                                 // End section 1010
                             }
@@ -405,7 +398,7 @@ impl Global {
                     }
 
                     #[cfg(feature = "stat")]
-                    if tracing_pages() > 0 {
+                    if self.tracing_pages() > 0 {
                         // Section 1006
                         self.begin_diagnostic();
                         self.print_nl("%");
@@ -440,10 +433,10 @@ impl Global {
                         self.best_page_break = p;
                         self.best_size = page_goal!(self);
                         self.least_page_cost = c;
-                        let mut r = link(PAGE_INS_HEAD);
-                        while r != PAGE_INS_HEAD {
-                            *best_ins_ptr_mut(r) = last_ins_ptr(r);
-                            r = link(r);
+                        let mut r = self.link(self.page_ins_head());
+                        while r != self.page_ins_head() {
+                            *self.best_ins_ptr_mut(r) = self.last_ins_ptr(r);
+                            r = self.link(r);
                         }
                     }
                     if c == AWFUL_BAD || pi <= EJECT_PENALTY {
@@ -456,7 +449,7 @@ impl Global {
                 }
                 // End section 1005
 
-                if r#type(p) < GLUE_NODE || r#type(p) > KERN_NODE {
+                if self.r#type(p) < GLUE_NODE || self.r#type(p) > KERN_NODE {
                     break 'block Goto::Contribute;
                 }
                 Goto::UpdateHeight
@@ -465,21 +458,26 @@ impl Global {
             if let Goto::UpdateHeight = goto {
                 // update heights:
                 // Section 1004
-                let q = match r#type(p) {
+                let q = match self.r#type(p) {
                     KERN_NODE => p,
 
                     _ => {
-                        let q = glue_ptr(p);
-                        self.page_so_far[2 + stretch_order(q) as usize] += stretch(q);
-                        page_shrink!(self) += shrink(q);
-                        if shrink_order(q) != NORMAL && shrink(q) != 0 {
-                            return Err(TeXError::InfiniteGlueShrinkageOnCurrentPage);
+                        let mut q = self.glue_ptr(p);
+                        self.page_so_far[2 + self.stretch_order(q) as usize] += self.stretch(q);
+                        page_shrink!(self) += self.shrink(q);
+                        if self.shrink_order(q) != NORMAL && self.shrink(q) != 0 {
+                            self.error(TeXError::InfiniteGlueShrinkageOnCurrentPage)?;
+                            let r = self.new_spec(q)?;
+                            *self.shrink_order_mut(r) = NORMAL;
+                            self.delete_glue_ref(q);
+                            *self.glue_ptr_mut(p) = r;
+                            q = r;
                         }
                         q
                     }
                 };
 
-                page_total!(self) += page_depth!(self) + width(q);
+                page_total!(self) += page_depth!(self) + self.width(q);
                 page_depth!(self) = 0;
                 // End section 1004
             }
@@ -496,10 +494,10 @@ impl Global {
                     // End section 1003
 
                     // Section 998
-                    *link_mut(self.page_tail) = p;
+                    *self.link_mut(self.page_tail) = p;
                     self.page_tail = p;
-                    *link_mut(CONTRIB_HEAD) = link(p);
-                    *link_mut(p) = NULL;
+                    *self.link_mut(self.contrib_head()) = self.link(p);
+                    *self.link_mut(p) = NULL;
                     goto = Goto::Done;
                     // End section 998
                 },
@@ -513,8 +511,8 @@ impl Global {
                 _=> {
                     // done1:
                     // Section 999
-                    *link_mut(CONTRIB_HEAD) = link(p);
-                    *link_mut(p) = NULL;
+                    *self.link_mut(self.contrib_head()) = self.link(p);
+                    *self.link_mut(p) = NULL;
                     self.flush_node_list(p)?;
                     // End section 999
                 },
@@ -523,17 +521,17 @@ impl Global {
             // done:
             // End section 997
 
-            if link(CONTRIB_HEAD) == NULL {
+            if self.link(self.contrib_head()) == NULL {
                 break 'sec994;
             }
         }
 
         // Section 995
         if self.nest_ptr == 0 {
-            *self.tail_mut() = CONTRIB_HEAD;
+            *self.tail_mut() = self.contrib_head();
         }
         else {
-            contrib_tail!(self) = CONTRIB_HEAD;
+            contrib_tail!(self) = self.contrib_head();
         }
         // End section 995
         Ok(())
@@ -542,12 +540,12 @@ impl Global {
     // Section 1012
     fn fire_up(&mut self, c: HalfWord) -> TeXResult<()> {
         // Section 1013
-        if r#type(self.best_page_break) == PENALTY_NODE {
-            geq_word_define(INT_BASE + OUTPUT_PENALTY_CODE, penalty(self.best_page_break));
-            *penalty_mut(self.best_page_break) = INF_PENALTY;
+        if self.r#type(self.best_page_break) == PENALTY_NODE {
+            self.geq_word_define(INT_BASE + OUTPUT_PENALTY_CODE, self.penalty(self.best_page_break));
+            *self.penalty_mut(self.best_page_break) = INF_PENALTY;
         }
         else {
-            geq_word_define(INT_BASE + OUTPUT_PENALTY_CODE, INF_PENALTY);
+            self.geq_word_define(INT_BASE + OUTPUT_PENALTY_CODE, INF_PENALTY);
         }
         // End section 1013
 
@@ -556,7 +554,7 @@ impl Global {
                 self.delete_token_ref(self.top_mark());
             }
             *self.top_mark_mut() = self.bot_mark();
-            add_token_ref!(self.top_mark());
+            add_token_ref!(self, self.top_mark());
             self.delete_token_ref(self.first_mark());
             *self.first_mark_mut() = NULL;
         }
@@ -567,96 +565,96 @@ impl Global {
         }
 
         // Section 1015
-        if r#box(255) != NULL {
-            return Err(TeXError::Box255IsNotVoid);
+        if self.r#box(255) != NULL {
+            self.box_error(TeXError::Box255IsNotVoid, 255)?;
         }
         // End section 1015
 
         self.insert_penalties = 0;
-        let save_split_top_skip = split_top_skip();
-        if holding_inserts() <= 0 {
+        let save_split_top_skip = self.split_top_skip();
+        if self.holding_inserts() <= 0 {
             // Section 1018
-            let mut r = link(PAGE_INS_HEAD);
-            while r != PAGE_INS_HEAD {
-                if best_ins_ptr(r) != NULL {
-                    let n = subtype(r);
-                    ensure_vbox(n as u8)?;
-                    if r#box(n as HalfWord) == NULL {
-                        *box_mut(n as HalfWord) = self.new_null_box()?;
+            let mut r = self.link(self.page_ins_head());
+            while r != self.page_ins_head() {
+                if self.best_ins_ptr(r) != NULL {
+                    let n = self.subtype(r);
+                    self.ensure_vbox(n as u8)?;
+                    if self.r#box(n as HalfWord) == NULL {
+                        *self.box_mut(n as HalfWord) = self.new_null_box()?;
                     }
-                    let mut p = r#box(n as HalfWord) + LIST_OFFSET;
-                    while link(p) != NULL {
-                        p = link(p);
+                    let mut p = self.r#box(n as HalfWord) + LIST_OFFSET;
+                    while self.link(p) != NULL {
+                        p = self.link(p);
                     }
-                    *last_ins_ptr_mut(r) = p;
+                    *self.last_ins_ptr_mut(r) = p;
                 }
-                r = link(r);
+                r = self.link(r);
             }
             // End section 1018
         }
 
-        let mut q = HOLD_HEAD;
-        *link_mut(q) = NULL;
-        let mut prev_p = PAGE_HEAD;
-        let mut p = link(prev_p);
+        let mut q = self.hold_head();
+        *self.link_mut(q) = NULL;
+        let mut prev_p = self.page_head();
+        let mut p = self.link(prev_p);
         while p != self.best_page_break {
-            if r#type(p) == INS_NODE {
-                if holding_inserts() <= 0 {
+            if self.r#type(p) == INS_NODE {
+                if self.holding_inserts() <= 0 {
                     // Section 1020
-                    let mut r = link(PAGE_INS_HEAD);
-                    while subtype(r) != subtype(p) {
-                        r = link(r);
+                    let mut r = self.link(self.page_ins_head());
+                    while self.subtype(r) != self.subtype(p) {
+                        r = self.link(r);
                     }
-                    let wait = match best_ins_ptr(r) {
+                    let wait = match self.best_ins_ptr(r) {
                         NULL => true,
 
                         _ => {
                             let mut wait = false;
-                            let mut s = last_ins_ptr(r);
-                            *link_mut(s) = ins_ptr(p);
-                            if best_ins_ptr(r) == p {
+                            let mut s = self.last_ins_ptr(r);
+                            *self.link_mut(s) = self.ins_ptr(p);
+                            if self.best_ins_ptr(r) == p {
                                 // Section 1021
-                                if r#type(r) == SPLIT_UP && broken_ins(r) == p && broken_ptr(r) != NULL {
-                                    while link(s) != broken_ptr(r) {
-                                        s = link(s);
+                                if self.r#type(r) == SPLIT_UP && self.broken_ins(r) == p && self.broken_ptr(r) != NULL {
+                                    while self.link(s) != self.broken_ptr(r) {
+                                        s = self.link(s);
                                     }
-                                    *link_mut(s) = NULL;
-                                    *split_top_skip_mut() = split_top_ptr(p);
-                                    *ins_ptr_mut(p) = self.prune_page_top(broken_ptr(r))?;
-                                    if ins_ptr(p) != NULL {
-                                        self.temp_ptr = vpack!(self, ins_ptr(p), NATURAL)?;
-                                        *height_mut(p) = height(self.temp_ptr) + depth(self.temp_ptr);
+                                    *self.link_mut(s) = NULL;
+                                    *self.split_top_skip_mut() = self.split_top_ptr(p);
+                                    *self.ins_ptr_mut(p) = self.prune_page_top(self.broken_ptr(r))?;
+                                    if self.ins_ptr(p) != NULL {
+                                        self.temp_ptr = vpack!(self, self.ins_ptr(p), NATURAL)?;
+                                        *self.height_mut(p) = self.height(self.temp_ptr) + self.depth(self.temp_ptr);
                                         self.free_node(self.temp_ptr, BOX_NODE_SIZE);
                                         wait = true;
                                     }
                                 }
-                                *best_ins_ptr_mut(r) = NULL;
-                                let n = subtype(r);
-                                self.temp_ptr = list_ptr(r#box(n as HalfWord));
-                                self.free_node(r#box(n as HalfWord), BOX_NODE_SIZE);
-                                *box_mut(n as HalfWord) = vpack!(self, self.temp_ptr, NATURAL)?;
+                                *self.best_ins_ptr_mut(r) = NULL;
+                                let n = self.subtype(r);
+                                self.temp_ptr = self.list_ptr(self.r#box(n as HalfWord));
+                                self.free_node(self.r#box(n as HalfWord), BOX_NODE_SIZE);
+                                *self.box_mut(n as HalfWord) = vpack!(self, self.temp_ptr, NATURAL)?;
                                 // End section 1021
                             }
                             else {
-                                while link(s) != NULL {
-                                    s = link(s);
+                                while self.link(s) != NULL {
+                                    s = self.link(s);
                                 }
-                                *last_ins_ptr_mut(r) = s;
+                                *self.last_ins_ptr_mut(r) = s;
                             }
                             wait
                         }
                     };
 
                     // Section 1022
-                    *link_mut(prev_p) = link(p);
-                    *link_mut(p) = NULL;
+                    *self.link_mut(prev_p) = self.link(p);
+                    *self.link_mut(p) = NULL;
                     if wait {
-                        *link_mut(q) = p;
+                        *self.link_mut(q) = p;
                         q = p;
                         self.insert_penalties += 1;
                     }
                     else {
-                        self.delete_glue_ref(split_top_ptr(p));
+                        self.delete_glue_ref(self.split_top_ptr(p));
                         self.free_node(p, INS_NODE_SIZE);
                     }
                     p = prev_p;
@@ -664,27 +662,27 @@ impl Global {
                     // End section 1020
                 }
             }
-            else if r#type(p) == MARK_NODE {
+            else if self.r#type(p) == MARK_NODE {
                 // Section 1016
                 if self.first_mark() == NULL {
-                    *self.first_mark_mut() = mark_ptr(p);
-                    add_token_ref!(self.first_mark());
+                    *self.first_mark_mut() = self.mark_ptr(p);
+                    add_token_ref!(self, self.first_mark());
                 }
                 if self.bot_mark() != NULL {
                     self.delete_token_ref(self.bot_mark());
                 }
-                *self.bot_mark_mut() = mark_ptr(p);
-                add_token_ref!(self.bot_mark());
+                *self.bot_mark_mut() = self.mark_ptr(p);
+                add_token_ref!(self, self.bot_mark());
                 // End section 1016
             }
             prev_p = p;
-            p = link(prev_p);
+            p = self.link(prev_p);
         }
-        *split_top_skip_mut() = save_split_top_skip;
+        *self.split_top_skip_mut() = save_split_top_skip;
 
         // Section 1017
         if p != NULL {
-            if link(CONTRIB_HEAD) == NULL {
+            if self.link(self.contrib_head()) == NULL {
                 if self.nest_ptr == 0 {
                     *self.tail_mut() = self.page_tail;
                 }
@@ -692,25 +690,25 @@ impl Global {
                     contrib_tail!(self) = self.page_tail;
                 }
             }
-            *link_mut(self.page_tail) = link(CONTRIB_HEAD);
-            *link_mut(CONTRIB_HEAD) = p;
-            *link_mut(prev_p) = NULL;
+            *self.link_mut(self.page_tail) = self.link(self.contrib_head());
+            *self.link_mut(self.contrib_head()) = p;
+            *self.link_mut(prev_p) = NULL;
         }
-        let save_vbadness = vbadness();
-        *vbadness_mut() = INF_BAD;
-        let save_vfuzz = vfuzz();
-        *vfuzz_mut() = MAX_DIMEN;
-        *box_mut(255) = self.vpackage(link(PAGE_HEAD), self.best_size, EXACTLY, self.page_max_depth)?;
-        *vbadness_mut() = save_vbadness;
-        *vfuzz_mut() = save_vfuzz;
+        let save_vbadness = self.vbadness();
+        *self.vbadness_mut() = INF_BAD;
+        let save_vfuzz = self.vfuzz();
+        *self.vfuzz_mut() = MAX_DIMEN;
+        *self.box_mut(255) = self.vpackage(self.link(self.page_head()), self.best_size, EXACTLY, self.page_max_depth)?;
+        *self.vbadness_mut() = save_vbadness;
+        *self.vfuzz_mut() = save_vfuzz;
         if self.last_glue != MAX_HALFWORD {
             self.delete_glue_ref(self.last_glue);
         }
 
         // Section 991
         self.page_contents = EMPTY as SmallNumber;
-        self.page_tail = PAGE_HEAD;
-        *link_mut(PAGE_HEAD) = NULL;
+        self.page_tail = self.page_head();
+        *self.link_mut(self.page_head()) = NULL;
         self.last_glue = MAX_HALFWORD;
         self.last_penalty = 0;
         self.last_kern = 0;
@@ -718,31 +716,32 @@ impl Global {
         self.page_max_depth = 0;
         // End section 991
 
-        if q != HOLD_HEAD {
-            *link_mut(PAGE_HEAD) = link(HOLD_HEAD);
+        if q != self.hold_head() {
+            *self.link_mut(self.page_head()) = self.link(self.hold_head());
             self.page_tail = q;
         }
         // End section 1017
         
         // Section 1019
-        let mut r = link(PAGE_INS_HEAD);
-        while r != PAGE_INS_HEAD {
-            q = link(r);
+        let mut r = self.link(self.page_ins_head());
+        while r != self.page_ins_head() {
+            q = self.link(r);
             self.free_node(r, PAGE_INS_NODE_SIZE);
             r = q;
         }
-        *link_mut(PAGE_INS_HEAD) = PAGE_INS_HEAD;
+        *self.link_mut(self.page_ins_head()) = self.page_ins_head();
         // End section 1019
         // End section 1014
 
         if self.top_mark() != NULL && self.first_mark() == NULL {
             *self.first_mark_mut() = self.top_mark();
-            add_token_ref!(self.top_mark());
+            add_token_ref!(self, self.top_mark());
         }
 
-        if output_routine() != NULL {
-            if self.dead_cycles >= max_dead_cycles() {
-                return Err(TeXError::OutputLoop);
+        if self.output_routine() != NULL {
+            if self.dead_cycles >= self.max_dead_cycles() {
+                // Section 1024
+                self.error(TeXError::OutputLoop)?;
             }
             else {
                 // Section 1025
@@ -752,7 +751,7 @@ impl Global {
                 *self.mode_mut() = -VMODE;
                 *self.prev_depth_mut() = IGNORE_DEPTH;
                 *self.mode_line_mut() = -self.line;
-                self.begin_token_list(output_routine(), OUTPUT_TEXT)?;
+                self.begin_token_list(self.output_routine(), OUTPUT_TEXT)?;
                 self.new_save_level(OUTPUT_GROUP)?;
                 self.normal_paragraph()?;
                 self.scan_left_brace()?;
@@ -762,8 +761,8 @@ impl Global {
         }
 
         // Section 1023
-        if link(PAGE_HEAD) != NULL {
-            if link(CONTRIB_HEAD) == NULL {
+        if self.link(self.page_head()) != NULL {
+            if self.link(self.contrib_head()) == NULL {
                 if self.nest_ptr == 0 {
                     *self.tail_mut() = self.page_tail;
                 }
@@ -772,14 +771,14 @@ impl Global {
                 }
             }
             else {
-                *link_mut(self.page_tail) = link(CONTRIB_HEAD);
+                *self.link_mut(self.page_tail) = self.link(self.contrib_head());
             }
-            *link_mut(CONTRIB_HEAD) = link(PAGE_HEAD);
-            *link_mut(PAGE_HEAD) = NULL;
-            self.page_tail = PAGE_HEAD;
+            *self.link_mut(self.contrib_head()) = self.link(self.page_head());
+            *self.link_mut(self.page_head()) = NULL;
+            self.page_tail = self.page_head();
         }
-        self.ship_out(r#box(255))?;
-        *box_mut(255) = NULL;
+        self.ship_out(self.r#box(255))?;
+        *self.box_mut(255) = NULL;
         // End secction 1023
         Ok(())
     }
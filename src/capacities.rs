@@ -0,0 +1,234 @@
+use crate::constants::MEM_BOT;
+use crate::{
+    Global, Integer
+};
+
+// Capacities
+// The sizes of TeX's arrays (Section 11) are chosen when the program starts,
+// with the names of the variables of `texmf.cnf` in web2c.
+// The memory of a format is fixed by INITEX: `mem_top` is recorded in the
+// format, and a production run adds `extra_mem_top` words on top of it.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capacities {
+    // Words of dynamic memory for INITEX (`mem_top - mem_bot + 1`).
+    pub main_memory: Integer,
+    // Words added to the memory of the format in production runs.
+    pub extra_mem_top: Integer,
+    pub buf_size: Integer,
+    pub stack_size: Integer,
+    pub max_in_open: Integer,
+    pub font_max: Integer,
+    pub font_mem_size: Integer,
+    pub param_size: Integer,
+    pub nest_size: Integer,
+    pub max_strings: Integer,
+    pub pool_size: Integer,
+    pub save_size: Integer,
+    pub trie_size: Integer,
+    // Control sequences added to the `HASH_SIZE` of the hash table.
+    pub hash_extra: Integer,
+}
+
+impl Default for Capacities {
+    fn default() -> Self {
+        Self {
+            // The TRIP test requires `mem_top = mem_max = 3000`,
+            // and the other sizes of `tex.web`.
+            main_memory: if cfg!(feature = "trip") { 3000 } else { 30000 },
+            extra_mem_top: 0,
+            buf_size: if cfg!(feature = "trip") { 500 } else { 200_000 }, // 500 is not enough
+            stack_size: 200,
+            max_in_open: 6,
+            font_max: 75,
+            font_mem_size: 20000,
+            param_size: 60,
+            nest_size: 40,
+            max_strings: 3000,
+            pool_size: 32000,
+            save_size: 600,
+            trie_size: 8000,
+            hash_extra: 0,
+        }
+    }
+}
+
+impl Capacities {
+    // The capacities with their names, in the order of format files.
+    pub(crate) fn fields_mut(&mut self) -> [(&'static str, &mut Integer); 14] {
+        [
+            ("main_memory", &mut self.main_memory),
+            ("extra_mem_top", &mut self.extra_mem_top),
+            ("buf_size", &mut self.buf_size),
+            ("stack_size", &mut self.stack_size),
+            ("max_in_open", &mut self.max_in_open),
+            ("font_max", &mut self.font_max),
+            ("font_mem_size", &mut self.font_mem_size),
+            ("param_size", &mut self.param_size),
+            ("nest_size", &mut self.nest_size),
+            ("max_strings", &mut self.max_strings),
+            ("pool_size", &mut self.pool_size),
+            ("save_size", &mut self.save_size),
+            ("trie_size", &mut self.trie_size),
+            ("hash_extra", &mut self.hash_extra),
+        ]
+    }
+
+    pub(crate) fn values(&self) -> Vec<(&'static str, Integer)> {
+        self.clone().fields_mut().into_iter().map(|(name, value)| (name, *value)).collect()
+    }
+
+    fn value_mut(&mut self, name: &str) -> Option<&mut Integer> {
+        self.fields_mut().into_iter().find(|(n, _)| *n == name).map(|(_, value)| value)
+    }
+
+    // The smallest capacities TeX can run with, as the `inf_` constants of web2c.
+    pub(crate) fn minimum(name: &str) -> Integer {
+        match name {
+            "main_memory" => 2999,
+            "buf_size" => 500,
+            "stack_size" => 200,
+            "max_in_open" => 6,
+            "font_max" => 50,
+            "font_mem_size" => 20000,
+            "param_size" => 60,
+            "nest_size" => 40,
+            "max_strings" => 3000,
+            "pool_size" => 32000,
+            "save_size" => 600,
+            "trie_size" => 8000,
+            _ => 0
+        }
+    }
+
+    // The first capacity below its minimum, if any.
+    pub(crate) fn below_minimum(&self) -> Option<&'static str> {
+        self.values()
+            .into_iter()
+            .find(|&(name, value)| value < Self::minimum(name))
+            .map(|(name, _)| name)
+    }
+
+    // Sets the capacity `name` from a line `name = value` or `name=value`.
+    // Other variables are ignored, so that a `texmf.cnf` can hold them.
+    // A value below the minimum of the capacity is raised to it,
+    // as `setup_bound_var` and `const_chk` do in web2c.
+    pub fn set(&mut self, line: &str) -> Result<(), String> {
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(format!("'{line}' is not of the form name=value"));
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if let Some(capacity) = self.value_mut(name) {
+            match value.parse::<Integer>() {
+                Ok(n) if n >= 0 => *capacity = n.max(Self::minimum(name)),
+                _ => return Err(format!("bad value '{value}' for {name}"))
+            }
+        }
+        Ok(())
+    }
+
+    // Reads the lines of a `texmf.cnf` file: `%` starts a comment,
+    // and blank lines are skipped.
+    pub fn read_cnf(&mut self, text: &str) -> Result<(), String> {
+        for line in text.lines() {
+            let line = line.split('%').next().unwrap().trim();
+            if !line.is_empty() {
+                self.set(line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Size of an array that grows geometrically to hold `needed` entries,
+// or `None` beyond the ceiling `sup`.
+// Nothing grows with the `trip` feature: the TRIP test expects the overflows.
+pub(crate) fn grown_size(size: Integer, needed: Integer, sup: Integer) -> Option<Integer> {
+    if cfg!(feature = "trip") || needed > sup {
+        None
+    }
+    else {
+        Some(needed.max(size.saturating_mul(2).min(sup)))
+    }
+}
+
+impl Global {
+    // The capacities of the run, recorded in the formats it dumps.
+    pub(crate) fn capacities(&self) -> Capacities {
+        Capacities {
+            main_memory: self.mem_top - MEM_BOT + 1,
+            extra_mem_top: self.extra_mem_top,
+            buf_size: self.buf_size,
+            stack_size: self.stack_size,
+            max_in_open: self.max_in_open,
+            font_max: self.font_max,
+            font_mem_size: self.font_mem_size,
+            param_size: self.param_size,
+            nest_size: self.nest_size,
+            max_strings: self.max_strings,
+            pool_size: self.pool_size,
+            save_size: self.save_size,
+            trie_size: self.trie_size,
+            hash_extra: self.hash_extra,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_capacities() {
+        let mut capacities = Capacities::default();
+        assert_eq!(capacities.set("main_memory=500000"), Ok(()));
+        assert_eq!(capacities.set(" hash_extra = 50000 "), Ok(()));
+        assert_eq!(capacities.main_memory, 500000);
+        assert_eq!(capacities.hash_extra, 50000);
+        // Unknown variables are ignored.
+        assert_eq!(capacities.set("TEXINPUTS=.:/usr/share/texmf//"), Ok(()));
+        assert_eq!(capacities, Capacities { main_memory: 500000, hash_extra: 50000, ..Capacities::default() });
+    }
+
+    #[test]
+    fn set_bad_capacities() {
+        let mut capacities = Capacities::default();
+        assert_eq!(capacities.set("stack_size"), Err("'stack_size' is not of the form name=value".to_string()));
+        assert_eq!(capacities.set("stack_size=-1"), Err("bad value '-1' for stack_size".to_string()));
+        assert_eq!(capacities.set("stack_size=ten"), Err("bad value 'ten' for stack_size".to_string()));
+        assert_eq!(capacities, Capacities::default());
+    }
+
+    #[test]
+    fn capacities_are_raised_to_their_minimum() {
+        let mut capacities = Capacities::default();
+        for name in ["stack_size", "nest_size", "save_size", "main_memory", "hash_extra"] {
+            assert_eq!(capacities.set(&format!("{name}=0")), Ok(()));
+        }
+        assert_eq!(capacities.stack_size, 200);
+        assert_eq!(capacities.nest_size, 40);
+        assert_eq!(capacities.save_size, 600);
+        assert_eq!(capacities.main_memory, 2999);
+        assert_eq!(capacities.hash_extra, 0);
+        assert_eq!(capacities.below_minimum(), None);
+        assert_eq!(Capacities { save_size: 10, ..Capacities::default() }.below_minimum(), Some("save_size"));
+    }
+
+    #[test]
+    fn read_cnf_file() {
+        let mut capacities = Capacities::default();
+        let cnf = "% texmf.cnf\n\
+            \n\
+            main_memory = 100000 % words\n\
+            TEXINPUTS = .\n\
+            nest_size=1\n";
+        assert_eq!(capacities.read_cnf(cnf), Ok(()));
+        assert_eq!(capacities.main_memory, 100000);
+        assert_eq!(capacities.nest_size, 40);
+
+        let mut capacities = Capacities::default();
+        assert_eq!(capacities.read_cnf("buf_size=1000\npool_size=-5\nstack_size=300\n"), Err("bad value '-5' for pool_size".to_string()));
+        assert_eq!(capacities.buf_size, 1000);
+        assert_eq!(capacities.stack_size, 200);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::capacities::Capacities;
+use crate::constants::{BATCH_MODE, FATAL_ERROR_STOP, SPOTLESS};
+use crate::error::{TeXError, TeXResult};
+use crate::io::{AlphaFileIn, AlphaFileOut, MemoryFileSystem};
+use crate::{Global, Integer, OutputFormat, PRELOADED_FORMAT};
+
+// Compiling a document in memory, without touching the file system.
+
+pub struct CompileOptions<'a> {
+    // Content of a format file, as dumped by INITEX.
+    pub format: &'a [u8],
+    // Name of the job: the source is read as `job_name.tex`,
+    // and the outputs are `job_name.dvi` (or `job_name.pdf`) and `job_name.log`.
+    pub job_name: &'a str,
+    pub output_format: OutputFormat,
+    // Error messages start with "file:line: " instead of "! ",
+    // as with the option `-file-line-error`.
+    pub file_line_error: bool,
+    // The diagnostics are written as JSON lines, as with the option `-diagnostics=`.
+    pub diagnostics: bool,
+    // Other files the job can read (with `\input`, `\font`, ...),
+    // given by name and content. Names are the ones TeX looks for,
+    // for instance "TeXfonts/cmr5.tfm" for `\font\x=cmr5`.
+    pub inputs: &'a [(&'a str, &'a [u8])],
+    // Sizes of the arrays; the memory is the one of the format.
+    pub capacities: Capacities,
+}
+
+impl Default for CompileOptions<'_> {
+    fn default() -> Self {
+        Self {
+            format: PRELOADED_FORMAT,
+            job_name: "texput",
+            output_format: OutputFormat::Dvi,
+            file_line_error: false,
+            diagnostics: false,
+            inputs: &[],
+            capacities: Capacities::default(),
+        }
+    }
+}
+
+pub struct CompileOutput {
+    // `None` if no page was shipped out, or if the output format is not DVI.
+    pub dvi: Option<Vec<u8>>,
+    // `None` if no page was shipped out, or if the output format is not PDF.
+    pub pdf: Option<Vec<u8>>,
+    // One SVG file per page, empty if the output format is not SVG.
+    pub svg: Vec<Vec<u8>>,
+    pub log: String,
+    // The JSON lines of the diagnostics, `None` if they were not asked for.
+    pub diagnostics: Option<String>,
+    // Files opened with `\openout`, in alphabetical order of their names.
+    pub writes: Vec<WriteOutput>,
+    // Errors in the order they were reported. TeX recovers from most
+    // of them; if `history` is `FATAL_ERROR_STOP`, the last one stopped it.
+    pub errors: Vec<CompileError>,
+    // One of `SPOTLESS`, `WARNING_ISSUED`, `ERROR_MESSAGE_ISSUED`
+    // and `FATAL_ERROR_STOP`.
+    pub history: usize,
+}
+
+pub struct WriteOutput {
+    pub name: String,
+    pub content: String,
+}
+
+pub struct CompileError {
+    // The message that follows "! " in the log.
+    pub message: String,
+    pub help: Vec<String>,
+    // Line in the file being read when the error occurred.
+    pub line: Option<Integer>,
+}
+
+impl CompileError {
+    fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            help: vec![],
+            line: None,
+        }
+    }
+}
+
+// Runs TeX on `source` with the format given in `options`.
+// The terminal is not used: nothing is printed and nothing is read,
+// and TeX runs in batch mode.
+pub fn compile(source: &str, options: &CompileOptions) -> CompileOutput {
+    let file_system = MemoryFileSystem::new();
+    for (name, content) in options.inputs {
+        file_system.insert(name, content.to_vec());
+    }
+    file_system.insert(&format!("{}.tex", options.job_name), source.as_bytes().to_vec());
+
+    let mut global = Global {
+        file_system: Box::new(file_system.clone()),
+        term_in: AlphaFileIn::t_open_in_empty(),
+        term_out: AlphaFileOut::t_open_out_sink(),
+        reported_errors: Some(vec![]),
+        output_format: options.output_format,
+        file_line_error: options.file_line_error,
+        ..Global::new(&options.capacities)
+    };
+    let diagnostics_name = format!("{}.diagnostics", options.job_name);
+    if options.diagnostics {
+        global.open_diagnostics(&diagnostics_name);
+    }
+
+    let (errors, history) = match global.start_job(options) {
+        Ok(()) => {
+            match global.run_job() {
+                Ok(()) => {
+                    if let Err(texerror) = global.close_files_and_terminate() {
+                        global.succumb(texerror);
+                    }
+                },
+                Err(texerror) => global.succumb(texerror)
+            }
+            (global.reported_errors.take().unwrap_or_default(), global.history)
+        },
+        Err(error) => (vec![error], FATAL_ERROR_STOP)
+    };
+
+    let diagnostics = file_system.remove(&diagnostics_name)
+        .map(|content| String::from_utf8_lossy(&content).into_owned());
+    let log = file_system.remove(&format!("{}.log", options.job_name)).unwrap_or_default();
+    let dvi = file_system.remove(&format!("{}.dvi", options.job_name));
+    let pdf = file_system.remove(&format!("{}.pdf", options.job_name));
+    let svg = (1..)
+        .map_while(|n| file_system.remove(&format!("{}-{n}.svg", options.job_name)))
+        .collect();
+    let writes = file_system.created()
+        .into_iter()
+        .filter_map(|name| {
+            let content = file_system.remove(&name)?;
+            Some(WriteOutput {
+                name,
+                content: String::from_utf8_lossy(&content).into_owned()
+            })
+        })
+        .collect();
+
+    CompileOutput {
+        dvi,
+        pdf,
+        svg,
+        log: String::from_utf8_lossy(&log).into_owned(),
+        diagnostics,
+        writes,
+        errors,
+        history
+    }
+}
+
+impl Global {
+    // Section 1332, up to the loading of the format.
+    // Errors happening here cannot be reported with `error`
+    // since nothing is ready yet.
+    fn start_job(&mut self, options: &CompileOptions) -> Result<(), CompileError> {
+        // Section 14
+        let bad = self.check_constant_values_for_consistency();
+        if bad > 0 {
+            return Err(CompileError::new(&format!("Ouch---my internal constants have been clobbered!---case {bad}")));
+        }
+        // End section 14
+
+        self.initialize();
+        self.initialize_output_routines();
+        self.initialize_input_routines();
+        if !self.init_terminal(options.job_name) {
+            return Err(CompileError::new("The job name is empty."));
+        }
+        if options.format.is_empty() {
+            return Err(CompileError::new("Sorry, I can't find that format."));
+        }
+        self.fmt_file.set_preloaded(options.format.to_vec());
+        match self.load_fmt_file() {
+            Ok(()) => (),
+            Err(TeXError::BadFormat(error)) => {
+                return Err(CompileError::new(&format!("Fatal format file error: {}; I'm stymied", error.reason())));
+            },
+            Err(texerror) => {
+                // Any other error is fatal, it is reported by `succumb`
+                // and recorded by `error`.
+                self.succumb(texerror);
+                let error = self.reported_errors.as_mut().and_then(|errors| errors.pop());
+                return Err(error.unwrap_or_else(|| CompileError::new("The format could not be loaded.")));
+            }
+        }
+        self.fmt_file.close();
+        self.prepare_first_line();
+        self.interaction = BATCH_MODE;
+        Ok(())
+    }
+
+    // Section 1337 from the first input file, and the main program
+    // up to `final_cleanup`.
+    fn run_job(&mut self) -> TeXResult<()> {
+        self.fix_date_and_time();
+        self.sec75_initialize_print_selector();
+        self.start_input()?; // \input assumed
+        self.history = SPOTLESS; // ready to go!
+        self.main_control()?;  // come to life
+        self.final_cleanup()   // prepare for death
+    }
+
+    // Called by `error` once the message and its context are printed.
+    pub(crate) fn record_error(&mut self) {
+        if self.reported_errors.is_none() {
+            return;
+        }
+        // The message starts after "! ".
+        let message = String::from_utf8_lossy(&self.err_message).into_owned();
+        let error = CompileError {
+            message: message.trim_end().to_string(),
+            help: self.help_line.iter().map(|s| s.to_string()).collect(),
+            line: if self.in_open > 0 {
+                Some(self.line)
+            }
+            else {
+                None
+            }
+        };
+        if let Some(errors) = self.reported_errors.as_mut() {
+            errors.push(error);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::constants::ERROR_MESSAGE_ISSUED;
+
+    // A format dumped by INITEX from `first_line`, as `tex-rust -ini` does
+    // (Section 1332), with the capacities `capacities`.
+    pub(crate) fn dump_format(first_line: &str, capacities: &Capacities) -> Vec<u8> {
+        let file_system = MemoryFileSystem::new();
+        let mut global = Global {
+            file_system: Box::new(file_system.clone()),
+            term_in: AlphaFileIn::t_open_in_empty(),
+            term_out: AlphaFileOut::t_open_out_sink(),
+            initex_mode: true,
+            ..Global::new(capacities)
+        };
+        global.initialize();
+        global.get_strings_started().and_then(|()| global.init_prim()).unwrap();
+        global.init_str_ptr_set(global.str_ptr());
+        global.init_pool_ptr_set(global.pool_ptr());
+        global.initialize_output_routines();
+        global.initialize_input_routines();
+        assert!(global.init_terminal(first_line));
+        global.prepare_first_line();
+        global.interaction = BATCH_MODE;
+        global.fix_date_and_time();
+        global.sec75_initialize_print_selector();
+        global.history = SPOTLESS;
+        global.main_control().and_then(|()| global.final_cleanup()).unwrap();
+        global.close_files_and_terminate().unwrap();
+        file_system.remove("texput.fmt").expect("no format was dumped")
+    }
+
+    // Braces are the only category codes needed by the tests.
+    pub(crate) const MINIMAL_FORMAT: &str = "\\catcode`\\{=1 \\catcode`\\}=2 \\dump";
+
+    #[test]
+    fn compile_reports_output_and_errors() {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let options = CompileOptions {
+            format: &format,
+            job_name: "paper",
+            ..CompileOptions::default()
+        };
+        let output = compile("\\shipout\\hbox{\\vrule width 1pt height 2pt}\n\\undefined\n\\end\n", &options);
+
+        let dvi = output.dvi.expect("no DVI file");
+        assert_eq!(&dvi[..2], &[247, 2]); // pre, id_byte
+        assert!(output.log.contains("Output written on paper.dvi (1 page"));
+        assert!(output.log.contains("! Undefined control sequence."));
+        assert_eq!(output.history, ERROR_MESSAGE_ISSUED);
+        assert_eq!(output.errors.len(), 1);
+        assert_eq!(output.errors[0].message, "Undefined control sequence.");
+        assert_eq!(output.errors[0].line, Some(2));
+        assert!(output.pdf.is_none());
+    }
+
+    #[test]
+    fn compile_without_format() {
+        let options = CompileOptions {
+            format: &[],
+            ..CompileOptions::default()
+        };
+        let output = compile("\\end", &options);
+        assert_eq!(output.history, FATAL_ERROR_STOP);
+        assert_eq!(output.errors[0].message, "Sorry, I can't find that format.");
+        assert!(output.dvi.is_none());
+    }
+
+    #[test]
+    fn compile_with_too_small_capacities() {
+        let options = CompileOptions {
+            capacities: Capacities {
+                stack_size: 0,
+                ..Capacities::default()
+            },
+            ..CompileOptions::default()
+        };
+        let output = compile("\\end", &options);
+        assert_eq!(output.history, FATAL_ERROR_STOP);
+        assert_eq!(output.errors[0].message, "Ouch---my internal constants have been clobbered!---case 8");
+    }
+
+    #[test]
+    fn compile_with_bad_format() {
+        let mut format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        format.truncate(format.len() / 2);
+        for (format, reason) in [(&format[..], "corrupted"), (b"\\relax\\dump", "not a format file")] {
+            let options = CompileOptions {
+                format,
+                ..CompileOptions::default()
+            };
+            let output = compile("\\end", &options);
+            assert_eq!(output.history, FATAL_ERROR_STOP);
+            assert_eq!(output.errors.len(), 1);
+            assert_eq!(output.errors[0].message, format!("Fatal format file error: {reason}; I'm stymied"));
+            assert!(output.dvi.is_none());
+        }
+    }
+
+    #[test]
+    fn compile_with_file_line_error() {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let chapter: &[u8] = b"\\relax\n\\undefined\n";
+        let options = CompileOptions {
+            format: &format,
+            job_name: "paper",
+            file_line_error: true,
+            inputs: &[("chapter.tex", chapter), ("/abs/part.tex", chapter)],
+            ..CompileOptions::default()
+        };
+        let output = compile("\\input chapter\n\\input /abs/part\n\\undefined\n\\end\n", &options);
+        let lines: Vec<&str> = output.log.lines().filter(|line| line.contains(": Undefined")).collect();
+        assert_eq!(lines, [
+            "./chapter.tex:2: Undefined control sequence.",
+            "/abs/part.tex:2: Undefined control sequence.",
+            "./paper.tex:3: Undefined control sequence."
+        ]);
+        assert_eq!(output.errors[0].message, "Undefined control sequence.");
+    }
+
+    // Braces, math shifts, superscripts and macro parameters.
+    const RECOVERY_FORMAT: &str = "\\catcode`\\{=1 \\catcode`\\}=2 \\catcode`\\$=3 \\catcode`\\#=6 \\catcode`\\^=7 \\dump";
+
+    fn compile_with_recovery_format(source: &str) -> CompileOutput {
+        let format = dump_format(RECOVERY_FORMAT, &Capacities::default());
+        let options = CompileOptions {
+            format: &format,
+            ..CompileOptions::default()
+        };
+        compile(source, &options)
+    }
+
+    fn messages(output: &CompileOutput) -> Vec<&str> {
+        output.errors.iter().map(|error| error.message.as_str()).collect()
+    }
+
+    // TeX goes on after each error, and the page is still shipped out.
+    fn assert_recovered(output: &CompileOutput) {
+        assert_eq!(output.history, ERROR_MESSAGE_ISSUED);
+        assert!(output.dvi.is_some());
+        assert!(output.log.contains("Output written on texput.dvi (1 page"), "{}", output.log);
+    }
+
+    #[test]
+    fn recover_from_missing_dollar() {
+        // Without math fonts, the formula is then deleted.
+        let output = compile_with_recovery_format("\\shipout\\hbox{x^2$}\n\\end\n");
+        assert_eq!(messages(&output), ["Missing $ inserted.", "Math formula deleted: Insufficient symbol fonts."]);
+        assert!(output.log.contains("<inserted text> \n                $\n"), "{}", output.log);
+        assert_recovered(&output);
+    }
+
+    #[test]
+    fn recover_from_missing_number() {
+        let output = compile_with_recovery_format("\\count1=\\relax\\message{count1=\\the\\count1}\\shipout\\hbox{}\n\\end\n");
+        assert_eq!(messages(&output), ["Missing number, treated as zero."]);
+        assert_eq!(output.errors[0].help[0], "A number should have been here; I inserted '0'.");
+        assert!(output.log.contains("count1=0"));
+        assert_recovered(&output);
+    }
+
+    #[test]
+    fn recover_from_extra_right_brace() {
+        let output = compile_with_recovery_format("\\shipout\\hbox{$x}$}\n\\end\n");
+        assert_eq!(messages(&output), ["Extra }, or forgotten $.", "Math formula deleted: Insufficient symbol fonts."]);
+        assert_recovered(&output);
+    }
+
+    #[test]
+    fn recover_from_runaway_argument() {
+        let output = compile_with_recovery_format("\\def\\a#1{}\\a{x\n\n}\\shipout\\hbox{}\n\\end\n");
+        assert_eq!(messages(&output), ["Paragraph ended before \\a was complete.", "Too many }'s."]);
+        assert_eq!(output.errors[0].line, Some(2));
+        assert!(output.log.contains("Runaway argument?\n{x \n! Paragraph ended before \\a was complete."), "{}", output.log);
+        assert_recovered(&output);
+    }
+
+    #[test]
+    fn recover_from_forbidden_control_sequences() {
+        let output = compile_with_recovery_format(
+            "\\outer\\def\\foo{}\\def\\a{\\foo}\n\\iffalse\\foo\\fi\n\\shipout\\hbox{}\n\\end\n"
+        );
+        assert_eq!(
+            messages(&output),
+            [
+                "Forbidden control sequence found while scanning definition of \\a.",
+                "Too many }'s.",
+                "Incomplete \\iffalse; all text was ignored after line 2.",
+                "Extra \\fi."
+            ]
+        );
+        // The help is chosen with the control sequence given to the error.
+        assert!(output.log.contains("A forbidden control sequence occurred in skipped text."), "{}", output.log);
+        assert_recovered(&output);
+    }
+
+    #[test]
+    fn stop_after_100_errors() {
+        let output = compile_with_recovery_format("\\def\\x{\\undefined\\x}\\x\n\\end\n");
+        assert_eq!(output.history, FATAL_ERROR_STOP);
+        assert_eq!(output.errors.len(), 100);
+        assert!(output.errors.iter().all(|error| error.message == "Undefined control sequence."));
+        assert!(output.log.contains("(That makes 100 errors; please try again.)"), "{}", output.log);
+        assert!(output.dvi.is_none());
+    }
+}
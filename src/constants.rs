@@ -10,14 +10,18 @@ pub(crate) const EXT_DVI: StrNum = 259;
 pub(crate) const EXT_FMT: StrNum = 260;
 pub(crate) const EXT_TFM: StrNum = 261;
 
-pub(crate) const TEX_AREA: StrNum = 262;
-pub(crate) const TEX_FONT_AREA: StrNum = 263;
-
 pub(crate) const TEX_AREA_STRING: &str = "TeXinputs/";
 pub(crate) const TEX_FONT_AREA_STRING: &str = "TeXfonts/";
 pub(crate) const TEX_FORMAT_AREA: &str = "TeXformats/";
 pub(crate) const TEX_FORMAT_DEFAULT: &str = "plain.fmt";
 
+// The search paths when TEXINPUTS, TFMFONTS and TEXFORMATS are not set.
+pub(crate) const TEX_INPUTS_DEFAULT: &[&str] = &[".", TEX_AREA_STRING];
+pub(crate) const TFM_FONTS_DEFAULT: &[&str] = &[TEX_FONT_AREA_STRING];
+pub(crate) const TEX_FORMATS_DEFAULT: &[&str] = &[".", TEX_FORMAT_AREA];
+pub(crate) const TEX_TYPE1_AREA: &str = "TeXtype1/";
+pub(crate) const FONT_MAP_DEFAULT: &str = "pdftex.map";
+
 pub(crate) const FONT_STRING: StrNum = 264;
 pub(crate) const NOTEXPANDED_STRING: StrNum = 265;
 pub(crate) const NULLFONT_STRING: StrNum = 266;
@@ -25,34 +29,44 @@ pub(crate) const INACCESSIBLE_STRING: StrNum = 267;
 pub(crate) const INITEX_IDENT_STRING: StrNum = 268;
 pub(crate) const ENDWRITE_STRING: StrNum = 269;
 pub(crate) const ENDTEMPLATE_STRING: StrNum = 270;
+pub(crate) const EXT_PDF: StrNum = 271;
+pub(crate) const EXT_SVG: StrNum = 272;
 
 // Part 1: Introduction
 // Section 2
 pub(crate) const BANNER: &str = "This is TeX, Version 3.141592653";
 
 // Section 11
-pub(crate) const MEM_MAX: Integer = 30000;
+// The other constants of this section are chosen at runtime (see `Capacities`).
+// With the `trip` feature, the values required by the TRIP test.
+#[cfg(not(feature = "trip"))]
 pub(crate) const MEM_MIN: Integer = 0;
-pub(crate) const BUF_SIZE: Integer = 200_000; // 500 is not enough
+#[cfg(feature = "trip")]
+pub(crate) const MEM_MIN: Integer = 1;
+#[cfg(not(feature = "trip"))]
 pub(crate) const ERROR_LINE: Integer = 72;
+#[cfg(feature = "trip")]
+pub(crate) const ERROR_LINE: Integer = 64;
+#[cfg(not(feature = "trip"))]
 pub(crate) const HALF_ERROR_LINE: Integer = 36;
+#[cfg(feature = "trip")]
+pub(crate) const HALF_ERROR_LINE: Integer = 32;
+#[cfg(not(feature = "trip"))]
 pub(crate) const MAX_PRINT_LINE: Integer = 79;
-pub(crate) const STACK_SIZE: Integer = 200;
-pub(crate) const MAX_IN_OPEN: Integer = 6;
-pub(crate) const FONT_MAX: Integer = 75;
-pub(crate) const FONT_MEM_SIZE: Integer = 20000;
-pub(crate) const PARAM_SIZE: Integer = 60;
-pub(crate) const NEST_SIZE: Integer = 40;
-pub(crate) const MAX_STRINGS: Integer = 3000;
-pub(crate) const POOL_SIZE: Integer = 32000;
-pub(crate) const SAVE_SIZE: Integer = 600;
-pub(crate) const TRIE_SIZE: Integer = 8000;
+#[cfg(feature = "trip")]
+pub(crate) const MAX_PRINT_LINE: Integer = 72;
 pub(crate) const TRIE_OP_SIZE: Integer = 500;
 pub(crate) const DVI_BUF_SIZE: Integer = 800;
+// Ceilings of the arrays that grow when they are full, as `sup_` in web2c.
+pub(crate) const SUP_MAIN_MEMORY: Integer = 256_000_000;
+pub(crate) const SUP_POOL_SIZE: Integer = 40_000_000;
+pub(crate) const SUP_SAVE_SIZE: Integer = 80_000;
 
 // Section 12
+#[cfg(not(feature = "trip"))]
 pub(crate) const MEM_BOT: Integer = 0;
-pub(crate) const MEM_TOP: Integer = 30000;
+#[cfg(feature = "trip")]
+pub(crate) const MEM_BOT: Integer = 1;
 pub(crate) const FONT_BASE: Integer = 0;
 pub(crate) const HASH_SIZE: Integer = 2100;
 pub(crate) const HASH_PRIME: Integer = 1777;
@@ -78,14 +92,16 @@ pub(crate) const NEW_STRING: Integer = 21;
 
 // Part 6: Reporting errors
 // Section 73
-pub(crate) const BATCH_MODE: Integer = 0;
-pub(crate) const ERROR_STOP_MODE: Integer = 3;
+pub const BATCH_MODE: Integer = 0;
+pub const NONSTOP_MODE: Integer = 1;
+pub const SCROLL_MODE: Integer = 2;
+pub const ERROR_STOP_MODE: Integer = 3;
 
 // Section 76
 pub const SPOTLESS: usize = 0;
-pub(crate) const WARNING_ISSUED: usize = 1;
-// pub(crate) const ERROR_MESSAGE_ISSUED: usize = 2;
-// pub(crate) const FATAL_ERROR_STOP: usize = 3;
+pub const WARNING_ISSUED: usize = 1;
+pub const ERROR_MESSAGE_ISSUED: usize = 2;
+pub const FATAL_ERROR_STOP: usize = 3;
 
 // Part 7: Arithmetic with scaled dimensions
 // Section 101
@@ -188,21 +204,7 @@ pub(crate) const FILL_GLUE: Integer = FIL_GLUE + GLUE_SPEC_SIZE;
 pub(crate) const SS_GLUE: Integer = FILL_GLUE + GLUE_SPEC_SIZE;
 pub(crate) const FIL_NEG_GLUE: Integer = SS_GLUE + GLUE_SPEC_SIZE;
 pub(crate) const LO_MEM_STAT_MAX: Integer = FIL_NEG_GLUE + GLUE_SPEC_SIZE - 1;
-pub(crate) const PAGE_INS_HEAD: HalfWord = MEM_TOP;
-pub(crate) const CONTRIB_HEAD: HalfWord = MEM_TOP - 1;
-pub(crate) const PAGE_HEAD: HalfWord = MEM_TOP - 2;
-pub(crate) const TEMP_HEAD: HalfWord = MEM_TOP - 3;
-pub(crate) const HOLD_HEAD: HalfWord = MEM_TOP - 4;
-pub(crate) const ADJUST_HEAD: HalfWord = MEM_TOP - 5;
-pub(crate) const ACTIVE: HalfWord = MEM_TOP - 7;
-pub(crate) const ALIGN_HEAD: HalfWord = MEM_TOP - 8;
-pub(crate) const END_SPAN: HalfWord = MEM_TOP - 9;
-pub(crate) const OMIT_TEMPLATE: HalfWord = MEM_TOP - 10;
-pub(crate) const NULL_LIST: HalfWord = MEM_TOP - 11;
-pub(crate) const LIG_TRICK: Integer = MEM_TOP - 12;
-pub(crate) const GARBAGE: HalfWord = MEM_TOP - 12;
-pub(crate) const BACKUP_HEAD: HalfWord = MEM_TOP - 13;
-pub(crate) const HI_MEM_STAT_MIN: Integer = MEM_TOP - 13;
+// The locations at the top of `mem` depend on `mem_top` (see memory.rs).
 pub(crate) const HI_MEM_STAT_USAGE: Integer = 14;
 
 // Part 15: The command codes
@@ -554,7 +556,7 @@ pub(crate) const LEFT_BRACE_TOKEN: HalfWord = 0x100;
 pub(crate) const LEFT_BRACE_LIMIT: HalfWord = 0x200;
 pub(crate) const RIGHT_BRACE_TOKEN: HalfWord = 0x200;
 pub(crate) const RIGHT_BRACE_LIMIT: HalfWord = 0x300;
-// pub(crate) const MATH_SHIFT_TOKEN: HalfWord = 0x300;
+pub(crate) const MATH_SHIFT_TOKEN: HalfWord = 0x300;
 pub(crate) const TAB_TOKEN: HalfWord = 0x400;
 pub(crate) const OUT_PARAM_TOKEN: HalfWord = 0x500;
 pub(crate) const SPACE_TOKEN: HalfWord = 0xa20;
@@ -624,7 +626,7 @@ pub(crate) const POINT_TOKEN: HalfWord = OTHER_TOKEN + b'.' as HalfWord;
 pub(crate) const CONTINENTAL_POINT_TOKEN: HalfWord = OTHER_TOKEN + b',' as HalfWord;
 
 // Section 445
-// pub(crate) const INFINITY: Integer = 0x7fff_ffff;
+pub(crate) const INFINITY: Integer = 0x7fff_ffff;
 pub(crate) const ZERO_TOKEN: HalfWord = OTHER_TOKEN + b'0' as HalfWord;
 pub(crate) const A_TOKEN: HalfWord = LETTER_TOKEN + b'A' as HalfWord;
 pub(crate) const OTHER_A_TOKEN: HalfWord = OTHER_TOKEN + b'A' as HalfWord;
@@ -842,7 +844,6 @@ pub(crate) const DECENT_FIT: usize = 2;
 pub(crate) const ACTIVE_NODE_SIZE: Integer = 3;
 pub(crate) const UNHYPHENATED: QuarterWord = 0;
 pub(crate) const HYPHENATED: QuarterWord = 1;
-pub(crate) const LAST_ACTIVE: HalfWord = ACTIVE;
 
 // Section 821
 pub(crate) const PASSIVE_NODE_SIZE: Integer = 2;
@@ -914,6 +915,16 @@ pub(crate) const SHOW_BOX_CODE: HalfWord = 1;
 pub(crate) const SHOW_THE_CODE: HalfWord = 2;
 pub(crate) const SHOW_LISTS: HalfWord = 3;
 
+// Part 50: Dumping and undumping the tables
+// Section 1307
+// A format file starts with "TeXf" and the version of its layout.
+pub(crate) const FORMAT_MAGIC: Integer = i32::from_le_bytes(*b"TeXf");
+pub(crate) const FORMAT_VERSION: Integer = 1;
+// Features of the engine recorded in the format.
+pub(crate) const FORMAT_STAT: Integer = 1;
+pub(crate) const FORMAT_DEBUG: Integer = 2;
+pub(crate) const FORMAT_TRIP: Integer = 4;
+
 // Part 53: Extensions
 // Section 1341
 pub(crate) const WRITE_NODE_SIZE: Integer = 2;
@@ -5,70 +5,14 @@ mod modes;
 mod nodes;
 mod stack;
 
-pub use equivalent::end_line_char;
-pub(crate) use equivalent::{
-    eqtb, eqtb_mut, XEQ_LEVEL, eq_level, eq_type, equiv, count, glue_par,
-    new_line_char, new_line_char_mut, escape_char,
-    show_box_breadth, show_box_depth, error_context_lines,
-    year, month, day, time,
-    tracing_online, par_shape_ptr, cat_code,
-    floating_penalty, split_top_skip, split_max_depth,
-    looseness, box_max_depth, every_hbox, every_vbox,
-    hang_indent, hang_after, r#box, box_mut, left_hyphen_min,
-    right_hyphen_min, par_indent, every_par, cur_font, widow_penalty,
-    tracing_commands, every_job, language, space_skip, sf_code,
-    hsize, xspace_skip, math_code, global_defs,
-    display_widow_penalty, every_display, every_math, pre_display_penalty,
-    pre_display_size, display_indent, display_width, math_surround,
-    fam_fnt, cur_fam, del_code, post_display_penalty,
-    vsize, skip, holding_inserts, split_top_skip_mut,
-    vbadness, vbadness_mut, vfuzz_mut, vfuzz, max_dead_cycles, output_routine,
-    max_depth, dimen, null_delimiter_space, tracing_macros,
-    mu_skip, mag, every_cr, overfull_rule, overfull_rule_mut,
-    left_skip, right_skip, pretolerance, tolerance,
-    emergency_stretch, adj_demerits, double_hyphen_demerits, final_hyphen_demerits,
-    line_penalty, ex_hyphen_penalty, hyphen_penalty, inter_line_penalty,
-    club_penalty, broken_penalty, lc_code, uc_hyph, script_space,
-    bin_op_penalty, rel_penalty, delimiter_factor, delimiter_shortfall,
-    hbadness, hfuzz, baseline_skip, line_skip_limit, tracing_output, v_offset,
-    h_offset, default_hyphen_char, default_skew_char, tracing_lost_chars, pausing,
-    day_mut, month_mut, year_mut, time_mut, equiv_mut, eq_type_mut, eq_level_mut,
-    cat_code_mut, cur_font_mut,
-    del_code_mut, end_line_char_mut, escape_char_mut, hang_after_mut,
-    lc_code_mut, mag_mut, math_code_mut, max_dead_cycles_mut,
-    par_shape_ptr_mut, sf_code_mut, tolerance_mut, uc_code_mut,
-    tracing_stats_mut
-};
-
-#[cfg(feature = "stat")]
-pub(crate) use equivalent::{tracing_restores, tracing_stats, tracing_paragraphs, tracing_pages};
+pub(crate) use equivalent::{Eqtb, XeqLevel};
 
-pub(crate) use hash::{
-    hash, hash_mut, text, font_id_text, font_id_text_mut, next_mut, text_mut
-};
+pub(crate) use hash::Hash;
 
-pub(crate) use memory::{
-    MEM, mem, mem_mut, MemoryWord, info, info_mut, link, link_mut, llink, llink_mut,
-    node_size, node_size_mut, rlink, rlink_mut, token_ref_count_mut
-};
+pub(crate) use memory::MemoryWord;
 
 pub(crate) use modes::ListStateRecord;
 
-pub(crate) use nodes::{
-    adjust_ptr, adjust_ptr_mut, character, character_mut, depth, depth_mut,
-    float_cost, float_cost_mut, font, font_mut, glue_order, glue_order_mut,
-    glue_ptr, glue_ptr_mut, glue_ref_count, glue_ref_count_mut, glue_set,
-    glue_set_mut, glue_shrink, glue_shrink_mut, glue_sign, glue_sign_mut,
-    glue_stretch, glue_stretch_mut, height, height_mut, ins_ptr, ins_ptr_mut,
-    leader_ptr, leader_ptr_mut, lig_ptr, lig_ptr_mut, list_ptr, list_ptr_mut,
-    mark_ptr, mark_ptr_mut, penalty, penalty_mut, post_break, post_break_mut,
-    pre_break, pre_break_mut, r#type, replace_count, replace_count_mut,
-    shift_amount, shift_amount_mut, shrink, shrink_mut, shrink_order,
-    shrink_order_mut, span_count, span_count_mut, split_top_ptr,
-    split_top_ptr_mut, stretch, stretch_mut, stretch_order, stretch_order_mut,
-    subtype, subtype_mut, type_mut, width, width_mut
-};
-
 pub(crate) use stack::{
-    InputFile, InStateRecord, LineStack, Status, geq_word_define
+    InputFile, InStateRecord, LineStack, Status
 };
@@ -1,12 +1,6 @@
 use crate::constants::*;
-use crate::datastructures::{
-    MemoryWord, eq_level_mut, eq_type_mut, equiv_mut
-};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
-use crate::strings::{
-    str_pool, str_pool_mut, append_char, cur_length, length, make_string, pool_ptr, pool_ptr_set,
-    str_ptr, str_room, str_start
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, StrNum
 };
@@ -16,8 +10,16 @@ use std::ops::{Index, IndexMut};
 // Part 18: The hash table
 
 // Section 256
-pub(crate) struct Hash([MemoryWord; (UNDEFINED_CONTROL_SEQUENCE - HASH_BASE) as usize]);
-pub(crate) static mut HASH: Hash = Hash([MemoryWord::ZERO; (UNDEFINED_CONTROL_SEQUENCE - HASH_BASE) as usize]);
+// It goes up to `eqtb_top`: the locations after `EQTB_SIZE` are those
+// of `hash_extra`, and the ones from `UNDEFINED_CONTROL_SEQUENCE` to
+// `EQTB_SIZE` are not used.
+pub(crate) struct Hash(Vec<MemoryWord>);
+
+impl Hash {
+    pub(crate) fn new(hash_extra: Integer) -> Self {
+        Self(vec![MemoryWord::ZERO; (EQTB_SIZE + hash_extra - HASH_BASE + 1) as usize])
+    }
+}
 
 impl Index<usize> for Hash {
     type Output = MemoryWord;
@@ -32,29 +34,31 @@ impl IndexMut<usize> for Hash {
     }
 }
 
-pub(crate) fn hash(p: usize) -> MemoryWord {
-    unsafe { HASH[p] }
-}
+impl Global {
+    pub(crate) fn hash(&self, p: usize) -> MemoryWord {
+        self.hash[p]
+    }
 
-pub(crate) fn hash_mut(p: usize) -> &'static mut MemoryWord {
-    unsafe { &mut HASH[p] }
-}
+    pub(crate) fn hash_mut(&mut self, p: usize) -> &mut MemoryWord {
+        &mut self.hash[p]
+    }
 
-// Section 256
-fn next(p: HalfWord) -> HalfWord {
-    hash(p as usize).hh_lh()
-}
+    // Section 256
+    fn next(&self, p: HalfWord) -> HalfWord {
+        self.hash(p as usize).hh_lh()
+    }
 
-pub(crate) fn next_mut(p: HalfWord) -> &'static mut HalfWord {
-    hash_mut(p as usize).hh_lh_mut()
-}
+    pub(crate) fn next_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.hash_mut(p as usize).hh_lh_mut()
+    }
 
-pub(crate) fn text(p: HalfWord) -> HalfWord {
-    hash(p as usize).hh_rh()
-}
+    pub(crate) fn text(&self, p: HalfWord) -> HalfWord {
+        self.hash(p as usize).hh_rh()
+    }
 
-pub(crate) fn text_mut(p: HalfWord) -> &'static mut HalfWord {
-    hash_mut(p as usize).hh_rh_mut()
+    pub(crate) fn text_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.hash_mut(p as usize).hh_rh_mut()
+    }
 }
 
 impl Global {
@@ -63,12 +67,14 @@ impl Global {
     }
 }
 
-pub(crate) fn font_id_text(p: QuarterWord) -> HalfWord {
-    text(FONT_ID_BASE + p as HalfWord)
-}
+impl Global {
+    pub(crate) fn font_id_text(&self, p: QuarterWord) -> HalfWord {
+        self.text(FONT_ID_BASE + p as HalfWord)
+    }
 
-pub(crate) fn font_id_text_mut(p: QuarterWord) -> &'static mut HalfWord {
-    text_mut(FONT_ID_BASE + p as HalfWord)
+    pub(crate) fn font_id_text_mut(&mut self, p: QuarterWord) -> &mut HalfWord {
+        self.text_mut(FONT_ID_BASE + p as HalfWord)
+    }
 }
 
 impl Global {
@@ -82,49 +88,57 @@ impl Global {
         // End section 261
         let mut p = h + HASH_BASE;
         loop {
-            if text(p) > 0
-               && length(text(p) as StrNum) == l 
-               && self.str_eq_buf(text(p) as StrNum, j)
+            if self.text(p) > 0
+               && self.length(self.text(p) as StrNum) == l 
+               && self.str_eq_buf(self.text(p) as StrNum, j)
             {
                 break; // Goto found
             }
-            if next(p) == 0 {
+            if self.next(p) == 0 {
                 if self.no_new_control_sequence {
                     p = UNDEFINED_CONTROL_SEQUENCE;
                 }
                 else {
                     // Section 260
-                    if text(p) > 0 {
-                        loop {
-                            if self.hash_is_full() {
-                                return Err(TeXError::Overflow("hash size", HASH_SIZE));
-                            }
-                            self.hash_used -= 1;
-                            if text(self.hash_used) == 0 {
-                                break;
+                    if self.text(p) > 0 {
+                        // The locations of `hash_extra` are used first.
+                        if self.hash_high < self.hash_extra {
+                            self.hash_high += 1;
+                            *self.next_mut(p) = EQTB_SIZE + self.hash_high;
+                            p = EQTB_SIZE + self.hash_high;
+                        }
+                        else {
+                            loop {
+                                if self.hash_is_full() {
+                                    return Err(TeXError::Overflow("hash size", HASH_SIZE + self.hash_extra));
+                                }
+                                self.hash_used -= 1;
+                                if self.text(self.hash_used) == 0 {
+                                    break;
+                                }
                             }
+                            *self.next_mut(p) = self.hash_used;
+                            p = self.hash_used;
                         }
-                        *next_mut(p) = self.hash_used;
-                        p = self.hash_used;
                     }
-                    str_room(l)?;
-                    let d = cur_length();
-                    while pool_ptr() > str_start(str_ptr()) {
-                        pool_ptr_set(pool_ptr() - 1);
-                        *str_pool_mut(pool_ptr() + l) = str_pool(pool_ptr());
+                    self.str_room(l)?;
+                    let d = self.cur_length();
+                    while self.pool_ptr() > self.str_start(self.str_ptr()) {
+                        self.pool_ptr_set(self.pool_ptr() - 1);
+                        *self.str_pool_mut(self.pool_ptr() + l) = self.str_pool(self.pool_ptr());
                     }
                     for k in j..(j + l) {
-                        append_char(self.buffer[k]);
+                        self.append_char(self.buffer[k]);
                     }
-                    *text_mut(p) = make_string()? as HalfWord;
-                    pool_ptr_set(pool_ptr() + d);
+                    *self.text_mut(p) = self.make_string()? as HalfWord;
+                    self.pool_ptr_set(self.pool_ptr() + d);
                     #[cfg(feature = "stat")]
                     { self.cs_count += 1; }
                     // End section 260
                 }
                 break; // Goto found
             }
-            p = next(p);
+            p = self.next(p);
         }
         // found:
         Ok(p)
@@ -143,9 +157,9 @@ impl Global {
             // and only insertion in the pool. 
             self.id_lookup(0, s.len())?
         };
-        *eq_level_mut(self.cur_val) = LEVEL_ONE;
-        *eq_type_mut(self.cur_val) = c;
-        *equiv_mut(self.cur_val) = o;
+        *self.eq_level_mut(self.cur_val) = LEVEL_ONE;
+        *self.eq_type_mut(self.cur_val) = c;
+        *self.equiv_mut(self.cur_val) = o;
 
         Ok(())
     }
@@ -1,22 +1,11 @@
+use crate::capacities::grown_size;
 use crate::constants::*;
-use crate::datastructures::{
-    adjust_ptr, adjust_ptr_mut, glue_ptr, glue_ptr_mut, glue_ref_count,
-    glue_ref_count_mut, ins_ptr, ins_ptr_mut, leader_ptr, leader_ptr_mut,
-    lig_ptr, lig_ptr_mut, list_ptr, list_ptr_mut, mark_ptr, post_break,
-    post_break_mut, pre_break, pre_break_mut, r#type, split_top_ptr, subtype
-};
 use crate::error::{TeXError, TeXResult};
-use crate::extensions::write_tokens;
-use crate::math::{
-    display_mlist, math_type, script_mlist, script_script_mlist, text_mlist
-};
 use crate::{
     Global, GlueRatio, HalfWord, Integer, QuarterWord, Scaled,
     denominator, lig_char, nucleus, numerator, subscr, supscr
 };
 
-#[cfg(feature = "debug")]
-use crate::datastructures::equiv;
 
 // Part 8: Packed data
 
@@ -149,31 +138,176 @@ impl MemoryWord {
 
 // Part 9: Dynamic memory allocation
 
-pub(crate) static mut MEM: [MemoryWord; (MEM_MAX - MEM_MIN + 1) as usize] = [MemoryWord::ZERO; (MEM_MAX - MEM_MIN + 1) as usize];
+impl Global {
+    // Section 162
+    // The static locations at the top of `mem`.
+    pub(crate) fn page_ins_head(&self) -> HalfWord {
+        self.mem_top
+    }
 
-pub(crate) fn mem(p: usize) -> MemoryWord {
-    unsafe { MEM[p] }
-}
+    pub(crate) fn contrib_head(&self) -> HalfWord {
+        self.mem_top - 1
+    }
 
-pub(crate) fn mem_mut(p: usize) -> &'static mut MemoryWord {
-    unsafe { &mut MEM[p] }
-}
+    pub(crate) fn page_head(&self) -> HalfWord {
+        self.mem_top - 2
+    }
 
-// Section 118
-pub(crate) fn link(p: HalfWord) -> HalfWord {
-    mem(p as usize).hh_rh()
-}
+    pub(crate) fn temp_head(&self) -> HalfWord {
+        self.mem_top - 3
+    }
 
-pub(crate) fn link_mut(p: HalfWord) -> &'static mut HalfWord {
-    mem_mut(p as usize).hh_rh_mut()
-}
+    pub(crate) fn hold_head(&self) -> HalfWord {
+        self.mem_top - 4
+    }
 
-pub(crate) fn info(p: HalfWord) -> HalfWord {
-    mem(p as usize).hh_lh()
-}
+    pub(crate) fn adjust_head(&self) -> HalfWord {
+        self.mem_top - 5
+    }
+
+    pub(crate) fn active(&self) -> HalfWord {
+        self.mem_top - 7
+    }
+
+    pub(crate) fn align_head(&self) -> HalfWord {
+        self.mem_top - 8
+    }
+
+    pub(crate) fn end_span(&self) -> HalfWord {
+        self.mem_top - 9
+    }
+
+    pub(crate) fn omit_template(&self) -> HalfWord {
+        self.mem_top - 10
+    }
+
+    pub(crate) fn null_list(&self) -> HalfWord {
+        self.mem_top - 11
+    }
+
+    pub(crate) fn lig_trick(&self) -> HalfWord {
+        self.mem_top - 12
+    }
+
+    pub(crate) fn garbage(&self) -> HalfWord {
+        self.mem_top - 12
+    }
+
+    pub(crate) fn backup_head(&self) -> HalfWord {
+        self.mem_top - 13
+    }
+
+    pub(crate) fn hi_mem_stat_min(&self) -> HalfWord {
+        self.mem_top - 13
+    }
+
+    // `mem` is reallocated for a format built with another `mem_top`.
+    // Production runs have `extra_mem_top` more words for one-word nodes.
+    pub(crate) fn set_mem_top(&mut self, mem_top: Integer) {
+        self.mem_top = mem_top;
+        self.mem_max = if self.initex_mode {
+            mem_top
+        }
+        else {
+            mem_top + self.extra_mem_top
+        };
+        self.resize_mem();
+        #[cfg(feature = "debug")]
+        { self.was_hi_min = self.mem_max; }
+        // Sections 215 and 991 were done with the previous `mem_top`.
+        *self.head_mut() = self.contrib_head();
+        *self.tail_mut() = self.contrib_head();
+        self.page_tail = self.page_head();
+    }
+
+    fn resize_mem(&mut self) {
+        let size = (self.mem_max - self.mem_min + 1) as usize;
+        self.mem.resize(size, MemoryWord::ZERO);
+        #[cfg(feature = "debug")]
+        {
+            self.free.resize(size, false);
+            self.was_free.resize(size, false);
+        }
+    }
+
+    // In production runs, one-word nodes can be added above `mem_max`
+    // as in Section 120, so `mem` grows up to `SUP_MAIN_MEMORY` words.
+    // INITEX cannot grow: a format ends at `mem_top`.
+    fn grow_mem(&mut self) -> bool {
+        if self.initex_mode {
+            return false;
+        }
+        let size = self.mem_max - self.mem_min + 1;
+        match grown_size(size, size + 1, SUP_MAIN_MEMORY) {
+            Some(size) => {
+                self.mem_max = self.mem_min + size - 1;
+                self.resize_mem();
+                true
+            },
+            None => false
+        }
+    }
+
+    // Variable-size nodes are added below `mem_min`, as with `extra_mem_bot`
+    // in web2c, so that no pointer changes. The new words form a free block
+    // of at least `s + 2` words, linked into the `rover` ring as in Section 126.
+    // The lowest word is not used. INITEX cannot grow: a format starts at `MEM_BOT`.
+    fn grow_mem_bot(&mut self, s: Integer) -> bool {
+        if self.initex_mode {
+            return false;
+        }
+        let size = self.mem_max - self.mem_min + 1;
+        let Some(new_size) = grown_size(size, size + s + 3, SUP_MAIN_MEMORY) else {
+            return false;
+        };
+        let added = (new_size - size) as usize;
+        let old_mem_min = self.mem_min;
+        self.mem_min -= added as Integer;
+        self.mem.splice(0..0, std::iter::repeat_n(MemoryWord::ZERO, added));
+        #[cfg(feature = "debug")]
+        {
+            self.free.splice(0..0, std::iter::repeat_n(false, added));
+            self.was_free.splice(0..0, std::iter::repeat_n(false, added));
+        }
+        *self.link_mut(self.mem_min) = NULL;
+        *self.info_mut(self.mem_min) = NULL;
+        let p = self.llink(self.rover);
+        let q = self.mem_min + 1;
+        *self.rlink_mut(p) = q;
+        *self.llink_mut(self.rover) = q;
+        *self.rlink_mut(q) = self.rover;
+        *self.llink_mut(q) = p;
+        *self.link_mut(q) = EMPTY_FLAG;
+        *self.node_size_mut(q) = old_mem_min - q;
+        self.rover = q;
+        true
+    }
+
+    // `mem` is indexed from `mem_min`.
+    pub(crate) fn mem(&self, p: usize) -> MemoryWord {
+        self.mem[(p as Integer - self.mem_min) as usize]
+    }
+
+    pub(crate) fn mem_mut(&mut self, p: usize) -> &mut MemoryWord {
+        &mut self.mem[(p as Integer - self.mem_min) as usize]
+    }
+
+    // Section 118
+    pub(crate) fn link(&self, p: HalfWord) -> HalfWord {
+        self.mem(p as usize).hh_rh()
+    }
 
-pub(crate) fn info_mut(p: HalfWord) -> &'static mut HalfWord {
-    mem_mut(p as usize).hh_lh_mut()
+    pub(crate) fn link_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.mem_mut(p as usize).hh_rh_mut()
+    }
+
+    pub(crate) fn info(&self, p: HalfWord) -> HalfWord {
+        self.mem(p as usize).hh_lh()
+    }
+
+    pub(crate) fn info_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.mem_mut(p as usize).hh_lh_mut()
+    }
 }
 
 impl Global {
@@ -182,7 +316,7 @@ impl Global {
         let mut p = self.avail;
         match p {
             NULL => {
-                if self.mem_end < MEM_MAX {
+                if self.mem_end < self.mem_max || self.grow_mem() {
                     self.mem_end += 1;
                     p = self.mem_end;
                 }
@@ -191,14 +325,14 @@ impl Global {
                     p = self.hi_mem_min;
                     if self.hi_mem_min <= self.lo_mem_max {
                         self.runaway();
-                        return Err(TeXError::Overflow("main memory size", MEM_MAX + 1 - MEM_MIN))
+                        return Err(TeXError::Overflow("main memory size", self.mem_max + 1 - self.mem_min))
                     }
                 }
             }
-            _ => self.avail = link(self.avail),
+            _ => self.avail = self.link(self.avail),
         }
 
-        *link_mut(p) = NULL;
+        *self.link_mut(p) = NULL;
         #[cfg(feature = "stat")]
         { self.dyn_used += 1; }
         Ok(p)
@@ -209,7 +343,7 @@ impl Global {
 #[macro_export]
 macro_rules! free_avail {
     ($s:ident, $p:expr) => {
-        *link_mut($p) = $s.avail;
+        *$s.link_mut($p) = $s.avail;
         $s.avail = $p;
         #[cfg(feature = "stat")]
         { $s.dyn_used -= 1; }
@@ -225,8 +359,8 @@ macro_rules! fast_get_avail {
             $p = $s.get_avail()?;
         }
         else {
-            $s.avail = link($p);
-            *link_mut($p) = NULL;
+            $s.avail = $s.link($p);
+            *$s.link_mut($p) = NULL;
             #[cfg(feature = "stat")]
             { $s.dyn_used += 1; }
         }
@@ -240,14 +374,14 @@ impl Global {
             let mut r = p;
             let q = loop {
                 let q = r;
-                r = link(r);
+                r = self.link(r);
                 #[cfg(feature = "stat")]
                 { self.dyn_used -= 1; }
                 if r == NULL {
                     break q; // Last node on the list
                 }
             };
-            *link_mut(q) = self.avail;
+            *self.link_mut(q) = self.avail;
             self.avail = p;
         }
     }
@@ -255,33 +389,35 @@ impl Global {
 
 // Section 124
 macro_rules! is_empty {
-    ($p:expr) => {
-        link($p) == EMPTY_FLAG    
+    ($s:ident, $p:expr) => {
+        $s.link($p) == EMPTY_FLAG    
     };
 }
 
-pub(crate) fn node_size(p: HalfWord) -> HalfWord {
-    info(p)
-}
+impl Global {
+    pub(crate) fn node_size(&self, p: HalfWord) -> HalfWord {
+        self.info(p)
+    }
 
-pub(crate) fn node_size_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p)
-}
+    pub(crate) fn node_size_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p)
+    }
 
-pub(crate) fn llink(p: HalfWord) -> HalfWord {
-    info(p + 1)
-}
+    pub(crate) fn llink(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 1)
+    }
 
-pub(crate) fn llink_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 1)
-}
+    pub(crate) fn llink_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 1)
+    }
 
-pub(crate) fn rlink(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+    pub(crate) fn rlink(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-pub(crate) fn rlink_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
+    pub(crate) fn rlink_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 }
 
 impl Global {
@@ -294,12 +430,12 @@ impl Global {
             loop {
                 if let Some(node) = self.sec127_try_to_allocate(p, s) {
                     // found:
-                    *link_mut(node) = NULL;
+                    *self.link_mut(node) = NULL;
                     #[cfg(feature = "stat")]
                     { self.var_used += s; }
                     return Ok(node);
                 }
-                p = rlink(p);
+                p = self.rlink(p);
                 if p == self.rover {
                     break;
                 }
@@ -313,8 +449,8 @@ impl Global {
             {
                 self.sec126_grow_more();
             }
-            else {
-                return Err(TeXError::Overflow("main memory size", MEM_MAX + 1 - MEM_MIN));
+            else if !self.grow_mem_bot(s) {
+                return Err(TeXError::Overflow("main memory size", self.mem_max + 1 - self.mem_min));
             }
         }
     }
@@ -327,67 +463,67 @@ impl Global {
         else {
             self.lo_mem_max + 1 + (self.hi_mem_min - self.lo_mem_max) / 2
         };
-        let p = llink(self.rover);
+        let p = self.llink(self.rover);
         let q = self.lo_mem_max;
-        *rlink_mut(p) = q;
-        *llink_mut(self.rover) = q;
+        *self.rlink_mut(p) = q;
+        *self.llink_mut(self.rover) = q;
 
         if t > MEM_BOT + MAX_HALFWORD {
             t = MEM_BOT + MAX_HALFWORD;
         }
-        *rlink_mut(q) = self.rover;
-        *llink_mut(q) = p;
-        *link_mut(q) = EMPTY_FLAG;
-        *node_size_mut(q) = t - self.lo_mem_max;
+        *self.rlink_mut(q) = self.rover;
+        *self.llink_mut(q) = p;
+        *self.link_mut(q) = EMPTY_FLAG;
+        *self.node_size_mut(q) = t - self.lo_mem_max;
         self.lo_mem_max = t;
-        *link_mut(self.lo_mem_max) = NULL;
-        *info_mut(self.lo_mem_max) = NULL;
+        *self.link_mut(self.lo_mem_max) = NULL;
+        *self.info_mut(self.lo_mem_max) = NULL;
         self.rover = q;
         // goto restart
     }
 
     // Section 127
     fn sec127_try_to_allocate(&mut self, p: HalfWord, s: Integer) -> Option<HalfWord> {
-        let mut q = p + node_size(p);
-        while is_empty!(q) {
-            let t = rlink(q);
+        let mut q = p + self.node_size(p);
+        while is_empty!(self, q) {
+            let t = self.rlink(q);
             if q == self.rover {
                 self.rover = t;
             }
-            *llink_mut(t) = llink(q);
-            *rlink_mut(llink(q)) = t;
-            q += node_size(q);
+            *self.llink_mut(t) = self.llink(q);
+            *self.rlink_mut(self.llink(q)) = t;
+            q += self.node_size(q);
         }
         let r = q - s;
         if r > p + 1 {
             // Section 128
-            *node_size_mut(p) = r - p;
+            *self.node_size_mut(p) = r - p;
             self.rover = p;
             return Some(r);
             // End section 128
         }
-        if r == p && rlink(p) != p {
+        if r == p && self.rlink(p) != p {
             // Section 129
-            self.rover = rlink(p);
-            let t = llink(p);
-            *llink_mut(self.rover) = t;
-            *rlink_mut(t) = self.rover;
+            self.rover = self.rlink(p);
+            let t = self.llink(p);
+            *self.llink_mut(self.rover) = t;
+            *self.rlink_mut(t) = self.rover;
             return Some(r);
             // End section 129
         }
-        *node_size_mut(p) = q - p;
+        *self.node_size_mut(p) = q - p;
         None
     }
 
     // Section 130
     pub(crate) fn free_node(&mut self, p: HalfWord, s: HalfWord) {
-        *node_size_mut(p) = s;
-        *link_mut(p) = EMPTY_FLAG;
-        let q = llink(self.rover);
-        *llink_mut(p) = q;
-        *rlink_mut(p) = self.rover;
-        *llink_mut(self.rover) = p;
-        *rlink_mut(q) = p;
+        *self.node_size_mut(p) = s;
+        *self.link_mut(p) = EMPTY_FLAG;
+        let q = self.llink(self.rover);
+        *self.llink_mut(p) = q;
+        *self.rlink_mut(p) = self.rover;
+        *self.llink_mut(self.rover) = p;
+        *self.rlink_mut(q) = p;
         #[cfg(feature = "stat")]
         { self.var_used -= s; }
     }
@@ -395,36 +531,36 @@ impl Global {
     // Section 131
     pub(crate) fn sort_avail(&mut self) -> TeXResult<()> {
         let _ = self.get_node(0x4000_0000)?;
-        let mut p = rlink(self.rover);
-        *rlink_mut(self.rover) = MAX_HALFWORD;
+        let mut p = self.rlink(self.rover);
+        *self.rlink_mut(self.rover) = MAX_HALFWORD;
         let old_rover = self.rover;
         while p != old_rover {
             // Section 132
             if p < self.rover {
                 let q = p;
-                p = rlink(q);
-                *rlink_mut(q) = self.rover;
+                p = self.rlink(q);
+                *self.rlink_mut(q) = self.rover;
                 self.rover = q;
             }
             else {
                 let mut q = self.rover;
-                while rlink(q) < p {
-                    q = rlink(q);
+                while self.rlink(q) < p {
+                    q = self.rlink(q);
                 }
-                let r = rlink(p);
-                *rlink_mut(p) = rlink(q);
-                *rlink_mut(q) = p;
+                let r = self.rlink(p);
+                *self.rlink_mut(p) = self.rlink(q);
+                *self.rlink_mut(q) = p;
                 p = r;
             }
             // End secction 132
         }
         p = self.rover;
-        while rlink(p) != MAX_HALFWORD {
-            *llink_mut(rlink(p)) = p;
-            p = rlink(p);
+        while self.rlink(p) != MAX_HALFWORD {
+            *self.llink_mut(self.rlink(p)) = p;
+            p = self.rlink(p);
         }
-        *rlink_mut(p) = self.rover;
-        *llink_mut(self.rover) = p;
+        *self.rlink_mut(p) = self.rover;
+        *self.llink_mut(self.rover) = p;
         Ok(())
     }
 }
@@ -435,11 +571,11 @@ impl Global {
 impl Global {
     // Section 167
     pub(crate) fn check_mem(&mut self, print_locs: bool) {
-        for p in MEM_MIN..=self.lo_mem_max {
-            self.free[p as usize] = false;
+        for p in self.mem_min..=self.lo_mem_max {
+            self.free[(p - self.mem_min) as usize] = false;
         }
         for p in self.hi_mem_min..=self.mem_end {
-            self.free[p as usize] = false;
+            self.free[(p - self.mem_min) as usize] = false;
         }
         self.sec168_check_single_word_avail_list();
         self.sec169_check_variable_size_avail_list();
@@ -447,11 +583,11 @@ impl Global {
         if print_locs {
             self.sec171_print_newly_busy_locations();
         }
-        for p in MEM_MIN..=self.lo_mem_max {
-            self.was_free[p as usize] = self.free[p as usize];
+        for p in self.mem_min..=self.lo_mem_max {
+            self.was_free[(p - self.mem_min) as usize] = self.free[(p - self.mem_min) as usize];
         }
         for p in self.hi_mem_min..=self.mem_end {
-            self.was_free[p as usize] = self.free[p as usize];
+            self.was_free[(p - self.mem_min) as usize] = self.free[(p - self.mem_min) as usize];
         }
         self.was_mem_end = self.mem_end;
         self.was_lo_max = self.lo_mem_max;
@@ -463,14 +599,14 @@ impl Global {
         let mut p = self.avail;
         let mut q = NULL;
         while p != NULL {
-            if p > self.mem_end || p < self.hi_mem_min || self.free[p as usize] {
+            if p > self.mem_end || p < self.hi_mem_min || self.free[(p - self.mem_min) as usize] {
                 self.print_nl("AVAIL list clobbered at ");
                 self.print_int(q);
                 break; // Goto done1
             }
-            self.free[p as usize] = true;
+            self.free[(p - self.mem_min) as usize] = true;
             q = p;
-            p = link(q);
+            p = self.link(q);
         }
         // done1:
     }
@@ -481,28 +617,28 @@ impl Global {
         let mut q = NULL;
         'outer: loop {
             if p >= self.lo_mem_max
-                || p < MEM_MIN
-                || rlink(p) >= self.lo_mem_max
-                || rlink(p) < MEM_MIN
-                || !is_empty!(p)
-                || node_size(p) < 2
-                || p + node_size(p) > self.lo_mem_max
-                || llink(rlink(p)) != p
+                || p < self.mem_min
+                || self.rlink(p) >= self.lo_mem_max
+                || self.rlink(p) < self.mem_min
+                || !is_empty!(self, p)
+                || self.node_size(p) < 2
+                || p + self.node_size(p) > self.lo_mem_max
+                || self.llink(self.rlink(p)) != p
             {
                 self.print_nl("Double-AVAIL list clobbered at ");
                 self.print_int(q);
                 break 'outer; // Goto done2
             }
-            for q in p..(p + node_size(p)) {
-                if self.free[q as usize] {
+            for q in p..(p + self.node_size(p)) {
+                if self.free[(q - self.mem_min) as usize] {
                     self.print_nl("Doubly free location at ");
                     self.print_int(q);
                     break 'outer; // Goto done2
                 }
-                self.free[q as usize] = true;
+                self.free[(q - self.mem_min) as usize] = true;
             }
             q = p;
-            p = rlink(p);
+            p = self.rlink(p);
             if p == self.rover {
                 break 'outer;
             }
@@ -512,16 +648,16 @@ impl Global {
     
     // Section 170
     fn sec170_check_flags_of_unavailable_nodes(&mut self) {
-        let mut p = MEM_MIN;
+        let mut p = self.mem_min;
         while p <= self.lo_mem_max {
-            if is_empty!(p) {
+            if is_empty!(self, p) {
                 self.print_nl("Bad flag at ");
                 self.print_int(p);
             }
-            while p <= self.lo_mem_max && !self.free[p as usize] {
+            while p <= self.lo_mem_max && !self.free[(p - self.mem_min) as usize] {
                 p += 1;
             }
-            while p <= self.lo_mem_max && self.free[p as usize] {
+            while p <= self.lo_mem_max && self.free[(p - self.mem_min) as usize] {
                 p += 1;
             }
         }
@@ -530,15 +666,15 @@ impl Global {
     // Section 171
     fn sec171_print_newly_busy_locations(&mut self) {
         self.print_nl("New busy locs:");
-        for p in MEM_MIN..=self.lo_mem_max {
-            if !self.free[p as usize] && (p > self.was_lo_max || self.was_free[p as usize]) {
+        for p in self.mem_min..=self.lo_mem_max {
+            if !self.free[(p - self.mem_min) as usize] && (p > self.was_lo_max || self.was_free[(p - self.mem_min) as usize]) {
                 self.print_char(b' ');
                 self.print_int(p);
             }
         }
         for p in self.hi_mem_min..=self.mem_end {
-            if !self.free[p as usize]
-                && (p < self.was_hi_min || p > self.was_mem_end || self.was_free[p as usize])
+            if !self.free[(p - self.mem_min) as usize]
+                && (p < self.was_hi_min || p > self.was_mem_end || self.was_free[(p - self.mem_min) as usize])
             {
                 self.print_char(b' ');
                 self.print_int(p);
@@ -549,25 +685,25 @@ impl Global {
     // Section 172
     #[cfg(feature = "debug")]
     pub(crate) fn search_mem(&mut self, p: HalfWord) {
-        for q in MEM_MIN..=self.lo_mem_max {
-            if link(q) == p {
+        for q in self.mem_min..=self.lo_mem_max {
+            if self.link(q) == p {
                 self.print_nl("LINK(");
                 self.print_int(q);
                 self.print_char(b')');
             }
-            if info(q) == p {
+            if self.info(q) == p {
                 self.print_nl("INFO(");
                 self.print_int(q);
                 self.print_char(b')');
             }
         }
         for q in self.hi_mem_min..=self.mem_end {
-            if link(q) == p {
+            if self.link(q) == p {
                 self.print_nl("LINK(");
                 self.print_int(q);
                 self.print_char(b')');
             }
-            if info(q) == p {
+            if self.info(q) == p {
                 self.print_nl("INFO(");
                 self.print_int(q);
                 self.print_char(b')');
@@ -576,7 +712,7 @@ impl Global {
 
         // Section 255
         for q in ACTIVE_BASE..=(BOX_BASE + 255) {
-            if equiv(q) == p {
+            if self.equiv(q) == p {
                 self.print_nl("EQUIV(");
                 self.print_int(q);
                 self.print_char(b')');
@@ -610,22 +746,24 @@ impl Global {
 
 // Part 13: Destroying boxes
 
-// Section 200
-fn token_ref_count(p: HalfWord) -> HalfWord {
-    info(p)
-}
+impl Global {
+    // Section 200
+    fn token_ref_count(&self, p: HalfWord) -> HalfWord {
+        self.info(p)
+    }
 
-pub(crate) fn token_ref_count_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p)
+    pub(crate) fn token_ref_count_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p)
+    }
 }
 
 impl Global {
     pub(crate) fn delete_token_ref(&mut self, p: HalfWord) {
-        if token_ref_count(p) == NULL {
+        if self.token_ref_count(p) == NULL {
             self.flush_list(p);
         }
         else {
-            *token_ref_count_mut(p) -= 1;
+            *self.token_ref_count_mut(p) -= 1;
         }
     }
 }
@@ -633,11 +771,11 @@ impl Global {
 // Section 201
 macro_rules! fast_delete_glue_ref {
     ($s:ident, $p:expr) => {
-        if glue_ref_count($p) == NULL {
+        if $s.glue_ref_count($p) == NULL {
             $s.free_node($p, GLUE_SPEC_SIZE);
         }
         else {
-            *glue_ref_count_mut($p) -= 1;
+            *$s.glue_ref_count_mut($p) -= 1;
         }
     };
 }
@@ -651,17 +789,17 @@ impl Global {
     // Section 202
     pub(crate) fn flush_node_list(&mut self, mut p: HalfWord) -> TeXResult<()> {
         while p != NULL {
-            let q = link(p);
+            let q = self.link(p);
             if self.is_char_node(p) {
                 free_avail!(self, p);
             }
             else {
                 let mut done = false;
-                match r#type(p) {
+                match self.r#type(p) {
                     HLIST_NODE
                     | VLIST_NODE
                     | UNSET_NODE => {
-                        self.flush_node_list(list_ptr(p))?;
+                        self.flush_node_list(self.list_ptr(p))?;
                         self.free_node(p, BOX_NODE_SIZE);
                         done = true;
                     },
@@ -672,20 +810,20 @@ impl Global {
                     },
 
                     INS_NODE => {
-                        self.flush_node_list(ins_ptr(p))?;
-                        self.delete_glue_ref(split_top_ptr(p));
+                        self.flush_node_list(self.ins_ptr(p))?;
+                        self.delete_glue_ref(self.split_top_ptr(p));
                         self.free_node(p, INS_NODE_SIZE);
                         done = true;
                     },
 
                     WHATSIT_NODE => {
                         // Section 1358
-                        match subtype(p) as Integer {
+                        match self.subtype(p) as Integer {
                             OPEN_NODE => self.free_node(p, OPEN_NODE_SIZE),
 
                             WRITE_NODE
                             | SPECIAL_NODE => {
-                                self.delete_token_ref(write_tokens(p));
+                                self.delete_token_ref(self.write_tokens(p));
                                 self.free_node(p, WRITE_NODE_SIZE);
                             },
 
@@ -699,9 +837,9 @@ impl Global {
                     },
 
                     GLUE_NODE => {
-                        fast_delete_glue_ref!(self, glue_ptr(p));
-                        if leader_ptr(p) != NULL {
-                            self.flush_node_list(leader_ptr(p))?;
+                        fast_delete_glue_ref!(self, self.glue_ptr(p));
+                        if self.leader_ptr(p) != NULL {
+                            self.flush_node_list(self.leader_ptr(p))?;
                         }
                     },
 
@@ -709,16 +847,16 @@ impl Global {
                     | MATH_NODE
                     | PENALTY_NODE => (),
 
-                    LIGATURE_NODE => self.flush_node_list(lig_ptr(p))?,
+                    LIGATURE_NODE => self.flush_node_list(self.lig_ptr(p))?,
 
-                    MARK_NODE => self.delete_token_ref(mark_ptr(p)),
+                    MARK_NODE => self.delete_token_ref(self.mark_ptr(p)),
 
                     DISC_NODE => {
-                        self.flush_node_list(pre_break(p))?;
-                        self.flush_node_list(post_break(p))?;
+                        self.flush_node_list(self.pre_break(p))?;
+                        self.flush_node_list(self.post_break(p))?;
                     }
 
-                    ADJUST_NODE => self.flush_node_list(adjust_ptr(p))?,
+                    ADJUST_NODE => self.flush_node_list(self.adjust_ptr(p))?,
 
                     // Section 698
                     STYLE_NODE => {
@@ -727,10 +865,10 @@ impl Global {
                     },
 
                     CHOICE_NODE => {
-                        self.flush_node_list(display_mlist(p))?;
-                        self.flush_node_list(text_mlist(p))?;
-                        self.flush_node_list(script_mlist(p))?;
-                        self.flush_node_list(script_script_mlist(p))?;
+                        self.flush_node_list(self.display_mlist(p))?;
+                        self.flush_node_list(self.text_mlist(p))?;
+                        self.flush_node_list(self.script_mlist(p))?;
+                        self.flush_node_list(self.script_script_mlist(p))?;
                         self.free_node(p, STYLE_NODE_SIZE);
                         done = true;
                     },
@@ -748,19 +886,19 @@ impl Global {
                     | UNDER_NOAD
                     | VCENTER_NOAD
                     | ACCENT_NOAD => {
-                        if math_type(nucleus!(p)) >= SUB_BOX {
-                            self.flush_node_list(info(nucleus!(p)))?;
+                        if self.math_type(nucleus!(p)) >= SUB_BOX {
+                            self.flush_node_list(self.info(nucleus!(p)))?;
                         }
-                        if math_type(supscr!(p)) >= SUB_BOX {
-                            self.flush_node_list(info(supscr!(p)))?;
+                        if self.math_type(supscr!(p)) >= SUB_BOX {
+                            self.flush_node_list(self.info(supscr!(p)))?;
                         }
-                        if math_type(subscr!(p)) >= SUB_BOX {
-                            self.flush_node_list(info(subscr!(p)))?;
+                        if self.math_type(subscr!(p)) >= SUB_BOX {
+                            self.flush_node_list(self.info(subscr!(p)))?;
                         }
-                        if r#type(p) == RADICAL_NOAD {
+                        if self.r#type(p) == RADICAL_NOAD {
                             self.free_node(p, RADICAL_NOAD_SIZE);
                         }
-                        else if r#type(p) == ACCENT_NOAD {
+                        else if self.r#type(p) == ACCENT_NOAD {
                             self.free_node(p, ACCENT_NOAD_SIZE);
                         }
                         else {
@@ -776,8 +914,8 @@ impl Global {
                     },
 
                     FRACTION_NOAD => {
-                        self.flush_node_list(info(numerator!(p)))?;
-                        self.flush_node_list(info(denominator!(p)))?;
+                        self.flush_node_list(self.info(numerator!(p)))?;
+                        self.flush_node_list(self.info(denominator!(p)))?;
                         self.free_node(p, FRACTION_NOAD_SIZE);
                         done = true;
                     }
@@ -800,15 +938,15 @@ impl Global {
 // Section 203
 #[macro_export]
 macro_rules! add_token_ref {
-    ($p:expr) => {
-        *token_ref_count_mut($p) += 1;
+    ($s:ident, $p:expr) => {
+        *$s.token_ref_count_mut($p) += 1;
     };
 }
 
 #[macro_export]
 macro_rules! add_glue_ref {
-    ($p:expr) => {
-        *glue_ref_count_mut($p) += 1;
+    ($s:ident, $p:expr) => {
+        *$s.glue_ref_count_mut($p) += 1;
     };
 }
 
@@ -828,16 +966,16 @@ impl Global {
 
             while words > 0 {
                 words -= 1;
-                *mem_mut((r + words) as usize) = mem((p + words) as usize);
+                *self.mem_mut((r + words) as usize) = self.mem((p + words) as usize);
             }
             // End section 205
 
-            *link_mut(q) = r;
+            *self.link_mut(q) = r;
             q = r;
-            p = link(p);
+            p = self.link(p);
         }
-        *link_mut(q) = NULL;
-        q = link(h);
+        *self.link_mut(q) = NULL;
+        q = self.link(h);
         free_avail!(self, h);
         Ok(q)
     }
@@ -846,14 +984,14 @@ impl Global {
     fn sec206_case_statement(&mut self, p: HalfWord) -> TeXResult<(HalfWord, HalfWord)> {
         let r: HalfWord;
         let mut words: HalfWord = 1;
-        match r#type(p) {
+        match self.r#type(p) {
             HLIST_NODE
             | VLIST_NODE
             | UNSET_NODE => {
                 r = self.get_node(BOX_NODE_SIZE)?;
-                *mem_mut((r + 6) as usize) = mem((p + 6) as usize);
-                *mem_mut((r + 5) as usize) = mem((p + 5) as usize);
-                *list_ptr_mut(r) = self.copy_node_list(list_ptr(p))?;
+                *self.mem_mut((r + 6) as usize) = self.mem((p + 6) as usize);
+                *self.mem_mut((r + 5) as usize) = self.mem((p + 5) as usize);
+                *self.list_ptr_mut(r) = self.copy_node_list(self.list_ptr(p))?;
                 words = 5;
             },
 
@@ -864,15 +1002,15 @@ impl Global {
 
             INS_NODE => {
                 r = self.get_node(INS_NODE_SIZE)?;
-                *mem_mut((r + 4) as usize) = mem((p + 4) as usize);
-                add_glue_ref!(split_top_ptr(p));
-                *ins_ptr_mut(r) = self.copy_node_list(ins_ptr(p))?;
+                *self.mem_mut((r + 4) as usize) = self.mem((p + 4) as usize);
+                add_glue_ref!(self, self.split_top_ptr(p));
+                *self.ins_ptr_mut(r) = self.copy_node_list(self.ins_ptr(p))?;
                 words = INS_NODE_SIZE - 1;
             },
 
             WHATSIT_NODE => {
                 // Section 1357
-                match subtype(p) as Integer {
+                match self.subtype(p) as Integer {
                     OPEN_NODE => {
                         r = self.get_node(OPEN_NODE_SIZE)?;
                         words = OPEN_NODE_SIZE;
@@ -881,7 +1019,7 @@ impl Global {
                     WRITE_NODE
                     | SPECIAL_NODE => {
                         r = self.get_node(WRITE_NODE_SIZE)?;
-                        add_token_ref!(write_tokens(p));
+                        add_token_ref!(self, self.write_tokens(p));
                         words = WRITE_NODE_SIZE;
                     },
 
@@ -898,9 +1036,9 @@ impl Global {
 
             GLUE_NODE => {
                 r = self.get_node(SMALL_NODE_SIZE)?;
-                add_glue_ref!(glue_ptr(p));
-                *glue_ptr_mut(r) = glue_ptr(p);
-                *leader_ptr_mut(r) = self.copy_node_list(leader_ptr(p))?;
+                add_glue_ref!(self, self.glue_ptr(p));
+                *self.glue_ptr_mut(r) = self.glue_ptr(p);
+                *self.leader_ptr_mut(r) = self.copy_node_list(self.leader_ptr(p))?;
             },
 
             KERN_NODE
@@ -912,25 +1050,25 @@ impl Global {
 
             LIGATURE_NODE => {
                 r = self.get_node(SMALL_NODE_SIZE)?;
-                *mem_mut(lig_char!(r) as usize) = mem(lig_char!(p) as usize);
-                *lig_ptr_mut(r) = self.copy_node_list(lig_ptr(p))?;
+                *self.mem_mut(lig_char!(r) as usize) = self.mem(lig_char!(p) as usize);
+                *self.lig_ptr_mut(r) = self.copy_node_list(self.lig_ptr(p))?;
             },
 
             DISC_NODE => {
                 r = self.get_node(SMALL_NODE_SIZE)?;
-                *pre_break_mut(r) = self.copy_node_list(pre_break(p))?;
-                *post_break_mut(r) = self.copy_node_list(post_break(p))?;
+                *self.pre_break_mut(r) = self.copy_node_list(self.pre_break(p))?;
+                *self.post_break_mut(r) = self.copy_node_list(self.post_break(p))?;
             },
 
             MARK_NODE => {
                 r = self.get_node(SMALL_NODE_SIZE)?;
-                add_token_ref!(mark_ptr(p));
+                add_token_ref!(self, self.mark_ptr(p));
                 words = SMALL_NODE_SIZE;
             },
 
             ADJUST_NODE => {
                 r = self.get_node(SMALL_NODE_SIZE)?;
-                *adjust_ptr_mut(r) = self.copy_node_list(adjust_ptr(p))?;
+                *self.adjust_ptr_mut(r) = self.copy_node_list(self.adjust_ptr(p))?;
             },
             
             _ => return Err(TeXError::Confusion("copying"))
@@ -938,3 +1076,34 @@ impl Global {
         Ok((r, words))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+    use crate::constants::SPOTLESS;
+
+    // 2000 rules need 8000 words of variable-size memory,
+    // more than the whole memory of the format.
+    #[test]
+    #[cfg_attr(feature = "trip", ignore = "nothing grows with the trip feature")]
+    fn variable_size_memory_grows() {
+        let capacities = Capacities {
+            main_memory: 3000,
+            ..Capacities::default()
+        };
+        let format = dump_format(MINIMAL_FORMAT, &capacities);
+        let options = CompileOptions {
+            format: &format,
+            ..CompileOptions::default()
+        };
+        let source = "\\def\\rules{\\hrule\\global\\advance\\count1 by 1 \\ifnum\\count1<2000 \\expandafter\\rules\\fi}\n\
+            \\setbox0\\vbox{\\rules}\\message{\\the\\count1:\\the\\ht0}\\shipout\\box0 \\end\n";
+        let output = compile(source, &options);
+        assert!(output.errors.is_empty(), "{}", output.errors[0].message);
+        assert_eq!(output.history, SPOTLESS);
+        assert!(output.log.contains("2000:799.9878pt"));
+        assert!(output.dvi.is_some());
+    }
+}
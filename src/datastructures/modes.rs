@@ -1,5 +1,4 @@
-use crate::constants::NEST_SIZE;
-use crate::datastructures::{MemoryWord, link_mut};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, Scaled, free_avail
@@ -95,8 +94,8 @@ impl Global {
 macro_rules! tail_append {
     ($s:ident, $p:expr) => {
         {
-            *link_mut($s.tail()) = $p;
-            *$s.tail_mut() = link($s.tail());
+            *$s.link_mut($s.tail()) = $p;
+            *$s.tail_mut() = $s.link($s.tail());
         }
     };
 }
@@ -106,8 +105,8 @@ impl Global {
     pub(crate) fn push_nest(&mut self) -> TeXResult<()> {
         if self.nest_ptr > self.max_nest_stack {
             self.max_nest_stack = self.nest_ptr;
-            if self.nest_ptr == NEST_SIZE as usize {
-                return Err(TeXError::Overflow("semantic nest size", NEST_SIZE))
+            if self.nest_ptr == self.nest_size as usize {
+                return Err(TeXError::Overflow("semantic nest size", self.nest_size))
             }
         }
         self.nest[self.nest_ptr] = self.cur_list;
@@ -1,8 +1,4 @@
 use crate::constants::*;
-use crate::datastructures::{
-    mem, mem_mut, glue_par, info, info_mut, link, link_mut, llink, llink_mut,
-    rlink, rlink_mut
-};
 use crate::error::TeXResult;
 use crate::{
     Global, GlueRatio, HalfWord, Integer, QuarterWord, Scaled, SmallNumber
@@ -10,21 +6,23 @@ use crate::{
 
 // Part 10: Data structures for boxes and their friends
 
-// Section 133
-pub(crate) fn r#type(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b0()
-}
+impl Global {
+    // Section 133
+    pub(crate) fn r#type(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b0()
+    }
 
-pub(crate) fn type_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b0_mut()
-}
+    pub(crate) fn type_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b0_mut()
+    }
 
-pub(crate) fn subtype(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b1()
-}
+    pub(crate) fn subtype(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b1()
+    }
 
-pub(crate) fn subtype_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b1_mut()
+    pub(crate) fn subtype_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b1_mut()
+    }
 }
 
 // Section 134
@@ -34,85 +32,87 @@ impl Global {
     }
 }
 
-pub(crate) fn font(p: HalfWord) -> QuarterWord {
-    r#type(p)
-}
+impl Global {
+    pub(crate) fn font(&self, p: HalfWord) -> QuarterWord {
+        self.r#type(p)
+    }
 
-pub(crate) fn font_mut(p: HalfWord) -> &'static mut QuarterWord {
-    type_mut(p)
-}
+    pub(crate) fn font_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.type_mut(p)
+    }
 
-pub(crate) fn character(p: HalfWord) -> QuarterWord {
-    subtype(p)
-}
+    pub(crate) fn character(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p)
+    }
 
-pub(crate) fn character_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p)
-}
+    pub(crate) fn character_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p)
+    }
 
-// Section 135
-pub(crate) fn width(p: HalfWord) -> Scaled {
-    mem((p + WIDTH_OFFSET) as usize).sc()
-}
+    // Section 135
+    pub(crate) fn width(&self, p: HalfWord) -> Scaled {
+        self.mem((p + WIDTH_OFFSET) as usize).sc()
+    }
 
-pub(crate) fn width_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + WIDTH_OFFSET) as usize).sc_mut()
-}
+    pub(crate) fn width_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + WIDTH_OFFSET) as usize).sc_mut()
+    }
 
-pub(crate) fn depth(p: HalfWord) -> Scaled {
-    mem((p + DEPTH_OFFSET) as usize).sc()
-}
+    pub(crate) fn depth(&self, p: HalfWord) -> Scaled {
+        self.mem((p + DEPTH_OFFSET) as usize).sc()
+    }
 
-pub(crate) fn depth_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + DEPTH_OFFSET) as usize).sc_mut()
-}
+    pub(crate) fn depth_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + DEPTH_OFFSET) as usize).sc_mut()
+    }
 
-pub(crate) fn height(p: HalfWord) -> Scaled {
-    mem((p + HEIGHT_OFFSET) as usize).sc()
-}
+    pub(crate) fn height(&self, p: HalfWord) -> Scaled {
+        self.mem((p + HEIGHT_OFFSET) as usize).sc()
+    }
 
-pub(crate) fn height_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + HEIGHT_OFFSET) as usize).sc_mut()
-}
+    pub(crate) fn height_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + HEIGHT_OFFSET) as usize).sc_mut()
+    }
 
-pub(crate) fn shift_amount(p: HalfWord) -> Scaled {
-    mem(p as usize + 4).sc()
-}
+    pub(crate) fn shift_amount(&self, p: HalfWord) -> Scaled {
+        self.mem(p as usize + 4).sc()
+    }
 
-pub(crate) fn shift_amount_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut(p as usize + 4).sc_mut()
-}
+    pub(crate) fn shift_amount_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut(p as usize + 4).sc_mut()
+    }
 
-pub(crate) fn list_ptr(p: HalfWord) -> HalfWord {
-    link(p + LIST_OFFSET)
-}
+    pub(crate) fn list_ptr(&self, p: HalfWord) -> HalfWord {
+        self.link(p + LIST_OFFSET)
+    }
 
-pub(crate) fn list_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + LIST_OFFSET)
-}
+    pub(crate) fn list_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + LIST_OFFSET)
+    }
 
-pub(crate) fn glue_order(p: HalfWord) -> QuarterWord {
-    subtype(p + LIST_OFFSET)
-}
+    pub(crate) fn glue_order(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p + LIST_OFFSET)
+    }
 
-pub(crate) fn glue_order_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p + LIST_OFFSET)
-}
+    pub(crate) fn glue_order_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p + LIST_OFFSET)
+    }
 
-pub(crate) fn glue_sign(p: HalfWord) -> QuarterWord {
-    r#type(p + LIST_OFFSET)
-}
+    pub(crate) fn glue_sign(&self, p: HalfWord) -> QuarterWord {
+        self.r#type(p + LIST_OFFSET)
+    }
 
-pub(crate) fn glue_sign_mut(p: HalfWord) -> &'static mut QuarterWord {
-    type_mut(p + LIST_OFFSET)
-}
+    pub(crate) fn glue_sign_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.type_mut(p + LIST_OFFSET)
+    }
 
-pub(crate) fn glue_set(p: HalfWord) -> GlueRatio {
-    mem((p + GLUE_OFFSET) as usize).gr()
-}
+    pub(crate) fn glue_set(&mut self, p: HalfWord) -> GlueRatio {
+        self.mem((p + GLUE_OFFSET) as usize).gr()
+    }
 
-pub(crate) fn glue_set_mut(p: HalfWord) -> &'static mut GlueRatio {
-    mem_mut((p + GLUE_OFFSET) as usize).gr_mut()
+    pub(crate) fn glue_set_mut(&mut self, p: HalfWord) -> &mut GlueRatio {
+        self.mem_mut((p + GLUE_OFFSET) as usize).gr_mut()
+    }
 }
 
 // Section 138
@@ -127,72 +127,74 @@ impl Global {
     // Section 136
      pub(crate) fn new_null_box(&mut self) -> TeXResult<HalfWord> {
         let p = self.get_node(BOX_NODE_SIZE)?;
-        *type_mut(p) = HLIST_NODE;
-        *subtype_mut(p) = MIN_QUARTERWORD;
-        *width_mut(p) = 0;
-        *depth_mut(p) = 0;
-        *height_mut(p) = 0;
-        *shift_amount_mut(p) = 0;
-        *list_ptr_mut(p) =  NULL;
-        *glue_sign_mut(p) = NORMAL;
-        *glue_order_mut(p) = NORMAL;
-        *glue_set_mut(p) = 0.0;
+        *self.type_mut(p) = HLIST_NODE;
+        *self.subtype_mut(p) = MIN_QUARTERWORD;
+        *self.width_mut(p) = 0;
+        *self.depth_mut(p) = 0;
+        *self.height_mut(p) = 0;
+        *self.shift_amount_mut(p) = 0;
+        *self.list_ptr_mut(p) =  NULL;
+        *self.glue_sign_mut(p) = NORMAL;
+        *self.glue_order_mut(p) = NORMAL;
+        *self.glue_set_mut(p) = 0.0;
         Ok(p)
     }
 
     // Section 139
     pub(crate) fn new_rule(&mut self) -> TeXResult<HalfWord> {
         let p = self.get_node(RULE_NODE_SIZE)?;
-        *type_mut(p) = RULE_NODE;
-        *subtype_mut(p) = 0;
-        *width_mut(p) = NULL_FLAG;
-        *depth_mut(p) = NULL_FLAG;
-        *height_mut(p) = NULL_FLAG;
+        *self.type_mut(p) = RULE_NODE;
+        *self.subtype_mut(p) = 0;
+        *self.width_mut(p) = NULL_FLAG;
+        *self.depth_mut(p) = NULL_FLAG;
+        *self.height_mut(p) = NULL_FLAG;
         Ok(p)
     }
 }
 
-// Section 140
-pub(crate) fn float_cost(p: HalfWord) -> Integer {
-    mem((p + 1) as usize).int()
-}
+impl Global {
+    // Section 140
+    pub(crate) fn float_cost(&self, p: HalfWord) -> Integer {
+        self.mem((p + 1) as usize).int()
+    }
 
-pub(crate) fn float_cost_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + 1) as usize).int_mut()
-}
+    pub(crate) fn float_cost_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + 1) as usize).int_mut()
+    }
 
-pub(crate) fn ins_ptr(p: HalfWord) -> HalfWord {
-    info(p + 4)
-}
+    pub(crate) fn ins_ptr(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 4)
+    }
 
-pub(crate) fn ins_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 4)
-}
+    pub(crate) fn ins_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 4)
+    }
 
-pub(crate) fn split_top_ptr(p: HalfWord) -> HalfWord {
-    link(p + 4)
-}
+    pub(crate) fn split_top_ptr(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 4)
+    }
 
-pub(crate) fn split_top_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 4)
-}
+    pub(crate) fn split_top_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 4)
+    }
 
-// Section 141
-pub(crate) fn mark_ptr(p: HalfWord) -> Integer {
-    mem((p + 1) as usize).int()
-}
+    // Section 141
+    pub(crate) fn mark_ptr(&self, p: HalfWord) -> Integer {
+        self.mem((p + 1) as usize).int()
+    }
 
-pub(crate) fn mark_ptr_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + 1) as usize).int_mut()
-}
+    pub(crate) fn mark_ptr_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + 1) as usize).int_mut()
+    }
 
-// Section 142
-pub(crate) fn adjust_ptr(p: HalfWord) -> Integer {
-    mark_ptr(p)
-}
+    // Section 142
+    pub(crate) fn adjust_ptr(&self, p: HalfWord) -> Integer {
+        self.mark_ptr(p)
+    }
 
-pub(crate) fn adjust_ptr_mut(p: HalfWord) -> &'static mut Integer {
-    mark_ptr_mut(p)
+    pub(crate) fn adjust_ptr_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mark_ptr_mut(p)
+    }
 }
 
 // Section 143
@@ -203,76 +205,80 @@ macro_rules! lig_char {
     };
 }
 
-// Section 143
-pub(crate) fn lig_ptr(p: HalfWord) -> HalfWord {
-    link(lig_char!(p))
-}
+impl Global {
+    // Section 143
+    pub(crate) fn lig_ptr(&self, p: HalfWord) -> HalfWord {
+        self.link(lig_char!(p))
+    }
 
-pub(crate) fn lig_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(lig_char!(p))
+    pub(crate) fn lig_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(lig_char!(p))
+    }
 }
 
 impl Global {
     // Section 144
     pub(crate) fn new_ligature(&mut self, f: QuarterWord, c: QuarterWord, q: HalfWord) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = LIGATURE_NODE;
-        *font_mut(lig_char!(p)) = f;
-        *character_mut(lig_char!(p)) = c;
-        *lig_ptr_mut(p) = q;
-        *subtype_mut(p) = 0;
+        *self.type_mut(p) = LIGATURE_NODE;
+        *self.font_mut(lig_char!(p)) = f;
+        *self.character_mut(lig_char!(p)) = c;
+        *self.lig_ptr_mut(p) = q;
+        *self.subtype_mut(p) = 0;
         Ok(p)
     }
 
     pub(crate) fn new_lig_item(&mut self, c: QuarterWord) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *character_mut(p) = c;
-        *lig_ptr_mut(p) = NULL;
+        *self.character_mut(p) = c;
+        *self.lig_ptr_mut(p) = NULL;
         Ok(p)
     }
 }
 
-// Section 145
-pub(crate) fn replace_count(p: HalfWord) -> QuarterWord {
-    subtype(p)
-}
+impl Global {
+    // Section 145
+    pub(crate) fn replace_count(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p)
+    }
 
-pub(crate) fn replace_count_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p)
-}
+    pub(crate) fn replace_count_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p)
+    }
 
-pub(crate) fn pre_break(p: HalfWord) -> HalfWord {
-    llink(p)
-}
+    pub(crate) fn pre_break(&self, p: HalfWord) -> HalfWord {
+        self.llink(p)
+    }
 
-pub(crate) fn pre_break_mut(p: HalfWord) -> &'static mut HalfWord {
-    llink_mut(p)
-}
+    pub(crate) fn pre_break_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.llink_mut(p)
+    }
 
-pub(crate) fn post_break(p: HalfWord) -> HalfWord {
-    rlink(p)
-}
+    pub(crate) fn post_break(&self, p: HalfWord) -> HalfWord {
+        self.rlink(p)
+    }
 
-pub(crate) fn post_break_mut(p: HalfWord) -> &'static mut HalfWord {
-    rlink_mut(p)
+    pub(crate) fn post_break_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.rlink_mut(p)
+    }
 }
 
 impl Global {
     pub(crate) fn new_disc(&mut self) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = DISC_NODE;
-        *replace_count_mut(p) = 0;
-        *pre_break_mut(p) = NULL;
-        *post_break_mut(p) = NULL;
+        *self.type_mut(p) = DISC_NODE;
+        *self.replace_count_mut(p) = 0;
+        *self.pre_break_mut(p) = NULL;
+        *self.post_break_mut(p) = NULL;
         Ok(p)
     }
 
     // Section 147
     pub(crate) fn new_math(&mut self, w: Scaled, s: SmallNumber) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = MATH_NODE;
-        *subtype_mut(p) = s;
-        *width_mut(p) = w;
+        *self.type_mut(p) = MATH_NODE;
+        *self.subtype_mut(p) = s;
+        *self.width_mut(p) = w;
         Ok(p)
     }
 }
@@ -280,171 +286,177 @@ impl Global {
 // Section 148
 #[macro_export]
 macro_rules! precedes_break {
-    ($p:expr) => {
-        r#type($p) < MATH_NODE
+    ($s:ident, $p:expr) => {
+        $s.r#type($p) < MATH_NODE
     };
 }
 
 #[macro_export]
 macro_rules! non_discardable {
-    ($p:expr) => {
-        r#type($p) < MATH_NODE
+    ($s:ident, $p:expr) => {
+        $s.r#type($p) < MATH_NODE
     };
 }
 
-// Section 149
-pub(crate) fn glue_ptr(p: HalfWord) -> HalfWord {
-    llink(p)
-}
+impl Global {
+    // Section 149
+    pub(crate) fn glue_ptr(&self, p: HalfWord) -> HalfWord {
+        self.llink(p)
+    }
 
-pub(crate) fn glue_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    llink_mut(p)
-}
+    pub(crate) fn glue_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.llink_mut(p)
+    }
 
-pub(crate) fn leader_ptr(p: HalfWord) -> HalfWord {
-    rlink(p)
-}
+    pub(crate) fn leader_ptr(&self, p: HalfWord) -> HalfWord {
+        self.rlink(p)
+    }
 
-pub(crate) fn leader_ptr_mut(p: HalfWord) -> &'static mut HalfWord {
-    rlink_mut(p)
-}
+    pub(crate) fn leader_ptr_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.rlink_mut(p)
+    }
 
-// Section 150
-pub(crate) fn glue_ref_count(p: HalfWord) -> HalfWord {
-    link(p)
-}
+    // Section 150
+    pub(crate) fn glue_ref_count(&self, p: HalfWord) -> HalfWord {
+        self.link(p)
+    }
 
-pub(crate) fn glue_ref_count_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p)
-}
+    pub(crate) fn glue_ref_count_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p)
+    }
 
-pub(crate) fn stretch( p: HalfWord) -> Scaled {
-    mem((p + 2) as usize).sc()
-}
+    pub(crate) fn stretch(&self,  p: HalfWord) -> Scaled {
+        self.mem((p + 2) as usize).sc()
+    }
 
-pub(crate) fn stretch_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + 2) as usize).sc_mut()
-}
+    pub(crate) fn stretch_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + 2) as usize).sc_mut()
+    }
 
-pub(crate) fn shrink(p: HalfWord) -> Scaled {
-    mem((p + 3) as usize).sc()
-}
+    pub(crate) fn shrink(&self, p: HalfWord) -> Scaled {
+        self.mem((p + 3) as usize).sc()
+    }
 
-pub(crate) fn shrink_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + 3) as usize).sc_mut()
-}
+    pub(crate) fn shrink_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + 3) as usize).sc_mut()
+    }
 
-pub(crate) fn stretch_order(p: HalfWord) -> QuarterWord {
-    r#type(p)
-}
+    pub(crate) fn stretch_order(&self, p: HalfWord) -> QuarterWord {
+        self.r#type(p)
+    }
 
-pub(crate) fn stretch_order_mut(p: HalfWord) -> &'static mut QuarterWord {
-    type_mut(p)
-}
+    pub(crate) fn stretch_order_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.type_mut(p)
+    }
 
-pub(crate) fn shrink_order(p: HalfWord) -> QuarterWord {
-    subtype(p)
-}
+    pub(crate) fn shrink_order(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p)
+    }
 
-pub(crate) fn shrink_order_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p)
+    pub(crate) fn shrink_order_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p)
+    }
 }
 
 impl Global {
      // Section 151
      pub(crate) fn new_spec(&mut self, p: HalfWord) -> TeXResult<HalfWord> {
         let q = self.get_node(GLUE_SPEC_SIZE)?;
-        *mem_mut(q as usize) = mem(p as usize);
-        *glue_ref_count_mut(q) = NULL;
-        *width_mut(q) = width(p);
-        *stretch_mut(q) = stretch(p);
-        *shrink_mut(q) = shrink(p);
+        *self.mem_mut(q as usize) = self.mem(p as usize);
+        *self.glue_ref_count_mut(q) = NULL;
+        *self.width_mut(q) = self.width(p);
+        *self.stretch_mut(q) = self.stretch(p);
+        *self.shrink_mut(q) = self.shrink(p);
         Ok(q)
     }
 
     // Section 152
     pub(crate) fn new_param_glue(&mut self, n: SmallNumber) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = GLUE_NODE;
-        *subtype_mut(p) = n + 1;
-        *leader_ptr_mut(p) = NULL;
-        let q = glue_par(n as HalfWord);
-        *glue_ptr_mut(p) = q;
-        *glue_ref_count_mut(q) += 1;
+        *self.type_mut(p) = GLUE_NODE;
+        *self.subtype_mut(p) = n + 1;
+        *self.leader_ptr_mut(p) = NULL;
+        let q = self.glue_par(n as HalfWord);
+        *self.glue_ptr_mut(p) = q;
+        *self.glue_ref_count_mut(q) += 1;
         Ok(p)
     }
 
     // Section 153
     pub(crate) fn new_glue(&mut self, q: HalfWord) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = GLUE_NODE;
-        *subtype_mut(p) = NORMAL;
-        *leader_ptr_mut(p) = NULL;
-        *glue_ptr_mut(p) = q;
-        *glue_ref_count_mut(q) += 1;
+        *self.type_mut(p) = GLUE_NODE;
+        *self.subtype_mut(p) = NORMAL;
+        *self.leader_ptr_mut(p) = NULL;
+        *self.glue_ptr_mut(p) = q;
+        *self.glue_ref_count_mut(q) += 1;
         Ok(p)
     }
 
     // Section 154
     pub(crate) fn new_skip_param(&mut self, n: SmallNumber) -> TeXResult<HalfWord> {
-        self.temp_ptr = self.new_spec(glue_par(n as HalfWord))?;
+        self.temp_ptr = self.new_spec(self.glue_par(n as HalfWord))?;
         let p = self.new_glue(self.temp_ptr)?;
-        *glue_ref_count_mut(self.temp_ptr) = NULL;
-        *subtype_mut(p) = n + 1;
+        *self.glue_ref_count_mut(self.temp_ptr) = NULL;
+        *self.subtype_mut(p) = n + 1;
         Ok(p)
     }
 
     // Section 156
     pub(crate) fn new_kern(&mut self, w: Scaled) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = KERN_NODE;
-        *subtype_mut(p) = NORMAL;
-        *width_mut(p) = w;
+        *self.type_mut(p) = KERN_NODE;
+        *self.subtype_mut(p) = NORMAL;
+        *self.width_mut(p) = w;
         Ok(p)
     }
 }
 
-// Section 157
-pub(crate) fn penalty(p: HalfWord) -> Integer {
-    mem((p + 1) as usize).int()
-}
+impl Global {
+    // Section 157
+    pub(crate) fn penalty(&self, p: HalfWord) -> Integer {
+        self.mem((p + 1) as usize).int()
+    }
 
-pub(crate) fn penalty_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + 1) as usize).int_mut()
+    pub(crate) fn penalty_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + 1) as usize).int_mut()
+    }
 }
 
 impl Global {
     // Section 158
     pub(crate) fn new_penalty(&mut self, m: Integer) -> TeXResult<HalfWord> {
         let p = self.get_node(SMALL_NODE_SIZE)?;
-        *type_mut(p) = PENALTY_NODE;
-        *subtype_mut(p) = 0;
-        *penalty_mut(p) = m;
+        *self.type_mut(p) = PENALTY_NODE;
+        *self.subtype_mut(p) = 0;
+        *self.penalty_mut(p) = m;
         Ok(p)
     }
 }
 
-// Section 159
-pub(crate) fn glue_stretch(p: HalfWord) -> Scaled {
-    mem((p + GLUE_OFFSET) as usize).sc()
-}
+impl Global {
+    // Section 159
+    pub(crate) fn glue_stretch(&self, p: HalfWord) -> Scaled {
+        self.mem((p + GLUE_OFFSET) as usize).sc()
+    }
 
-pub(crate) fn glue_stretch_mut(p: HalfWord) -> &'static mut Scaled {
-    mem_mut((p + GLUE_OFFSET) as usize).sc_mut()
-}
+    pub(crate) fn glue_stretch_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.mem_mut((p + GLUE_OFFSET) as usize).sc_mut()
+    }
 
-pub(crate) fn glue_shrink(p: HalfWord) -> Scaled {
-    shift_amount(p)
-}
+    pub(crate) fn glue_shrink(&self, p: HalfWord) -> Scaled {
+        self.shift_amount(p)
+    }
 
-pub(crate) fn glue_shrink_mut(p: HalfWord) -> &'static mut Scaled {
-    shift_amount_mut(p)
-}
+    pub(crate) fn glue_shrink_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.shift_amount_mut(p)
+    }
 
-pub(crate) fn span_count(p: HalfWord) -> QuarterWord {
-    subtype(p)
-}
+    pub(crate) fn span_count(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p)
+    }
 
-pub(crate) fn span_count_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p)
+    pub(crate) fn span_count_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p)
+    }
 }
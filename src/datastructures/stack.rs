@@ -1,8 +1,6 @@
+use crate::capacities::grown_size;
 use crate::constants::*;
-use crate::datastructures::{
-    eqtb, eqtb_mut, XEQ_LEVEL, MemoryWord, eq_level, eq_level_mut, eq_type_mut,
-    equiv_mut, info, info_mut, link, token_ref_count_mut, tracing_macros
-};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileIn;
 use crate::{
@@ -11,12 +9,10 @@ use crate::{
 
 use std::ops::{Index, IndexMut};
 
-#[cfg(feature = "stat")]
-use crate::datastructures::tracing_restores;
 
 // Part 19: Saving and restoring equivalents
 
-pub(crate) struct InputFile(pub(crate) [AlphaFileIn; MAX_IN_OPEN as usize]);
+pub(crate) struct InputFile(pub(crate) Vec<AlphaFileIn>);
 
 impl Index<usize> for InputFile {
     type Output = AlphaFileIn;
@@ -31,7 +27,7 @@ impl IndexMut<usize> for InputFile {
     }
 }
 
-pub(crate) struct LineStack(pub(crate) [Integer; MAX_IN_OPEN as usize]);
+pub(crate) struct LineStack(pub(crate) Vec<Integer>);
 
 impl Index<usize> for LineStack {
     type Output = Integer;
@@ -76,8 +72,15 @@ impl Global {
     fn check_full_save_stack(&mut self) -> TeXResult<()> {
         if self.save_ptr > self.max_save_stack as usize {
             self.max_save_stack = self.save_ptr as Integer;
-            if self.max_save_stack > SAVE_SIZE - 6 {
-                return Err(TeXError::Overflow("save size", SAVE_SIZE));
+            // The save stack grows up to `SUP_SAVE_SIZE` entries.
+            if self.max_save_stack > self.save_size - 6 {
+                match grown_size(self.save_size, self.max_save_stack + 6, SUP_SAVE_SIZE) {
+                    Some(size) => {
+                        self.save_size = size;
+                        self.save_stack.resize((size + 1) as usize, MemoryWord::ZERO);
+                    },
+                    None => return Err(TeXError::Overflow("save size", self.save_size))
+                }
             }
         }
         Ok(())
@@ -120,7 +123,7 @@ impl Global {
             SHAPE_REF => {
                 let q = w.equiv_field();
                 if q != NULL {
-                    self.free_node(q, 2*info(q) + 1);
+                    self.free_node(q, 2*self.info(q) + 1);
                 }
             },
 
@@ -138,7 +141,7 @@ impl Global {
             *self.save_type_mut(self.save_ptr) = RESTORE_ZERO;
         }
         else {
-            self.save_stack[self.save_ptr] = eqtb(p as usize);
+            self.save_stack[self.save_ptr] = self.eqtb(p as usize);
             self.save_ptr += 1;
             *self.save_type_mut(self.save_ptr) = RESTOVE_OLD_VALUE;
         }
@@ -150,41 +153,43 @@ impl Global {
 
     // Section 277
     pub(crate) fn eq_define(&mut self, p: HalfWord, t: QuarterWord, e: HalfWord) -> TeXResult<()> {
-        if eq_level(p) == self.cur_level {
-            self.eq_destroy(eqtb(p as usize))?;
+        if self.eq_level(p) == self.cur_level {
+            self.eq_destroy(self.eqtb(p as usize))?;
         }
         else if self.cur_level > LEVEL_ONE {
-            self.eq_save(p, eq_level(p))?;
+            self.eq_save(p, self.eq_level(p))?;
         }
-        *eq_level_mut(p) = self.cur_level;
-        *eq_type_mut(p) = t;
-        *equiv_mut(p) = e;
+        *self.eq_level_mut(p) = self.cur_level;
+        *self.eq_type_mut(p) = t;
+        *self.equiv_mut(p) = e;
         Ok(())
     }
 
     // Section 278
     pub(crate) fn eq_word_define(&mut self, p: HalfWord, w: Integer) -> TeXResult<()> {
-        if unsafe { XEQ_LEVEL[p as usize] } != self.cur_level {
-            self.eq_save(p, unsafe { XEQ_LEVEL[p as usize] })?;
-            unsafe { XEQ_LEVEL[p as usize] = self.cur_level; }
+        if self.xeq_level[p as usize] != self.cur_level {
+            self.eq_save(p, self.xeq_level[p as usize])?;
+            self.xeq_level[p as usize] = self.cur_level;
         }
-        *eqtb_mut(p as usize).int_mut() = w;
+        *self.eqtb_mut(p as usize).int_mut() = w;
         Ok(())
     }
 
     // Section 279
     pub(crate) fn geq_define(&mut self, p: HalfWord, t: QuarterWord, e: HalfWord) -> TeXResult<()> {
-        self.eq_destroy(eqtb(p as usize))?;
-        *eq_level_mut(p) = LEVEL_ONE;
-        *eq_type_mut(p) = t;
-        *equiv_mut(p) = e;
+        self.eq_destroy(self.eqtb(p as usize))?;
+        *self.eq_level_mut(p) = LEVEL_ONE;
+        *self.eq_type_mut(p) = t;
+        *self.equiv_mut(p) = e;
         Ok(())
     }
 }
 
-pub(crate) fn geq_word_define(p: HalfWord, w: Integer) {
-    *eqtb_mut(p as usize).int_mut() = w;
-    unsafe { XEQ_LEVEL[p as usize] = LEVEL_ONE; }
+impl Global {
+    pub(crate) fn geq_word_define(&mut self, p: HalfWord, w: Integer) {
+        *self.eqtb_mut(p as usize).int_mut() = w;
+        self.xeq_level[p as usize] = LEVEL_ONE;
+    }
 }
 
 impl Global {
@@ -226,37 +231,37 @@ impl Global {
                         self.save_ptr -= 1;
                     }
                     else {
-                        self.save_stack[self.save_ptr] = eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
+                        self.save_stack[self.save_ptr] = self.eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
                     }
                     // Section 283
-                    if p < INT_BASE {
-                        if eq_level(p) == LEVEL_ONE {
+                    if !(INT_BASE..=EQTB_SIZE).contains(&p) {
+                        if self.eq_level(p) == LEVEL_ONE {
                             self.eq_destroy(self.save_stack[self.save_ptr])?;
                             #[cfg(feature = "stat")]
-                            if tracing_restores() > 0 {
+                            if self.tracing_restores() > 0 {
                                 self.restore_trace(p, "retaining");
                             }
                         }
                         else {
-                            self.eq_destroy(eqtb(p as usize))?;
-                            *eqtb_mut(p as usize) = self.save_stack[self.save_ptr];
+                            self.eq_destroy(self.eqtb(p as usize))?;
+                            *self.eqtb_mut(p as usize) = self.save_stack[self.save_ptr];
                             #[cfg(feature = "stat")]
-                            if tracing_restores() > 0 {
+                            if self.tracing_restores() > 0 {
                                 self.restore_trace(p, "restoring");
                             }
                         }
                     }
-                    else if unsafe { XEQ_LEVEL[p as usize] } != LEVEL_ONE {
-                        *eqtb_mut(p as usize) = self.save_stack[self.save_ptr];
-                        unsafe { XEQ_LEVEL[p as usize] = l; }
+                    else if self.xeq_level[p as usize] != LEVEL_ONE {
+                        *self.eqtb_mut(p as usize) = self.save_stack[self.save_ptr];
+                        self.xeq_level[p as usize] = l;
                         #[cfg(feature = "stat")]
-                        if tracing_restores() > 0 {
+                        if self.tracing_restores() > 0 {
                             self.restore_trace(p, "restoring");
                         }
                     }
                     else {
                         #[cfg(feature = "stat")]
-                        if tracing_restores() > 0 {
+                        if self.tracing_restores() > 0 {
                             self.restore_trace(p, "retaining");
                         }
                     }
@@ -295,7 +300,7 @@ pub(crate) struct InStateRecord {
     start_field: HalfWord,
     pub(crate) loc_field: HalfWord,
     pub(crate) limit_field: HalfWord,
-    name_field: HalfWord,
+    pub(crate) name_field: HalfWord,
 }
 
 impl Global {
@@ -374,12 +379,12 @@ impl Global {
 
                 Status::Matching => {
                     self.print("argument");
-                    TEMP_HEAD
+                    self.temp_head()
                 },
 
                 Status::Aligning => {
                     self.print("preamble");
-                    HOLD_HEAD
+                    self.hold_head()
                 },
 
                 _ /* Absorbing */ => {
@@ -389,7 +394,7 @@ impl Global {
             };
             self.print_char(b'?');
             self.print_ln();
-            self.show_token_list(link(p), NULL, ERROR_LINE - 10);
+            self.show_token_list(self.link(p), NULL, ERROR_LINE - 10);
         }
     }
 }
@@ -431,8 +436,8 @@ impl Global {
     fn push_input(&mut self) -> TeXResult<()> {
         if self.input_ptr > self.max_in_stack {
             self.max_in_stack = self.input_ptr;
-            if self.input_ptr == STACK_SIZE as usize {
-                return Err(TeXError::Overflow("input stack size", STACK_SIZE));
+            if self.input_ptr == self.stack_size as usize {
+                return Err(TeXError::Overflow("input stack size", self.stack_size));
             }
         }
         self.input_stack[self.input_ptr] = self.cur_input;
@@ -453,13 +458,13 @@ impl Global {
         *self.start_mut() = p;
         *self.token_type_mut() = t;
         if t >= MACRO {
-            add_token_ref!(p);
+            add_token_ref!(self, p);
             if t == MACRO {
                 *self.param_start_mut() = self.param_ptr as HalfWord;
             }
             else {
-                *self.loc_mut() = link(p);
-                if tracing_macros() > 1 {
+                *self.loc_mut() = self.link(p);
+                if self.tracing_macros() > 1 {
                     self.begin_diagnostic();
                     self.print_nl("");
                     match t {
@@ -517,7 +522,7 @@ impl Global {
         }
 
         let p = self.get_avail()?;
-        *info_mut(p) = self.cur_tok;
+        *self.info_mut(p) = self.cur_tok;
         if self.cur_tok < RIGHT_BRACE_LIMIT {
             if self.cur_tok < LEFT_BRACE_LIMIT {
                 self.align_state -= 1;
@@ -535,21 +540,34 @@ impl Global {
     }
 
     // Section 327
-    // No `back_error` or `ins_error`.
-    // When an error occurs, it's endgame.
+    pub(crate) fn back_error(&mut self, texerror: TeXError) -> TeXResult<()> {
+        self.ok_to_interrupt = false;
+        self.back_input()?;
+        self.ok_to_interrupt = true;
+        self.error(texerror)
+    }
+
+    pub(crate) fn ins_error(&mut self, texerror: TeXError) -> TeXResult<()> {
+        self.ok_to_interrupt = false;
+        self.back_input()?;
+        *self.token_type_mut() = INSERTED;
+        self.ok_to_interrupt = true;
+        self.error(texerror)
+    }
 
     // Section 328
     pub(crate) fn begin_file_reading(&mut self) -> TeXResult<()> {
-        if self.in_open == MAX_IN_OPEN as usize {
-            return Err(TeXError::Overflow("text input levels", MAX_IN_OPEN));
+        if self.in_open == self.max_in_open as usize {
+            return Err(TeXError::Overflow("text input levels", self.max_in_open));
         }
-        if self.first == BUF_SIZE {
-            return Err(TeXError::Overflow("buffer size", BUF_SIZE));
+        if self.first == self.buf_size {
+            return Err(TeXError::Overflow("buffer size", self.buf_size));
         }
         self.in_open += 1;
         self.push_input()?;
         *self.index_mut() = self.in_open as QuarterWord;
         self.line_stack[self.in_open] = self.line;
+        self.source_filename_stack[self.in_open].clear();
         *self.start_mut() = self.first;
         *self.state_mut() = MID_LINE;
         *self.name_mut() = 0;
@@ -566,4 +584,16 @@ impl Global {
         self.pop_input();
         self.in_open -= 1;
     }
+
+    // Section 330
+    pub(crate) fn clear_for_error_prompt(&mut self) {
+        while self.state() != TOKEN_LIST
+            && self.terminal_input()
+            && self.input_ptr > 0
+            && self.loc() > self.limit()
+        {
+            self.end_file_reading();
+        }
+        self.print_ln();
+    }
 }
@@ -1,21 +1,16 @@
 use crate::constants::{
-    MAX_QUARTERWORD, NULL, NULL_FONT, POOL_SIZE, TERM_ONLY
+    MAX_QUARTERWORD, NULL, NULL_FONT, TERM_ONLY
 };
-use crate::datastructures::{eqtb, mem, info, link};
 use crate::error::TeXResult;
-use crate::io::term_input_int;
-use crate::strings::pool_ptr;
 use crate::{
     Global, Integer, QuarterWord, StrNum, update_terminal
 };
 
-use std::io::Write;
-
 // Part 52: Debugging
 
 macro_rules! read_integer {
-    () => {
-        match term_input_int()? {
+    ($s:ident) => {
+        match $s.term_input_int()? {
             Some(m) => m,
             None => {
                 println!("\n! The input must be an integer.");
@@ -26,11 +21,11 @@ macro_rules! read_integer {
 }
 
 macro_rules! read_integer_prompt {
-    ($s:expr) => {
+    ($s:ident, $p:expr) => {
         {
-            print!("debug ({}=) # ", $s);
-            update_terminal!();
-            read_integer!()
+            print!("debug ({}=) # ", $p);
+            update_terminal!($s);
+            read_integer!($s)
         }
     };
 }
@@ -61,92 +56,92 @@ impl Global {
         loop {
             println!();
             print!("debug # ");
-            update_terminal!();
+            update_terminal!(self);
 
-            let m = read_integer!();
+            let m = read_integer!(self);
 
             match m {
                 1 => {
-                    let n = read_integer_prompt!("n");
-                    self.print_word(mem(n as usize));
+                    let n = read_integer_prompt!(self, "n");
+                    self.print_word(self.mem(n as usize));
                 },
 
                 2 => {
-                    let n = read_integer_prompt!("n");
-                    self.print_int(info(n));
+                    let n = read_integer_prompt!(self, "n");
+                    self.print_int(self.info(n));
                 },
 
                 3 => {
-                    let n = read_integer_prompt!("n");
-                    self.print_int(link(n));
+                    let n = read_integer_prompt!(self, "n");
+                    self.print_int(self.link(n));
                 },
 
                 4 => {
-                    let n = read_integer_prompt!("n");
-                    self.print_word(eqtb(n as usize));
+                    let n = read_integer_prompt!(self, "n");
+                    self.print_word(self.eqtb(n as usize));
                 },
 
                 5 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.print_word(self.font_info[n as usize]);
                 },
 
                 6 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.print_word(self.save_stack[n as usize]);
                 },
 
                 7 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.show_box(n);
                 },
 
                 8 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.breadth_max = 10_000;
-                    self.depth_threshold = POOL_SIZE - (pool_ptr() as Integer) - 10;
+                    self.depth_threshold = self.pool_size - (self.pool_ptr() as Integer) - 10;
                     self.show_node_list(n);
                 },
 
                 9 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.show_token_list(n, NULL, 1000);
                 },
 
                 10 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     if n >= 0 {
                         self.slow_print(n as usize);
                     }
                 },
 
                 11 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.check_mem(n > 0);
                 },
 
                 12 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.search_mem(n);
                 },
 
                 13 => {
-                    let n = read_integer_prompt!("n");
-                    let l = read_integer_prompt!("l");
+                    let n = read_integer_prompt!(self, "n");
+                    let l = read_integer_prompt!(self, "l");
                     if n >= 0 && n <= (MAX_QUARTERWORD as Integer) {
                         self.print_cmd_chr(n as QuarterWord, l);
                     }
                 },
 
                 14 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     for k in 0..=n {
                         self.print_strnumber(self.buffer[k as usize] as StrNum);
                     }
                 },
 
                 15 => {
-                    let n = read_integer_prompt!("n");
+                    let n = read_integer_prompt!(self, "n");
                     self.font_in_short_display = NULL_FONT as QuarterWord;
                     self.short_display(n);
                 }
@@ -0,0 +1,418 @@
+use super::reader::{Command, Dvi, FontDef, Page, Postamble, Preamble};
+use super::tfm::{TfmWidths, font_path, read_tfm, tfm_widths};
+use crate::io::FileSystem;
+
+use std::collections::HashMap;
+
+// Comparing DVI files
+// Two files are the same if they typeset the same pages: the commands
+// are compared once decoded, with `w0`, `x0`, `y0` and `z0` replaced by
+// the movements they stand for, so that the way a movement is encoded
+// and the locations of the commands in the files do not matter.
+// `nop` is ignored.
+
+#[derive(Clone, Copy, Default)]
+pub struct DviDiffOptions {
+    // The comment of the preamble, which holds the date of the run.
+    pub ignore_comment: bool,
+    // `\special` commands are compared without their contents.
+    pub ignore_specials: bool,
+}
+
+// Where a difference is found. Pages are numbered from 1
+// in the order of the files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DviPart {
+    Preamble,
+    Page(usize),
+    Postamble
+}
+
+// The first difference between two files.
+#[derive(Debug)]
+pub struct DviDifference {
+    pub part: DviPart,
+    // `\count0` to `\count9` of the page, without the trailing zeros.
+    pub counts: Vec<i32>,
+    // Location of the difference in each file.
+    pub locs: [usize; 2],
+    // Position on the page before the commands that differ, in DVI units.
+    // `h` is unknown after a character of a font without TFM file.
+    pub h: Option<i32>,
+    pub v: Option<i32>,
+    // What each file has there.
+    pub found: [String; 2],
+}
+
+impl DviDifference {
+    // The difference described with the names of the two files.
+    pub fn report(&self, names: [&str; 2]) -> String {
+        let mut report = match self.part {
+            DviPart::Preamble => String::from("Difference in the preamble"),
+            DviPart::Page(page) => {
+                let counts: Vec<String> = self.counts.iter().map(|count| count.to_string()).collect();
+                format!("Difference on page {page} [{}]", counts.join("."))
+            },
+            DviPart::Postamble => String::from("Difference in the postamble")
+        };
+        if let Some(v) = self.v {
+            match self.h {
+                Some(h) => report.push_str(&format!(" at h={h}, v={v}")),
+                None => report.push_str(&format!(" at h=?, v={v}"))
+            }
+        }
+        report.push_str(":\n");
+        for ((name, loc), found) in names.iter().zip(self.locs).zip(&self.found) {
+            report.push_str(&format!("  {name} (byte {loc}): {found}\n"));
+        }
+        report
+    }
+}
+
+// A command once decoded.
+#[derive(PartialEq)]
+enum Op {
+    // With `true` for `put`.
+    Char(bool, i32),
+    Rule(bool, i32, i32),
+    Push,
+    Pop,
+    Right(i32),
+    Down(i32),
+    Font(i32),
+    FontDef(FontDef),
+    Special(Vec<u8>),
+    Eop,
+}
+
+// The commands of a page with their location, decoded.
+fn page_ops(page: &Page, options: &DviDiffOptions) -> Vec<(usize, Command, Op)> {
+    let (mut w, mut x, mut y, mut z) = (0, 0, 0, 0);
+    let mut stack = vec![];
+    let mut ops = vec![];
+    for (loc, command) in &page.commands {
+        let op = match command {
+            Command::SetChar(c) | Command::Set(_, c) => Op::Char(false, *c),
+            Command::Put(_, c) => Op::Char(true, *c),
+            Command::SetRule(a, b) => Op::Rule(false, *a, *b),
+            Command::PutRule(a, b) => Op::Rule(true, *a, *b),
+            Command::Push => {
+                stack.push((w, x, y, z));
+                Op::Push
+            },
+            Command::Pop => {
+                if let Some(registers) = stack.pop() {
+                    (w, x, y, z) = registers;
+                }
+                Op::Pop
+            },
+            Command::Right(_, b) => Op::Right(*b),
+            Command::W0 => Op::Right(w),
+            Command::W(_, b) => {
+                w = *b;
+                Op::Right(w)
+            },
+            Command::X0 => Op::Right(x),
+            Command::X(_, b) => {
+                x = *b;
+                Op::Right(x)
+            },
+            Command::Down(_, a) => Op::Down(*a),
+            Command::Y0 => Op::Down(y),
+            Command::Y(_, a) => {
+                y = *a;
+                Op::Down(y)
+            },
+            Command::Z0 => Op::Down(z),
+            Command::Z(_, a) => {
+                z = *a;
+                Op::Down(z)
+            },
+            Command::FntNum(k) | Command::Fnt(_, k) => Op::Font(*k),
+            Command::FntDef(_, def) => Op::FontDef(def.clone()),
+            Command::Xxx(_, _) if options.ignore_specials => Op::Special(vec![]),
+            Command::Xxx(_, text) => Op::Special(text.clone()),
+            // `Dvi::parse` does not keep the other commands in a page.
+            _ => continue
+        };
+        ops.push((*loc, command.clone(), op));
+    }
+    ops.push((page.eop, Command::Eop, Op::Eop));
+    ops
+}
+
+// The position on the page, followed from the decoded commands.
+struct Position<'a> {
+    h: Option<i32>,
+    v: i32,
+    stack: Vec<(Option<i32>, i32)>,
+    font: i32,
+    fonts: &'a HashMap<i32, Option<TfmWidths>>,
+}
+
+impl Position<'_> {
+    fn advance(&mut self, op: &Op) {
+        match op {
+            Op::Char(false, c) => {
+                let width = self.fonts.get(&self.font)
+                    .and_then(|font| font.as_ref())
+                    .and_then(|font| font.width(*c));
+                self.h = self.h.zip(width).map(|(h, width)| h.wrapping_add(width));
+            },
+            Op::Rule(false, _, b) | Op::Right(b) => self.h = self.h.map(|h| h.wrapping_add(*b)),
+            Op::Down(a) => self.v = self.v.wrapping_add(*a),
+            Op::Push => self.stack.push((self.h, self.v)),
+            Op::Pop => {
+                if let Some((h, v)) = self.stack.pop() {
+                    (self.h, self.v) = (h, v);
+                }
+            },
+            Op::Font(k) => self.font = *k,
+            _ => ()
+        }
+    }
+}
+
+fn trimmed_counts(counts: &[i32; 10]) -> Vec<i32> {
+    let mut j = 9;
+    while counts[j] == 0 && j > 0 {
+        j -= 1;
+    }
+    counts[..=j].to_vec()
+}
+
+fn describe_preamble(preamble: &Preamble, options: &DviDiffOptions) -> String {
+    let mut text = format!(
+        "num/den={}/{}, mag={}",
+        preamble.numerator, preamble.denominator, preamble.mag
+    );
+    if !options.ignore_comment {
+        text.push_str(&format!(", comment '{}'", String::from_utf8_lossy(&preamble.comment)));
+    }
+    text
+}
+
+fn describe_postamble(postamble: &Postamble) -> String {
+    format!(
+        "maxv={}, maxh={}, maxstackdepth={}, totalpages={}",
+        postamble.max_v, postamble.max_h, postamble.max_push, postamble.total_pages
+    )
+}
+
+fn describe_font(font: Option<&FontDef>) -> String {
+    match font {
+        Some(def) => Command::FntDef(1, def.clone()).to_string(),
+        None => String::from("no such font")
+    }
+}
+
+// The first difference in a page that both files have.
+fn page_difference(
+    number: usize,
+    pages: [&Page; 2],
+    options: &DviDiffOptions,
+    fonts: &HashMap<i32, Option<TfmWidths>>
+) -> Option<DviDifference> {
+    let counts = trimmed_counts(&pages[0].counts);
+    if pages[0].counts != pages[1].counts {
+        return Some(DviDifference {
+            part: DviPart::Page(number),
+            counts,
+            locs: [pages[0].loc, pages[1].loc],
+            h: None,
+            v: None,
+            found: [
+                Command::Bop(pages[0].counts, 0).to_string(),
+                Command::Bop(pages[1].counts, 0).to_string()
+            ]
+        });
+    }
+    let ops = [page_ops(pages[0], options), page_ops(pages[1], options)];
+    let mut position = Position {
+        h: Some(0),
+        v: 0,
+        stack: vec![],
+        font: -1,
+        fonts
+    };
+    for ((loc0, command0, op0), (loc1, command1, op1)) in ops[0].iter().zip(&ops[1]) {
+        if op0 != op1 {
+            return Some(DviDifference {
+                part: DviPart::Page(number),
+                counts,
+                locs: [*loc0, *loc1],
+                h: position.h,
+                v: Some(position.v),
+                found: [command0.to_string(), command1.to_string()]
+            });
+        }
+        position.advance(op0);
+    }
+    None
+}
+
+// The first difference between the DVI files `first` and `second`, or
+// `None` if they typeset the same pages. The widths of the characters,
+// used to give the position of the difference, are read from the TFM
+// files with `file_system`, along TFMFONTS. The error tells which file
+// cannot be read.
+pub fn dvi_diff(
+    first: &[u8],
+    second: &[u8],
+    options: &DviDiffOptions,
+    file_system: &mut dyn FileSystem
+) -> Result<Option<DviDifference>, String> {
+    let first = Dvi::parse(first).map_err(|message| format!("first file: {message}"))?;
+    let second = Dvi::parse(second).map_err(|message| format!("second file: {message}"))?;
+    let outside_pages = |part: DviPart, locs: [usize; 2], found: [String; 2]| Some(DviDifference {
+        part,
+        counts: vec![],
+        locs,
+        h: None,
+        v: None,
+        found
+    });
+
+    let preambles = [&first.preamble, &second.preamble];
+    let found = preambles.map(|preamble| describe_preamble(preamble, options));
+    if found[0] != found[1] {
+        return Ok(outside_pages(DviPart::Preamble, [0, 0], found));
+    }
+
+    // All fonts are defined in the postamble.
+    let mut fonts = HashMap::new();
+    let mut font_path = font_path();
+    for def in &first.postamble.fonts {
+        let widths = read_tfm(file_system, &mut font_path, def).and_then(|tfm| tfm_widths(&tfm, def.scaled_size));
+        fonts.insert(def.number, widths);
+    }
+    for k in 0..first.pages.len().max(second.pages.len()) {
+        let difference = match (first.pages.get(k), second.pages.get(k)) {
+            (Some(page0), Some(page1)) => page_difference(k + 1, [page0, page1], options, &fonts),
+            // One of the files has no more pages.
+            (page0, page1) => {
+                let page = page0.or(page1).unwrap();
+                let describe = |page: Option<&Page>| match page {
+                    Some(page) => Command::Bop(page.counts, 0).to_string(),
+                    None => String::from("post")
+                };
+                Some(DviDifference {
+                    part: DviPart::Page(k + 1),
+                    counts: trimmed_counts(&page.counts),
+                    locs: [page0.map_or(first.post_loc, |page| page.loc), page1.map_or(second.post_loc, |page| page.loc)],
+                    h: None,
+                    v: None,
+                    found: [describe(page0), describe(page1)]
+                })
+            }
+        };
+        if difference.is_some() {
+            return Ok(difference);
+        }
+    }
+
+    let locs = [first.post_loc, second.post_loc];
+    let found = [describe_postamble(&first.postamble), describe_postamble(&second.postamble)];
+    if found[0] != found[1] {
+        return Ok(outside_pages(DviPart::Postamble, locs, found));
+    }
+    let mut fonts = [first.postamble.fonts.clone(), second.postamble.fonts.clone()];
+    for fonts in fonts.iter_mut() {
+        fonts.sort_by_key(|def| def.number);
+    }
+    for k in 0..fonts[0].len().max(fonts[1].len()) {
+        let defs = [fonts[0].get(k), fonts[1].get(k)];
+        if defs[0] != defs[1] {
+            return Ok(outside_pages(DviPart::Postamble, locs, defs.map(describe_font)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dvi::dvitype::tests::{SMALL_PAGE, dvi_file, fonts};
+
+    fn diff(first: &[u8], second: &[u8], options: DviDiffOptions) -> Option<DviDifference> {
+        dvi_diff(first, second, &options, &mut fonts()).unwrap()
+    }
+
+    fn ignoring_comments() -> DviDiffOptions {
+        DviDiffOptions {
+            ignore_comment: true,
+            ..DviDiffOptions::default()
+        }
+    }
+
+    #[test]
+    fn identical_files() {
+        let dvi = dvi_file(SMALL_PAGE);
+        assert!(diff(&dvi, &dvi, DviDiffOptions::default()).is_none());
+        // Another run only differs by the date in the comment.
+        assert!(diff(&dvi, &dvi_file(SMALL_PAGE), ignoring_comments()).is_none());
+    }
+
+    #[test]
+    fn different_comments() {
+        let dvi = dvi_file(SMALL_PAGE);
+        // The comment starts at byte 15, after ` TeX output `.
+        let mut other = dvi.clone();
+        other[15 + 12] = b'1';
+        other[15 + 13] = b'9';
+        other[15 + 14] = b'9';
+        other[15 + 15] = b'9';
+        let difference = diff(&dvi, &other, DviDiffOptions::default()).unwrap();
+        assert_eq!(difference.part, DviPart::Preamble);
+        assert_eq!(difference.locs, [0, 0]);
+        assert!(difference.found[1].starts_with("num/den=25400000/473628672, mag=1000, comment ' TeX output 1999."), "{}", difference.found[1]);
+        assert!(diff(&dvi, &other, ignoring_comments()).is_none());
+    }
+
+    #[test]
+    fn different_specials() {
+        let first = dvi_file("\\shipout\\hbox{A\\special{color push}B}\n");
+        let second = dvi_file("\\shipout\\hbox{A\\special{color pop}B}\n");
+        let difference = diff(&first, &second, ignoring_comments()).unwrap();
+        assert_eq!(difference.part, DviPart::Page(1));
+        assert_eq!(difference.found, ["xxx 'color push'".to_string(), "xxx 'color pop'".to_string()]);
+        let options = DviDiffOptions {
+            ignore_comment: true,
+            ignore_specials: true
+        };
+        assert!(diff(&first, &second, options).is_none());
+    }
+
+    #[test]
+    fn different_characters() {
+        let first = dvi_file(&SMALL_PAGE.replace('C', "F"));
+        let second = dvi_file(&SMALL_PAGE.replace('C', "X"));
+        let difference = diff(&first, &second, ignoring_comments()).unwrap();
+        assert_eq!(difference.part, DviPart::Page(1));
+        assert_eq!(difference.counts, [0]);
+        assert_eq!(difference.locs, [120, 120]);
+        assert_eq!((difference.h, difference.v), (Some(1021272), Some(447828)));
+        assert_eq!(difference.found, ["setchar70".to_string(), "setchar88".to_string()]);
+        assert_eq!(
+            difference.report(["first.dvi", "second.dvi"]),
+            "Difference on page 1 [0] at h=1021272, v=447828:\n  first.dvi (byte 120): setchar70\n  second.dvi (byte 120): setchar88\n"
+        );
+    }
+
+    #[test]
+    fn different_number_of_pages() {
+        let first = dvi_file(SMALL_PAGE);
+        let second = dvi_file(&format!("{SMALL_PAGE}\\count0=2 {SMALL_PAGE}"));
+        let difference = diff(&first, &second, ignoring_comments()).unwrap();
+        assert_eq!(difference.part, DviPart::Page(2));
+        assert_eq!(difference.counts, [2]);
+        assert_eq!(difference.found, ["post".to_string(), "bop 2.0.0.0.0.0.0.0.0.0".to_string()]);
+    }
+
+    #[test]
+    fn bad_files() {
+        let dvi = dvi_file(SMALL_PAGE);
+        let error = dvi_diff(&dvi, &dvi[..100], &DviDiffOptions::default(), &mut fonts()).unwrap_err();
+        assert_eq!(error, "second file: the file ended prematurely");
+    }
+}
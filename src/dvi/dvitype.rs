@@ -0,0 +1,1239 @@
+use super::reader::{Command, DviReader, FontDef};
+use super::tfm::{INVALID_WIDTH, font_path, read_tfm, tfm_widths};
+use crate::constants::{BOP, FNT_DEF1, ID_BYTE, POST, POST_POST, PRE};
+use crate::io::{FileSystem, SearchPath};
+
+// DVItype
+// The listing of a DVI file written by Knuth's DVItype 3.6,
+// with the same options and the same messages, so that the output of
+// two versions of TeX can be compared without other tools.
+// Fonts are loaded from their TFM files to follow the positions
+// in pixels (`hh` and `vv`) as a device driver would.
+
+const BANNER: &str = "This is DVItype, Version 3.6";
+
+// Output levels
+const ERRORS_ONLY: u8 = 0;
+const TERSE: u8 = 1;
+const MNEMONICS_ONLY: u8 = 2;
+const THE_WORKS: u8 = 4;
+
+// Section 586
+const NOP: u8 = 138;
+const FNT_DEF4: u8 = 246;
+
+const LINE_LENGTH: usize = 79;
+const STACK_SIZE: i32 = 100;
+const MAX_DRIFT: i32 = 2;
+const INFINITY: i32 = 0x7fff_ffff;
+
+pub struct DvitypeOptions {
+    // From 0 (errors only) to 4 (the works).
+    pub output_level: u8,
+    // The counts of the first page to show, `None` matches any value.
+    pub start_page: Vec<Option<i32>>,
+    pub max_pages: i32,
+    // Pixels per inch.
+    pub resolution: f64,
+    // Overrides the magnification of the file if positive.
+    pub new_mag: i32,
+}
+
+impl Default for DvitypeOptions {
+    fn default() -> Self {
+        Self {
+            output_level: THE_WORKS,
+            start_page: vec![None],
+            max_pages: 1_000_000,
+            resolution: 300.0,
+            new_mag: 0
+        }
+    }
+}
+
+impl DvitypeOptions {
+    // A starting page such as `1.*.-5`: at most ten counts
+    // separated by periods, where `*` matches any value.
+    pub fn parse_start_page(spec: &str) -> Option<Vec<Option<i32>>> {
+        let start_page = spec.split('.')
+            .map(|count| match count {
+                "*" => Some(None),
+                _ => count.parse().ok().map(Some)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if start_page.len() > 10 {
+            return None;
+        }
+        Some(start_page)
+    }
+}
+
+struct Font {
+    def: FontDef,
+    // A thin space, for the positions in pixels.
+    space: i32,
+    bc: i32,
+    ec: i32,
+    // Widths of characters `bc` to `ec`, in DVI units and in pixels.
+    widths: Vec<i32>,
+    pixel_widths: Vec<i32>,
+}
+
+struct DviType<'a> {
+    reader: DviReader<'a>,
+    file_system: &'a mut dyn FileSystem,
+    font_path: SearchPath,
+    out: String,
+
+    out_mode: u8,
+    start_page: Vec<Option<i32>>,
+    max_pages: i32,
+    resolution: f64,
+    new_mag: i32,
+
+    numerator: i32,
+    denominator: i32,
+    mag: i32,
+    conv: f64,
+    true_conv: f64,
+
+    fonts: Vec<Font>,
+    cur_font: Option<usize>,
+
+    // Characters waiting to be printed between brackets.
+    text_buf: Vec<u8>,
+    // Location of the current command, and whether it is shown.
+    a: usize,
+    showing: bool,
+
+    h: i32,
+    v: i32,
+    w: i32,
+    x: i32,
+    y: i32,
+    z: i32,
+    hh: i32,
+    vv: i32,
+    stack: Vec<[i32; 8]>,
+
+    max_v: i32,
+    max_h: i32,
+    max_s: i32,
+    max_v_so_far: i32,
+    max_h_so_far: i32,
+    max_s_so_far: i32,
+    total_pages: i32,
+    page_count: i32,
+    count: [i32; 10],
+    old_backpointer: i32,
+    in_postamble: bool,
+    started: bool,
+    after_pre: usize,
+    post_loc: usize,
+    first_backpointer: i32,
+}
+
+// Characters that are not printable ASCII are shown as '?'.
+fn xchr(c: u8) -> char {
+    match c {
+        32..=126 => c as char,
+        _ => '?'
+    }
+}
+
+fn bad_dvi(message: String) -> String {
+    format!("Bad DVI file: {message}!")
+}
+
+impl DviType<'_> {
+    fn print(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn print_ln(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.out.push('\n');
+    }
+
+    fn print_font(&mut self, f: Option<usize>) {
+        match f {
+            Some(f) => {
+                let name: String = self.fonts[f].def.area.iter()
+                    .chain(&self.fonts[f].def.name)
+                    .map(|&c| xchr(c))
+                    .collect();
+                self.print(&name);
+            },
+            None => self.print("UNDEFINED!")
+        }
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text_buf.is_empty() {
+            if self.out_mode > ERRORS_ONLY {
+                let text: String = self.text_buf.iter().map(|&c| xchr(c)).collect();
+                self.print_ln(&format!("[{text}]"));
+            }
+            self.text_buf.clear();
+        }
+    }
+
+    fn out_text(&mut self, c: u8) {
+        if self.text_buf.len() == LINE_LENGTH - 2 {
+            self.flush_text();
+        }
+        self.text_buf.push(c);
+    }
+
+    // Commands are shown with their location.
+    fn show(&mut self, s: &str) {
+        self.flush_text();
+        self.showing = true;
+        let a = self.a;
+        self.print(&format!("{a}: {s}"));
+    }
+
+    fn major(&mut self, s: &str) {
+        if self.out_mode > ERRORS_ONLY {
+            self.show(s);
+        }
+    }
+
+    fn minor(&mut self, s: &str) {
+        if self.out_mode > TERSE {
+            self.showing = true;
+            let a = self.a;
+            self.print(&format!("{a}: {s}"));
+        }
+    }
+
+    fn error(&mut self, s: &str) {
+        if !self.showing {
+            self.show(s);
+        }
+        else {
+            self.print(&format!(" {s}"));
+        }
+    }
+
+    fn pixel_round(&self, x: f64) -> i32 {
+        (self.conv * x).round() as i32
+    }
+
+    fn rule_pixels(&self, x: i32) -> i32 {
+        let n = (self.conv * x as f64).trunc();
+        if n < self.conv * x as f64 {
+            n as i32 + 1
+        }
+        else {
+            n as i32
+        }
+    }
+
+    fn font_space(&self) -> i32 {
+        self.cur_font.map_or(0, |f| self.fonts[f].space)
+    }
+
+    fn start_match(&self) -> bool {
+        self.start_page.iter()
+            .zip(self.count)
+            .all(|(start, count)| start.is_none_or(|start| start == count))
+    }
+
+    fn print_options(&mut self) {
+        self.print_ln("Options selected:");
+        let start: Vec<String> = self.start_page.iter()
+            .map(|count| count.map_or(String::from("*"), |count| count.to_string()))
+            .collect();
+        self.print_ln(&format!("  Starting page = {} ", start.join(".")));
+        self.print_ln(&format!("  Maximum number of pages = {}", self.max_pages));
+        self.print(&format!("  Output level = {}", self.out_mode));
+        match self.out_mode {
+            ERRORS_ONLY => self.print_ln(" (showing bops, fonts, and error messages only)"),
+            TERSE => self.print_ln(" (terse)"),
+            MNEMONICS_ONLY => self.print_ln(" (mnemonics)"),
+            THE_WORKS => self.print_ln(" (the works)"),
+            _ => self.print_ln(" (verbose)")
+        }
+        self.print_ln(&format!("  Resolution = {:12.8} pixels per inch", self.resolution));
+        if self.new_mag > 0 {
+            self.print_ln(&format!("  New magnification factor = {:8.3} ", self.new_mag as f64 / 1000.0));
+        }
+    }
+
+    fn process_preamble(&mut self) -> Result<(), String> {
+        if self.reader.peek() != Some(PRE) {
+            return Err(bad_dvi("First byte isn't start of preamble".to_string()));
+        }
+        let Command::Pre(preamble) = self.reader.command() else { unreachable!() };
+        if preamble.id != ID_BYTE {
+            self.print_ln(&format!("identification in byte 1 should be {ID_BYTE}!"));
+        }
+        // Compute the conversion factors
+        self.numerator = preamble.numerator;
+        self.denominator = preamble.denominator;
+        if self.numerator <= 0 {
+            return Err(bad_dvi(format!("numerator is {}", self.numerator)));
+        }
+        if self.denominator <= 0 {
+            return Err(bad_dvi(format!("denominator is {}", self.denominator)));
+        }
+        self.print_ln(&format!("numerator/denominator={}/{}", self.numerator, self.denominator));
+        self.conv = (self.numerator as f64 / 254000.0) * (self.resolution / self.denominator as f64);
+        self.mag = preamble.mag;
+        if self.new_mag > 0 {
+            self.mag = self.new_mag;
+        }
+        else if self.mag <= 0 {
+            return Err(bad_dvi(format!("magnification is {}", self.mag)));
+        }
+        self.true_conv = self.conv;
+        self.conv = self.true_conv * (self.mag as f64 / 1000.0);
+        self.print_ln(&format!("magnification={}; {:16.8} pixels per DVI unit", self.mag, self.conv));
+        let comment: String = preamble.comment.iter().map(|&c| xchr(c)).collect();
+        self.print_ln(&format!("'{comment}'"));
+        self.after_pre = self.reader.loc;
+        Ok(())
+    }
+
+    fn define_font(&mut self, def: FontDef) {
+        let e = def.number;
+        let f = self.fonts.iter().position(|font| font.def.number == e);
+        let (q, d) = (def.scaled_size, def.design_size);
+        let m = if q <= 0 || d <= 0 {
+            1000
+        }
+        else {
+            ((1000.0 * self.conv * q as f64) / (self.true_conv * d as f64)).round() as i32
+        };
+        if self.showing {
+            self.print(": ");
+        }
+        else {
+            self.print(&format!("Font {e}: "));
+        }
+        let name: String = def.area.iter().chain(&def.name).map(|&c| xchr(c)).collect();
+        if name.is_empty() {
+            self.print("null font name!");
+        }
+        self.print(&name);
+        if m != 1000 {
+            self.print(&format!(" scaled {m}"));
+        }
+        if (self.out_mode == THE_WORKS && self.in_postamble)
+            || (self.out_mode < THE_WORKS && !self.in_postamble)
+        {
+            if f.is_some() {
+                self.print_ln("---this font was already defined!");
+            }
+        }
+        else if f.is_none() {
+            self.print_ln("---this font wasn't loaded before!");
+        }
+        match f {
+            None => self.load_font(def),
+            Some(f) => self.check_font(f, &def)
+        }
+    }
+
+    fn load_font(&mut self, def: FontDef) {
+        let (c, q, d) = (def.check_sum, def.scaled_size, def.design_size);
+        match read_tfm(self.file_system, &mut self.font_path, &def) {
+            None => self.print("---not loaded, TFM file can't be opened!"),
+            Some(_) if q <= 0 || q >= 0o1000000000 => {
+                self.print(&format!("---not loaded, bad scale ({q})!"));
+            },
+            Some(_) if d <= 0 || d >= 0o1000000000 => {
+                self.print(&format!("---not loaded, bad design size ({d})!"));
+            },
+            Some(tfm) => match tfm_widths(&tfm, q) {
+                Some(tfm) => {
+                    // Finish loading the new font info
+                    if c != 0 && tfm.check_sum != 0 && c != tfm.check_sum {
+                        self.print_ln("---beware: check sums do not agree!");
+                        self.print_ln(&format!("   ({c} vs. {})", tfm.check_sum));
+                        self.print("   ");
+                    }
+                    self.print(&format!("---loaded at size {q} DVI units"));
+                    let d = ((100.0 * self.conv * q as f64) / (self.true_conv * d as f64)).round() as i32;
+                    if d != 100 {
+                        self.print_ln(" ");
+                        self.print(&format!(" (this font is magnified {d}%)"));
+                    }
+                    let pixel_widths = tfm.widths.iter()
+                        .map(|&width| match width {
+                            INVALID_WIDTH => 0,
+                            _ => (self.conv * width as f64).round() as i32
+                        })
+                        .collect();
+                    self.fonts.push(Font {
+                        def,
+                        space: q / 6,
+                        bc: tfm.bc,
+                        ec: tfm.ec,
+                        widths: tfm.widths,
+                        pixel_widths
+                    });
+                },
+                None => self.print_ln("---not loaded, TFM file is bad")
+            }
+        }
+        if self.out_mode == ERRORS_ONLY {
+            self.print_ln(" ");
+        }
+    }
+
+    fn check_font(&mut self, f: usize, def: &FontDef) {
+        let old = &self.fonts[f].def;
+        let check_sum = old.check_sum != def.check_sum;
+        let scaled_size = old.scaled_size != def.scaled_size;
+        let design_size = old.design_size != def.design_size;
+        let mismatch = old.area.iter().chain(&old.name).ne(def.area.iter().chain(&def.name));
+        if check_sum {
+            self.print_ln("---check sum doesn't match previous definition!");
+        }
+        if scaled_size {
+            self.print_ln("---scaled size doesn't match previous definition!");
+        }
+        if design_size {
+            self.print_ln("---design size doesn't match previous definition!");
+        }
+        if mismatch {
+            self.print_ln("---font name doesn't match previous definition!");
+        }
+    }
+
+
+    fn print_pixel_move(&mut self, name: &str, pixel_name: &str, x: i64, q: i64, xx: i32) {
+        if self.showing && self.out_mode > MNEMONICS_ONLY {
+            let sign = if q >= 0 { "+" } else { "" };
+            self.print(&format!(" {name}:={x}{sign}{q}={}, {pixel_name}:={xx}", x + q));
+        }
+    }
+
+    // A parameter that would take `x` out of range is changed.
+    fn check_overflow(&mut self, x: i32, q: i32) -> i32 {
+        let (x, q, infinity) = (x as i64, q as i64, INFINITY as i64);
+        let mut q = q;
+        if x > 0 && q > 0 && x > infinity - q {
+            self.error(&format!("arithmetic overflow! parameter changed from {q} to {}", infinity - x));
+            q = infinity - x;
+        }
+        if x < 0 && q < 0 && -x > q + infinity {
+            self.error(&format!("arithmetic overflow! parameter changed from {q} to {}", -x - infinity));
+            q = -x - infinity;
+        }
+        q as i32
+    }
+
+    // The pixel position `xx` follows `x`, but it does not drift
+    // by more than `MAX_DRIFT` from the rounded position.
+    fn drift(&self, x: i32, q: i32, xx: i32) -> i32 {
+        let xxx = self.pixel_round(x as f64 + q as f64);
+        if (xxx - xx).abs() > MAX_DRIFT {
+            if xxx > xx {
+                xxx - MAX_DRIFT
+            }
+            else {
+                xxx + MAX_DRIFT
+            }
+        }
+        else {
+            xx
+        }
+    }
+
+    fn move_right(&mut self, q: i32) {
+        let q = self.check_overflow(self.h, q);
+        self.hh = self.drift(self.h, q, self.hh);
+        self.print_pixel_move("h", "hh", self.h as i64, q as i64, self.hh);
+        self.h += q;
+        if self.h.abs() > self.max_h_so_far {
+            if self.h.abs() as i64 > self.max_h as i64 + 99 {
+                self.error(&format!("warning: |h|>{}!", self.max_h));
+                self.max_h = self.h.abs();
+            }
+            self.max_h_so_far = self.h.abs();
+        }
+    }
+
+    fn move_down(&mut self, p: i32) {
+        let p = self.check_overflow(self.v, p);
+        self.vv = self.drift(self.v, p, self.vv);
+        self.print_pixel_move("v", "vv", self.v as i64, p as i64, self.vv);
+        self.v += p;
+        if self.v.abs() > self.max_v_so_far {
+            if self.v.abs() as i64 > self.max_v as i64 + 99 {
+                self.error(&format!("warning: |v|>{}!", self.max_v));
+                self.max_v = self.v.abs();
+            }
+            self.max_v_so_far = self.v.abs();
+        }
+    }
+
+    // Horizontal movements larger than a thin space are
+    // interword spaces: `hh` starts again from `h`.
+    fn out_space(&mut self, s: &str, p: i32) -> Next {
+        let space = self.font_space();
+        if p >= space || p <= -4 * space {
+            self.out_text(b' ');
+            self.hh = self.pixel_round(self.h as f64 + p as f64);
+        }
+        else {
+            self.hh += self.pixel_round(p as f64);
+        }
+        self.minor(&format!("{s} {p}"));
+        Next::MoveRight(p)
+    }
+
+    fn out_vmove(&mut self, s: &str, p: i32) -> Next {
+        if p.unsigned_abs() as i64 >= 5 * self.font_space() as i64 {
+            self.vv = self.pixel_round(self.v as f64 + p as f64);
+        }
+        else {
+            self.vv += self.pixel_round(p as f64);
+        }
+        self.major(&format!("{s} {p}"));
+        Next::MoveDown(p)
+    }
+
+    fn fin_set(&mut self, put: bool, p: i32) -> Next {
+        let p = if p < 0 {
+            255 - ((-1 - p) % 256)
+        }
+        else {
+            p % 256
+        };
+        let width = self.cur_font
+            .map(|f| &self.fonts[f])
+            .filter(|font| p >= font.bc && p <= font.ec)
+            .map(|font| (font.widths[(p - font.bc) as usize], font.pixel_widths[(p - font.bc) as usize]));
+        let (q, pixel_width) = match width {
+            Some((q, pixel_width)) if q != INVALID_WIDTH => (q, pixel_width),
+            _ => {
+                self.error(&format!("character {p} invalid in font "));
+                self.print_font(self.cur_font);
+                if self.cur_font.is_some() {
+                    self.print("!");
+                }
+                (0, 0)
+            }
+        };
+        if put {
+            return Next::Done;
+        }
+        self.hh += pixel_width;
+        Next::MoveRight(q)
+    }
+
+    fn fin_rule(&mut self, put: bool, p: i32, q: i32) -> Next {
+        if self.showing {
+            self.print(&format!(" height {p}, width {q}"));
+            if self.out_mode > MNEMONICS_ONLY {
+                if p <= 0 || q <= 0 {
+                    self.print(" (invisible)");
+                }
+                else {
+                    let (height, width) = (self.rule_pixels(p), self.rule_pixels(q));
+                    self.print(&format!(" ({height}x{width} pixels)"));
+                }
+            }
+        }
+        if put {
+            return Next::Done;
+        }
+        if self.showing && self.out_mode > MNEMONICS_ONLY {
+            self.print_ln(" ");
+        }
+        self.hh += self.rule_pixels(q);
+        Next::MoveRight(q)
+    }
+
+    fn change_font(&mut self, p: i32) {
+        self.cur_font = self.fonts.iter().position(|font| font.def.number == p);
+        if self.cur_font.is_none() {
+            self.error(&format!("invalid font selection: font {p} was never defined!"));
+        }
+        if self.showing && self.out_mode > MNEMONICS_ONLY {
+            self.print(" current font is ");
+            self.print_font(self.cur_font);
+        }
+    }
+
+    fn push(&mut self) -> Next {
+        self.major("push");
+        let s = self.stack.len() as i32;
+        if s == self.max_s_so_far {
+            self.max_s_so_far = s + 1;
+            if s == self.max_s {
+                self.error("deeper than claimed in postamble!");
+            }
+            if s == STACK_SIZE {
+                self.error(&format!("DVItype capacity exceeded (stack size={STACK_SIZE})"));
+                return Next::Stop;
+            }
+        }
+        self.stack.push([self.h, self.v, self.w, self.x, self.y, self.z, self.hh, self.vv]);
+        Next::ShowState(s)
+    }
+
+    fn pop(&mut self) -> Next {
+        self.major("pop");
+        match self.stack.pop() {
+            Some([h, v, w, x, y, z, hh, vv]) => {
+                (self.h, self.v, self.w, self.x, self.y, self.z, self.hh, self.vv) = (h, v, w, x, y, z, hh, vv);
+            },
+            None => self.error("(illegal at level zero)!")
+        }
+        Next::ShowState(self.stack.len() as i32)
+    }
+
+    // The commands of a page, up to `eop`. It is `false` if the page
+    // ends with an error.
+    fn do_page(&mut self) -> Result<bool, String> {
+        self.cur_font = None;
+        self.stack.clear();
+        (self.h, self.v, self.w, self.x, self.y, self.z, self.hh, self.vv) = (0, 0, 0, 0, 0, 0, 0, 0);
+        loop {
+            self.a = self.reader.loc;
+            self.showing = false;
+            let command = self.reader.command();
+            if self.reader.eof() {
+                return Err(bad_dvi("the file ended prematurely".to_string()));
+            }
+            let next = match command {
+                Command::SetChar(p) => {
+                    if p > b' ' as i32 && p <= b'~' as i32 {
+                        self.out_text(p as u8);
+                        self.minor(&format!("setchar{p}"));
+                    }
+                    else {
+                        self.major(&format!("setchar{p}"));
+                    }
+                    Next::FinSet(false, p)
+                },
+                Command::Set(n, p) => {
+                    self.major(&format!("set{n} {p}"));
+                    Next::FinSet(false, p)
+                },
+                Command::Put(n, p) => {
+                    self.major(&format!("put{n} {p}"));
+                    Next::FinSet(true, p)
+                },
+                Command::SetRule(p, q) => {
+                    self.major("setrule");
+                    Next::FinRule(false, p, q)
+                },
+                Command::PutRule(p, q) => {
+                    self.major("putrule");
+                    Next::FinRule(true, p, q)
+                },
+                Command::Nop => {
+                    self.minor("nop");
+                    Next::Done
+                },
+                Command::Bop(..) => {
+                    self.error("bop occurred before eop!");
+                    Next::Stop
+                },
+                Command::Eop => {
+                    self.major("eop");
+                    if !self.stack.is_empty() {
+                        self.error(&format!("stack not empty at end of page (level {})!", self.stack.len()));
+                    }
+                    self.print_ln(" ");
+                    return Ok(true);
+                },
+                Command::Push => self.push(),
+                Command::Pop => self.pop(),
+                Command::Right(n, p) => self.out_space(&format!("right{n}"), p),
+                Command::W0 => self.out_space("w0", self.w),
+                Command::W(n, p) => {
+                    self.w = p;
+                    self.out_space(&format!("w{n}"), p)
+                },
+                Command::X0 => self.out_space("x0", self.x),
+                Command::X(n, p) => {
+                    self.x = p;
+                    self.out_space(&format!("x{n}"), p)
+                },
+                Command::Down(n, p) => self.out_vmove(&format!("down{n}"), p),
+                Command::Y0 => self.out_vmove("y0", self.y),
+                Command::Y(n, p) => {
+                    self.y = p;
+                    self.out_vmove(&format!("y{n}"), p)
+                },
+                Command::Z0 => self.out_vmove("z0", self.z),
+                Command::Z(n, p) => {
+                    self.z = p;
+                    self.out_vmove(&format!("z{n}"), p)
+                },
+                Command::FntNum(p) => {
+                    self.major(&format!("fntnum{p}"));
+                    Next::ChangeFont(p)
+                },
+                Command::Fnt(n, p) => {
+                    self.major(&format!("fnt{n} {p}"));
+                    Next::ChangeFont(p)
+                },
+                Command::FntDef(n, def) => {
+                    self.major(&format!("fntdef{n} {}", def.number));
+                    self.define_font(def);
+                    Next::Done
+                },
+                Command::Xxx(_, bytes) => {
+                    self.major("xxx '");
+                    if self.showing {
+                        let text: String = bytes.iter().map(|&c| xchr(c)).collect();
+                        self.print(&format!("{text}'"));
+                    }
+                    if bytes.iter().any(|&c| !(b' '..=b'~').contains(&c)) {
+                        self.error("non-ASCII character in xxx command!");
+                    }
+                    Next::Done
+                },
+                Command::Pre(_) => {
+                    self.error("preamble command within a page!");
+                    Next::Stop
+                },
+                Command::Post(_) | Command::PostPost(..) => {
+                    self.error("postamble command within a page!");
+                    Next::Stop
+                },
+                Command::Undefined(o) => {
+                    self.error(&format!("undefined command {o}!"));
+                    Next::Done
+                }
+            };
+            let next = match next {
+                Next::FinSet(put, p) => self.fin_set(put, p),
+                Next::FinRule(put, p, q) => self.fin_rule(put, p, q),
+                next => next
+            };
+            match next {
+                Next::MoveRight(q) => self.move_right(q),
+                Next::MoveDown(p) => self.move_down(p),
+                Next::ShowState(ss) => {
+                    if self.showing && self.out_mode > MNEMONICS_ONLY {
+                        self.print_ln(" ");
+                        self.print(&format!(
+                            "level {ss}:(h={},v={},w={},x={},y={},z={},hh={},vv={})",
+                            self.h, self.v, self.w, self.x, self.y, self.z, self.hh, self.vv
+                        ));
+                    }
+                },
+                Next::ChangeFont(p) => self.change_font(p),
+                Next::Stop => {
+                    self.print_ln("!");
+                    return Ok(false);
+                },
+                _ => ()
+            }
+            if self.showing {
+                self.print_ln(" ");
+            }
+        }
+    }
+
+    // Font definitions and `nop` are skipped up to the next `bop` or `post`.
+    fn scan_bop(&mut self) -> Result<(), String> {
+        loop {
+            if self.reader.eof() {
+                return Err(bad_dvi("the file ended prematurely".to_string()));
+            }
+            match self.reader.peek() {
+                Some(NOP | FNT_DEF1..=FNT_DEF4) => {
+                    if let Command::FntDef(_, def) = self.reader.command() {
+                        self.define_font(def);
+                    }
+                },
+                _ => break
+            }
+        }
+        let k = self.reader.get_byte();
+        if k == POST {
+            self.in_postamble = true;
+            return Ok(());
+        }
+        if k != BOP {
+            return Err(bad_dvi(format!("byte {} is not bop", self.reader.loc - 1)));
+        }
+        let new_backpointer = self.reader.loc as i32 - 1;
+        self.page_count += 1;
+        for k in 0..10 {
+            self.count[k] = self.reader.signed_quad();
+        }
+        if self.reader.signed_quad() != self.old_backpointer {
+            self.print_ln(&format!(
+                "backpointer in byte {} should be {}!",
+                self.reader.loc - 4,
+                self.old_backpointer
+            ));
+        }
+        self.old_backpointer = new_backpointer;
+        Ok(())
+    }
+
+    // Pages are skipped up to the starting page, or to the postamble.
+    fn skip_pages(&mut self, bop_seen: bool) -> Result<(), String> {
+        self.showing = false;
+        let mut bop_seen = bop_seen;
+        loop {
+            if !bop_seen {
+                self.scan_bop()?;
+                if self.in_postamble {
+                    return Ok(());
+                }
+                if !self.started && self.start_match() {
+                    self.started = true;
+                    return Ok(());
+                }
+            }
+            // Skip until finding `eop`
+            loop {
+                if self.reader.eof() {
+                    return Err(bad_dvi("the file ended prematurely".to_string()));
+                }
+                let loc = self.reader.loc;
+                match self.reader.command() {
+                    Command::Eop => break,
+                    Command::FntDef(_, def) => {
+                        self.define_font(def);
+                        self.print_ln(" ");
+                    },
+                    Command::Bop(..)
+                    | Command::Pre(_)
+                    | Command::Post(_)
+                    | Command::PostPost(..)
+                    | Command::Undefined(_) => return Err(bad_dvi(format!("illegal command at byte {loc}"))),
+                    _ => ()
+                }
+            }
+            bop_seen = false;
+        }
+    }
+
+    fn translate_pages(&mut self) -> Result<(), String> {
+        while self.max_pages > 0 {
+            self.max_pages -= 1;
+            self.print_ln(" ");
+            let counts: Vec<String> = self.count[..self.start_page.len()].iter()
+                .map(|count| count.to_string())
+                .collect();
+            self.print_ln(&format!("{}: beginning of page {} ", self.reader.loc - 45, counts.join(".")));
+            if !self.do_page()? {
+                return Err(bad_dvi("page ended unexpectedly".to_string()));
+            }
+            self.scan_bop()?;
+            if self.in_postamble {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // The postamble is found from the end of the file.
+    fn find_postamble(&mut self) -> Result<(), String> {
+        let n = self.reader.len();
+        if n < 53 {
+            return Err(bad_dvi(format!("only {n} bytes long")));
+        }
+        let mut m = n - 4;
+        let k = loop {
+            if m == 0 {
+                return Err(bad_dvi("all 223s".to_string()));
+            }
+            self.reader.move_to_byte(m);
+            let k = self.reader.get_byte();
+            m -= 1;
+            if k != 223 {
+                break k;
+            }
+        };
+        if k != ID_BYTE {
+            return Err(bad_dvi(format!("ID byte is {k}")));
+        }
+        let m = m.saturating_sub(3);
+        self.reader.move_to_byte(m);
+        let q = self.reader.signed_quad();
+        if q < 0 || q as i64 > m as i64 - 30 {
+            return Err(bad_dvi(format!("post pointer {q} at byte {m}")));
+        }
+        self.reader.move_to_byte(q as usize);
+        if self.reader.get_byte() != POST {
+            return Err(bad_dvi(format!("byte {q} is not post")));
+        }
+        self.post_loc = q as usize;
+        self.first_backpointer = self.reader.signed_quad();
+        Ok(())
+    }
+
+    fn read_postamble(&mut self) -> Result<(), String> {
+        self.showing = false;
+        self.post_loc = self.reader.loc - 5;
+        self.print_ln(&format!("Postamble starts at byte {}.", self.post_loc));
+        if self.reader.signed_quad() != self.numerator {
+            self.print_ln("numerator doesn't match the preamble!");
+        }
+        if self.reader.signed_quad() != self.denominator {
+            self.print_ln("denominator doesn't match the preamble!");
+        }
+        if self.reader.signed_quad() != self.mag && self.new_mag == 0 {
+            self.print_ln("magnification doesn't match the preamble!");
+        }
+        self.max_v = self.reader.signed_quad();
+        self.max_h = self.reader.signed_quad();
+        self.max_s = self.reader.get_two_bytes();
+        self.total_pages = self.reader.get_two_bytes();
+        self.print_ln(&format!(
+            "maxv={}, maxh={}, maxstackdepth={}, totalpages={}",
+            self.max_v, self.max_h, self.max_s, self.total_pages
+        ));
+        if self.out_mode < THE_WORKS {
+            // Compare the lust parameters with the accumulated facts
+            if self.max_v as i64 + 99 < self.max_v_so_far as i64 {
+                self.print_ln(&format!("warning: observed maxv was {}", self.max_v_so_far));
+            }
+            if self.max_h as i64 + 99 < self.max_h_so_far as i64 {
+                self.print_ln(&format!("warning: observed maxh was {}", self.max_h_so_far));
+            }
+            if self.max_s < self.max_s_so_far {
+                self.print_ln(&format!("warning: observed maxstackdepth was {}", self.max_s_so_far));
+            }
+            if self.page_count != self.total_pages {
+                self.print_ln(&format!("there are really {} pages, not {}!", self.page_count, self.total_pages));
+            }
+        }
+        // Process the font definitions of the postamble
+        while let Some(NOP | FNT_DEF1..=FNT_DEF4) = self.reader.peek() {
+            if let Command::FntDef(_, def) = self.reader.command() {
+                self.define_font(def);
+                self.print_ln(" ");
+            }
+        }
+        if self.reader.get_byte() != POST_POST {
+            self.print_ln(&format!("byte {} is not postpost!", self.reader.loc - 1));
+        }
+        // Make sure that the end of the file is well-formed
+        if self.reader.signed_quad() != self.post_loc as i32 {
+            self.print_ln(&format!("bad postamble pointer in byte {}!", self.reader.loc - 4));
+        }
+        if self.reader.get_byte() != ID_BYTE {
+            self.print_ln(&format!("identification in byte {} should be {ID_BYTE}!", self.reader.loc - 1));
+        }
+        let k = self.reader.loc;
+        let mut m = 223;
+        while m == 223 && !self.reader.eof() {
+            m = self.reader.get_byte();
+        }
+        if !self.reader.eof() {
+            return Err(bad_dvi(format!("signature in byte {} should be 223", self.reader.loc - 1)));
+        }
+        if self.reader.loc < k + 4 {
+            self.print_ln(&format!("not enough signature bytes at end of file ({})", self.reader.loc - k));
+        }
+        Ok(())
+    }
+
+    // With the postamble read first, the pages are counted by following
+    // the pointers from the last `bop`, and the starting page is found.
+    fn count_pages(&mut self) -> Result<(), String> {
+        let mut q = self.post_loc as i64;
+        let mut p = self.first_backpointer;
+        let mut start_loc = None;
+        if p < 0 {
+            self.in_postamble = true;
+        }
+        else {
+            loop {
+                if p as i64 > q - 46 {
+                    return Err(bad_dvi(format!("page link {p} after byte {q}")));
+                }
+                q = p as i64;
+                self.reader.move_to_byte(q as usize);
+                if self.reader.get_byte() != BOP {
+                    return Err(bad_dvi(format!("byte {q} is not bop")));
+                }
+                self.page_count += 1;
+                for k in 0..10 {
+                    self.count[k] = self.reader.signed_quad();
+                }
+                p = self.reader.signed_quad();
+                if self.start_match() {
+                    start_loc = Some(q as usize);
+                    self.old_backpointer = p;
+                }
+                if p < 0 {
+                    break;
+                }
+            }
+            let Some(mut start_loc) = start_loc else {
+                return Err("starting page number could not be found!".to_string());
+            };
+            // Starting from the first page, the font definitions
+            // before it are read too.
+            if self.old_backpointer < 0 {
+                start_loc = self.after_pre;
+            }
+            self.reader.move_to_byte(start_loc);
+        }
+        if self.page_count != self.total_pages {
+            self.print_ln(&format!("there are really {} pages, not {}!", self.page_count, self.total_pages));
+        }
+        Ok(())
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        self.print_ln(BANNER);
+        self.print_options();
+        self.process_preamble()?;
+        if self.out_mode == THE_WORKS {
+            self.find_postamble()?;
+            self.in_postamble = true;
+            self.read_postamble()?;
+            self.in_postamble = false;
+            self.count_pages()?;
+        }
+        if !self.in_postamble {
+            self.skip_pages(false)?;
+        }
+        if !self.in_postamble {
+            self.translate_pages()?;
+        }
+        if self.out_mode < THE_WORKS {
+            if !self.in_postamble {
+                self.skip_pages(true)?;
+            }
+            if self.reader.signed_quad() != self.old_backpointer {
+                self.print_ln(&format!(
+                    "backpointer in byte {} should be {}!",
+                    self.reader.loc - 4,
+                    self.old_backpointer
+                ));
+            }
+            self.read_postamble()?;
+        }
+        Ok(())
+    }
+}
+
+// How a command is finished once its parameters are known.
+enum Next {
+    Done,
+    FinSet(bool, i32),
+    FinRule(bool, i32, i32),
+    MoveRight(i32),
+    MoveDown(i32),
+    ShowState(i32),
+    ChangeFont(i32),
+    Stop,
+}
+
+// The listing of the DVI file `bytes` with the `options` of DVItype.
+// TFM files are read with `file_system`, along TFMFONTS as TeX looks
+// for them. If DVItype has to stop, the error is the listing up to
+// the message that explains why.
+pub fn dvitype(bytes: &[u8], options: &DvitypeOptions, file_system: &mut dyn FileSystem) -> Result<String, String> {
+    let mut dvitype = DviType {
+        reader: DviReader::new(bytes),
+        file_system,
+        font_path: font_path(),
+        out: String::new(),
+        out_mode: options.output_level.min(THE_WORKS),
+        start_page: options.start_page.clone(),
+        max_pages: options.max_pages,
+        resolution: options.resolution,
+        new_mag: options.new_mag,
+        numerator: 0,
+        denominator: 0,
+        mag: 0,
+        conv: 0.0,
+        true_conv: 0.0,
+        fonts: vec![],
+        cur_font: None,
+        text_buf: vec![],
+        a: 0,
+        showing: false,
+        h: 0,
+        v: 0,
+        w: 0,
+        x: 0,
+        y: 0,
+        z: 0,
+        hh: 0,
+        vv: 0,
+        stack: vec![],
+        max_v: INFINITY - 99,
+        max_h: INFINITY - 99,
+        max_s: STACK_SIZE + 1,
+        max_v_so_far: 0,
+        max_h_so_far: 0,
+        max_s_so_far: 0,
+        total_pages: 0,
+        page_count: 0,
+        count: [0; 10],
+        old_backpointer: -1,
+        in_postamble: false,
+        started: false,
+        after_pre: 0,
+        post_loc: 0,
+        first_backpointer: 0
+    };
+    if dvitype.start_page.is_empty() {
+        dvitype.start_page.push(None);
+    }
+    match dvitype.run() {
+        Ok(()) => Ok(dvitype.out),
+        Err(message) => {
+            dvitype.print_ln(&format!(" {message}"));
+            Err(dvitype.out)
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+    use crate::io::MemoryFileSystem;
+
+    const CMR10: &[u8] = include_bytes!("../../TeXfonts/cmr10.tfm");
+
+    // A page with two characters, a kern, a box with a character and a rule.
+    pub(crate) const SMALL_PAGE: &str = "\\shipout\\hbox{AB\\kern1pt\\hbox{C}\\vrule width 2pt height 3pt}\n";
+
+    // The DVI file of `pages` typeset with cmr10.
+    pub(crate) fn dvi_file(pages: &str) -> Vec<u8> {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let options = CompileOptions {
+            format: &format,
+            inputs: &[("TeXfonts/cmr10.tfm", CMR10)],
+            ..CompileOptions::default()
+        };
+        let output = compile(&format!("\\font\\x=cmr10 \\x\n{pages}\\end\n"), &options);
+        output.dvi.expect("no DVI file")
+    }
+
+    // The TFM files of the fonts, where DVItype looks for them.
+    pub(crate) fn fonts() -> MemoryFileSystem {
+        let file_system = MemoryFileSystem::new();
+        file_system.insert("TeXfonts/cmr10.tfm", CMR10.to_vec());
+        file_system
+    }
+
+    fn options(output_level: u8) -> DvitypeOptions {
+        DvitypeOptions {
+            output_level,
+            ..DvitypeOptions::default()
+        }
+    }
+
+    // The lines of `listing` from the first one that starts with `first`.
+    fn lines_from<'a>(listing: &'a str, first: &str) -> Vec<&'a str> {
+        listing.lines().skip_while(|line| !line.starts_with(first)).collect()
+    }
+
+    #[test]
+    fn the_works() {
+        let listing = dvitype(&dvi_file(SMALL_PAGE), &options(THE_WORKS), &mut fonts()).unwrap();
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines[..8], [
+            "This is DVItype, Version 3.6",
+            "Options selected:",
+            "  Starting page = * ",
+            "  Maximum number of pages = 1000000",
+            "  Output level = 4 (the works)",
+            "  Resolution = 300.00000000 pixels per inch",
+            "numerator/denominator=25400000/473628672",
+            "magnification=1000;       0.00006334 pixels per DVI unit"
+        ]);
+        assert!(lines[8].starts_with("' TeX output "), "{}", lines[8]);
+        assert_eq!(lines[9..], [
+            "Postamble starts at byte 136.",
+            "maxv=447828, maxh=1625660, maxstackdepth=1, totalpages=1",
+            "Font 0: cmr10---loaded at size 655360 DVI units ",
+            " ",
+            "42: beginning of page 0 ",
+            "87: down3 447828 v:=0+447828=447828, vv:=28 ",
+            "91: fntdef1 0: cmr10 ",
+            "112: fntnum0 current font is cmr10 ",
+            "113: setchar65 h:=0+491521=491521, hh:=31 ",
+            "114: setchar66 h:=491521+464215=955736, hh:=60 ",
+            "[AB]",
+            "115: push ",
+            "level 0:(h=955736,v=447828,w=0,x=0,y=0,z=0,hh=60,vv=28) ",
+            "116: right3 65536 h:=955736+65536=1021272, hh:=64 ",
+            "120: setchar67 h:=1021272+473316=1494588, hh:=94 ",
+            "[C]",
+            "121: pop ",
+            "level 0:(h=955736,v=447828,w=0,x=0,y=0,z=0,hh=60,vv=28) ",
+            "122: right3 538852 h:=955736+538852=1494588, hh:=95 ",
+            "[ ]",
+            "126: setrule height 196608, width 131072 (13x9 pixels) ",
+            " h:=1494588+131072=1625660, hh:=104 ",
+            "135: eop "
+        ]);
+    }
+
+    // The postamble comes last, once the pages are read.
+    #[test]
+    fn mnemonics() {
+        let listing = dvitype(&dvi_file(SMALL_PAGE), &options(MNEMONICS_ONLY), &mut fonts()).unwrap();
+        assert_eq!(lines_from(&listing, "113:"), [
+            "113: setchar65 ",
+            "114: setchar66 ",
+            "[AB]",
+            "115: push ",
+            "116: right3 65536 ",
+            "120: setchar67 ",
+            "[C]",
+            "121: pop ",
+            "122: right3 538852 ",
+            "[ ]",
+            "126: setrule height 196608, width 131072 ",
+            "135: eop ",
+            "Postamble starts at byte 136.",
+            "maxv=447828, maxh=1625660, maxstackdepth=1, totalpages=1",
+            "Font 0: cmr10 "
+        ]);
+    }
+
+    #[test]
+    fn missing_font() {
+        let listing = dvitype(&dvi_file(SMALL_PAGE), &options(MNEMONICS_ONLY), &mut MemoryFileSystem::new()).unwrap();
+        assert!(listing.contains("91: fntdef1 0: cmr10---not loaded, TFM file can't be opened!"), "{listing}");
+    }
+
+    #[test]
+    fn truncated_file() {
+        let dvi = dvi_file(SMALL_PAGE);
+        let listing = dvitype(&dvi[..40], &options(THE_WORKS), &mut fonts()).unwrap_err();
+        assert!(listing.ends_with(" Bad DVI file: only 40 bytes long!\n"), "{listing}");
+        // Without the postamble, the pages are still listed
+        // up to the end of the file.
+        let listing = dvitype(&dvi[..118], &options(MNEMONICS_ONLY), &mut fonts()).unwrap_err();
+        assert!(listing.ends_with("115: push \n Bad DVI file: the file ended prematurely!\n"), "{listing}");
+    }
+
+    #[test]
+    fn bad_postamble_pointer() {
+        let mut dvi = dvi_file(SMALL_PAGE);
+        // The pointer follows `post_post`, before the identification byte
+        // and the 223s.
+        let q = dvi.iter().rposition(|&b| b != 223).unwrap() - 4;
+        assert_eq!(dvi[q - 1], POST_POST);
+        dvi[q..q + 4].copy_from_slice(&1000i32.to_be_bytes());
+        let listing = dvitype(&dvi, &options(THE_WORKS), &mut fonts()).unwrap_err();
+        assert!(listing.ends_with(&format!(" Bad DVI file: post pointer 1000 at byte {q}!\n")), "{listing}");
+
+        dvi[q..q + 4].copy_from_slice(&100i32.to_be_bytes());
+        let listing = dvitype(&dvi, &options(THE_WORKS), &mut fonts()).unwrap_err();
+        assert!(listing.ends_with(" Bad DVI file: byte 100 is not post!\n"), "{listing}");
+
+        // Read in order, the pointer is only reported.
+        let listing = dvitype(&dvi, &options(MNEMONICS_ONLY), &mut fonts()).unwrap();
+        assert!(listing.ends_with(&format!("Font 0: cmr10 \nbad postamble pointer in byte {q}!\n")), "{listing}");
+    }
+}
@@ -0,0 +1,450 @@
+use std::fmt;
+
+// Reading DVI files
+// A DVI file is a preamble, pages between `bop` and `eop`, and a postamble
+// listing the fonts, as written by Part 31 and Part 32.
+// `DviReader` decodes one command at a time, `Dvi::parse` reads a whole file.
+
+// The preamble: `pre i num den mag k x[k]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preamble {
+    pub id: u8,
+    pub numerator: i32,
+    pub denominator: i32,
+    pub mag: i32,
+    pub comment: Vec<u8>,
+}
+
+// A font definition `fnt_def k c s d a l n[a+l]`,
+// found in the pages and in the postamble.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontDef {
+    pub number: i32,
+    pub check_sum: i32,
+    pub scaled_size: i32,
+    pub design_size: i32,
+    pub area: Vec<u8>,
+    pub name: Vec<u8>,
+}
+
+// The postamble: `post p num den mag l u s t`, followed by the
+// font definitions and `post_post q i 223...`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Postamble {
+    // Location of the last `bop`.
+    pub last_bop: i32,
+    pub numerator: i32,
+    pub denominator: i32,
+    pub mag: i32,
+    pub max_v: i32,
+    pub max_h: i32,
+    pub max_push: i32,
+    pub total_pages: i32,
+    pub fonts: Vec<FontDef>,
+}
+
+// A DVI command with its parameters. When a command exists in several
+// sizes, the first field is the size in bytes of its first parameter
+// (`right3` is `Right(3, b)`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    SetChar(i32),
+    Set(u8, i32),
+    // Height and width.
+    SetRule(i32, i32),
+    Put(u8, i32),
+    PutRule(i32, i32),
+    Nop,
+    // The ten counts and the location of the previous `bop`.
+    Bop([i32; 10], i32),
+    Eop,
+    Push,
+    Pop,
+    Right(u8, i32),
+    W0,
+    W(u8, i32),
+    X0,
+    X(u8, i32),
+    Down(u8, i32),
+    Y0,
+    Y(u8, i32),
+    Z0,
+    Z(u8, i32),
+    FntNum(i32),
+    Fnt(u8, i32),
+    Xxx(u8, Vec<u8>),
+    FntDef(u8, FontDef),
+    Pre(Preamble),
+    // Without its fonts, which are the commands that follow.
+    Post(Postamble),
+    // The location of `post` and the identification byte.
+    PostPost(i32, u8),
+    Undefined(u8),
+}
+
+// A page: the location of its `bop`, its counts, the location
+// of the previous `bop`, its commands with their location,
+// and the location of its `eop`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Page {
+    pub loc: usize,
+    pub counts: [i32; 10],
+    pub prev: i32,
+    pub commands: Vec<(usize, Command)>,
+    pub eop: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dvi {
+    pub preamble: Preamble,
+    pub pages: Vec<Page>,
+    pub postamble: Postamble,
+    // Location of `post`.
+    pub post_loc: usize,
+}
+
+// Commands are shown with the names of DVItype, such as `setchar65`,
+// `right3 -65536` or `xxx 'color push'`.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::SetChar(c) => write!(f, "setchar{c}"),
+            Command::Set(n, c) => write!(f, "set{n} {c}"),
+            Command::SetRule(a, b) => write!(f, "setrule height {a}, width {b}"),
+            Command::Put(n, c) => write!(f, "put{n} {c}"),
+            Command::PutRule(a, b) => write!(f, "putrule height {a}, width {b}"),
+            Command::Nop => write!(f, "nop"),
+            Command::Bop(counts, _) => {
+                let counts: Vec<String> = counts.iter().map(|count| count.to_string()).collect();
+                write!(f, "bop {}", counts.join("."))
+            },
+            Command::Eop => write!(f, "eop"),
+            Command::Push => write!(f, "push"),
+            Command::Pop => write!(f, "pop"),
+            Command::Right(n, b) => write!(f, "right{n} {b}"),
+            Command::W0 => write!(f, "w0"),
+            Command::W(n, b) => write!(f, "w{n} {b}"),
+            Command::X0 => write!(f, "x0"),
+            Command::X(n, b) => write!(f, "x{n} {b}"),
+            Command::Down(n, a) => write!(f, "down{n} {a}"),
+            Command::Y0 => write!(f, "y0"),
+            Command::Y(n, a) => write!(f, "y{n} {a}"),
+            Command::Z0 => write!(f, "z0"),
+            Command::Z(n, a) => write!(f, "z{n} {a}"),
+            Command::FntNum(k) => write!(f, "fntnum{k}"),
+            Command::Fnt(n, k) => write!(f, "fnt{n} {k}"),
+            Command::Xxx(_, x) => write!(f, "xxx '{}'", String::from_utf8_lossy(x)),
+            Command::FntDef(n, def) => write!(
+                f,
+                "fntdef{n} {}: {}{} at {} (design size {}, check sum {})",
+                def.number,
+                String::from_utf8_lossy(&def.area),
+                String::from_utf8_lossy(&def.name),
+                def.scaled_size,
+                def.design_size,
+                def.check_sum
+            ),
+            Command::Pre(preamble) => write!(f, "pre '{}'", String::from_utf8_lossy(&preamble.comment)),
+            Command::Post(_) => write!(f, "post"),
+            Command::PostPost(..) => write!(f, "post_post"),
+            Command::Undefined(o) => write!(f, "undefined command {o}")
+        }
+    }
+}
+
+pub(crate) struct DviReader<'a> {
+    bytes: &'a [u8],
+    // Location of the next byte.
+    pub(crate) loc: usize,
+}
+
+impl<'a> DviReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, loc: 0 }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub(crate) fn eof(&self) -> bool {
+        self.loc >= self.bytes.len()
+    }
+
+    pub(crate) fn move_to_byte(&mut self, loc: usize) {
+        self.loc = loc;
+    }
+
+    // The next byte, without reading it.
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.loc).copied()
+    }
+
+    // Zero is read past the end of the file.
+    pub(crate) fn get_byte(&mut self) -> u8 {
+        match self.bytes.get(self.loc) {
+            Some(&b) => {
+                self.loc += 1;
+                b
+            },
+            None => 0
+        }
+    }
+
+    // An unsigned integer of `n` bytes, except that four bytes are signed.
+    fn unsigned(&mut self, n: u8) -> i32 {
+        if n == 4 {
+            return self.signed(4);
+        }
+        (0..n).fold(0, |a, _| a * 256 + self.get_byte() as i32)
+    }
+
+    // A signed integer of `n` bytes.
+    fn signed(&mut self, n: u8) -> i32 {
+        let first = self.get_byte() as i8 as i32;
+        (1..n).fold(first, |a, _| a.wrapping_mul(256).wrapping_add(self.get_byte() as i32))
+    }
+
+    pub(crate) fn get_two_bytes(&mut self) -> i32 {
+        self.unsigned(2)
+    }
+
+    pub(crate) fn signed_quad(&mut self) -> i32 {
+        self.signed(4)
+    }
+
+    fn bytes(&mut self, n: i32) -> Vec<u8> {
+        (0..n).map(|_| self.get_byte()).collect()
+    }
+
+    fn font_def(&mut self, n: u8) -> FontDef {
+        let number = self.unsigned(n);
+        let check_sum = self.signed_quad();
+        let scaled_size = self.signed_quad();
+        let design_size = self.signed_quad();
+        let a = self.get_byte() as i32;
+        let l = self.get_byte() as i32;
+        FontDef {
+            number,
+            check_sum,
+            scaled_size,
+            design_size,
+            area: self.bytes(a),
+            name: self.bytes(l)
+        }
+    }
+
+    // The command at `loc`, with its parameters.
+    pub(crate) fn command(&mut self) -> Command {
+        let o = self.get_byte();
+        match o {
+            0..=127 => Command::SetChar(o as i32),
+            128..=131 => Command::Set(o - 127, self.unsigned(o - 127)),
+            132 => Command::SetRule(self.signed_quad(), self.signed_quad()),
+            133..=136 => Command::Put(o - 132, self.unsigned(o - 132)),
+            137 => Command::PutRule(self.signed_quad(), self.signed_quad()),
+            138 => Command::Nop,
+            139 => {
+                let mut counts = [0; 10];
+                for count in counts.iter_mut() {
+                    *count = self.signed_quad();
+                }
+                Command::Bop(counts, self.signed_quad())
+            },
+            140 => Command::Eop,
+            141 => Command::Push,
+            142 => Command::Pop,
+            143..=146 => Command::Right(o - 142, self.signed(o - 142)),
+            147 => Command::W0,
+            148..=151 => Command::W(o - 147, self.signed(o - 147)),
+            152 => Command::X0,
+            153..=156 => Command::X(o - 152, self.signed(o - 152)),
+            157..=160 => Command::Down(o - 156, self.signed(o - 156)),
+            161 => Command::Y0,
+            162..=165 => Command::Y(o - 161, self.signed(o - 161)),
+            166 => Command::Z0,
+            167..=170 => Command::Z(o - 166, self.signed(o - 166)),
+            171..=234 => Command::FntNum(o as i32 - 171),
+            235..=238 => Command::Fnt(o - 234, self.unsigned(o - 234)),
+            239..=242 => {
+                let k = self.unsigned(o - 238);
+                Command::Xxx(o - 238, self.bytes(k))
+            },
+            243..=246 => Command::FntDef(o - 242, self.font_def(o - 242)),
+            247 => {
+                let id = self.get_byte();
+                let numerator = self.signed_quad();
+                let denominator = self.signed_quad();
+                let mag = self.signed_quad();
+                let k = self.get_byte() as i32;
+                Command::Pre(Preamble {
+                    id,
+                    numerator,
+                    denominator,
+                    mag,
+                    comment: self.bytes(k)
+                })
+            },
+            248 => Command::Post(Postamble {
+                last_bop: self.signed_quad(),
+                numerator: self.signed_quad(),
+                denominator: self.signed_quad(),
+                mag: self.signed_quad(),
+                max_v: self.signed_quad(),
+                max_h: self.signed_quad(),
+                max_push: self.get_two_bytes(),
+                total_pages: self.get_two_bytes(),
+                fonts: vec![]
+            }),
+            249 => Command::PostPost(self.signed_quad(), self.get_byte()),
+            _ => Command::Undefined(o)
+        }
+    }
+}
+
+impl Dvi {
+    // Reads the file from the beginning to `post_post`.
+    // The error message tells what is wrong and where.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = DviReader::new(bytes);
+        let preamble = match reader.command() {
+            Command::Pre(preamble) => preamble,
+            _ => return Err("First byte isn't start of preamble".to_string())
+        };
+        let mut pages = vec![];
+        loop {
+            if reader.eof() {
+                return Err("the file ended prematurely".to_string());
+            }
+            let loc = reader.loc;
+            match reader.command() {
+                Command::Nop | Command::FntDef(..) => (),
+                Command::Bop(counts, prev) => {
+                    let mut page = Page { loc, counts, prev, commands: vec![], eop: 0 };
+                    loop {
+                        if reader.eof() {
+                            return Err("the file ended prematurely".to_string());
+                        }
+                        let loc = reader.loc;
+                        match reader.command() {
+                            Command::Eop => {
+                                page.eop = loc;
+                                break;
+                            },
+                            Command::Bop(..)
+                            | Command::Pre(_)
+                            | Command::Post(_)
+                            | Command::PostPost(..)
+                            | Command::Undefined(_) => return Err(format!("illegal command at byte {loc}")),
+                            command => page.commands.push((loc, command))
+                        }
+                    }
+                    pages.push(page);
+                },
+                Command::Post(mut postamble) => {
+                    loop {
+                        let loc = reader.loc;
+                        match reader.command() {
+                            Command::Nop => (),
+                            Command::FntDef(_, font) => postamble.fonts.push(font),
+                            Command::PostPost(..) => break,
+                            _ => return Err(format!("byte {loc} is not postpost"))
+                        }
+                    }
+                    return Ok(Self { preamble, pages, postamble, post_loc: loc });
+                },
+                _ => return Err(format!("byte {loc} is not bop"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::FATAL_ERROR_STOP;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+    use crate::dvi::dvitype::tests::{SMALL_PAGE, dvi_file};
+
+    #[test]
+    fn parse_dvi_file() {
+        let dvi = Dvi::parse(&dvi_file(SMALL_PAGE)).unwrap();
+        assert_eq!((dvi.preamble.id, dvi.preamble.numerator, dvi.preamble.denominator, dvi.preamble.mag), (2, 25400000, 473628672, 1000));
+        assert!(dvi.preamble.comment.starts_with(b" TeX output "));
+        assert_eq!(dvi.pages.len(), 1);
+        let page = &dvi.pages[0];
+        assert_eq!((page.loc, page.counts, page.prev, page.eop), (42, [0; 10], -1, 135));
+        let commands: Vec<String> = page.commands.iter().map(|(loc, command)| format!("{loc}: {command}")).collect();
+        assert_eq!(commands, [
+            "87: down3 447828",
+            "91: fntdef1 0: cmr10 at 655360 (design size 655360, check sum 1274110073)",
+            "112: fntnum0",
+            "113: setchar65",
+            "114: setchar66",
+            "115: push",
+            "116: right3 65536",
+            "120: setchar67",
+            "121: pop",
+            "122: right3 538852",
+            "126: setrule height 196608, width 131072"
+        ]);
+        assert_eq!(dvi.post_loc, 136);
+        assert_eq!(dvi.postamble.last_bop, 42);
+        assert_eq!((dvi.postamble.max_v, dvi.postamble.max_h, dvi.postamble.max_push, dvi.postamble.total_pages), (447828, 1625660, 1, 1));
+        assert_eq!(dvi.postamble.fonts.len(), 1);
+        let font = &dvi.postamble.fonts[0];
+        assert_eq!((font.number, font.scaled_size, font.design_size, &font.name[..]), (0, 655360, 655360, &b"cmr10"[..]));
+    }
+
+    #[test]
+    fn parse_bad_dvi_file() {
+        let dvi = dvi_file(SMALL_PAGE);
+        assert_eq!(Dvi::parse(&dvi[1..]), Err("First byte isn't start of preamble".to_string()));
+        assert_eq!(Dvi::parse(&dvi[..118]), Err("the file ended prematurely".to_string()));
+        assert_eq!(Dvi::parse(&dvi[..136]), Err("the file ended prematurely".to_string()));
+        let mut damaged = dvi.clone();
+        damaged[120] = 250; // an undefined command instead of `setchar67`
+        assert_eq!(Dvi::parse(&damaged), Err("illegal command at byte 120".to_string()));
+    }
+
+    // Room for the tokens of a long special.
+    fn capacities() -> Capacities {
+        Capacities {
+            main_memory: 100_000,
+            ..Capacities::default()
+        }
+    }
+
+    #[test]
+    fn long_special() {
+        // 40000 digits, more than the initial size of the pool, which grows
+        // unless it is the `trip` feature: then the pool overflows, as in TeX.
+        let format = dump_format(MINIMAL_FORMAT, &capacities());
+        let options = CompileOptions {
+            format: &format,
+            capacities: capacities(),
+            ..CompileOptions::default()
+        };
+        let output = compile(
+            "\\def\\a{0123456789}\\def\\b{\\a\\a\\a\\a\\a\\a\\a\\a\\a\\a}\n\
+            \\def\\c{\\b\\b\\b\\b\\b\\b\\b\\b\\b\\b}\\def\\d{\\c\\c\\c\\c\\c\\c\\c\\c\\c\\c}\n\
+            \\shipout\\hbox{\\special{\\d\\d\\d\\d}}\\end\n",
+            &options
+        );
+        if cfg!(feature = "trip") {
+            assert_eq!(output.history, FATAL_ERROR_STOP);
+            assert!(output.errors[0].message.starts_with("TeX capacity exceeded, sorry [pool size="), "{}", output.log);
+            return;
+        }
+        let dvi = Dvi::parse(&output.dvi.expect("no DVI file")).unwrap();
+        let specials: Vec<&Vec<u8>> = dvi.pages[0].commands.iter().filter_map(|(_, command)| match command {
+            Command::Xxx(4, x) => Some(x),
+            _ => None
+        }).collect();
+        assert_eq!(specials.len(), 1);
+        assert_eq!(specials[0].len(), 40000);
+        assert!(specials[0].starts_with(b"01234567890123"));
+    }
+}
@@ -0,0 +1,118 @@
+use super::reader::FontDef;
+use crate::constants::TFM_FONTS_DEFAULT;
+use crate::io::{FileSystem, SearchPath};
+
+use std::io::Read;
+
+// The widths of the characters of a font used in a DVI file,
+// read from its TFM file, for the readers that follow positions.
+
+pub(crate) const INVALID_WIDTH: i32 = 0x7fff_ffff;
+
+pub(crate) struct TfmWidths {
+    pub(crate) check_sum: i32,
+    pub(crate) bc: i32,
+    pub(crate) ec: i32,
+    // Widths of characters `bc` to `ec` in DVI units,
+    // `INVALID_WIDTH` for the characters that do not exist.
+    pub(crate) widths: Vec<i32>,
+}
+
+impl TfmWidths {
+    pub(crate) fn width(&self, c: i32) -> Option<i32> {
+        if c < self.bc || c > self.ec {
+            return None;
+        }
+        Some(self.widths[(c - self.bc) as usize]).filter(|&width| width != INVALID_WIDTH)
+    }
+}
+
+// The fonts are looked for along TFMFONTS, as TeX does.
+pub(crate) fn font_path() -> SearchPath {
+    SearchPath::from_env("TFMFONTS", TFM_FONTS_DEFAULT)
+}
+
+// The TFM file of the font, the first one found along `font_path`
+// (Section 563). `None` if it cannot be read or if it is empty.
+pub(crate) fn read_tfm(file_system: &mut dyn FileSystem, font_path: &mut SearchPath, def: &FontDef) -> Option<Vec<u8>> {
+    let mut name = String::from_utf8_lossy(&def.area).into_owned();
+    name.push_str(&String::from_utf8_lossy(&def.name));
+    name.push_str(".tfm");
+    let mut file = font_path.candidates(file_system, &name)
+        .into_iter()
+        .find_map(|candidate| file_system.open_bytes(&candidate).ok())?;
+    let mut tfm = vec![];
+    file.read_to_end(&mut tfm).ok()?;
+    Some(tfm).filter(|tfm| !tfm.is_empty())
+}
+
+// The widths scaled to `z`, as TeX does in Section 571 and Section 572.
+// `None` if the file is bad.
+pub(crate) fn tfm_widths(tfm: &[u8], z: i32) -> Option<TfmWidths> {
+    let word = |k: usize| -> Option<[i32; 4]> {
+        let b = tfm.get(4 * k..4 * k + 4)?;
+        Some([b[0] as i32, b[1] as i32, b[2] as i32, b[3] as i32])
+    };
+    let lh = word(0).map(|b| b[2] * 256 + b[3])? as usize;
+    let [b0, b1, b2, b3] = word(1)?;
+    let mut bc = b0 * 256 + b1;
+    let ec = b2 * 256 + b3;
+    if ec < bc {
+        bc = ec + 1;
+    }
+    let nw = word(2).map(|b| b[0] * 256 + b[1])?;
+    if nw == 0 || nw > 256 {
+        return None;
+    }
+    word(5 + lh)?;
+    let check_sum = match lh {
+        0 => 0,
+        _ => {
+            let [b0, b1, b2, b3] = word(6)?;
+            (((b0 as i8 as i32) * 256 + b1) * 256 + b2) * 256 + b3
+        }
+    };
+
+    let mut indices = vec![];
+    for k in 0..(ec - bc + 1) as usize {
+        let b0 = word(6 + lh + k)?[0];
+        if b0 > nw {
+            return None;
+        }
+        indices.push(b0 as usize);
+    }
+
+    // Section 572
+    let mut z = z;
+    let mut alpha = 16;
+    while z >= 0o40000000 {
+        z /= 2;
+        alpha += alpha;
+    }
+    let beta = 256 / alpha;
+    alpha *= z;
+    let base = 6 + lh + (ec - bc + 1) as usize;
+    let mut in_width = vec![];
+    for k in 0..nw as usize {
+        let [b0, b1, b2, b3] = word(base + k)?;
+        let mut width = (((((b3 * z) / 256) + (b2 * z)) / 256) + (b1 * z)) / beta;
+        if b0 > 0 {
+            if b0 < 255 {
+                return None;
+            }
+            width -= alpha;
+        }
+        in_width.push(width);
+    }
+    if in_width[0] != 0 {
+        return None;
+    }
+
+    let widths = indices.into_iter()
+        .map(|index| match index {
+            0 => INVALID_WIDTH,
+            _ => in_width[index]
+        })
+        .collect();
+    Some(TfmWidths { check_sum, bc, ec, widths })
+}
@@ -1,33 +1,33 @@
 use crate::constants::*;
-use crate::datastructures::{
-    Status, r#box, font_id_text, mag
-};
-use crate::io::term_input_string;
-use crate::math::fam;
+use crate::datastructures::Status;
+use crate::io::FormatError;
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Scaled, StrNum, update_terminal
 };
 
-use std::io::Write;
-
 // Part 6: Reporting errors
 
+#[derive(Debug)]
 pub enum TeXError {
-    Arith,
     Overflow(&'static str, Integer),
     Confusion(&'static str),
     IO(&'static str),
     Fatal(&'static str),
+    // Section 81
+    // TeX stops after an error that has already been reported.
+    JumpOut,
     
-    // Other errors that do no stop original TeX,
-    // but will stop this implementation.
+    // Other errors do not stop TeX: they are reported with `error`
+    // and TeX recovers as well as it can.
+    // Section 98
+    Interruption,
     // Section 288
     IncompatibleMag,
     IllegalMag(Integer),
     // Section 336
-    IncompleteIf,
+    IncompleteIf(HalfWord),
     // Section 338
-    FileEndedOrForbiddenCSFound,
+    FileEndedOrForbiddenCSFound(HalfWord),
     // Section 346
     InvalidCharacter,
     // Section 370
@@ -88,7 +88,6 @@ pub enum TeXError {
     // Section 510
     ExtraFiOrElse,
     // Section 530
-    CantFindFile,
     CantWriteFile,
     // Section 561
     TfmNotLoadable(bool, HalfWord, Scaled),
@@ -148,6 +147,7 @@ pub enum TeXError {
     MissingDollar,
     // Section 1064, 1065
     MissingEndGroup,
+    MissingDollar2,
     MissingMathRight,
     MissingRightBrace,
     // Section 1066
@@ -220,6 +220,8 @@ pub enum TeXError {
     MissingTo2,
     // Section 1232
     InvalidCode(Integer, HalfWord),
+    // Section 1236
+    Arith,
     // Section 1237
     CantUseAfterCmd(QuarterWord),
     // Section 1241
@@ -234,17 +236,30 @@ pub enum TeXError {
     ImproperAt(Integer),
     // Section 1283
     ErrMessage(StrNum),
+    // Section 1293
+    // Not an error: after `\show...`, TeX stops as for an error.
+    ShowWhatever,
+    // Section 1303
+    BadFormat(FormatError),
     // Section 1304
     CantDumpInGroup,
     // Section 1372
     UnbalancedWriteCmd,
-
-    // Format
-    CantFindFormat,
 }
 
 pub(crate) type TeXResult<T> = Result<T, TeXError>;
 
+impl TeXError {
+    // The name of the variant, as given in diagnostics.
+    pub(crate) fn name(&self) -> String {
+        let name = format!("{self:?}");
+        match name.split_once('(') {
+            Some((name, _)) => name.to_string(),
+            None => name
+        }
+    }
+}
+
 macro_rules! help_lines {
     ($($lines:expr),*) => {
         vec![$($lines),*]
@@ -252,6 +267,45 @@ macro_rules! help_lines {
 }
 
 impl Global {
+    // Section 73
+    // With `file_line_error`, "file:line: " is printed instead of "! ".
+    // As in web2c, a relative file name starts with "./".
+    // The recorded error message does not contain this prefix.
+    pub(crate) fn print_err(&mut self, s: &str) {
+        match self.current_file_line() {
+            Some((name, line)) if self.file_line_error => {
+                self.print_nl("");
+                if !name.starts_with('/') && !name.starts_with("./") && !name.starts_with("../") {
+                    self.print("./");
+                }
+                self.print(&name);
+                self.print_char(b':');
+                self.print_int(line);
+                self.print(": ");
+            },
+            _ => self.print_nl("! ")
+        }
+        self.err_message.clear();
+        self.print(s);
+    }
+
+    // The innermost file being read, and its current line.
+    pub(crate) fn current_file_line(&self) -> Option<(String, Integer)> {
+        let mut level = self.in_open;
+        while level > 0 && self.source_filename_stack[level].is_empty() {
+            level -= 1;
+        }
+        if level == 0 {
+            None
+        }
+        else if level == self.in_open {
+            Some((self.source_filename_stack[level].clone(), self.line))
+        }
+        else {
+            Some((self.source_filename_stack[level].clone(), self.line_stack[level + 1]))
+        }
+    }
+
     // Section 92
     pub(crate) fn normalize_selector(&mut self) -> TeXResult<()> {
         self.selector = if self.log_opened {
@@ -281,38 +335,219 @@ impl Global {
         if self.selector == LOG_ONLY || self.selector == NO_PRINT {
             self.selector += 1;
         }
-        println!();
-        println!("! Interruption.");
-        println!("You rang?");
-        println!("Not supported at the moment.");
-        println!("Type <return> to continue or X to quit.");
-        print!("? ");
-        update_terminal!();
-        if term_input_string()?.trim() == "X" {
-            Err(TeXError::Fatal("interrupted by the user"))
+        self.deletions_allowed = false;
+        self.error(TeXError::Interruption)?;
+        self.deletions_allowed = true;
+        self.interrupt = false;
+        Ok(())
+    }
+
+    // Section 93
+    // The error stops TeX: it is reported (if not already done),
+    // then the files are closed.
+    pub fn succumb(&mut self, texerror: TeXError) {
+        if !matches!(texerror, TeXError::JumpOut) {
+            if self.interaction == ERROR_STOP_MODE {
+                self.interaction = SCROLL_MODE; // no more interaction
+            }
+            self.halt_on_error = false; // TeX stops anyway
+            self.history = FATAL_ERROR_STOP;
+            if self.error(texerror).is_err() {
+                self.term_out.write_str("Ouch---the error procedure produced an error!");
+                self.term_out.write_cr();
+            }
         }
-        else {
-            Ok(())
+        // Section 81
+        if self.close_files_and_terminate().is_err() {
+            self.term_out.write_str("Ouch---I can't close the files!");
+            self.term_out.write_cr();
         }
+        update_terminal!(self);
     }
 
     // Section 992
-    fn box_error(&mut self, n: HalfWord) {
+    pub(crate) fn box_error(&mut self, texerror: TeXError, n: HalfWord) -> TeXResult<()> {
+        self.error(texerror)?;
         self.begin_diagnostic();
-        self.print_nl("Content of \\box255:");
-        self.show_box(r#box(n));
+        self.print_nl("The following box has been deleted:");
+        self.show_box(self.r#box(n));
         self.end_diagnostic(true);
+        self.flush_node_list(self.r#box(n))?;
+        *self.box_mut(n) = NULL;
+        Ok(())
+    }
+
+    // Section 83
+    fn sec83_get_users_advice(&mut self) -> TeXResult<()> {
+        loop {
+            // continue:
+            if self.interaction != ERROR_STOP_MODE {
+                return Ok(());
+            }
+            self.clear_for_error_prompt();
+            self.prompt_input("? ")?;
+            if self.last == self.first {
+                return Ok(());
+            }
+            let c = self.buffer[self.first as usize].to_ascii_uppercase();
+
+            // Section 84
+            match c {
+                b'0'..=b'9' if self.deletions_allowed => {
+                    self.sec88_delete_tokens(c)?;
+                    continue;
+                },
+
+                #[cfg(feature = "debug")]
+                b'D' => {
+                    self.debug_help()?;
+                    continue;
+                },
+
+                b'E' if self.base_ptr > 0 && self.input_stack[self.base_ptr].name_field >= 256 => {
+                    self.print_nl("You want to edit file ");
+                    self.slow_print(self.input_stack[self.base_ptr].name_field as StrNum);
+                    self.print(" at line ");
+                    self.print_int(self.line);
+                    self.interaction = SCROLL_MODE;
+                    return Err(TeXError::JumpOut);
+                },
+
+                b'H' => {
+                    self.sec89_print_the_help_information();
+                    continue;
+                },
+
+                b'I' => {
+                    // Section 87
+                    self.begin_file_reading()?;
+                    if self.last > self.first + 1 {
+                        *self.loc_mut() = self.first + 1;
+                        self.buffer[self.first as usize] = b' ';
+                    }
+                    else {
+                        self.prompt_input("insert>")?;
+                        *self.loc_mut() = self.first;
+                    }
+                    self.first = self.last;
+                    *self.limit_mut() = self.last - 1; // no end_line_char ends this line
+                    return Ok(());
+                    // End section 87
+                },
+
+                b'Q' | b'R' | b'S' => {
+                    // Section 86
+                    self.error_count = 0;
+                    self.interaction = BATCH_MODE + (c - b'Q') as Integer;
+                    self.print("OK, entering ");
+                    match self.interaction {
+                        BATCH_MODE => {
+                            self.print_esc("batchmode");
+                            self.selector -= 1;
+                        },
+                        NONSTOP_MODE => self.print_esc("nonstopmode"),
+                        _ => self.print_esc("scrollmode"),
+                    }
+                    self.print("...");
+                    self.print_ln();
+                    update_terminal!(self);
+                    return Ok(());
+                    // End section 86
+                },
+
+                b'X' => {
+                    self.interaction = SCROLL_MODE;
+                    return Err(TeXError::JumpOut);
+                },
+
+                _ => (),
+            }
+
+            // Section 85
+            self.print("Type <return> to proceed, S to scroll future error messages,");
+            self.print_nl("R to run without stopping, Q to run quietly,");
+            self.print_nl("I to insert something, ");
+            if self.base_ptr > 0 && self.input_stack[self.base_ptr].name_field >= 256 {
+                self.print("E to edit your file,");
+            }
+            if self.deletions_allowed {
+                self.print_nl("1 or ... or 9 to ignore the next 1 to 9 tokens of input,");
+            }
+            #[cfg(feature = "debug")]
+            self.print_nl("D to debug,");
+            self.print_nl("H for help, X to quit.");
+            // End section 85
+        }
+    }
+
+    // Section 88
+    fn sec88_delete_tokens(&mut self, c: u8) -> TeXResult<()> {
+        let s1 = self.cur_tok;
+        let s2 = self.cur_cmd;
+        let s3 = self.cur_chr;
+        let s4 = self.align_state;
+        self.align_state = 1_000_000;
+        self.ok_to_interrupt = false;
+        let next = self.buffer[(self.first + 1) as usize];
+        let mut c = if self.last > self.first + 1 && next.is_ascii_digit() {
+            (c - b'0') as Integer * 10 + (next - b'0') as Integer
+        }
+        else {
+            (c - b'0') as Integer
+        };
+        while c > 0 {
+            self.get_token()?; // one-level recursive call of error is possible
+            c -= 1;
+        }
+        self.cur_tok = s1;
+        self.cur_cmd = s2;
+        self.cur_chr = s3;
+        self.align_state = s4;
+        self.ok_to_interrupt = true;
+        self.help_line = help_lines!(
+            "I have just deleted some text, as you asked.",
+            "You can now delete more, or insert, or whatever."
+        );
+        self.show_context();
+        Ok(())
+    }
+
+    // Section 89
+    fn sec89_print_the_help_information(&mut self) {
+        if self.use_err_help {
+            self.give_err_help();
+            self.use_err_help = false;
+        }
+        else {
+            if self.help_line.is_empty() {
+                self.help_line = help_lines!(
+                    "Sorry, I don't know how to help in this situation.",
+                    "Maybe you should try asking a human?"
+                );
+            }
+            for k in 0..self.help_line.len() {
+                self.print(self.help_line[k]);
+                self.print_ln();
+            }
+        }
+        self.help_line = help_lines!(
+            "Sorry, I already gave what help I could...",
+            "Maybe you should try asking a human?",
+            "An error might have occurred before I noticed any problems.",
+            "'If all else fails, read the instructions.'"
+        );
+    }
+
+    // Section 1284
+    fn give_err_help(&mut self) {
+        self.token_show(self.err_help());
     }
 
     // Section 82
     pub fn error(&mut self, texerror: TeXError) -> TeXResult<()> {
-        // Section 73
         macro_rules! print_err {
             ($s:expr) => {
-                {
-                    self.print_nl("! ");
-                    self.print($s);
-                }
+                self.print_err($s)
             };
         }
         
@@ -327,7 +562,25 @@ impl Global {
             };
         }
 
-        let help_message = match texerror {
+        if self.history < ERROR_MESSAGE_ISSUED {
+            self.history = ERROR_MESSAGE_ISSUED;
+        }
+        self.err_message.clear();
+        self.recording_err_message = true;
+        let showing = matches!(texerror, TeXError::ShowWhatever);
+        let variant = if self.diagnostics_enabled() {
+            texerror.name()
+        }
+        else {
+            String::new()
+        };
+        self.help_line = match texerror {
+            // Already reported
+            TeXError::JumpOut | TeXError::BadFormat(_) => {
+                self.recording_err_message = false;
+                return Ok(());
+            },
+
             TeXError::IO(s) => {
                 self.selector = TERM_ONLY;
                 print_err!("Input/output error(");
@@ -369,44 +622,64 @@ impl Global {
                 help_lines!("I'm broken. Please show this to someone who can fix can fix.")
             },
 
+            // Section 98
+            TeXError::Interruption => {
+                print_err!("Interruption");
+                help_lines!(
+                    "You rang?",
+                    "Try to insert an instruction for me (e.g., `I\\showlists'),",
+                    "unless you just want to quit by typing `X'."
+                )
+            },
+
             // Section 288
             TeXError::IncompatibleMag => {
                 print_err!("Incompatible magnification (");
-                self.print_int(mag());
+                self.print_int(self.mag());
+                self.print(");");
+                self.print_nl(" the previous value will be retained (");
+                self.print_int(self.mag_set);
                 self.print(").");
-                help_lines!("I can handle only one magnification ratio per job.")
+                help_lines!(
+                    "I can handle only one magnification ratio per job. So I've",
+                    "reverted to the magnification you used earlier on this page."
+                )
             },
 
             TeXError::IllegalMag(mag) => {
-                print_err!("Illegal magnification (");
+                print_err!("Illegal magnification has been changed to 1000 (");
                 self.print_int(mag);
                 self.print(").");
                 help_lines!("The magnification ratio must be between 1 and 32768.")
             },
 
             // Section 336
-            TeXError::IncompleteIf => {
+            TeXError::IncompleteIf(cs) => {
                 print_err!("Incomplete ");
                 self.print_cmd_chr(IF_TEST, self.cur_if as HalfWord);
+                self.print("; all text was ignored after line ");
+                self.print_int(self.skip_line);
                 self.print_char(b'.');
-                if self.cur_cs == 0 {
+                if cs == 0 {
                     help_lines!(
                         "The file ended while I was skipping conditional text.",
-                        "This kind of error happens when you say '\\if...' and forget\nthe matching '\\fi'."
+                        "This kind of error happens when you say '\\if...' and forget",
+                        "the matching '\\fi'. I've inserted a '\\fi'; this might work."
                     )
                 }
                 else {
                     help_lines!(
                         "A forbidden control sequence occurred in skipped text.",
-                        "This kind of error happens when you say '\\if...' and forget\nthe matching '\\fi'."
+                        "This kind of error happens when you say '\\if...' and forget",
+                        "the matching '\\fi'. I've inserted a '\\fi'; this might work."
                     )
                 }
             },
 
             // Section 338
-            TeXError::FileEndedOrForbiddenCSFound => {
+            TeXError::FileEndedOrForbiddenCSFound(cs) => {
                 self.runaway();
-                match self.cur_cs {
+                match cs {
                     0 => print_err!("File ended"),
                     _ => print_err!("Forbidden control sequence found"),
                 }
@@ -425,14 +698,19 @@ impl Global {
                 self.print_char(b'.');
                 help_lines!(
                     "I suspect you have forgotten a '}', causing me",
-                    "to read past where you wanted me to stop."
+                    "to read past where you wanted me to stop.",
+                    "I'll try to recover; but if the error is serious,",
+                    "you'd better fix your file."
                 )
             },
 
             // Section 346
             TeXError::InvalidCharacter => {
                 print_err!("Text line contains an invalid character.");
-                help_lines!("A funny symbol that I can't read has just been input.")
+                help_lines!(
+                    "A funny symbol that I can't read has just been input.",
+                    "Continue, and I'll forget that it ever happened."
+                )
             },
 
             // Section 370
@@ -440,14 +718,16 @@ impl Global {
                 print_err!("Undefined control sequence.");
                 help_lines!(
                     "The control sequence at the end of the top line",
-                    "of your error message was never \\def'ed."
+                    "of your error message was never \\def'ed. If you have",
+                    "misspelled it (e.g., '\\hobx'), just continue and I'll",
+                    "forget about whatever was undefined."
                 )
             },
 
             // Section 373
             TeXError::MissingEncCSName => {
                 print_err!("Missing ");
-                self.print_esc("endcsname.");
+                self.print_esc("endcsname inserted.");
                 help_lines!(
                     "The control sequence marked <to be read again> should",
                     "not appear between \\csname and \\endcsname."
@@ -462,7 +742,9 @@ impl Global {
                 help_lines!(
                     "I've run accros a '}' that doesn't seem to match anything.",
                     "For example, '\\def\\a#1{...}' and '\\a}' should produce",
-                    "this error."
+                    "this error. The '\\par' that I've just inserted will cause",
+                    "me to report a runaway argument that might be the root",
+                    "of the problem."
                 )
             },
 
@@ -473,7 +755,8 @@ impl Global {
                 self.print(" was complete.");
                 help_lines!(
                     "I suspect you've forgotten a '}', causing me to apply this",
-                    "control sequence to too much text. How can we recover?"
+                    "control sequence to too much text. How can we recover?",
+                    "My plan is to forget the whole thing and hope for the best."
                 )
             },
 
@@ -492,21 +775,25 @@ impl Global {
 
             // Section 403
             TeXError::MissingLeftBrace => {
-                print_err!("Missing {.");
-                help_lines!("A left brace was mandatory here.")
+                print_err!("Missing { inserted.");
+                help_lines!(
+                    "A left brace was mandatory here, so I've put one in.",
+                    "You might want to delete and/or insert some corrections",
+                    "so that I will find a matching right brace soon."
+                )
             },
 
             // Section 408
             TeXError::IncompatibleGlueUnits => {
                 print_err!("Incompatible glue units.");
-                help_lines!("I'm not going to assume that 1mu=1pt, you must choose.")
+                help_lines!("I'm going to assume that 1mu=1pt when they're mixed.")
             },
 
             // Section 415, 446
             TeXError::MissingNumber => {
-                print_err!("Missing number.");
+                print_err!("Missing number, treated as zero.");
                 help_lines!(
-                    "A number should have been here.",
+                    "A number should have been here; I inserted '0'.",
                     "(If you can't figure out why I needed to see a number,",
                     "look up 'weird error' in the index of the TeXbook.)"
                 )
@@ -520,7 +807,8 @@ impl Global {
                 help_lines!(
                     "You can refer to \\spacefactor only in horizontal mode;",
                     "you can refer to \\prevdepth only in vertical mode, and",
-                    "neither of these is meaningful inside \\write."
+                    "neither of these is meaningful inside \\write. So",
+                    "I'm forgetting what you said and using zero instead."
                 )
             },
 
@@ -530,92 +818,135 @@ impl Global {
                 self.print_cmd_chr(self.cur_cmd, self.cur_chr);
                 self.print("' after ");
                 self.print_esc("the.");
-                help_lines!("I'm not forgetting what you said, fix that.")
+                help_lines!("I'm forgetting what you said and using zero instead.")
             },
 
             // Section 433
             TeXError::BadRegisterCode => {
-                print_err!("Bad register code.");
-                help_lines!("A register must be between 0 and 255.")
+                print_err!("Bad register code (");
+                self.print_int(self.cur_val);
+                self.print(").");
+                help_lines!(
+                    "A register number must be between 0 and 255.",
+                    "I changed this one to zero."
+                )
             },
 
             // Section 434
             TeXError::BadCharacterCode => {
-                print_err!("Bad character code.");
-                help_lines!("A character number must be between 0 and 255.")
+                print_err!("Bad character code (");
+                self.print_int(self.cur_val);
+                self.print(").");
+                help_lines!(
+                    "A character number must be between 0 and 255.",
+                    "I changed this one to zero."
+                )
             },
 
             // Section 435
             TeXError::BadNumber => {
-                print_err!("Bad number.");
-                help_lines!("I expected to read a number between 0 and 15.")
+                print_err!("Bad number (");
+                self.print_int(self.cur_val);
+                self.print(").");
+                help_lines!(
+                    "Since I expected to read a number between 0 and 15,",
+                    "I changed this one to zero."
+                )
             },
 
             // Section 436
             TeXError::BadMathChar => {
-                print_err!("Bad mathchar.");
-                help_lines!("A mathchar number must be between 0 and 32767.")
+                print_err!("Bad mathchar (");
+                self.print_int(self.cur_val);
+                self.print(").");
+                help_lines!(
+                    "A mathchar number must be between 0 and 32767.",
+                    "I changed this one to zero."
+                )
             },
 
             // Section 437
             TeXError::BadDelimiterCode => {
-                print_err!("Bad delimiter code.");
-                help_lines!("A numeric delimiter code must be between 0 and 2^{27}-1.")
+                print_err!("Bad delimiter code (");
+                self.print_int(self.cur_val);
+                self.print(").");
+                help_lines!(
+                    "A numeric delimiter code must be between 0 and 2^{27}-1.",
+                    "I changed this one to zero."
+                )
             },
 
             // Section 442
             TeXError::ImproperAlphabeticConstant => {
                 print_err!("Improper alphabetic constant.");
-                help_lines!("A one-character control sequence belongs after a ` mark.")
+                help_lines!(
+                    "A one-character control sequence belongs after a ` mark.",
+                    "So I'm essentially inserting \\0 here."
+                )
             },
 
             // Section 445
             TeXError::NumberTooBig => {
                 print_err!("Number too big.");
-                help_lines!("I can only go up to 2 147 483 647 = \"7FFFFFFF.")
+                help_lines!(
+                    "I can only go up to 2 147 483 647 = \"7FFFFFFF,",
+                    "so I'm using that number instead of yours."
+                )
             },
 
             // Section 454
             TeXError::IllegalUnitOfMeasureFilll => {
-                print_err!("Illegal unit of measure.");
+                print_err!("Illegal unit of measure (replaced by filll).");
                 help_lines!("I dddon't go any higher than filll.")
             },
 
             // Section 456
             TeXError::IllegalUnitOfMeasureMu => {
-                print_err!("Illegal unit of measure.");
-                help_lines!("The unit of measurement in math glue must be mu.")
+                print_err!("Illegal unit of measure (mu inserted).");
+                help_lines!(
+                    "The unit of measurement in math glue must be mu.",
+                    "To recover gracefully from this error, it's best to",
+                    "delete the erroneous units; e.g., type '2' to delete",
+                    "two letters. (See Chapter 27 of The TeXbook.)"
+                )
             },
 
             // Section 459
             TeXError::IllegalUnitOfMeasurePt => {
-                print_err!("Illegal unit of measure.");
+                print_err!("Illegal unit of measure (pt inserted).");
                 help_lines!(
                     "Dimensions can be in units of em, ex, in, pt, pc,",
-                    "cm, mm, dd, cc, bp, or sp; but yours is a new one!"
+                    "cm, mm, dd, cc, bp, or sp; but yours is a new one!",
+                    "I'll assume that you meant to say pt, for printer's points."
                 )
             },
 
             // Section 460
             TeXError::DimensionTooLarge => {
                 print_err!("Dimension too large.");
-                help_lines!("I can't work with sizes bigger than about 576 cm.")
+                help_lines!(
+                    "I can't work with sizes bigger than about 576 cm.",
+                    "Continue and I'll use the largest value I can."
+                )
             },
 
             // Section 475
             TeXError::MissingLeftBrace2 => {
-                print_err!("Missing {.");
-                help_lines!("Where was the left brace? You said saomething like `\\def\\a}'.")
+                print_err!("Missing { inserted.");
+                help_lines!(
+                    "Where was the left brace? You said saomething like `\\def\\a}',",
+                    "which I'm going to interpret as `\\def\\a{}'."
+                )
             },
 
             // Section 476
             TeXError::AlreadyNineParameters => {
                 print_err!("You already have nine parameters.");
-                help_lines!("Remove the extra #.")
+                help_lines!("I'm going to ignore the # sign you just used.")
             },
             TeXError::ParametersNumberedConsecutively => {
                 print_err!("Parameters must be numbered consecutively.");
-                help_lines!("Check your parameters numbering.")
+                help_lines!("I've inserted the digit you should have used after the #.")
             },
 
             // Section 479
@@ -626,7 +957,7 @@ impl Global {
                 help_lines!(
                     "You meant to type ## instead of #, right?",
                     "Or maybe a } was forgotten somewhere earlier, and things",
-                    "are all screwed up?"
+                    "are all screwed up? I'm going to assume that you meant ##."
                 )
             },
 
@@ -641,12 +972,12 @@ impl Global {
             TeXError::ExtraOr => {
                 print_err!("Extra ");
                 self.print_esc("or.");
-                help_lines!("It doesn't match any \\if.")
+                help_lines!("I'm ignoring this; it doesn't match any \\if.")
             },
 
             // Section 503
             TeXError::MissingEqual(this_if) => {
-                print_err!("Missing = for ");
+                print_err!("Missing = inserted for ");
                 self.print_cmd_chr(IF_TEST, this_if);
                 self.print_char(b'.');
                 help_lines!("I was expecting to see '<', '=' or '>'. Didn't.")
@@ -657,17 +988,10 @@ impl Global {
                 print_err!("Extra ");
                 self.print_cmd_chr(FI_OR_ELSE, self.cur_chr);
                 self.print_char(b'.');
-                help_lines!("It doesn't match any \\if.")
+                help_lines!("I'm ignoring this; it doesn't match any \\if.")
             },
 
             // Section 530
-            TeXError::CantFindFile => {
-                print_err!("I can't find file '");
-                self.print_file_name(self.cur_name, self.cur_area, self.cur_ext);
-                self.print("'.");
-                help_lines!("Maybe you have mispelled the name.")
-            },
-
             TeXError::CantWriteFile => {
                 print_err!("I can't write on file '");
                 self.print_file_name(self.cur_name, self.cur_area, self.cur_ext);
@@ -696,7 +1020,10 @@ impl Global {
                         false => " not loadable: Metric (TFM) file not found."
                     }
                 );
-                help_lines!("I wasn't able to read the size data for this font.")
+                help_lines!(
+                    "I wasn't able to read the size data for this font,",
+                    "so I will ignore the font specification."
+                )
             },
 
             // Section 567
@@ -715,7 +1042,7 @@ impl Global {
                     self.print(" scaled ");
                     self.print_int(-s);
                 }
-                print_err!(" not loaded: Not enough room left.");
+                self.print(" not loaded: Not enough room left.");
                 help_lines!(
                     "I'am afraid I won't be able to make use of this font,",
                     "because my memory for character-size data is too small.",
@@ -735,7 +1062,7 @@ impl Global {
             // Section 579
             TeXError::FontHasOnly(f) => {
                 print_err!("Font ");
-                self.print_esc_strnumber(font_id_text(f) as StrNum);
+                self.print_esc_strnumber(self.font_id_text(f) as StrNum);
                 self.print(" has only ");
                 self.print_int(self.font_params[f as usize] as Integer);
                 self.print(" fontdimen parameters.");
@@ -759,14 +1086,15 @@ impl Global {
                 print_err!("");
                 self.print_size(self.cur_size as Integer);
                 self.print_char(b' ');
-                self.print_int(fam(a) as Integer);
+                self.print_int(self.fam(a) as Integer);
                 self.print(" is undefined (character ");
                 self.print_strnumber(self.cur_c as StrNum);
                 self.print(").");
                 help_lines!(
                     "Somewhere in the math formula just ended, you used the",
                     "stated character from an undefined font family. For example,",
-                    "plain TeX doesn't allow \\it or \\sl in subscripts."
+                    "plain TeX doesn't allow \\it or \\sl in subscripts. Proceed,",
+                    "and I'll try to forget that I needed that character."
                 )
             },
 
@@ -782,7 +1110,7 @@ impl Global {
 
             // Section 783
             TeXError::MissingCroisillonAlign => {
-                print_err!("Missing # in alignment preamble.");
+                print_err!("Missing # inserted in alignment preamble.");
                 help_lines!(
                     "There should be exactly one # between &'s, when an \\halign",
                     "or \\valign is being set up. In this case you had none."
@@ -801,10 +1129,12 @@ impl Global {
 
             // Section 792
             TeXError::ExtraAlignmentTab => {
-                print_err!("Extra alignment tab.");
+                print_err!("Extra alignment tab has been changed to ");
+                self.print_esc("cr.");
                 help_lines!(
                     "You have given more \\span or & marks than there were",
-                    "in the preamble to the \\halign or \\valign now in progress."
+                    "in the preamble to the \\halign or \\valign now in progress.",
+                    "So I'll assume that you meant to type \\cr instead."
                 )
             },
 
@@ -815,7 +1145,8 @@ impl Global {
                     "The paragraph just ended includes some glue that has",
                     "infinite shrinkability, e.g., '\\hskip 0pt minus 1fil'.",
                     "Such glue doesn't belong there---it allows a paragraph",
-                    "of any length to fit on one line."
+                    "of any length to fit on one line. But it's safe to proceed,",
+                    "since the offensive shrinkability has been made finite."
                 )
             },
 
@@ -832,7 +1163,10 @@ impl Global {
             // Section 937
             TeXError::NotALetter => {
                 print_err!("Not a letter.");
-                help_lines!("Letters in \\hyphenation words must have \\lccode>0.")
+                help_lines!(
+                    "Letters in \\hyphenation words must have \\lccode>0.",
+                    "Proceed; I'll ignore the character I just read."
+                )
             },
 
             // Section 960
@@ -867,7 +1201,8 @@ impl Global {
                 help_lines!(
                     "The box you are \\vsplitting contains some infinitely",
                     "shrinkable glue, e.g., '\\vss' or '\\vskip 0pt minus 1fil'.",
-                    "Such glue doesn't belong there."
+                    "Such glue doesn't belong there; but you can safely proceed,",
+                    "since the offensive shrinkability has been made finite."
                 )
             },
 
@@ -878,7 +1213,7 @@ impl Global {
                 self.print_esc("vbox.");
                 help_lines!(
                     "The box you are trying to split is an \\hbox.",
-                    "I can't slit such a box."
+                    "I can't split such a box, so I'll leave it alone."
                 )
             },
 
@@ -887,7 +1222,8 @@ impl Global {
                 print_err!("Insertions can only be added to a vbox.");
                 help_lines!(
                     "Tut tut: You're trying to \\insert into a",
-                    "\\box register that now contains an \\hbox."
+                    "\\box register that now contains an \\hbox.",
+                    "Proceed, and I'll discard its present contents."
                 )
             },
 
@@ -897,7 +1233,8 @@ impl Global {
                 help_lines!(
                     "The page about to be output contains some infinitely",
                     "shrinkable glue, e.g., '\\vss' or '\\vskip 0pt minus 1fil'.",
-                    "Such glue doesn't belong there."
+                    "Such glue doesn't belong there; but you can safely proceed,",
+                    "since the offensive shrinkability has been made finite."
                 )
             },
 
@@ -909,7 +1246,8 @@ impl Global {
                 self.print_char(b'.');
                 help_lines!(
                     "The correction glue for page breaking with insertions",
-                    "must have finite shrinkability."
+                    "must have finite shrinkability. But you may proceed,",
+                    "since the offensive shrinkability has been made finite."
                 )
             },
 
@@ -917,7 +1255,10 @@ impl Global {
             TeXError::Box255IsNotVoid => {
                 print_err!("");
                 self.print_esc("box255 is not void.");
-                help_lines!("You shouldn't use \\box255 except in \\output routines.")
+                help_lines!(
+                    "You shouldn't use \\box255 except in \\output routines.",
+                    "Proceed, and I'll discard its present contents."
+                )
             },
 
             // Section 1024
@@ -927,8 +1268,8 @@ impl Global {
                 self.print(" consecutive dead cycles.");
                 help_lines!(
                     "I've concluded that your \\output is awry; it never does a",
-                    "\\shipout. Next time increase \\maxdeadcycles if you want me",
-                    "to be more patient!"
+                    "\\shipout, so I'm shipping \\box255 out myself. Next time",
+                    "increase \\maxdeadcycles if you want me to be more patient!"
                 )
             },
 
@@ -949,7 +1290,8 @@ impl Global {
                 self.print_char(b'.');
                 help_lines!(
                     "Your \\output commands should empty \\box255,",
-                    "e.g., by saying '\\shipout\\box255'."
+                    "e.g., by saying '\\shipout\\box255'.",
+                    "Proceed; I'll discard its present contents."
                 )
             },
 
@@ -961,30 +1303,32 @@ impl Global {
 
             // Section 1047
             TeXError::MissingDollar => {
-                print_err!("Missing $.");
+                print_err!("Missing $ inserted.");
                 help_lines!(
-                    "Either you forgot opening or closing math mode with $,",
-                    "or a math character/control sequence is used outside",
-                    "of math mode (or vice versa)."
+                    "I've inserted a begin-math/end-math symbol since I think",
+                    "you left one out. Proceed, with fingers crossed."
                 )
             },
 
             // Section 1064, 1065
-            TeXError::MissingEndGroup => {
+            TeXError::MissingEndGroup
+            | TeXError::MissingDollar2
+            | TeXError::MissingMathRight
+            | TeXError::MissingRightBrace => {
                 print_err!("Missing ");
-                self.print_esc("endgroup.");
-                help_lines!("Groups not properly nested (did you forget to close one?)")
-            },
-
-            TeXError::MissingMathRight => {
-                print_err!("Missing ");
-                self.print_esc("right.");
-                help_lines!("Groups not properly nested (did you forget a \"\\right.\"?")
-            },
-
-            TeXError::MissingRightBrace => {
-                print_err!("Missing }.");
-                help_lines!("Groups not properly nested (did you forget a right brace?)")
+                match texerror {
+                    TeXError::MissingEndGroup => self.print_esc("endgroup"),
+                    TeXError::MissingDollar2 => self.print_char(b'$'),
+                    TeXError::MissingMathRight => self.print_esc("right."),
+                    _ => self.print_char(b'}'),
+                }
+                self.print(" inserted.");
+                help_lines!(
+                    "I've inserted something that you may have forgotten (see the",
+                    "<inserted text> above). With luck, this will get me unwedged.",
+                    "But if you really didn't forget anything, try typing '2' now;",
+                    "then my insertion and my current dilemma will both disappear."
+                )
             },
 
             // Section 1066
@@ -998,7 +1342,10 @@ impl Global {
             // Section 1068
             TeXError::TooManyRightBraces => {
                 print_err!("Too many }'s.");
-                help_lines!("You've closed more groups than you opened.")
+                help_lines!(
+                    "You've closed more groups than you opened.",
+                    "Such booboos are generally harmless, so keep going."
+                )
             },
 
             // Section 1069
@@ -1011,8 +1358,11 @@ impl Global {
                     _ => (), // Cannot happen
                 }
                 help_lines!(
-                    "Perhaps the } is legitimate and you forgot",
-                    "something else, as in '\\hbox{$x}'."
+                    "I've deleted a group-closing symbol because it seems to be",
+                    "spurious, as in '$x}$'. But perhaps the } is legitimate and",
+                    "you forgot something else, as in '\\hbox{$x}'. In such cases",
+                    "the way to recover is to insert both the forgotten and the",
+                    "deleted material, e.g., by typing 'I$}'."
                 )
             },
 
@@ -1021,8 +1371,8 @@ impl Global {
                 print_err!("Leaders not followed by proper glue.");
                 help_lines!(
                     "You should say '\\leaders <box or rule><hskip or vskip>'.",
-                    "I found the <box or rule>, but there's not suitable",
-                    "<hskip or vskip>."
+                    "I found the <box or rule>, but there's no suitable",
+                    "<hskip or vskip>, so I'm ignoring these leaders."
                 )
             },
 
@@ -1039,10 +1389,10 @@ impl Global {
 
             // Section 1082
             TeXError::MissingTo => {
-                print_err!("Missing 'to'.");
+                print_err!("Missing 'to' inserted.");
                 help_lines!(
-                    "I'm working on '\\vskip<box number> to <dimen>',",
-                    "and didn't found 'to'."
+                    "I'm working on '\\vsplit<box number> to <dimen>';",
+                    "will look for the <dimen> next."
                 )
             },
 
@@ -1051,7 +1401,8 @@ impl Global {
                 print_err!("A <box> was supposed to be here.");
                 help_lines!(
                     "I was expecting to see \\hbox or \\vbox or \\copy or \\box or",
-                    "something like that."
+                    "something like that. So you might find something missing in",
+                    "your output. But keep trying; you can fix this later."
                 )
             },
 
@@ -1133,17 +1484,19 @@ impl Global {
 
             // Section 1127
             TeXError::MissingLeftBrace3 => {
-                print_err!("Missing {.");
+                print_err!("Missing { inserted.");
                 help_lines!(
-                    "Something needs to be done to fix the current column",
-                    "of the current alignment."
+                    "I've put in what seems to be necessary to fix",
+                    "the current column of the current alignment.",
+                    "Try to go on, since this might almost work."
                 )
             },
             TeXError::MissingRightBrace2 => {
-                print_err!("Missing }.");
+                print_err!("Missing } inserted.");
                 help_lines!(
-                    "Something needs to be done to fix the current column",
-                    "of the current alignment."
+                    "I've put in what seems to be necessary to fix",
+                    "the current column of the current alignment.",
+                    "Try to go on, since this might almost work."
                 )
             },
 
@@ -1187,7 +1540,8 @@ impl Global {
             // Section 1132
             TeXError::MissingCr => {
                 print_err!("Missing ");
-                self.print_esc("cr.");
+                self.print_esc("cr");
+                self.print(" inserted.");
                 help_lines!("I'm guessing that you meant to end an alignment here.")
             },
 
@@ -1195,24 +1549,25 @@ impl Global {
             TeXError::ExtraEndcsname => {
                 print_err!("Extra ");
                 self.print_esc("endcsname.");
-                help_lines!("I wasn't doing a \\csname.")
+                help_lines!("I'm ignoring this, since I wasn't doing a \\csname.")
             },
 
             // Section 1159
             TeXError::LimitControlsMustFollowMathOp => {
                 print_err!("Limit controls must follow a math operator.");
-                help_lines!("Misplaced \\limits or \\nolimits command.")
+                help_lines!("I'm ignoring this misplaced \\limits or \\nolimits command.")
             },
 
             // Section 1161
             TeXError::MissingDelimiterLeftParen => {
-                print_err!("Missing delimiter (.");
+                print_err!("Missing delimiter (. inserted).");
                 help_lines!(
                     "I was expecting to see something like '(' or '\\{' or",
                     "'\\}' here. Maybe you typed, e.g., '{' instead of '\\{',",
                     "and now braces are unbalanced.",
                     "Acceptable delimiters are characters whose \\delcode is",
-                    "nonnegative, or you can use '\\delimiter <delimiter code>'."
+                    "nonnegative, or you can use '\\delimiter <delimiter code>'.",
+                    "I've inserted a null delimiter instead."
                 )
             },
 
@@ -1220,7 +1575,10 @@ impl Global {
             TeXError::UseMathAccentInMathMode => {
                 print_err!("Please use ");
                 self.print_esc("mathaccent for accents in math mode.");
-                help_lines!("Accents are not the same in formulas as they are in text.")
+                help_lines!(
+                    "I'm changing \\accent to \\mathaccent here; wish me luck.",
+                    "(Accents are not the same in formulas as they are in text.)"
+                )
             },
 
             // Section 1177
@@ -1228,7 +1586,7 @@ impl Global {
                 print_err!("Double superscript.");
                 help_lines!(
                     "You wrote something like x^1^2 which is confusing:",
-                    "did you mean {x^1}^2 or x^{1^2} (or something else)?"
+                    "did you mean {x^1}^2 or x^{1^2}? I'm treating it as x^1{}^2."
                 )
             },
 
@@ -1236,7 +1594,7 @@ impl Global {
                 print_err!("Double subscript.");
                 help_lines!(
                     "You wrote something like x_1_2 which is confusing:",
-                    "did you mean {x_1}_2 or x_{1_2} (or something else)?"
+                    "did you mean {x_1}_2 or x_{1_2}? I'm treating it as x_1{}_2."
                 )
             },
 
@@ -1246,7 +1604,8 @@ impl Global {
                 help_lines!(
                     "This fraction specification is bad, since I don't",
                     "know whether a construction like 'x \\over y \\over z'",
-                    "means {x \\over y} \\over z' or 'x \\over {y \\over z}'."
+                    "means {x \\over y} \\over z' or 'x \\over {y \\over z}'.",
+                    "I'm ignoring this fraction specification."
                 )
             },
 
@@ -1254,12 +1613,12 @@ impl Global {
             TeXError::ExtraMathRight => {
                 print_err!("Extra ");
                 self.print_esc("right.");
-                help_lines!("This \\right has no matching \\left.")
+                help_lines!("I'm ignoring a \\right that had no matching \\left.")
             },
 
             // Section 1195
             TeXError::InsufficientSymbolFonts => {
-                print_err!("Insufficient symbol fonts for math formula.");
+                print_err!("Math formula deleted: Insufficient symbol fonts.");
                 help_lines!(
                     "Sorry, but I can't typeset math unless \\textfont 2",
                     "and \\scriptfont 2 and \\scriptscriptfont 2 have all",
@@ -1268,7 +1627,7 @@ impl Global {
             },
 
             TeXError::InsufficientExtensionFonts => {
-                print_err!("Insufficient extension fonts for math formula.");
+                print_err!("Math formula deleted: Insufficient extension fonts.");
                 help_lines!(
                     "Sorry, but I can't typeset math unless \\textfont 3",
                     "and \\scriptfont 3 and \\scriptscriptfont 3 have all",
@@ -1279,12 +1638,15 @@ impl Global {
             // Section 1197
             TeXError::DisplayMathEndsWithDollars => {
                 print_err!("Display math should end with $$.");
-                help_lines!("The '$' that I just saw supposedly matches a previous '$$'.")
+                help_lines!(
+                    "The '$' that I just saw supposedly matches a previous '$$'.",
+                    "So I shall assume that you typed '$$' both times."
+                )
             },
 
             // Section 1207
             TeXError::MissingDollarDollar => {
-                print_err!("Missing $$.");
+                print_err!("Missing $$ inserted.");
                 help_lines!(
                     "Displays can use special alignments (like \\eqalignno)",
                     "only if nothing but the alignment itself is between $$'s."
@@ -1296,10 +1658,7 @@ impl Global {
                 print_err!("You can't use a prefix with '");
                 self.print_cmd_chr(self.cur_cmd, self.cur_chr);
                 self.print("'.");
-                help_lines!(
-                    "I could ignore you said \\long or \\outer or \\global here,",
-                    "but I don't."
-                )
+                help_lines!("I'll pretend you didn't say \\long or \\outer or \\global.")
             },
 
             // Section 1213
@@ -1309,19 +1668,28 @@ impl Global {
                 self.print_esc("outer' with '");
                 self.print_cmd_chr(self.cur_cmd, self.cur_chr);
                 self.print("'.");
-                help_lines!("I could ignore you said \\long or \\outer here, but I don't.")
+                help_lines!("I'll pretend you didn't say \\long or \\outer here.")
             },
 
             // Section 1215
             TeXError::MissingControlSequence => {
-                print_err!("Missing control sequence.");
-                help_lines!("Please don't say '\\def cs{...}', say '\\def\\cs{...}'.")
+                print_err!("Missing control sequence inserted.");
+                help_lines!(
+                    "Please don't say '\\def cs{...}', say '\\def\\cs{...}'.",
+                    "I've inserted an inaccessible control sequence so that your",
+                    "definition will be completed without mixing me up too badly.",
+                    "You can recover graciously from this error, if you're",
+                    "careful; see exercise 27.2 in The TeXbook."
+                )
             },
 
             // Section 1225
             TeXError::MissingTo2 => {
-                print_err!("Missing 'to'.");
-                help_lines!("You should have said '\\read<number> to \\cs'.")
+                print_err!("Missing 'to' inserted.");
+                help_lines!(
+                    "You should have said '\\read<number> to \\cs'.",
+                    "I'm going to look for the \\cs now."
+                )
             },
 
             // Section 1232
@@ -1357,7 +1725,7 @@ impl Global {
                 self.print("' after ");
                 self.print_cmd_chr(q, 0);
                 self.print_char(b'.');
-                help_lines!("Try to fix that.")
+                help_lines!("I'm forgetting what you said and not changing anything.")
             },
 
             // Section 1241
@@ -1366,7 +1734,8 @@ impl Global {
                 self.print_esc("setbox.");
                 help_lines!(
                     "Sorry, \\setbox is not allowed after \\halign in a display,",
-                    "or between \\accent and an accented character."
+                    "or between \\accent and an accented character.",
+                    "I'm ignoring this \\setbox."
                 )
             },
 
@@ -1398,10 +1767,10 @@ impl Global {
             TeXError::ImproperAt(s) => {
                 print_err!("Improper 'at' size (");
                 self.print_scaled(s);
-                self.print("pt).");
+                self.print("pt), replaced by 10pt.");
                 help_lines!(
                     "I can only handle fonts at positive sizes that are",
-                    "less than 2048pt."
+                    "less than 2048pt, so I've changed what you said to 10pt."
                 )
             },
 
@@ -1410,12 +1779,49 @@ impl Global {
                 print_err!("");
                 self.slow_print(s);
                 self.print_char(b'.');
-                help_lines!(
-                    "This error message was generated by an \\errmessage",
-                    "command, so I can't give any explicit help.",
-                    "Pretend that you're Hercule Poirot: Examine all clues,",
-                    "and deduce the truth by order and method."
-                )
+                if self.err_help() != NULL {
+                    self.use_err_help = true;
+                    vec![]
+                }
+                else if self.long_help_seen {
+                    help_lines!("(That was another \\errmessage.)")
+                }
+                else {
+                    if self.interaction < ERROR_STOP_MODE {
+                        self.long_help_seen = true;
+                    }
+                    help_lines!(
+                        "This error message was generated by an \\errmessage",
+                        "command, so I can't give any explicit help.",
+                        "Pretend that you're Hercule Poirot: Examine all clues,",
+                        "and deduce the truth by order and method."
+                    )
+                }
+            },
+
+            // Section 1293
+            // The message has already been printed.
+            TeXError::ShowWhatever => {
+                if self.interaction < ERROR_STOP_MODE {
+                    self.error_count -= 1;
+                    vec![]
+                }
+                else if self.tracing_online() > 0 {
+                    help_lines!(
+                        "This isn't an error message; I'm just \\showing something.",
+                        "Type 'I\\show...' to show more (e.g., \\show\\cs,",
+                        "\\showthe\\count10, \\showbox255, \\showlists)."
+                    )
+                }
+                else {
+                    help_lines!(
+                        "This isn't an error message; I'm just \\showing something.",
+                        "Type 'I\\show...' to show more (e.g., \\show\\cs,",
+                        "\\showthe\\count10, \\showbox255, \\showlists).",
+                        "And type 'I\\tracingonline=1\\show...' to show boxes and",
+                        "lists on your terminal as well as in the transcript file."
+                    )
+                }
             },
 
             // Section 1304
@@ -1432,41 +1838,58 @@ impl Global {
                     "I can't handle that very well; good luck."
                 )
             }
-
-            // Format
-            TeXError::CantFindFormat => {
-                print_err!("Sorry, I can't find that format.");
-                help_lines!(
-                    "Either you got the name wrong (e.g., 'plaine.fmt'",
-                    "instead of 'plain.fmt') or the format is not present",
-                    "in the format directory or the current one."
-                )
-            }
         };
 
-        self.show_context();
-        for help_line in help_message {
-            self.print_nl(help_line);
+        self.recording_err_message = false;
+
+        if self.diagnostics_enabled() && !showing {
+            let message = std::mem::take(&mut self.err_message);
+            self.begin_recording();
+            self.show_context();
+            let context = self.end_recording();
+            self.err_message = message;
+            self.write_error_diagnostic(&variant, &context);
+        }
+        else {
+            self.show_context();
+        }
+        if !showing {
+            self.record_error();
+        }
+        if self.halt_on_error {
+            self.history = FATAL_ERROR_STOP;
+            return Err(TeXError::JumpOut);
+        }
+        if self.interaction == ERROR_STOP_MODE {
+            return self.sec83_get_users_advice();
         }
-        self.print_ln();
 
-        // Supplementary messages
-        match texerror {
-            TeXError::InsertionCanOnlyBeAddedToVbox(n) => self.box_error(n),
-            TeXError::Box255IsNotVoid => self.box_error(255),
-            _ => (),
+        self.error_count += 1;
+        if self.error_count == 100 {
+            self.print_nl("(That makes 100 errors; please try again.)");
+            self.history = FATAL_ERROR_STOP;
+            return Err(TeXError::JumpOut);
         }
 
-        // Interactive debugging
-        #[cfg(feature = "debug")]
-        if self.interaction == ERROR_STOP_MODE {
-            println!("Type \"D\" to debug or <return> to quit.");
-            print!("? ");
-            update_terminal!();
-            if term_input_string()?.trim() == "D" {
-                self.debug_help()?;
+        // Section 90
+        if self.interaction > BATCH_MODE {
+            self.selector -= 1; // avoid terminal output
+        }
+        if self.use_err_help {
+            self.print_ln();
+            self.give_err_help();
+        }
+        else {
+            for k in 0..self.help_line.len() {
+                self.print_nl(self.help_line[k]);
             }
         }
+        self.print_ln();
+        if self.interaction > BATCH_MODE {
+            self.selector += 1; // re-enable terminal output
+        }
+        self.print_ln();
+        // End section 90
 
         Ok(())
     }
@@ -1,9 +1,5 @@
 use crate::builder::norm_min;
 use crate::constants::*;
-use crate::datastructures::{
-    info, info_mut, language, left_hyphen_min, link, link_mut,
-    right_hyphen_min, subtype, subtype_mut, r#type, type_mut
-};
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, SmallNumber
@@ -11,69 +7,71 @@ use crate::{
 
 // Part 53: Extensions
 
-// Section 1341
-pub(crate) fn what_lang(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+impl Global {
+    // Section 1341
+    pub(crate) fn what_lang(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-pub(crate) fn what_lang_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
-}
+    pub(crate) fn what_lang_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 
-pub(crate) fn what_lhm(p: HalfWord) -> QuarterWord {
-    r#type(p + 1)
-}
+    pub(crate) fn what_lhm(&self, p: HalfWord) -> QuarterWord {
+        self.r#type(p + 1)
+    }
 
-pub(crate) fn what_lhm_mut(p: HalfWord) -> &'static mut QuarterWord {
-    type_mut(p + 1)
-}
+    pub(crate) fn what_lhm_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.type_mut(p + 1)
+    }
 
-pub(crate) fn what_rhm(p: HalfWord) -> QuarterWord {
-    subtype(p + 1)
-}
+    pub(crate) fn what_rhm(&self, p: HalfWord) -> QuarterWord {
+        self.subtype(p + 1)
+    }
 
-pub(crate) fn what_rhm_mut(p: HalfWord) -> &'static mut QuarterWord {
-    subtype_mut(p + 1)
-}
+    pub(crate) fn what_rhm_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.subtype_mut(p + 1)
+    }
 
-pub(crate) fn write_tokens(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+    pub(crate) fn write_tokens(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-pub(crate) fn write_tokens_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
-}
+    pub(crate) fn write_tokens_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 
-pub(crate) fn write_stream(p: HalfWord) -> HalfWord {
-    info(p + 1)
-}
+    pub(crate) fn write_stream(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 1)
+    }
 
-pub(crate) fn write_stream_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 1)
-}
+    pub(crate) fn write_stream_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 1)
+    }
 
-pub(crate) fn open_name(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+    pub(crate) fn open_name(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-pub(crate) fn open_name_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
-}
+    pub(crate) fn open_name_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 
-pub(crate) fn open_area(p: HalfWord) -> HalfWord {
-    info(p + 2)
-}
+    pub(crate) fn open_area(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 2)
+    }
 
-pub(crate)fn open_area_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 2)
-}
+    pub(crate) fn open_area_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 2)
+    }
 
-pub(crate) fn open_ext(p: HalfWord) -> HalfWord {
-    link(p + 2)
-}
+    pub(crate) fn open_ext(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 2)
+    }
 
-pub(crate) fn open_ext_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 2)
+    pub(crate) fn open_ext_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 2)
+    }
 }
 
 impl Global {
@@ -85,9 +83,9 @@ impl Global {
                 self.new_write_whatsit(OPEN_NODE_SIZE)?;
                 self.scan_optional_equals()?;
                 self.scan_file_name()?;
-                *open_name_mut(self.tail()) = self.cur_name as Integer;
-                *open_area_mut(self.tail()) = self.cur_area as Integer;
-                *open_ext_mut(self.tail()) = self.cur_ext as Integer;
+                *self.open_name_mut(self.tail()) = self.cur_name as Integer;
+                *self.open_area_mut(self.tail()) = self.cur_area as Integer;
+                *self.open_ext_mut(self.tail()) = self.cur_ext as Integer;
                 // End section 1351
             },
             
@@ -97,23 +95,23 @@ impl Global {
                 self.new_write_whatsit(WRITE_NODE_SIZE)?;
                 self.cur_cs = k;
                 _ = self.scan_toks(false, false)?;
-                *write_tokens_mut(self.tail()) = self.def_ref;
+                *self.write_tokens_mut(self.tail()) = self.def_ref;
                 // End section 1352
             },
             
             CLOSE_NODE => {
                 // Section 1353
                 self.new_write_whatsit(WRITE_NODE_SIZE)?;
-                *write_tokens_mut(self.tail()) = NULL;
+                *self.write_tokens_mut(self.tail()) = NULL;
                 // End section 1353
             },
 
             SPECIAL_NODE => {
                 // Section 1354
                 self.new_whatsit(SPECIAL_NODE as QuarterWord, WRITE_NODE_SIZE)?;
-                *write_stream_mut(self.tail()) = NULL;
+                *self.write_stream_mut(self.tail()) = NULL;
                 _ = self.scan_toks(false, true)?;
-                *write_tokens_mut(self.tail()) = self.def_ref;
+                *self.write_tokens_mut(self.tail()) = self.def_ref;
                 // End section 1354
             },
 
@@ -126,7 +124,7 @@ impl Global {
                     self.out_what(self.tail())?;
                     self.flush_node_list(self.tail())?;
                     *self.tail_mut() = p;
-                    *link_mut(p) = NULL;
+                    *self.link_mut(p) = NULL;
                 }
                 else {
                     self.back_input()?;
@@ -137,7 +135,7 @@ impl Global {
             SET_LANGUAGE_CODE => {
                 // Section 1377
                 if self.mode().abs() != HMODE {
-                    return Err(TeXError::ReportIllegalCase);
+                    return self.report_illegal_case();
                 }
                 self.new_whatsit(LANGUAGE_NODE as QuarterWord, SMALL_NODE_SIZE)?;
                 self.scan_int()?;
@@ -147,9 +145,9 @@ impl Global {
                 else {
                     0
                 };
-                *what_lang_mut(self.tail()) = self.clang();
-                *what_lhm_mut(self.tail()) = norm_min(left_hyphen_min()) as QuarterWord;
-                *what_rhm_mut(self.tail()) = norm_min(right_hyphen_min()) as QuarterWord;
+                *self.what_lang_mut(self.tail()) = self.clang();
+                *self.what_lhm_mut(self.tail()) = norm_min(self.left_hyphen_min()) as QuarterWord;
+                *self.what_rhm_mut(self.tail()) = norm_min(self.right_hyphen_min()) as QuarterWord;
                 // End section 1377
             },
 
@@ -163,9 +161,9 @@ impl Global {
     // Section 1349
     fn new_whatsit(&mut self, s: SmallNumber, w: Integer) -> TeXResult<()> {
         let p = self.get_node(w)?;
-        *type_mut(p) = WHATSIT_NODE;
-        *subtype_mut(p) = s;
-        *link_mut(self.tail()) = p;
+        *self.type_mut(p) = WHATSIT_NODE;
+        *self.subtype_mut(p) = s;
+        *self.link_mut(self.tail()) = p;
         *self.tail_mut() = p;
         Ok(())
     }
@@ -185,24 +183,24 @@ impl Global {
                 self.cur_val = 16;
             }
         }
-        *write_stream_mut(self.tail()) = self.cur_val;
+        *self.write_stream_mut(self.tail()) = self.cur_val;
         Ok(())
     }
 
     // Section 1376
     pub(crate) fn fix_language(&mut self) -> TeXResult<()> {
-        let l = if (1..=254).contains(&language()) {
-            language()
+        let l = if (1..=254).contains(&self.language()) {
+            self.language()
         }
         else {
             0
         };
         if l != self.clang() {
             self.new_whatsit(LANGUAGE_NODE as QuarterWord, SMALL_NODE_SIZE)?;
-            *what_lang_mut(self.tail()) = l;
+            *self.what_lang_mut(self.tail()) = l;
             *self.clang_mut() = l;
-            *what_lhm_mut(self.tail()) = norm_min(left_hyphen_min()) as QuarterWord;
-            *what_rhm_mut(self.tail()) = norm_min(right_hyphen_min()) as QuarterWord;
+            *self.what_lhm_mut(self.tail()) = norm_min(self.left_hyphen_min()) as QuarterWord;
+            *self.what_rhm_mut(self.tail()) = norm_min(self.right_hyphen_min()) as QuarterWord;
         }
         Ok(())
     }
@@ -1,9 +1,5 @@
-use crate::arithmetic::xn_over_d;
 use crate::constants::*;
-use crate::datastructures::{
-    MemoryWord, character_mut, default_hyphen_char, default_skew_char,
-    font_mut, tracing_lost_chars,
-};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
 use crate::io::ByteFileInSelector;
 use crate::{
@@ -132,15 +128,24 @@ impl Global {
 
     // Section 560
     pub(crate) fn read_font_info(&mut self, u: HalfWord, nom: StrNum, aire: StrNum, s: Scaled) -> TeXResult<usize> {
+        match self.load_font_info(u, nom, aire, s) {
+            Err(texerror @ (TeXError::TfmNotLoadable(..) | TeXError::TfmNotLoaded(..))) => {
+                // Section 561
+                self.tfm_file.close();
+                self.error(texerror)?;
+                Ok(NULL_FONT as usize)
+            },
+            result => result
+        }
+    }
+
+    // The font is not loaded if `TfmNotLoadable` or `TfmNotLoaded` is returned.
+    fn load_font_info(&mut self, u: HalfWord, nom: StrNum, aire: StrNum, s: Scaled) -> TeXResult<usize> {
         // Section 562
         // Section 563
-        if aire == EMPTY_STRING {
-            self.pack_file_name(nom, TEX_FONT_AREA, EXT_TFM);
-        }
-        else {
-            self.pack_file_name(nom, aire, EXT_TFM);
-        }
-        if !self.b_open_in(ByteFileInSelector::TfmFile) {
+        // The file is looked for along TFMFONTS.
+        self.pack_file_name(nom, aire, EXT_TFM);
+        if !self.b_open_in_path(ByteFileInSelector::TfmFile) {
             return Err(TeXError::TfmNotLoadable(false, u, s));
         }
         // End section 563
@@ -218,8 +223,8 @@ impl Global {
         if np < 7 {
             lf += 7 - np;
         }
-        if self.font_ptr == (FONT_MAX as QuarterWord)
-            || (self.fmem_ptr as Integer) + lf > FONT_MEM_SIZE
+        if self.font_ptr == (self.font_max as QuarterWord)
+            || (self.fmem_ptr as Integer) + lf > self.font_mem_size
         {
             return Err(TeXError::TfmNotLoaded(u, s));
         }
@@ -258,7 +263,7 @@ impl Global {
         if s != -1000 {
             z = match s.cmp(&0) {
                 Greater | Equal => s,
-                Less => xn_over_d(z, -s, 1000)?.0,
+                Less => self.xn_over_d(z, -s, 1000).0,
             };
         }
         self.font_size[f] = z;
@@ -452,8 +457,8 @@ impl Global {
             Less => 7,
         };
 
-        self.hyphen_char[f] = default_hyphen_char();
-        self.skew_char[f] = default_skew_char();
+        self.hyphen_char[f] = self.default_hyphen_char();
+        self.skew_char[f] = self.default_skew_char();
         self.bchar_label[f] = match bch_label.cmp(&nl) {
             Less => (bch_label + self.lig_kern_base[f]) as usize,
             Equal | Greater => NON_ADDRESS as usize,
@@ -487,13 +492,15 @@ impl Global {
 
     // Section 581
     pub(crate) fn char_warning(&mut self, f: QuarterWord, c: u8) {
-        if tracing_lost_chars() > 0 {
+        if self.tracing_lost_chars() > 0 {
             self.begin_diagnostic();
+            self.begin_warning();
             self.print_nl("Missing character: There is no ");
             self.print_strnumber(c as usize);
             self.print(" in font ");
             self.print_strnumber(self.font_name[f as usize]);
             self.print_char(b'!');
+            self.end_warning("missing_character");
             self.end_diagnostic(false);
         }
     }
@@ -505,8 +512,8 @@ impl Global {
             && self.char_info(f, c as QuarterWord).char_exists()
         {
             let p = self.get_avail()?;
-            *font_mut(p) = f;
-            *character_mut(p) = c as QuarterWord;
+            *self.font_mut(p) = f;
+            *self.character_mut(p) = c as QuarterWord;
             Ok(p)
         }
         else {
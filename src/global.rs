@@ -1,38 +1,71 @@
+use crate::compile::CompileError;
 use crate::constants::*;
 use crate::datastructures::{
-    InStateRecord, InputFile, LineStack, ListStateRecord, MemoryWord, Status,
-    link, new_line_char_mut, subtype
+    Eqtb, Hash, InStateRecord, InputFile, LineStack, ListStateRecord, MemoryWord,
+    Status, XeqLevel
 };
 use crate::error::TeXResult;
 use crate::io::{
-    AlphaFileIn, AlphaFileOut, ByteFileIn, ByteFileOut
+    AlphaFileIn, AlphaFileOut, ByteFileIn, ByteFileOut, FileSystem, SearchPath
 };
 use crate::parser::{
-    TrieOpHash, TrieTaken, if_line_field
+    TrieOpHash, TrieTaken
 };
 use crate::breaker::{Array1to6, HyfArray};
+use crate::pdf::PdfFile;
+use crate::strings::StrPool;
+use crate::svg::SvgFile;
 use crate::{
-    ASCIICode, HalfWord, Integer, QuarterWord, Scaled, SmallNumber, StrNum
+    ASCIICode, HalfWord, Integer, OutputFormat, QuarterWord, Scaled, SmallNumber, StrNum,
+    end_line_char_inactive
 };
 
-#[cfg(feature = "stat")]
-use crate::{
-    datastructures::tracing_stats,
-    strings::{init_pool_ptr, init_str_ptr, pool_ptr, str_ptr}
-};
+
+use std::io::Write;
 
 pub struct Global {
     pub initex_mode: bool,
+    // Section 11, chosen at runtime (see `Capacities`)
+    pub(crate) mem_max: Integer,
+    pub(crate) mem_top: Integer,
+    pub(crate) extra_mem_top: Integer,
+    pub(crate) buf_size: Integer,
+    pub(crate) stack_size: Integer,
+    pub(crate) max_in_open: Integer,
+    pub(crate) font_max: Integer,
+    pub(crate) font_mem_size: Integer,
+    pub(crate) param_size: Integer,
+    pub(crate) nest_size: Integer,
+    pub(crate) max_strings: Integer,
+    pub(crate) pool_size: Integer,
+    pub(crate) save_size: Integer,
+    pub(crate) trie_size: Integer,
+    pub(crate) hash_extra: Integer,
+
     // Section 26
     pub(crate) name_of_file: String,
+    // All files are opened through it, on disk by default.
+    pub file_system: Box<dyn FileSystem>,
+    // Where `\input` and `\openin` files, TFM files and formats are found.
+    pub(crate) input_path: SearchPath,
+    pub(crate) font_path: SearchPath,
+    pub(crate) format_path: SearchPath,
+    // Where output files are written, if not in the current directory.
+    pub output_directory: Option<String>,
+    // Side channel for diagnostics in JSON, see `open_diagnostics`.
+    pub(crate) diagnostics: Option<Box<dyn Write + Send>>,
 
     // Section 30
-    pub buffer: [ASCIICode; (BUF_SIZE + 1) as usize],
+    pub buffer: Vec<ASCIICode>,
     pub(crate) buffer_string: String,
     pub first: Integer,
     pub last: Integer,
     pub(crate) max_buf_stack: Integer,
 
+    // Section 32
+    pub(crate) term_in: AlphaFileIn,
+    pub(crate) term_out: AlphaFileOut,
+
     // Section 54
     pub(crate) log_file: AlphaFileOut,
     pub(crate) selector: Integer,
@@ -45,26 +78,45 @@ pub struct Global {
     pub(crate) first_count: Integer,
 
     // Section 73
-    // We only use two modes:
-    // - batch mode: no output on terminal
-    // - error_stop_mode: stops at first opportunity
-    // Any error stops the execution.
-    pub(crate) interaction: Integer,
+    pub interaction: Integer,
+    // Error messages start with "file:line:" instead of "!".
+    pub file_line_error: bool,
 
     // Section 76
-    // `deletions_allowed` and `error_count` are not supported
-    // (`error_count` would stop at 1).
+    pub(crate) deletions_allowed: bool,
     pub(crate) set_box_allowed: bool,
     pub history: usize,
+    pub(crate) error_count: Integer,
+    // Stop at the first error instead of recovering.
+    pub halt_on_error: bool,
+
+    // Section 79
+    pub(crate) help_line: Vec<&'static str>,
+    pub(crate) use_err_help: bool,
+    // The last error message is also recorded here (without context and help),
+    // so it can be reported to callers of the library.
+    pub(crate) err_message: Vec<u8>,
+    pub(crate) recording_err_message: bool,
+    // Every error reported, when running with `compile`.
+    pub(crate) reported_errors: Option<Vec<CompileError>>,
 
     // Section 96
     pub(crate) interrupt: bool,
     pub(crate) ok_to_interrupt: bool,
 
+    // Section 104
+    pub(crate) arith_error: bool,
+
+    // Section 39
+    pub(crate) pool: StrPool,
+
     // Section 115
     pub(crate) temp_ptr: HalfWord,
 
     // Section 116
+    pub(crate) mem: Vec<MemoryWord>,
+    // `MEM_MIN` until variable-size memory grows below it (Section 125).
+    pub(crate) mem_min: HalfWord,
     pub(crate) lo_mem_max: HalfWord,
     pub(crate) hi_mem_min: HalfWord,
 
@@ -81,9 +133,9 @@ pub struct Global {
 
     // Section 165
     #[cfg(feature = "debug")]
-    pub(crate) free: [bool; (MEM_MAX - MEM_MIN  +1) as usize],
+    pub(crate) free: Vec<bool>,
     #[cfg(feature = "debug")]
-    pub(crate) was_free: [bool; (MEM_MAX - MEM_MIN  + 1) as usize],
+    pub(crate) was_free: Vec<bool>,
     #[cfg(feature = "debug")]
     pub(crate) was_mem_end: HalfWord,
     #[cfg(feature = "debug")]
@@ -101,7 +153,7 @@ pub struct Global {
     pub(crate) breadth_max: Integer,
 
     // Section 213
-    pub(crate) nest: [ListStateRecord; (NEST_SIZE + 1) as usize],
+    pub(crate) nest: Vec<ListStateRecord>,
     pub(crate) nest_ptr: usize,
     pub(crate) max_nest_stack: usize,
     pub(crate) cur_list: ListStateRecord,
@@ -110,13 +162,20 @@ pub struct Global {
     // Section 246
     pub(crate) old_setting: Integer,
 
+    // Section 253
+    pub(crate) eqtb: Eqtb,
+    pub(crate) xeq_level: XeqLevel,
+
     // Section 256
+    pub(crate) hash: Hash,
     pub(crate) hash_used: HalfWord,
+    // Control sequences used in the `hash_extra` region, after `EQTB_SIZE`.
+    pub(crate) hash_high: HalfWord,
     pub(crate) no_new_control_sequence: bool,
     pub(crate) cs_count: Integer,
 
     // Section 271
-    pub(crate) save_stack: [MemoryWord; (SAVE_SIZE + 1) as usize],
+    pub(crate) save_stack: Vec<MemoryWord>,
     pub(crate) save_ptr: usize,
     pub(crate) max_save_stack: Integer,
     pub(crate) cur_level: QuarterWord,
@@ -133,7 +192,7 @@ pub struct Global {
     pub(crate) cur_tok: HalfWord,
 
     // Section 301
-    pub(crate) input_stack: [InStateRecord; (STACK_SIZE + 1) as usize],
+    pub(crate) input_stack: Vec<InStateRecord>,
     pub(crate) input_ptr: usize,
     pub(crate) max_in_stack: usize,
     pub(crate) cur_input: InStateRecord,
@@ -144,6 +203,9 @@ pub struct Global {
     pub(crate) input_file: InputFile,
     pub(crate) line: Integer,
     pub(crate) line_stack: LineStack,
+    // Name of the file read at each level, empty if the level
+    // is not reading a file (used by `file_line_error`).
+    pub(crate) source_filename_stack: Vec<String>,
 
     // Section 305
     pub(crate) scanner_status: Status,
@@ -151,7 +213,7 @@ pub struct Global {
     pub(crate) def_ref: HalfWord,
 
     // Section 308
-    pub(crate) param_stack: [HalfWord; (PARAM_SIZE + 1) as usize],
+    pub(crate) param_stack: Vec<HalfWord>,
     pub(crate) param_ptr: usize,
     pub(crate) max_param_stack: usize,
 
@@ -213,9 +275,19 @@ pub struct Global {
     pub(crate) name_in_progress: bool,
     pub(crate) job_name: StrNum,
     pub(crate) log_opened: bool,
+    // Used as `job_name` instead of the name of the first input file.
+    pub job_name_override: Option<String>,
 
     // Section 532
     pub(crate) dvi_file: ByteFileOut,
+    // Pages are shipped out in `dvi_file`, or in `pdf` and `svg`.
+    pub output_format: OutputFormat,
+    pub(crate) pdf: PdfFile,
+    pub(crate) svg: SvgFile,
+    // Where Type 1 fonts and encodings are found for PDF output,
+    // and the map file if it is not `pdftex.map` in that directory.
+    pub type1_directory: String,
+    pub font_map_file: Option<String>,
     pub(crate) output_file_name: StrNum,
     pub(crate) log_name: StrNum,
 
@@ -223,35 +295,35 @@ pub struct Global {
     pub(crate) tfm_file: ByteFileIn,
 
     // Section 549
-    pub(crate) font_info: [MemoryWord; (FONT_MEM_SIZE + 1) as usize],
+    pub(crate) font_info: Vec<MemoryWord>,
     pub(crate) fmem_ptr: usize,
     pub(crate) font_ptr: QuarterWord,
-    pub(crate) font_check: [MemoryWord; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_size: [Scaled; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_dsize: [Scaled; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_params: [usize; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_name: [StrNum; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_area: [StrNum; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_bc: [u8; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_ec: [u8; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_glue: [HalfWord; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_used: [bool; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) hyphen_char: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) skew_char: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) bchar_label: [usize; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_bchar: [usize; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) font_false_bchar: [usize; (FONT_MAX - FONT_BASE + 1) as usize],
+    pub(crate) font_check: Vec<MemoryWord>,
+    pub(crate) font_size: Vec<Scaled>,
+    pub(crate) font_dsize: Vec<Scaled>,
+    pub(crate) font_params: Vec<usize>,
+    pub(crate) font_name: Vec<StrNum>,
+    pub(crate) font_area: Vec<StrNum>,
+    pub(crate) font_bc: Vec<u8>,
+    pub(crate) font_ec: Vec<u8>,
+    pub(crate) font_glue: Vec<HalfWord>,
+    pub(crate) font_used: Vec<bool>,
+    pub(crate) hyphen_char: Vec<Integer>,
+    pub(crate) skew_char: Vec<Integer>,
+    pub(crate) bchar_label: Vec<usize>,
+    pub(crate) font_bchar: Vec<usize>,
+    pub(crate) font_false_bchar: Vec<usize>,
 
     // Section 550
-    pub(crate) char_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) width_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) height_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) depth_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) italic_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) lig_kern_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) kern_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) exten_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
-    pub(crate) param_base: [Integer; (FONT_MAX - FONT_BASE + 1) as usize],
+    pub(crate) char_base: Vec<Integer>,
+    pub(crate) width_base: Vec<Integer>,
+    pub(crate) height_base: Vec<Integer>,
+    pub(crate) depth_base: Vec<Integer>,
+    pub(crate) italic_base: Vec<Integer>,
+    pub(crate) lig_kern_base: Vec<Integer>,
+    pub(crate) kern_base: Vec<Integer>,
+    pub(crate) exten_base: Vec<Integer>,
+    pub(crate) param_base: Vec<Integer>,
 
     // Section 555
     pub(crate) null_character: MemoryWord,
@@ -342,6 +414,9 @@ pub struct Global {
     pub(crate) background: Array1to6,
     pub(crate) break_width: Array1to6,
 
+    // Section 826
+    pub(crate) no_shrink_error_yet: bool,
+
     // Section 828
     pub(crate) cur_p: HalfWord,
     pub(crate) second_pass: bool,
@@ -407,7 +482,7 @@ pub struct Global {
     pub(crate) rt_hit: bool,
 
     // Section 921
-    pub(crate) trie: [MemoryWord; (TRIE_SIZE + 1) as usize],
+    pub(crate) trie: Vec<MemoryWord>,
     pub(crate) hyf_distance: HyfArray,
     pub(crate) hyf_num: HyfArray,
     pub(crate) hyf_next: HyfArray,
@@ -426,12 +501,12 @@ pub struct Global {
     pub(crate) trie_op_ptr: usize,
 
     // Section 947
-    pub(crate) trie_c: [u8; (TRIE_SIZE + 1) as usize],
-    pub(crate) trie_o: [QuarterWord; (TRIE_SIZE + 1) as usize],
-    pub(crate) trie_l: [usize; (TRIE_SIZE + 1) as usize],
-    pub(crate) trie_r: [usize; (TRIE_SIZE + 1) as usize],
+    pub(crate) trie_c: Vec<u8>,
+    pub(crate) trie_o: Vec<QuarterWord>,
+    pub(crate) trie_l: Vec<usize>,
+    pub(crate) trie_r: Vec<usize>,
     pub(crate) trie_ptr: usize,
-    pub(crate) trie_hash: [usize; (TRIE_SIZE + 1) as usize],
+    pub(crate) trie_hash: Vec<usize>,
 
     // Section 950
     pub(crate) trie_taken: TrieTaken,
@@ -478,6 +553,9 @@ pub struct Global {
     // Section 1266
     pub(crate) after_token: HalfWord,
 
+    // Section 1281
+    pub(crate) long_help_seen: bool,
+
     // Section 1299
     pub format_ident: StrNum,
 
@@ -504,7 +582,7 @@ impl Global {
         }
         // End section 1378
 
-        *new_line_char_mut() = -1;
+        *self.new_line_char_mut() = -1;
 
         #[cfg(feature = "stat")]
         macro_rules! wlog_ln {
@@ -521,24 +599,24 @@ impl Global {
         }
 
         #[cfg(feature = "stat")]
-        if tracing_stats() > 0 {
+        if self.tracing_stats() > 0 {
             // Section 1334
             if self.log_opened {
                 wlog_ln!(" ");
                 wlog_ln!("Here is how much of TeX's memory you used:");
-                wlog_ln!(&format!(" {} string", str_ptr() - init_str_ptr()));
-                if str_ptr() != init_str_ptr() + 1 {
+                wlog!(&format!(" {} string", self.str_ptr() - self.init_str_ptr()));
+                if self.str_ptr() != self.init_str_ptr() + 1 {
                     wlog!("s");
                 }
-                wlog_ln!(&format!(" out of {}", MAX_STRINGS - init_str_ptr() as Integer));
-                wlog_ln!(&format!(" {} string characters out of {}", pool_ptr() - init_pool_ptr(), POOL_SIZE - init_pool_ptr() as Integer));
-                wlog_ln!(&format!(" {} words of memory out of {}", self.lo_mem_max - MEM_MIN + self.mem_end - self.hi_mem_min + 2, self.mem_end + 1 - MEM_MIN));
-                wlog_ln!(&format!(" {} multiletter control sequences out of {}", self.cs_count, HASH_SIZE));
+                wlog_ln!(&format!(" out of {}", self.max_strings - self.init_str_ptr() as Integer));
+                wlog_ln!(&format!(" {} string characters out of {}", self.pool_ptr() - self.init_pool_ptr(), self.pool_size - self.init_pool_ptr() as Integer));
+                wlog_ln!(&format!(" {} words of memory out of {}", self.lo_mem_max - self.mem_min + self.mem_end - self.hi_mem_min + 2, self.mem_end + 1 - self.mem_min));
+                wlog_ln!(&format!(" {} multiletter control sequences out of {}", self.cs_count, HASH_SIZE + self.hash_extra));
                 wlog!(&format!(" {} words of font info for {} font", self.fmem_ptr, self.font_ptr as Integer - FONT_BASE));
                 if (self.font_ptr as Integer) != FONT_BASE + 1 {
                     wlog!("s");
                 }
-                wlog_ln!(&format!(", out of {} for {}", FONT_MEM_SIZE, FONT_MAX - FONT_BASE));
+                wlog_ln!(&format!(", out of {} for {}", self.font_mem_size, self.font_max - FONT_BASE));
                 wlog!(&format!(" {} hyphenation exception", self.hyph_count));
                 if self.hyph_count != 1 {
                     wlog!("s");
@@ -551,11 +629,11 @@ impl Global {
                         self.max_param_stack,
                         self.max_buf_stack + 1,
                         self.max_save_stack + 6,
-                        STACK_SIZE,
-                        NEST_SIZE,
-                        PARAM_SIZE,
-                        BUF_SIZE,
-                        SAVE_SIZE
+                        self.stack_size,
+                        self.nest_size,
+                        self.param_size,
+                        self.buf_size,
+                        self.save_size
                     )
                 );
             }
@@ -571,9 +649,11 @@ impl Global {
                 self.print_nl("Transcript written on ");
                 self.slow_print(self.log_name);
                 self.print_char(b'.');
-                self.print_ln();
             }
         }
+        // As in web2c, the terminal ends with a new line.
+        self.print_ln();
+        self.close_diagnostics();
         Ok(())
     }
 
@@ -581,7 +661,7 @@ impl Global {
     pub fn final_cleanup(&mut self) -> TeXResult<()> {
         let c = self.cur_chr;
         if c != 1 {
-            *new_line_char_mut() = -1;
+            *self.new_line_char_mut() = -1;
         }
         if self.job_name == 0 {
             self.open_log_file()?;
@@ -615,10 +695,10 @@ impl Global {
                 self.print_int(self.if_line);
             }
             self.print(" was incomplete)");
-            self.if_line = if_line_field(self.cond_ptr);
-            self.cur_if = subtype(self.cond_ptr);
+            self.if_line = self.if_line_field(self.cond_ptr);
+            self.cur_if = self.subtype(self.cond_ptr);
             self.temp_ptr = self.cond_ptr;
-            self.cond_ptr = link(self.cond_ptr);
+            self.cond_ptr = self.link(self.cond_ptr);
             self.free_node(self.temp_ptr, IF_NODE_SIZE);
         }
 
@@ -649,4 +729,32 @@ impl Global {
         }
         Ok(())
     }
+
+    // Section 1337
+    // The first line asks for a format with `&name`.
+    pub fn format_in_first_line(&self) -> bool {
+        self.buffer[self.loc() as usize] == b'&'
+    }
+
+    // Section 1337, once the format is loaded.
+    pub fn prepare_first_line(&mut self) {
+        while self.loc() < self.limit() && self.buffer[self.loc() as usize] == b' ' {
+            *self.loc_mut() += 1;
+        }
+        if end_line_char_inactive!(self) {
+            *self.limit_mut() -= 1;
+        }
+        else {
+            let limit = self.limit() as usize;
+            self.buffer[limit] = self.end_line_char() as u8;
+        }
+    }
+
+    // Section 1337
+    // `\input` is assumed, except if the first line starts with
+    // an escape character.
+    pub fn first_line_is_file_name(&self) -> bool {
+        self.loc() < self.limit()
+            && self.cat_code(self.buffer[self.loc() as usize] as HalfWord) != ESCAPE as HalfWord
+    }
 }
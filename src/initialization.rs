@@ -1,46 +1,77 @@
+use crate::capacities::Capacities;
 use crate::breaker::{
-    Array1to6, HyfArray, line_number_mut
+    Array1to6, HyfArray
 };
 use crate::constants::*;
 use crate::datastructures::{
-    eqtb, eqtb_mut, hash, hash_mut, MEM, XEQ_LEVEL, InStateRecord, InputFile, LineStack,
-    ListStateRecord, MemoryWord, Status, box_mut, cat_code_mut, cur_font_mut,
-    day_mut, del_code_mut, end_line_char_mut, eq_level_mut, eq_type_mut,
-    equiv_mut, escape_char_mut, glue_ref_count_mut, hang_after_mut, info_mut,
-    lc_code_mut, link_mut, llink_mut, mag, mag_mut, math_code_mut,
-    max_dead_cycles_mut, month_mut, next_mut, node_size_mut, par_shape_ptr_mut,
-    rlink_mut, sf_code_mut, shrink_mut, shrink_order_mut, stretch_mut,
-    stretch_order_mut, subtype_mut, text_mut, time_mut, tolerance_mut, type_mut,
-    uc_code_mut, year_mut
+    Eqtb, Hash, XeqLevel, InStateRecord, InputFile, LineStack,
+    ListStateRecord, MemoryWord, Status
 };
 use crate::error::{TeXError, TeXResult};
 use crate::io::{
-    AlphaFileIn, AlphaFileOut, ByteFileIn, ByteFileOut
+    AlphaFileIn, AlphaFileOut, ByteFileIn, ByteFileOut, DiskFileSystem, SearchPath
 };
 use crate::parser::{TrieOpHash, TrieTaken};
-use crate::strings::str_ptr;
+use crate::pdf::PdfFile;
+use crate::strings::StrPool;
+use crate::svg::SvgFile;
 use crate::{
-    Global, HalfWord, Integer, QuarterWord, SmallNumber, hi, page_depth, update_terminal
+    Global, HalfWord, Integer, OutputFormat, QuarterWord, SmallNumber, hi, page_depth,
+    update_terminal
 };
 
-use std::io::Write;
-
 // Initialization
 impl Default for Global {
     fn default() -> Self {
+        Self::new(&Capacities::default())
+    }
+}
+
+impl Global {
+    // The arrays are allocated with the sizes of `capacities`.
+    pub fn new(capacities: &Capacities) -> Self {
+        let mem_top = MEM_BOT + capacities.main_memory - 1;
+        let font_max = capacities.font_max;
         Self {
             initex_mode: false,
 
+            // Section 11
+            mem_max: mem_top,
+            mem_top,
+            extra_mem_top: capacities.extra_mem_top,
+            buf_size: capacities.buf_size,
+            stack_size: capacities.stack_size,
+            max_in_open: capacities.max_in_open,
+            font_max,
+            font_mem_size: capacities.font_mem_size,
+            param_size: capacities.param_size,
+            nest_size: capacities.nest_size,
+            max_strings: capacities.max_strings,
+            pool_size: capacities.pool_size,
+            save_size: capacities.save_size,
+            trie_size: capacities.trie_size,
+            hash_extra: capacities.hash_extra,
+
             // Section 26
             name_of_file: String::new(),
+            file_system: Box::new(DiskFileSystem),
+            input_path: SearchPath::new(None, TEX_INPUTS_DEFAULT),
+            font_path: SearchPath::new(None, TFM_FONTS_DEFAULT),
+            format_path: SearchPath::new(None, TEX_FORMATS_DEFAULT),
+            output_directory: None,
+            diagnostics: None,
 
             // Section 30
-            buffer: [0; (BUF_SIZE + 1) as usize],
+            buffer: vec![0; (capacities.buf_size + 1) as usize],
             buffer_string: String::new(),
             first: 0,
             last: 0,
             max_buf_stack: 0,
 
+            // Section 32
+            term_in: AlphaFileIn::t_open_in(),
+            term_out: AlphaFileOut::t_open_out(),
+
             // Section 54
             log_file: AlphaFileOut::new(),
             selector: 0,
@@ -54,19 +85,38 @@ impl Default for Global {
 
             // Section 73
             interaction: 0,
+            file_line_error: false,
 
             // Section 76
+            deletions_allowed: true,
             set_box_allowed: false,
             history: 0,
+            error_count: 0,
+            halt_on_error: false,
+
+            // Section 79
+            help_line: vec![],
+            use_err_help: false,
+            err_message: vec![],
+            recording_err_message: false,
+            reported_errors: None,
 
             // Section 96
             interrupt: false,
             ok_to_interrupt: false,
 
+            // Section 104
+            arith_error: false,
+
+            // Section 39
+            pool: StrPool::new(capacities.pool_size, capacities.max_strings),
+
             // Section 115
             temp_ptr: 0,
 
             // Section 116
+            mem: vec![MemoryWord::ZERO; (mem_top - MEM_MIN + 1) as usize],
+            mem_min: MEM_MIN,
             lo_mem_max: 0,
             hi_mem_min: 0,
 
@@ -83,9 +133,9 @@ impl Default for Global {
 
             // Section 165
             #[cfg(feature = "debug")]
-            free: [false; (MEM_MAX - MEM_MIN  +1) as usize],
+            free: vec![false; (mem_top - MEM_MIN  +1) as usize],
             #[cfg(feature = "debug")]
-            was_free: [false; (MEM_MAX - MEM_MIN  + 1) as usize],
+            was_free: vec![false; (mem_top - MEM_MIN  + 1) as usize],
             #[cfg(feature = "debug")]
             was_mem_end: 0,
             #[cfg(feature = "debug")]
@@ -103,7 +153,7 @@ impl Default for Global {
             breadth_max: 0,
 
             // Section 213
-            nest: [ListStateRecord::default(); (NEST_SIZE + 1) as usize],
+            nest: vec![ListStateRecord::default(); (capacities.nest_size + 1) as usize],
             nest_ptr: 0,
             max_nest_stack: 0,
             cur_list: ListStateRecord::default(),
@@ -112,13 +162,19 @@ impl Default for Global {
             // Section 246
             old_setting: 0,
 
+            // Section 253
+            eqtb: Eqtb::new(capacities.hash_extra),
+            xeq_level: XeqLevel::default(),
+
             // Section 256
+            hash: Hash::new(capacities.hash_extra),
             hash_used: 0,
+            hash_high: 0,
             no_new_control_sequence: false,
             cs_count: 0,
 
             // Section 271
-            save_stack: [MemoryWord::ZERO; (SAVE_SIZE + 1) as usize],
+            save_stack: vec![MemoryWord::ZERO; (capacities.save_size + 1) as usize],
             save_ptr: 0,
             max_save_stack: 0,
             cur_level: 0,
@@ -135,7 +191,7 @@ impl Default for Global {
             cur_tok: 0,
 
             // Section 301
-            input_stack: [InStateRecord::default(); (STACK_SIZE + 1) as usize],
+            input_stack: vec![InStateRecord::default(); (capacities.stack_size + 1) as usize],
             input_ptr: 0,
             max_in_stack: 0,
             cur_input: InStateRecord::default(),
@@ -143,9 +199,10 @@ impl Default for Global {
             // Section 304
             in_open: 0,
             open_parens: 0,
-            input_file: InputFile([AlphaFileIn::INIT; MAX_IN_OPEN as usize]),
+            input_file: InputFile((0..capacities.max_in_open).map(|_| AlphaFileIn::INIT).collect()),
             line: 0,
-            line_stack: LineStack([0; MAX_IN_OPEN as usize]),
+            line_stack: LineStack(vec![0; capacities.max_in_open as usize]),
+            source_filename_stack: vec![String::new(); capacities.max_in_open as usize + 1],
 
             // Section 305
             scanner_status: Status::Normal,
@@ -153,7 +210,7 @@ impl Default for Global {
             def_ref: 0,
 
             // Section 308
-            param_stack: [0; (PARAM_SIZE + 1) as usize],
+            param_stack: vec![0; (capacities.param_size + 1) as usize],
             param_ptr: 0,
             max_param_stack: 0,
 
@@ -215,9 +272,15 @@ impl Default for Global {
             name_in_progress: false,
             job_name: 0,
             log_opened: false,
+            job_name_override: None,
 
             // Section 532
             dvi_file: ByteFileOut::new(),
+            output_format: OutputFormat::Dvi,
+            pdf: PdfFile::new(font_max),
+            svg: SvgFile::new(),
+            type1_directory: TEX_TYPE1_AREA.to_string(),
+            font_map_file: None,
             output_file_name: 0,
             log_name: 0,
 
@@ -225,35 +288,35 @@ impl Default for Global {
             tfm_file: ByteFileIn::new(),
 
             // Section 549
-            font_info: [MemoryWord::ZERO; (FONT_MEM_SIZE + 1) as usize],
+            font_info: vec![MemoryWord::ZERO; (capacities.font_mem_size + 1) as usize],
             fmem_ptr: 0,
             font_ptr: 0,
-            font_check: [MemoryWord::ZERO; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_size: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_dsize: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_params: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_name: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_area: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_bc: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_ec: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_glue: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_used: [false; (FONT_MAX - FONT_BASE + 1) as usize],
-            hyphen_char: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            skew_char: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            bchar_label: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_bchar: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            font_false_bchar: [0; (FONT_MAX - FONT_BASE + 1) as usize],
+            font_check: vec![MemoryWord::ZERO; (font_max - FONT_BASE + 1) as usize],
+            font_size: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_dsize: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_params: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_name: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_area: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_bc: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_ec: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_glue: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_used: vec![false; (font_max - FONT_BASE + 1) as usize],
+            hyphen_char: vec![0; (font_max - FONT_BASE + 1) as usize],
+            skew_char: vec![0; (font_max - FONT_BASE + 1) as usize],
+            bchar_label: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_bchar: vec![0; (font_max - FONT_BASE + 1) as usize],
+            font_false_bchar: vec![0; (font_max - FONT_BASE + 1) as usize],
 
             // Section 550
-            char_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            width_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            height_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            depth_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            italic_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            lig_kern_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            kern_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            exten_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
-            param_base: [0; (FONT_MAX - FONT_BASE + 1) as usize],
+            char_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            width_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            height_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            depth_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            italic_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            lig_kern_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            kern_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            exten_base: vec![0; (font_max - FONT_BASE + 1) as usize],
+            param_base: vec![0; (font_max - FONT_BASE + 1) as usize],
 
             // Section 555
             null_character: MemoryWord::ZERO,
@@ -344,6 +407,9 @@ impl Default for Global {
             background: Array1to6::default(),
             break_width: Array1to6::default(),
 
+            // Section 826
+            no_shrink_error_yet: true,
+
             // Section 828
             cur_p: 0,
             second_pass: false,
@@ -411,7 +477,7 @@ impl Default for Global {
             // Section 921
             // hyf_distance, hyf_num, hyf_next:
             // we start indexing with 1, value in index 0 is unused.
-            trie: [MemoryWord::ZERO; (TRIE_SIZE + 1) as usize],
+            trie: vec![MemoryWord::ZERO; (capacities.trie_size + 1) as usize],
             hyf_distance: HyfArray::default(),
             hyf_num: HyfArray::default(),
             hyf_next: HyfArray::default(),
@@ -430,15 +496,15 @@ impl Default for Global {
             trie_op_ptr: 0,
 
             // Section 947
-            trie_c: [0; (TRIE_SIZE + 1) as usize],
-            trie_o: [0; (TRIE_SIZE + 1) as usize],
-            trie_l: [0; (TRIE_SIZE + 1) as usize],
-            trie_r: [0; (TRIE_SIZE + 1) as usize],
+            trie_c: vec![0; (capacities.trie_size + 1) as usize],
+            trie_o: vec![0; (capacities.trie_size + 1) as usize],
+            trie_l: vec![0; (capacities.trie_size + 1) as usize],
+            trie_r: vec![0; (capacities.trie_size + 1) as usize],
             trie_ptr: 0,
-            trie_hash: [0; (TRIE_SIZE + 1) as usize],
+            trie_hash: vec![0; (capacities.trie_size + 1) as usize],
 
             // Section 950
-            trie_taken: TrieTaken::default(),
+            trie_taken: TrieTaken::new(capacities.trie_size),
             trie_min: [0; 256],
             trie_max: 0,
             trie_not_ready: false,
@@ -482,6 +548,9 @@ impl Default for Global {
             // Section 1266
             after_token: 0,
 
+            // Section 1281
+            long_help_seen: false,
+
             // Section 1299
             format_ident: 0,
 
@@ -509,15 +578,16 @@ impl Global {
         // End section 55
 
         // Section 61
-        print!("{BANNER}");
+        self.term_out.write_str(BANNER);
         if self.format_ident == 0 {
-            println!(" (no format preloaded)");
+            self.term_out.write_str(" (no format preloaded)");
+            self.term_out.write_cr();
         }
         else {
             self.slow_print(self.format_ident);
             self.print_ln();
         }
-        update_terminal!();
+        update_terminal!(self);
         // End section 61
 
         // Section 528
@@ -533,190 +603,194 @@ impl Global {
 
     // Section 288
     pub(crate) fn prepare_mag(&mut self) -> TeXResult<()> {
-        if self.mag_set > 0 && mag() != self.mag_set {
-            return Err(TeXError::IncompatibleMag);
+        if self.mag_set > 0 && self.mag() != self.mag_set {
+            self.error(TeXError::IncompatibleMag)?; // int_error
+            self.geq_word_define(INT_BASE + MAG_CODE, self.mag_set);
         }
-        if mag() <= 0 || mag() > 32768 {
-            return Err(TeXError::IllegalMag(mag()));
+        if self.mag() <= 0 || self.mag() > 32768 {
+            self.error(TeXError::IllegalMag(self.mag()))?; // int_error
+            self.geq_word_define(INT_BASE + MAG_CODE, 1000);
         }
-        self.mag_set = mag();
+        self.mag_set = self.mag();
         Ok(())
     }
 
     fn sec164_initialize_table_entries(&mut self) {
         // Section 164
-        unsafe {
-            for word in MEM[(MEM_BOT + 1) as usize..=(LO_MEM_STAT_MAX as usize)].iter_mut() {
-                word.sc = 0;
-            }
+        for word in self.mem[((MEM_BOT + 1 - self.mem_min) as usize)..=((LO_MEM_STAT_MAX - self.mem_min) as usize)].iter_mut() {
+            *word.sc_mut() = 0;
         }
         let mut k = MEM_BOT;
         while k <= LO_MEM_STAT_MAX {
-            *glue_ref_count_mut(k) = NULL + 1;
-            *stretch_order_mut(k) = NORMAL;
-            *shrink_order_mut(k) = NORMAL;
+            *self.glue_ref_count_mut(k) = NULL + 1;
+            *self.stretch_order_mut(k) = NORMAL;
+            *self.shrink_order_mut(k) = NORMAL;
             k += GLUE_SPEC_SIZE;
         }
 
-        *stretch_mut(FIL_GLUE) = UNITY;
-        *stretch_order_mut(FIL_GLUE) = FIL;
+        *self.stretch_mut(FIL_GLUE) = UNITY;
+        *self.stretch_order_mut(FIL_GLUE) = FIL;
 
-        *stretch_mut(FILL_GLUE) = UNITY;
-        *stretch_order_mut(FILL_GLUE) = FILL;
+        *self.stretch_mut(FILL_GLUE) = UNITY;
+        *self.stretch_order_mut(FILL_GLUE) = FILL;
 
-        *stretch_mut(SS_GLUE) = UNITY;
-        *stretch_order_mut(SS_GLUE) = FIL;
+        *self.stretch_mut(SS_GLUE) = UNITY;
+        *self.stretch_order_mut(SS_GLUE) = FIL;
 
-        *shrink_mut(SS_GLUE) = UNITY;
-        *shrink_order_mut(SS_GLUE) = FIL;
+        *self.shrink_mut(SS_GLUE) = UNITY;
+        *self.shrink_order_mut(SS_GLUE) = FIL;
 
-        *stretch_mut(FIL_NEG_GLUE) = -UNITY;
-        *stretch_order_mut(FIL_NEG_GLUE) = FIL;
+        *self.stretch_mut(FIL_NEG_GLUE) = -UNITY;
+        *self.stretch_order_mut(FIL_NEG_GLUE) = FIL;
 
         self.rover = LO_MEM_STAT_MAX + 1;
-        *link_mut(self.rover) = EMPTY_FLAG;
-        *node_size_mut(self.rover) = 1000;
-        *llink_mut(self.rover) = self.rover;
-        *rlink_mut(self.rover) = self.rover;
+        *self.link_mut(self.rover) = EMPTY_FLAG;
+        *self.node_size_mut(self.rover) = 1000;
+        *self.llink_mut(self.rover) = self.rover;
+        *self.rlink_mut(self.rover) = self.rover;
         self.lo_mem_max = self.rover + 1000;
-        *link_mut(self.lo_mem_max) = NULL;
-        *info_mut(self.lo_mem_max) = NULL;
-        unsafe {
-            MEM[(HI_MEM_STAT_MIN as usize)..=(MEM_TOP as usize)].fill(MEM[self.lo_mem_max as usize]);
-        }
+        *self.link_mut(self.lo_mem_max) = NULL;
+        *self.info_mut(self.lo_mem_max) = NULL;
+        let w = self.mem(self.lo_mem_max as usize);
+        let (hi_mem_stat_min, mem_top) = (self.hi_mem_stat_min(), self.mem_top);
+        self.mem[((hi_mem_stat_min - self.mem_min) as usize)..=((mem_top - self.mem_min) as usize)].fill(w);
 
         // Section 790
-        *info_mut(OMIT_TEMPLATE) = END_TEMPLATE_TOKEN;
+        *self.info_mut(self.omit_template()) = END_TEMPLATE_TOKEN;
         // End section 790
 
         // Section 797
-        *link_mut(END_SPAN) = (MAX_QUARTERWORD as HalfWord) + 1;
-        *info_mut(END_SPAN) = NULL;
+        *self.link_mut(self.end_span()) = (MAX_QUARTERWORD as HalfWord) + 1;
+        *self.info_mut(self.end_span()) = NULL;
         // End section 797
 
         // Section 820
-        *type_mut(LAST_ACTIVE) = HYPHENATED;
-        *line_number_mut(LAST_ACTIVE) = MAX_HALFWORD;
-        *subtype_mut(LAST_ACTIVE) = 0;
+        *self.type_mut(self.last_active()) = HYPHENATED;
+        *self.line_number_mut(self.last_active()) = MAX_HALFWORD;
+        *self.subtype_mut(self.last_active()) = 0;
         // End section 820
 
         // Section 981
-        *subtype_mut(PAGE_INS_HEAD) = 255;
-        *type_mut(PAGE_INS_HEAD) = SPLIT_UP;
-        *link_mut(PAGE_INS_HEAD) = PAGE_INS_HEAD;
+        *self.subtype_mut(self.page_ins_head()) = 255;
+        *self.type_mut(self.page_ins_head()) = SPLIT_UP;
+        *self.link_mut(self.page_ins_head()) = self.page_ins_head();
         // End section 981
 
         // Section 988
-        *type_mut(PAGE_HEAD) = GLUE_NODE;
-        *subtype_mut(PAGE_HEAD) = NORMAL;
+        *self.type_mut(self.page_head()) = GLUE_NODE;
+        *self.subtype_mut(self.page_head()) = NORMAL;
         // End section 988
         // End section 790
 
         self.avail = NULL;
-        self.mem_end = MEM_TOP;
-        self.hi_mem_min = HI_MEM_STAT_MIN;
+        self.mem_end = self.mem_top;
+        self.hi_mem_min = self.hi_mem_stat_min();
         self.var_used = LO_MEM_STAT_MAX + 1 - MEM_BOT;
         self.dyn_used = HI_MEM_STAT_USAGE;
         // End section 164
 
         // Section 222
-        *eq_type_mut(UNDEFINED_CONTROL_SEQUENCE) = UNDEFINED_CS;
-        *equiv_mut(UNDEFINED_CONTROL_SEQUENCE) = NULL;
-        *eq_level_mut(UNDEFINED_CONTROL_SEQUENCE) = LEVEL_ZERO;
+        *self.eq_type_mut(UNDEFINED_CONTROL_SEQUENCE) = UNDEFINED_CS;
+        *self.equiv_mut(UNDEFINED_CONTROL_SEQUENCE) = NULL;
+        *self.eq_level_mut(UNDEFINED_CONTROL_SEQUENCE) = LEVEL_ZERO;
         for k in ACTIVE_BASE..UNDEFINED_CONTROL_SEQUENCE {
-            *eqtb_mut(k as usize) = eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
+        }
+        for k in (EQTB_SIZE + 1)..=self.eqtb_top() {
+            *self.eqtb_mut(k as usize) = self.eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
         }
         // End section 222
 
         // Section 228
-        *equiv_mut(GLUE_BASE) = ZERO_GLUE;
-        *eq_level_mut(GLUE_BASE) = LEVEL_ONE;
-        *eq_type_mut(GLUE_BASE) = GLUE_REF;
+        *self.equiv_mut(GLUE_BASE) = ZERO_GLUE;
+        *self.eq_level_mut(GLUE_BASE) = LEVEL_ONE;
+        *self.eq_type_mut(GLUE_BASE) = GLUE_REF;
         for k in (GLUE_BASE + 1)..LOCAL_BASE {
-            *eqtb_mut(k as usize) = eqtb(GLUE_BASE as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(GLUE_BASE as usize);
         }
-        *glue_ref_count_mut(ZERO_GLUE) += LOCAL_BASE - GLUE_BASE;
+        *self.glue_ref_count_mut(ZERO_GLUE) += LOCAL_BASE - GLUE_BASE;
         // End section 228
 
         // Section 232
-        *par_shape_ptr_mut() = NULL;
-        *eq_type_mut(PAR_SHAPE_LOC) = SHAPE_REF;
-        *eq_level_mut(PAR_SHAPE_LOC) = LEVEL_ONE;
+        *self.par_shape_ptr_mut() = NULL;
+        *self.eq_type_mut(PAR_SHAPE_LOC) = SHAPE_REF;
+        *self.eq_level_mut(PAR_SHAPE_LOC) = LEVEL_ONE;
         for k in OUTPUT_ROUTINE_LOC..=(TOKS_BASE + 255) {
-            *eqtb_mut(k as usize) = eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
         }
-        *box_mut(0) = NULL;
-        *eq_type_mut(BOX_BASE) = BOX_REF;
-        *eq_level_mut(BOX_BASE) = LEVEL_ONE;
+        *self.box_mut(0) = NULL;
+        *self.eq_type_mut(BOX_BASE) = BOX_REF;
+        *self.eq_level_mut(BOX_BASE) = LEVEL_ONE;
         for k in (BOX_BASE + 1)..=(BOX_BASE + 255) {
-            *eqtb_mut(k as usize) = eqtb(BOX_BASE as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(BOX_BASE as usize);
         }
-        *cur_font_mut() = NULL_FONT;
-        *eq_type_mut(CUR_FONT_LOC) = DATA ;
-        *eq_level_mut(CUR_FONT_LOC) = LEVEL_ONE;
+        *self.cur_font_mut() = NULL_FONT;
+        *self.eq_type_mut(CUR_FONT_LOC) = DATA ;
+        *self.eq_level_mut(CUR_FONT_LOC) = LEVEL_ONE;
         for k in MATH_FONT_BASE..=(MATH_FONT_BASE + 47) {
-            *eqtb_mut(k as usize) = eqtb(CUR_FONT_LOC as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(CUR_FONT_LOC as usize);
         }
-        *equiv_mut(CAT_CODE_BASE) = 0;
-        *eq_type_mut(CAT_CODE_BASE) = DATA;
-        *eq_level_mut(CAT_CODE_BASE) = LEVEL_ONE;
+        *self.equiv_mut(CAT_CODE_BASE) = 0;
+        *self.eq_type_mut(CAT_CODE_BASE) = DATA;
+        *self.eq_level_mut(CAT_CODE_BASE) = LEVEL_ONE;
         for k in (CAT_CODE_BASE + 1)..INT_BASE {
-            *eqtb_mut(k as usize) = eqtb(CAT_CODE_BASE as usize);
+            *self.eqtb_mut(k as usize) = self.eqtb(CAT_CODE_BASE as usize);
         }
         for k in 0..=255 {
-            *cat_code_mut(k) = OTHER_CHAR as HalfWord;
-            *math_code_mut(k) = hi!(k);
-            *sf_code_mut(k) = 1000;
+            *self.cat_code_mut(k) = OTHER_CHAR as HalfWord;
+            *self.math_code_mut(k) = hi!(k);
+            *self.sf_code_mut(k) = 1000;
         }
-        *cat_code_mut(CARRIAGE_RETURN) = CAR_RET as HalfWord;
-        *cat_code_mut(b' ' as HalfWord) = SPACER as HalfWord;
-        *cat_code_mut(b'\\' as HalfWord) = ESCAPE as HalfWord;
-        *cat_code_mut(b'%' as HalfWord) = COMMENT as HalfWord;
-        *cat_code_mut(INVALID_CODE) = INVALID_CHAR as HalfWord;
-        *cat_code_mut(NULL_CODE) = IGNORE as HalfWord;
+        *self.cat_code_mut(CARRIAGE_RETURN) = CAR_RET as HalfWord;
+        *self.cat_code_mut(b' ' as HalfWord) = SPACER as HalfWord;
+        *self.cat_code_mut(b'\\' as HalfWord) = ESCAPE as HalfWord;
+        *self.cat_code_mut(b'%' as HalfWord) = COMMENT as HalfWord;
+        *self.cat_code_mut(INVALID_CODE) = INVALID_CHAR as HalfWord;
+        *self.cat_code_mut(NULL_CODE) = IGNORE as HalfWord;
         for k in b'0'..=b'9' {
-            *math_code_mut(k as HalfWord) = hi!(k as Integer + VAR_CODE);
+            *self.math_code_mut(k as HalfWord) = hi!(k as Integer + VAR_CODE);
         }
         for k in (b'A' as HalfWord)..=(b'Z' as HalfWord) {
-            *cat_code_mut(k) = LETTER as HalfWord;
-            *cat_code_mut(k + (b'a' - b'A') as HalfWord) = LETTER as HalfWord;
-            *math_code_mut(k) = hi!(k + VAR_CODE as HalfWord + 256);
-            *math_code_mut(k + (b'a' - b'A') as HalfWord) = hi!((k + (b'a' - b'A') as HalfWord + VAR_CODE + 256));
-            *lc_code_mut(k) = k + (b'a' - b'A') as HalfWord;
-            *lc_code_mut(k + (b'a' - b'A') as HalfWord) = k + (b'a' - b'A') as HalfWord;
-            *uc_code_mut(k) = k;
-            *uc_code_mut(k + (b'a' - b'A') as HalfWord) = k;
-            *sf_code_mut(k) = 999;
+            *self.cat_code_mut(k) = LETTER as HalfWord;
+            *self.cat_code_mut(k + (b'a' - b'A') as HalfWord) = LETTER as HalfWord;
+            *self.math_code_mut(k) = hi!(k + VAR_CODE as HalfWord + 256);
+            *self.math_code_mut(k + (b'a' - b'A') as HalfWord) = hi!((k + (b'a' - b'A') as HalfWord + VAR_CODE + 256));
+            *self.lc_code_mut(k) = k + (b'a' - b'A') as HalfWord;
+            *self.lc_code_mut(k + (b'a' - b'A') as HalfWord) = k + (b'a' - b'A') as HalfWord;
+            *self.uc_code_mut(k) = k;
+            *self.uc_code_mut(k + (b'a' - b'A') as HalfWord) = k;
+            *self.sf_code_mut(k) = 999;
         }
         // End section 232
 
         // Section 240
         for k in INT_BASE..DEL_CODE_BASE {
-            *eqtb_mut(k as usize).int_mut() = 0;
+            *self.eqtb_mut(k as usize).int_mut() = 0;
         }
-        *mag_mut() = 1000;
-        *tolerance_mut() = 10000;
-        *hang_after_mut() = 1;
-        *max_dead_cycles_mut() = 25;
-        *escape_char_mut() = b'\\' as Integer;
-        *end_line_char_mut() = CARRIAGE_RETURN;
+        *self.mag_mut() = 1000;
+        *self.tolerance_mut() = 10000;
+        *self.hang_after_mut() = 1;
+        *self.max_dead_cycles_mut() = 25;
+        *self.escape_char_mut() = b'\\' as Integer;
+        *self.end_line_char_mut() = CARRIAGE_RETURN;
         for k in 0..=255 {
-            *del_code_mut(k) = -1;
+            *self.del_code_mut(k) = -1;
         }
-        *del_code_mut(b'.' as HalfWord) = 0;
+        *self.del_code_mut(b'.' as HalfWord) = 0;
         // End section 240
 
         // Section 250
         for k in DIMEN_BASE..=EQTB_SIZE {
-            *eqtb_mut(k as usize).sc_mut() = 0;
+            *self.eqtb_mut(k as usize).sc_mut() = 0;
         }
         // End section 250
 
         // Section 258
         self.hash_used = FROZEN_CONTROL_SEQUENCE;
+        self.hash_high = 0;
         self.cs_count = 0;
-        *eq_type_mut(FROZEN_DONT_EXPAND) = DONT_EXPAND;
-        *text_mut(FROZEN_DONT_EXPAND) = NOTEXPANDED_STRING as HalfWord;
+        *self.eq_type_mut(FROZEN_DONT_EXPAND) = DONT_EXPAND;
+        *self.text_mut(FROZEN_DONT_EXPAND) = NOTEXPANDED_STRING as HalfWord;
         // End section 258
 
         // Section 552
@@ -765,7 +839,7 @@ impl Global {
         // End section 951
 
         // Section 1216
-        *text_mut(FROZEN_PROTECTION) = INACCESSIBLE_STRING as HalfWord;
+        *self.text_mut(FROZEN_PROTECTION) = INACCESSIBLE_STRING as HalfWord;
         // End section 1216
 
         // Section 1301
@@ -773,10 +847,10 @@ impl Global {
         // End Section 1301
 
         // Section 1369
-        *text_mut(END_WRITE) = ENDWRITE_STRING as HalfWord;
-        *eq_level_mut(END_WRITE) = LEVEL_ONE;
-        *eq_type_mut(END_WRITE) = OUTER_CALL;
-        *equiv_mut(END_WRITE) = NULL;
+        *self.text_mut(END_WRITE) = ENDWRITE_STRING as HalfWord;
+        *self.eq_level_mut(END_WRITE) = LEVEL_ONE;
+        *self.eq_type_mut(END_WRITE) = OUTER_CALL;
+        *self.equiv_mut(END_WRITE) = NULL;
         // End section 1369
     }
 }
@@ -808,23 +882,33 @@ fn epoch_to_date(mut days: u64) -> (Integer, Integer, Integer) {
     (y, m, d)
 }
 
-// Section 241
-// The time is UTC.
-pub fn fix_date_and_time() {
-    let now = std::time::SystemTime::now();
-    match now.duration_since(std::time::UNIX_EPOCH) {
-        Ok(time) => {
-            let seconds = time.as_secs();
-            let days = seconds / 86_400;
-            *time_mut() = ((seconds % 86_400) / 60) as Integer;
-            (*year_mut(), *month_mut(), *day_mut()) = epoch_to_date(days);
-        },
-        Err(_) => {
-            // Default date and time if there is a problem
-            *time_mut() = 12 * 60;
-            *day_mut() = 14;
-            *month_mut() = 7;
-            *year_mut() = 1789;   
+impl Global {
+    // Section 241
+    // The time is UTC.
+    pub fn fix_date_and_time(&mut self) {
+        // The TRIP test expects the date of `tex.web`.
+        if cfg!(feature = "trip") {
+            *self.time_mut() = 12 * 60;
+            *self.day_mut() = 4;
+            *self.month_mut() = 7;
+            *self.year_mut() = 1776;
+            return;
+        }
+        let now = std::time::SystemTime::now();
+        match now.duration_since(std::time::UNIX_EPOCH) {
+            Ok(time) => {
+                let seconds = time.as_secs();
+                let days = seconds / 86_400;
+                *self.time_mut() = ((seconds % 86_400) / 60) as Integer;
+                (*self.year_mut(), *self.month_mut(), *self.day_mut()) = epoch_to_date(days);
+            },
+            Err(_) => {
+                // Default date and time if there is a problem
+                *self.time_mut() = 12 * 60;
+                *self.day_mut() = 14;
+                *self.month_mut() = 7;
+                *self.year_mut() = 1789;   
+            }
         }
     }
 }
@@ -837,7 +921,11 @@ impl Global {
         // End section 74
 
         // Section 77
+        self.deletions_allowed = true;
         self.set_box_allowed = true;
+        self.error_count = 0;
+        self.help_line.clear();
+        self.use_err_help = false;
         // End section 77
 
         // Section 97
@@ -848,9 +936,9 @@ impl Global {
         // Section 166
         #[cfg(feature = "debug")]
         {
-            self.was_mem_end = MEM_MIN;
-            self.was_lo_max = MEM_MIN;
-            self.was_hi_min = MEM_MAX;
+            self.was_mem_end = self.mem_min;
+            self.was_lo_max = self.mem_min;
+            self.was_hi_min = self.mem_max;
             self.panicking = false;
         }
 
@@ -858,16 +946,16 @@ impl Global {
         self.nest_ptr = 0;
         self.max_nest_stack = 0;
         *self.mode_mut() = VMODE;
-        *self.head_mut() = CONTRIB_HEAD;
-        *self.tail_mut() = CONTRIB_HEAD;
+        *self.head_mut() = self.contrib_head();
+        *self.tail_mut() = self.contrib_head();
         *self.prev_depth_mut() = IGNORE_DEPTH;
         *self.mode_line_mut() = 0;
         *self.prev_graf_mut() = 0;
         self.shown_mode = 0;
         // Section 991
         self.page_contents = EMPTY as SmallNumber;
-        self.page_tail = PAGE_HEAD;
-        *link_mut(PAGE_HEAD) = NULL;
+        self.page_tail = self.page_head();
+        *self.link_mut(self.page_head()) = NULL;
         self.last_glue = MAX_HALFWORD;
         self.last_penalty = 0;
         self.last_kern = 0;
@@ -878,17 +966,15 @@ impl Global {
 
         // Section 254
         for k in INT_BASE..=EQTB_SIZE {
-            unsafe {
-                XEQ_LEVEL[k as usize] = LEVEL_ONE;
-            }
+            self.xeq_level[k as usize] = LEVEL_ONE;
         }
 
         // Section 257
         self.no_new_control_sequence = true;
-        *next_mut(HASH_BASE) = 0;
-        *text_mut(HASH_BASE) = 0;
-        for k in (HASH_BASE + 1)..UNDEFINED_CONTROL_SEQUENCE {
-            *hash_mut(k as usize) = hash(HASH_BASE as usize);
+        *self.next_mut(HASH_BASE) = 0;
+        *self.text_mut(HASH_BASE) = 0;
+        for k in (HASH_BASE + 1)..=self.eqtb_top() {
+            *self.hash_mut(k as usize) = self.hash(HASH_BASE as usize);
         }
 
         // Section 272
@@ -1004,6 +1090,10 @@ impl Global {
         self.after_token = 0;
         // End section 1267
 
+        // Section 1282
+        self.long_help_seen = false;
+        // End section 1282
+
         // Section 1300
         self.format_ident = 0;
         // End section 1300
@@ -1025,7 +1115,7 @@ impl Global {
         self.scanner_status = Status::Normal;
         self.warning_index = NULL;
         *self.state_mut() = NEW_LINE;
-        *self.start_mut() = 0;
+        *self.start_mut() = 1;
         *self.index_mut() = 0;
         self.line = 0;
         *self.name_mut() = 0;
@@ -1165,8 +1255,8 @@ impl Global {
         self.primitive(b"divide", DIVIDE, 0)?;
         self.primitive(b"endcsname", END_CS_NAME, 0)?;
         self.primitive(b"endgroup", END_GROUP, 0)?;
-        *text_mut(FROZEN_END_GROUP) = (str_ptr() - 1) as HalfWord; // "endgroup"
-        *eqtb_mut(FROZEN_END_GROUP as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_END_GROUP) = (self.str_ptr() - 1) as HalfWord; // "endgroup"
+        *self.eqtb_mut(FROZEN_END_GROUP as usize) = self.eqtb(self.cur_val as usize);
 
         self.primitive(b"expandafter", EXPAND_AFTER, 0)?;
         self.primitive(b"font", DEF_FONT, 0)?;
@@ -1191,8 +1281,8 @@ impl Global {
         self.primitive(b"radical", RADICAL, 0)?;
         self.primitive(b"read", READ_TO_CS, 0)?;
         self.primitive(b"relax", RELAX, 256)?;
-        *text_mut(FROZEN_RELAX) = (str_ptr() - 1) as HalfWord; // "relax"
-        *eqtb_mut(FROZEN_RELAX as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_RELAX) = (self.str_ptr() - 1) as HalfWord; // "relax"
+        *self.eqtb_mut(FROZEN_RELAX as usize) = self.eqtb(self.cur_val as usize);
 
         self.primitive(b"setbox", SET_BOX, 0)?;
         self.primitive(b"the", THE, 0)?;
@@ -1267,29 +1357,29 @@ impl Global {
 
         // Section 491
         self.primitive(b"fi", FI_OR_ELSE, FI_CODE)?;
-        *text_mut(FROZEN_FI) = (str_ptr() - 1) as HalfWord; // "fi"
-        *eqtb_mut(FROZEN_FI as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_FI) = (self.str_ptr() - 1) as HalfWord; // "fi"
+        *self.eqtb_mut(FROZEN_FI as usize) = self.eqtb(self.cur_val as usize);
         self.primitive(b"or", FI_OR_ELSE, OR_CODE)?;
         self.primitive(b"else", FI_OR_ELSE, ELSE_CODE)?;
 
         // Section 553
         self.primitive(b"nullfont", SET_FONT, NULL_FONT)?;
-        *text_mut(FROZEN_NULL_FONT) = (str_ptr() - 1) as HalfWord; // "nullfont"
-        *eqtb_mut(FROZEN_NULL_FONT as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_NULL_FONT) = (self.str_ptr() - 1) as HalfWord; // "nullfont"
+        *self.eqtb_mut(FROZEN_NULL_FONT as usize) = self.eqtb(self.cur_val as usize);
 
         // Section 780
         self.primitive(b"span", TAB_MARK, SPAN_CODE)?;
         self.primitive(b"cr", CAR_RET, CR_CODE)?;
-        *text_mut(FROZEN_CR) = (str_ptr() - 1) as HalfWord; // "cr"
-        *eqtb_mut(FROZEN_CR as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_CR) = (self.str_ptr() - 1) as HalfWord; // "cr"
+        *self.eqtb_mut(FROZEN_CR as usize) = self.eqtb(self.cur_val as usize);
         self.primitive(b"crcr", CAR_RET, CR_CR_CODE)?;
-        *text_mut(FROZEN_END_TEMPLATE) = ENDTEMPLATE_STRING as HalfWord;
-        *text_mut(FROZEN_ENDV) = ENDTEMPLATE_STRING as HalfWord;
-        *eq_type_mut(FROZEN_ENDV) = ENDV;
-        *equiv_mut(FROZEN_ENDV) = NULL_LIST;
-        *eq_level_mut(FROZEN_ENDV) = LEVEL_ONE;
-        *eqtb_mut(FROZEN_END_TEMPLATE as usize) = eqtb(FROZEN_ENDV as usize);
-        *eq_type_mut(FROZEN_END_TEMPLATE) = END_TEMPLATE;
+        *self.text_mut(FROZEN_END_TEMPLATE) = ENDTEMPLATE_STRING as HalfWord;
+        *self.text_mut(FROZEN_ENDV) = ENDTEMPLATE_STRING as HalfWord;
+        *self.eq_type_mut(FROZEN_ENDV) = ENDV;
+        *self.equiv_mut(FROZEN_ENDV) = self.null_list();
+        *self.eq_level_mut(FROZEN_ENDV) = LEVEL_ONE;
+        *self.eqtb_mut(FROZEN_END_TEMPLATE as usize) = self.eqtb(FROZEN_ENDV as usize);
+        *self.eq_type_mut(FROZEN_END_TEMPLATE) = END_TEMPLATE;
 
         // Section 983
         self.primitive(b"pagegoal", SET_PAGE_DIMEN, 0)?;
@@ -1391,8 +1481,8 @@ impl Global {
         // Section 1188
         self.primitive(b"left", LEFT_RIGHT, LEFT_NOAD as HalfWord)?;
         self.primitive(b"right", LEFT_RIGHT, RIGHT_NOAD as HalfWord)?;
-        *text_mut(FROZEN_RIGHT) = (str_ptr() - 1) as HalfWord; // "right"
-        *eqtb_mut(FROZEN_RIGHT as usize) = eqtb(self.cur_val as usize);
+        *self.text_mut(FROZEN_RIGHT) = (self.str_ptr() - 1) as HalfWord; // "right"
+        *self.eqtb_mut(FROZEN_RIGHT as usize) = self.eqtb(self.cur_val as usize);
 
         // Section 1208
         self.primitive(b"long", PREFIX, 1)?;
@@ -1438,8 +1528,8 @@ impl Global {
         // Section 1262
         // \nonstopmode and \scrollmode are merged with \batch_mode
         self.primitive(b"batchmode", SET_INTERACTION, BATCH_MODE)?;
-        self.primitive(b"nonstopmode", SET_INTERACTION, BATCH_MODE)?;
-        self.primitive(b"scrollmode", SET_INTERACTION, BATCH_MODE)?;
+        self.primitive(b"nonstopmode", SET_INTERACTION, NONSTOP_MODE)?;
+        self.primitive(b"scrollmode", SET_INTERACTION, SCROLL_MODE)?;
         self.primitive(b"errorstopmode", SET_INTERACTION, ERROR_STOP_MODE)?;
 
         // Section 1272
@@ -1492,25 +1582,28 @@ impl Global {
         if DVI_BUF_SIZE % 8 != 0 {
             bad = 3
         }
-        if MEM_BOT + 1100 > MEM_TOP {
+        if MEM_BOT + 1100 > self.mem_top {
             bad = 4
         }
         if HASH_PRIME > HASH_SIZE {
             bad = 5
         }
-        if MAX_IN_OPEN >= 128 {
+        if self.max_in_open >= 128 {
             bad = 6
         }
-        if MEM_TOP < 256 + 11 {
+        if self.mem_top < 256 + 11 {
             bad = 7
         }
+        if self.capacities().below_minimum().is_some() {
+            bad = 8
+        }
         // End section 14
 
         // Section 111
-        if self.initex_mode && (MEM_MIN != MEM_BOT || MEM_MAX != MEM_TOP) {
+        if self.initex_mode && (MEM_MIN != MEM_BOT || self.mem_max != self.mem_top) {
             bad = 10;
         }
-        if MEM_MIN > MEM_BOT || MEM_MAX < MEM_TOP {
+        if MEM_MIN > MEM_BOT || self.mem_max < self.mem_top {
             bad = 10;
         }
         if MIN_QUARTERWORD > 0 || MAX_QUARTERWORD < 127 {
@@ -1525,25 +1618,25 @@ impl Global {
             bad = 13;
         }
         if MEM_MIN < MIN_HALFWORD
-            || MEM_MAX >= MAX_HALFWORD
+            || self.mem_max >= MAX_HALFWORD
             || (MEM_BOT - MEM_MIN) > MAX_HALFWORD + 1
         {
             bad = 14;
         }
         if FONT_BASE < (MIN_QUARTERWORD as Integer)
-            || FONT_MAX > (MAX_QUARTERWORD as Integer)
+            || self.font_max > (MAX_QUARTERWORD as Integer)
         {
             bad = 15;
         }
-        if FONT_MAX > FONT_BASE + 256 {
+        if self.font_max > FONT_BASE + 256 {
             bad = 16;
         }
-        if SAVE_SIZE > MAX_HALFWORD
-            || MAX_STRINGS > MAX_HALFWORD
+        if self.save_size > MAX_HALFWORD
+            || self.max_strings > MAX_HALFWORD
         {
             bad = 17;
         }
-        if BUF_SIZE > MAX_HALFWORD {
+        if self.buf_size > MAX_HALFWORD {
             bad = 18;
         }
         if MAX_QUARTERWORD - MIN_QUARTERWORD < 255 {
@@ -1552,13 +1645,13 @@ impl Global {
         // End section 111
 
         // Section 290
-        if CS_TOKEN_FLAG + UNDEFINED_CONTROL_SEQUENCE > MAX_HALFWORD {
+        if CS_TOKEN_FLAG + self.eqtb_top() > MAX_HALFWORD {
             bad = 21;
         }
         // End section 290
 
         // Section 1249
-        if 2*MAX_HALFWORD < MEM_TOP - MEM_MIN {
+        if 2*MAX_HALFWORD < self.mem_top - MEM_MIN {
             bad = 41;
         }
         // End section 1249
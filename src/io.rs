@@ -1,12 +1,17 @@
 mod alphafile;
 mod basic_printing;
 mod bytefile;
+mod deflate;
+mod diagnostics;
 mod display_boxes;
 mod display_context;
 mod display_math;
 mod display_tokens;
 mod dumping;
+mod file_system;
+mod fmtinfo;
 mod other_printing;
+mod search_path;
 mod terminal;
 
 pub(crate) use alphafile::{
@@ -14,10 +19,17 @@ pub(crate) use alphafile::{
 };
 
 pub(crate) use bytefile::{
-    ByteFileIn, ByteFileOut, ByteFileInSelector, ByteFileOutSelector
+    ByteFileIn, ByteFileOut, ByteFileInSelector, ByteFileOutSelector, Checksum
 };
 
-pub(crate) use terminal::term_input_string;
+pub(crate) use dumping::FormatError;
 
-#[cfg(feature = "debug")]
-pub(crate) use terminal::term_input_int;
+pub(crate) use search_path::SearchPath;
+
+pub use file_system::{
+    DiskFileSystem, FileSystem, MemoryFileSystem
+};
+
+pub use fmtinfo::{
+    FmtInfoOptions, FormatSource, find_format, fmt_info
+};
@@ -1,18 +1,15 @@
 use crate::constants::{
-    BANNER, BUF_SIZE, EXT_LOG, LOG_ONLY
-};
-use crate::datastructures::{
-    day, end_line_char, month, time, year
+    BANNER, EXT_LOG, LOG_ONLY
 };
 use crate::error::{TeXError, TeXResult};
 use crate::{
-    Global, HalfWord, Integer
+    Global, HalfWord, Integer, update_terminal
 };
 
-use std::fs::File;
-use std::io::{Write, BufRead, BufReader};
+use std::io::{BufRead, BufReader, Cursor, Write, stdin, stdout};
 
 #[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy)]
 pub(crate) enum AlphaFileInSelector {
     CurFile,
     ReadFile(usize),
@@ -25,16 +22,38 @@ pub(crate) enum AlphaFileOutSelector {
 }
 
 pub(crate) struct AlphaFileIn {
-    file: Option<BufReader<File>>
+    file: Option<Box<dyn BufRead + Send>>
 }
 
 pub(crate) struct AlphaFileOut {
-    file: Option<File>
+    file: Option<Box<dyn Write + Send>>
 }
 
 impl AlphaFileIn {
     pub(crate) const INIT: Self = Self { file: None };
 
+    // Section 33
+    pub(crate) fn t_open_in() -> Self {
+        Self {
+            file: Some(Box::new(BufReader::new(stdin())))
+        }
+    }
+
+    // A terminal with nothing to read.
+    pub(crate) fn t_open_in_empty() -> Self {
+        Self {
+            file: Some(Box::new(Cursor::new(vec![])))
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn read_line(&mut self, line: &mut String) -> std::io::Result<usize> {
+        match &mut self.file {
+            Some(f) => f.read_line(line),
+            None => Ok(0)
+        }
+    }
+
     pub(crate) fn close(&mut self) {
         self.file = None;
     }
@@ -49,6 +68,26 @@ impl AlphaFileOut {
         }
     }
 
+    // Section 33
+    pub(crate) fn t_open_out() -> Self {
+        Self {
+            file: Some(Box::new(stdout()))
+        }
+    }
+
+    // A terminal that prints nowhere.
+    pub(crate) fn t_open_out_sink() -> Self {
+        Self {
+            file: Some(Box::new(std::io::sink()))
+        }
+    }
+
+    pub(crate) fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            _ = file.flush();
+        }
+    }
+
     pub(crate) fn write_cr(&mut self) {
         if self.file.as_mut().unwrap().write_all(b"\n").is_err() {
             panic!();
@@ -81,32 +120,31 @@ impl AlphaFileOut {
 impl Global {
     // Section 27
     pub(crate) fn a_open_in(&mut self, selection: AlphaFileInSelector) -> bool {
-        match File::open(&self.name_of_file) {
-            Ok(file) => {
-                let alpha_file = match selection {
-                    AlphaFileInSelector::CurFile => self.cur_file_mut(),
-                    AlphaFileInSelector::ReadFile(n) => &mut self.read_file[n],
-                    AlphaFileInSelector::TermIn => return false,
-                };
-                alpha_file.file = Some(BufReader::new(file));
-                true
-            },
-            Err(_) => false
-        }
+        let file = match self.file_system.open_text(&self.name_of_file) {
+            Ok(file) => file,
+            Err(_) => return false
+        };
+        let alpha_file = match selection {
+            AlphaFileInSelector::CurFile => self.cur_file_mut(),
+            AlphaFileInSelector::ReadFile(n) => &mut self.read_file[n],
+            AlphaFileInSelector::TermIn => return false,
+        };
+        alpha_file.file = Some(file);
+        true
     }
 
     pub(crate) fn a_open_out(&mut self, selection: AlphaFileOutSelector) -> TeXResult<()> {
-        match File::create(&self.name_of_file) {
-            Ok(file) => {
-                let alpha_file = match selection {
-                    AlphaFileOutSelector::LogFile => &mut self.log_file,
-                    AlphaFileOutSelector::WriteFile(j) => &mut self.write_file[j],
-                };
-                alpha_file.file = Some(file);
-                Ok(())
-            },
-            Err(_) => Err(TeXError::CantWriteFile),
-        }
+        self.pack_output_directory();
+        let file = match self.file_system.create(&self.name_of_file) {
+            Ok(file) => file,
+            Err(_) => return Err(TeXError::CantWriteFile)
+        };
+        let alpha_file = match selection {
+            AlphaFileOutSelector::LogFile => &mut self.log_file,
+            AlphaFileOutSelector::WriteFile(j) => &mut self.write_file[j],
+        };
+        alpha_file.file = Some(file);
+        Ok(())
     }
 }
 
@@ -138,10 +176,12 @@ impl Global {
             },
 
             AlphaFileInSelector::TermIn => {
-                match std::io::stdin().read_line(&mut self.buffer_string) {
-                    Ok(0) => return Ok(false), // EOF
-                    Ok(_) => (),
-                    Err(_) => return Err(TeXError::IO("reading from terminal")),
+                if let Some(f) = &mut self.term_in.file {
+                    match f.read_line(&mut self.buffer_string) {
+                        Ok(0) => return Ok(false), // EOF
+                        Ok(_) => (),
+                        Err(_) => return Err(TeXError::IO("reading from terminal")),
+                    }
                 }
             }
         };
@@ -153,8 +193,8 @@ impl Global {
 
         if self.last >= self.max_buf_stack {
             self.max_buf_stack = self.last;
-            if self.max_buf_stack >= BUF_SIZE {
-                return Err(TeXError::Overflow("buffer size", BUF_SIZE));
+            if self.max_buf_stack >= self.buf_size {
+                return Err(TeXError::Overflow("buffer size", self.buf_size));
             }
         }
 
@@ -172,11 +212,59 @@ impl Global {
         &mut self.cur_input.loc_field
     }
 
+    // Section 37
+    // The first line is supplied by the caller (the command line).
+    // If it is blank, it is asked on the terminal with the prompt `**`.
+    // Returns false if there is no first line.
+    pub fn init_terminal(&mut self, line: &str) -> bool {
+        let line = line.trim_end().as_bytes();
+        let blank = line.iter().all(|&c| c == b' ');
+        if !blank {
+            // We expect the command line is only ASCII.
+            if self.first as usize + line.len() >= self.buf_size as usize {
+                self.term_out.write_str("! Unable to read an entire line---buf_size=");
+                self.term_out.write_str(&self.buf_size.to_string());
+                self.term_out.write_cr();
+                return false;
+            }
+            self.last = self.first + line.len() as Integer;
+            self.buffer[(self.first as usize)..(self.last as usize)].copy_from_slice(line);
+        }
+
+        loop {
+            if blank {
+                self.term_out.write_str("**");
+                update_terminal!(self);
+                if !matches!(self.input_ln(AlphaFileInSelector::TermIn), Ok(true)) {
+                    self.term_out.write_cr();
+                    self.term_out.write_str("! End of file on the terminal... why?");
+                    self.term_out.write_cr();
+                    return false;
+                }
+            }
+            *self.loc_mut() = self.first;
+            while self.loc() < self.last && self.buffer[self.loc() as usize] == b' ' {
+                *self.loc_mut() += 1;
+            }
+            if self.loc() < self.last {
+                break;
+            }
+            self.term_out.write_str("Please type the name of your input file.");
+            self.term_out.write_cr();
+        }
+
+        // Section 331
+        *self.limit_mut() = self.last;
+        self.first = self.last + 1;
+        true
+    }
+
     // Section 534
     pub(crate) fn open_log_file(&mut self) -> TeXResult<()> {
         let old_setting = self.selector;
         if self.job_name == 0 {
-            return Err(TeXError::IO("no job file name"));
+            let texput = self.put_string(b"texput")?;
+            self.job_name = self.get_job_name(texput)?;
         }
         self.pack_job_name(EXT_LOG);
         self.a_open_out(AlphaFileOutSelector::LogFile)?;
@@ -187,10 +275,10 @@ impl Global {
         self.input_stack[self.input_ptr] = self.cur_input;
         self.print_nl("**");
         let mut l = self.input_stack[0].limit_field;
-        if self.buffer[l as usize] == (end_line_char() as u8) {
+        if self.buffer[l as usize] == (self.end_line_char() as u8) {
             l -= 1;
         }
-        for k in 0..=l {
+        for k in 1..=l {
             self.print_strnumber(self.buffer[k as usize] as usize);
         }
         self.print_ln();
@@ -202,14 +290,14 @@ impl Global {
     // No prompt to ask another log file name.
 
     // Section 536
-    fn sec536_print_banner_line(&mut self) {
+    pub(crate) fn sec536_print_banner_line(&mut self) {
         self.log_file.write_str(BANNER);
         self.print_strnumber(self.format_ident);
         self.print("  ");
-        self.print_int(day());
+        self.print_int(self.day());
         self.print_char(b' ');
         self.print(
-                match month() {
+                match self.month() {
                     1 => "JAN ",
                     2 => "FEB ",
                     3 => "MAR ",
@@ -224,10 +312,10 @@ impl Global {
                     _ => "DEC "
             }
         );
-        self.print_int(year());
+        self.print_int(self.year());
         self.print_char(b' ');
-        self.print_two(time() / 60);
+        self.print_two(self.time() / 60);
         self.print_char(b':');
-        self.print_two(time() % 60);
+        self.print_two(self.time() % 60);
     }
 }
@@ -1,10 +1,5 @@
 use crate::constants::*;
-use crate::datastructures::{
-    escape_char, new_line_char, new_line_char_mut, 
-};
-use crate::strings::{
-    str_pool, XCHR, append_char, pool_ptr, str_ptr, str_start
-};
+use crate::strings::XCHR;
 use crate::{
     Global, Integer, Scaled, StrNum, odd
 };
@@ -22,14 +17,14 @@ impl Global {
     pub(crate) fn print_ln(&mut self) {
         match self.selector {
             TERM_AND_LOG => {
-                println!();
+                self.term_out.write_cr();
                 self.term_offset = 0;
                 self.log_file.write_cr();
                 self.file_offset = 0;
             },
 
             TERM_ONLY => {
-                println!();
+                self.term_out.write_cr();
                 self.term_offset = 0;
             },
 
@@ -44,21 +39,24 @@ impl Global {
 
             _ => self.write_file[self.selector as usize].write_cr(),
         }
+        if self.recording_err_message && self.selector >= NO_PRINT && self.selector <= TERM_AND_LOG {
+            self.err_message.push(b'\n');
+        }
     }
 
     // Section 58
     pub(crate) fn print_char(&mut self, s: u8) {
         // Section 244 (first condition)
-        if s as Integer == new_line_char() && self.selector < PSEUDO {
+        if s as Integer == self.new_line_char() && self.selector < PSEUDO {
             self.print_ln();
             return;
         }
         match self.selector {
             TERM_AND_LOG => {
-                print!("{}", XCHR[s as usize]);
+                self.term_out.write(&[XCHR[s as usize] as u8]);
                 self.term_offset += 1;
                 if self.term_offset == MAX_PRINT_LINE {
-                    println!();
+                    self.term_out.write_cr();
                     self.term_offset = 0;
                 }
                 self.log_file.write(&[XCHR[s as usize] as u8]);
@@ -79,10 +77,10 @@ impl Global {
             },
 
             TERM_ONLY => {
-                print!("{}", XCHR[s as usize]);
+                self.term_out.write(&[XCHR[s as usize] as u8]);
                 self.term_offset += 1;
                 if self.term_offset == MAX_PRINT_LINE {
-                    println!();
+                    self.term_out.write_cr();
                     self.term_offset = 0;
                 }
             },
@@ -96,13 +94,16 @@ impl Global {
             },
 
             NEW_STRING => {
-                if pool_ptr() < POOL_SIZE as usize {
-                    append_char(s);
+                if self.str_room(1).is_ok() {
+                    self.append_char(s);
                 }
             }
             
             _ => self.write_file[self.selector as usize].write(&[XCHR[s as usize] as u8]),
         }
+        if self.recording_err_message && self.selector >= NO_PRINT && self.selector <= TERM_AND_LOG {
+            self.err_message.push(XCHR[s as usize] as u8);
+        }
         self.tally += 1;
     }
 
@@ -115,7 +116,7 @@ impl Global {
 
     // Section 59
     pub(crate) fn print_strnumber(&mut self, s: StrNum) {
-        if s >= str_ptr() {
+        if s >= self.str_ptr() {
             self.print("???");
         }
         else if s < 256 {
@@ -124,36 +125,36 @@ impl Global {
                 return;
             }
             // Section 244 (first condition below)
-            if s as Integer == new_line_char() && self.selector < PSEUDO {
+            if s as Integer == self.new_line_char() && self.selector < PSEUDO {
                 return self.print_ln();
             }
-            let nl = new_line_char();
-            *new_line_char_mut() = -1;
-            let mut j = str_start(s);
-            while j < str_start(s + 1) {
-                self.print_char(str_pool(j));
+            let nl = self.new_line_char();
+            *self.new_line_char_mut() = -1;
+            let mut j = self.str_start(s);
+            while j < self.str_start(s + 1) {
+                self.print_char(self.str_pool(j));
                 j += 1;
             }
-            *new_line_char_mut() = nl;
+            *self.new_line_char_mut() = nl;
             return;
         }
 
-        let mut j = str_start(s);
-        while j < str_start(s + 1) {
-            self.print_char(str_pool(j));
+        let mut j = self.str_start(s);
+        while j < self.str_start(s + 1) {
+            self.print_char(self.str_pool(j));
             j += 1;
         }
     }
 
     // Section 60
     pub fn slow_print(&mut self, s: StrNum) {
-        if s >= str_ptr() || s < 256 {
+        if s >= self.str_ptr() || s < 256 {
             self.print_strnumber(s);
         }
         else {
-            let mut j = str_start(s);
-            while j < str_start(s + 1) {
-                self.print_strnumber(str_pool(j) as usize);
+            let mut j = self.str_start(s);
+            while j < self.str_start(s + 1) {
+                self.print_strnumber(self.str_pool(j) as usize);
                 j += 1;
             }
         }
@@ -180,7 +181,7 @@ impl Global {
 
     // Section 63
     pub(crate) fn print_esc_strnumber(&mut self, s: StrNum) {
-        let c = escape_char();
+        let c = self.escape_char();
         if (0..256).contains(&c) {
             self.print_strnumber(c as usize);
         }
@@ -189,7 +190,7 @@ impl Global {
 
     // Another one that does not use pool but &str
     pub(crate) fn print_esc(&mut self, s: &str) {
-        let c = escape_char();
+        let c = self.escape_char();
         if (0..256).contains(&c) {
             self.print_strnumber(c as usize);
         }
@@ -296,9 +297,9 @@ impl Global {
 
     // Section 70
     pub(crate) fn print_current_string(&mut self) {
-        let mut j = str_start(str_ptr());
-        while j < pool_ptr() {
-            self.print_char(str_pool(j));
+        let mut j = self.str_start(self.str_ptr());
+        while j < self.pool_ptr() {
+            self.print_char(self.str_pool(j));
             j += 1;
         }
     }
@@ -1,59 +1,100 @@
 use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
+use crate::io::deflate::{deflate, inflate, is_zlib_stream};
 use crate::{
     Global, HalfWord, Integer
 };
 
-use std::fs::File;
-use std::io::{Read, Write};
+use std::borrow::Cow;
+use std::io::{Cursor, Read, Write};
 
+#[derive(Clone, Copy)]
 pub(crate) enum ByteFileInSelector {
     TfmFile,
     FmtFile,
 }
 
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum ByteFileOutSelector {
     DviFile,
+    PdfFile,
+    SvgFile,
     FmtFile
 }
 
 // Section 25
 pub struct ByteFileIn {
-    file: Option<File>,
-    bytes: Option<&'static [u8]>
+    file: Option<Box<dyn Read + Send>>
 }
 
 pub(crate) struct ByteFileOut {
-    file: Option<File>
+    file: Option<Box<dyn Write + Send>>,
+    // Of the bytes written since the file was opened.
+    checksum: Checksum,
+    // The bytes of a compressed file, deflated when it is closed.
+    deflated: Option<Vec<u8>>
+}
+
+// Adler-32, which ends the format files.
+#[derive(Clone, Copy)]
+pub(crate) struct Checksum {
+    a: u32,
+    b: u32
+}
+
+impl Checksum {
+    pub(crate) const INIT: Self = Self { a: 1, b: 0 };
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.a = (self.a + byte as u32) % 65521;
+            self.b = (self.b + self.a) % 65521;
+        }
+    }
+
+    pub(crate) fn value(&self) -> Integer {
+        ((self.b << 16) | self.a) as Integer
+    }
 }
 
 impl ByteFileIn {
     pub(crate) fn new() -> Self {
         Self {
-            file: None,
-            bytes: None
+            file: None
         }
     }
 
     pub fn close(&mut self) {
         self.file = None;
-        self.bytes = None;
     }
 
-    pub fn set_preloaded(&mut self, bytes: &'static [u8]) {
-        self.bytes = Some(bytes);
+    // The bytes are either embedded in the binary,
+    // or owned when supplied at runtime.
+    pub fn set_preloaded(&mut self, bytes: impl Into<Cow<'static, [u8]>>) {
+        self.file = Some(Box::new(Cursor::new(bytes.into())));
+    }
+
+    // The rest of the file, to check a format before reading it.
+    // A compressed file is recognized by its zlib header and inflated.
+    pub(crate) fn read_all(&mut self) -> Option<Vec<u8>> {
+        let mut bytes = vec![];
+        let result = self.file.as_mut()?.read_to_end(&mut bytes);
+        self.close();
+        result.ok()?;
+        match is_zlib_stream(&bytes) {
+            true => inflate(&bytes),
+            false => Some(bytes)
+        }
     }
 
     pub(crate) fn fbyte(&mut self) -> Option<u8> {
         let mut buf = [0; 1];
-        let result = if self.file.is_some() {
-            self.file.as_mut().unwrap().read(&mut buf)
-        }
-        else {
-            self.bytes.as_mut().unwrap().read(&mut buf)
+        let result = match self.file.as_mut() {
+            Some(file) => file.read_exact(&mut buf),
+            None => return None
         };
         match result {
-            Ok(1) => Some(buf[0]),
+            Ok(()) => Some(buf[0]),
             _ => {
                 // EOF, or end of iterator or error.
                 // If the caller really expected some bytes,
@@ -66,14 +107,12 @@ impl ByteFileIn {
 
     pub(crate) fn read_wd(&mut self) -> Option<MemoryWord> {
         let mut buf = [0; 8];
-        let result = if self.file.is_some() {
-            self.file.as_mut().unwrap().read(&mut buf)
-        }
-        else {
-            self.bytes.as_mut().unwrap().read(&mut buf)
+        let result = match self.file.as_mut() {
+            Some(file) => file.read_exact(&mut buf),
+            None => return None
         };
         match result {
-            Ok(8) => {
+            Ok(()) => {
                 let b = u64::from_le_bytes(buf);
                 Some(MemoryWord { word: b })
             },
@@ -89,14 +128,12 @@ impl ByteFileIn {
 
     pub(crate) fn read_int(&mut self) -> Option<Integer> {
         let mut buf = [0; 4];
-        let result = if self.file.is_some() {
-            self.file.as_mut().unwrap().read(&mut buf)
-        }
-        else {
-            self.bytes.as_mut().unwrap().read(&mut buf)
+        let result = match self.file.as_mut() {
+            Some(file) => file.read_exact(&mut buf),
+            None => return None
         };
         match result {
-            Ok(4) => {
+            Ok(()) => {
                 Some(Integer::from_le_bytes(buf))
             },
             _ => {
@@ -112,14 +149,12 @@ impl ByteFileIn {
     // Section 564
     pub(crate) fn read_sixteen(&mut self) -> Option<HalfWord> {
         let mut buf = [0; 2];
-        let result = if self.file.is_some() {
-            self.file.as_mut().unwrap().read(&mut buf)
-        }
-        else {
-            self.bytes.as_mut().unwrap().read(&mut buf)
+        let result = match self.file.as_mut() {
+            Some(file) => file.read_exact(&mut buf),
+            None => return None
         };
         match result {
-            Ok(2) => {
+            Ok(()) => {
                 if buf[0] > 127 {
                     None
                 }
@@ -139,14 +174,12 @@ impl ByteFileIn {
     
     pub(crate) fn read_four_quarters(&mut self) -> Option<(u8, u8, u8, u8)> {
         let mut buf = [0; 4];
-        let result = if self.file.is_some() {
-            self.file.as_mut().unwrap().read(&mut buf)
-        }
-        else {
-            self.bytes.as_mut().unwrap().read(&mut buf)
+        let result = match self.file.as_mut() {
+            Some(file) => file.read_exact(&mut buf),
+            None => return None
         };
         match result {
-            Ok(4) => {
+            Ok(()) => {
                 Some((buf[0], buf[1], buf[2], buf[3]))
             },
             _ => {
@@ -163,62 +196,74 @@ impl ByteFileIn {
 impl ByteFileOut {
     pub(crate) fn new() -> Self {
         Self {
-            file: None
+            file: None,
+            checksum: Checksum::INIT,
+            deflated: None
         }
     }
 
+    pub(crate) fn checksum(&self) -> Integer {
+        self.checksum.value()
+    }
+
     pub(crate) fn close(&mut self) {
+        if let Some(bytes) = self.deflated.take()
+            && self.file.as_mut().unwrap().write_all(&deflate(&bytes)).is_err() {
+            panic!();
+        }
         self.file = None;
     }
 
     pub(crate) fn write(&mut self, b: &[u8]) {
-        if self.file.as_mut().unwrap().write_all(b).is_err() {
-            panic!();
+        match self.deflated.as_mut() {
+            Some(bytes) => bytes.extend_from_slice(b),
+            None => {
+                if self.file.as_mut().unwrap().write_all(b).is_err() {
+                    panic!();
+                }
+            }
         }
+        self.checksum.update(b);
     }
 
     pub(crate) fn write_wd(&mut self, w: MemoryWord) {
-        if self.file.as_mut().unwrap().write_all(&w.word().to_le_bytes()).is_err() {
-            panic!()
-        }
+        self.write(&w.word().to_le_bytes());
     }
 
     pub(crate) fn write_int(&mut self, x: Integer) {
-        if self.file.as_mut().unwrap().write_all(&x.to_le_bytes()).is_err() {
-            panic!();
-        }
+        self.write(&x.to_le_bytes());
     }
 }
 
 impl Global {
     pub(crate) fn b_open_in(&mut self, selection: ByteFileInSelector) -> bool {
-        match File::open(&self.name_of_file) {
-            Ok(file) => {
-                let byte_file = match selection {
-                    ByteFileInSelector::TfmFile => &mut self.tfm_file,
-                    ByteFileInSelector::FmtFile => &mut self.fmt_file,
-                };
-                byte_file.file = Some(file);
-                if byte_file.bytes.is_some() {
-                    byte_file.bytes = None;
-                }
-                true
-            }
-            Err(_) => false,
-        }
+        let file = match self.file_system.open_bytes(&self.name_of_file) {
+            Ok(file) => file,
+            Err(_) => return false
+        };
+        let byte_file = match selection {
+            ByteFileInSelector::TfmFile => &mut self.tfm_file,
+            ByteFileInSelector::FmtFile => &mut self.fmt_file,
+        };
+        byte_file.file = Some(file);
+        true
     }
 
     pub(crate) fn b_open_out(&mut self, selection: ByteFileOutSelector) -> TeXResult<()> {
-        match File::create(&self.name_of_file) {
-            Ok(file) => {
-                let byte_file = match selection {
-                    ByteFileOutSelector::DviFile => &mut self.dvi_file,
-                    ByteFileOutSelector::FmtFile => &mut self.fmt_file_out,
-                };
-                byte_file.file = Some(file);
-                Ok(())
-            },
-            Err(_) => Err(TeXError::CantWriteFile),
-        }
+        self.pack_output_directory();
+        let file = match self.file_system.create(&self.name_of_file) {
+            Ok(file) => file,
+            Err(_) => return Err(TeXError::CantWriteFile)
+        };
+        let (byte_file, compressed) = match selection {
+            ByteFileOutSelector::DviFile => (&mut self.dvi_file, false),
+            ByteFileOutSelector::PdfFile => (&mut self.pdf.file, false),
+            ByteFileOutSelector::SvgFile => (&mut self.svg.file, false),
+            ByteFileOutSelector::FmtFile => (&mut self.fmt_file_out, true),
+        };
+        byte_file.file = Some(file);
+        byte_file.checksum = Checksum::INIT;
+        byte_file.deflated = compressed.then(Vec::new);
+        Ok(())
     }
 }
@@ -0,0 +1,640 @@
+use crate::io::Checksum;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// Compression of format files
+// A zlib stream (RFC 1950) of deflate blocks (RFC 1951): the strings found
+// by LZ77 are coded with dynamic Huffman codes, one block every
+// `BLOCK_SYMBOLS` symbols. The Adler-32 of the data ends the stream.
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 128;
+const HASH_BITS: usize = 15;
+const BLOCK_SYMBOLS: usize = 1 << 16;
+const END_OF_BLOCK: usize = 256;
+const MAX_BITS: usize = 15;
+const MAX_CODE_LENGTH_BITS: usize = 7;
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258
+];
+const LENGTH_EXTRA: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0
+];
+const DIST_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577
+];
+const DIST_EXTRA: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13
+];
+// The order in which the lengths of the code length code are sent.
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+// A zlib header: deflate method, no preset dictionary, valid check bits.
+pub(crate) fn is_zlib_stream(bytes: &[u8]) -> bool {
+    bytes.len() >= 2
+        && bytes[0] & 0x0f == 8
+        && bytes[1] & 0x20 == 0
+        && ((bytes[0] as u32) << 8 | bytes[1] as u32).is_multiple_of(31)
+}
+
+fn adler32(data: &[u8]) -> [u8; 4] {
+    let mut checksum = Checksum::INIT;
+    checksum.update(data);
+    checksum.value().to_be_bytes()
+}
+
+#[derive(Clone, Copy)]
+enum Symbol {
+    Literal(u8),
+    // Length and distance of a copy.
+    Copy(usize, usize)
+}
+
+// Greedy matching: the positions with the same hash of their next three
+// bytes are chained, the most recent first.
+fn lz77(data: &[u8]) -> Vec<Symbol> {
+    let hash = |i: usize| {
+        ((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize) & ((1 << HASH_BITS) - 1)
+    };
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; data.len()];
+    let mut symbols = vec![];
+    let mut i = 0;
+    while i < data.len() {
+        let max = (data.len() - i).min(MAX_MATCH);
+        let (mut length, mut distance) = (0, 0);
+        if max >= MIN_MATCH {
+            let mut j = head[hash(i)];
+            let mut chain = 0;
+            while j != usize::MAX && i - j <= WINDOW_SIZE && chain < MAX_CHAIN && length < max {
+                if data[j + length] == data[i + length] {
+                    let l = (0..max).find(|&l| data[j + l] != data[i + l]).unwrap_or(max);
+                    if l > length {
+                        length = l;
+                        distance = i - j;
+                    }
+                }
+                j = prev[j];
+                chain += 1;
+            }
+        }
+        let step = if length >= MIN_MATCH {
+            symbols.push(Symbol::Copy(length, distance));
+            length
+        }
+        else {
+            symbols.push(Symbol::Literal(data[i]));
+            1
+        };
+        // The last two positions do not start three bytes.
+        let end = (i + step).min((data.len() + 1).saturating_sub(MIN_MATCH));
+        for (k, link) in prev.iter_mut().enumerate().take(end).skip(i) {
+            let h = hash(k);
+            *link = head[h];
+            head[h] = k;
+        }
+        i += step;
+    }
+    symbols
+}
+
+// The code of a length, its extra bits and their value.
+fn length_code(length: usize) -> (usize, u32, u32) {
+    let k = (0..29).rev().find(|&k| LENGTH_BASE[k] <= length).unwrap();
+    (257 + k, LENGTH_EXTRA[k], (length - LENGTH_BASE[k]) as u32)
+}
+
+fn dist_code(distance: usize) -> (usize, u32, u32) {
+    let k = (0..30).rev().find(|&k| DIST_BASE[k] <= distance).unwrap();
+    (k, DIST_EXTRA[k], (distance - DIST_BASE[k]) as u32)
+}
+
+// Huffman code lengths, limited to `limit` bits by halving the
+// frequencies until the tree is shallow enough.
+fn code_lengths(freqs: &[u32], limit: usize) -> Vec<u8> {
+    let mut freqs = freqs.to_vec();
+    loop {
+        let lengths = huffman_lengths(&freqs);
+        if lengths.iter().all(|&l| l as usize <= limit) {
+            return lengths;
+        }
+        for f in freqs.iter_mut().filter(|f| **f > 0) {
+            *f = f.div_ceil(2);
+        }
+    }
+}
+
+fn huffman_lengths(freqs: &[u32]) -> Vec<u8> {
+    let n = freqs.len();
+    let mut lengths = vec![0; n];
+    let used: Vec<usize> = (0..n).filter(|&s| freqs[s] > 0).collect();
+    if used.len() == 1 {
+        lengths[used[0]] = 1;
+    }
+    if used.len() <= 1 {
+        return lengths;
+    }
+    // Nodes `0..n` are the leaves, the internal nodes come after them so
+    // that a parent always has a larger index than its children.
+    let mut parent = vec![0; 2*n];
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = used.iter()
+        .map(|&s| Reverse((freqs[s] as u64, s)))
+        .collect();
+    let mut next = n;
+    while let (Some(Reverse((w1, a))), Some(Reverse((w2, b)))) = (heap.pop(), heap.pop()) {
+        parent[a] = next;
+        parent[b] = next;
+        heap.push(Reverse((w1 + w2, next)));
+        next += 1;
+    }
+    let root = next - 1;
+    let mut depth = vec![0usize; next];
+    for k in (n..root).rev() {
+        depth[k] = depth[parent[k]] + 1;
+    }
+    for &s in &used {
+        lengths[s] = (depth[parent[s]] + 1).min(u8::MAX as usize) as u8;
+    }
+    lengths
+}
+
+// Canonical codes from the lengths, as in RFC 1951 Section 3.2.2.
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let mut count = [0u16; MAX_BITS + 1];
+    for &l in lengths.iter().filter(|&&l| l > 0) {
+        count[l as usize] += 1;
+    }
+    let mut next_code = [0u16; MAX_BITS + 1];
+    let mut code = 0;
+    for bits in 1..=MAX_BITS {
+        code = (code + count[bits - 1]) << 1;
+        next_code[bits] = code;
+    }
+    lengths.iter()
+        .map(|&l| {
+            if l == 0 {
+                return 0;
+            }
+            let code = next_code[l as usize];
+            next_code[l as usize] += 1;
+            code
+        })
+        .collect()
+}
+
+// A code used by the block must have a partner, as a single code of one
+// bit would be incomplete.
+fn at_least_two(freqs: &mut [u32]) {
+    let mut k = 0;
+    while freqs.iter().filter(|&&f| f > 0).count() < 2 {
+        if freqs[k] == 0 {
+            freqs[k] = 1;
+        }
+        k += 1;
+    }
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u32
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, n: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += n;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Huffman codes are packed starting with their most significant bit.
+    fn write_code(&mut self, code: u16, length: u8) {
+        self.write_bits((code.reverse_bits() >> (16 - length)) as u32, length as u32);
+    }
+
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.write_bits(0, 8 - self.count);
+        }
+    }
+}
+
+// Run-length coding of the code lengths with the codes 16, 17 and 18,
+// each with its extra bits.
+fn run_lengths(lengths: &[u8]) -> Vec<(usize, u32, u32)> {
+    let mut runs = vec![];
+    let mut i = 0;
+    while i < lengths.len() {
+        let l = lengths[i];
+        let run = lengths[i..].iter().take_while(|&&m| m == l).count();
+        if l == 0 && run >= 11 {
+            let r = run.min(138);
+            runs.push((18, 7, (r - 11) as u32));
+            i += r;
+        }
+        else if l == 0 && run >= 3 {
+            runs.push((17, 3, (run - 3) as u32));
+            i += run;
+        }
+        else if l > 0 && run >= 4 {
+            runs.push((l as usize, 0, 0));
+            let r = (run - 1).min(6);
+            runs.push((16, 2, (r - 3) as u32));
+            i += 1 + r;
+        }
+        else {
+            runs.push((l as usize, 0, 0));
+            i += 1;
+        }
+    }
+    runs
+}
+
+fn write_block(writer: &mut BitWriter, symbols: &[Symbol], last: bool) {
+    let mut lit_freqs = [0u32; 286];
+    let mut dist_freqs = [0u32; 30];
+    for &symbol in symbols {
+        match symbol {
+            Symbol::Literal(c) => lit_freqs[c as usize] += 1,
+            Symbol::Copy(length, distance) => {
+                lit_freqs[length_code(length).0] += 1;
+                dist_freqs[dist_code(distance).0] += 1;
+            }
+        }
+    }
+    lit_freqs[END_OF_BLOCK] += 1;
+    at_least_two(&mut lit_freqs);
+    at_least_two(&mut dist_freqs);
+    let lit_lengths = code_lengths(&lit_freqs, MAX_BITS);
+    let dist_lengths = code_lengths(&dist_freqs, MAX_BITS);
+    let hlit = 1 + lit_lengths.iter().rposition(|&l| l > 0).unwrap().max(END_OF_BLOCK);
+    let hdist = 1 + dist_lengths.iter().rposition(|&l| l > 0).unwrap();
+
+    let all_lengths = [&lit_lengths[..hlit], &dist_lengths[..hdist]].concat();
+    let runs = run_lengths(&all_lengths);
+    let mut cl_freqs = [0u32; 19];
+    for &(code, _, _) in &runs {
+        cl_freqs[code] += 1;
+    }
+    at_least_two(&mut cl_freqs);
+    let cl_lengths = code_lengths(&cl_freqs, MAX_CODE_LENGTH_BITS);
+    let cl_codes = canonical_codes(&cl_lengths);
+    let hclen = 1 + CODE_LENGTH_ORDER.iter().rposition(|&k| cl_lengths[k] > 0).unwrap().max(3);
+
+    writer.write_bits(last as u32, 1);
+    writer.write_bits(2, 2);
+    writer.write_bits((hlit - 257) as u32, 5);
+    writer.write_bits((hdist - 1) as u32, 5);
+    writer.write_bits((hclen - 4) as u32, 4);
+    for &k in &CODE_LENGTH_ORDER[..hclen] {
+        writer.write_bits(cl_lengths[k] as u32, 3);
+    }
+    for &(code, extra, value) in &runs {
+        writer.write_code(cl_codes[code], cl_lengths[code]);
+        writer.write_bits(value, extra);
+    }
+
+    let lit_codes = canonical_codes(&lit_lengths);
+    let dist_codes = canonical_codes(&dist_lengths);
+    for &symbol in symbols {
+        match symbol {
+            Symbol::Literal(c) => writer.write_code(lit_codes[c as usize], lit_lengths[c as usize]),
+            Symbol::Copy(length, distance) => {
+                let (code, extra, value) = length_code(length);
+                writer.write_code(lit_codes[code], lit_lengths[code]);
+                writer.write_bits(value, extra);
+                let (code, extra, value) = dist_code(distance);
+                writer.write_code(dist_codes[code], dist_lengths[code]);
+                writer.write_bits(value, extra);
+            }
+        }
+    }
+    writer.write_code(lit_codes[END_OF_BLOCK], lit_lengths[END_OF_BLOCK]);
+}
+
+pub(crate) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        bytes: vec![0x78, 0x9c],
+        buffer: 0,
+        count: 0
+    };
+    let symbols = lz77(data);
+    let mut blocks = symbols.chunks(BLOCK_SYMBOLS).peekable();
+    if blocks.peek().is_none() {
+        write_block(&mut writer, &[], true);
+    }
+    while let Some(block) = blocks.next() {
+        write_block(&mut writer, block, blocks.peek().is_none());
+    }
+    writer.flush();
+    writer.bytes.extend(adler32(data));
+    writer.bytes
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u32
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, n: u32) -> Option<u32> {
+        while self.count < n {
+            let byte = *self.bytes.get(self.pos)?;
+            self.pos += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u64 << n) - 1) as u32;
+        self.buffer >>= n;
+        self.count -= n;
+        Some(value)
+    }
+
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+// A canonical code: the number of codes of each length and the symbols
+// ordered by code.
+struct Huffman {
+    count: [i32; MAX_BITS + 1],
+    symbols: Vec<usize>
+}
+
+impl Huffman {
+    // Incomplete codes are accepted, an over-subscribed one is not.
+    fn new(lengths: &[u8]) -> Option<Self> {
+        let mut count = [0; MAX_BITS + 1];
+        for &l in lengths {
+            count[l as usize] += 1;
+        }
+        count[0] = 0;
+        let mut left = 1;
+        for &c in &count[1..] {
+            left = 2*left - c;
+            if left < 0 {
+                return None;
+            }
+        }
+        let mut symbols: Vec<usize> = (0..lengths.len()).filter(|&s| lengths[s] > 0).collect();
+        symbols.sort_by_key(|&s| lengths[s]);
+        Some(Self { count, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Option<usize> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for &count in &self.count[1..] {
+            code |= reader.bits(1)? as i32;
+            if code - count < first {
+                return Some(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Option<(Huffman, Huffman)> {
+    let hlit = reader.bits(5)? as usize + 257;
+    let hdist = reader.bits(5)? as usize + 1;
+    let hclen = reader.bits(4)? as usize + 4;
+    if hlit > 286 || hdist > 30 {
+        return None;
+    }
+    let mut cl_lengths = [0; 19];
+    for &k in &CODE_LENGTH_ORDER[..hclen] {
+        cl_lengths[k] = reader.bits(3)? as u8;
+    }
+    let cl_code = Huffman::new(&cl_lengths)?;
+    let mut lengths = vec![];
+    while lengths.len() < hlit + hdist {
+        let (l, repeat) = match cl_code.decode(reader)? {
+            code @ 0..=15 => (code as u8, 1),
+            16 => (*lengths.last()?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?)
+        };
+        lengths.extend(std::iter::repeat_n(l, repeat as usize));
+    }
+    if lengths.len() > hlit + hdist || lengths[END_OF_BLOCK] == 0 {
+        return None;
+    }
+    Some((Huffman::new(&lengths[..hlit])?, Huffman::new(&lengths[hlit..])?))
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    (Huffman::new(&lengths).unwrap(), Huffman::new(&[5; 30]).unwrap())
+}
+
+fn inflate_block(reader: &mut BitReader, out: &mut Vec<u8>, lit: &Huffman, dist: &Huffman) -> Option<()> {
+    loop {
+        let code = lit.decode(reader)?;
+        match code {
+            0..=255 => out.push(code as u8),
+            END_OF_BLOCK => return Some(()),
+            257..=285 => {
+                let k = code - 257;
+                let length = LENGTH_BASE[k] + reader.bits(LENGTH_EXTRA[k])? as usize;
+                let k = dist.decode(reader)?;
+                let distance = *DIST_BASE.get(k)? + reader.bits(*DIST_EXTRA.get(k)?)? as usize;
+                if distance > out.len() {
+                    return None;
+                }
+                let start = out.len() - distance;
+                for i in start..(start + length) {
+                    out.push(out[i]);
+                }
+            },
+            _ => return None
+        }
+    }
+}
+
+// The data of a zlib stream, `None` if it is damaged.
+pub(crate) fn inflate(stream: &[u8]) -> Option<Vec<u8>> {
+    if !is_zlib_stream(stream) {
+        return None;
+    }
+    let mut reader = BitReader {
+        bytes: &stream[2..],
+        pos: 0,
+        buffer: 0,
+        count: 0
+    };
+    let mut out = vec![];
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let length = reader.bits(16)?;
+                if reader.bits(16)? != !length & 0xffff {
+                    return None;
+                }
+                let block = reader.bytes.get(reader.pos..(reader.pos + length as usize))?;
+                out.extend_from_slice(block);
+                reader.pos += length as usize;
+            },
+            1 => {
+                let (lit, dist) = fixed_codes();
+                inflate_block(&mut reader, &mut out, &lit, &dist)?;
+            },
+            2 => {
+                let (lit, dist) = dynamic_codes(&mut reader)?;
+                inflate_block(&mut reader, &mut out, &lit, &dist)?;
+            },
+            _ => return None
+        }
+        if last {
+            break;
+        }
+    }
+    reader.align();
+    match reader.bytes.get(reader.pos..(reader.pos + 4)) {
+        Some(trailer) if *trailer == adler32(&out) => Some(out),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Made by zlib: `zlib.compress(data, level)` in Python.
+    // Level 0 gives a stored block, short data a block with fixed codes.
+    const STORED: [u8; 27] = [
+        0x78, 0x01, 0x01, 0x10, 0x00, 0xef, 0xff, 0x54, 0x65, 0x58, 0x3a, 0x20, 0x54, 0x68, 0x65, 0x20,
+        0x50, 0x72, 0x6f, 0x67, 0x72, 0x61, 0x6d, 0x2b, 0xde, 0x05, 0x85
+    ];
+    const FIXED: [u8; 16] = [
+        0x78, 0xda, 0x8b, 0x29, 0x4a, 0xcd, 0x49, 0xac, 0x88, 0xc1, 0x20, 0x01, 0x7a, 0xc8, 0x09, 0xe1
+    ];
+    // `\def\a<i>{<i*i>}` for i from 0 to 39, one per line.
+    const DYNAMIC: [u8; 191] = [
+        0x78, 0xda, 0x4d, 0x91, 0x4b, 0x6e, 0xc4, 0x30, 0x0c, 0x43, 0xf7, 0x73, 0x1a, 0xfd, 0x2c, 0xdb,
+        0x77, 0xc9, 0x66, 0x80, 0x99, 0x5e, 0x82, 0xe8, 0xdd, 0xab, 0x14, 0xb0, 0x98, 0x1d, 0x13, 0x45,
+        0xe4, 0x13, 0x73, 0x7d, 0xbe, 0x3f, 0xd7, 0x5b, 0x20, 0xbf, 0xaf, 0xeb, 0x5f, 0x2a, 0xf4, 0x48,
+        0x43, 0x1c, 0xe9, 0xd8, 0x47, 0x06, 0x34, 0x8f, 0x1e, 0xb0, 0x71, 0x74, 0xc2, 0xfb, 0xfd, 0x44,
+        0xf4, 0xf7, 0x0b, 0xd9, 0x36, 0x1b, 0xab, 0xdd, 0x55, 0xa0, 0xc2, 0xd8, 0xca, 0x35, 0xce, 0x0c,
+        0x1a, 0xbd, 0xa5, 0x5e, 0x91, 0xed, 0xa7, 0x05, 0xb0, 0x3b, 0x49, 0x0b, 0x81, 0x0c, 0x9a, 0x05,
+        0xc4, 0xd9, 0x84, 0x2d, 0xee, 0x2d, 0xb8, 0xd1, 0x73, 0x17, 0x2e, 0x2f, 0x15, 0x04, 0x59, 0x4c,
+        0x11, 0xc1, 0x59, 0xd5, 0xb0, 0x7a, 0xcf, 0x1c, 0xc3, 0xda, 0xd3, 0x02, 0x63, 0x76, 0x9e, 0x0d,
+        0x24, 0x59, 0x2c, 0x91, 0x8f, 0xd9, 0xc4, 0x7c, 0xec, 0x2d, 0xcc, 0x87, 0x67, 0xd5, 0xc2, 0x3c,
+        0x17, 0x6c, 0xb2, 0xb8, 0x62, 0x93, 0xd3, 0xab, 0x17, 0xe1, 0x11, 0x5e, 0xc5, 0x08, 0x2f, 0xf4,
+        0x6a, 0x46, 0x79, 0xbe, 0x8f, 0xaa, 0x94, 0x3c, 0x9e, 0xf5, 0xc8, 0xe2, 0x7c, 0x42, 0x9d, 0xad,
+        0xfa, 0xba, 0x1b, 0xa7, 0xf3, 0x86, 0x8e, 0xfb, 0x7f, 0xfc, 0x01, 0xfa, 0xb2, 0xa8, 0xc5
+    ];
+
+    // Bytes from a linear congruential generator, which LZ77 rarely matches.
+    fn noise(length: usize) -> Vec<u8> {
+        let mut state: u32 = 1;
+        (0..length)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8]) {
+        let stream = deflate(data);
+        assert!(is_zlib_stream(&stream));
+        assert_eq!(inflate(&stream).as_deref(), Some(data), "{} bytes", data.len());
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(b"");
+        round_trip(b"a");
+        round_trip(b"\\relax\\relax\\relax");
+        // Runs around `MAX_MATCH`, coded with one or two matches.
+        for length in [MAX_MATCH - 1, MAX_MATCH, MAX_MATCH + 1, MAX_MATCH + MIN_MATCH, 2 * MAX_MATCH + 1] {
+            round_trip(&vec![b'x'; length]);
+            let mut data = noise(10);
+            data.extend(std::iter::repeat_n(b'y', length));
+            data.extend(noise(10));
+            round_trip(&data);
+        }
+        // Matches as far back as the window allows.
+        let mut data = noise(WINDOW_SIZE);
+        data.extend_from_within(..1000);
+        round_trip(&data);
+    }
+
+    #[test]
+    fn round_trips_across_blocks() {
+        // Mostly literals, one symbol per byte.
+        for length in [BLOCK_SYMBOLS - 1, BLOCK_SYMBOLS, BLOCK_SYMBOLS + 1, 3 * BLOCK_SYMBOLS + 7] {
+            round_trip(&noise(length));
+        }
+        // Matches and literals, with matches crossing the boundaries.
+        let data: Vec<u8> = (0..40000u32).flat_map(|i| format!("\\count{}={}\n", i % 300, i / 7).into_bytes()).collect();
+        assert!(lz77(&data).len() > BLOCK_SYMBOLS);
+        round_trip(&data);
+    }
+
+    #[test]
+    fn streams_made_by_zlib() {
+        assert_eq!(STORED[2] >> 1 & 3, 0);
+        assert_eq!(inflate(&STORED).as_deref(), Some(&b"TeX: The Program"[..]));
+        assert_eq!(FIXED[2] >> 1 & 3, 1);
+        assert_eq!(inflate(&FIXED).as_deref(), Some(&b"\\relax\\relax\\relax\\relax"[..]));
+        assert_eq!(DYNAMIC[2] >> 1 & 3, 2);
+        let data: String = (0..40).map(|i| format!("\\def\\a{i}{{{}}}\n", i * i)).collect();
+        assert_eq!(inflate(&DYNAMIC).as_deref(), Some(data.as_bytes()));
+    }
+
+    #[test]
+    fn damaged_streams() {
+        let stream = deflate(&noise(5000));
+        for length in [0, 1, 2, 3, 100, stream.len() - 5, stream.len() - 1] {
+            assert_eq!(inflate(&stream[..length]), None, "truncated to {length} bytes");
+        }
+        assert_eq!(inflate(&STORED[..20]), None);
+        assert_eq!(inflate(&DYNAMIC[..DYNAMIC.len() / 2]), None);
+
+        // A wrong checksum.
+        let mut damaged = stream.clone();
+        *damaged.last_mut().unwrap() ^= 1;
+        assert_eq!(inflate(&damaged), None);
+        // A wrong header.
+        let mut damaged = stream.clone();
+        damaged[1] ^= 1;
+        assert_eq!(inflate(&damaged), None);
+        // A stored block whose length does not match its complement.
+        let mut damaged = STORED;
+        damaged[5] ^= 1;
+        assert_eq!(inflate(&damaged), None);
+        // The reserved block type 3.
+        let mut damaged = FIXED;
+        damaged[2] |= 0b110;
+        assert_eq!(inflate(&damaged), None);
+        // Flipped bits in the blocks, caught by the codes or the checksum.
+        for k in (2..stream.len() - 4).step_by(97) {
+            let mut damaged = stream.clone();
+            damaged[k] ^= 0x24;
+            assert_eq!(inflate(&damaged), None, "byte {k} flipped");
+        }
+    }
+}
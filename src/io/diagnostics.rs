@@ -0,0 +1,279 @@
+use crate::constants::FATAL_ERROR_STOP;
+use crate::{Global, Integer};
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+// Diagnostics side channel: errors, box warnings, missing characters
+// and shipped pages are written as JSON objects, one per line,
+// in addition to the usual terminal and log output.
+
+// A JSON object, built field by field.
+pub(crate) struct JsonObject {
+    text: String
+}
+
+impl JsonObject {
+    pub(crate) fn new(kind: &str, severity: &str) -> Self {
+        let object = Self {
+            text: String::from("{")
+        };
+        object.str("type", kind).str("severity", severity)
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.text.len() > 1 {
+            self.text.push(',');
+        }
+        push_json_string(&mut self.text, key);
+        self.text.push(':');
+    }
+
+    pub(crate) fn str(mut self, key: &str, value: &str) -> Self {
+        self.key(key);
+        push_json_string(&mut self.text, value);
+        self
+    }
+
+    pub(crate) fn int(mut self, key: &str, value: Integer) -> Self {
+        self.key(key);
+        _ = write!(self.text, "{value}");
+        self
+    }
+
+    pub(crate) fn strs(mut self, key: &str, values: &[String]) -> Self {
+        self.key(key);
+        self.text.push('[');
+        for (k, value) in values.iter().enumerate() {
+            if k > 0 {
+                self.text.push(',');
+            }
+            push_json_string(&mut self.text, value);
+        }
+        self.text.push(']');
+        self
+    }
+
+    pub(crate) fn ints(mut self, key: &str, values: &[Integer]) -> Self {
+        self.key(key);
+        self.text.push('[');
+        for (k, value) in values.iter().enumerate() {
+            if k > 0 {
+                self.text.push(',');
+            }
+            _ = write!(self.text, "{value}");
+        }
+        self.text.push(']');
+        self
+    }
+
+    fn null(mut self, key: &str) -> Self {
+        self.key(key);
+        self.text.push_str("null");
+        self
+    }
+}
+
+fn push_json_string(text: &mut String, s: &str) {
+    text.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            c if (c as u32) < 0x20 => _ = write!(text, "\\u{:04x}", c as u32),
+            c => text.push(c)
+        }
+    }
+    text.push('"');
+}
+
+impl Global {
+    // Opens the diagnostics file `name`, which is an output file.
+    pub fn open_diagnostics(&mut self, name: &str) -> bool {
+        self.name_of_file.clear();
+        self.name_of_file.push_str(name);
+        self.pack_output_directory();
+        match self.file_system.create(&self.name_of_file) {
+            Ok(file) => {
+                self.diagnostics = Some(file);
+                true
+            },
+            Err(_) => false
+        }
+    }
+
+    pub(crate) fn diagnostics_enabled(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    // The text printed from now on is recorded,
+    // until `end_recording` returns it.
+    pub(crate) fn begin_recording(&mut self) {
+        self.err_message.clear();
+        self.recording_err_message = true;
+    }
+
+    pub(crate) fn end_recording(&mut self) -> String {
+        self.recording_err_message = false;
+        let text = String::from_utf8_lossy(&self.err_message).into_owned();
+        self.err_message.clear();
+        text
+    }
+
+    // The object gets the file and line being read, then it is written.
+    pub(crate) fn write_diagnostic(&mut self, object: JsonObject) {
+        let mut object = match self.current_file_line() {
+            Some((file, line)) => object.str("file", &file).int("line", line),
+            None => object.null("file").null("line")
+        };
+        object.text.push_str("}\n");
+        if let Some(diagnostics) = self.diagnostics.as_mut() {
+            _ = diagnostics.write_all(object.text.as_bytes());
+        }
+    }
+
+    // Once the run is over.
+    pub(crate) fn close_diagnostics(&mut self) {
+        if let Some(mut diagnostics) = self.diagnostics.take() {
+            _ = diagnostics.flush();
+        }
+    }
+
+    // Section 82
+    // Called by `error` once the message and its context are printed.
+    pub(crate) fn write_error_diagnostic(&mut self, variant: &str, context: &str) {
+        let severity = if self.history == FATAL_ERROR_STOP {
+            "fatal"
+        }
+        else {
+            "error"
+        };
+        let message = String::from_utf8_lossy(&self.err_message).trim().to_string();
+        let help: Vec<String> = self.help_line.iter().map(|s| s.to_string()).collect();
+        let object = JsonObject::new("error", severity)
+            .str("variant", variant)
+            .str("message", &message)
+            .strs("help", &help)
+            .strs("context", &lines(context));
+        self.write_diagnostic(object);
+    }
+}
+
+impl Global {
+    // Warnings are recorded from their first line,
+    // and written when `end_warning` is called.
+    pub(crate) fn begin_warning(&mut self) {
+        if self.diagnostics_enabled() {
+            self.begin_recording();
+        }
+    }
+
+    pub(crate) fn end_warning(&mut self, kind: &str) {
+        if self.diagnostics_enabled() {
+            let message = self.end_recording();
+            let object = JsonObject::new(kind, "warning")
+                .str("message", message.trim());
+            self.write_diagnostic(object);
+        }
+    }
+
+    // Section 638
+    // The counts are the ones printed between brackets.
+    pub(crate) fn write_page_diagnostic(&mut self) {
+        if self.diagnostics_enabled() {
+            let mut j = 9;
+            while self.count(j) == 0 && j > 0 {
+                j -= 1;
+            }
+            let counts: Vec<Integer> = (0..=j).map(|k| self.count(k)).collect();
+            let object = JsonObject::new("page", "info")
+                .int("page", self.total_pages)
+                .ints("counts", &counts);
+            self.write_diagnostic(object);
+        }
+    }
+}
+
+// Text printed on several lines, without the empty lines
+// at the beginning and at the end.
+fn lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = text.lines()
+        .map(|line| line.trim_end().to_string())
+        .skip_while(|line| line.is_empty())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+
+    #[test]
+    fn strings_are_escaped() {
+        let object = JsonObject::new("test", "info")
+            .str("quote", "say \"hi\"")
+            .str("backslash", "\\relax")
+            .str("controls", "a\nb\rc\td\u{1}e\u{1f}")
+            .strs("list", &["\"".to_string(), "é".to_string()]);
+        assert_eq!(
+            object.text,
+            r#"{"type":"test","severity":"info","quote":"say \"hi\"","backslash":"\\relax","controls":"a\nb\rc\td\u0001e\u001f","list":["\"","é"]"#
+        );
+    }
+
+    fn diagnostics(source: &str) -> Vec<String> {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let options = CompileOptions {
+            format: &format,
+            job_name: "paper",
+            diagnostics: true,
+            ..CompileOptions::default()
+        };
+        let output = compile(source, &options);
+        output.diagnostics.expect("no diagnostics").lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn error_and_page() {
+        let lines = diagnostics("\\undefined\n\\count1=5 \\shipout\\hbox{}\n\\end\n");
+        assert_eq!(lines, [
+            r#"{"type":"error","severity":"error","variant":"UndefinedControlSequence","message":"Undefined control sequence.","help":["The control sequence at the end of the top line","of your error message was never \\def'ed. If you have","misspelled it (e.g., '\\hobx'), just continue and I'll","forget about whatever was undefined."],"context":["l.1 \\undefined"],"file":"paper.tex","line":1}"#,
+            r#"{"type":"page","severity":"info","page":1,"counts":[0,5],"file":"paper.tex","line":2}"#
+        ]);
+    }
+
+    #[test]
+    fn box_warnings() {
+        let lines = diagnostics("\\setbox0\\hbox to 1pt{\\vrule width 2pt}\n\\setbox0\\hbox to 10pt{\\hskip 0pt plus 1pt}\n\\end\n");
+        assert_eq!(lines, [
+            r#"{"type":"box","severity":"warning","message":"Overfull \\hbox (1.0pt too wide) detected at line 1","file":"paper.tex","line":1}"#,
+            r#"{"type":"box","severity":"warning","message":"Underfull \\hbox (badness 10000) detected at line 2","file":"paper.tex","line":2}"#
+        ]);
+    }
+
+    #[test]
+    fn missing_character() {
+        let lines = diagnostics("\\tracinglostchars=1 \\setbox0\\hbox{A}\n\\end\n");
+        assert_eq!(lines, [
+            r#"{"type":"missing_character","severity":"warning","message":"Missing character: There is no A in font nullfont!","file":"paper.tex","line":1}"#
+        ]);
+    }
+
+    // In batch mode, the end of the file without `\end` stops TeX.
+    #[test]
+    fn fatal_error() {
+        let lines = diagnostics("\\relax\n");
+        assert_eq!(lines, [
+            r#"{"type":"error","severity":"fatal","variant":"Fatal","message":"Emergency stop.","help":["*** (job aborted, no legal \\end found)"],"context":["<*> paper"],"file":null,"line":null}"#
+        ]);
+    }
+}
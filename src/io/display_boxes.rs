@@ -1,21 +1,4 @@
 use crate::constants::*;
-use crate::datastructures::{
-    adjust_ptr, character, depth, float_cost, font, font_id_text, glue_order,
-    glue_ptr, glue_set, glue_shrink, glue_sign, glue_stretch, height, ins_ptr,
-    leader_ptr, lig_ptr, link, list_ptr, mark_ptr, penalty, post_break,
-    pre_break, replace_count, shift_amount, show_box_breadth, show_box_depth,
-    shrink, shrink_order, span_count, split_top_ptr, stretch, stretch_order,
-    subtype, r#type, width
-};
-use crate::extensions::{
-    open_area, open_ext, open_name, what_lang, what_lhm, what_rhm, write_tokens
-};
-use crate::math::{
-    display_mlist, script_mlist, script_script_mlist, text_mlist
-};
-use crate::strings::{
-    append_char, cur_length, flush_char, pool_ptr
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Real, Scaled, StrNum,
     is_running, lig_char, odd
@@ -28,29 +11,29 @@ use std::cmp::Ordering::{Equal, Greater, Less};
 impl Global {
     // Section 174
     pub(crate) fn short_display(&mut self, mut p: Integer) {
-        while p > MEM_MIN {
+        while p > self.mem_min {
             if self.is_char_node(p) {
                 if p <= self.mem_end {
-                    if font(p) != self.font_in_short_display {
-                        if font(p) < FONT_BASE as QuarterWord
-                            || font(p) > FONT_MAX as QuarterWord
+                    if self.font(p) != self.font_in_short_display {
+                        if self.font(p) < FONT_BASE as QuarterWord
+                            || self.font(p) > self.font_max as QuarterWord
                         {
                             self.print_char(b'*');
                         }
                         else {
                             // Section 267
-                            self.print_esc_strnumber(font_id_text(font(p)) as StrNum);
+                            self.print_esc_strnumber(self.font_id_text(self.font(p)) as StrNum);
                             // End section 267
                         }
                         self.print_char(b' ');
-                        self.font_in_short_display = font(p);
+                        self.font_in_short_display = self.font(p);
                     }
-                    self.print_strnumber(character(p) as StrNum);
+                    self.print_strnumber(self.character(p) as StrNum);
                 }
             }
             else {
                 // Section 175
-                match r#type(p) {
+                match self.r#type(p) {
                     HLIST_NODE
                     | VLIST_NODE
                     | INS_NODE
@@ -62,22 +45,22 @@ impl Global {
                     RULE_NODE => self.print_char(b'|'),
 
                     GLUE_NODE => {
-                        if glue_ptr(p) != ZERO_GLUE {
+                        if self.glue_ptr(p) != ZERO_GLUE {
                             self.print_char(b' ');
                         }
                     },
 
                     MATH_NODE => self.print_char(b'$'),
 
-                    LIGATURE_NODE => self.short_display(lig_ptr(p)),
+                    LIGATURE_NODE => self.short_display(self.lig_ptr(p)),
 
                     DISC_NODE => {
-                        self.short_display(pre_break(p));
-                        self.short_display(post_break(p));
-                        let mut n = replace_count(p);
+                        self.short_display(self.pre_break(p));
+                        self.short_display(self.post_break(p));
+                        let mut n = self.replace_count(p);
                         while n > 0 {
-                            if link(p) != NULL {
-                                p = link(p);
+                            if self.link(p) != NULL {
+                                p = self.link(p);
                             }
                             n -= 1;
                         }
@@ -87,7 +70,7 @@ impl Global {
                 }
                 // End section 175
             }
-            p = link(p);
+            p = self.link(p);
         }
     }
 
@@ -97,16 +80,16 @@ impl Global {
             self.print_esc("CLOBBERED.")
         }
         else {
-            if font(p) < FONT_BASE as QuarterWord || font(p) > FONT_MAX as QuarterWord {
+            if self.font(p) < FONT_BASE as QuarterWord || self.font(p) > self.font_max as QuarterWord {
                 self.print_char(b'*');
             }
             else {
                 // Section 267
-                self.print_esc_strnumber(font_id_text(font(p)) as StrNum);
+                self.print_esc_strnumber(self.font_id_text(self.font(p)) as StrNum);
                 // End section 267
             }
             self.print_char(b' ');
-            self.print_strnumber(character(p) as StrNum);
+            self.print_strnumber(self.character(p) as StrNum);
         }
     }
 
@@ -116,7 +99,7 @@ impl Global {
             self.print_esc("CLOBBERED.");
         }
         else {
-            self.show_token_list(link(p), NULL, MAX_PRINT_LINE - 10);
+            self.show_token_list(self.link(p), NULL, MAX_PRINT_LINE - 10);
         }
         self.print_char(b'}');
     }
@@ -150,21 +133,21 @@ impl Global {
 
     // Section 178
     pub(crate) fn print_spec(&mut self, p: Integer, s: &str) {
-        if p < MEM_MIN || p >= self.lo_mem_max {
+        if p < self.mem_min || p >= self.lo_mem_max {
             self.print_char(b'*');
         }
         else {
-            self.print_scaled(width(p));
+            self.print_scaled(self.width(p));
             if !s.is_empty() {
                 self.print(s);
             }
-            if stretch(p) != 0 {
+            if self.stretch(p) != 0 {
                 self.print(" plus ");
-                self.print_glue(stretch(p), stretch_order(p) as Integer, s);
+                self.print_glue(self.stretch(p), self.stretch_order(p) as Integer, s);
             }
-            if shrink(p) != 0 {
+            if self.shrink(p) != 0 {
                 self.print(" minus ");
-                self.print_glue(shrink(p), shrink_order(p) as Integer, s);
+                self.print_glue(self.shrink(p), self.shrink_order(p) as Integer, s);
             }
         }
     }
@@ -174,22 +157,22 @@ impl Global {
 #[macro_export]
 macro_rules! node_list_display {
     ($s:ident, $p:expr) => {
-        append_char(b'.');
+        $s.append_char(b'.');
         $s.show_node_list($p);
-        flush_char();
+        $s.flush_char();
     };
 }
 impl Global {
     // Section 182
     pub(crate) fn show_node_list(&mut self, mut p: Integer) {
-        if cur_length() as Integer > self.depth_threshold {
+        if self.cur_length() as Integer > self.depth_threshold {
             if p > NULL {
                 self.print(" []");
             }
             return;
         }
         let mut n = 0;
-        while p > MEM_MIN {
+        while p > self.mem_min {
             self.print_ln();
             self.print_current_string();
             if p > self.mem_end {
@@ -202,7 +185,7 @@ impl Global {
                 return;
             }
             self.sec183_display_node(p);
-            p = link(p);
+            p = self.link(p);
         }
     }
 
@@ -212,7 +195,7 @@ impl Global {
             self.print_font_and_char(p);
         }
         else {
-            match r#type(p) {
+            match self.r#type(p) {
                 HLIST_NODE
                 | VLIST_NODE
                 | UNSET_NODE => self.sec184_display_box(p),
@@ -220,58 +203,58 @@ impl Global {
                 RULE_NODE => {
                     // Section 187
                     self.print_esc("rule(");
-                    self.print_rule_dimen(height(p));
+                    self.print_rule_dimen(self.height(p));
                     self.print_char(b'+');
-                    self.print_rule_dimen(depth(p));
+                    self.print_rule_dimen(self.depth(p));
                     self.print(")x");
-                    self.print_rule_dimen(width(p));
+                    self.print_rule_dimen(self.width(p));
                     // End section 187
                 },  
 
                 INS_NODE => {
                     // Section 188
                     self.print_esc("insert");
-                    self.print_int(subtype(p) as Integer);
+                    self.print_int(self.subtype(p) as Integer);
                     self.print(", natural size ");
-                    self.print_scaled(height(p));
+                    self.print_scaled(self.height(p));
                     self.print("; split(");
-                    self.print_spec(split_top_ptr(p), "");
+                    self.print_spec(self.split_top_ptr(p), "");
                     self.print_char(b',');
-                    self.print_scaled(depth(p));
+                    self.print_scaled(self.depth(p));
                     self.print("); float cost ");
-                    self.print_int(float_cost(p));
-                    node_list_display!(self, ins_ptr(p));
+                    self.print_int(self.float_cost(p));
+                    node_list_display!(self, self.ins_ptr(p));
                     // End section 188
                 },
 
                 WHATSIT_NODE => {
                     // Section 1356
-                    match subtype(p) as Integer {
+                    match self.subtype(p) as Integer {
                         OPEN_NODE => {
                             self.print_write_whatsit("openout", p);
                             self.print_char(b'=');
-                            self.print_file_name(open_name(p) as StrNum, open_area(p) as StrNum, open_ext(p) as StrNum);
+                            self.print_file_name(self.open_name(p) as StrNum, self.open_area(p) as StrNum, self.open_ext(p) as StrNum);
                         },
 
                         WRITE_NODE => {
                             self.print_write_whatsit("write", p);
-                            self.print_mark(write_tokens(p));
+                            self.print_mark(self.write_tokens(p));
                         },
 
                         CLOSE_NODE => self.print_write_whatsit("closeout", p),
 
                         SPECIAL_NODE => {
                             self.print_esc("special");
-                            self.print_mark(write_tokens(p));
+                            self.print_mark(self.write_tokens(p));
                         },
 
                         LANGUAGE_NODE => {
                             self.print_esc("setlanguage");
-                            self.print_int(what_lang(p));
+                            self.print_int(self.what_lang(p));
                             self.print(" (hyphenmin ");
-                            self.print_int(what_lhm(p) as Integer);
+                            self.print_int(self.what_lhm(p) as Integer);
                             self.print_char(b',');
-                            self.print_int(what_rhm(p) as Integer);
+                            self.print_int(self.what_rhm(p) as Integer);
                             self.print_char(b')');
                         }
 
@@ -284,19 +267,19 @@ impl Global {
 
                 KERN_NODE => {
                     // Section 191
-                    if subtype(p) != MU_GLUE {
+                    if self.subtype(p) != MU_GLUE {
                         self.print_esc("kern");
-                        if subtype(p) != NORMAL {
+                        if self.subtype(p) != NORMAL {
                             self.print_char(b' ');
                         }
-                        self.print_scaled(width(p));
-                        if subtype(p) == ACC_KERN {
+                        self.print_scaled(self.width(p));
+                        if self.subtype(p) == ACC_KERN {
                             self.print(" (for accent)");
                         }
                     }
                     else {
                         self.print_esc("mkern");
-                        self.print_scaled(width(p));
+                        self.print_scaled(self.width(p));
                         self.print("mu");
                     }
                     // End section 191
@@ -305,15 +288,15 @@ impl Global {
                 MATH_NODE => {
                     // Section 192
                     self.print_esc("math");
-                    if subtype(p) == BEFORE {
+                    if self.subtype(p) == BEFORE {
                         self.print("on");
                     }
                     else {
                         self.print("off");
                     }
-                    if width(p) != 0 {
+                    if self.width(p) != 0 {
                         self.print(", surrounded ");
-                        self.print_scaled(width(p));
+                        self.print_scaled(self.width(p));
                     }
                     // End section 192
                 }
@@ -322,12 +305,12 @@ impl Global {
                     // Section 193
                     self.print_font_and_char(lig_char!(p));
                     self.print(" (ligature ");
-                    if subtype(p) > 1 {
+                    if self.subtype(p) > 1 {
                         self.print_char(b'|');
                     }
-                    self.font_in_short_display = font(lig_char!(p));
-                    self.short_display(lig_ptr(p));
-                    if odd!(subtype(p)) {
+                    self.font_in_short_display = self.font(lig_char!(p));
+                    self.short_display(self.lig_ptr(p));
+                    if odd!(self.subtype(p)) {
                         self.print_char(b'|');
                     }
                     self.print_char(b')');
@@ -337,56 +320,56 @@ impl Global {
                 PENALTY_NODE => {
                     // Section 194
                     self.print_esc("penalty ");
-                    self.print_int(penalty(p));
+                    self.print_int(self.penalty(p));
                     // End section 194
                 },
 
                 DISC_NODE => {
                     // Section 195
                     self.print_esc("discretionary");
-                    if replace_count(p) > 0 {
+                    if self.replace_count(p) > 0 {
                         self.print_esc(" replacing ");
-                        self.print_int(replace_count(p) as Integer);
+                        self.print_int(self.replace_count(p) as Integer);
                     }
-                    node_list_display!(self, pre_break(p));
-                    append_char(b'|');
-                    self.show_node_list(post_break(p));
-                    flush_char();
+                    node_list_display!(self, self.pre_break(p));
+                    self.append_char(b'|');
+                    self.show_node_list(self.post_break(p));
+                    self.flush_char();
                     // End section 195
                 }
 
                 MARK_NODE => {
                     // Secion 196
                     self.print_esc("mark");
-                    self.print_mark(mark_ptr(p));
+                    self.print_mark(self.mark_ptr(p));
                     // End section 196
                 },
 
                 ADJUST_NODE => {
                     // Section 197
                     self.print_esc("vadjust");
-                    node_list_display!(self, adjust_ptr(p));
+                    node_list_display!(self, self.adjust_ptr(p));
                     // End section 197
                 },
 
                 // Section 690
-                STYLE_NODE => self.print_style(subtype(p) as Integer),
+                STYLE_NODE => self.print_style(self.subtype(p) as Integer),
 
                 CHOICE_NODE => {
                     // Section 695
                     self.print_esc("mathchoice");
-                    append_char(b'D');
-                    self.show_node_list(display_mlist(p));
-                    flush_char();
-                    append_char(b'T');
-                    self.show_node_list(text_mlist(p));
-                    flush_char();
-                    append_char(b'S');
-                    self.show_node_list(script_mlist(p));
-                    flush_char();
-                    append_char(b's');
-                    self.show_node_list(script_script_mlist(p));
-                    flush_char();
+                    self.append_char(b'D');
+                    self.show_node_list(self.display_mlist(p));
+                    self.flush_char();
+                    self.append_char(b'T');
+                    self.show_node_list(self.text_mlist(p));
+                    self.flush_char();
+                    self.append_char(b'S');
+                    self.show_node_list(self.script_mlist(p));
+                    self.flush_char();
+                    self.append_char(b's');
+                    self.show_node_list(self.script_script_mlist(p));
+                    self.flush_char();
                     // End section 695
                 },
 
@@ -416,40 +399,40 @@ impl Global {
 
     // Section 184
     fn sec184_display_box(&mut self, p: Integer) {
-        match r#type(p) {
+        match self.r#type(p) {
             HLIST_NODE => self.print_esc("h"),
             VLIST_NODE => self.print_esc("v"),
             _ /* Unset */ => self.print_esc("unset"),
         }
         self.print("box(");
-        self.print_scaled(height(p));
+        self.print_scaled(self.height(p));
         self.print_char(b'+');
-        self.print_scaled(depth(p));
+        self.print_scaled(self.depth(p));
         self.print(")x");
-        self.print_scaled(width(p));
-        if r#type(p) == UNSET_NODE {
+        self.print_scaled(self.width(p));
+        if self.r#type(p) == UNSET_NODE {
             // Secion 185
-            if span_count(p) != MIN_QUARTERWORD {
+            if self.span_count(p) != MIN_QUARTERWORD {
                 self.print(" (");
-                self.print_int((span_count(p) + 1) as Integer);
+                self.print_int((self.span_count(p) + 1) as Integer);
                 self.print(" columns)");
             }
-            if glue_stretch(p) != 0 {
+            if self.glue_stretch(p) != 0 {
                 self.print(", stretch ");
-                self.print_glue(glue_stretch(p), glue_order(p) as Integer, "");
+                self.print_glue(self.glue_stretch(p), self.glue_order(p) as Integer, "");
             }
-            if glue_shrink(p) != 0 {
+            if self.glue_shrink(p) != 0 {
                 self.print(", shrink ");
-                self.print_glue(glue_shrink(p), glue_sign(p) as Integer, "");
+                self.print_glue(self.glue_shrink(p), self.glue_sign(p) as Integer, "");
             }
             // End section 185
         }
         else {
             // Section 186
-            let g = glue_set(p);
-            if g != 0.0 && glue_sign(p) != NORMAL {
+            let g = self.glue_set(p);
+            if g != 0.0 && self.glue_sign(p) != NORMAL {
                 self.print(", glue set ");
-                if glue_sign(p) == SHRINKING {
+                if self.glue_sign(p) == SHRINKING {
                     self.print("- ");
                 }
                 if g.abs() > 20_000.0 {
@@ -459,42 +442,42 @@ impl Global {
                     else {
                         self.print("< -");
                     }
-                    self.print_glue(20000*UNITY, glue_order(p) as Integer, "");
+                    self.print_glue(20000*UNITY, self.glue_order(p) as Integer, "");
                 }
                 else {
-                    self.print_glue(((UNITY as Real)*g).round() as Scaled, glue_order(p) as Integer, "");
+                    self.print_glue(((UNITY as Real)*g).round() as Scaled, self.glue_order(p) as Integer, "");
                 }
             }
             // End section 186
 
-            if shift_amount(p) != 0 {
+            if self.shift_amount(p) != 0 {
                 self.print(", shifted ");
-                self.print_scaled(shift_amount(p));
+                self.print_scaled(self.shift_amount(p));
             }
         }
-        node_list_display!(self, list_ptr(p));
+        node_list_display!(self, self.list_ptr(p));
     }
 
     // Section 189
     fn sec189_display_glue(&mut self, p: Integer) {
-        if subtype(p) >= A_LEADERS {
+        if self.subtype(p) >= A_LEADERS {
             // Section 190
-            match subtype(p) {
+            match self.subtype(p) {
                 C_LEADERS => self.print_esc("cleaders "),
                 X_LEADERS => self.print_esc("xleaders "),
                 _         => self.print_esc("leaders "),
             }
-            self.print_spec(glue_ptr(p), "");
-            node_list_display!(self, leader_ptr(p));
+            self.print_spec(self.glue_ptr(p), "");
+            node_list_display!(self, self.leader_ptr(p));
             // End section 190
         }
         else {
             self.print_esc("glue");
-            if subtype(p) != NORMAL {
+            if self.subtype(p) != NORMAL {
                 self.print_char(b'(');
                 
-                match subtype(p).cmp(&COND_MATH_GLUE) {
-                    Less => self.print_skip_param(subtype(p) as Integer - 1),
+                match self.subtype(p).cmp(&COND_MATH_GLUE) {
+                    Less => self.print_skip_param(self.subtype(p) as Integer - 1),
                     Equal => self.print_esc("nonscript"),
                     Greater => self.print_esc("mskip"),
                 }
@@ -502,13 +485,13 @@ impl Global {
                 self.print_char(b')');
             }
 
-            if subtype(p) != COND_MATH_GLUE {
+            if self.subtype(p) != COND_MATH_GLUE {
                 self.print_char(b' ');
-                if subtype(p) < COND_MATH_GLUE {
-                    self.print_spec(glue_ptr(p), "");
+                if self.subtype(p) < COND_MATH_GLUE {
+                    self.print_spec(self.glue_ptr(p), "");
                 }
                 else {
-                    self.print_spec(glue_ptr(p), "mu");
+                    self.print_spec(self.glue_ptr(p), "mu");
                 }
             }
         }
@@ -517,15 +500,15 @@ impl Global {
     // Section 198
     pub(crate) fn show_box(&mut self, p: HalfWord) {
         // Section 236
-        self.depth_threshold = show_box_depth();
-        self.breadth_max = show_box_breadth();
+        self.depth_threshold = self.show_box_depth();
+        self.breadth_max = self.show_box_breadth();
         // End section 236
 
         if self.breadth_max <= 0 {
             self.breadth_max = 5;
         }
-        if pool_ptr() as Integer + self.depth_threshold >= POOL_SIZE {
-            self.depth_threshold = POOL_SIZE - (pool_ptr() - 1) as Integer;
+        if self.pool_ptr() as Integer + self.depth_threshold >= self.pool_size {
+            self.depth_threshold = self.pool_size - (self.pool_ptr() - 1) as Integer;
         }
         self.show_node_list(p);
         self.print_ln();
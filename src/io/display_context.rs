@@ -1,7 +1,4 @@
 use crate::constants::*;
-use crate::datastructures::{
-    end_line_char, error_context_lines, link
-};
 use crate::{
     Global, Integer, StrNum
 };
@@ -22,7 +19,7 @@ impl Global {
             }
             if self.base_ptr == self.input_ptr
                 || bottom_line
-                || nn < error_context_lines()
+                || nn < self.error_context_lines()
             {
                 // Section 312
                 if self.base_ptr == self.input_ptr
@@ -47,7 +44,7 @@ impl Global {
                 }
                 // End section 312
             }
-            else if nn == error_context_lines() {
+            else if nn == self.error_context_lines() {
                 self.print_nl("...");
                 nn += 1;
             }
@@ -200,7 +197,7 @@ impl Global {
     // Section 318
     fn sec318_pseudoprint_the_line(&mut self) -> Integer {
         let l = self.begin_pseudoprint();
-        let j = match (self.buffer[self.limit() as usize] as Integer) == end_line_char() {
+        let j = match (self.buffer[self.limit() as usize] as Integer) == self.end_line_char() {
             true => self.limit(),
             false => self.limit() + 1,
         };
@@ -222,7 +219,7 @@ impl Global {
             self.show_token_list(self.start(), self.loc(), 100_000);
         }
         else {
-            self.show_token_list(link(self.start()), self.loc(), 100_000);
+            self.show_token_list(self.link(self.start()), self.loc(), 100_000);
         }
         l
     }
@@ -1,13 +1,4 @@
 use crate::constants::*;
-use crate::datastructures::{
-    character, info, subtype, r#type
-};
-use crate::math::{
-    fam, large_char, large_fam, math_type, small_char, small_fam, thickness
-};
-use crate::strings::{
-    append_char, cur_length, flush_char
-};
 use crate::{
     ASCIICode, Global, HalfWord, Integer, QuarterWord,
     accent_chr, delimiter, denominator, left_delimiter,
@@ -18,14 +9,14 @@ impl Global {
     // Section 691
     pub(crate) fn print_fam_and_char(&mut self, p: HalfWord) {
         self.print_esc("fam");
-        self.print_int(fam(p) as Integer);
+        self.print_int(self.fam(p) as Integer);
         self.print_char(b' ');
-        self.print_strnumber(character(p) as usize);
+        self.print_strnumber(self.character(p) as usize);
     }
 
     pub(crate) fn print_delimiter(&mut self, p: HalfWord) {
-        let mut a = (small_fam(p) as Integer)*256 + small_char(p) as Integer;
-        a = a*4096 + (large_fam(p) as Integer)*256 + large_char(p) as Integer;
+        let mut a = (self.small_fam(p) as Integer)*256 + self.small_char(p) as Integer;
+        a = a*4096 + (self.large_fam(p) as Integer)*256 + self.large_char(p) as Integer;
         if a < 0 {
             self.print_int(a);
         }
@@ -36,37 +27,37 @@ impl Global {
 
     // Section 692
     pub(crate) fn print_subsidiary_data(&mut self, p: HalfWord, c: ASCIICode) {
-        if (cur_length() as Integer) >= self.depth_threshold {
-            if math_type(p) != EMPTY {
+        if (self.cur_length() as Integer) >= self.depth_threshold {
+            if self.math_type(p) != EMPTY {
                 self.print(" []");
             }
         }
         else {
-            append_char(c);
+            self.append_char(c);
             self.temp_ptr = p;
-            match math_type(p) {
+            match self.math_type(p) {
                 MATH_CHAR => {
                     self.print_ln();
                     self.print_current_string();
                     self.print_fam_and_char(p);
                 },
 
-                SUB_BOX => self.show_node_list(info(self.temp_ptr)), // Section 693
+                SUB_BOX => self.show_node_list(self.info(self.temp_ptr)), // Section 693
 
                 SUB_MLIST => {
-                    if info(p) == NULL {
+                    if self.info(p) == NULL {
                         self.print_ln();
                         self.print_current_string();
                         self.print("{}");
                     }
                     else {
-                        self.show_node_list(info(self.temp_ptr));
+                        self.show_node_list(self.info(self.temp_ptr));
                     }
                 },
 
                 _ => (), // Do nothing
             }
-            flush_char();
+            self.flush_char();
         }
     }
 
@@ -83,7 +74,7 @@ impl Global {
 
     // Section 696
     pub(crate) fn sec696_display_normal_noad(&mut self, p: Integer) {
-        match r#type(p) {
+        match self.r#type(p) {
             ORD_NOAD => self.print_esc("mathord"),
             OP_NOAD => self.print_esc("mathop"),
             BIN_NOAD => self.print_esc("mathbin"),
@@ -114,15 +105,15 @@ impl Global {
             _ => (), // No other cases.
         }
 
-        if subtype(p) != NORMAL {
-            if subtype(p) == LIMITS {
+        if self.subtype(p) != NORMAL {
+            if self.subtype(p) == LIMITS {
                 self.print_esc("limits");
             }
             else {
                 self.print_esc("nomilits");
             }
         }
-        if r#type(p) < LEFT_NOAD {
+        if self.r#type(p) < LEFT_NOAD {
             self.print_subsidiary_data(nucleus!(p), b'.');
         }
         self.print_subsidiary_data(supscr!(p), b'^');
@@ -132,24 +123,24 @@ impl Global {
     // Section 697
     pub(crate) fn sec697_display_fraction_noad(&mut self, p: Integer) {
         self.print_esc("fraction, thickness ");
-        if thickness(p) == DEFAULT_CODE {
+        if self.thickness(p) == DEFAULT_CODE {
             self.print("= default");
         }
         else {
-            self.print_scaled(thickness(p));
+            self.print_scaled(self.thickness(p));
         }
-        if small_fam(left_delimiter!(p)) != 0
-            || small_char(left_delimiter!(p)) != MIN_QUARTERWORD
-            || large_fam(left_delimiter!(p)) != 0
-            || large_char(left_delimiter!(p)) != MIN_QUARTERWORD
+        if self.small_fam(left_delimiter!(p)) != 0
+            || self.small_char(left_delimiter!(p)) != MIN_QUARTERWORD
+            || self.large_fam(left_delimiter!(p)) != 0
+            || self.large_char(left_delimiter!(p)) != MIN_QUARTERWORD
         {
             self.print(", left-delimiter ");
             self.print_delimiter(left_delimiter!(p));
         }
-        if small_fam(right_delimiter!(p)) != 0
-            || small_char(right_delimiter!(p)) != MIN_QUARTERWORD
-            || large_fam(right_delimiter!(p)) != 0
-            || large_char(right_delimiter!(p)) != MIN_QUARTERWORD
+        if self.small_fam(right_delimiter!(p)) != 0
+            || self.small_char(right_delimiter!(p)) != MIN_QUARTERWORD
+            || self.large_fam(right_delimiter!(p)) != 0
+            || self.large_char(right_delimiter!(p)) != MIN_QUARTERWORD
         {
             self.print(", right-delimiter ");
             self.print_delimiter(right_delimiter!(p));
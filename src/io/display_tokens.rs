@@ -1,5 +1,4 @@
 use crate::constants::*;
-use crate::datastructures::{info, link};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, StrNum,
 };
@@ -21,13 +20,13 @@ impl Global {
             if p < self.hi_mem_min || p > self.mem_end {
                 return self.print_esc("CLOBBERED.");
             }
-            if info(p) >= CS_TOKEN_FLAG {
-                self.print_cs((info(p)) - CS_TOKEN_FLAG);
+            if self.info(p) >= CS_TOKEN_FLAG {
+                self.print_cs((self.info(p)) - CS_TOKEN_FLAG);
             }
             else {
-                let m = (info(p) / 256) as QuarterWord;
-                let c = info(p) % 256;
-                if info(p) < 0 {
+                let m = (self.info(p) / 256) as QuarterWord;
+                let c = self.info(p) % 256;
+                if self.info(p) < 0 {
                     self.print_esc("BAD.");
                 }
                 else {
@@ -76,7 +75,7 @@ impl Global {
                 }
             }
             // End section 293
-            p = link(p);
+            p = self.link(p);
         }
         if p != NULL {
             self.print_esc("ETC.");
@@ -86,7 +85,7 @@ impl Global {
     // Section 295
     pub(crate) fn token_show(&mut self, p: HalfWord) {
         if p != NULL {
-            self.show_token_list(link(p), NULL, 10_000_000);
+            self.show_token_list(self.link(p), NULL, 10_000_000);
         }
     }
 
@@ -734,8 +733,10 @@ impl Global {
             // Section 1263
             SET_INTERACTION => {
                 match chr_code {
-                    ERROR_STOP_MODE => self.print_esc("errorstopmode"),
-                    _ => self.print_esc("batchmode"),
+                    BATCH_MODE => self.print_esc("batchmode"),
+                    NONSTOP_MODE => self.print_esc("nonstopmode"),
+                    SCROLL_MODE => self.print_esc("scrollmode"),
+                    _ => self.print_esc("errorstopmode"),
                 }
             },
             // End section 1263
@@ -1,26 +1,176 @@
+use crate::capacities::Capacities;
 use crate::constants::*;
-use crate::datastructures::{
-    eqtb, eqtb_mut, hash, hash_mut, mem, mem_mut, MemoryWord, day, eq_level, eq_type, equiv,
-    font_id_text, link, month, node_size, rlink, text, tracing_stats_mut, year
-};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
-use crate::io::{ByteFileInSelector, ByteFileOutSelector};
-use crate::strings::{
-    str_pool, str_pool_mut, init_pool_ptr_set, init_str_ptr_set, make_string, pool_ptr,
-    pool_ptr_set, str_ptr, str_ptr_set, str_room, str_start, str_start_mut
-};
+use crate::io::{ByteFileIn, ByteFileInSelector, ByteFileOutSelector, Checksum};
 use crate::{
-    Global, Integer, QuarterWord, StrNum
+    Global, Integer, QuarterWord, StrNum, embedded_format, update_terminal
 };
 
 // Part 50: Dumping and undumping the tables
 
+// Section 1303
+// Why a format file cannot be loaded.
+#[derive(Debug)]
+pub enum FormatError {
+    NotAFormat,
+    // Made by another engine, or by this one with other features.
+    WrongEngine(String),
+    // Its tables do not fit in the capacities of the run.
+    DifferentCapacities(String),
+    Corrupted,
+}
+
+impl FormatError {
+    pub(crate) fn reason(&self) -> String {
+        match self {
+            Self::NotAFormat => "not a format file".to_string(),
+            Self::WrongEngine(engine) => format!("wrong engine, it was made by {engine}"),
+            Self::DifferentCapacities(s) => format!("built with different capacities, {s}"),
+            Self::Corrupted => "corrupted".to_string(),
+        }
+    }
+}
+
+// Section 1307
+pub(crate) fn format_engine() -> String {
+    format!("tex-rust {}", env!("CARGO_PKG_VERSION"))
+}
+
+pub(crate) fn format_features() -> Integer {
+    let mut features = 0;
+    if cfg!(feature = "stat") {
+        features |= FORMAT_STAT;
+    }
+    if cfg!(feature = "debug") {
+        features |= FORMAT_DEBUG;
+    }
+    if cfg!(feature = "trip") {
+        features |= FORMAT_TRIP;
+    }
+    features
+}
+
+// For example "tex-rust 0.2.0 with stat, debug (format version 1)".
+fn engine_name(engine: &str, features: Integer, version: Integer) -> String {
+    let names: Vec<&str> = [(FORMAT_STAT, "stat"), (FORMAT_DEBUG, "debug"), (FORMAT_TRIP, "trip")]
+        .into_iter()
+        .filter(|&(feature, _)| features & feature != 0)
+        .map(|(_, name)| name)
+        .collect();
+    if names.is_empty() {
+        format!("{engine} (format version {version})")
+    }
+    else {
+        format!("{engine} with {} (format version {version})", names.join(", "))
+    }
+}
+
+// The header of a format file, see `store_fmt_file`.
+pub(crate) struct FormatHeader {
+    version: Integer,
+    engine: String,
+    features: Integer,
+    capacities: Vec<Integer>,
+}
+
+impl FormatHeader {
+    // The header follows the magic number, `None` if the file ends.
+    fn read(file: &mut ByteFileIn) -> Option<Self> {
+        let version = file.read_int()?;
+        let n = file.read_int().filter(|n| (0..=255).contains(n))?;
+        let engine: Vec<u8> = (0..n).map(|_| file.fbyte()).collect::<Option<_>>()?;
+        let features = file.read_int()?;
+        let n = file.read_int().filter(|n| (0..=255).contains(n))?;
+        let capacities = (0..n).map(|_| file.read_int()).collect::<Option<_>>()?;
+        Some(Self {
+            version,
+            engine: String::from_utf8_lossy(&engine).into_owned(),
+            features,
+            capacities
+        })
+    }
+
+    // Only the engine that dumped a format, with the same features, can load it.
+    fn check_engine(&self) -> Result<(), FormatError> {
+        if self.version != FORMAT_VERSION || self.engine != format_engine() || self.features != format_features() {
+            return Err(FormatError::WrongEngine(self.engine_name()));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn engine_name(&self) -> String {
+        engine_name(&self.engine, self.features, self.version)
+    }
+
+    // The capacities of INITEX, with the default for the missing ones.
+    pub(crate) fn capacities(&self) -> Capacities {
+        let mut capacities = Capacities::default();
+        for ((_, capacity), &value) in capacities.fields_mut().into_iter().zip(&self.capacities) {
+            *capacity = value;
+        }
+        capacities
+    }
+}
+
+// The header of a format, compressed or not.
+pub(crate) fn read_format_header(format: &[u8]) -> Result<FormatHeader, FormatError> {
+    let mut file = ByteFileIn::new();
+    file.set_preloaded(format.to_vec());
+    let bytes = file.read_all().ok_or(FormatError::Corrupted)?;
+    file.set_preloaded(bytes);
+    if file.read_int() != Some(FORMAT_MAGIC) {
+        return Err(FormatError::NotAFormat);
+    }
+    let header = FormatHeader::read(&mut file).ok_or(FormatError::Corrupted)?;
+    header.check_engine()?;
+    Ok(header)
+}
+
 impl Global {
     // Section 524
-    // Format file should be supplied on command line, or plain used as default.
-    // The string `s` is supplied on the command line,
-    // but it falls back the defaukt (which is plain.fmt) if empty.
-    pub fn open_fmt_file(&mut self, s: &str) -> TeXResult<()> {
+    // The format is given by `&name` at the start of the first line.
+    // Otherwise, or if it cannot be found, the string `s`
+    // supplied on the command line is used, and it falls back
+    // to the default (which is plain.fmt) if empty.
+    // As the job has not started, problems are only written on the terminal.
+    pub fn open_fmt_file(&mut self, s: &str) -> bool {
+        let mut j = self.loc();
+        if self.buffer[self.loc() as usize] == b'&' {
+            *self.loc_mut() += 1;
+            j = self.loc();
+            self.buffer[self.last as usize] = b' ';
+            while self.buffer[j as usize] != b' ' {
+                j += 1;
+            }
+            let name = String::from_utf8_lossy(&self.buffer[(self.loc() as usize)..(j as usize)]).into_owned();
+            if self.try_open_fmt_file(&name) {
+                *self.loc_mut() = j;
+                return true;
+            }
+            self.term_out.write_str("Sorry, I can't find that format; will try ");
+            self.term_out.write_str(if s.is_empty() { "PLAIN" } else { s });
+            self.term_out.write_str(".");
+            self.term_out.write_cr();
+            update_terminal!(self);
+        }
+        if !self.try_open_fmt_file(s) {
+            if s.is_empty() {
+                self.term_out.write_str("I can't find the PLAIN format file!");
+            }
+            else {
+                self.term_out.write_str("I can't find the format file '");
+                self.term_out.write_str(s);
+                self.term_out.write_str("'!");
+            }
+            self.term_out.write_cr();
+            return false;
+        }
+        *self.loc_mut() = j;
+        true
+    }
+
+    fn try_open_fmt_file(&mut self, s: &str) -> bool {
         let fmt_fname = if s.is_empty() {
             TEX_FORMAT_DEFAULT.to_string()
         }
@@ -34,26 +184,22 @@ impl Global {
             }
         };
 
-        // Try first without area
-        self.name_of_file.clear();
-        self.name_of_file.push_str(&fmt_fname);
-        if !self.b_open_in(ByteFileInSelector::FmtFile) {
-            // Second try with area
-            self.name_of_file.clear();
-            self.name_of_file.push_str(TEX_FORMAT_AREA);
-            self.name_of_file.push_str(&fmt_fname);
-            if !self.b_open_in(ByteFileInSelector::FmtFile) {
-                return Err(TeXError::CantFindFormat);
-            }
+        // The embedded formats come before the files.
+        if let Some(format) = embedded_format(fmt_fname.strip_suffix(".fmt").unwrap_or(&fmt_fname)) {
+            self.fmt_file.set_preloaded(format);
+            return true;
         }
-        Ok(())
+
+        // Then the files along TEXFORMATS.
+        self.name_of_file = fmt_fname;
+        self.b_open_in_path(ByteFileInSelector::FmtFile)
     }
 
     // Section 1302
     pub(crate) fn store_fmt_file(&mut self) -> TeXResult<()> {
         // Section 1304
         if self.save_ptr != 0 {
-            return Err(TeXError::CantDumpInGroup);
+            return Err(TeXError::CantDumpInGroup); // succumb
         }
         // End section 1304
 
@@ -62,18 +208,18 @@ impl Global {
         self.print(" (preloaded format=");
         self.print_strnumber(self.job_name);
         self.print_char(b' ');
-        self.print_int(year());
+        self.print_int(self.year());
         self.print_char(b'.');
-        self.print_int(month());
+        self.print_int(self.month());
         self.print_char(b'.');
-        self.print_int(day());
+        self.print_int(self.day());
         self.print_char(b')');
         self.selector = match self.interaction {
             BATCH_MODE => LOG_ONLY,
             _ => TERM_AND_LOG,
         };
-        str_room(1)?;
-        self.format_ident = make_string()?;
+        self.str_room(1)?;
+        self.format_ident = self.make_string()?;
         self.pack_job_name(EXT_FMT);
 
         self.b_open_out(ByteFileOutSelector::FmtFile)?;
@@ -98,44 +244,56 @@ impl Global {
         // End section 1305
 
         // Section 1307
+        // The header: the engine with its features, and the capacities of the run.
+        dump_int!(FORMAT_MAGIC);
+        dump_int!(FORMAT_VERSION);
+        let engine = format_engine();
+        dump_int!(engine.len() as Integer);
+        self.fmt_file_out.write(engine.as_bytes());
+        dump_int!(format_features());
+        let capacities = self.capacities().values();
+        dump_int!(capacities.len() as Integer);
+        for (_, value) in capacities {
+            dump_int!(value);
+        }
         dump_int!(0); // @$ = 0, external pool file is not used
         dump_int!(MEM_BOT);
-        dump_int!(MEM_TOP);
+        dump_int!(self.mem_top);
         dump_int!(EQTB_SIZE);
         dump_int!(HASH_PRIME);
         dump_int!(HYPH_SIZE);
         // End section 1307
 
         // Section 1309
-        dump_int!(pool_ptr() as Integer);
-        dump_int!(str_ptr() as Integer);
-        for k in 0..=str_ptr() {
-            dump_int!(str_start(k) as Integer);
+        dump_int!(self.pool_ptr() as Integer);
+        dump_int!(self.str_ptr() as Integer);
+        for k in 0..=self.str_ptr() {
+            dump_int!(self.str_start(k) as Integer);
         }
         let mut k = 0;
 
         macro_rules! dump_four_ASCII {
             () => {
                 let w = MemoryWord { qqqq: [
-                    str_pool(k) as QuarterWord,
-                    str_pool(k + 1) as QuarterWord,
-                    str_pool(k + 2) as QuarterWord,
-                    str_pool(k + 3) as QuarterWord
+                    self.str_pool(k) as QuarterWord,
+                    self.str_pool(k + 1) as QuarterWord,
+                    self.str_pool(k + 2) as QuarterWord,
+                    self.str_pool(k + 3) as QuarterWord
                 ]};
                 dump_wd!(w);
             };
         }
 
-        while k + 4 < pool_ptr() {
+        while k + 4 < self.pool_ptr() {
             dump_four_ASCII!();
             k += 4;
         }
-        k = pool_ptr() - 4;
+        k = self.pool_ptr() - 4;
         dump_four_ASCII!();
         self.print_ln();
-        self.print_int(str_ptr() as Integer);
+        self.print_int(self.str_ptr() as Integer);
         self.print(" strings of total length ");
-        self.print_int(pool_ptr() as Integer);
+        self.print_int(self.pool_ptr() as Integer);
         // End section 1309
 
         // Section 1311
@@ -148,12 +306,12 @@ impl Global {
         let mut x = 0;
         loop {
             for k in p..=(q + 1) {
-                dump_wd!(mem(k as usize));
+                dump_wd!(self.mem(k as usize));
             }
             x += q + 2 - p;
             self.var_used += q - p;
-            p = q + node_size(q);
-            q = rlink(q);
+            p = q + self.node_size(q);
+            q = self.rlink(q);
             if q == self.rover {
                 break;
             }
@@ -161,19 +319,19 @@ impl Global {
         self.var_used += self.lo_mem_max - p;
         self.dyn_used = self.mem_end + 1 - self.hi_mem_min;
         for k in p..=self.lo_mem_max {
-            dump_wd!(mem(k as usize));
+            dump_wd!(self.mem(k as usize));
         }
         x += self.lo_mem_max + 1 - p;
         dump_int!(self.hi_mem_min);
         dump_int!(self.avail);
         for k in self.hi_mem_min..=self.mem_end {
-            dump_wd!(mem(k as usize));
+            dump_wd!(self.mem(k as usize));
         }
         x += self.mem_end + 1 - self.hi_mem_min;
         p = self.avail;
         while p != NULL {
             self.dyn_used -= 1;
-            p = link(p);
+            p = self.link(p);
         }
         dump_int!(self.var_used);
         dump_int!(self.dyn_used);
@@ -192,9 +350,9 @@ impl Global {
             let mut j = k;
             let mut found1 = false;
             while j < INT_BASE - 1 {
-                if equiv(j) == equiv(j + 1)
-                    && eq_type(j) == eq_type(j + 1)
-                    && eq_level(j) == eq_level(j + 1)
+                if self.equiv(j) == self.equiv(j + 1)
+                    && self.eq_type(j) == self.eq_type(j + 1)
+                    && self.eq_level(j) == self.eq_level(j + 1)
                 {
                     found1 = true;
                     break;
@@ -205,9 +363,9 @@ impl Global {
                 j += 1;
                 let l = j;
                 while j < INT_BASE - 1 {
-                    if equiv(j) != equiv(j + 1)
-                        || eq_type(j) != eq_type(j + 1)
-                        || eq_level(j) != eq_level(j + 1)
+                    if self.equiv(j) != self.equiv(j + 1)
+                        || self.eq_type(j) != self.eq_type(j + 1)
+                        || self.eq_level(j) != self.eq_level(j + 1)
                     {
                         break; // Goto done1
                     }
@@ -222,7 +380,7 @@ impl Global {
             // done1:
             dump_int!(l - k);
             while k < l {
-                dump_wd!(eqtb(k as usize));
+                dump_wd!(self.eqtb(k as usize));
                 k += 1;
             }
             k = j + 1;
@@ -239,7 +397,7 @@ impl Global {
             let mut j = k;
             let mut found2 = false;
             while j < EQTB_SIZE {
-                if eqtb(j as usize).int() == eqtb((j + 1) as usize).int() {
+                if self.eqtb(j as usize).int() == self.eqtb((j + 1) as usize).int() {
                     found2 = true;
                     break;
                 }
@@ -249,7 +407,7 @@ impl Global {
                 j += 1;
                 let l = j;
                 while j < EQTB_SIZE {
-                    if eqtb(j as usize).int() != eqtb((j + 1) as usize).int() {
+                    if self.eqtb(j as usize).int() != self.eqtb((j + 1) as usize).int() {
                         break; // Goto done2
                     }
                     j += 1;
@@ -263,7 +421,7 @@ impl Global {
             // done2:
             dump_int!(l - k);
             while k < l {
-                dump_wd!(eqtb(k as usize));
+                dump_wd!(self.eqtb(k as usize));
                 k += 1;
             }
             k = j + 1;
@@ -282,15 +440,22 @@ impl Global {
         dump_int!(self.hash_used);
         self.cs_count = FROZEN_CONTROL_SEQUENCE - 1 - self.hash_used;
         for p in HASH_BASE..=self.hash_used {
-            if text(p) != 0 {
+            if self.text(p) != 0 {
                 dump_int!(p);
-                dump_wd!(hash(p as usize));
+                dump_wd!(self.hash(p as usize));
                 self.cs_count += 1;
             }
         }
         for p in (self.hash_used + 1)..UNDEFINED_CONTROL_SEQUENCE {
-            dump_wd!(hash(p as usize));
+            dump_wd!(self.hash(p as usize));
         }
+        // The control sequences of `hash_extra`, with their equivalents.
+        dump_int!(self.hash_high);
+        for p in (EQTB_SIZE + 1)..=(EQTB_SIZE + self.hash_high) {
+            dump_wd!(self.hash(p as usize));
+            dump_wd!(self.eqtb(p as usize));
+        }
+        self.cs_count += self.hash_high;
         dump_int!(self.cs_count);
         self.print_ln();
         self.print_int(self.cs_count);
@@ -330,7 +495,7 @@ impl Global {
             dump_int!(self.font_bchar[k] as Integer);
             dump_int!(self.font_false_bchar[k] as Integer);
             self.print_nl("\\font");
-            self.print_esc_strnumber(font_id_text(k as QuarterWord) as StrNum);
+            self.print_esc_strnumber(self.font_id_text(k as QuarterWord) as StrNum);
             self.print_char(b'=');
             self.print_file_name(self.font_name[k], self.font_area[k], EMPTY_STRING);
             if self.font_size[k] != self.font_dsize[k] {
@@ -378,22 +543,9 @@ impl Global {
             dump_int!(self.hyf_num[k] as Integer);
             dump_int!(self.hyf_next[k] as Integer);
         }
-        self.print_nl("Hyphenation trie of length ");
-        self.print_int(self.trie_max as Integer);
-        self.print(" has ");
-        self.print_int(self.trie_op_ptr as Integer);
-        self.print(" op");
-        if self.trie_op_ptr != 1 {
-            self.print_char(b's');
-        }
-        self.print(" out of ");
-        self.print_int(TRIE_OP_SIZE);
+        self.print_hyphenation_trie();
         for k in (0..=255).rev() {
             if self.trie_used[k] > MIN_QUARTERWORD {
-                self.print_nl("  ");
-                self.print_int(self.trie_used[k] as Integer);
-                self.print(" for language ");
-                self.print_int(k as Integer);
                 dump_int!(k as Integer);
                 dump_int!(self.trie_used[k] as Integer);
             }
@@ -404,7 +556,9 @@ impl Global {
         dump_int!(self.interaction);
         dump_int!(self.format_ident as Integer);
         dump_int!(69069);
-        *tracing_stats_mut() = 0;
+        // The checksum of the previous bytes.
+        dump_int!(self.fmt_file_out.checksum());
+        *self.tracing_stats_mut() = 0;
         // End section 1326
 
         // Section 1329
@@ -416,278 +570,507 @@ impl Global {
 }
 
 impl Global {
-    // Section 1303
-    pub fn load_fmt_file(&mut self) -> bool {
-        let bad_fmt = 'load_fmt: {
-            // Section 1306
-            macro_rules! undump {
-                ($min:expr, $max:expr) => {
-                    match self.fmt_file.read_int().filter(|&x| ($min..=$max).contains(&x)) {
-                        Some(x) => x,
-                        None => break 'load_fmt false,
-                    }
-                };
-            }
-
-            macro_rules! undump_size {
-                ($min:expr, $max:expr, $s:expr) => {
-                    match self.fmt_file.read_int() {
-                        Some(x) if x < $min => break 'load_fmt false,
-                        Some(x) if x > $max => {
-                            println!("---! Must increase the {}", $s);
-                            break 'load_fmt false;
-                        },
-                        Some(x) => x,
-                        None => break 'load_fmt false,
-                    }
-                };
-            }
-
-            macro_rules! undump_wd {
-                () => {
-                    match self.fmt_file.read_wd() {
-                        Some(w) => w,
-                        None => break 'load_fmt false,
-                    }
-                };
+    // Section 1324
+    // The size of the trie and its operations for each language.
+    pub(crate) fn print_hyphenation_trie(&mut self) {
+        self.print_nl("Hyphenation trie of length ");
+        self.print_int(self.trie_max as Integer);
+        self.print(" has ");
+        self.print_int(self.trie_op_ptr as Integer);
+        self.print(" op");
+        if self.trie_op_ptr != 1 {
+            self.print_char(b's');
+        }
+        self.print(" out of ");
+        self.print_int(TRIE_OP_SIZE);
+        for k in (0..=255).rev() {
+            if self.trie_used[k] > MIN_QUARTERWORD {
+                self.print_nl("  ");
+                self.print_int(self.trie_used[k] as Integer);
+                self.print(" for language ");
+                self.print_int(k as Integer);
             }
+        }
+    }
 
-            macro_rules! undump_int {
-                () => {
-                    match self.fmt_file.read_int() {
-                        Some(x) => x,
-                        None => break 'load_fmt false,
-                    }
-                };
-            }
-            // End section 1306
+    // Section 1303
+    // The reason of a failure is written on the terminal,
+    // as the job has not started.
+    pub fn load_fmt_file(&mut self) -> TeXResult<()> {
+        self.undump_format().map_err(|error| {
+            self.term_out.write_str(&format!("(Fatal format file error: {}; I'm stymied)", error.reason()));
+            self.term_out.write_cr();
+            TeXError::BadFormat(error)
+        })
+    }
 
-            // Section 1308
-            macro_rules! check_constant {
-                ($x:expr) => {
-                    match self.fmt_file.read_int() {
-                        Some(x) if x != $x => break 'load_fmt false,
-                        None => break 'load_fmt false,
-                        _ => ()
-                    }
-                };
-            }
-
-            check_constant!(0);
-            check_constant!(MEM_BOT);
-            check_constant!(MEM_TOP);
-            check_constant!(EQTB_SIZE);
-            check_constant!(HASH_PRIME);
-            check_constant!(HYPH_SIZE);
-            // End section 1308
-
-            // Section 1310
-            pool_ptr_set(undump_size!(0, POOL_SIZE, "string pool size") as usize);
-            str_ptr_set(undump_size!(0, MAX_STRINGS, "max strings") as usize);
-            for k in 0..=str_ptr() {
-                *str_start_mut(k) = undump!(0, pool_ptr() as Integer) as usize;
-            }
-            let mut k = 0;
-
-            macro_rules! undump_four_ASCII {
-                () => {
-                    match self.fmt_file.read_wd() {
-                        Some(w) => {
-                            *str_pool_mut(k) = w.qqqq_b0() as u8;
-                            *str_pool_mut(k + 1) = w.qqqq_b1() as u8;
-                            *str_pool_mut(k + 2) = w.qqqq_b2() as u8;
-                            *str_pool_mut(k + 3) = w.qqqq_b3() as u8;
-                        },
-                        None => break 'load_fmt false,
-                    }
-                };
-            }
+    fn undump_format(&mut self) -> Result<(), FormatError> {
+        // The whole file is read first, to check its header and its checksum.
+        let Some(bytes) = self.fmt_file.read_all() else {
+            return Err(FormatError::Corrupted);
+        };
+        if bytes.len() < 12 || bytes[..4] != FORMAT_MAGIC.to_le_bytes() {
+            return Err(FormatError::NotAFormat);
+        }
+        let (data, sum) = bytes.split_at(bytes.len() - 4);
+        let mut checksum = Checksum::INIT;
+        checksum.update(data);
+        let corrupted = sum != checksum.value().to_le_bytes();
+        self.fmt_file.set_preloaded(bytes);
 
-            while k + 4 < pool_ptr() {
-                undump_four_ASCII!();
-                k += 4;
-            }
-            k = pool_ptr() - 4;
-            undump_four_ASCII!();
-            init_str_ptr_set(str_ptr());
-            init_pool_ptr_set(pool_ptr());
-            // End section 1310
-
-            // Section 1312
-            self.lo_mem_max = undump!(LO_MEM_STAT_MAX + 1000, HI_MEM_STAT_MIN - 1);
-            self.rover = undump!(LO_MEM_STAT_MAX + 1, self.lo_mem_max);
-            let mut p = MEM_BOT;
-            let mut q = self.rover;
-            loop {
-                for k in p..=(q + 1) {
-                    *mem_mut(k as usize) = undump_wd!();
+        // Section 1306
+        macro_rules! undump {
+            ($min:expr, $max:expr) => {
+                match self.fmt_file.read_int().filter(|&x| ($min..=$max).contains(&x)) {
+                    Some(x) => x,
+                    None => return Err(FormatError::Corrupted),
                 }
-                p = q + node_size(q);
-                if p > self.lo_mem_max || (q >= rlink(q) && rlink(q) != self.rover) {
-                    break 'load_fmt false;
-                }
-                q = rlink(q);
-                if q == self.rover {
-                    break;
-                }
-            }
-            for k in p..=self.lo_mem_max {
-                *mem_mut(k as usize) = undump_wd!();
-            }
-            // This is never true: if MEM_MIN < MEM_BOT - 2
-            self.hi_mem_min = undump!(self.lo_mem_max + 1, HI_MEM_STAT_MIN);
-            self.avail = undump!(NULL, MEM_TOP);
-            self.mem_end = MEM_TOP;
-            for k in self.hi_mem_min..=self.mem_end {
-                *mem_mut(k as usize) = undump_wd!();
-            }
-            self.var_used = undump_int!();
-            self.dyn_used = undump_int!();
-            // End section 1312
-
-            // Section 1314
-            // Section 1317
-            let mut k = ACTIVE_BASE;
-            loop {
-                let mut x = undump_int!();
-                if x < 1 || k + x > EQTB_SIZE + 1 {
-                    break 'load_fmt false;
-                }
-                for j in k..(k + x) {
-                    *eqtb_mut(j as usize) = undump_wd!();
+            };
+        }
+
+        macro_rules! undump_size {
+            ($min:expr, $max:expr, $s:expr) => {
+                match self.fmt_file.read_int() {
+                    Some(x) if x < $min => return Err(FormatError::Corrupted),
+                    Some(x) if x > $max => {
+                        return Err(FormatError::DifferentCapacities(format!("{} must be at least {x}", $s)));
+                    },
+                    Some(x) => x,
+                    None => return Err(FormatError::Corrupted),
                 }
-                k += x;
-                x = undump_int!();
-                if x < 0 || k + x > EQTB_SIZE + 1 {
-                    break 'load_fmt false;
+            };
+        }
+
+        macro_rules! undump_wd {
+            () => {
+                match self.fmt_file.read_wd() {
+                    Some(w) => w,
+                    None => return Err(FormatError::Corrupted),
                 }
-                for j in k..(k + x) {
-                    *eqtb_mut(j as usize) = eqtb((k - 1) as usize);
+            };
+        }
+
+        macro_rules! undump_int {
+            () => {
+                match self.fmt_file.read_int() {
+                    Some(x) => x,
+                    None => return Err(FormatError::Corrupted),
                 }
-                k += x;
+            };
+        }
+        // End section 1306
 
-                if k > EQTB_SIZE {
-                    break;
+        // Section 1308
+        macro_rules! check_constant {
+            ($x:expr) => {
+                match self.fmt_file.read_int() {
+                    Some(x) if x != $x => {
+                        return Err(FormatError::DifferentCapacities(format!("{} is {x} instead of {}", stringify!($x), $x)));
+                    },
+                    None => return Err(FormatError::Corrupted),
+                    _ => ()
                 }
-            }
-            // End section 1317
+            };
+        }
 
-            self.par_loc = undump!(HASH_BASE, FROZEN_CONTROL_SEQUENCE);
-            self.par_token = CS_TOKEN_FLAG + self.par_loc;
-            self.write_loc = undump!(HASH_BASE, FROZEN_CONTROL_SEQUENCE);
+        // The header, see `store_fmt_file`.
+        undump_int!();
+        let Some(header) = FormatHeader::read(&mut self.fmt_file) else {
+            return Err(FormatError::Corrupted);
+        };
+        header.check_engine()?;
+        if corrupted {
+            return Err(FormatError::Corrupted);
+        }
+        // The capacities of INITEX are not needed: the tables are checked
+        // below against the capacities of this run.
 
-            // Section 1319
-            self.hash_used = undump!(HASH_BASE, FROZEN_CONTROL_SEQUENCE);
-            p = HASH_BASE - 1;
-            loop {
-                p = undump!(p + 1, self.hash_used);
-                *hash_mut(p as usize) = undump_wd!();
-                if p == self.hash_used {
-                    break;
+        check_constant!(0);
+        check_constant!(MEM_BOT);
+        // The memory of the format is used instead of `main_memory`.
+        let x = undump_int!();
+        if x < MEM_BOT + 1100 || x >= MAX_HALFWORD - self.extra_mem_top {
+            return Err(FormatError::Corrupted);
+        }
+        self.set_mem_top(x);
+        check_constant!(EQTB_SIZE);
+        check_constant!(HASH_PRIME);
+        check_constant!(HYPH_SIZE);
+        // End section 1308
+
+        // Section 1310
+        // The pool grows to hold the strings of the format,
+        // except with the `trip` feature.
+        let x = undump_size!(0, self.sup_pool_size() - 1, "pool_size");
+        self.pool_ptr_set(0);
+        if self.str_room(x as usize).is_err() {
+            return Err(FormatError::Corrupted);
+        }
+        self.pool_ptr_set(x as usize);
+        let x = undump_size!(0, self.max_strings, "max_strings");
+        self.str_ptr_set(x as usize);
+        for k in 0..=self.str_ptr() {
+            *self.str_start_mut(k) = undump!(0, self.pool_ptr() as Integer) as usize;
+        }
+        let mut k = 0;
+
+        macro_rules! undump_four_ASCII {
+            () => {
+                match self.fmt_file.read_wd() {
+                    Some(w) => {
+                        *self.str_pool_mut(k) = w.qqqq_b0() as u8;
+                        *self.str_pool_mut(k + 1) = w.qqqq_b1() as u8;
+                        *self.str_pool_mut(k + 2) = w.qqqq_b2() as u8;
+                        *self.str_pool_mut(k + 3) = w.qqqq_b3() as u8;
+                    },
+                    None => return Err(FormatError::Corrupted),
                 }
+            };
+        }
+
+        while k + 4 < self.pool_ptr() {
+            undump_four_ASCII!();
+            k += 4;
+        }
+        k = self.pool_ptr() - 4;
+        undump_four_ASCII!();
+        self.init_str_ptr_set(self.str_ptr());
+        self.init_pool_ptr_set(self.pool_ptr());
+        // End section 1310
+
+        // Section 1312
+        self.lo_mem_max = undump!(LO_MEM_STAT_MAX + 1000, self.hi_mem_stat_min() - 1);
+        self.rover = undump!(LO_MEM_STAT_MAX + 1, self.lo_mem_max);
+        let mut p = MEM_BOT;
+        let mut q = self.rover;
+        loop {
+            for k in p..=(q + 1) {
+                *self.mem_mut(k as usize) = undump_wd!();
             }
-            for p in (self.hash_used + 1)..UNDEFINED_CONTROL_SEQUENCE {
-                *hash_mut(p as usize) = undump_wd!();
-            }
-            self.cs_count = undump_int!();
-            // End section 1319
-            // End section 1314
-
-            // Section 1321
-            self.fmem_ptr = undump_size!(7, FONT_MEM_SIZE, "font meme size") as usize;
-            for k in 0..self.fmem_ptr {
-                self.font_info[k] = undump_wd!();
-            }
-            self.font_ptr = undump_size!(FONT_BASE, FONT_MAX, "font max") as QuarterWord;
-            for k in (NULL_FONT as usize)..=(self.font_ptr as usize) {
-                // Section 1323
-                self.font_check[k] = undump_wd!();
-                self.font_size[k] = undump_int!();
-                self.font_dsize[k] = undump_int!();
-                self.font_params[k] = undump!(MIN_HALFWORD, MAX_HALFWORD) as usize;
-                self.hyphen_char[k] = undump_int!();
-                self.skew_char[k] = undump_int!();
-                self.font_name[k] = undump!(0, str_ptr() as Integer) as usize;
-                self.font_area[k] = undump!(0, str_ptr() as Integer) as usize;
-                self.font_bc[k] = undump!(0, 255) as u8;
-                self.font_ec[k] = undump!(0, 255) as u8;
-                self.char_base[k] = undump_int!();
-                self.width_base[k] = undump_int!();
-                self.height_base[k] = undump_int!();
-                self.depth_base[k] = undump_int!();
-                self.italic_base[k] = undump_int!();
-                self.lig_kern_base[k] = undump_int!();
-                self.kern_base[k] = undump_int!();
-                self.exten_base[k] = undump_int!();
-                self.param_base[k] = undump_int!();
-                self.font_glue[k] = undump!(MIN_HALFWORD, self.lo_mem_max);
-                self.bchar_label[k] = undump!(0, (self.fmem_ptr - 1) as Integer) as usize;
-                self.font_bchar[k] = undump!(MIN_QUARTERWORD as Integer, NON_CHAR) as usize;
-                self.font_false_bchar[k] = undump!(MIN_QUARTERWORD as Integer, NON_CHAR) as usize;
-                // End section 1323
-            }
-            // End section 1321
-
-            // Section 1325
-            self.hyph_count = undump!(0, HYPH_SIZE);
-            for _ in 1..=self.hyph_count {
-                let j = undump!(0, HYPH_SIZE) as usize;
-                self.hyph_word[j] = undump!(0, str_ptr() as Integer) as usize;
-                self.hyph_list[j] = undump!(MIN_HALFWORD, MAX_HALFWORD);
-            }
-            let mut j = undump_size!(0, TRIE_SIZE, "trie size") as usize;
-            if self.initex_mode {
-                self.trie_max = j;
+            p = q + self.node_size(q);
+            if p > self.lo_mem_max || (q >= self.rlink(q) && self.rlink(q) != self.rover) {
+                return Err(FormatError::Corrupted);
             }
-            for k in 0..=j {
-                self.trie[k] = undump_wd!();
+            q = self.rlink(q);
+            if q == self.rover {
+                break;
             }
+        }
+        for k in p..=self.lo_mem_max {
+            *self.mem_mut(k as usize) = undump_wd!();
+        }
+        // This is never true: if MEM_MIN < MEM_BOT - 2
+        self.hi_mem_min = undump!(self.lo_mem_max + 1, self.hi_mem_stat_min());
+        self.avail = undump!(NULL, self.mem_top);
+        self.mem_end = self.mem_top;
+        for k in self.hi_mem_min..=self.mem_end {
+            *self.mem_mut(k as usize) = undump_wd!();
+        }
+        self.var_used = undump_int!();
+        self.dyn_used = undump_int!();
+        // End section 1312
 
-            j = undump_size!(0, TRIE_OP_SIZE, "trie op size") as usize;
-            if self.initex_mode {
-                self.trie_op_ptr = j;
+        // Section 1314
+        // Section 1317
+        let mut k = ACTIVE_BASE;
+        loop {
+            let mut x = undump_int!();
+            if x < 1 || k + x > EQTB_SIZE + 1 {
+                return Err(FormatError::Corrupted);
+            }
+            for j in k..(k + x) {
+                *self.eqtb_mut(j as usize) = undump_wd!();
+            }
+            k += x;
+            x = undump_int!();
+            if x < 0 || k + x > EQTB_SIZE + 1 {
+                return Err(FormatError::Corrupted);
             }
-            for k in 1..=j {
-                self.hyf_distance[k] = undump!(0, 63) as QuarterWord;
-                self.hyf_num[k] = undump!(0, 63) as QuarterWord;
-                self.hyf_next[k] = undump!(MIN_QUARTERWORD as Integer, MAX_QUARTERWORD as Integer) as QuarterWord;
+            for j in k..(k + x) {
+                *self.eqtb_mut(j as usize) = self.eqtb((k - 1) as usize);
             }
+            k += x;
 
-            if self.initex_mode {
-                for k in 0..=255 {
-                    self.trie_used[k] = MIN_QUARTERWORD;
-                }
+            if k > EQTB_SIZE {
+                break;
             }
-            let mut k = 256;
-            while j > 0 {
-                k = undump!(0, k - 1);
-                let x = undump!(1, j as Integer) as usize;
-                if self.initex_mode {
-                    self.trie_used[k as usize] = x as QuarterWord;
-                }
-                j -= x;
-                self.op_start[k as usize] = j as usize;
+        }
+        // End section 1317
+
+        self.par_loc = undump!(HASH_BASE, self.eqtb_top());
+        self.par_token = CS_TOKEN_FLAG + self.par_loc;
+        self.write_loc = undump!(HASH_BASE, self.eqtb_top());
+
+        // Section 1319
+        self.hash_used = undump!(HASH_BASE, FROZEN_CONTROL_SEQUENCE);
+        p = HASH_BASE - 1;
+        loop {
+            p = undump!(p + 1, self.hash_used);
+            *self.hash_mut(p as usize) = undump_wd!();
+            if p == self.hash_used {
+                break;
+            }
+        }
+        for p in (self.hash_used + 1)..UNDEFINED_CONTROL_SEQUENCE {
+            *self.hash_mut(p as usize) = undump_wd!();
+        }
+        self.hash_high = undump_size!(0, self.hash_extra, "hash_extra");
+        for p in (EQTB_SIZE + 1)..=(EQTB_SIZE + self.hash_high) {
+            *self.hash_mut(p as usize) = undump_wd!();
+            *self.eqtb_mut(p as usize) = undump_wd!();
+        }
+        for p in (EQTB_SIZE + self.hash_high + 1)..=self.eqtb_top() {
+            *self.eqtb_mut(p as usize) = self.eqtb(UNDEFINED_CONTROL_SEQUENCE as usize);
+        }
+        self.cs_count = undump_int!();
+        // End section 1319
+        // End section 1314
+
+        // Section 1321
+        self.fmem_ptr = undump_size!(7, self.font_mem_size, "font_mem_size") as usize;
+        for k in 0..self.fmem_ptr {
+            self.font_info[k] = undump_wd!();
+        }
+        self.font_ptr = undump_size!(FONT_BASE, self.font_max, "font_max") as QuarterWord;
+        for k in (NULL_FONT as usize)..=(self.font_ptr as usize) {
+            // Section 1323
+            self.font_check[k] = undump_wd!();
+            self.font_size[k] = undump_int!();
+            self.font_dsize[k] = undump_int!();
+            self.font_params[k] = undump!(MIN_HALFWORD, MAX_HALFWORD) as usize;
+            self.hyphen_char[k] = undump_int!();
+            self.skew_char[k] = undump_int!();
+            self.font_name[k] = undump!(0, self.str_ptr() as Integer) as usize;
+            self.font_area[k] = undump!(0, self.str_ptr() as Integer) as usize;
+            self.font_bc[k] = undump!(0, 255) as u8;
+            self.font_ec[k] = undump!(0, 255) as u8;
+            self.char_base[k] = undump_int!();
+            self.width_base[k] = undump_int!();
+            self.height_base[k] = undump_int!();
+            self.depth_base[k] = undump_int!();
+            self.italic_base[k] = undump_int!();
+            self.lig_kern_base[k] = undump_int!();
+            self.kern_base[k] = undump_int!();
+            self.exten_base[k] = undump_int!();
+            self.param_base[k] = undump_int!();
+            self.font_glue[k] = undump!(MIN_HALFWORD, self.lo_mem_max);
+            self.bchar_label[k] = undump!(0, (self.fmem_ptr - 1) as Integer) as usize;
+            self.font_bchar[k] = undump!(MIN_QUARTERWORD as Integer, NON_CHAR) as usize;
+            self.font_false_bchar[k] = undump!(MIN_QUARTERWORD as Integer, NON_CHAR) as usize;
+            // End section 1323
+        }
+        // End section 1321
+
+        // Section 1325
+        self.hyph_count = undump!(0, HYPH_SIZE);
+        for _ in 1..=self.hyph_count {
+            let j = undump!(0, HYPH_SIZE) as usize;
+            self.hyph_word[j] = undump!(0, self.str_ptr() as Integer) as usize;
+            self.hyph_list[j] = undump!(MIN_HALFWORD, MAX_HALFWORD);
+        }
+        let mut j = undump_size!(0, self.trie_size, "trie_size") as usize;
+        if self.initex_mode {
+            self.trie_max = j;
+        }
+        for k in 0..=j {
+            self.trie[k] = undump_wd!();
+        }
+
+        j = undump_size!(0, TRIE_OP_SIZE, "trie_op_size") as usize;
+        if self.initex_mode {
+            self.trie_op_ptr = j;
+        }
+        for k in 1..=j {
+            self.hyf_distance[k] = undump!(0, 63) as QuarterWord;
+            self.hyf_num[k] = undump!(0, 63) as QuarterWord;
+            self.hyf_next[k] = undump!(MIN_QUARTERWORD as Integer, MAX_QUARTERWORD as Integer) as QuarterWord;
+        }
+
+        if self.initex_mode {
+            for k in 0..=255 {
+                self.trie_used[k] = MIN_QUARTERWORD;
             }
+        }
+        let mut k = 256;
+        while j > 0 {
+            k = undump!(0, k - 1);
+            let x = undump!(1, j as Integer) as usize;
             if self.initex_mode {
-                self.trie_not_ready = false;
+                self.trie_used[k as usize] = x as QuarterWord;
             }
-            // End section 1325
+            j -= x;
+            self.op_start[k as usize] = j;
+        }
+        if self.initex_mode {
+            self.trie_not_ready = false;
+        }
+        // End section 1325
 
-            // Section 1327
-            // Interaction is restricted to BATCH_MODE and ERROR_STOP_MODE only.
-            // If NONSTOP_MODE or SCROLL_MODE are met, it falls back to BATCH_MODE.
-            self.interaction = match undump!(0, 3) {
-                ERROR_STOP_MODE => ERROR_STOP_MODE,
-                _ => BATCH_MODE,
-            };
-            self.format_ident = undump!(0, str_ptr() as Integer) as usize;
-            undump_int!() != 69069
-            // End section 1327
+        // Section 1327
+        self.interaction = undump!(BATCH_MODE, ERROR_STOP_MODE);
+        self.format_ident = undump!(0, self.str_ptr() as Integer) as usize;
+        if undump_int!() != 69069 {
+            return Err(FormatError::Corrupted);
+        }
+        // End section 1327
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+    use crate::io::deflate::inflate;
+    use crate::io::{AlphaFileIn, AlphaFileOut, MemoryFileSystem};
+
+    // The header starts with the magic number, the version,
+    // then the length of the name of the engine and the name.
+    const VERSION_OFFSET: usize = 4;
+    const ENGINE_OFFSET: usize = 12;
+
+    // The format, as dumped (compressed) and inflated.
+    fn formats() -> (Vec<u8>, Vec<u8>) {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let inflated = inflate(&format).unwrap();
+        (format, inflated)
+    }
+
+    fn load(format: &[u8], capacities: &Capacities) -> Result<(), String> {
+        let mut global = Global {
+            file_system: Box::new(MemoryFileSystem::new()),
+            term_in: AlphaFileIn::t_open_in_empty(),
+            term_out: AlphaFileOut::t_open_out_sink(),
+            ..Global::new(capacities)
         };
-        
-        if bad_fmt {
-            println!("(Fatal format file error; I'm stymied)");
+        global.initialize();
+        global.fmt_file.set_preloaded(format.to_vec());
+        global.undump_format().map_err(|error| error.reason())
+    }
+
+    #[test]
+    fn formats_are_loaded_compressed_or_not() {
+        let (format, inflated) = formats();
+        assert_eq!(load(&format, &Capacities::default()), Ok(()));
+        assert_eq!(load(&inflated, &Capacities::default()), Ok(()));
+        let header = read_format_header(&format).map_err(|error| error.reason()).unwrap();
+        assert_eq!(header.engine_name(), engine_name(&format_engine(), format_features(), FORMAT_VERSION));
+        assert_eq!(header.capacities().values(), Capacities::default().values());
+    }
+
+    #[test]
+    fn not_a_format() {
+        let reason = Err("not a format file".to_string());
+        assert_eq!(load(b"%!PS-Adobe-3.0\n", &Capacities::default()), reason);
+        assert_eq!(load(&[], &Capacities::default()), reason);
+        assert_eq!(read_format_header(b"\\relax\n").map(|_| ()).map_err(|error| error.reason()), reason);
+    }
+
+    #[test]
+    fn wrong_engine() {
+        let (_, mut inflated) = formats();
+        inflated[ENGINE_OFFSET] = b'T';
+        let reason = load(&inflated, &Capacities::default()).unwrap_err();
+        assert!(reason.starts_with("wrong engine, it was made by Tex-rust "), "{reason}");
+
+        let (_, mut inflated) = formats();
+        inflated[VERSION_OFFSET..VERSION_OFFSET + 4].copy_from_slice(&99i32.to_le_bytes());
+        let reason = load(&inflated, &Capacities::default()).unwrap_err();
+        assert!(reason.starts_with("wrong engine, it was made by tex-rust "), "{reason}");
+        assert!(reason.ends_with("(format version 99)"), "{reason}");
+        assert!(read_format_header(&inflated).is_err());
+    }
+
+    #[test]
+    fn different_capacities() {
+        let (format, _) = formats();
+        let capacities = Capacities {
+            max_strings: 500,
+            ..Capacities::default()
+        };
+        let reason = load(&format, &capacities).unwrap_err();
+        assert!(reason.starts_with("built with different capacities, max_strings must be at least "), "{reason}");
+    }
+
+    #[test]
+    fn corrupted() {
+        let corrupted = Err("corrupted".to_string());
+        let (format, inflated) = formats();
+        // A byte of the tables, caught by the checksum.
+        let mut flipped = inflated.clone();
+        let k = flipped.len() / 2;
+        flipped[k] ^= 0x10;
+        assert_eq!(load(&flipped, &Capacities::default()), corrupted);
+        // The checksum itself.
+        let mut flipped = inflated.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert_eq!(load(&flipped, &Capacities::default()), corrupted);
+        // Truncated files, compressed or not.
+        assert_eq!(load(&inflated[..inflated.len() - 100], &Capacities::default()), corrupted);
+        assert_eq!(load(&format[..format.len() / 2], &Capacities::default()), corrupted);
+        assert_eq!(load(&inflated[..ENGINE_OFFSET + 2], &Capacities::default()), corrupted);
+    }
+
+    // The name of the `n`th control sequence, in letters only.
+    fn cs_name(mut n: usize) -> String {
+        let mut name = String::from("cs");
+        loop {
+            name.push((b'a' + (n % 26) as u8) as char);
+            n /= 26;
+            if n == 0 {
+                return name;
+            }
+        }
+    }
+
+    // Defines `\csa`, `\csb`, ... as `0`, `1`, ...
+    fn definitions(count: usize) -> String {
+        (0..count).map(|n| format!("\\def\\{}{{{n}}}", cs_name(n))).collect()
+    }
+
+    // Room for the definitions, on a single line.
+    fn hash_extra() -> Capacities {
+        Capacities {
+            hash_extra: 1000,
+            main_memory: 100_000,
+            buf_size: 200_000,
+            max_strings: 10000,
+            pool_size: 100_000,
+            ..Capacities::default()
         }
-        !bad_fmt
+    }
+
+    #[test]
+    fn control_sequences_of_hash_extra() {
+        // More control sequences than `HASH_SIZE`, some are in `hash_extra`.
+        let count = HASH_SIZE as usize + 500;
+        let format = dump_format(&format!("\\catcode`\\{{=1 \\catcode`\\}}=2 {}\\dump", definitions(count)), &hash_extra());
+        let reason = load(&format, &Capacities::default()).unwrap_err();
+        assert!(reason.starts_with("built with different capacities, "), "{reason}");
+
+        // Every control sequence is found, with its value.
+        let source: String = (0..count).map(|n| format!("\\ifnum\\{}={n} \\else\\error\\fi\n", cs_name(n))).collect();
+        let options = CompileOptions {
+            format: &format,
+            capacities: hash_extra(),
+            ..CompileOptions::default()
+        };
+        let output = compile(&format!("{source}\\end\n"), &options);
+        assert!(output.errors.is_empty(), "{}", output.log);
+        assert_eq!(output.history, SPOTLESS);
+    }
+
+    #[test]
+    fn hash_overflow() {
+        let format = dump_format(MINIMAL_FORMAT, &hash_extra());
+        let options = CompileOptions {
+            format: &format,
+            capacities: hash_extra(),
+            ..CompileOptions::default()
+        };
+        let output = compile(&format!("{}\\end\n", definitions(HASH_SIZE as usize + 1000)), &options);
+        assert_eq!(output.history, FATAL_ERROR_STOP);
+        assert_eq!(output.errors[0].message, format!("TeX capacity exceeded, sorry [hash size={}]", HASH_SIZE + 1000));
     }
 }
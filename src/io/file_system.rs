@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Cursor, Read, Result, Write};
+use std::sync::{Arc, Mutex};
+
+// Every file opened by TeX (input files, TFM and format files, log and DVI
+// files, `\openout` files) goes through a `FileSystem`, identified by
+// `name_of_file`.
+pub trait FileSystem: Send {
+    // Open a text file for reading, such as input files.
+    fn open_text(&mut self, name: &str) -> Result<Box<dyn BufRead + Send>>;
+
+    // Open a binary file for reading, such as TFM and format files.
+    fn open_bytes(&mut self, name: &str) -> Result<Box<dyn Read + Send>>;
+
+    // Create a file for writing, or truncate it if it already exists.
+    fn create(&mut self, name: &str) -> Result<Box<dyn Write + Send>>;
+
+    // Names of the subdirectories of `dir` (given as `dir/`, or empty for
+    // the current directory), for the search paths ending with `//`.
+    fn subdirectories(&mut self, _dir: &str) -> Vec<String> {
+        vec![]
+    }
+}
+
+// The default: files are on disk, relative to the current directory.
+#[derive(Default)]
+pub struct DiskFileSystem;
+
+impl FileSystem for DiskFileSystem {
+    fn open_text(&mut self, name: &str) -> Result<Box<dyn BufRead + Send>> {
+        Ok(Box::new(BufReader::new(File::open(name)?)))
+    }
+
+    fn open_bytes(&mut self, name: &str) -> Result<Box<dyn Read + Send>> {
+        Ok(Box::new(File::open(name)?))
+    }
+
+    fn create(&mut self, name: &str) -> Result<Box<dyn Write + Send>> {
+        Ok(Box::new(File::create(name)?))
+    }
+
+    fn subdirectories(&mut self, dir: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+            return vec![];
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+// Files kept in memory.
+// Clones share the same files, so a handle can be kept to retrieve
+// what has been written once it is given to `Global`.
+#[derive(Clone, Default)]
+pub struct MemoryFileSystem {
+    files: Arc<Mutex<MemoryFiles>>
+}
+
+#[derive(Default)]
+struct MemoryFiles {
+    contents: BTreeMap<String, Vec<u8>>,
+    created: BTreeSet<String>
+}
+
+// A file being written, its content grows on every write.
+struct MemoryWriter {
+    name: String,
+    files: Arc<Mutex<MemoryFiles>>
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, name: &str, bytes: Vec<u8>) {
+        self.files.lock().unwrap().contents.insert(name.to_string(), bytes);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Vec<u8>> {
+        self.files.lock().unwrap().contents.get(name).cloned()
+    }
+
+    pub fn remove(&self, name: &str) -> Option<Vec<u8>> {
+        self.files.lock().unwrap().contents.remove(name)
+    }
+
+    // Names of the files created by TeX, in alphabetical order.
+    pub fn created(&self) -> Vec<String> {
+        self.files.lock().unwrap().created.iter().cloned().collect()
+    }
+
+    fn open(&self, name: &str) -> Result<Cursor<Vec<u8>>> {
+        match self.get(name) {
+            Some(bytes) => Ok(Cursor::new(bytes)),
+            None => Err(std::io::ErrorKind::NotFound.into())
+        }
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn open_text(&mut self, name: &str) -> Result<Box<dyn BufRead + Send>> {
+        Ok(Box::new(self.open(name)?))
+    }
+
+    fn open_bytes(&mut self, name: &str) -> Result<Box<dyn Read + Send>> {
+        Ok(Box::new(self.open(name)?))
+    }
+
+    fn create(&mut self, name: &str) -> Result<Box<dyn Write + Send>> {
+        let mut files = self.files.lock().unwrap();
+        files.contents.insert(name.to_string(), vec![]);
+        files.created.insert(name.to_string());
+        Ok(Box::new(MemoryWriter {
+            name: name.to_string(),
+            files: self.files.clone()
+        }))
+    }
+
+    // The directories are the prefixes of the names of the files.
+    fn subdirectories(&mut self, dir: &str) -> Vec<String> {
+        let files = self.files.lock().unwrap();
+        let subdirectories: BTreeSet<&str> = files.contents.keys()
+            .filter_map(|name| name.strip_prefix(dir)?.split_once('/'))
+            .map(|(subdirectory, _)| subdirectory)
+            .collect();
+        subdirectories.into_iter().map(str::to_string).collect()
+    }
+}
+
+impl Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if let Some(bytes) = self.files.lock().unwrap().contents.get_mut(&self.name) {
+            bytes.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_files_are_shared_by_clones() {
+        let handle = MemoryFileSystem::new();
+        let mut file_system = handle.clone();
+        let mut file = file_system.create("paper.log").unwrap();
+        file.write_all(b"first line\n").unwrap();
+        file.write_all(b"second line\n").unwrap();
+        drop(file);
+
+        let mut lines = vec![];
+        for line in file_system.open_text("paper.log").unwrap().lines() {
+            lines.push(line.unwrap());
+        }
+        assert_eq!(lines, ["first line", "second line"]);
+        let mut bytes = vec![];
+        file_system.open_bytes("paper.log").unwrap().read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, b"first line\nsecond line\n");
+        assert_eq!(handle.get("paper.log").as_deref(), Some(&bytes[..]));
+        assert_eq!(handle.created(), ["paper.log"]);
+    }
+
+    #[test]
+    fn create_truncates_an_inserted_file() {
+        let mut file_system = MemoryFileSystem::new();
+        file_system.insert("paper.dvi", vec![1, 2, 3]);
+        file_system.create("paper.dvi").unwrap().write_all(&[4]).unwrap();
+        assert_eq!(file_system.get("paper.dvi"), Some(vec![4]));
+    }
+
+    #[test]
+    fn missing_and_removed_files() {
+        let mut file_system = MemoryFileSystem::new();
+        assert_eq!(file_system.open_text("paper.tex").err().map(|e| e.kind()), Some(std::io::ErrorKind::NotFound));
+        assert!(file_system.open_bytes("cmr10.tfm").is_err());
+
+        file_system.insert("paper.tex", b"\\end".to_vec());
+        assert!(file_system.open_text("paper.tex").is_ok());
+        assert_eq!(file_system.remove("paper.tex"), Some(b"\\end".to_vec()));
+        assert_eq!(file_system.remove("paper.tex"), None);
+        assert!(file_system.open_text("paper.tex").is_err());
+        assert!(file_system.created().is_empty());
+    }
+
+    #[test]
+    fn memory_subdirectories() {
+        let mut file_system = MemoryFileSystem::new();
+        for name in ["story.tex", "TeXfonts/cmr10.tfm", "TeXfonts/ams/msam10.tfm", "fonts/cm/cmr10.tfm", "fonts/cm/cmr12.tfm"] {
+            file_system.insert(name, vec![]);
+        }
+        assert_eq!(file_system.subdirectories(""), ["TeXfonts", "fonts"]);
+        assert_eq!(file_system.subdirectories("fonts/"), ["cm"]);
+        assert_eq!(file_system.subdirectories("TeXfonts/"), ["ams"]);
+        assert!(file_system.subdirectories("fonts/cm/").is_empty());
+        assert!(file_system.subdirectories("missing/").is_empty());
+    }
+
+    #[test]
+    fn disk_subdirectories() {
+        let dir = std::env::temp_dir().join(format!("tex-rust-subdirectories-{}", std::process::id()));
+        fs::create_dir_all(dir.join("cm")).unwrap();
+        fs::create_dir_all(dir.join("ams/extra")).unwrap();
+        fs::write(dir.join("cmr10.tfm"), b"").unwrap();
+        let name = format!("{}/", dir.display());
+
+        let mut subdirectories = DiskFileSystem.subdirectories(&name);
+        subdirectories.sort();
+        assert_eq!(subdirectories, ["ams", "cm"]);
+        assert_eq!(DiskFileSystem.subdirectories(&format!("{name}ams/")), ["extra"]);
+        assert!(DiskFileSystem.subdirectories(&format!("{name}missing/")).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+use crate::constants::*;
+use crate::error::{TeXError, TeXResult};
+use crate::io::{
+    AlphaFileIn, AlphaFileOut, AlphaFileOutSelector, MemoryFileSystem
+};
+use crate::io::dumping::{FormatHeader, read_format_header};
+use crate::{
+    Global, Integer, QuarterWord, StrNum, embedded_format
+};
+
+// Inspection of format files
+// The format is loaded as by INITEX, with the capacities recorded in its
+// header, then its identification and the space used in its tables are
+// printed as in the log of `\dump`.
+
+// The report is written in this file of a file system in memory.
+const FMT_INFO_FILE: &str = "fmtinfo.log";
+
+#[derive(Default)]
+pub struct FmtInfoOptions {
+    // List every defined control sequence with its meaning.
+    pub meanings: bool,
+    // Where the format was found, given in the report.
+    pub source: FormatSource,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FormatSource {
+    #[default]
+    File,
+    Embedded
+}
+
+// As for `&name`, the embedded format `name` comes before the file
+// `name.fmt`, and ".fmt" is added if the extension is not present.
+pub fn find_format(file_name: &str) -> Option<(Vec<u8>, FormatSource)> {
+    let name = file_name.strip_suffix(".fmt").unwrap_or(file_name);
+    match embedded_format(name) {
+        Some(format) => Some((format.to_vec(), FormatSource::Embedded)),
+        None => std::fs::read(format!("{name}.fmt")).ok().map(|bytes| (bytes, FormatSource::File))
+    }
+}
+
+// The report on `format`, or the reason why it cannot be loaded.
+pub fn fmt_info(format: &[u8], options: &FmtInfoOptions) -> Result<String, String> {
+    let header = read_format_header(format).map_err(|error| error.reason())?;
+    let file_system = MemoryFileSystem::new();
+    let mut global = Global {
+        file_system: Box::new(file_system.clone()),
+        term_in: AlphaFileIn::t_open_in_empty(),
+        term_out: AlphaFileOut::t_open_out_sink(),
+        initex_mode: true,
+        ..Global::new(&header.capacities())
+    };
+    global.initialize();
+    global.fmt_file.set_preloaded(format.to_vec());
+    match global.load_fmt_file() {
+        Ok(()) => (),
+        Err(TeXError::BadFormat(error)) => return Err(error.reason()),
+        Err(_) => return Err("the format cannot be loaded".to_string())
+    }
+    global.fmt_file.close();
+    if global.print_fmt_info(&header, options).is_err() {
+        return Err("the report cannot be written".to_string());
+    }
+    let report = file_system.remove(FMT_INFO_FILE).unwrap_or_default();
+    Ok(String::from_utf8_lossy(&report).into_owned())
+}
+
+impl Global {
+    fn print_fmt_info(&mut self, header: &FormatHeader, options: &FmtInfoOptions) -> TeXResult<()> {
+        self.name_of_file = FMT_INFO_FILE.to_string();
+        self.a_open_out(AlphaFileOutSelector::LogFile)?;
+        self.selector = LOG_ONLY;
+
+        // The date and time are the ones of the dump.
+        self.sec536_print_banner_line();
+        self.print_nl("Made by ");
+        self.print(&header.engine_name());
+        self.print(match options.source {
+            FormatSource::File => ", read from a file",
+            FormatSource::Embedded => ", embedded in the binary"
+        });
+        self.print_nl("Capacities of INITEX:");
+        for (name, value) in header.capacities().values() {
+            self.print_nl(" ");
+            self.print(name);
+            self.print_char(b'=');
+            self.print_int(value);
+        }
+
+        // As in Sections 1309, 1318, 1320, 1324 and 1334.
+        self.print_nl("");
+        self.print_int(self.str_ptr() as Integer);
+        self.print(" strings of total length ");
+        self.print_int(self.pool_ptr() as Integer);
+        self.print_nl("");
+        self.print_int(self.lo_mem_max - self.mem_min + self.mem_end - self.hi_mem_min + 2);
+        self.print(" words of memory out of ");
+        self.print_int(self.mem_end + 1 - self.mem_min);
+        self.print_nl("");
+        self.print_int(self.cs_count);
+        self.print(" multiletter control sequences out of ");
+        self.print_int(HASH_SIZE + self.hash_extra);
+        for k in ((FONT_BASE + 1) as usize)..=(self.font_ptr as usize) {
+            self.print_nl("\\font");
+            self.print_esc_strnumber(self.font_id_text(k as QuarterWord) as StrNum);
+            self.print_char(b'=');
+            self.print_file_name(self.font_name[k], self.font_area[k], EMPTY_STRING);
+            self.print(" at ");
+            self.print_scaled(self.font_size[k]);
+            self.print("pt");
+        }
+        self.print_nl("");
+        self.print_int((self.fmem_ptr - 7) as Integer);
+        self.print(" words of font info for ");
+        self.print_int(self.font_ptr as Integer - FONT_BASE);
+        self.print(" preloaded font");
+        if self.font_ptr as Integer != FONT_BASE + 1 {
+            self.print_char(b's');
+        }
+        self.print_nl("");
+        self.print_int(self.hyph_count);
+        self.print(" hyphenation exception");
+        if self.hyph_count != 1 {
+            self.print_char(b's');
+        }
+        self.print_hyphenation_trie();
+
+        if options.meanings {
+            self.print_ln();
+            self.print_meanings();
+        }
+        self.print_ln();
+        self.log_file.close();
+        Ok(())
+    }
+
+    // The defined control sequences in the order of their names,
+    // shown as by `\show` (Section 1294).
+    fn print_meanings(&mut self) {
+        let mut defined: Vec<(Vec<u8>, Integer)> = (ACTIVE_BASE..FROZEN_CONTROL_SEQUENCE)
+            .chain((EQTB_SIZE + 1)..=self.eqtb_top())
+            .filter(|&p| self.eq_type(p) != UNDEFINED_CS)
+            .filter_map(|p| {
+                let name = match p {
+                    _ if p < SINGLE_BASE => vec![(p - ACTIVE_BASE) as u8],
+                    _ if p < NULL_CS => vec![(p - SINGLE_BASE) as u8],
+                    NULL_CS => vec![],
+                    _ if self.text(p) == 0 => return None,
+                    _ => {
+                        let s = self.text(p) as StrNum;
+                        (self.str_start(s)..self.str_start(s + 1)).map(|k| self.str_pool(k)).collect()
+                    }
+                };
+                Some((name, p))
+            })
+            .collect();
+        defined.sort();
+        for (_, p) in defined {
+            self.print_nl("");
+            self.sprint_cs(p);
+            self.print_char(b'=');
+            self.cur_cmd = self.eq_type(p);
+            self.cur_chr = self.equiv(p);
+            self.print_meaning();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EMBEDDED_FORMATS;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+
+    fn capacities() -> Capacities {
+        Capacities {
+            hash_extra: 100,
+            font_max: 60,
+            ..Capacities::default()
+        }
+    }
+
+    #[test]
+    fn report() {
+        let format = dump_format(MINIMAL_FORMAT, &capacities());
+        let report = fmt_info(&format, &FmtInfoOptions::default()).unwrap();
+        // The second line may be broken by `max_print_line`.
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("This is TeX, Version 3.141592653 (preloaded format=texput "), "{report}");
+        let joined = report.replace('\n', "");
+        assert!(joined.contains(&format!("Made by tex-rust {}", env!("CARGO_PKG_VERSION"))), "{report}");
+        assert!(joined.contains(&format!("format version {FORMAT_VERSION}), read from a fileCapacities of INITEX:")), "{report}");
+        let first = lines.iter().position(|&line| line == "Capacities of INITEX:").unwrap() + 1;
+        for (k, (name, value)) in capacities().values().into_iter().enumerate() {
+            assert_eq!(lines[first + k], format!(" {name}={value}"));
+        }
+        assert!(lines.iter().any(|line| line.ends_with(&format!(" multiletter control sequences out of {}", HASH_SIZE + 100))), "{report}");
+        assert!(lines.contains(&"0 words of font info for 0 preloaded fonts"), "{report}");
+        assert!(lines.contains(&"0 hyphenation exceptions"), "{report}");
+        assert!(!report.contains("\\relax=\\relax"));
+    }
+
+    #[test]
+    fn meanings_of_an_embedded_format() {
+        let format = dump_format("\\catcode`\\{=1 \\catcode`\\}=2 \\catcode`\\#=6 \\def\\greeting#1{Hello #1}\\dump", &Capacities::default());
+        let options = FmtInfoOptions {
+            meanings: true,
+            source: FormatSource::Embedded
+        };
+        let report = fmt_info(&format, &options).unwrap();
+        assert!(report.replace('\n', "").contains("), embedded in the binaryCapacities of INITEX:"), "{report}");
+        assert!(report.contains("\n\\greeting=macro:\n#1->Hello #1\n"), "{report}");
+        assert!(report.contains("\n\\relax=\\relax\n"), "{report}");
+    }
+
+    #[test]
+    fn bad_formats() {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let options = FmtInfoOptions::default();
+        assert_eq!(fmt_info(&format[..format.len() / 2], &options), Err("corrupted".to_string()));
+        assert_eq!(fmt_info(b"%!PS-Adobe-3.0\n", &options), Err("not a format file".to_string()));
+        assert_eq!(fmt_info(&[], &options), Err("not a format file".to_string()));
+    }
+
+    #[test]
+    fn formats_are_found() {
+        for (name, format) in EMBEDDED_FORMATS {
+            assert_eq!(find_format(name), Some((format.to_vec(), FormatSource::Embedded)));
+            assert_eq!(find_format(&format!("{name}.fmt")), Some((format.to_vec(), FormatSource::Embedded)));
+        }
+        let name = std::env::temp_dir().join(format!("tex-rust-fmtinfo-{}", std::process::id())).display().to_string();
+        assert_eq!(find_format(&name), None);
+        std::fs::write(format!("{name}.fmt"), b"format").unwrap();
+        assert_eq!(find_format(&name), Some((b"format".to_vec(), FormatSource::File)));
+        assert_eq!(find_format(&format!("{name}.fmt")), Some((b"format".to_vec(), FormatSource::File)));
+        std::fs::remove_file(format!("{name}.fmt")).unwrap();
+    }
+}
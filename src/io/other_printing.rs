@@ -1,23 +1,12 @@
-use crate::arithmetic::x_over_n;
-use crate::builder::broken_ins;
 use crate::constants::*;
-use crate::datastructures::{
-    r#box, cat_code, count, height, link, subtype, text, tracing_online, r#type
-};
-use crate::error::TeXResult;
-use crate::extensions::write_stream;
-use crate::strings::str_ptr;
+use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, Scaled, StrNum,
     page_goal, page_shrink, page_total
 };
 
 #[cfg(feature = "stat")]
-use crate::{
-    ho,
-    datastructures::{
-        eqtb, hash, eq_type, equiv, info, par_shape_ptr},
-};
+use crate::ho;
 
 use std::cmp::Ordering::{Equal, Greater, Less};
 
@@ -72,40 +61,40 @@ impl Global {
             }
             if p == 0 {
                 // Section 986
-                if PAGE_HEAD != self.page_tail {
+                if self.page_head() != self.page_tail {
                     self.print_nl("### current page:");
                     if self.output_active {
                         self.print(" (held over for next output");
                     }
-                    self.show_box(link(PAGE_HEAD));
+                    self.show_box(self.link(self.page_head()));
                     if (self.page_contents as HalfWord) > EMPTY {
                         self.print_nl("total height ");
                         self.print_totals();
                         self.print_nl(" goal height ");
                         self.print_scaled(page_goal!(self));
-                        let mut r = link(PAGE_INS_HEAD);
-                        while r != PAGE_INS_HEAD {
+                        let mut r = self.link(self.page_ins_head());
+                        while r != self.page_ins_head() {
                             self.print_ln();
                             self.print_esc("insert");
-                            let mut t = subtype(r) as Scaled;
+                            let mut t = self.subtype(r) as Scaled;
                             self.print_int(t);
                             self.print(" adds ");
-                            t = if count(t) == 1000 {
-                                height(r)
+                            t = if self.count(t) == 1000 {
+                                self.height(r)
                             }
                             else {
-                                x_over_n(height(r), 1000)?.0*count(t)
+                                self.x_over_n(self.height(r), 1000).0*self.count(t)
                             };
                             self.print_scaled(t);
-                            if r#type(r) == SPLIT_UP {
-                                let mut q = PAGE_HEAD;
+                            if self.r#type(r) == SPLIT_UP {
+                                let mut q = self.page_head();
                                 t = 0;
                                 loop {
-                                    q = link(q);
-                                    if r#type(q) == INS_NODE && subtype(q) == subtype(r) {
+                                    q = self.link(q);
+                                    if self.r#type(q) == INS_NODE && self.subtype(q) == self.subtype(r) {
                                         t += 1;
                                     }
-                                    if q == broken_ins(r) {
+                                    if q == self.broken_ins(r) {
                                         break;
                                     }
                                 }
@@ -113,17 +102,17 @@ impl Global {
                                 self.print_int(t);
                                 self.print(" might split");
                             }
-                            r = link(r);
+                            r = self.link(r);
                         }
                     }
                 }
                 // End section 986
 
-                if link(CONTRIB_HEAD) != NULL {
+                if self.link(self.contrib_head()) != NULL {
                     self.print_nl("### recent contributions:");
                 }
             }
-            self.show_box(link(self.nest[p].head_field));
+            self.show_box(self.link(self.nest[p].head_field));
 
             // Section 219
             match m.abs() / (MAX_COMMAND + 1) as Integer {
@@ -284,7 +273,7 @@ impl Global {
     // Section 245
     pub(crate) fn begin_diagnostic(&mut self) {
         self.old_setting = self.selector;
-        if tracing_online() <= 0 && self.selector == TERM_AND_LOG {
+        if self.tracing_online() <= 0 && self.selector == TERM_AND_LOG {
             self.selector -= 1;
             if self.history == SPOTLESS {
                 self.history = WARNING_ISSUED;
@@ -306,14 +295,14 @@ impl Global {
         if n < ACTIVE_BASE {
             self.print_char(b'?');
         }
-        else if n < GLUE_BASE {
+        else if n < GLUE_BASE || (n > EQTB_SIZE && n <= self.eqtb_top()) {
             // Section 223
             self.sprint_cs(n);
             self.print_char(b'=');
-            self.print_cmd_chr(eq_type(n), equiv(n));
-            if eq_type(n) >= CALL {
+            self.print_cmd_chr(self.eq_type(n), self.equiv(n));
+            if self.eq_type(n) >= CALL {
                 self.print_char(b':');
-                self.show_token_list(link(equiv(n)), NULL, 32);
+                self.show_token_list(self.link(self.equiv(n)), NULL, 32);
             }
             // End section 223
         }
@@ -323,23 +312,23 @@ impl Global {
                 self.print_skip_param(n - GLUE_BASE);
                 self.print_char(b'=');
                 if n < GLUE_BASE + THIN_MU_SKIP_CODE {
-                    self.print_spec(equiv(n), "pt");
+                    self.print_spec(self.equiv(n), "pt");
                 }
                 else {
-                    self.print_spec(equiv(n), "mu");
+                    self.print_spec(self.equiv(n), "mu");
                 }
             }
             else if n < MU_SKIP_BASE {
                 self.print_esc("skip");
                 self.print_int(n - SKIP_BASE);
                 self.print_char(b'=');
-                self.print_spec(equiv(n), "pt");
+                self.print_spec(self.equiv(n), "pt");
             }
             else {
                 self.print_esc("muskip");
                 self.print_int(n - MU_SKIP_BASE);
                 self.print_char(b'=');
-                self.print_spec(equiv(n), "mu");
+                self.print_spec(self.equiv(n), "mu");
             }
             // End section 229
         }
@@ -348,39 +337,39 @@ impl Global {
             if n == PAR_SHAPE_LOC {
                 self.print_esc("parshape");
                 self.print_char(b'=');
-                if par_shape_ptr() == NULL {
+                if self.par_shape_ptr() == NULL {
                     self.print_char(b'0');
                 }
                 else {
-                    self.print_int(info(par_shape_ptr()));
+                    self.print_int(self.info(self.par_shape_ptr()));
                 }
             }
             else if n < TOKS_BASE {
                 self.print_cmd_chr(ASSIGN_TOKS, n);
                 self.print_char(b'=');
-                if equiv(n) != NULL {
-                    self.show_token_list(link(equiv(n)), NULL, 32);
+                if self.equiv(n) != NULL {
+                    self.show_token_list(self.link(self.equiv(n)), NULL, 32);
                 }
             }
             else if n < BOX_BASE {
                 self.print_esc("toks");
                 self.print_int(n - TOKS_BASE);
                 self.print_char(b'=');
-                if equiv(n) != NULL {
-                    self.show_token_list(link(equiv(n)), NULL, 32);
+                if self.equiv(n) != NULL {
+                    self.show_token_list(self.link(self.equiv(n)), NULL, 32);
                 }
             }
             else if n < CUR_FONT_LOC {
                 self.print_esc("BOX");
                 self.print_int(n - BOX_BASE);
                 self.print_char(b'=');
-                if equiv(n) == NULL {
+                if self.equiv(n) == NULL {
                     self.print("void");
                 }
                 else {
                     self.depth_threshold = 0;
                     self.breadth_max = 1;
-                    self.show_node_list(equiv(n));
+                    self.show_node_list(self.equiv(n));
                 }
             }
             else if n < CAT_CODE_BASE {
@@ -405,7 +394,7 @@ impl Global {
                 self.print_int(n - DEL_CODE_BASE);
             }
             self.print_char(b'=');
-            self.print_int(eqtb(n as usize).int());
+            self.print_int(self.eqtb(n as usize).int());
             // End section 242
         }
         else if n <= EQTB_SIZE {
@@ -418,7 +407,7 @@ impl Global {
                 self.print_int(n - SCALED_BASE);
             }
             self.print_char(b'=');
-            self.print_scaled(eqtb(n as usize).sc());
+            self.print_scaled(self.eqtb(n as usize).sc());
             self.print("pt");
             // End section 251
         }
@@ -447,7 +436,7 @@ impl Global {
         }
         self.print_char(b'=');
         self.print_esc_strnumber(
-            hash((FONT_ID_BASE + equiv(n)) as usize).hh_rh() as StrNum
+            self.hash((FONT_ID_BASE + self.equiv(n)) as usize).hh_rh() as StrNum
         );
     }
 
@@ -472,13 +461,13 @@ impl Global {
                 self.print_int(n - SF_CODE_BASE);
             }
             self.print_char(b'=');
-            self.print_int(equiv(n))
+            self.print_int(self.equiv(n))
         }
         else {
             self.print_esc("mathcode");
             self.print_int(n - MATH_CODE_BASE);
             self.print_char(b'=');
-            self.print_int(ho!(equiv(n)));
+            self.print_int(ho!(self.equiv(n)));
         }
     }
 }
@@ -495,7 +484,7 @@ impl Global {
                 }
                 else {
                     self.print_esc_strnumber((p - SINGLE_BASE) as StrNum);
-                    if cat_code(p - SINGLE_BASE) == LETTER as HalfWord{
+                    if self.cat_code(p - SINGLE_BASE) == LETTER as HalfWord{
                         self.print_char(b' ');
                     }
                 }
@@ -507,14 +496,14 @@ impl Global {
                 self.print_strnumber((p - ACTIVE_BASE) as StrNum);
             }
         }
-        else if p >= UNDEFINED_CONTROL_SEQUENCE {
+        else if (UNDEFINED_CONTROL_SEQUENCE..=EQTB_SIZE).contains(&p) || p > self.eqtb_top() {
             self.print_esc("IMPOSSIBLE.");
         }
-        else if text(p) < 0 || text(p) >= (str_ptr() as Integer) {
+        else if self.text(p) < 0 || self.text(p) >= (self.str_ptr() as Integer) {
             self.print_esc("NONEXISTENT.");
         }
         else {
-            self.print_esc_strnumber(text(p) as StrNum);
+            self.print_esc_strnumber(self.text(p) as StrNum);
             self.print_char(b' ');
         }
     }
@@ -534,7 +523,7 @@ impl Global {
             }
         }
         else {
-            self.print_esc_strnumber(text(p) as StrNum);
+            self.print_esc_strnumber(self.text(p) as StrNum);
         }
     }
 
@@ -577,11 +566,11 @@ impl Global {
                     self.print_nl("> \\box");
                     self.print_int(self.cur_val);
                     self.print_char(b'=');
-                    if r#box(self.cur_val) == NULL {
+                    if self.r#box(self.cur_val) == NULL {
                         self.print("void");
                     }
                     else {
-                        self.show_box(r#box(self.cur_val));
+                        self.show_box(self.r#box(self.cur_val));
                     }
                     // End section 1296
                 },
@@ -603,8 +592,8 @@ impl Global {
                     // Section 1297
                     _ = self.the_toks()?;
                     self.print_nl("> ");
-                    self.token_show(TEMP_HEAD);
-                    self.flush_list(link(TEMP_HEAD));
+                    self.token_show(self.temp_head());
+                    self.flush_list(self.link(self.temp_head()));
                     break 'block; // Goto common_ending
                     // End section 1297
                 }
@@ -612,8 +601,8 @@ impl Global {
 
             // Section 1298
             self.end_diagnostic(true);
-            self.print_nl("! OK");
-            if self.selector == TERM_AND_LOG && tracing_online() <= 0 {
+            self.print_err("OK");
+            if self.selector == TERM_AND_LOG && self.tracing_online() <= 0 {
                 self.selector = TERM_ONLY;
                 self.print(" (see the transcript file)");
                 self.selector = TERM_AND_LOG;
@@ -622,15 +611,14 @@ impl Global {
         }
 
         // common_ending:
-        // No support for interaction.
-        Ok(())
+        self.error(TeXError::ShowWhatever)
     }
 
     // Section 1355
     pub(crate) fn print_write_whatsit(&mut self, s: &str, p: HalfWord) {
         self.print_esc(s);
-        match write_stream(p).cmp(&16) {
-            Less => self.print_int(write_stream(p)),
+        match self.write_stream(p).cmp(&16) {
+            Less => self.print_int(self.write_stream(p)),
             Equal => self.print_char(b'*'),
             Greater => self.print_char(b'-'),
         }
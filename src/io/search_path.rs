@@ -0,0 +1,236 @@
+use crate::constants::*;
+use crate::io::{
+    AlphaFileInSelector, ByteFileInSelector, FileSystem
+};
+use crate::Global;
+
+// Search paths
+// As in kpathsea, a path is a list of directories separated by `:`.
+// A directory ending with `//` also stands for all its subdirectories,
+// and an empty entry (such as a trailing `:`) for the default directories.
+// The current directory is `.`, its files are named without a prefix.
+
+pub(crate) struct SearchPath {
+    // The directories as prefixes of the names, `dir//` for a recursive one.
+    entries: Vec<String>,
+    // The prefixes once the subdirectories are listed.
+    directories: Option<Vec<String>>,
+}
+
+impl SearchPath {
+    // The path given by `spec`, the default one if there is none.
+    pub(crate) fn new(spec: Option<&str>, default: &[&str]) -> Self {
+        let mut entries = vec![];
+        for entry in spec.unwrap_or_default().split(':') {
+            if entry.is_empty() {
+                entries.extend(default.iter().map(|dir| prefix(dir)));
+            }
+            else if let Some(dir) = entry.strip_suffix("//") {
+                entries.push(prefix(dir) + "//");
+            }
+            else {
+                entries.push(prefix(entry));
+            }
+        }
+        Self {
+            entries,
+            directories: None
+        }
+    }
+
+    // The path given by the environment variable `variable`.
+    pub(crate) fn from_env(variable: &str, default: &[&str]) -> Self {
+        Self::new(std::env::var(variable).ok().as_deref(), default)
+    }
+
+    fn directories(&mut self, file_system: &mut dyn FileSystem) -> Vec<String> {
+        if self.directories.is_none() {
+            let mut directories = vec![];
+            for entry in &self.entries {
+                match entry.strip_suffix("//") {
+                    Some(dir) => list_subdirectories(file_system, dir.to_string(), &mut directories),
+                    None => directories.push(entry.clone())
+                }
+            }
+            self.directories = Some(directories);
+        }
+        self.directories.clone().unwrap_or_default()
+    }
+
+    // The names tried for `name`. A name with a directory is first tried
+    // as it is, and only then if it is absolute or explicitly relative.
+    pub(crate) fn candidates(&mut self, file_system: &mut dyn FileSystem, name: &str) -> Vec<String> {
+        let mut names = vec![];
+        if name.contains('/') {
+            names.push(name.to_string());
+            if name.starts_with('/') || name.starts_with("./") || name.starts_with("../") {
+                return names;
+            }
+        }
+        for dir in self.directories(file_system) {
+            let candidate = dir + name;
+            if !names.contains(&candidate) {
+                names.push(candidate);
+            }
+        }
+        names
+    }
+}
+
+// `dir/`, or nothing for the current directory.
+fn prefix(dir: &str) -> String {
+    match dir {
+        "" | "." | "./" => String::new(),
+        _ if dir.ends_with('/') => dir.to_string(),
+        _ => format!("{dir}/")
+    }
+}
+
+// `dir` then its subdirectories, depth first in alphabetical order.
+fn list_subdirectories(file_system: &mut dyn FileSystem, dir: String, directories: &mut Vec<String>) {
+    let mut subdirectories = file_system.subdirectories(&dir);
+    subdirectories.sort();
+    directories.push(dir.clone());
+    for subdirectory in subdirectories {
+        list_subdirectories(file_system, format!("{dir}{subdirectory}/"), directories);
+    }
+}
+
+impl Global {
+    // The paths of the environment variables TEXINPUTS (for `\input`
+    // and `\openin`), TFMFONTS and TEXFORMATS.
+    pub fn read_search_paths(&mut self) {
+        self.input_path = SearchPath::from_env("TEXINPUTS", TEX_INPUTS_DEFAULT);
+        self.font_path = SearchPath::from_env("TFMFONTS", TFM_FONTS_DEFAULT);
+        self.format_path = SearchPath::from_env("TEXFORMATS", TEX_FORMATS_DEFAULT);
+    }
+
+    // Section 27, with `name_of_file` looked for along TEXINPUTS.
+    // It becomes the name of the file found.
+    pub(crate) fn a_open_in_path(&mut self, selection: AlphaFileInSelector) -> bool {
+        let name = self.name_of_file.clone();
+        for candidate in self.input_path.candidates(self.file_system.as_mut(), &name) {
+            self.name_of_file = candidate;
+            if self.a_open_in(selection) {
+                return true;
+            }
+        }
+        self.name_of_file = name;
+        false
+    }
+
+    // The same for TFM files along TFMFONTS, and formats along TEXFORMATS.
+    pub(crate) fn b_open_in_path(&mut self, selection: ByteFileInSelector) -> bool {
+        let name = self.name_of_file.clone();
+        let path = match selection {
+            ByteFileInSelector::TfmFile => &mut self.font_path,
+            ByteFileInSelector::FmtFile => &mut self.format_path,
+        };
+        for candidate in path.candidates(self.file_system.as_mut(), &name) {
+            self.name_of_file = candidate;
+            if self.b_open_in(selection) {
+                return true;
+            }
+        }
+        self.name_of_file = name;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::MemoryFileSystem;
+
+    fn file_system(names: &[&str]) -> MemoryFileSystem {
+        let file_system = MemoryFileSystem::new();
+        for name in names {
+            file_system.insert(name, vec![]);
+        }
+        file_system
+    }
+
+    #[test]
+    fn recursive_directories() {
+        let mut file_system = file_system(&[
+            "fonts/cmr10.tfm",
+            "fonts/public/cm/cmr10.tfm",
+            "fonts/public/ams/msam10.tfm",
+            "fonts/local/logo10.tfm",
+            "fontsextra/cmr10.tfm",
+            "TeXfonts/cmr10.tfm"
+        ]);
+        let mut path = SearchPath::new(Some("fonts//:TeXfonts"), TFM_FONTS_DEFAULT);
+        // Depth first, in alphabetical order.
+        assert_eq!(path.candidates(&mut file_system, "cmr10.tfm"), [
+            "fonts/cmr10.tfm",
+            "fonts/local/cmr10.tfm",
+            "fonts/public/cmr10.tfm",
+            "fonts/public/ams/cmr10.tfm",
+            "fonts/public/cm/cmr10.tfm",
+            "TeXfonts/cmr10.tfm"
+        ]);
+        // The subdirectories are listed once.
+        file_system.insert("fonts/new/cmr10.tfm", vec![]);
+        assert_eq!(path.candidates(&mut file_system, "cmr10.tfm").len(), 6);
+
+        let mut path = SearchPath::new(Some(".//"), TFM_FONTS_DEFAULT);
+        assert_eq!(path.candidates(&mut file_system, "logo10.tfm")[..4], [
+            "logo10.tfm",
+            "TeXfonts/logo10.tfm",
+            "fonts/logo10.tfm",
+            "fonts/local/logo10.tfm"
+        ]);
+    }
+
+    #[test]
+    fn empty_entries_are_the_defaults() {
+        let mut file_system = MemoryFileSystem::new();
+        let mut candidates = |spec: Option<&str>| {
+            SearchPath::new(spec, TEX_INPUTS_DEFAULT).candidates(&mut file_system, "story.tex")
+        };
+        assert_eq!(candidates(None), ["story.tex", "TeXinputs/story.tex"]);
+        assert_eq!(candidates(Some("")), ["story.tex", "TeXinputs/story.tex"]);
+        assert_eq!(candidates(Some("mytex:")), ["mytex/story.tex", "story.tex", "TeXinputs/story.tex"]);
+        assert_eq!(candidates(Some(":mytex/")), ["story.tex", "TeXinputs/story.tex", "mytex/story.tex"]);
+        assert_eq!(candidates(Some("a::b")), ["a/story.tex", "story.tex", "TeXinputs/story.tex", "b/story.tex"]);
+        // A directory is tried once.
+        assert_eq!(candidates(Some("TeXinputs:")), ["TeXinputs/story.tex", "story.tex"]);
+        assert_eq!(candidates(Some("mytex")), ["mytex/story.tex"]);
+    }
+
+    #[test]
+    fn names_with_a_directory() {
+        let mut file_system = MemoryFileSystem::new();
+        let mut path = SearchPath::new(Some("mytex:"), TEX_INPUTS_DEFAULT);
+        assert_eq!(path.candidates(&mut file_system, "/tmp/story.tex"), ["/tmp/story.tex"]);
+        assert_eq!(path.candidates(&mut file_system, "./story.tex"), ["./story.tex"]);
+        assert_eq!(path.candidates(&mut file_system, "../story.tex"), ["../story.tex"]);
+        // Other names are tried as they are first.
+        assert_eq!(path.candidates(&mut file_system, "chapters/one.tex"), [
+            "chapters/one.tex",
+            "mytex/chapters/one.tex",
+            "TeXinputs/chapters/one.tex"
+        ]);
+    }
+
+    #[test]
+    fn files_are_opened_along_the_path() {
+        let file_system = file_system(&["mytex/macros/story.tex", "fonts/cm/cmr10.tfm"]);
+        let mut global = Global {
+            file_system: Box::new(file_system),
+            input_path: SearchPath::new(Some("mytex//:"), TEX_INPUTS_DEFAULT),
+            font_path: SearchPath::new(Some("fonts//"), TFM_FONTS_DEFAULT),
+            ..Global::new(&Default::default())
+        };
+        global.name_of_file = String::from("story.tex");
+        assert!(global.a_open_in_path(AlphaFileInSelector::ReadFile(0)));
+        assert_eq!(global.name_of_file, "mytex/macros/story.tex");
+        global.name_of_file = String::from("cmr10.tfm");
+        assert!(global.b_open_in_path(ByteFileInSelector::TfmFile));
+        assert_eq!(global.name_of_file, "fonts/cm/cmr10.tfm");
+        global.name_of_file = String::from("cmr12.tfm");
+        assert!(!global.b_open_in_path(ByteFileInSelector::TfmFile));
+        assert_eq!(global.name_of_file, "cmr12.tfm");
+    }
+}
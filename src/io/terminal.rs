@@ -1,3 +1,4 @@
+use crate::constants::NONSTOP_MODE;
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileInSelector;
 use crate::{Global, StrNum};
@@ -5,41 +6,40 @@ use crate::{Global, StrNum};
 #[cfg(feature = "debug")]
 use crate::Integer;
 
-use std::io::{stdin, Write};
-
 // Section 34
 #[macro_export]
 macro_rules! update_terminal {
-    () => {
-        _ = std::io::stdout().flush();
+    ($s:ident) => {
+        $s.term_out.flush();
     };
 }
 
-#[cfg(feature = "debug")]
-pub(crate) fn term_input_int() -> TeXResult<Option<Integer>> {
-    let mut input_line = String::new();
-    match stdin().read_line(&mut input_line) {
-        Ok(_) => (),
-        Err(_) => return Err(TeXError::IO("reading from terminal")),
+impl Global {
+    #[cfg(feature = "debug")]
+    pub(crate) fn term_input_int(&mut self) -> TeXResult<Option<Integer>> {
+        let input_line = self.term_input_string()?;
+        match input_line.trim().parse::<Integer>() {
+            Ok(x) => Ok(Some(x)),
+            Err(_) => Ok(None)
+        }
     }
 
-    match input_line.trim().parse::<Integer>() {
-        Ok(x) => Ok(Some(x)),
-        Err(_) => Ok(None)
-    }
-}
-
-pub(crate) fn term_input_string() -> TeXResult<String> {
-    let mut input = String::new();
-    match stdin().read_line(&mut input) {
-        Ok(_) => Ok(input),
-        Err(_) => Err(TeXError::IO("reading from terminal")),
+    #[cfg(feature = "debug")]
+    pub(crate) fn term_input_string(&mut self) -> TeXResult<String> {
+        let mut input = String::new();
+        match self.term_in.read_line(&mut input) {
+            Ok(_) => Ok(input),
+            Err(_) => Err(TeXError::IO("reading from terminal")),
+        }
     }
-}
 
-impl Global {
+    // Section 71
+    // TeX never waits for the terminal in batch and nonstop modes.
     fn term_input(&mut self) -> TeXResult<()> {
-        update_terminal!();
+        if self.interaction <= NONSTOP_MODE {
+            return Err(TeXError::Fatal("*** (job aborted, no legal \\end found)"));
+        }
+        update_terminal!(self);
         if !self.input_ln(AlphaFileInSelector::TermIn)? {
             return Err(TeXError::Fatal("End of file on the terminal!"));
         }
@@ -63,7 +63,7 @@ impl Global {
 
 #[macro_export]
 macro_rules! clear_terminal {
-    () => {
-        print!("\x1B[2J\x1B[1;1H");
+    ($s:ident) => {
+        $s.term_out.write_str("\x1B[2J\x1B[1;1H");
     };
 }
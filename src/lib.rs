@@ -1,12 +1,18 @@
+// Case statements follow the structure of TeX: The Program,
+// conditions inside a case are not merged into match guards.
+#![allow(clippy::collapsible_match)]
+
 mod alignment;
 mod arithmetic;
 mod breaker;
 mod builder;
+mod capacities;
+mod compile;
 pub mod constants;
 pub mod datastructures;
 #[cfg(feature = "debug")]
 mod debug;
-mod dvi;
+pub mod dvi;
 mod error;
 mod extensions;
 mod font_metric;
@@ -15,9 +21,34 @@ pub mod initialization;
 mod io;
 mod math;
 mod parser;
+mod pdf;
 pub mod strings;
+mod svg;
 
+pub use capacities::Capacities;
+pub use compile::{
+    CompileError, CompileOptions, CompileOutput, WriteOutput, compile
+};
+pub use dvi::OutputFormat;
 pub use global::Global;
+pub use io::{
+    DiskFileSystem, FileSystem, FmtInfoOptions, FormatSource, MemoryFileSystem, find_format, fmt_info
+};
+
+// Formats embedded in the binary at build time with their names (see build.rs),
+// `plain` by default if `plain.fmt` was available.
+pub const EMBEDDED_FORMATS: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/embedded_formats.rs"));
+
+// The first embedded format, empty if there is none.
+pub const PRELOADED_FORMAT: &[u8] = match EMBEDDED_FORMATS.first() {
+    Some((_, format)) => format,
+    None => &[]
+};
+
+// The embedded format `name`, given without the extension `.fmt`.
+pub fn embedded_format(name: &str) -> Option<&'static [u8]> {
+    EMBEDDED_FORMATS.iter().find(|(n, _)| *n == name).map(|(_, format)| *format)
+}
 
 // Types defined here
 type ASCIICode = u8;
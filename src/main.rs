@@ -1,40 +1,299 @@
 use tex_rust::{
-    constants::SPOTLESS,
-    datastructures::end_line_char,
-    initialization::fix_date_and_time,
-    strings::{
-        get_strings_started, init_pool_ptr_set, init_str_ptr_set,
-        pool_ptr, str_ptr
+    constants::{
+        BATCH_MODE, ERROR_MESSAGE_ISSUED, ERROR_STOP_MODE, FATAL_ERROR_STOP, NONSTOP_MODE,
+        SCROLL_MODE, SPOTLESS
     },
-    Global, Integer, end_line_char_inactive
+    dvi::{DviDiffOptions, DvitypeOptions, dvi_diff, dvitype},
+    Capacities, DiskFileSystem, FmtInfoOptions, Global, Integer, OutputFormat, PRELOADED_FORMAT,
+    embedded_format, find_format, fmt_info
 };
 
-// Part 51: The main program
+use std::io::Write;
+use std::path::Path;
 
-const PRELOADED_FORMAT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/preloaded_format.fmt"));
+// Part 51: The main program
 
 fn help() {
-    println!("Usage: tex-rust [-ini] TEXNAME[.tex] [-fmt=FMTNAME[.fmt]]");
+    println!("Usage: tex-rust [OPTION]... [TEXNAME[.tex]] [COMMANDS]");
+    println!("   or: tex-rust [OPTION]... \\FIRST-LINE");
+    println!("   or: tex-rust [OPTION]... &FMT ARGS");
     println!();
-    println!("  Run TeX on TEXNAME, usually to create TEXNAME.dvi.");
-    println!("  Contrary to original TeX, there is no prompt input if no file is provided.");
+    println!("   or: tex-rust dvitype [OPTION]... FILE.dvi");
+    println!("   or: tex-rust dvidiff [OPTION]... FIRST.dvi SECOND.dvi");
+    println!("   or: tex-rust fmtinfo [OPTION]... FILE.fmt");
+    println!();
+    println!("  Run TeX on TEXNAME, usually to create TEXNAME.dvi (or TEXNAME.pdf, or");
+    println!("  TEXNAME-1.svg, TEXNAME-2.svg, ... with one file per page).");
+    println!("  Any remaining COMMANDS are processed as TeX input after TEXNAME is read.");
+    println!("  If the first line begins with a backslash, interpret it as TeX input.");
+    println!("  If the first line begins with &FMT, use FMT.fmt as the format file.");
+    println!("  If no arguments are given, prompt for the first line with '**'.");
     println!("  A format file can be given as input (plain.fmt will be assumed by default).");
+    println!("  The formats embedded in the binary are found before the files, and the");
+    println!("  program name selects one of them (for a symlink named after the format).");
     println!();
     println!("  For dumping format, INITEX is available with `-ini` option.");
     println!("  Note that a format could still be supplied to construct a new format");
     println!("  on top of another.");
     println!();
-    println!("-fmt=FMTNAME  use FMTNAME as format file instead of plain");
-    println!("                (equal sign and file extension are optional)");
-    println!("-ini          INITEX mode for dumping formats")
+    println!("  Input files are looked for along TEXINPUTS (default .:TeXinputs/), font");
+    println!("  metrics along TFMFONTS (TeXfonts/) and formats along TEXFORMATS");
+    println!("  (.:TeXformats/). A directory ending with // includes its subdirectories,");
+    println!("  an empty entry stands for the default path.");
+    println!();
+    println!("-fmt=FMTNAME            use FMTNAME as format file instead of plain");
+    println!("                          (equal sign and file extension are optional)");
+    println!("-diagnostics=FILE        write errors, warnings and pages as JSON lines in FILE");
+    println!("-file-line-error         print error messages as file:line:error");
+    println!("-halt-on-error           stop at the first error");
+    println!("-ini                     INITEX mode for dumping formats");
+    println!("-interaction=STRING      set interaction mode (STRING=batchmode/nonstopmode/");
+    println!("                          scrollmode/errorstopmode)");
+    println!("-jobname=STRING          set the job name to STRING");
+    println!("-output-directory=DIR    write all output files in DIR");
+    println!("-output-format=FORMAT    write pages in FORMAT (dvi, pdf or svg, default dvi)");
+    println!("-type1-directory=DIR     look for Type 1 fonts and encodings in DIR");
+    println!("                          (PDF output, default TeXtype1/)");
+    println!("-map-file=FILE           use FILE as font map (default pdftex.map in the");
+    println!("                          Type 1 directory)");
+    println!("-cnf-line=STRING         set a capacity as in texmf.cnf (for instance");
+    println!("                          main_memory=500000), after reading texmf.cnf");
+    println!();
+    println!("  With `dvitype`, list the commands of FILE.dvi as DVItype does.");
+    println!();
+    println!("-output-level=N          amount of output, from 0 (errors only) to 4 (default)");
+    println!("-page-start=PAGE         start at the first page matching PAGE (for instance");
+    println!("                          1.*.-5, default *)");
+    println!("-max-pages=N             list at most N pages");
+    println!("-dpi=R                   resolution in pixels per inch (default 300)");
+    println!("-magnification=M         override the magnification of the file");
+    println!();
+    println!("  With `dvidiff`, compare the pages of two DVI files and show the first");
+    println!("  difference. The exit status is 0 if there is none, 1 otherwise.");
+    println!();
+    println!("-ignore-comment          do not compare the comments of the preambles");
+    println!("                          (they hold the date of the run)");
+    println!("-ignore-specials         do not compare the contents of \\special commands");
+    println!();
+    println!("  With `fmtinfo`, load FILE.fmt (or the embedded format of that name) and");
+    println!("  show its identification, the date of the dump, the capacities of INITEX");
+    println!("  and the space used in its tables.");
+    println!();
+    println!("-meanings                also list every defined control sequence with");
+    println!("                          its meaning")
+}
+
+// Value of the option `-name=value` or `-name value` found at `args[*n]`.
+// `n` is moved to the value in the second case.
+fn option_value<'a>(args: &'a [String], n: &mut usize, name: &str) -> Option<&'a str> {
+    let arg = args[*n].strip_prefix(name)?;
+    if let Some(value) = arg.strip_prefix('=') {
+        Some(value)
+    }
+    else if arg.is_empty() && *n + 1 < args.len() {
+        *n += 1;
+        Some(&args[*n])
+    }
+    else {
+        None
+    }
+}
+
+fn interaction_mode(s: &str) -> Option<Integer> {
+    match s {
+        "batchmode" => Some(BATCH_MODE),
+        "nonstopmode" => Some(NONSTOP_MODE),
+        "scrollmode" => Some(SCROLL_MODE),
+        "errorstopmode" => Some(ERROR_STOP_MODE),
+        _ => None
+    }
+}
+
+// `tex-rust dvitype [OPTION]... FILE.dvi`
+fn main_dvitype(args: &[String]) {
+    let mut options = DvitypeOptions::default();
+    let mut file_name = None;
+    let mut n = 0;
+    while n < args.len() {
+        if let Some(value) = option_value(args, &mut n, "-output-level") {
+            match value.parse() {
+                Ok(level @ 0..=4) => options.output_level = level,
+                _ => {
+                    println!("Sorry, the output level must be between 0 and 4.");
+                    return;
+                }
+            }
+        }
+        else if let Some(value) = option_value(args, &mut n, "-page-start") {
+            match DvitypeOptions::parse_start_page(value) {
+                Some(start_page) => options.start_page = start_page,
+                None => {
+                    println!("Sorry, I don't recognize starting page '{value}'.");
+                    return;
+                }
+            }
+        }
+        else if let Some(value) = option_value(args, &mut n, "-max-pages") {
+            match value.parse() {
+                Ok(max_pages) => options.max_pages = max_pages,
+                Err(_) => {
+                    println!("Sorry, I don't recognize number of pages '{value}'.");
+                    return;
+                }
+            }
+        }
+        else if let Some(value) = option_value(args, &mut n, "-dpi") {
+            match value.parse() {
+                Ok(resolution) if resolution > 0.0 => options.resolution = resolution,
+                _ => {
+                    println!("Sorry, I don't recognize resolution '{value}'.");
+                    return;
+                }
+            }
+        }
+        else if let Some(value) = option_value(args, &mut n, "-magnification") {
+            match value.parse() {
+                Ok(new_mag) => options.new_mag = new_mag,
+                Err(_) => {
+                    println!("Sorry, I don't recognize magnification '{value}'.");
+                    return;
+                }
+            }
+        }
+        else {
+            file_name = Some(args[n].as_str());
+        }
+        n += 1;
+    }
+    let Some(file_name) = file_name else {
+        println!("Usage: tex-rust dvitype [OPTION]... FILE.dvi");
+        return;
+    };
+    let bytes = match std::fs::read(file_name) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            println!("I can't find file '{file_name}'.");
+            std::process::exit(1);
+        }
+    };
+    let result = dvitype(&bytes, &options, &mut DiskFileSystem);
+    let (Ok(listing) | Err(listing)) = &result;
+    _ = std::io::stdout().write_all(listing.as_bytes());
+    if result.is_err() {
+        std::process::exit(1);
+    }
+}
+
+// `tex-rust dvidiff [OPTION]... FIRST.dvi SECOND.dvi`
+fn main_dvidiff(args: &[String]) {
+    let mut options = DviDiffOptions::default();
+    let mut file_names = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "-ignore-comment" => options.ignore_comment = true,
+            "-ignore-specials" => options.ignore_specials = true,
+            _ => file_names.push(arg.as_str())
+        }
+    }
+    let [first, second] = file_names[..] else {
+        println!("Usage: tex-rust dvidiff [OPTION]... FIRST.dvi SECOND.dvi");
+        std::process::exit(2);
+    };
+    let mut files = vec![];
+    for name in [first, second] {
+        match std::fs::read(name) {
+            Ok(bytes) => files.push(bytes),
+            Err(_) => {
+                println!("I can't find file '{name}'.");
+                std::process::exit(2);
+            }
+        }
+    }
+    match dvi_diff(&files[0], &files[1], &options, &mut DiskFileSystem) {
+        Ok(None) => (),
+        Ok(Some(difference)) => {
+            print!("{}", difference.report([first, second]));
+            std::process::exit(1);
+        },
+        Err(message) => {
+            println!("Bad DVI file, {message}!");
+            std::process::exit(2);
+        }
+    }
+}
+
+// `tex-rust fmtinfo [OPTION]... FILE.fmt`
+fn main_fmtinfo(args: &[String]) {
+    let mut options = FmtInfoOptions::default();
+    let mut file_name = None;
+    for arg in args {
+        match arg.as_str() {
+            "-meanings" => options.meanings = true,
+            _ => file_name = Some(arg.as_str())
+        }
+    }
+    let Some(file_name) = file_name else {
+        println!("Usage: tex-rust fmtinfo [OPTION]... FILE.fmt");
+        return;
+    };
+    let Some((bytes, source)) = find_format(file_name) else {
+        println!("I can't find file '{}.fmt'.", file_name.strip_suffix(".fmt").unwrap_or(file_name));
+        std::process::exit(1);
+    };
+    options.source = source;
+    match fmt_info(&bytes, &options) {
+        Ok(report) => print!("{report}"),
+        Err(reason) => {
+            println!("Bad format file, {reason}!");
+            std::process::exit(1);
+        }
+    }
 }
 
 fn main() {
     // Parsing arguments from command line
     let args: Vec<String> = std::env::args().collect();
-    let mut input_fname = "";
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("dvitype") => {
+            main_dvitype(&args[2..]);
+            return;
+        },
+        Some("dvidiff") => {
+            main_dvidiff(&args[2..]);
+            return;
+        },
+        Some("fmtinfo") => {
+            main_fmtinfo(&args[2..]);
+            return;
+        },
+        _ => ()
+    }
+    // As with the symlinks of TeX Live, the name of the program selects
+    // an embedded format, and `initex` is the INITEX mode.
+    let program = args.first()
+        .and_then(|arg| Path::new(arg).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut first_line: Vec<&str> = vec![];
     let mut format_fname = "";
-    let mut ini = false;
+    let mut ini = program == "initex";
+    let mut interaction = None;
+    let mut halt_on_error = false;
+    let mut file_line_error = false;
+    let mut job_name = None;
+    let mut output_directory = None;
+    let mut diagnostics = None;
+    let mut output_format = OutputFormat::Dvi;
+    let mut type1_directory = None;
+    let mut font_map_file = None;
+    // Capacities are read from `texmf.cnf` in the current directory,
+    // then from the `-cnf-line` options.
+    let mut capacities = Capacities::default();
+    if let Ok(text) = std::fs::read_to_string("texmf.cnf")
+        && let Err(message) = capacities.read_cnf(&text) {
+        println!("Sorry, in texmf.cnf: {message}.");
+        std::process::exit(1);
+    }
     let mut n = 1;
     while n < args.len() {
         if args[n] == "-h"
@@ -47,6 +306,59 @@ fn main() {
         if args[n] == "-ini" {
             ini = true;
         }
+        else if args[n] == "-halt-on-error" {
+            halt_on_error = true;
+        }
+        else if args[n] == "-file-line-error" {
+            file_line_error = true;
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-interaction") {
+            match interaction_mode(value) {
+                Some(mode) => interaction = Some(mode),
+                None => {
+                    println!("Sorry, I don't recognize interaction mode '{value}'.");
+                    std::process::exit(1);
+                }
+            }
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-jobname") {
+            job_name = Some(value.to_string());
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-output-directory") {
+            output_directory = Some(value.to_string());
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-output-format") {
+            match value {
+                "dvi" => output_format = OutputFormat::Dvi,
+                "pdf" => output_format = OutputFormat::Pdf,
+                "svg" => output_format = OutputFormat::Svg,
+                _ => {
+                    println!("Sorry, I don't recognize output format '{value}'.");
+                    std::process::exit(1);
+                }
+            }
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-type1-directory") {
+            // Directory names end with a slash, as `TeXtype1/`.
+            if value.ends_with('/') {
+                type1_directory = Some(value.to_string());
+            }
+            else {
+                type1_directory = Some(format!("{value}/"));
+            }
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-map-file") {
+            font_map_file = Some(value.to_string());
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-diagnostics") {
+            diagnostics = Some(value);
+        }
+        else if let Some(value) = option_value(&args, &mut n, "-cnf-line") {
+            if let Err(message) = capacities.set(value) {
+                println!("Sorry, {message}.");
+                std::process::exit(1);
+            }
+        }
         else if args[n].starts_with("-fmt=") {
             if format_fname.is_empty() {
                 format_fname = args[n].split_at(5).1;
@@ -60,36 +372,38 @@ fn main() {
                 n += 1;
             }
         }
-        else if input_fname.is_empty() {
-            input_fname = &args[n];
+        else {
+            first_line.push(&args[n]);
         }
         n += 1;
     }
-    if input_fname.is_empty() {
-        help();
-        return;
-    }
 
     // Section 1332
-    let mut global = Global::default();
+    let mut global = Global::new(&capacities);
+    global.history = FATAL_ERROR_STOP; // in case we quit during initialization
     global.initex_mode = ini;
+    global.halt_on_error = halt_on_error;
+    global.file_line_error = file_line_error;
+    global.job_name_override = job_name;
+    global.output_directory = output_directory;
+    global.output_format = output_format;
+    if let Some(directory) = type1_directory {
+        global.type1_directory = directory;
+    }
+    global.font_map_file = font_map_file;
+    global.read_search_paths();
+    if let Some(name) = diagnostics
+        && !global.open_diagnostics(name) {
+        println!("I can't write on file '{name}'.");
+        final_end(global);
+    }
 
-    macro_rules! manage_error {
+    // Once the job has started, TeX closes its files before stopping.
+    macro_rules! succumb_on_error {
         (global.$f:ident($($args:expr),*)) => {
             if let Err(texerror) = global.$f($($args),*) {
-                if global.error(texerror).is_err() {
-                    println!("Ouch---the error procedure produced an error!");
-                }
-                return;
-            }
-        };
-
-        ($f:ident($($args:expr),*)) => {
-            if let Err(texerror) = $f($($args),*) {
-                if global.error(texerror).is_err() {
-                    println!("Ouch---the error procedure produced an error!");
-                }
-                return;
+                global.succumb(texerror);
+                final_end(global);
             }
         };
     }
@@ -99,26 +413,32 @@ fn main() {
     if bad > 0 {
         println!("Ouch---my internal constants have been clobbered!");
         println!("---case {bad}");
-        return;
+        final_end(global);
     }
     // End section 14
 
     global.initialize();
 
-    // Load format file from preloaded format in the binary
-    if !global.initex_mode && !PRELOADED_FORMAT.is_empty() {
-        global.fmt_file.set_preloaded(PRELOADED_FORMAT);
-        if !global.load_fmt_file() {
-            return; // Final end
+    // Load format file from preloaded format in the binary,
+    // the one of the program name if there is one.
+    // With `-fmt`, only the format given is loaded.
+    let preloaded_format = embedded_format(&program).unwrap_or(PRELOADED_FORMAT);
+    if !global.initex_mode && format_fname.is_empty() && !preloaded_format.is_empty() {
+        global.fmt_file.set_preloaded(preloaded_format);
+        if global.load_fmt_file().is_err() {
+            final_end(global);
         }
     }
 
     if global.initex_mode {
-        manage_error!(get_strings_started());    
-        manage_error!(global.init_prim());
-        init_str_ptr_set(str_ptr());
-        init_pool_ptr_set(pool_ptr());
-        fix_date_and_time();
+        // Errors cannot be shown yet, as in Section 51.
+        if global.get_strings_started().and_then(|()| global.init_prim()).is_err() {
+            println!("! You have to increase pool_size or max_strings.");
+            final_end(global);
+        }
+        global.init_str_ptr_set(global.str_ptr());
+        global.init_pool_ptr_set(global.pool_ptr());
+        global.fix_date_and_time();
     }
 
     // Start of TeX
@@ -126,48 +446,52 @@ fn main() {
     
     // Section 1337
     global.initialize_input_routines();
-    if global.format_ident == 0 || !format_fname.is_empty() {
+    if !global.init_terminal(&first_line.join(" ")) {
+        final_end(global);
+    }
+    if global.format_ident == 0 || global.format_in_first_line() || !format_fname.is_empty() {
         if global.format_ident != 0 {
             global.initialize(); // erase preloaded format
         }
-        manage_error!(global.open_fmt_file(format_fname));
-        if !global.load_fmt_file() {
-            return; // Final end
+        if !global.open_fmt_file(format_fname) {
+            final_end(global);
+        }
+        if global.load_fmt_file().is_err() {
+            final_end(global);
         }
         global.fmt_file.close();
     }
 
-    // We copy input filename at the beginning of `buffer`.
-    // loc already points to the start of the buffer, but we must
-    // set last and limit accordingly to the length of the name.
-    // An end of line is added at the end of the name,
-    // except if end_line_char_inactive is true which might
-    // happen if the format modified the end of line character.
-    let len = input_fname.len();
-    global.buffer[..len].copy_from_slice(input_fname.as_bytes());
-    // We expect filenames are only ASCII.
-    *global.limit_mut() = len as Integer;
-    *global.limit_mut() = if end_line_char_inactive!() {
-         (len - 1) as Integer
+    global.prepare_first_line();
+
+    // The mode given on the command line overrides the one of the format.
+    if let Some(mode) = interaction {
+        global.interaction = mode;
     }
-    else {
-        global.buffer[len] = end_line_char() as u8;
-        len as Integer
-    };
-    global.last = len as Integer;
-    global.first = global.last + 1;
 
-    fix_date_and_time();
+    global.fix_date_and_time();
     global.sec75_initialize_print_selector();
 
-    manage_error!(global.start_input()); // \input assumed
+    if global.first_line_is_file_name() {
+        succumb_on_error!(global.start_input()); // \input assumed
+    }
     // End section 1337
 
     global.history = SPOTLESS; // ready to go!
 
-    manage_error!(global.main_control());  // come to life
-    manage_error!(global.final_cleanup()); // prepare for death
+    succumb_on_error!(global.main_control());  // come to life
+    succumb_on_error!(global.final_cleanup()); // prepare for death
 
     // End of TeX
-    manage_error!(global.close_files_and_terminate());
+    succumb_on_error!(global.close_files_and_terminate());
+    final_end(global);
+}
+
+// Section 1332, `final_end`
+// As with web2c, the exit status is 1 if an error message was issued,
+// or if TeX stopped before the job started.
+fn final_end(global: Global) -> ! {
+    let history = global.history;
+    drop(global); // the terminal is flushed
+    std::process::exit(if history >= ERROR_MESSAGE_ISSUED { 1 } else { 0 });
 }
@@ -2,11 +2,3 @@ mod math_lists;
 mod math_structures;
 mod math_subroutines;
 mod math_typesetting;
-
-pub(crate) use math_structures::{
-    display_mlist, display_mlist_mut, fam, fam_mut, large_char, large_char_mut,
-    large_fam, large_fam_mut, math_type, math_type_mut, script_mlist,
-    script_mlist_mut, script_script_mlist, script_script_mlist_mut, small_char,
-    small_char_mut, small_fam, small_fam_mut, text_mlist, text_mlist_mut,
-    thickness, thickness_mut
-};
@@ -1,21 +1,6 @@
 use crate::builder::norm_min;
 use crate::constants::*;
-use crate::datastructures::{
-    mem, mem_mut, character, character_mut, cur_fam, cur_font, del_code, display_indent,
-    display_widow_penalty, display_width, eq_type, equiv, every_display,
-    every_math, fam_fnt, font, glue_order, glue_ptr, glue_sign, hang_after,
-    hang_indent, hsize, info, info_mut, left_hyphen_min, link, link_mut,
-    list_ptr, math_code, math_surround, par_shape_ptr, post_display_penalty,
-    pre_display_penalty, pre_display_size, right_hyphen_min, shift_amount,
-    shift_amount_mut, shrink, shrink_order, stretch, stretch_order, subtype,
-    subtype_mut, r#type, type_mut, width
-};
 use crate::error::{TeXError, TeXResult};
-use crate::math::{
-    display_mlist_mut, fam_mut, large_char_mut, large_fam_mut, math_type,
-    math_type_mut, script_mlist_mut, script_script_mlist_mut, small_char_mut,
-    small_fam_mut, text_mlist_mut, thickness_mut
-};
 
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Scaled, SmallNumber, accent_chr,
@@ -29,8 +14,8 @@ use crate::{
 
 // Section 1151
 macro_rules! fam_in_range {
-    () => {
-        (0..16).contains(&cur_fam())
+    ($s:ident) => {
+        (0..16).contains(&$s.cur_fam())
     };
 }
 
@@ -53,20 +38,20 @@ impl Global {
                 -MAX_DIMEN
             }
             else {
-                self.line_break(display_widow_penalty())?;
+                self.line_break(self.display_widow_penalty())?;
                 self.sec1146_calculate_the_natural_width()
             };
 
             // Section 1149
-            let (l, s) = if par_shape_ptr() == NULL {
-                if hang_indent() != 0
-                    && (hang_after() >= 0
-                    && self.prev_graf() + 2 > hang_after()
-                    || self.prev_graf() + 1 < hang_after())
+            let (l, s) = if self.par_shape_ptr() == NULL {
+                if self.hang_indent() != 0
+                    && (self.hang_after() >= 0
+                    && self.prev_graf() + 2 > self.hang_after()
+                    || self.prev_graf() + 1 < self.hang_after())
                 {
-                    let l = hsize() - hang_indent().abs();
-                    let s = if hang_indent() > 0 {
-                        hang_indent()
+                    let l = self.hsize() - self.hang_indent().abs();
+                    let s = if self.hang_indent() > 0 {
+                        self.hang_indent()
                     }
                     else {
                         0
@@ -74,20 +59,20 @@ impl Global {
                     (l, s)
                 }
                 else {
-                    (hsize(), 0)
+                    (self.hsize(), 0)
                 }
             }
             else {
-                let n = info(par_shape_ptr());
+                let n = self.info(self.par_shape_ptr());
                 let p = if self.prev_graf() + 2 >= n {
-                    par_shape_ptr() + 2*n
+                    self.par_shape_ptr() + 2*n
                 }
                 else {
-                    par_shape_ptr() + 2*(self.prev_graf() + 2)
+                    self.par_shape_ptr() + 2*(self.prev_graf() + 2)
                 };
 
-                let s = mem((p - 1) as usize).sc();
-                let l = mem(p as usize).sc();
+                let s = self.mem((p - 1) as usize).sc();
+                let l = self.mem(p as usize).sc();
                 (l, s)
             };
             // End section 1149
@@ -98,8 +83,8 @@ impl Global {
             self.eq_word_define(DIMEN_BASE + PRE_DISPLAY_SIZE_CODE, w)?;
             self.eq_word_define(DIMEN_BASE + DISPLAY_WIDTH_CODE, l)?;
             self.eq_word_define(DIMEN_BASE + DISPLAY_INDENT_CODE, s)?;
-            if every_display() != NULL {
-                self.begin_token_list(every_display(), EVERY_DISPLAY_TEXT)?;
+            if self.every_display() != NULL {
+                self.begin_token_list(self.every_display(), EVERY_DISPLAY_TEXT)?;
             }
             if self.nest_ptr == 1 {
                 self.build_page()?;
@@ -111,8 +96,8 @@ impl Global {
             // Section 1139
             self.push_math(MATH_SHIFT_GROUP)?;
             self.eq_word_define(INT_BASE + CUR_FAM_CODE, -1)?;
-            if every_math() != NULL {
-                self.begin_token_list(every_math(), EVERY_MATH_TEXT)?;
+            if self.every_math() != NULL {
+                self.begin_token_list(self.every_math(), EVERY_MATH_TEXT)?;
             }
             // End section 1139
         }
@@ -126,8 +111,8 @@ impl Global {
         // Section 1139
         self.push_math(MATH_SHIFT_GROUP)?;
         self.eq_word_define(INT_BASE + CUR_FAM_CODE, -1)?;
-        if every_math() != NULL {
-            self.begin_token_list(every_math(), EVERY_MATH_TEXT)?;
+        if self.every_math() != NULL {
+            self.begin_token_list(self.every_math(), EVERY_MATH_TEXT)?;
         }
         // End section 1139
         Ok(())
@@ -135,52 +120,52 @@ impl Global {
 
     // Section 1146
     fn sec1146_calculate_the_natural_width(&mut self) -> Scaled {
-        let mut v = shift_amount(self.just_box) + 2*self.quad(cur_font() as QuarterWord);
+        let mut v = self.shift_amount(self.just_box) + 2*self.quad(self.cur_font() as QuarterWord);
         let mut w = -MAX_DIMEN;
-        let mut p = list_ptr(self.just_box);
+        let mut p = self.list_ptr(self.just_box);
         while p != NULL {
             // Section 1147
             let (found, d) = 'reswitch: loop {
                 if self.is_char_node(p) {
-                    let f = font(p);
-                    break (true, self.char_width(f, self.char_info(f, character(p))));
+                    let f = self.font(p);
+                    break (true, self.char_width(f, self.char_info(f, self.character(p))));
                 }
 
-                match r#type(p) {
+                match self.r#type(p) {
                     HLIST_NODE
                     | VLIST_NODE
-                    | RULE_NODE => break (true, width(p)),
+                    | RULE_NODE => break (true, self.width(p)),
 
                     LIGATURE_NODE => {
                         // Section 652
-                        *mem_mut(LIG_TRICK as usize) = mem(lig_char!(p) as usize);
-                        *link_mut(LIG_TRICK) = link(p);
-                        p = LIG_TRICK;
+                        *self.mem_mut(self.lig_trick() as usize) = self.mem(lig_char!(p) as usize);
+                        *self.link_mut(self.lig_trick()) = self.link(p);
+                        p = self.lig_trick();
                         continue 'reswitch; // Goto reswitch
                         // End section 652
                     },
 
                     KERN_NODE
-                    | MATH_NODE => break (false, width(p)),
+                    | MATH_NODE => break (false, self.width(p)),
 
                     GLUE_NODE => {
                         // Section 1148
-                        let q = glue_ptr(p);
-                        let d = width(q);
-                        if glue_sign(self.just_box) == STRETCHING {
-                            if glue_order(self.just_box) == stretch_order(q)
-                                && stretch(q) != 0
+                        let q = self.glue_ptr(p);
+                        let d = self.width(q);
+                        if self.glue_sign(self.just_box) == STRETCHING {
+                            if self.glue_order(self.just_box) == self.stretch_order(q)
+                                && self.stretch(q) != 0
                             {
                                 v = MAX_DIMEN;
                             }
                         }
-                        else if glue_sign(self.just_box) == SHRINKING
-                            && glue_order(self.just_box) == shrink_order(q)
-                            && shrink(q) != 0
+                        else if self.glue_sign(self.just_box) == SHRINKING
+                            && self.glue_order(self.just_box) == self.shrink_order(q)
+                            && self.shrink(q) != 0
                         {
                             v = MAX_DIMEN;
                         }
-                        break (subtype(p) >= A_LEADERS, d);
+                        break (self.subtype(p) >= A_LEADERS, d);
                         // End section 1148
                     },
 
@@ -209,7 +194,7 @@ impl Global {
             }
 
             // not_found:
-            p = link(p);
+            p = self.link(p);
         }
         // done:
         w
@@ -224,12 +209,12 @@ impl Global {
                     LETTER
                     | OTHER_CHAR
                     | CHAR_GIVEN => {
-                        let c = ho!(math_code(self.cur_chr));
+                        let c = ho!(self.math_code(self.cur_chr));
                         if c == 32768 {
                             // Section 1152
                             self.cur_cs = self.cur_chr + ACTIVE_BASE;
-                            self.cur_cmd = eq_type(self.cur_cs);
-                            self.cur_chr = equiv(self.cur_cs);
+                            self.cur_cmd = self.eq_type(self.cur_cs);
+                            self.cur_chr = self.equiv(self.cur_cs);
                             self.x_token()?;
                             self.back_input()?;
                             // End section 1152
@@ -271,10 +256,10 @@ impl Global {
             }
         };
 
-        *math_type_mut(p) = MATH_CHAR;
-        *character_mut(p) = (c % 256) as QuarterWord;
-        *fam_mut(p) = if c >= VAR_CODE && fam_in_range!() {
-            cur_fam() as QuarterWord
+        *self.math_type_mut(p) = MATH_CHAR;
+        *self.character_mut(p) = (c % 256) as QuarterWord;
+        *self.fam_mut(p) = if c >= VAR_CODE && fam_in_range!(self) {
+            self.cur_fam() as QuarterWord
         }
         else {
             ((c / 256) % 16) as QuarterWord
@@ -287,27 +272,27 @@ impl Global {
         if c >= 32768 {
             // Section 1152
             self.cur_cs = self.cur_chr + ACTIVE_BASE;
-            self.cur_cmd = eq_type(self.cur_cs);
-            self.cur_chr = equiv(self.cur_cs);
+            self.cur_cmd = self.eq_type(self.cur_cs);
+            self.cur_chr = self.equiv(self.cur_cs);
             self.x_token()?;
             self.back_input()?;
             // End section 1152
         }
         else {
             let p = self.new_noad()?;
-            *math_type_mut(nucleus!(p)) = MATH_CHAR;
-            *character_mut(nucleus!(p)) = (c % 256) as QuarterWord;
-            *fam_mut(nucleus!(p)) = ((c / 256) % 16) as QuarterWord;
+            *self.math_type_mut(nucleus!(p)) = MATH_CHAR;
+            *self.character_mut(nucleus!(p)) = (c % 256) as QuarterWord;
+            *self.fam_mut(nucleus!(p)) = ((c / 256) % 16) as QuarterWord;
             if c >= VAR_CODE {
-                if fam_in_range!() {
-                    *fam_mut(nucleus!(p)) = cur_fam() as QuarterWord;
+                if fam_in_range!(self) {
+                    *self.fam_mut(nucleus!(p)) = self.cur_fam() as QuarterWord;
                 }
-                *type_mut(p) = ORD_NOAD;
+                *self.type_mut(p) = ORD_NOAD;
             }
             else {
-                *type_mut(p) = ORD_NOAD + (c / 4096) as QuarterWord;
+                *self.type_mut(p) = ORD_NOAD + (c / 4096) as QuarterWord;
             }
-            *link_mut(self.tail()) = p;
+            *self.link_mut(self.tail()) = p;
             *self.tail_mut() = p;
         }
         Ok(())
@@ -315,12 +300,12 @@ impl Global {
 
     // Section 1159
     pub(crate) fn math_limit_switch(&mut self) -> TeXResult<()> {
-        if self.head() != self.tail() && r#type(self.tail()) == OP_NOAD {
-            *subtype_mut(self.tail()) = self.cur_chr as QuarterWord;
+        if self.head() != self.tail() && self.r#type(self.tail()) == OP_NOAD {
+            *self.subtype_mut(self.tail()) = self.cur_chr as QuarterWord;
             Ok(())
         }
         else {
-            Err(TeXError::LimitControlsMustFollowMathOp)
+            self.error(TeXError::LimitControlsMustFollowMathOp)
         }
     }
 
@@ -332,7 +317,7 @@ impl Global {
         else {
             sec404_get_next_nonblank_nonrelax_noncall_token!(self);
             match self.cur_cmd {
-                LETTER | OTHER_CHAR => self.cur_val = del_code(self.cur_chr),
+                LETTER | OTHER_CHAR => self.cur_val = self.del_code(self.cur_chr),
 
                 DELIM_NUM => self.scan_twenty_seven_bit_int()?,
 
@@ -340,24 +325,26 @@ impl Global {
             }
         }
         if self.cur_val < 0 {
-            return Err(TeXError::MissingDelimiterLeftParen)
+            // Section 1161
+            self.back_error(TeXError::MissingDelimiterLeftParen)?;
+            self.cur_val = 0;
         }
         
-        *small_fam_mut(p) = ((self.cur_val / 1_048_576) % 16) as QuarterWord;
-        *small_char_mut(p) = ((self.cur_val / 4096) % 256) as QuarterWord;
-        *large_fam_mut(p) = ((self.cur_val / 256) % 16) as QuarterWord;
-        *large_char_mut(p) = (self.cur_val % 256) as QuarterWord;
+        *self.small_fam_mut(p) = ((self.cur_val / 1_048_576) % 16) as QuarterWord;
+        *self.small_char_mut(p) = ((self.cur_val / 4096) % 256) as QuarterWord;
+        *self.large_fam_mut(p) = ((self.cur_val / 256) % 16) as QuarterWord;
+        *self.large_char_mut(p) = (self.cur_val % 256) as QuarterWord;
         Ok(())
     }
 
     // Section 1163
     pub(crate) fn math_radical(&mut self) -> TeXResult<()> {
         tail_append!(self, self.get_node(RADICAL_NOAD_SIZE)?);
-        *type_mut(self.tail()) = RADICAL_NOAD;
-        *subtype_mut(self.tail()) = NORMAL;
-        *mem_mut(nucleus!(self.tail()) as usize) = self.empty_field;
-        *mem_mut(subscr!(self.tail()) as usize) = self.empty_field;
-        *mem_mut(supscr!(self.tail()) as usize) = self.empty_field;
+        *self.type_mut(self.tail()) = RADICAL_NOAD;
+        *self.subtype_mut(self.tail()) = NORMAL;
+        *self.mem_mut(nucleus!(self.tail()) as usize) = self.empty_field;
+        *self.mem_mut(subscr!(self.tail()) as usize) = self.empty_field;
+        *self.mem_mut(supscr!(self.tail()) as usize) = self.empty_field;
         self.scan_delimiter(left_delimiter!(self.tail()), true)?;
         self.scan_math(nucleus!(self.tail()))
     }
@@ -365,19 +352,20 @@ impl Global {
     // Section 1165
     pub(crate) fn math_ac(&mut self) -> TeXResult<()> {
         if self.cur_cmd == ACCENT {
-            return Err(TeXError::UseMathAccentInMathMode);
+            // Section 1166
+            self.error(TeXError::UseMathAccentInMathMode)?;
         }
         tail_append!(self, self.get_node(ACCENT_NOAD_SIZE)?);
-        *type_mut(self.tail()) = ACCENT_NOAD;
-        *subtype_mut(self.tail()) = NORMAL;
-        *mem_mut(nucleus!(self.tail()) as usize) = self.empty_field;
-        *mem_mut(subscr!(self.tail()) as usize) = self.empty_field;
-        *mem_mut(supscr!(self.tail()) as usize) = self.empty_field;
-        *math_type_mut(accent_chr!(self.tail())) = MATH_CHAR;
+        *self.type_mut(self.tail()) = ACCENT_NOAD;
+        *self.subtype_mut(self.tail()) = NORMAL;
+        *self.mem_mut(nucleus!(self.tail()) as usize) = self.empty_field;
+        *self.mem_mut(subscr!(self.tail()) as usize) = self.empty_field;
+        *self.mem_mut(supscr!(self.tail()) as usize) = self.empty_field;
+        *self.math_type_mut(accent_chr!(self.tail())) = MATH_CHAR;
         self.scan_fifteen_bit_int()?;
-        *character_mut(accent_chr!(self.tail())) = (self.cur_val % 256) as QuarterWord;
-        *fam_mut(accent_chr!(self.tail())) = if self.cur_val >= VAR_CODE && fam_in_range!() {
-            cur_fam() as QuarterWord
+        *self.character_mut(accent_chr!(self.tail())) = (self.cur_val % 256) as QuarterWord;
+        *self.fam_mut(accent_chr!(self.tail())) = if self.cur_val >= VAR_CODE && fam_in_range!(self) {
+            self.cur_fam() as QuarterWord
         }
         else {
             ((self.cur_val / 256) % 16) as QuarterWord
@@ -400,11 +388,11 @@ impl Global {
         self.unsave()?;
         let p = self.fin_mlist(NULL)?;
         match self.saved(-1) {
-            0 => *display_mlist_mut(self.tail()) = p,
-            1 => *text_mlist_mut(self.tail()) = p,
-            2 => *script_mlist_mut(self.tail()) = p,
+            0 => *self.display_mlist_mut(self.tail()) = p,
+            1 => *self.text_mlist_mut(self.tail()) = p,
+            2 => *self.script_mlist_mut(self.tail()) = p,
             _ /* 3 */ => {
-                *script_script_mlist_mut(self.tail()) = p;
+                *self.script_script_mlist_mut(self.tail()) = p;
                 self.save_ptr -= 1;
                 return Ok(());
             }
@@ -418,9 +406,9 @@ impl Global {
     pub(crate) fn sub_sup(&mut self) -> TeXResult<()> {
         let mut t = EMPTY;
         let mut p = NULL;
-        if self.tail() != self.head() && scripts_allowed!(self.tail()) {
+        if self.tail() != self.head() && scripts_allowed!(self, self.tail()) {
             p = supscr!(self.tail()) + (self.cur_cmd as HalfWord) - (SUP_MARK as HalfWord);
-            t = math_type(p);
+            t = self.math_type(p);
         }
 
         if p == NULL || t != EMPTY {
@@ -429,10 +417,10 @@ impl Global {
             p = supscr!(self.tail()) + (self.cur_cmd as HalfWord) - (SUP_MARK as HalfWord);
             if t != EMPTY {
                 if self.cur_cmd == SUP_MARK {
-                    return Err(TeXError::DoubleSuperscript);
+                    self.error(TeXError::DoubleSuperscript)?;
                 }
                 else {
-                    return Err(TeXError::DoubleSubscript);
+                    self.error(TeXError::DoubleSubscript)?;
                 }
             }
             // End section 1177
@@ -444,18 +432,26 @@ impl Global {
     pub(crate) fn math_fraction(&mut self) -> TeXResult<()> {
         let c = self.cur_chr;
         if self.incomplete_noad() != NULL {
-            Err(TeXError::AmbiguousFraction)
+            // Section 1183
+            if c >= DELIMITED_CODE {
+                self.scan_delimiter(self.garbage(), false)?;
+                self.scan_delimiter(self.garbage(), false)?;
+            }
+            if c % DELIMITED_CODE == ABOVE_CODE {
+                self.scan_dimen(false, false, false)?;
+            }
+            self.error(TeXError::AmbiguousFraction)
         }
         else {
             *self.incomplete_noad_mut() = self.get_node(FRACTION_NOAD_SIZE)?;
-            *type_mut(self.incomplete_noad()) = FRACTION_NOAD;
-            *subtype_mut(self.incomplete_noad()) = NORMAL;
-            *math_type_mut(numerator!(self.incomplete_noad())) = SUB_MLIST;
-            *info_mut(numerator!(self.incomplete_noad())) = link(self.head());
-            *mem_mut(denominator!(self.incomplete_noad()) as usize) = self.empty_field;
-            *mem_mut(left_delimiter!(self.incomplete_noad()) as usize) = self.null_delimiter;
-            *mem_mut(right_delimiter!(self.incomplete_noad()) as usize) = self.null_delimiter;
-            *link_mut(self.head()) = NULL;
+            *self.type_mut(self.incomplete_noad()) = FRACTION_NOAD;
+            *self.subtype_mut(self.incomplete_noad()) = NORMAL;
+            *self.math_type_mut(numerator!(self.incomplete_noad())) = SUB_MLIST;
+            *self.info_mut(numerator!(self.incomplete_noad())) = self.link(self.head());
+            *self.mem_mut(denominator!(self.incomplete_noad()) as usize) = self.empty_field;
+            *self.mem_mut(left_delimiter!(self.incomplete_noad()) as usize) = self.null_delimiter;
+            *self.mem_mut(right_delimiter!(self.incomplete_noad()) as usize) = self.null_delimiter;
+            *self.link_mut(self.head()) = NULL;
             *self.tail_mut() = self.head();
 
             // Section 1182
@@ -464,7 +460,7 @@ impl Global {
                 self.scan_delimiter(right_delimiter!(self.incomplete_noad()), false)?;
             }
 
-            *thickness_mut(self.incomplete_noad()) = match c % DELIMITED_CODE {
+            *self.thickness_mut(self.incomplete_noad()) = match c % DELIMITED_CODE {
                 ABOVE_CODE => {
                     self.scan_dimen(false, false, false)?;
                     self.cur_val
@@ -483,26 +479,26 @@ impl Global {
     pub(crate) fn fin_mlist(&mut self, p: HalfWord) -> TeXResult<HalfWord> {
         let q = if self.incomplete_noad() != NULL {
             // Section 1185
-            *math_type_mut(denominator!(self.incomplete_noad())) = SUB_MLIST;
-            *info_mut(denominator!(self.incomplete_noad())) = link(self.head());
+            *self.math_type_mut(denominator!(self.incomplete_noad())) = SUB_MLIST;
+            *self.info_mut(denominator!(self.incomplete_noad())) = self.link(self.head());
             if p == NULL {
                 self.incomplete_noad()
             }
             else {
-                let q = info(numerator!(self.incomplete_noad()));
-                if r#type(q) != LEFT_NOAD {
+                let q = self.info(numerator!(self.incomplete_noad()));
+                if self.r#type(q) != LEFT_NOAD {
                     return Err(TeXError::Confusion("right"));
                 }
-                *info_mut(numerator!(self.incomplete_noad())) = link(q);
-                *link_mut(q) = self.incomplete_noad();
-                *link_mut(self.incomplete_noad()) = p;
+                *self.info_mut(numerator!(self.incomplete_noad())) = self.link(q);
+                *self.link_mut(q) = self.incomplete_noad();
+                *self.link_mut(self.incomplete_noad()) = p;
                 q
             }
             // End section 1985
         }
         else {
-            *link_mut(self.tail()) = p;
-            link(self.head())
+            *self.link_mut(self.tail()) = p;
+            self.link(self.head())
         };
 
         self.pop_nest();
@@ -515,7 +511,8 @@ impl Global {
         if t == RIGHT_NOAD as HalfWord && self.cur_group != MATH_LEFT_GROUP {
             // Section 1192
             if self.cur_group == MATH_SHIFT_GROUP {
-                Err(TeXError::ExtraMathRight)
+                self.scan_delimiter(self.garbage(), false)?;
+                self.error(TeXError::ExtraMathRight)
             }
             else {
                 self.off_save()
@@ -524,20 +521,20 @@ impl Global {
         }
         else {
             let p = self.new_noad()?;
-            *type_mut(p) = t as QuarterWord;
+            *self.type_mut(p) = t as QuarterWord;
             self.scan_delimiter(delimiter!(p), false)?;
             if t == LEFT_NOAD as HalfWord {
                 self.push_math(MATH_LEFT_GROUP)?;
-                *link_mut(self.head()) = p;
+                *self.link_mut(self.head()) = p;
                 *self.tail_mut() = p;
             }
             else {
                 let p = self.fin_mlist(p)?;
                 self.unsave()?;
                 tail_append!(self, self.new_noad()?);
-                *type_mut(self.tail()) = INNER_NOAD;
-                *math_type_mut(nucleus!(self.tail())) = SUB_MLIST;
-                *info_mut(nucleus!(self.tail())) = p;
+                *self.type_mut(self.tail()) = INNER_NOAD;
+                *self.math_type_mut(nucleus!(self.tail())) = SUB_MLIST;
+                *self.info_mut(nucleus!(self.tail())) = p;
             }
             Ok(())
         }
@@ -545,58 +542,24 @@ impl Global {
 
     // Section 1194
     pub(crate) fn after_math(&mut self) -> TeXResult<()> {
-        // Section 1195
-        if self.font_params[fam_fnt(2 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-            || self.font_params[fam_fnt(2 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-            || self.font_params[fam_fnt(2 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-        {
-            return Err(TeXError::InsufficientSymbolFonts);
-        }
-
-        if self.font_params[fam_fnt(3 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-            || self.font_params[fam_fnt(3 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-            || self.font_params[fam_fnt(3 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-        {
-            return Err(TeXError::InsufficientExtensionFonts);
-        }
-        // End section 1195
-
+        let mut danger = self.sec1195_check_math_fonts()?;
         let mut m = self.mode();
         let mut l = false;
         let mut p = self.fin_mlist(NULL)?;
         let a = if self.mode() == -m {
-            // Section 1197
-            self.get_x_token()?;
-            if self.cur_cmd != MATH_SHIFT {
-                return Err(TeXError::DisplayMathEndsWithDollars);
-            }
-            // End section 1197
+            self.sec1197_check_that_another_dollar_follows()?;
 
             self.cur_mlist = p;
             self.cur_style = TEXT_STYLE;
             self.mlist_penalties = false;
             self.mlist_to_hlist()?;
-            let a = hpack!(self, link(TEMP_HEAD), NATURAL)?;
+            let a = hpack!(self, self.link(self.temp_head()), NATURAL)?;
             self.unsave()?;
             self.save_ptr -= 1;
             if self.saved(0) == 1 {
                 l = true;
             }
-            
-            // Section 1195
-            if self.font_params[fam_fnt(2 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-                || self.font_params[fam_fnt(2 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-                || self.font_params[fam_fnt(2 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
-            {
-                return Err(TeXError::InsufficientSymbolFonts);
-            }
-            if self.font_params[fam_fnt(3 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-                || self.font_params[fam_fnt(3 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-                || self.font_params[fam_fnt(3 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
-            {
-                return Err(TeXError::InsufficientExtensionFonts);
-            }
-            // End section 1195
+            danger = self.sec1195_check_math_fonts()?;
 
             m = self.mode();
             p = self.fin_mlist(NULL)?;
@@ -608,53 +571,81 @@ impl Global {
 
         if m < 0 {
             // Section 1196
-            tail_append!(self, self.new_math(math_surround(), BEFORE)?);
+            tail_append!(self, self.new_math(self.math_surround(), BEFORE)?);
             self.cur_mlist = p;
             self.cur_style = TEXT_STYLE;
             self.mlist_penalties = self.mode() > 0;
             self.mlist_to_hlist()?;
-            *link_mut(self.tail()) = link(TEMP_HEAD);
-            while link(self.tail()) != NULL {
-                *self.tail_mut() = link(self.tail());
+            *self.link_mut(self.tail()) = self.link(self.temp_head());
+            while self.link(self.tail()) != NULL {
+                *self.tail_mut() = self.link(self.tail());
             }
-            tail_append!(self, self.new_math(math_surround(), AFTER)?);
+            tail_append!(self, self.new_math(self.math_surround(), AFTER)?);
             *self.space_factor_mut() = 1000;
             self.unsave()
             // End section 1196
         }
         else {
             if a == NULL {
-                // Section 1197
-                self.get_x_token()?;
-                if self.cur_cmd != MATH_SHIFT {
-                    return Err(TeXError::DisplayMathEndsWithDollars);
-                }
-                // End section 1197
+                self.sec1197_check_that_another_dollar_follows()?;
             }
-            self.sec1199_finish_displayed_math(p, a, l)
+            self.sec1199_finish_displayed_math(p, a, l, danger)
+        }
+    }
+
+    // Section 1195
+    // Returns `danger`: true if the current math list has been flushed.
+    fn sec1195_check_math_fonts(&mut self) -> TeXResult<bool> {
+        if self.font_params[self.fam_fnt(2 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
+            || self.font_params[self.fam_fnt(2 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
+            || self.font_params[self.fam_fnt(2 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHSY_PARAMS
+        {
+            self.error(TeXError::InsufficientSymbolFonts)?;
+            self.flush_math()?;
+            Ok(true)
+        }
+        else if self.font_params[self.fam_fnt(3 + TEXT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
+            || self.font_params[self.fam_fnt(3 + SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
+            || self.font_params[self.fam_fnt(3 + SCRIPT_SCRIPT_SIZE as HalfWord) as usize] < TOTAL_MATHEX_PARAMS
+        {
+            self.error(TeXError::InsufficientExtensionFonts)?;
+            self.flush_math()?;
+            Ok(true)
+        }
+        else {
+            Ok(false)
         }
     }
 
+    // Section 1197
+    fn sec1197_check_that_another_dollar_follows(&mut self) -> TeXResult<()> {
+        self.get_x_token()?;
+        if self.cur_cmd != MATH_SHIFT {
+            self.back_error(TeXError::DisplayMathEndsWithDollars)?;
+        }
+        Ok(())
+    }
+
     // Section 1199
-    fn sec1199_finish_displayed_math(&mut self, mut p: HalfWord, a: HalfWord, l: bool) -> TeXResult<()> {
+    fn sec1199_finish_displayed_math(&mut self, mut p: HalfWord, a: HalfWord, l: bool, danger: bool) -> TeXResult<()> {
         self.cur_mlist = p;
         self.cur_style = DISPLAY_STYLE;
         self.mlist_penalties = false;
         self.mlist_to_hlist()?;
-        p = link(TEMP_HEAD);
-        self.adjust_tail = ADJUST_HEAD;
+        p = self.link(self.temp_head());
+        self.adjust_tail = self.adjust_head();
         let mut b = hpack!(self, p, NATURAL)?;
-        p = list_ptr(b);
+        p = self.list_ptr(b);
         let t = self.adjust_tail;
         self.adjust_tail = NULL;
-        let mut w = width(b);
-        let z = display_width();
-        let s = display_indent();
-        let (mut e, q) = if a == NULL {
+        let mut w = self.width(b);
+        let z = self.display_width();
+        let s = self.display_indent();
+        let (mut e, q) = if a == NULL || danger {
             (0, 0)
         }
         else {
-            let e = width(a);
+            let e = self.width(a);
             (e, e + self.math_quad(TEXT_SIZE))
         };
 
@@ -675,7 +666,7 @@ impl Global {
                     b = hpack!(self, p, z, EXACTLY)?;
                 }
             }
-            w = width(b);
+            w = self.width(b);
             // End section 1201
         }
         
@@ -683,15 +674,15 @@ impl Global {
         let mut d = half!(z - w);
         if e > 0 && d < 2*e {
             d = half!(z - w - e);
-            if p != NULL && !self.is_char_node(p) && r#type(p) == GLUE_NODE {
+            if p != NULL && !self.is_char_node(p) && self.r#type(p) == GLUE_NODE {
                 d = 0;
             }
         }
         // End section 1202
 
         // Section 1203
-        tail_append!(self, self.new_penalty(pre_display_penalty())?);
-        let (g1, mut g2) = if d + s <= pre_display_size() || l {
+        tail_append!(self, self.new_penalty(self.pre_display_penalty())?);
+        let (g1, mut g2) = if d + s <= self.pre_display_size() || l {
             (ABOVE_DISPLAY_SKIP_CODE, BELOW_DISPLAY_SKIP_CODE)
         }
         else {
@@ -699,7 +690,7 @@ impl Global {
         };
 
         if l && e == 0 {
-            *shift_amount_mut(a) = s;
+            *self.shift_amount_mut(a) = s;
             self.append_to_vlist(a)?;
             tail_append!(self, self.new_penalty(INF_PENALTY)?);
         }
@@ -712,33 +703,33 @@ impl Global {
         if e != 0 {
             let r = self.new_kern(z - w - e - d)?;
             if l {
-                *link_mut(a) = r;
-                *link_mut(r) = b;
+                *self.link_mut(a) = r;
+                *self.link_mut(r) = b;
                 b = a;
                 d = 0;
             }
             else {
-                *link_mut(b) = r;
-                *link_mut(r) = a;
+                *self.link_mut(b) = r;
+                *self.link_mut(r) = a;
             }
             b = hpack!(self, b, NATURAL)?;
         }
-        *shift_amount_mut(b) = s + d;
+        *self.shift_amount_mut(b) = s + d;
         self.append_to_vlist(b)?;
         // End section 1204
 
         // Section 1205
         if a != NULL && e == 0 && !l {
             tail_append!(self, self.new_penalty(INF_PENALTY)?);
-            *shift_amount_mut(a) = s + z - width(a);
+            *self.shift_amount_mut(a) = s + z - self.width(a);
             self.append_to_vlist(a)?;
             g2 = 0;
         }
-        if t != ADJUST_HEAD {
-            *link_mut(self.tail()) = link(ADJUST_HEAD);
+        if t != self.adjust_head() {
+            *self.link_mut(self.tail()) = self.link(self.adjust_head());
             *self.tail_mut() = t;
         }
-        tail_append!(self, self.new_penalty(post_display_penalty())?);
+        tail_append!(self, self.new_penalty(self.post_display_penalty())?);
         if g2 > 0 {
             tail_append!(self, self.new_param_glue(g2 as SmallNumber)?);
         }
@@ -759,7 +750,7 @@ impl Global {
         *self.space_factor_mut() = 1000;
         self.set_cur_lang();
         *self.clang_mut() = self.cur_lang as HalfWord;
-        *self.prev_graf_mut() = (norm_min(left_hyphen_min())*64 + norm_min(right_hyphen_min()))*65536 + self.cur_lang as Integer;
+        *self.prev_graf_mut() = (norm_min(self.left_hyphen_min())*64 + norm_min(self.right_hyphen_min()))*65536 + self.cur_lang as Integer;
         sec443_scan_an_optional_space!(self);
         if self.nest_ptr == 1 {
             self.build_page()?;
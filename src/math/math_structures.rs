@@ -1,10 +1,6 @@
 use crate::constants::{
     CHOICE_NODE, NOAD_SIZE, NORMAL, NULL, ORD_NOAD, STYLE_NODE, STYLE_NODE_SIZE
 };
-use crate::datastructures::{
-    mem, mem_mut, depth_mut, font, font_mut, info, info_mut, link, link_mut,
-    subtype_mut, type_mut, width, width_mut
-};
 use crate::error::TeXResult;
 use crate::{
     Global, HalfWord, QuarterWord, Scaled, SmallNumber
@@ -34,21 +30,23 @@ macro_rules! subscr {
     };
 }
 
-// Section 681
-pub(crate) fn math_type(p: HalfWord) -> HalfWord {
-    link(p)
-}
+impl Global {
+    // Section 681
+    pub(crate) fn math_type(&self, p: HalfWord) -> HalfWord {
+        self.link(p)
+    }
 
-pub(crate) fn math_type_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p)
-}
+    pub(crate) fn math_type_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p)
+    }
 
-pub(crate) fn fam(p: HalfWord) -> QuarterWord {
-    font(p)
-}
+    pub(crate) fn fam(&self, p: HalfWord) -> QuarterWord {
+        self.font(p)
+    }
 
-pub(crate) fn fam_mut(p: HalfWord) -> &'static mut QuarterWord {
-    font_mut(p)
+    pub(crate) fn fam_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.font_mut(p)
+    }
 }
 
 // Section 683
@@ -80,45 +78,47 @@ macro_rules! denominator {
     };
 }
 
-// Section 683
-pub(crate) fn small_fam(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b0()
-}
+impl Global {
+    // Section 683
+    pub(crate) fn small_fam(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b0()
+    }
 
-pub(crate) fn small_fam_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b0_mut()
-}
+    pub(crate) fn small_fam_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b0_mut()
+    }
 
-pub(crate) fn small_char(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b1()
-}
+    pub(crate) fn small_char(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b1()
+    }
 
-pub(crate) fn small_char_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b1_mut()
-}
+    pub(crate) fn small_char_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b1_mut()
+    }
 
-pub(crate) fn large_fam(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b2()
-}
+    pub(crate) fn large_fam(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b2()
+    }
 
-pub(crate) fn large_fam_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b2_mut()
-}
+    pub(crate) fn large_fam_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b2_mut()
+    }
 
-pub(crate) fn large_char(p: HalfWord) -> QuarterWord {
-    mem(p as usize).qqqq_b3()
-}
+    pub(crate) fn large_char(&self, p: HalfWord) -> QuarterWord {
+        self.mem(p as usize).qqqq_b3()
+    }
 
-pub(crate) fn large_char_mut(p: HalfWord) -> &'static mut QuarterWord {
-    mem_mut(p as usize).qqqq_b3_mut()
-}
+    pub(crate) fn large_char_mut(&mut self, p: HalfWord) -> &mut QuarterWord {
+        self.mem_mut(p as usize).qqqq_b3_mut()
+    }
 
-pub(crate) fn thickness(p: HalfWord) -> Scaled {
-    width(p)
-}
+    pub(crate) fn thickness(&self, p: HalfWord) -> Scaled {
+        self.width(p)
+    }
 
-pub(crate) fn thickness_mut(p: HalfWord) -> &'static mut Scaled {
-    width_mut(p)
+    pub(crate) fn thickness_mut(&mut self, p: HalfWord) -> &mut Scaled {
+        self.width_mut(p)
+    }
 }
 
 // Section 687
@@ -138,75 +138,77 @@ macro_rules! delimiter {
 
 #[macro_export]
 macro_rules! scripts_allowed {
-    ($p:expr) => {
-        r#type($p) >= ORD_NOAD && r#type($p) < LEFT_NOAD
+    ($s:ident, $p:expr) => {
+        $s.r#type($p) >= ORD_NOAD && $s.r#type($p) < LEFT_NOAD
     };
 }
 
-// Section 689
-pub(crate) fn display_mlist(p: HalfWord) -> HalfWord {
-    info(p + 1)
-}
+impl Global {
+    // Section 689
+    pub(crate) fn display_mlist(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 1)
+    }
 
-pub(crate) fn display_mlist_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 1)
-}
+    pub(crate) fn display_mlist_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 1)
+    }
 
-pub(crate) fn text_mlist(p: HalfWord) -> HalfWord {
-    link(p + 1)
-}
+    pub(crate) fn text_mlist(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 1)
+    }
 
-pub(crate) fn text_mlist_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 1)
-}
+    pub(crate) fn text_mlist_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 1)
+    }
 
-pub(crate) fn script_mlist(p: HalfWord) -> HalfWord {
-    info(p + 2)
-}
+    pub(crate) fn script_mlist(&self, p: HalfWord) -> HalfWord {
+        self.info(p + 2)
+    }
 
-pub(crate) fn script_mlist_mut(p: HalfWord) -> &'static mut HalfWord {
-    info_mut(p + 2)
-}
+    pub(crate) fn script_mlist_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.info_mut(p + 2)
+    }
 
-pub(crate) fn script_script_mlist(p: HalfWord) -> HalfWord {
-    link(p + 2)
-}
+    pub(crate) fn script_script_mlist(&self, p: HalfWord) -> HalfWord {
+        self.link(p + 2)
+    }
 
-pub(crate) fn script_script_mlist_mut(p: HalfWord) -> &'static mut HalfWord {
-    link_mut(p + 2)
+    pub(crate) fn script_script_mlist_mut(&mut self, p: HalfWord) -> &mut HalfWord {
+        self.link_mut(p + 2)
+    }
 }
 
 impl Global {
     // Section 686
     pub(crate) fn new_noad(&mut self) -> TeXResult<HalfWord> {
         let p = self.get_node(NOAD_SIZE)?;
-        *type_mut(p) = ORD_NOAD;
-        *subtype_mut(p) = NORMAL;
-        *mem_mut(nucleus!(p) as usize) = self.empty_field;
-        *mem_mut(subscr!(p) as usize) = self.empty_field;
-        *mem_mut(supscr!(p) as usize) = self.empty_field;
+        *self.type_mut(p) = ORD_NOAD;
+        *self.subtype_mut(p) = NORMAL;
+        *self.mem_mut(nucleus!(p) as usize) = self.empty_field;
+        *self.mem_mut(subscr!(p) as usize) = self.empty_field;
+        *self.mem_mut(supscr!(p) as usize) = self.empty_field;
         Ok(p)
     }
 
     // Section 688
     pub(crate) fn new_style(&mut self, s: SmallNumber) -> TeXResult<HalfWord> {
         let p = self.get_node(STYLE_NODE_SIZE)?;
-        *type_mut(p) = STYLE_NODE;
-        *subtype_mut(p) = s;
-        *width_mut(p) = 0;
-        *depth_mut(p) = 0;
+        *self.type_mut(p) = STYLE_NODE;
+        *self.subtype_mut(p) = s;
+        *self.width_mut(p) = 0;
+        *self.depth_mut(p) = 0;
         Ok(p)
     }
 
     // Section 689
     pub(crate) fn new_choice(&mut self) -> TeXResult<HalfWord> {
         let p = self.get_node(STYLE_NODE_SIZE)?;
-        *type_mut(p) = CHOICE_NODE;
-        *subtype_mut(p) = 0;
-        *display_mlist_mut(p) = NULL;
-        *text_mlist_mut(p) = NULL;
-        *script_mlist_mut(p) = NULL;
-        *script_script_mlist_mut(p) = NULL;
+        *self.type_mut(p) = CHOICE_NODE;
+        *self.subtype_mut(p) = 0;
+        *self.display_mlist_mut(p) = NULL;
+        *self.text_mlist_mut(p) = NULL;
+        *self.script_mlist_mut(p) = NULL;
+        *self.script_script_mlist_mut(p) = NULL;
         Ok(p)
     }
 }
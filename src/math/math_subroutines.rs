@@ -1,16 +1,6 @@
-use crate::arithmetic::{mult_and_add, x_over_n, xn_over_d};
 use crate::constants::*;
-use crate::datastructures::{
-    MemoryWord, character, character_mut, depth, depth_mut, fam_fnt, font,
-    font_mut, height, height_mut, link, link_mut, list_ptr, list_ptr_mut,
-    null_delimiter_space, r#type, shift_amount_mut, shrink, shrink_mut,
-    shrink_order, shrink_order_mut, stretch, stretch_mut, stretch_order,
-    stretch_order_mut, subtype, subtype_mut, type_mut, width, width_mut
-};
+use crate::datastructures::MemoryWord;
 use crate::error::TeXResult;
-use crate::math::math_structures::{
-    large_char, large_fam, small_char, small_fam
-};
 use crate::{
     Global, HalfWord, QuarterWord, Scaled, SmallNumber,
     half, hpack, nx_plus_y, odd, vpack
@@ -22,7 +12,7 @@ use crate::{
 macro_rules! section_700_mathsy {
     ($fn_name:ident, $p:expr) => {
         pub(crate) fn $fn_name(&self, p: QuarterWord) -> Scaled {
-            self.font_info[$p + (self.param_base[fam_fnt(2 + p as HalfWord) as usize] as usize)].sc()
+            self.font_info[($p + self.param_base[self.fam_fnt(2 + p as HalfWord) as usize]) as usize].sc()
         }
     };
 }
@@ -31,7 +21,7 @@ macro_rules! section_700_mathsy {
 macro_rules! section_701_mathex {
     ($fn_name:ident, $p:expr) => {
         pub(crate) fn $fn_name(&self) -> Scaled {
-            self.font_info[$p + (self.param_base[fam_fnt(3 + self.cur_size as HalfWord) as usize] as usize)].sc()
+            self.font_info[($p + self.param_base[self.fam_fnt(3 + self.cur_size as HalfWord) as usize]) as usize].sc()
         }
     };
 }
@@ -83,7 +73,7 @@ macro_rules! sec703_set_up_values {
             16*(($s.cur_style - TEXT_STYLE) / 2)
         };
 
-        $s.cur_mu = x_over_n($s.math_quad($s.cur_size), 18)?.0;
+        $s.cur_mu = $s.x_over_n($s.math_quad($s.cur_size), 18).0;
     };
 }
 
@@ -118,19 +108,19 @@ impl Global {
     // Section 704
     pub(crate) fn fraction_rule(&mut self, t: Scaled) -> TeXResult<HalfWord> {
         let p = self.new_rule()?;
-        *height_mut(p) = t;
-        *depth_mut(p) = 0;
+        *self.height_mut(p) = t;
+        *self.depth_mut(p) = 0;
         Ok(p)
     }
 
     // Section 705
     pub(crate) fn overbar(&mut self, b: HalfWord, k: Scaled, t: Scaled) -> TeXResult<HalfWord> {
         let mut p = self.new_kern(k)?;
-        *link_mut(p) = b;
+        *self.link_mut(p) = b;
         let q = self.fraction_rule(t)?;
-        *link_mut(q) = p;
+        *self.link_mut(q) = p;
         p = self.new_kern(t)?;
-        *link_mut(p) = q;
+        *self.link_mut(p) = q;
         vpack!(self, p, NATURAL)
     }
 
@@ -139,8 +129,8 @@ impl Global {
         let mut f = NULL_FONT as QuarterWord;
         let mut w = 0;
         let mut large_attempt = false;
-        let mut z = small_fam(d);
-        let mut x = small_char(d);
+        let mut z = self.small_fam(d);
+        let mut x = self.small_char(d);
 
         let mut c = 0;
         let mut q = MemoryWord::ZERO;
@@ -150,7 +140,7 @@ impl Global {
                 z += s + 16;
                 'sec707: loop {
                     z -= 16;
-                    let g = fam_fnt(z as HalfWord);
+                    let g = self.fam_fnt(z as HalfWord);
                     if g != NULL_FONT {
                         // Section 708
                         let mut y = x;
@@ -197,8 +187,8 @@ impl Global {
                 break 'main_loop; // Goto done
             }
             large_attempt = true;
-            z = large_fam(d);
-            x = large_char(d);
+            z = self.large_fam(d);
+            x = self.large_char(d);
         }
 
         // found:
@@ -207,7 +197,7 @@ impl Global {
             if q.char_tag() == EXT_TAG {
                 // Section 713
                 let b = self.new_null_box()?;
-                *type_mut(b) = VLIST_NODE;
+                *self.type_mut(b) = VLIST_NODE;
                 let r = self.font_info[(self.exten_base[f as usize] as usize) + (q.rem_byte() as usize)];
 
                 // Section 714
@@ -215,7 +205,7 @@ impl Global {
                 let u = self.height_plus_depth(f, c);
                 w = 0;
                 q = self.char_info(f, c);
-                *width_mut(b) = self.char_width(f, q) + self.char_italic(f, q);
+                *self.width_mut(b) = self.char_width(f, q) + self.char_italic(f, q);
                 c = r.ext_bot();
                 if c != MIN_QUARTERWORD {
                     w += self.height_plus_depth(f, c);
@@ -260,7 +250,7 @@ impl Global {
                 if c != MIN_QUARTERWORD {
                     self.stack_into_box(b, f, c)?;
                 }
-                *depth_mut(b) = w - height(b);
+                *self.depth_mut(b) = w - self.height(b);
                 // End section 713
                 b
             }
@@ -271,11 +261,11 @@ impl Global {
         }
         else {
             let b = self.new_null_box()?;
-            *width_mut(b) = null_delimiter_space();
+            *self.width_mut(b) = self.null_delimiter_space();
             b
         };
 
-        *shift_amount_mut(b) = half!(height(b) - depth(b)) - self.axis_height(s);
+        *self.shift_amount_mut(b) = half!(self.height(b) - self.depth(b)) - self.axis_height(s);
         Ok(b)
     }
 
@@ -284,22 +274,22 @@ impl Global {
         let q = self.char_info(f, c);
         let hd = q.height_depth();
         let b = self.new_null_box()?;
-        *width_mut(b) = self.char_width(f, q) + self.char_italic(f, q);
-        *height_mut(b) = self.char_height(f, hd);
-        *depth_mut(b) = self.char_depth(f, hd);
+        *self.width_mut(b) = self.char_width(f, q) + self.char_italic(f, q);
+        *self.height_mut(b) = self.char_height(f, hd);
+        *self.depth_mut(b) = self.char_depth(f, hd);
         let p = self.get_avail()?;
-        *character_mut(p) = c;
-        *font_mut(p) = f;
-        *list_ptr_mut(b) = p;
+        *self.character_mut(p) = c;
+        *self.font_mut(p) = f;
+        *self.list_ptr_mut(b) = p;
         Ok(b)
     }
 
     // Section 711
     fn stack_into_box(&mut self, b: HalfWord, f: QuarterWord, c: QuarterWord) -> TeXResult<()> {
         let p = self.char_box(f, c)?;
-        *link_mut(p) = list_ptr(b);
-        *list_ptr_mut(b) = p;
-        *height_mut(b) = height(p);
+        *self.link_mut(p) = self.list_ptr(b);
+        *self.list_ptr_mut(b) = p;
+        *self.height_mut(b) = self.height(p);
         Ok(())
     }
 
@@ -312,40 +302,43 @@ impl Global {
 
     // Section 715
     pub(crate) fn rebox(&mut self, mut b: HalfWord, w: Scaled) -> TeXResult<HalfWord> {
-        if width(b) != w && list_ptr(b) != NULL {
-            if r#type(b) == VLIST_NODE {
+        if self.width(b) != w && self.list_ptr(b) != NULL {
+            if self.r#type(b) == VLIST_NODE {
                 b = hpack!(self, b, NATURAL)?;
             }
-            let mut p = list_ptr(b);
-            if self.is_char_node(p) && link(p) == NULL {
-                let f = font(p);
-                let v = self.char_width(f, self.char_info(f, character(p)));
-                if v != width(b) {
-                    *link_mut(p) = self.new_kern(width(b) - v)?;
+            let mut p = self.list_ptr(b);
+            if self.is_char_node(p) && self.link(p) == NULL {
+                let f = self.font(p);
+                let v = self.char_width(f, self.char_info(f, self.character(p)));
+                if v != self.width(b) {
+                    *self.link_mut(p) = self.new_kern(self.width(b) - v)?;
                 }
             }
             self.free_node(b, BOX_NODE_SIZE);
             b = self.new_glue(SS_GLUE)?;
-            *link_mut(b) = p;
-            while link(p) != NULL {
-                p = link(p);
+            *self.link_mut(b) = p;
+            while self.link(p) != NULL {
+                p = self.link(p);
             }
-            *link_mut(p) = self.new_glue(SS_GLUE)?;
+            *self.link_mut(p) = self.new_glue(SS_GLUE)?;
             hpack!(self, b, w, EXACTLY)
         }
         else {
-            *width_mut(b) = w;
+            *self.width_mut(b) = w;
             Ok(b)
         }
     }
 
     // Section 716
     pub(crate) fn math_glue(&mut self, g: HalfWord, m: Scaled) -> TeXResult<HalfWord> {
-        let (mut n, mut f) = x_over_n(m, 65536)?;
+        let (mut n, mut f) = self.x_over_n(m, 65536);
 
         macro_rules! mu_mult {
             ($x:expr) => {
-                nx_plus_y!(n, $x, xn_over_d($x, f, 65536)?.0)
+                {
+                    let quo = self.xn_over_d($x, f, 65536).0;
+                    nx_plus_y!(self, n, $x, quo)
+                }
             };
         }
 
@@ -354,35 +347,50 @@ impl Global {
             f += 65536;
         }
         let p = self.get_node(GLUE_SPEC_SIZE)?;
-        *width_mut(p) = mu_mult!(width(g));
-        *stretch_order_mut(p) = stretch_order(g);
+        *self.width_mut(p) = mu_mult!(self.width(g));
+        *self.stretch_order_mut(p) = self.stretch_order(g);
 
-        *stretch_mut(p) = match stretch_order(p) {
-            NORMAL => mu_mult!(stretch(g)),
-            _ => stretch(g),
+        *self.stretch_mut(p) = match self.stretch_order(p) {
+            NORMAL => mu_mult!(self.stretch(g)),
+            _ => self.stretch(g),
         };
 
-        *shrink_order_mut(p) = shrink_order(g);
+        *self.shrink_order_mut(p) = self.shrink_order(g);
         
-        *shrink_mut(p) = match shrink_order(p) {
-            NORMAL => mu_mult!(shrink(g)),
-            _ => shrink(g)
+        *self.shrink_mut(p) = match self.shrink_order(p) {
+            NORMAL => mu_mult!(self.shrink(g)),
+            _ => self.shrink(g)
         };
 
         Ok(p)
     }
 }
 
-// Section 717
-pub(crate) fn math_kern(p: HalfWord, m: Scaled) -> TeXResult<()> {
-    if subtype(p) == MU_GLUE {
-        let (mut n, mut f) = x_over_n(m, 65536)?;
-        if f < 0 {
-            n -= 1;
-            f += 65536;
+impl Global {
+    // Section 717
+    pub(crate) fn math_kern(&mut self, p: HalfWord, m: Scaled) -> TeXResult<()> {
+        if self.subtype(p) == MU_GLUE {
+            let (mut n, mut f) = self.x_over_n(m, 65536);
+            if f < 0 {
+                n -= 1;
+                f += 65536;
+            }
+            let quo = self.xn_over_d(self.width(p), f, 65536).0;
+            *self.width_mut(p) = nx_plus_y!(self, n, self.width(p), quo);
+            *self.subtype_mut(p) = EXPLICIT;
         }
-        *width_mut(p) = nx_plus_y!(n, width(p), xn_over_d(width(p), f, 65536)?.0);
-        *subtype_mut(p) = EXPLICIT;
+        Ok(())
+    }
+}
+
+impl Global {
+    // Section 718
+    pub(crate) fn flush_math(&mut self) -> TeXResult<()> {
+        self.flush_node_list(self.link(self.head()))?;
+        self.flush_node_list(self.incomplete_noad())?;
+        *self.link_mut(self.head()) = NULL;
+        *self.tail_mut() = self.head();
+        *self.incomplete_noad_mut() = NULL;
+        Ok(())
     }
-    Ok(())
 }
@@ -1,19 +1,5 @@
-use crate::arithmetic::x_over_n;
 use crate::constants::*;
-use crate::datastructures::{
-    mem, mem_mut, bin_op_penalty, character, character_mut, delimiter_factor,
-    delimiter_shortfall, depth, depth_mut, fam_fnt, glue_par, glue_ptr,
-    glue_ptr_mut, glue_ref_count_mut, height, height_mut, info, info_mut, link, link_mut, list_ptr, list_ptr_mut, rel_penalty, script_space, shift_amount,
-    shift_amount_mut, subtype, subtype_mut, r#type, type_mut, width, width_mut
-};
 use crate::error::{TeXError, TeXResult};
-use crate::math::{
-    display_mlist, display_mlist_mut, fam, fam_mut, math_type, math_type_mut,
-    script_mlist, script_mlist_mut, script_script_mlist,
-    script_script_mlist_mut, text_mlist, text_mlist_mut, thickness,
-    thickness_mut,
-    math_subroutines::math_kern,
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Scaled, SmallNumber, accent_chr,
     cramped_style, delimiter, denom_style, denominator, half, hpack,
@@ -28,15 +14,15 @@ impl Global {
     // Section 720
     fn clean_box(&mut self, p: HalfWord, s: SmallNumber) -> TeXResult<HalfWord> {
         let mut q = 'block: {
-            match math_type(p) {
+            match self.math_type(p) {
                 MATH_CHAR => {
                     self.cur_mlist = self.new_noad()?;
-                    *mem_mut(nucleus!(self.cur_mlist) as usize) = mem(p as usize);
+                    *self.mem_mut(nucleus!(self.cur_mlist) as usize) = self.mem(p as usize);
                 },
 
-                SUB_BOX => break 'block info(p), // Goto found
+                SUB_BOX => break 'block self.info(p), // Goto found
 
-                SUB_MLIST => self.cur_mlist = info(p),
+                SUB_MLIST => self.cur_mlist = self.info(p),
 
                 _ => break 'block self.new_null_box()?, // Goto found
             }
@@ -45,7 +31,7 @@ impl Global {
             self.cur_style = s;
             self.mlist_penalties = false;
             self.mlist_to_hlist()?;
-            let q = link(TEMP_HEAD);
+            let q = self.link(self.temp_head());
             self.cur_style = save_style;
             sec703_set_up_values!(self);
             q
@@ -55,7 +41,7 @@ impl Global {
         let x = if self.is_char_node(q) || q == NULL {
             hpack!(self, q, NATURAL)?
         }
-        else if link(q) == NULL && r#type(q) <= VLIST_NODE && shift_amount(q) == 0 {
+        else if self.link(q) == NULL && self.r#type(q) <= VLIST_NODE && self.shift_amount(q) == 0 {
             q
         }
         else {
@@ -63,12 +49,12 @@ impl Global {
         };
         
         // Section 721
-        q = list_ptr(x);
+        q = self.list_ptr(x);
         if self.is_char_node(q) {
-            let r = link(q);
-            if r != NULL && link(r) == NULL && !self.is_char_node(r) && r#type(r) == KERN_NODE {
+            let r = self.link(q);
+            if r != NULL && self.link(r) == NULL && !self.is_char_node(r) && self.r#type(r) == KERN_NODE {
                 self.free_node(r, SMALL_NODE_SIZE);
-                *link_mut(q) = NULL;
+                *self.link_mut(q) = NULL;
             }
         }
         // End section 721
@@ -77,10 +63,14 @@ impl Global {
 
     // Section 722
     fn fetch(&mut self, a: HalfWord) -> TeXResult<()> {
-        self.cur_c = character(a);
-        self.cur_f = fam_fnt((fam(a) + self.cur_size) as HalfWord) as QuarterWord;
+        self.cur_c = self.character(a);
+        self.cur_f = self.fam_fnt((self.fam(a) + self.cur_size) as HalfWord) as QuarterWord;
         if self.cur_f == (NULL_FONT as QuarterWord) {
-            return Err(TeXError::UndefinedCharacter(a));
+            // Section 723
+            self.error(TeXError::UndefinedCharacter(a))?;
+            self.cur_i = self.null_character;
+            *self.math_type_mut(a) = EMPTY;
+            return Ok(());
         }
         self.cur_i = if self.cur_c >= (self.font_bc[self.cur_f as usize] as QuarterWord)
             && self.cur_c <= (self.font_ec[self.cur_f as usize] as QuarterWord)
@@ -93,20 +83,22 @@ impl Global {
 
         if !self.cur_i.char_exists() {
             self.char_warning(self.cur_f, self.cur_c as u8);
-            *math_type_mut(a) = EMPTY;
+            *self.math_type_mut(a) = EMPTY;
             self.cur_i = self.null_character;
         }
         Ok(())
     }
 }
 
-// Section 725
-fn new_hlist(p: HalfWord) -> Integer {
-    mem(nucleus!(p) as usize).int()
-}
+impl Global {
+    // Section 725
+    fn new_hlist(&self, p: HalfWord) -> Integer {
+        self.mem(nucleus!(p) as usize).int()
+    }
 
-fn new_hlist_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut(nucleus!(p) as usize).int_mut()
+    fn new_hlist_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut(nucleus!(p) as usize).int_mut()
+    }
 }
 
 enum Sec727Goto {
@@ -133,7 +125,7 @@ impl Global {
             // Section 728
             let goto = 'reswitch: loop {
                 let mut delta = 0;
-                match r#type(q) {
+                match self.r#type(q) {
                     BIN_NOAD => {
                         match r_type {
                             BIN_NOAD
@@ -142,7 +134,7 @@ impl Global {
                             | OPEN_NOAD
                             | PUNCT_NOAD
                             | LEFT_NOAD => {
-                                *type_mut(q) = ORD_NOAD;
+                                *self.type_mut(q) = ORD_NOAD;
                                 continue 'reswitch;
                             },
                             _ => () // Do nothing
@@ -155,11 +147,11 @@ impl Global {
                     | RIGHT_NOAD => {
                         // Section 729
                         if r_type == BIN_NOAD {
-                            *type_mut(r) = ORD_NOAD;
+                            *self.type_mut(r) = ORD_NOAD;
                         }
                         // End section 729
 
-                        if r#type(q) == RIGHT_NOAD {
+                        if self.r#type(q) == RIGHT_NOAD {
                             break 'reswitch Sec727Goto::DoneWithNoad;
                         }
                     },
@@ -174,7 +166,7 @@ impl Global {
 
                     OP_NOAD => {
                         delta = self.make_op(q)?;
-                        if subtype(q) == LIMITS {
+                        if self.subtype(q) == LIMITS {
                             break 'reswitch Sec727Goto::CheckDimen;
                         }
                     },
@@ -197,7 +189,7 @@ impl Global {
                     
                     // Section 730
                     STYLE_NODE => {
-                        self.cur_style = subtype(q);
+                        self.cur_style = self.subtype(q);
                         sec703_set_up_values!(self);
                         break 'reswitch Sec727Goto::DoneWithNode;
                     },
@@ -207,41 +199,41 @@ impl Global {
                         let mut p: HalfWord;
                         match self.cur_style / 2 {
                             0 => {
-                                p = display_mlist(q);
-                                *display_mlist_mut(q) = NULL;
+                                p = self.display_mlist(q);
+                                *self.display_mlist_mut(q) = NULL;
                             },
 
                             1 => {
-                                p = text_mlist(q);
-                                *text_mlist_mut(q) = NULL;
+                                p = self.text_mlist(q);
+                                *self.text_mlist_mut(q) = NULL;
                             },
 
                             2 => {
-                                p = script_mlist(q);
-                                *script_mlist_mut(q) = NULL;
+                                p = self.script_mlist(q);
+                                *self.script_mlist_mut(q) = NULL;
                             },
 
                             _ /* 3 */ => {
-                                p = script_script_mlist(q);
-                                *script_script_mlist_mut(q) = NULL;
+                                p = self.script_script_mlist(q);
+                                *self.script_script_mlist_mut(q) = NULL;
                             }
                         }
 
-                        self.flush_node_list(display_mlist(q))?;
-                        self.flush_node_list(text_mlist(q))?;
-                        self.flush_node_list(script_mlist(q))?;
-                        self.flush_node_list(script_script_mlist(q))?;
-                        *type_mut(q) = STYLE_NODE;
-                        *subtype_mut(q) = self.cur_style;
-                        *width_mut(q) = 0;
-                        *depth_mut(q) = 0;
+                        self.flush_node_list(self.display_mlist(q))?;
+                        self.flush_node_list(self.text_mlist(q))?;
+                        self.flush_node_list(self.script_mlist(q))?;
+                        self.flush_node_list(self.script_script_mlist(q))?;
+                        *self.type_mut(q) = STYLE_NODE;
+                        *self.subtype_mut(q) = self.cur_style;
+                        *self.width_mut(q) = 0;
+                        *self.depth_mut(q) = 0;
                         if p != NULL {
-                            let z = link(q);
-                            *link_mut(q) = p;
-                            while link(p) != NULL {
-                                p = link(p);
+                            let z = self.link(q);
+                            *self.link_mut(q) = p;
+                            while self.link(p) != NULL {
+                                p = self.link(p);
                             }
-                            *link_mut(p) = z;
+                            *self.link_mut(p) = z;
                         }
                         break 'reswitch Sec727Goto::DoneWithNode;
                         // End section 731
@@ -255,29 +247,29 @@ impl Global {
                     | DISC_NODE => break 'reswitch Sec727Goto::DoneWithNode,
 
                     RULE_NODE => {
-                        if height(q) > max_h {
-                            max_h = height(q);
+                        if self.height(q) > max_h {
+                            max_h = self.height(q);
                         }
-                        if depth(q) > max_d {
-                            max_d = depth(q);
+                        if self.depth(q) > max_d {
+                            max_d = self.depth(q);
                         }
                         break 'reswitch Sec727Goto::DoneWithNode;
                     },
 
                     GLUE_NODE => {
                         // Section 732
-                        if subtype(q) == MU_GLUE {
-                            let x = glue_ptr(q);
+                        if self.subtype(q) == MU_GLUE {
+                            let x = self.glue_ptr(q);
                             let y = self.math_glue(x, self.cur_mu)?;
                             self.delete_glue_ref(x);
-                            *glue_ptr_mut(q) = y;
-                            *subtype_mut(q) = NORMAL;
+                            *self.glue_ptr_mut(q) = y;
+                            *self.subtype_mut(q) = NORMAL;
                         }
-                        else if self.cur_size != TEXT_SIZE && subtype(q) == COND_MATH_GLUE {
-                            let p = link(q);
-                            if p != NULL && (r#type(p) == GLUE_NODE || r#type(p) == KERN_NODE) {
-                                *link_mut(q) = link(p);
-                                *link_mut(p) = NULL;
+                        else if self.cur_size != TEXT_SIZE && self.subtype(q) == COND_MATH_GLUE {
+                            let p = self.link(q);
+                            if p != NULL && (self.r#type(p) == GLUE_NODE || self.r#type(p) == KERN_NODE) {
+                                *self.link_mut(q) = self.link(p);
+                                *self.link_mut(p) = NULL;
                                 self.flush_node_list(p)?;
                             }
                         }
@@ -286,7 +278,7 @@ impl Global {
                     },
 
                     KERN_NODE => {
-                        math_kern(q, self.cur_mu)?;
+                        self.math_kern(q, self.cur_mu)?;
                         break 'reswitch Sec727Goto::DoneWithNode;
                     },
                     // End section 730
@@ -295,7 +287,7 @@ impl Global {
                 }
 
                 // Section 754
-                let p = match math_type(nucleus!(q)) {
+                let p = match self.math_type(nucleus!(q)) {
                     MATH_CHAR
                     | MATH_TEXT_CHAR => {
                         // Section 755
@@ -303,11 +295,11 @@ impl Global {
                         if self.cur_i.char_exists() {
                             delta = self.char_italic(self.cur_f, self.cur_i);
                             let p = self.new_character(self.cur_f, self.cur_c as u8)?;
-                            if math_type(nucleus!(q)) == MATH_TEXT_CHAR && self.space(self.cur_f) != 0 {
+                            if self.math_type(nucleus!(q)) == MATH_TEXT_CHAR && self.space(self.cur_f) != 0 {
                                 delta = 0
                             }
-                            if math_type(subscr!(q)) == EMPTY && delta != 0 {
-                                *link_mut(p) = self.new_kern(delta)?;
+                            if self.math_type(subscr!(q)) == EMPTY && delta != 0 {
+                                *self.link_mut(p) = self.new_kern(delta)?;
                                 delta = 0
                             }
                             p
@@ -320,23 +312,23 @@ impl Global {
 
                     EMPTY => NULL,
 
-                    SUB_BOX => info(nucleus!(q)),
+                    SUB_BOX => self.info(nucleus!(q)),
 
                     SUB_MLIST => {
-                        self.cur_mlist = info(nucleus!(q));
+                        self.cur_mlist = self.info(nucleus!(q));
                         let save_style = self.cur_style;
                         self.mlist_penalties = false;
                         self.mlist_to_hlist()?;
                         self.cur_style = save_style;
                         sec703_set_up_values!(self);
-                        hpack!(self, link(TEMP_HEAD), NATURAL)?
+                        hpack!(self, self.link(self.temp_head()), NATURAL)?
                     },
 
                     _ => return Err(TeXError::Confusion("mlist2"))
                 };
 
-                *new_hlist_mut(q) = p;
-                if math_type(subscr!(q)) == EMPTY && math_type(supscr!(q)) == EMPTY {
+                *self.new_hlist_mut(q) = p;
+                if self.math_type(subscr!(q)) == EMPTY && self.math_type(supscr!(q)) == EMPTY {
                     break 'reswitch Sec727Goto::CheckDimen;
                 }
                 self.make_scripts(q, delta)?;
@@ -347,12 +339,12 @@ impl Global {
 
             if let Sec727Goto::CheckDimen = goto {
                 // check_dimensions:
-                let z = hpack!(self, new_hlist(q), NATURAL)?;
-                if height(z) > max_h {
-                    max_h = height(z);
+                let z = hpack!(self, self.new_hlist(q), NATURAL)?;
+                if self.height(z) > max_h {
+                    max_h = self.height(z);
                 }
-                if depth(z) > max_d {
-                    max_d = depth(z);
+                if self.depth(z) > max_d {
+                    max_d = self.depth(z);
                 }
                 self.free_node(z, BOX_NODE_SIZE);
             }
@@ -362,19 +354,19 @@ impl Global {
                 | Sec727Goto::DoneWithNoad => {
                     // done_with_noad:
                     r = q;
-                    r_type = r#type(r);
+                    r_type = self.r#type(r);
                 },
                 _ => (),
             }
 
             // done_with_node:
-            q = link(q);
+            q = self.link(q);
             // End section 727
         }
         
         // Section 729
         if r_type == BIN_NOAD {
-            *type_mut(r) = ORD_NOAD;
+            *self.type_mut(r) = ORD_NOAD;
         }
         // End section 729
 
@@ -384,8 +376,8 @@ impl Global {
     // Section 734
     fn make_over(&mut self, q: HalfWord) -> TeXResult<()> {
         let tmp = self.clean_box(nucleus!(q), cramped_style!(self.cur_style))?;
-        *info_mut(nucleus!(q)) = self.overbar(tmp, 3*self.default_rule_thickness(), self.default_rule_thickness())?;
-        *math_type_mut(nucleus!(q)) = SUB_BOX;
+        *self.info_mut(nucleus!(q)) = self.overbar(tmp, 3*self.default_rule_thickness(), self.default_rule_thickness())?;
+        *self.math_type_mut(nucleus!(q)) = SUB_BOX;
         Ok(())
     }
 
@@ -393,26 +385,26 @@ impl Global {
     fn make_under(&mut self, q: HalfWord) -> TeXResult<()> {
         let x = self.clean_box(nucleus!(q), self.cur_style)?;
         let p = self.new_kern(3*self.default_rule_thickness())?;
-        *link_mut(x) = p;
-        *link_mut(p) = self.fraction_rule(self.default_rule_thickness())?;
+        *self.link_mut(x) = p;
+        *self.link_mut(p) = self.fraction_rule(self.default_rule_thickness())?;
         let y = vpack!(self, x, NATURAL)?;
-        let delta = height(y) + depth(y) + self.default_rule_thickness();
-        *height_mut(y) = height(x);
-        *depth_mut(y) = delta - height(y);
-        *info_mut(nucleus!(q)) = y;
-        *math_type_mut(nucleus!(q)) = SUB_BOX;
+        let delta = self.height(y) + self.depth(y) + self.default_rule_thickness();
+        *self.height_mut(y) = self.height(x);
+        *self.depth_mut(y) = delta - self.height(y);
+        *self.info_mut(nucleus!(q)) = y;
+        *self.math_type_mut(nucleus!(q)) = SUB_BOX;
         Ok(())
     }
 
     // Section 736
     fn make_vcenter(&mut self, q: HalfWord) -> TeXResult<()> {
-        let v = info(nucleus!(q));
-        if r#type(v) != VLIST_NODE {
+        let v = self.info(nucleus!(q));
+        if self.r#type(v) != VLIST_NODE {
             return Err(TeXError::Confusion("vcenter"));
         }
-        let delta = height(v) + depth(v);
-        *height_mut(v) = self.axis_height(self.cur_size) + half!(delta);
-        *depth_mut(v) = delta - height(v);
+        let delta = self.height(v) + self.depth(v);
+        *self.height_mut(v) = self.axis_height(self.cur_size) + half!(delta);
+        *self.depth_mut(v) = delta - self.height(v);
         Ok(())
     }
 
@@ -427,15 +419,15 @@ impl Global {
             clr + clr.abs() / 4
         };
 
-        let y = self.var_delimiter(left_delimiter!(q), self.cur_size, height(x) + depth(x) + clr + self.default_rule_thickness())?;
-        let delta = depth(y) - (height(x) + depth(x) + clr);
+        let y = self.var_delimiter(left_delimiter!(q), self.cur_size, self.height(x) + self.depth(x) + clr + self.default_rule_thickness())?;
+        let delta = self.depth(y) - (self.height(x) + self.depth(x) + clr);
         if delta > 0 {
             clr += half!(delta);
         }
-        *shift_amount_mut(y) = -(height(x) + clr);
-        *link_mut(y) = self.overbar(x, clr, height(y))?;
-        *info_mut(nucleus!(q)) = hpack!(self, y, NATURAL)?;
-        *math_type_mut(nucleus!(q)) = SUB_BOX;
+        *self.shift_amount_mut(y) = -(self.height(x) + clr);
+        *self.link_mut(y) = self.overbar(x, clr, self.height(y))?;
+        *self.info_mut(nucleus!(q)) = hpack!(self, y, NATURAL)?;
+        *self.math_type_mut(nucleus!(q)) = SUB_BOX;
         Ok(())
     }
 
@@ -448,7 +440,7 @@ impl Global {
             let f = self.cur_f;
             // Section 741
             let mut s = 0;
-            if math_type(nucleus!(q)) == MATH_CHAR {
+            if self.math_type(nucleus!(q)) == MATH_CHAR {
                 self.fetch(nucleus!(q))?;
                 if self.cur_i.char_tag() == LIG_TAG {
                     let mut a = self.lig_kern_start(self.cur_f, self.cur_i);
@@ -476,8 +468,8 @@ impl Global {
             // End section 741
 
             let mut x = self.clean_box(nucleus!(q), cramped_style!(self.cur_style))?;
-            let w = width(x);
-            let mut h = height(x);
+            let w = self.width(x);
+            let mut h = self.height(x);
 
             // Section 740
             loop {
@@ -501,40 +493,40 @@ impl Global {
                 self.x_height(f)
             };
 
-            if (math_type(supscr!(q)) != EMPTY || math_type(subscr!(q)) != EMPTY) && math_type(nucleus!(q)) == MATH_CHAR {
+            if (self.math_type(supscr!(q)) != EMPTY || self.math_type(subscr!(q)) != EMPTY) && self.math_type(nucleus!(q)) == MATH_CHAR {
                 // Section 742
                 self.flush_node_list(x)?;
                 x = self.new_noad()?;
-                *mem_mut(nucleus!(x) as usize) = mem(nucleus!(q) as usize);
-                *mem_mut(supscr!(x) as usize) = mem(supscr!(q) as usize);
-                *mem_mut(subscr!(x) as usize) = mem(subscr!(q) as usize);
-                *mem_mut(supscr!(q) as usize) = self.empty_field;
-                *mem_mut(subscr!(q) as usize) = self.empty_field;
-                *math_type_mut(nucleus!(q)) = SUB_MLIST;
-                *info_mut(nucleus!(q)) = x;
+                *self.mem_mut(nucleus!(x) as usize) = self.mem(nucleus!(q) as usize);
+                *self.mem_mut(supscr!(x) as usize) = self.mem(supscr!(q) as usize);
+                *self.mem_mut(subscr!(x) as usize) = self.mem(subscr!(q) as usize);
+                *self.mem_mut(supscr!(q) as usize) = self.empty_field;
+                *self.mem_mut(subscr!(q) as usize) = self.empty_field;
+                *self.math_type_mut(nucleus!(q)) = SUB_MLIST;
+                *self.info_mut(nucleus!(q)) = x;
                 x = self.clean_box(nucleus!(q), self.cur_style)?;
-                delta += height(x) - h;
-                h = height(x);
+                delta += self.height(x) - h;
+                h = self.height(x);
                 // End section 742
             }
             let mut y = self.char_box(f, c)?;
-            *shift_amount_mut(y) = s + half!(w - width(y));
-            *width_mut(y) = 0;
+            *self.shift_amount_mut(y) = s + half!(w - self.width(y));
+            *self.width_mut(y) = 0;
             let mut p = self.new_kern(-delta)?;
-            *link_mut(p) = x;
-            *link_mut(y) = p;
+            *self.link_mut(p) = x;
+            *self.link_mut(y) = p;
             y = vpack!(self, y, NATURAL)?;
-            *width_mut(y) = width(x);
-            if height(y) < h {
+            *self.width_mut(y) = self.width(x);
+            if self.height(y) < h {
                 // Section 739
-                p = self.new_kern(h - height(y))?;
-                *link_mut(p) = list_ptr(y);
-                *list_ptr_mut(y) = p;
-                *height_mut(y) = h;
+                p = self.new_kern(h - self.height(y))?;
+                *self.link_mut(p) = self.list_ptr(y);
+                *self.list_ptr_mut(y) = p;
+                *self.height_mut(y) = h;
                 // End section 739
             }
-            *info_mut(nucleus!(q)) = y;
-            *math_type_mut(nucleus!(q)) = SUB_BOX;
+            *self.info_mut(nucleus!(q)) = y;
+            *self.math_type_mut(nucleus!(q)) = SUB_BOX;
         }
 
         Ok(())
@@ -542,31 +534,31 @@ impl Global {
 
     // Section 743
     fn make_fraction(&mut self, q: HalfWord) -> TeXResult<()> {
-        if thickness(q) == DEFAULT_CODE {
-            *thickness_mut(q) = self.default_rule_thickness();
+        if self.thickness(q) == DEFAULT_CODE {
+            *self.thickness_mut(q) = self.default_rule_thickness();
         }
 
         // Section 744
         let mut x = self.clean_box(numerator!(q), num_style!(self.cur_style))?;
         let mut z = self.clean_box(denominator!(q), denom_style!(self.cur_style))?;
-        if width(x) < width(z) {
-            x = self.rebox(x, width(z))?;
+        if self.width(x) < self.width(z) {
+            x = self.rebox(x, self.width(z))?;
         }
         else {
-            z = self.rebox(z, width(x))?;
+            z = self.rebox(z, self.width(x))?;
         }
         let (mut shift_up, mut shift_down) = if self.cur_style < TEXT_STYLE {
             (self.num1(self.cur_size), self.denom1(self.cur_size))
         }
         else {
-            match thickness(q) {
+            match self.thickness(q) {
                 0 => (self.num3(self.cur_size), self.denom2(self.cur_size)),
                 _ => (self.num2(self.cur_size), self.denom2(self.cur_size))
             }
         };
         // End section 744
 
-        let mut delta = if thickness(q) == 0 {
+        let mut delta = if self.thickness(q) == 0 {
             // Section 745
             let clr = if self.cur_style < TEXT_STYLE {
                 7*self.default_rule_thickness()
@@ -575,7 +567,7 @@ impl Global {
                 3*self.default_rule_thickness()
             };
 
-            let delta = half!(clr - ((shift_up - depth(x)) - (height(z) - shift_down)));
+            let delta = half!(clr - ((shift_up - self.depth(x)) - (self.height(z) - shift_down)));
             if delta > 0 {
                 shift_up += delta;
                 shift_down += delta;
@@ -586,15 +578,15 @@ impl Global {
         else {
             // Section 746
             let clr = if self.cur_style < TEXT_STYLE {
-                3*thickness(q)
+                3*self.thickness(q)
             }
             else {
-                thickness(q)
+                self.thickness(q)
             };
 
-            let delta = half!(thickness(q));
-            let delta1 = clr - ((shift_up - depth(x)) - (self.axis_height(self.cur_size) + delta));
-            let delta2 = clr - ((self.axis_height(self.cur_size) - delta) - (height(z) - shift_down));
+            let delta = half!(self.thickness(q));
+            let delta1 = clr - ((shift_up - self.depth(x)) - (self.axis_height(self.cur_size) + delta));
+            let delta2 = clr - ((self.axis_height(self.cur_size) - delta) - (self.height(z) - shift_down));
             if delta1 > 0 {
                 shift_up += delta1;
             }
@@ -607,27 +599,27 @@ impl Global {
 
         // Secetion 747
         let v = self.new_null_box()?;
-        *type_mut(v) = VLIST_NODE;
-        *height_mut(v) = shift_up + height(x);
-        *depth_mut(v) = depth(z) + shift_down;
-        *width_mut(v) = width(x);
-        let p = if thickness(q) == 0 {
-            let p = self.new_kern((shift_up - depth(x)) - (height(z) - shift_down))?;
-            *link_mut(p) = z;
+        *self.type_mut(v) = VLIST_NODE;
+        *self.height_mut(v) = shift_up + self.height(x);
+        *self.depth_mut(v) = self.depth(z) + shift_down;
+        *self.width_mut(v) = self.width(x);
+        let p = if self.thickness(q) == 0 {
+            let p = self.new_kern((shift_up - self.depth(x)) - (self.height(z) - shift_down))?;
+            *self.link_mut(p) = z;
             p
         }
         else {
-            let y = self.fraction_rule(thickness(q))?;
-            let mut p = self.new_kern((self.axis_height(self.cur_size) - delta) - (height(z) - shift_down))?;
-            *link_mut(y) = p;
-            *link_mut(p) = z;
-            p = self.new_kern((shift_up - depth(x)) - (self.axis_height(self.cur_size) + delta))?;
-            *link_mut(p) = y;
+            let y = self.fraction_rule(self.thickness(q))?;
+            let mut p = self.new_kern((self.axis_height(self.cur_size) - delta) - (self.height(z) - shift_down))?;
+            *self.link_mut(y) = p;
+            *self.link_mut(p) = z;
+            p = self.new_kern((shift_up - self.depth(x)) - (self.axis_height(self.cur_size) + delta))?;
+            *self.link_mut(p) = y;
             p
         };
 
-        *link_mut(x) = p;
-        *list_ptr_mut(v) = x;
+        *self.link_mut(x) = p;
+        *self.list_ptr_mut(v) = x;
         // End section 747
 
         // Section 748
@@ -639,21 +631,21 @@ impl Global {
         };
 
         x = self.var_delimiter(left_delimiter!(q), self.cur_size, delta)?;
-        *link_mut(x) = v;
+        *self.link_mut(x) = v;
         z = self.var_delimiter(right_delimiter!(q), self.cur_size, delta)?;
-        *link_mut(v) = z;
-        *new_hlist_mut(q) = hpack!(self, x, NATURAL)?;
+        *self.link_mut(v) = z;
+        *self.new_hlist_mut(q) = hpack!(self, x, NATURAL)?;
         // End section 748
         Ok(())
     }
 
     // Section 749
     fn make_op(&mut self, q: HalfWord) -> TeXResult<Scaled> {
-        if subtype(q) == NORMAL && self.cur_style < TEXT_STYLE {
-            *subtype_mut(q) = LIMITS;
+        if self.subtype(q) == NORMAL && self.cur_style < TEXT_STYLE {
+            *self.subtype_mut(q) = LIMITS;
         }
 
-        let delta = if math_type(nucleus!(q)) == MATH_CHAR {
+        let delta = if self.math_type(nucleus!(q)) == MATH_CHAR {
             self.fetch(nucleus!(q))?;
             if self.cur_style < TEXT_STYLE && self.cur_i.char_tag() == LIST_TAG {
                 let c = self.cur_i.rem_byte();
@@ -661,81 +653,81 @@ impl Global {
                 if i.char_exists() {
                     self.cur_c = c;
                     self.cur_i = i;
-                    *character_mut(nucleus!(q)) = c;
+                    *self.character_mut(nucleus!(q)) = c;
                 }
             }
             let delta = self.char_italic(self.cur_f, self.cur_i);
             let x = self.clean_box(nucleus!(q), self.cur_style)?;
-            if math_type(subscr!(q)) != EMPTY && subtype(q) != LIMITS {
-                *width_mut(x) -= delta;
+            if self.math_type(subscr!(q)) != EMPTY && self.subtype(q) != LIMITS {
+                *self.width_mut(x) -= delta;
             }
-            *shift_amount_mut(x) = half!(height(x) - depth(x)) - self.axis_height(self.cur_size);
-            *math_type_mut(nucleus!(q)) = SUB_BOX;
-            *info_mut(nucleus!(q)) = x;
+            *self.shift_amount_mut(x) = half!(self.height(x) - self.depth(x)) - self.axis_height(self.cur_size);
+            *self.math_type_mut(nucleus!(q)) = SUB_BOX;
+            *self.info_mut(nucleus!(q)) = x;
             delta
         }
         else {
             0
         };
 
-        if subtype(q) == LIMITS {
+        if self.subtype(q) == LIMITS {
             // Section 750
             let mut x = self.clean_box(supscr!(q), sup_style!(self.cur_style))?;
             let mut y = self.clean_box(nucleus!(q), self.cur_style)?;
             let mut z = self.clean_box(subscr!(q), sub_style!(self.cur_style))?;
             let v = self.new_null_box()?;
-            *type_mut(v) = VLIST_NODE;
-            *width_mut(v) = width(y);
-            if width(x) > width(v) {
-                *width_mut(v) = width(x);
+            *self.type_mut(v) = VLIST_NODE;
+            *self.width_mut(v) = self.width(y);
+            if self.width(x) > self.width(v) {
+                *self.width_mut(v) = self.width(x);
             }
-            if width(z) > width(v) {
-                *width_mut(v) = width(z);
+            if self.width(z) > self.width(v) {
+                *self.width_mut(v) = self.width(z);
             }
-            x = self.rebox(x, width(v))?;
-            y = self.rebox(y, width(v))?;
-            z = self.rebox(z, width(v))?;
-            *shift_amount_mut(x) = half!(delta);
-            *shift_amount_mut(z) = -shift_amount(x);
-            *height_mut(v) = height(y);
-            *depth_mut(v) = depth(y);
+            x = self.rebox(x, self.width(v))?;
+            y = self.rebox(y, self.width(v))?;
+            z = self.rebox(z, self.width(v))?;
+            *self.shift_amount_mut(x) = half!(delta);
+            *self.shift_amount_mut(z) = -self.shift_amount(x);
+            *self.height_mut(v) = self.height(y);
+            *self.depth_mut(v) = self.depth(y);
 
             // Section 751
-            if math_type(supscr!(q)) == EMPTY {
+            if self.math_type(supscr!(q)) == EMPTY {
                 self.free_node(x, BOX_NODE_SIZE);
-                *list_ptr_mut(v) = y;
+                *self.list_ptr_mut(v) = y;
             }
             else {
-                let mut shift_up = self.big_op_spacing3() - depth(x);
+                let mut shift_up = self.big_op_spacing3() - self.depth(x);
                 if shift_up < self.big_op_spacing1() {
                     shift_up = self.big_op_spacing1();
                 }
                 let mut p = self.new_kern(shift_up)?;
-                *link_mut(p) = y;
-                *link_mut(x) = p;
+                *self.link_mut(p) = y;
+                *self.link_mut(x) = p;
                 p = self.new_kern(self.big_op_spacing5())?;
-                *link_mut(p) = x;
-                *list_ptr_mut(v) = p;
-                *height_mut(v) += self.big_op_spacing5() + height(x) + depth(x) + shift_up;
+                *self.link_mut(p) = x;
+                *self.list_ptr_mut(v) = p;
+                *self.height_mut(v) += self.big_op_spacing5() + self.height(x) + self.depth(x) + shift_up;
             }
-            if math_type(subscr!(q)) == EMPTY {
+            if self.math_type(subscr!(q)) == EMPTY {
                 self.free_node(z, BOX_NODE_SIZE);
             }
             else {
-                let mut shift_down = self.big_op_spacing4() - height(z);
+                let mut shift_down = self.big_op_spacing4() - self.height(z);
                 if shift_down < self.big_op_spacing2() {
                     shift_down = self.big_op_spacing2();
                 }
                 let mut p = self.new_kern(shift_down)?;
-                *link_mut(y) = p;
-                *link_mut(p) = z;
+                *self.link_mut(y) = p;
+                *self.link_mut(p) = z;
                 p = self.new_kern(self.big_op_spacing5())?;
-                *link_mut(z) = p;
-                *depth_mut(v) += self.big_op_spacing5() + height(z) + depth(z) + shift_down;
+                *self.link_mut(z) = p;
+                *self.depth_mut(v) += self.big_op_spacing5() + self.height(z) + self.depth(z) + shift_down;
             }
             // End section 751
 
-            *new_hlist_mut(q) = v;
+            *self.new_hlist_mut(q) = v;
             // End section 750
         }
         Ok(delta)
@@ -744,22 +736,22 @@ impl Global {
     // Section 752
     fn make_ord(&mut self, q: HalfWord) -> TeXResult<()> {
         'restart: loop {
-            if math_type(subscr!(q)) == EMPTY
-                && math_type(supscr!(q)) == EMPTY
-                && math_type(nucleus!(q)) == MATH_CHAR
+            if self.math_type(subscr!(q)) == EMPTY
+                && self.math_type(supscr!(q)) == EMPTY
+                && self.math_type(nucleus!(q)) == MATH_CHAR
             {
-                let p = link(q);
+                let p = self.link(q);
                 if p != NULL
-                    && r#type(p) >= ORD_NOAD
-                    && r#type(p) <= PUNCT_NOAD
-                    && math_type(nucleus!(p)) == MATH_CHAR
-                    && fam(nucleus!(p)) == fam(nucleus!(q))
+                    && self.r#type(p) >= ORD_NOAD
+                    && self.r#type(p) <= PUNCT_NOAD
+                    && self.math_type(nucleus!(p)) == MATH_CHAR
+                    && self.fam(nucleus!(p)) == self.fam(nucleus!(q))
                 {
-                    *math_type_mut(nucleus!(q)) = MATH_TEXT_CHAR;
+                    *self.math_type_mut(nucleus!(q)) = MATH_TEXT_CHAR;
                     self.fetch(nucleus!(q))?;
                     if self.cur_i.char_tag() == LIG_TAG {
                         let mut a = self.lig_kern_start(self.cur_f, self.cur_i);
-                        self.cur_c = character(nucleus!(p));
+                        self.cur_c = self.character(nucleus!(p));
                         self.cur_i = self.font_info[a as usize];
                         if self.cur_i.skip_byte() > STOP_FLAG {
                             a = self.lig_kern_restart(self.cur_f, self.cur_i);
@@ -798,24 +790,24 @@ impl Global {
         if self.cur_i.next_char() == self.cur_c && self.cur_i.skip_byte() <= STOP_FLAG {
             if self.cur_i.op_byte() >= KERN_FLAG {
                 let p = self.new_kern(self.char_kern(self.cur_f, self.cur_i))?;
-                *link_mut(p) = link(q);
-                *link_mut(q) = p;
+                *self.link_mut(p) = self.link(q);
+                *self.link_mut(q) = p;
                 Ok(Goto::Return)
             }
             else {
                 self.check_interrupt()?;
                 match self.cur_i.op_byte() {
-                    1 | 5 => *character_mut(nucleus!(q)) = self.cur_i.rem_byte(),
+                    1 | 5 => *self.character_mut(nucleus!(q)) = self.cur_i.rem_byte(),
 
-                    2 | 6 => *character_mut(nucleus!(p)) = self.cur_i.rem_byte(),
+                    2 | 6 => *self.character_mut(nucleus!(p)) = self.cur_i.rem_byte(),
                     
                     3 | 7 | 11 => {
                         let r = self.new_noad()?;
-                        *character_mut(nucleus!(r)) = self.cur_i.rem_byte();
-                        *fam_mut(nucleus!(r)) = fam(nucleus!(q));
-                        *link_mut(q) = r;
-                        *link_mut(r) = p;
-                        *math_type_mut(nucleus!(r)) = if self.cur_i.op_byte() < 11 {
+                        *self.character_mut(nucleus!(r)) = self.cur_i.rem_byte();
+                        *self.fam_mut(nucleus!(r)) = self.fam(nucleus!(q));
+                        *self.link_mut(q) = r;
+                        *self.link_mut(r) = p;
+                        *self.math_type_mut(nucleus!(r)) = if self.cur_i.op_byte() < 11 {
                             MATH_CHAR
                         }
                         else {
@@ -824,10 +816,10 @@ impl Global {
                     },
                     
                     _ => {
-                        *link_mut(q) = link(p);
-                        *character_mut(nucleus!(q)) = self.cur_i.rem_byte();
-                        *mem_mut(subscr!(q) as usize) = mem(subscr!(p) as usize);
-                        *mem_mut(supscr!(q) as usize) = mem(supscr!(p) as usize);
+                        *self.link_mut(q) = self.link(p);
+                        *self.character_mut(nucleus!(q)) = self.cur_i.rem_byte();
+                        *self.mem_mut(subscr!(q) as usize) = self.mem(subscr!(p) as usize);
+                        *self.mem_mut(supscr!(q) as usize) = self.mem(supscr!(p) as usize);
                         self.free_node(p, NOAD_SIZE);
                     }
                 }
@@ -835,7 +827,7 @@ impl Global {
                     Ok(Goto::Return)
                 }
                 else {
-                    *math_type_mut(nucleus!(q)) = MATH_CHAR;
+                    *self.math_type_mut(nucleus!(q)) = MATH_CHAR;
                     Ok(Goto::Restart)
                 }
             }
@@ -847,7 +839,7 @@ impl Global {
 
     // Section 756
     fn make_scripts(&mut self, q: HalfWord, delta: Scaled) -> TeXResult<()> {
-        let mut p = new_hlist(q);
+        let mut p = self.new_hlist(q);
         let (mut shift_up, mut shift_down) = if self.is_char_node(p) {
             (0, 0)
         }
@@ -860,31 +852,31 @@ impl Global {
                 SCRIPT_SCRIPT_SIZE
             };
 
-            let shift_up = height(z) - self.sup_drop(t);
-            let shift_down = depth(z) + self.sub_drop(t);
+            let shift_up = self.height(z) - self.sup_drop(t);
+            let shift_down = self.depth(z) + self.sub_drop(t);
             self.free_node(z, BOX_NODE_SIZE);
             (shift_up, shift_down)
         };
 
-        let x = if math_type(supscr!(q)) == EMPTY {
+        let x = if self.math_type(supscr!(q)) == EMPTY {
             // Section 757
             let x = self.clean_box(subscr!(q), sub_style!(self.cur_style))?;
-            *width_mut(x) += script_space();
+            *self.width_mut(x) += self.script_space();
             if shift_down < self.sub1(self.cur_size) {
                 shift_down = self.sub1(self.cur_size);
             }
-            let clr = height(x) - (self.math_x_height(self.cur_size)*4).abs() / 5;
+            let clr = self.height(x) - (self.math_x_height(self.cur_size)*4).abs() / 5;
             if shift_down < clr {
                 shift_down = clr;
             }
-            *shift_amount_mut(x) = shift_down;
+            *self.shift_amount_mut(x) = shift_down;
             x
             // End section 757
         }
         else {
             // Section 758
             let mut x = self.clean_box(supscr!(q), sup_style!(self.cur_style))?;
-            *width_mut(x) += script_space();
+            *self.width_mut(x) += self.script_space();
             let mut clr = if odd!(self.cur_style) {
                 self.sup3(self.cur_size)
             }
@@ -898,51 +890,51 @@ impl Global {
             if shift_up < clr {
                 shift_up = clr;
             }
-            clr = depth(x) + self.math_x_height(self.cur_size).abs() / 4;
+            clr = self.depth(x) + self.math_x_height(self.cur_size).abs() / 4;
             if shift_up < clr {
                 shift_up = clr;
             }
             // End section 758
 
-            if math_type(subscr!(q)) == EMPTY {
-                *shift_amount_mut(x) = -shift_up;
+            if self.math_type(subscr!(q)) == EMPTY {
+                *self.shift_amount_mut(x) = -shift_up;
             }
             else {
                 // Section 759
                 let y = self.clean_box(subscr!(q), sub_style!(self.cur_style))?;
-                *width_mut(y) += script_space();
+                *self.width_mut(y) += self.script_space();
                 if shift_down < self.sub2(self.cur_size) {
                     shift_down = self.sub2(self.cur_size);
                 }
-                clr = 4*self.default_rule_thickness() - ((shift_up - depth(x)) - (height(y) - shift_down));
+                clr = 4*self.default_rule_thickness() - ((shift_up - self.depth(x)) - (self.height(y) - shift_down));
                 if clr > 0 {
                     shift_down += clr;
-                    clr = (self.math_x_height(self.cur_size)*4).abs() / 5 - (shift_up - depth(x));
+                    clr = (self.math_x_height(self.cur_size)*4).abs() / 5 - (shift_up - self.depth(x));
                     if clr > 0 {
                         shift_up += clr;
                         shift_down -= clr;
                     }
                 }
-                *shift_amount_mut(x) = delta;
-                p = self.new_kern((shift_up - depth(x)) - (height(y) - shift_down))?;
-                *link_mut(x) = p;
-                *link_mut(p) = y;
+                *self.shift_amount_mut(x) = delta;
+                p = self.new_kern((shift_up - self.depth(x)) - (self.height(y) - shift_down))?;
+                *self.link_mut(x) = p;
+                *self.link_mut(p) = y;
                 x = vpack!(self, x, NATURAL)?;
-                *shift_amount_mut(x) = shift_down;
+                *self.shift_amount_mut(x) = shift_down;
                 // End section 759
             }
             x
         };
 
-        if new_hlist(q) == NULL {
-            *new_hlist_mut(q) = x;
+        if self.new_hlist(q) == NULL {
+            *self.new_hlist_mut(q) = x;
         }
         else {
-            p = new_hlist(q);
-            while link(p) != NULL {
-                p = link(p);
+            p = self.new_hlist(q);
+            while self.link(p) != NULL {
+                p = self.link(p);
             }
-            *link_mut(p) = x;
+            *self.link_mut(p) = x;
         }
 
         Ok(())
@@ -950,8 +942,8 @@ impl Global {
 
     // Section 760
     fn sec760_make_a_second_pass(&mut self, mlist: HalfWord, style: QuarterWord, max_d: Scaled, max_h: Scaled, penalties: bool) -> TeXResult<()> {
-        let mut p = TEMP_HEAD;
-        *link_mut(p) = NULL;
+        let mut p = self.temp_head();
+        *self.link_mut(p) = NULL;
         let mut q = mlist;
         let mut r_type = 0;
         self.cur_style = style;
@@ -961,21 +953,21 @@ impl Global {
             let mut t = ORD_NOAD;
             let mut s = NOAD_SIZE;
             let mut pen = INF_PENALTY;
-            match r#type(q) {
+            match self.r#type(q) {
                 OP_NOAD
                 | OPEN_NOAD
                 | CLOSE_NOAD
                 | PUNCT_NOAD
-                | INNER_NOAD => t = r#type(q),
+                | INNER_NOAD => t = self.r#type(q),
 
                 BIN_NOAD => {
                     t = BIN_NOAD;
-                    pen = bin_op_penalty();
+                    pen = self.bin_op_penalty();
                 },
 
                 REL_NOAD => {
                     t = REL_NOAD;
-                    pen = rel_penalty();
+                    pen = self.rel_penalty();
                 },
 
                 ORD_NOAD
@@ -994,14 +986,14 @@ impl Global {
                 
                 STYLE_NODE => {
                     // Section 763
-                    self.cur_style = subtype(q);
+                    self.cur_style = self.subtype(q);
                     s = STYLE_NODE_SIZE;
                     sec703_set_up_values!(self);
                     // End section 763
 
                     // delete_q:
                     let r = q;
-                    q = link(q);
+                    q = self.link(q);
                     self.free_node(r, s);
                     continue;
                 },
@@ -1015,10 +1007,10 @@ impl Global {
                 | MARK_NODE
                 | GLUE_NODE
                 | KERN_NODE => {
-                    *link_mut(p) = q;
+                    *self.link_mut(p) = q;
                     p = q;
-                    q = link(q);
-                    *link_mut(p) = NULL;
+                    q = self.link(q);
+                    *self.link_mut(p) = NULL;
                     continue; // Goto done (continue)
                 }
                 
@@ -1062,32 +1054,32 @@ impl Global {
                 };
 
                 if x != 0 {
-                    let y = self.math_glue(glue_par(x), self.cur_mu)?;
+                    let y = self.math_glue(self.glue_par(x), self.cur_mu)?;
                     let z = self.new_glue(y)?;
-                    *glue_ref_count_mut(y) = NULL;
-                    *link_mut(p) = z;
+                    *self.glue_ref_count_mut(y) = NULL;
+                    *self.link_mut(p) = z;
                     p = z;
-                    *subtype_mut(z) = (x + 1) as QuarterWord;
+                    *self.subtype_mut(z) = (x + 1) as QuarterWord;
                 }
             }
             // End section 766
 
             // Section 767
-            if new_hlist(q) != NULL {
-                *link_mut(p) = new_hlist(q);
+            if self.new_hlist(q) != NULL {
+                *self.link_mut(p) = self.new_hlist(q);
                 loop {
-                    p = link(p);
-                    if link(p) == NULL {
+                    p = self.link(p);
+                    if self.link(p) == NULL {
                         break;
                     }
                 }
             }
 
-            if penalties && link(q) != NULL && pen < INF_PENALTY {
-                r_type = r#type(link(q));
+            if penalties && self.link(q) != NULL && pen < INF_PENALTY {
+                r_type = self.r#type(self.link(q));
                 if r_type != PENALTY_NODE && r_type != REL_NOAD {
                     let z = self.new_penalty(pen)?;
-                    *link_mut(p) = z;
+                    *self.link_mut(p) = z;
                     p = z;
                 }
             }
@@ -1096,7 +1088,7 @@ impl Global {
             r_type = t;
             // delete_q:
             let r = q;
-            q = link(q);
+            q = self.link(q);
             self.free_node(r, s);
             // done
         }
@@ -1117,12 +1109,12 @@ impl Global {
         if delta2 > delta1 {
             delta1 = delta2;
         }
-        let mut delta = (delta1 / 500)*delimiter_factor();
-        delta2 = delta1 + delta1 - delimiter_shortfall();
+        let mut delta = (delta1 / 500)*self.delimiter_factor();
+        delta2 = delta1 + delta1 - self.delimiter_shortfall();
         if delta < delta2 {
             delta = delta2;
         }
-        *new_hlist_mut(q) = self.var_delimiter(delimiter!(q), self.cur_size, delta)?;
-        Ok(r#type(q) - (LEFT_NOAD - OPEN_NOAD))
+        *self.new_hlist_mut(q) = self.var_delimiter(delimiter!(q), self.cur_size, delta)?;
+        Ok(self.r#type(q) - (LEFT_NOAD - OPEN_NOAD))
     }
 }
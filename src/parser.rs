@@ -6,5 +6,4 @@ mod get_next_token;
 mod hyph_scan;
 mod subroutines;
 
-pub(crate) use conditional::if_line_field;
 pub(crate) use hyph_scan::{TrieOpHash, TrieTaken};
@@ -1,10 +1,7 @@
 use crate::constants::*;
-use crate::datastructures::{
-    Status, end_line_char, info, link, link_mut, token_ref_count_mut
-};
+use crate::datastructures::Status;
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileInSelector;
-use crate::strings::{str_pool, pool_ptr, pool_ptr_set, str_room};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, end_line_char_inactive
 };
@@ -14,19 +11,19 @@ use crate::{
 impl Global {
     // Section 464
     fn str_toks(&mut self, b: usize) -> TeXResult<HalfWord> {
-        str_room(1)?;
-        let mut p = TEMP_HEAD;
-        *link_mut(p) = NULL;
+        self.str_room(1)?;
+        let mut p = self.temp_head();
+        *self.link_mut(p) = NULL;
         let mut k = b;
-        while k < pool_ptr() {
-            let t = match str_pool(k) {
+        while k < self.pool_ptr() {
+            let t = match self.str_pool(k) {
                 b' ' => SPACE_TOKEN,
-                _ => OTHER_TOKEN + str_pool(k) as HalfWord,
+                _ => OTHER_TOKEN + self.str_pool(k) as HalfWord,
             };
             self.fast_store_new_token(&mut p, t)?;
             k += 1;
         }
-        pool_ptr_set(b);
+        self.pool_ptr_set(b);
         Ok(p)
     }
 
@@ -36,16 +33,16 @@ impl Global {
         self.scan_something_internal(TOK_VAL as QuarterWord, false)?;
         if self.cur_val_level >= IDENT_VAL {
             // Section 466
-            let mut p = TEMP_HEAD;
-            *link_mut(p) = NULL;
+            let mut p = self.temp_head();
+            *self.link_mut(p) = NULL;
             if self.cur_val_level == IDENT_VAL {
                 self.store_new_token(&mut p, CS_TOKEN_FLAG + self.cur_val)?;
             }
             else if self.cur_val != NULL {
-                let mut r = link(self.cur_val);
+                let mut r = self.link(self.cur_val);
                 while r != NULL {
-                    self.fast_store_new_token(&mut p, info(r))?;
-                    r = link(r);
+                    self.fast_store_new_token(&mut p, self.info(r))?;
+                    r = self.link(r);
                 }
             }
             Ok(p)
@@ -54,7 +51,7 @@ impl Global {
         else {
             let old_setting = self.selector;
             self.selector = NEW_STRING;
-            let b = pool_ptr();
+            let b = self.pool_ptr();
             match self.cur_val_level {
                 INT_VAL => self.print_int(self.cur_val),
 
@@ -82,9 +79,9 @@ impl Global {
 
     // Section 467
     pub(crate) fn ins_the_toks(&mut self) -> TeXResult<()> {
-        *link_mut(GARBAGE) = self.the_toks()?;
+        *self.link_mut(self.garbage()) = self.the_toks()?;
         // ins_list(#) = begin_token_list(#, INSERTED)
-        self.begin_token_list(link(TEMP_HEAD), INSERTED)
+        self.begin_token_list(self.link(self.temp_head()), INSERTED)
     }
 
     // Section 470
@@ -118,7 +115,7 @@ impl Global {
         
         let old_setting = self.selector;
         self.selector = NEW_STRING;
-        let b = pool_ptr();
+        let b = self.pool_ptr();
 
         // Section 472
         match c {
@@ -153,8 +150,8 @@ impl Global {
         // End section 472
 
         self.selector = old_setting;
-        *link_mut(GARBAGE) = self.str_toks(b)?;
-        self.begin_token_list(link(TEMP_HEAD), INSERTED)
+        *self.link_mut(self.garbage()) = self.str_toks(b)?;
+        self.begin_token_list(self.link(self.temp_head()), INSERTED)
     }
 
     // Section 473
@@ -165,17 +162,20 @@ impl Global {
         };
         self.warning_index = self.cur_cs;
         self.def_ref = self.get_avail()?;
-        *token_ref_count_mut(self.def_ref) = NULL;
+        *self.token_ref_count_mut(self.def_ref) = NULL;
         let mut p = self.def_ref;
         let mut hash_brace = 0;
         let mut t = ZERO_TOKEN;
-        if macro_def {
-            self.sec474_scan_and_build_parameter_part(&mut p, &mut hash_brace, &mut t)?;
+        let found = if macro_def {
+            self.sec474_scan_and_build_parameter_part(&mut p, &mut hash_brace, &mut t)?
         }
         else {
             self.scan_left_brace()?;
+            false
+        };
+        if !found {
+            self.sec477_scan_and_build_body(&mut p, macro_def, xpand, t)?;
         }
-        self.sec477_scan_and_build_body(&mut p, macro_def, xpand, t)?;
 
         // found:
         self.scanner_status = Status::Normal;
@@ -186,7 +186,8 @@ impl Global {
     }
 
     // Section 474
-    fn sec474_scan_and_build_parameter_part(&mut self, p: &mut HalfWord, hash_brace: &mut HalfWord, t: &mut HalfWord) -> TeXResult<()> {
+    // Returns true if there is no body to scan (goto found).
+    fn sec474_scan_and_build_parameter_part(&mut self, p: &mut HalfWord, hash_brace: &mut HalfWord, t: &mut HalfWord) -> TeXResult<bool> {
         loop {
             self.get_token()?;
             if self.cur_tok < RIGHT_BRACE_LIMIT {
@@ -200,15 +201,15 @@ impl Global {
                     *hash_brace = self.cur_tok;
                     self.store_new_token(p, self.cur_tok)?;
                     self.store_new_token(p, END_MATCH_TOKEN)?;
-                    return Ok(()); // Goto done
+                    return Ok(false); // Goto done
                 }
                 if *t == ZERO_TOKEN + 9 {
-                    return Err(TeXError::AlreadyNineParameters);
+                    self.error(TeXError::AlreadyNineParameters)?;
                 }
                 else {
                     *t += 1;
                     if self.cur_tok != *t {
-                        return Err(TeXError::ParametersNumberedConsecutively);
+                        self.back_error(TeXError::ParametersNumberedConsecutively)?;
                     }
                     self.cur_tok = s;
                 }
@@ -220,10 +221,13 @@ impl Global {
         // done1:
         self.store_new_token(p, END_MATCH_TOKEN)?;
         if self.cur_cmd == RIGHT_BRACE {
-            Err(TeXError::MissingLeftBrace2)
+            // Section 475
+            self.align_state += 1;
+            self.error(TeXError::MissingLeftBrace2)?;
+            Ok(true) // Goto found
         }
         else {
-            Ok(())
+            Ok(false)
         }
     }
 
@@ -243,8 +247,8 @@ impl Global {
                     }
                     else {
                         let q = self.the_toks()?;
-                        if link(TEMP_HEAD) != NULL {
-                            *link_mut(*p) = link(TEMP_HEAD);
+                        if self.link(self.temp_head()) != NULL {
+                            *self.link_mut(*p) = self.link(self.temp_head());
                             *p = q;
                         }
                     }
@@ -269,6 +273,7 @@ impl Global {
             }
             else if self.cur_cmd == MAC_PARAM && macro_def {
                 // Section 479
+                let s = self.cur_tok;
                 if xpand {
                     self.get_x_token()?;
                 }
@@ -277,7 +282,8 @@ impl Global {
                 }
                 if self.cur_cmd != MAC_PARAM {
                     if self.cur_tok <= ZERO_TOKEN || self.cur_tok > t {
-                        return Err(TeXError::IllegalParameterNumber);
+                        self.back_error(TeXError::IllegalParameterNumber)?;
+                        self.cur_tok = s;
                     }
                     else {
                         self.cur_tok = OUT_PARAM_TOKEN - (b'0' as HalfWord) + self.cur_chr;
@@ -294,7 +300,7 @@ impl Global {
         self.scanner_status = Status::Defining;
         self.warning_index = r;
         self.def_ref = self.get_avail()?;
-        *token_ref_count_mut(self.def_ref) = NULL;
+        *self.token_ref_count_mut(self.def_ref) = NULL;
         let mut p = self.def_ref;
         self.store_new_token(&mut p, END_MATCH_TOKEN)?;
         let m = match n {
@@ -310,7 +316,7 @@ impl Global {
             match self.read_open[m as usize] {
                 CLOSED => {
                     // Section 484
-                    if self.interaction != BATCH_MODE {
+                    if self.interaction > NONSTOP_MODE {
                         if n < 0 {
                             self.prompt_input("")?;
                         }
@@ -322,7 +328,7 @@ impl Global {
                         }
                     }
                     else {
-                        return Err(TeXError::Fatal("*** (cannot \\read from terminal in batch mode)"));
+                        return Err(TeXError::Fatal("*** (cannot \\read from terminal in nonstop modes)"));
                     }
                     // End section 484
                 },
@@ -346,7 +352,8 @@ impl Global {
                         self.read_open[m as usize] = CLOSED;
                         if self.align_state != 1_000_000 {
                             self.runaway();
-                            return Err(TeXError::FileEndedWithin);
+                            self.align_state = 1_000_000;
+                            self.error(TeXError::FileEndedWithin)?;
                         }
                     }
                     // End section 486
@@ -354,11 +361,12 @@ impl Global {
             }
 
             *self.limit_mut() = self.last;
-            if end_line_char_inactive!() {
+            if end_line_char_inactive!(self) {
                 *self.limit_mut() -=1;
             }
             else {
-                self.buffer[self.limit() as usize] = end_line_char() as u8;
+                let limit = self.limit() as usize;
+                self.buffer[limit] = self.end_line_char() as u8;
             }
             self.first = self.limit() + 1;
             *self.loc_mut() = self.start();
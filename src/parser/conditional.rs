@@ -1,8 +1,5 @@
 use crate::constants::*;
-use crate::datastructures::{
-    mem, mem_mut, Status, equiv, info, link, link_mut, r#box, r#type, subtype,
-    subtype_mut, tracing_commands, type_mut
-};
+use crate::datastructures::Status;
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, SmallNumber,
@@ -18,13 +15,15 @@ enum Goto {
     Some(bool)
 }
 
-// Section 489
-pub(crate) fn if_line_field(p: HalfWord) -> Integer {
-    mem((p + 1) as usize).int()
-}
+impl Global {
+    // Section 489
+    pub(crate) fn if_line_field(&self, p: HalfWord) -> Integer {
+        self.mem((p + 1) as usize).int()
+    }
 
-fn if_line_field_mut(p: HalfWord) -> &'static mut Integer {
-    mem_mut((p + 1) as usize).int_mut()
+    fn if_line_field_mut(&mut self, p: HalfWord) -> &mut Integer {
+        self.mem_mut((p + 1) as usize).int_mut()
+    }
 }
 
 impl Global {
@@ -56,10 +55,10 @@ impl Global {
     // Section 496
     pub(crate) fn sec496_pop_the_condition_stack(&mut self) {
         let p = self.cond_ptr;
-        self.if_line = if_line_field(p);
-        self.cur_if = subtype(p);
-        self.if_limit = r#type(p);
-        self.cond_ptr = link(p);
+        self.if_line = self.if_line_field(p);
+        self.cur_if = self.subtype(p);
+        self.if_limit = self.r#type(p);
+        self.cond_ptr = self.link(p);
         self.free_node(p, IF_NODE_SIZE);
     }
 
@@ -74,11 +73,11 @@ impl Global {
                 if q == NULL {
                     return Err(TeXError::Confusion("if"));
                 }
-                if link(q) == p {
-                    *type_mut(q) = l;
+                if self.link(q) == p {
+                    *self.type_mut(q) = l;
                     break;
                 }
-                q = link(q);
+                q = self.link(q);
             }
         }
         Ok(())
@@ -88,10 +87,10 @@ impl Global {
     pub(crate) fn conditional(&mut self) -> TeXResult<()> {
         // Section 495
         let p = self.get_node(IF_NODE_SIZE)?;
-        *link_mut(p) = self.cond_ptr;
-        *type_mut(p) = self.if_limit;
-        *subtype_mut(p) = self.cur_if;
-        *if_line_field_mut(p) = self.if_line;
+        *self.link_mut(p) = self.cond_ptr;
+        *self.type_mut(p) = self.if_limit;
+        *self.subtype_mut(p) = self.cur_if;
+        *self.if_line_field_mut(p) = self.if_line;
         self.cond_ptr = p;
         self.cur_if = self.cur_chr as QuarterWord;
         self.if_limit = IF_CODE as QuarterWord;
@@ -110,7 +109,7 @@ impl Global {
             };
             // End section 501
     
-            if tracing_commands() > 1 {
+            if self.tracing_commands() > 1 {
                 // Section 502
                 self.begin_diagnostic();
                 match b {
@@ -133,7 +132,7 @@ impl Global {
                     if self.cur_chr != OR_CODE {
                         break 'block; // Goto common_ending
                     }
-                    return Err(TeXError::ExtraOr);
+                    self.error(TeXError::ExtraOr)?;
                 }
                 if self.cur_chr == FI_CODE {
                     self.sec496_pop_the_condition_stack();
@@ -202,7 +201,8 @@ impl Global {
                     (self.cur_tok - OTHER_TOKEN) as u8
                 }
                 else {
-                    return Err(TeXError::MissingEqual(this_if));
+                    self.back_error(TeXError::MissingEqual(this_if))?;
+                    b'='
                 };
 
                 match this_if {
@@ -237,7 +237,7 @@ impl Global {
             | IF_VBOX_CODE => {
                 // Section 505
                 self.scan_eight_bit_int()?;
-                let p = r#box(self.cur_val);
+                let p = self.r#box(self.cur_val);
                 if this_if == IF_VOID_CODE {
                     p == NULL
                 }
@@ -245,10 +245,10 @@ impl Global {
                     false
                 }
                 else if this_if == IF_HBOX_CODE {
-                    r#type(p) == HLIST_NODE
+                    self.r#type(p) == HLIST_NODE
                 }
                 else {
-                    r#type(p) == VLIST_NODE
+                    self.r#type(p) == VLIST_NODE
                 }
                 // End section 505
             },
@@ -289,19 +289,19 @@ impl Global {
         }
         else {
             // Section 508
-            p = link(self.cur_chr);
-            q = link(equiv(n));
+            p = self.link(self.cur_chr);
+            q = self.link(self.equiv(n));
             if p == q {
                 true
             }
             else {
                 while p != NULL && q != NULL {
-                    if info(p) != info(q) {
+                    if self.info(p) != self.info(q) {
                         p = NULL;
                     }
                     else {
-                        p = link(p);
-                        q = link(q);
+                        p = self.link(p);
+                        q = self.link(q);
                     }
                 }
                 p == NULL && q == NULL
@@ -317,7 +317,7 @@ impl Global {
     fn sec509_select_the_appropriate_case(&mut self, save_cond_ptr: HalfWord) -> TeXResult<Goto> {
         self.scan_int()?;
         let mut n = self.cur_val;
-        if tracing_commands() > 1 {
+        if self.tracing_commands() > 1 {
             self.begin_diagnostic();
             self.print("{case ");
             self.print_int(n);
@@ -1,8 +1,5 @@
 use crate::constants::*;
-use crate::datastructures::{
-    Status, eq_type, info, info_mut, link, link_mut,
-    tracing_commands, tracing_macros
-};
+use crate::datastructures::Status;
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord,  Integer, QuarterWord, StrNum,
@@ -18,7 +15,7 @@ impl Global {
         let cvl_backup = self.cur_val_level;
         let radix_backup = self.radix;
         let co_backup = self.cur_order;
-        let backup_backup = link(BACKUP_HEAD);
+        let backup_backup = self.link(self.backup_head());
         if self.cur_cmd < CALL {
             self.sec367_expand_a_nonmacro()?;
         }
@@ -35,13 +32,13 @@ impl Global {
         self.cur_val_level = cvl_backup;
         self.radix = radix_backup;
         self.cur_order = co_backup;
-        *link_mut(BACKUP_HEAD) = backup_backup;
+        *self.link_mut(self.backup_head()) = backup_backup;
         Ok(())
     }
 
     // Section 367
     fn sec367_expand_a_nonmacro(&mut self) -> TeXResult<()> {
-        if tracing_commands() > 1 {
+        if self.tracing_commands() > 1 {
             self.show_cur_cmd_chr();
         }
         match self.cur_cmd {
@@ -79,8 +76,8 @@ impl Global {
                 self.back_input()?;
                 if t >= CS_TOKEN_FLAG {
                     let p = self.get_avail()?;
-                    *info_mut(p) = CS_TOKEN_FLAG + FROZEN_DONT_EXPAND;
-                    *link_mut(p) = self.loc();
+                    *self.info_mut(p) = CS_TOKEN_FLAG + FROZEN_DONT_EXPAND;
+                    *self.link_mut(p) = self.loc();
                     *self.start_mut() = p;
                     *self.loc_mut() = p;
                 }
@@ -102,7 +99,7 @@ impl Global {
                         self.insert_relax()?;
                     }
                     else {
-                        return Err(TeXError::ExtraFiOrElse);
+                        self.error(TeXError::ExtraFiOrElse)?;
                     }
                 }
                 else {
@@ -128,7 +125,7 @@ impl Global {
                 // End section 378
             },
 
-            _ => return Err(TeXError::UndefinedControlSequence) 
+            _ => self.error(TeXError::UndefinedControlSequence)? // Section 370
         }
 
         Ok(())
@@ -137,8 +134,8 @@ impl Global {
     // Section 371
     pub(crate) fn store_new_token(&mut self, p: &mut HalfWord, info: HalfWord) -> TeXResult<()> {
         let q = self.get_avail()?;
-        *link_mut(*p) = q;
-        *info_mut(q) = info;
+        *self.link_mut(*p) = q;
+        *self.info_mut(q) = info;
         *p = q;
         Ok(())
     }
@@ -146,8 +143,8 @@ impl Global {
     pub(crate) fn fast_store_new_token(&mut self, p: &mut HalfWord, info: HalfWord) -> TeXResult<()> {
         let mut q: HalfWord;
         fast_get_avail!(self, q);
-        *link_mut(*p) = q;
-        *info_mut(q) = info;
+        *self.link_mut(*p) = q;
+        *self.info_mut(q) = info;
         *p = q;
         Ok(())
     }
@@ -166,22 +163,22 @@ impl Global {
             }
         }
         if self.cur_cmd != END_CS_NAME {
-            return Err(TeXError::MissingEncCSName);
+            self.back_error(TeXError::MissingEncCSName)?;
         }
 
         // Section 374
         let mut j = self.first;
-        p = link(r);
+        p = self.link(r);
         while p != NULL {
             if j >= self.max_buf_stack {
                 self.max_buf_stack = j + 1;
-                if self.max_buf_stack == BUF_SIZE {
-                    return Err(TeXError::Overflow("buffer size", BUF_SIZE));
+                if self.max_buf_stack == self.buf_size {
+                    return Err(TeXError::Overflow("buffer size", self.buf_size));
                 }
             }
-            self.buffer[j as usize] = (info(p) % 256) as u8;
+            self.buffer[j as usize] = (self.info(p) % 256) as u8;
             j += 1;
-            p = link(p);
+            p = self.link(p);
         }
         if j > self.first + 1 {
             self.no_new_control_sequence = false;
@@ -197,7 +194,7 @@ impl Global {
         // End section 374
 
         self.flush_list(r);
-        if eq_type(self.cur_cs) == UNDEFINED_CS {
+        if self.eq_type(self.cur_cs) == UNDEFINED_CS {
             self.eq_define(self.cur_cs, RELAX, 256)?;
         }
         self.cur_tok = self.cur_cs + CS_TOKEN_FLAG;
@@ -304,9 +301,9 @@ impl Global {
         let save_warning_index = self.warning_index;
         self.warning_index = self.cur_cs;
         let ref_count = self.cur_chr;
-        let mut r = link(ref_count);
+        let mut r = self.link(ref_count);
         let mut n = 0;
-        if tracing_macros() > 0 {
+        if self.tracing_macros() > 0 {
             // Section 401
             self.begin_diagnostic();
             self.print_ln();
@@ -315,8 +312,16 @@ impl Global {
             self.end_diagnostic(false);
             // End section 401
         }
-        if info(r) != END_MATCH_TOKEN {
-            (r, n) = self.sec391_scan_the_parameters_and_make(r, n)?;
+        if self.info(r) != END_MATCH_TOKEN {
+            match self.sec391_scan_the_parameters_and_make(r, n)? {
+                Some((new_r, new_n)) => (r, n) = (new_r, new_n),
+                None => {
+                    // Goto exit
+                    self.scanner_status = save_scanner_status;
+                    self.warning_index = save_warning_index;
+                    return Ok(());
+                }
+            }
         }
 
         // Section 390
@@ -328,12 +333,12 @@ impl Global {
         }
         self.begin_token_list(ref_count, MACRO)?;
         *self.name_mut() = self.warning_index;
-        *self.loc_mut() = link(r);
+        *self.loc_mut() = self.link(r);
         if n > 0 {
             if self.param_ptr + n > self.max_param_stack {
                 self.max_param_stack = self.param_ptr + n;
-                if self.max_param_stack > PARAM_SIZE as usize {
-                    return Err(TeXError::Overflow("parameter stack size", PARAM_SIZE));
+                if self.max_param_stack > self.param_size as usize {
+                    return Err(TeXError::Overflow("parameter stack size", self.param_size));
                 }
             }
             for m in 0..n {
@@ -350,27 +355,28 @@ impl Global {
     }
 
     // Section 391
-    fn sec391_scan_the_parameters_and_make(&mut self, mut r: HalfWord, mut n: usize) -> TeXResult<(HalfWord, usize)> {
+    // Returns `None` if the macro call has been aborted.
+    fn sec391_scan_the_parameters_and_make(&mut self, mut r: HalfWord, mut n: usize) -> TeXResult<Option<(HalfWord, usize)>> {
         self.scanner_status = Status::Matching;
-        let mut unbalance;
-        self.long_state = eq_type(self.cur_cs);
+        let mut unbalance = 0;
+        self.long_state = self.eq_type(self.cur_cs);
         if self.long_state >= OUTER_CALL {
             self.long_state -= 2;
         }
         'sec391: loop {
-            *link_mut(TEMP_HEAD) = NULL;
+            *self.link_mut(self.temp_head()) = NULL;
             let mut match_chr: HalfWord = 0;
             let s: HalfWord;
             let mut p: HalfWord = 0;
             let mut m: HalfWord = 0;
-            if info(r) > MATCH_TOKEN + 255 || info(r) < MATCH_TOKEN {
+            if self.info(r) > MATCH_TOKEN + 255 || self.info(r) < MATCH_TOKEN {
                 s = NULL;
             }
             else {
-                match_chr = info(r) - MATCH_TOKEN;
-                s = link(r);
+                match_chr = self.info(r) - MATCH_TOKEN;
+                s = self.link(r);
                 r = s;
-                p = TEMP_HEAD;
+                p = self.temp_head();
                 m = 0;
             }
             let mut rbrace_ptr: HalfWord = 0;
@@ -379,10 +385,10 @@ impl Global {
             // continue:
             'sec392: loop {
                 self.get_token()?;
-                if self.cur_tok == info(r) {
+                if self.cur_tok == self.info(r) {
                     // Section 394
-                    r = link(r);
-                    if (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&info(r)) {
+                    r = self.link(r);
+                    if (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&self.info(r)) {
                         if self.cur_tok < LEFT_BRACE_LIMIT {
                             self.align_state -= 1;
                         }
@@ -395,30 +401,32 @@ impl Global {
                 // Section 397
                 if s != r {
                     if s == NULL {
-                        return Err(TeXError::DoesNotMatchDefinition);
+                        // Section 398
+                        self.error(TeXError::DoesNotMatchDefinition)?;
+                        return Ok(None);
                     }
                     let mut t = s;
                     'sec397: loop {
-                        self.store_new_token(&mut p, info(t))?;
+                        self.store_new_token(&mut p, self.info(t))?;
                         m += 1;
-                        let mut u = link(t);
+                        let mut u = self.link(t);
                         let mut v = s;
                         'inner: loop {
                             if u == r {
-                                if self.cur_tok != info(v) {
+                                if self.cur_tok != self.info(v) {
                                     break 'inner; // Goto done
                                 }
-                                r = link(v);
+                                r = self.link(v);
                                 continue 'sec392;
                             }
-                            if info(u) != info(v) {
+                            if self.info(u) != self.info(v) {
                                 break 'inner; // Goto done
                             }
-                            u = link(u);
-                            v = link(v);
+                            u = self.link(u);
+                            v = self.link(v);
                         }
                         // done:
-                        t = link(t);
+                        t = self.link(t);
                         if t == r {
                             break 'sec397;
                         }
@@ -428,10 +436,8 @@ impl Global {
                 // End section 397
 
                 if self.cur_tok == self.par_token && self.long_state != LONG_CALL {
-                    // Section 396
-                    self.runaway();
-                    return Err(TeXError::ParagraphEndedBefore);
-                    // End section 396
+                    self.sec396_report_a_runaway_argument(n, unbalance)?;
+                    return Ok(None);
                 }
                 if self.cur_tok < RIGHT_BRACE_LIMIT {
                     if self.cur_tok < LEFT_BRACE_LIMIT {
@@ -441,8 +447,8 @@ impl Global {
                             self.fast_store_new_token(&mut p, self.cur_tok)?;
                             self.get_token()?;
                             if self.cur_tok == self.par_token && self.long_state != LONG_CALL {
-                                self.runaway();
-                                return Err(TeXError::ParagraphEndedBefore);
+                                self.sec396_report_a_runaway_argument(n, unbalance)?;
+                                return Ok(None);
                             }
                             if self.cur_tok < RIGHT_BRACE_LIMIT {
                                 if self.cur_tok < LEFT_BRACE_LIMIT {
@@ -462,19 +468,26 @@ impl Global {
                         // End section 399
                     }
                     else {
-                        return Err(TeXError::ArgumentExtraRightBrace);
+                        // Section 395
+                        self.back_input()?;
+                        self.align_state += 1;
+                        self.long_state = CALL;
+                        self.cur_tok = self.par_token;
+                        self.ins_error(TeXError::ArgumentExtraRightBrace)?;
+                        continue 'sec392; // Goto continue
+                        // End section 395
                     }
                 }
                 else {
                     // Section 393
-                    if self.cur_tok == SPACE_TOKEN && (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&info(r)) {
+                    if self.cur_tok == SPACE_TOKEN && (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&self.info(r)) {
                         continue 'sec392; // Goto continue
                     }
                     self.store_new_token(&mut p, self.cur_tok)?;
                     // End section 393
                 }
                 m += 1;
-                if (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&info(r)) {
+                if (MATCH_TOKEN..=END_MATCH_TOKEN).contains(&self.info(r)) {
                     break 'sec392;
                 }
             }
@@ -482,18 +495,18 @@ impl Global {
             // found:
             if s != NULL {
                 // Section 400
-                if m == 1 && info(p) < RIGHT_BRACE_LIMIT && p != TEMP_HEAD {
-                    *link_mut(rbrace_ptr) = NULL;
+                if m == 1 && self.info(p) < RIGHT_BRACE_LIMIT && p != self.temp_head() {
+                    *self.link_mut(rbrace_ptr) = NULL;
                     free_avail!(self, p);
-                    p = link(TEMP_HEAD);
-                    self.pstack[n] = link(p);
+                    p = self.link(self.temp_head());
+                    self.pstack[n] = self.link(p);
                     free_avail!(self, p);
                 }
                 else {
-                    self.pstack[n] = link(TEMP_HEAD);
+                    self.pstack[n] = self.link(self.temp_head());
                 }
                 n += 1;
-                if tracing_macros() > 0 {
+                if self.tracing_macros() > 0 {
                     self.begin_diagnostic();
                     self.print_nl_strnumber(match_chr as StrNum);
                     self.print_int(n as Integer);
@@ -505,10 +518,24 @@ impl Global {
             }
             // End section 392
 
-            if info(r) == END_MATCH_TOKEN {
+            if self.info(r) == END_MATCH_TOKEN {
                 break 'sec391;
             }
         }
-        Ok((r, n))
+        Ok(Some((r, n)))
+    }
+
+    // Section 396
+    fn sec396_report_a_runaway_argument(&mut self, n: usize, unbalance: Integer) -> TeXResult<()> {
+        if self.long_state == CALL {
+            self.runaway();
+            self.back_error(TeXError::ParagraphEndedBefore)?;
+        }
+        self.pstack[n] = self.link(self.temp_head());
+        self.align_state -= unbalance;
+        for m in 0..=n {
+            self.flush_list(self.pstack[m]);
+        }
+        Ok(())
     }
 }
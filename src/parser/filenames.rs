@@ -1,19 +1,11 @@
 use crate::constants::*;
-use crate::datastructures::end_line_char;
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileInSelector;
-use crate::strings::{
-    POOL, append_char, cur_length, flush_string, init_str_ptr, length,
-    make_string, pool_ptr, pool_ptr_set, str_eq_str, str_pool_slice,
-    str_ptr, str_ptr_set, str_room, str_start, str_start_mut
-};
 use crate::{
     Global, HalfWord, Integer, StrNum, end_line_char_inactive,
     sec406_get_next_nonblank_noncall_token, update_terminal
 };
 
-use std::io::Write;
-
 // Part 29: File names
 
 impl Global {
@@ -29,14 +21,14 @@ impl Global {
             Ok(false)
         }
         else {
-            str_room(1)?;
-            append_char(c);
+            self.str_room(1)?;
+            self.append_char(c);
             if c == b'/' {
-                self.area_delimiter = cur_length();
+                self.area_delimiter = self.cur_length();
                 self.ext_delimiter = 0;
             }
             else if c == b'.' && self.ext_delimiter == 0 {
-                self.ext_delimiter = cur_length();
+                self.ext_delimiter = self.cur_length();
             }
             Ok(true)
         }
@@ -44,67 +36,68 @@ impl Global {
 
     // Section 517
     pub(crate) fn end_name(&mut self) -> TeXResult<()> {
-        if str_ptr() + 3 > MAX_STRINGS as usize {
-            return Err(TeXError::Overflow("number of strings", MAX_STRINGS - init_str_ptr() as Integer));
+        if self.str_ptr() + 3 > self.max_strings as usize {
+            return Err(TeXError::Overflow("number of strings", self.max_strings - self.init_str_ptr() as Integer));
         }
 
         if self.area_delimiter == 0 {
             self.cur_area = EMPTY_STRING;
         }
         else {
-            self.cur_area = str_ptr();
-            *str_start_mut(str_ptr() + 1) = str_start(str_ptr()) + self.area_delimiter;
-            str_ptr_set(str_ptr() + 1);
+            self.cur_area = self.str_ptr();
+            *self.str_start_mut(self.str_ptr() + 1) = self.str_start(self.str_ptr()) + self.area_delimiter;
+            self.str_ptr_set(self.str_ptr() + 1);
         }
 
         if self.ext_delimiter == 0 {
             self.cur_ext = EMPTY_STRING;
-            self.cur_name = make_string()?;
+            self.cur_name = self.make_string()?;
         }
         else {
-            self.cur_name = str_ptr();
-            *str_start_mut(str_ptr() + 1) = str_start(str_ptr()) + self.ext_delimiter - self.area_delimiter - 1;
-            str_ptr_set(str_ptr() + 1);
-            self.cur_ext = make_string()?;
+            self.cur_name = self.str_ptr();
+            *self.str_start_mut(self.str_ptr() + 1) = self.str_start(self.str_ptr()) + self.ext_delimiter - self.area_delimiter - 1;
+            self.str_ptr_set(self.str_ptr() + 1);
+            self.cur_ext = self.make_string()?;
         }
         Ok(())
     }
 
     // Section 519
     pub(crate) fn pack_file_name(&mut self, n: StrNum, a: StrNum, e: StrNum) {
-        self.name_of_file.clear();
-        self.name_of_file.push_str(
+        let mut name_of_file = std::mem::take(&mut self.name_of_file);
+        name_of_file.clear();
+        name_of_file.push_str(
             unsafe {
-                std::str::from_utf8_unchecked(str_pool_slice(a))
+                std::str::from_utf8_unchecked(self.str_pool_slice(a))
             }
         );
-        self.name_of_file.push_str(
+        name_of_file.push_str(
             unsafe {
-                std::str::from_utf8_unchecked(str_pool_slice(n))
+                std::str::from_utf8_unchecked(self.str_pool_slice(n))
             }
         );
-        self.name_of_file.push_str(
+        name_of_file.push_str(
             unsafe {
-                std::str::from_utf8_unchecked(str_pool_slice(e))
+                std::str::from_utf8_unchecked(self.str_pool_slice(e))
             }
         );
+        self.name_of_file = name_of_file;
     }
 
     // Section 525
     pub(crate) fn make_name_string(&mut self) -> TeXResult<StrNum> {
         let l = self.name_of_file.len();
-        if pool_ptr() + l > POOL_SIZE as usize
-            || str_ptr() == MAX_STRINGS as usize
-            || cur_length() > 0
+        if self.str_room(l).is_err()
+            || self.str_ptr() == self.max_strings as usize
+            || self.cur_length() > 0
         {
             Ok(b'?' as StrNum)
         }
         else {
-            unsafe {
-                POOL[pool_ptr()..pool_ptr() + l].copy_from_slice(self.name_of_file.as_bytes());
-            }
-            pool_ptr_set(pool_ptr() + l);
-            make_string()
+            let pool_ptr = self.pool_ptr();
+            self.pool[pool_ptr..pool_ptr + l].copy_from_slice(self.name_of_file.as_bytes());
+            self.pool_ptr_set(self.pool_ptr() + l);
+            self.make_string()
         }
     }
 
@@ -143,39 +136,86 @@ impl Global {
         self.pack_cur_name();
     }
 
-    // Section 530, 531
-    // prompt_file_name: no user interaction.
+    // The name of the job is `s`, unless another one was given.
+    pub(crate) fn get_job_name(&mut self, s: StrNum) -> TeXResult<StrNum> {
+        match self.job_name_override.take() {
+            Some(name) => self.put_string(name.as_bytes()),
+            None => Ok(s)
+        }
+    }
+
+    // Output files are written in `output_directory`,
+    // except if their name is an absolute path.
+    pub(crate) fn pack_output_directory(&mut self) {
+        if let Some(directory) = &self.output_directory {
+            let path = std::path::Path::new(&self.name_of_file);
+            if path.is_relative() {
+                self.name_of_file = std::path::Path::new(directory)
+                    .join(path)
+                    .to_string_lossy()
+                    .into_owned();
+            }
+        }
+    }
+
+    // Section 530
+    // Only used for input files: TeX stops if an output file
+    // cannot be written.
+    fn prompt_input_file_name(&mut self) -> TeXResult<()> {
+        self.print_err("I can't find file '");
+        self.print_file_name(self.cur_name, self.cur_area, self.cur_ext);
+        self.print("'.");
+        self.show_context();
+        self.print_nl("Please type another input file name");
+        if self.interaction < SCROLL_MODE {
+            return Err(TeXError::Fatal("*** (job aborted, file error in nonstop mode)"));
+        }
+        self.prompt_input(": ")?;
+
+        // Section 531
+        self.begin_name();
+        let mut k = self.first;
+        while self.buffer[k as usize] == b' ' && k < self.last {
+            k += 1;
+        }
+        while k != self.last && self.more_name(self.buffer[k as usize])? {
+            k += 1;
+        }
+        self.end_name()?;
+        // End section 531
+
+        if self.str_eq_str(self.cur_ext, EMPTY_STRING) {
+            self.cur_ext = EXT_TEX;
+        }
+        self.pack_cur_name();
+        Ok(())
+    }
 
     // Section 537
     pub fn start_input(&mut self) -> TeXResult<()> {
         self.scan_file_name()?;
-        if str_eq_str(self.cur_ext, EMPTY_STRING) {
+        if self.str_eq_str(self.cur_ext, EMPTY_STRING) {
             self.cur_ext = EXT_TEX;
         }
         self.pack_cur_name();
-        self.begin_file_reading()?;
-        'block: {
-            if self.a_open_in(AlphaFileInSelector::CurFile) {
-                break 'block; // Goto done
-            }
-            if str_eq_str(self.cur_area, EMPTY_STRING) {
-                self.pack_file_name(self.cur_name, TEX_AREA, self.cur_ext);
-                if self.a_open_in(AlphaFileInSelector::CurFile) {
-                    break 'block; // Goto done
-                }
+        loop {
+            self.begin_file_reading()?;
+            // The file is looked for along TEXINPUTS.
+            if self.a_open_in_path(AlphaFileInSelector::CurFile) {
+                break; // Goto done
             }
             self.end_file_reading();
-            // No prompt for file
-            return Err(TeXError::CantFindFile);
+            self.prompt_input_file_name()?;
         }
 
         // done:
+        self.source_filename_stack[self.in_open].clone_from(&self.name_of_file);
         *self.name_mut() = self.make_name_string()? as HalfWord;
         if self.job_name == 0 {
-            self.job_name = self.cur_name;
+            self.job_name = self.get_job_name(self.cur_name)?;
             self.open_log_file()?
         }
-        if self.term_offset + length(self.name() as StrNum) as Integer > MAX_PRINT_LINE - 2 {
+        if self.term_offset + self.length(self.name() as StrNum) as Integer > MAX_PRINT_LINE - 2 {
             self.print_ln();
         }
         else if self.term_offset > 0 || self.file_offset > 0 {
@@ -184,11 +224,11 @@ impl Global {
         self.print_char(b'(');
         self.open_parens += 1;
         self.slow_print(self.name() as usize);
-        update_terminal!();
+        update_terminal!(self);
         *self.state_mut() = NEW_LINE;
         
-        if (self.name() as StrNum) == str_ptr() - 1 {
-            flush_string();
+        if (self.name() as StrNum) == self.str_ptr() - 1 {
+            self.flush_string();
             *self.name_mut() = self.cur_name as HalfWord;
         }
 
@@ -196,11 +236,12 @@ impl Global {
         self.line = 1;
         _ = self.input_ln(AlphaFileInSelector::CurFile)?; // Do nothing
         self.firm_up_the_line()?;
-        if end_line_char_inactive!() {
+        if end_line_char_inactive!(self) {
             *self.limit_mut() -= 1;
         }
         else {
-            self.buffer[self.limit() as usize] = end_line_char() as u8;
+            let limit = self.limit() as usize;
+            self.buffer[limit] = self.end_line_char() as u8;
         }
         self.first = self.limit() + 1;
         *self.loc_mut() = self.start();
@@ -1,18 +1,11 @@
-use crate::alignment::{
-    extra_info, extra_info_mut, v_part
-};
 use crate::constants::*;
-use crate::datastructures::{
-    Status, cat_code, end_line_char, eq_type, equiv, info, link, pausing
-};
+use crate::datastructures::Status;
 use crate::error::{TeXError, TeXResult};
 use crate::io::AlphaFileInSelector;
 use crate::{
-    Global, HalfWord, QuarterWord, StrNum, update_terminal
+    Global, HalfWord, QuarterWord, StrNum, back_list, ins_list, update_terminal
 };
 
-use std::io::Write;
-
 // Part 24: Getting the next token
 
 // Section 352
@@ -36,8 +29,8 @@ macro_rules! hex_to_cur_chr {
 // Section 360
 #[macro_export]
 macro_rules! end_line_char_inactive {
-    () => {
-        end_line_char() < 0 || end_line_char() > 255    
+    ($s:ident) => {
+        $s.end_line_char() < 0 || $s.end_line_char() > 255    
     };
 }
 
@@ -54,16 +47,58 @@ impl Global {
     // Section 336
     fn check_outer_validity(&mut self) -> TeXResult<()> {
         if self.scanner_status != Status::Normal {
+            self.deletions_allowed = false;
+            // Section 337
+            if self.cur_cs != 0 {
+                if self.state() == TOKEN_LIST || self.name() < 1 || self.name() > 17 {
+                    let p = self.get_avail()?;
+                    *self.info_mut(p) = CS_TOKEN_FLAG + self.cur_cs;
+                    back_list!(self, p);
+                }
+                self.cur_cmd = SPACER;
+                self.cur_chr = b' ' as HalfWord;
+            }
+            // End section 337
+
             if self.scanner_status != Status::Skipping {
-                Err(TeXError::FileEndedOrForbiddenCSFound)
+                // Section 338
+                let mut p = self.get_avail()?;
+                // Section 339
+                match self.scanner_status {
+                    Status::Defining | Status::Absorbing => {
+                        *self.info_mut(p) = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
+                    },
+                    Status::Matching => {
+                        *self.info_mut(p) = self.par_token;
+                        self.long_state = OUTER_CALL;
+                    },
+                    Status::Aligning => {
+                        *self.info_mut(p) = RIGHT_BRACE_TOKEN + b'}' as HalfWord;
+                        let q = p;
+                        p = self.get_avail()?;
+                        *self.link_mut(p) = q;
+                        *self.info_mut(p) = CS_TOKEN_FLAG + FROZEN_CR;
+                        self.align_state = -1_000_000;
+                    },
+                    _ => () // There are no other cases
+                }
+                ins_list!(self, p);
+                // End section 339
+                // `cur_cs` is cleared before the error, as in TeX.
+                let cs = self.cur_cs;
+                self.cur_cs = 0;
+                self.error(TeXError::FileEndedOrForbiddenCSFound(cs))?;
+                // End section 338
             }
             else {
-                Err(TeXError::IncompleteIf)
+                let cs = self.cur_cs;
+                self.cur_cs = 0;
+                self.cur_tok = CS_TOKEN_FLAG + FROZEN_FI;
+                self.ins_error(TeXError::IncompleteIf(cs))?;
             }
+            self.deletions_allowed = true;
         }
-        else {
-            Ok(())
-        }
+        Ok(())
     }
 
     // Section 341
@@ -77,10 +112,11 @@ impl Global {
                         self.cur_chr = self.buffer[self.loc() as usize] as HalfWord;
                         *self.loc_mut() += 1;
                         'reswitch: loop {
-                            self.cur_cmd = cat_code(self.cur_chr) as QuarterWord;
+                            self.cur_cmd = self.cat_code(self.cur_chr) as QuarterWord;
                             match self.sec344_change_state_if_necessary()? {
                                 Goto::Switch => continue 'switch,
                                 Goto::Reswitch => continue 'reswitch,
+                                Goto::Restart => continue 'restart,
                                 _ => break 'switch,
                             }
                         }
@@ -112,13 +148,13 @@ impl Global {
                 if self.scanner_status == Status::Aligning || self.cur_align == NULL {
                     return Err(TeXError::Fatal("(interwoven alignment preambles are not allowed)"));
                 }
-                self.cur_cmd = extra_info(self.cur_align) as QuarterWord;
-                *extra_info_mut(self.cur_align) = self.cur_chr;
+                self.cur_cmd = self.extra_info(self.cur_align) as QuarterWord;
+                *self.extra_info_mut(self.cur_align) = self.cur_chr;
                 if self.cur_cmd == OMIT {
-                    self.begin_token_list(OMIT_TEMPLATE, V_TEMPLATE)?;
+                    self.begin_token_list(self.omit_template(), V_TEMPLATE)?;
                 }
                 else {
-                    self.begin_token_list(v_part(self.cur_align), V_TEMPLATE)?;
+                    self.begin_token_list(self.v_part(self.cur_align), V_TEMPLATE)?;
                 }
                 self.align_state = 1_000_000;
                 // Goto restart
@@ -145,8 +181,8 @@ impl Global {
             (_, ACTIVE_CHAR) => {
                 // Section 353
                 self.cur_cs = self.cur_chr + ACTIVE_BASE;
-                self.cur_cmd = eq_type(self.cur_cs);
-                self.cur_chr = equiv(self.cur_cs);
+                self.cur_cmd = self.eq_type(self.cur_cs);
+                self.cur_chr = self.equiv(self.cur_cs);
                 *self.state_mut() = MID_LINE;
                 if self.cur_cmd >= OUTER_CALL {
                     self.check_outer_validity()?;
@@ -156,7 +192,11 @@ impl Global {
 
             (_, SUP_MARK) => return Ok(self.sec352_if_this_sup_mark_starts()),
 
-            (_, INVALID_CHAR) => return Err(TeXError::InvalidCharacter), // Section 346
+            // Section 346
+            (_, INVALID_CHAR) => {
+                self.error(TeXError::InvalidCharacter)?;
+                return Ok(Goto::Restart);
+            },
 
             // Section 347
             (MID_LINE, SPACER) => {
@@ -186,8 +226,8 @@ impl Global {
                 // Section 351
                 *self.loc_mut() = self.limit() + 1;
                 self.cur_cs = self.par_loc;
-                self.cur_cmd = eq_type(self.cur_cs);
-                self.cur_chr = equiv(self.cur_cs);
+                self.cur_cmd = self.eq_type(self.cur_cs);
+                self.cur_chr = self.equiv(self.cur_cs);
                 if self.cur_cmd >= OUTER_CALL {
                     self.check_outer_validity()?;
                 }
@@ -267,7 +307,7 @@ impl Global {
             'start_cs: loop {
                 let mut k = self.loc();
                 self.cur_chr = self.buffer[k as usize] as HalfWord;
-                let mut cat = cat_code(self.cur_chr);
+                let mut cat = self.cat_code(self.cur_chr);
                 k += 1;
                 if (cat as QuarterWord) == LETTER || (cat as QuarterWord) == SPACER {
                     *self.state_mut() = SKIP_BLANKS;
@@ -279,7 +319,7 @@ impl Global {
                     // Section 356
                     'sec356: loop {
                         self.cur_chr = self.buffer[k as usize] as HalfWord;
-                        cat = cat_code(self.cur_chr);
+                        cat = self.cat_code(self.cur_chr);
                         k += 1;
                         if (cat as QuarterWord) != LETTER || k > self.limit() {
                             break 'sec356;
@@ -376,8 +416,8 @@ impl Global {
         }
 
         // found:
-        self.cur_cmd = eq_type(self.cur_cs);
-        self.cur_chr = equiv(self.cur_cs);
+        self.cur_cmd = self.eq_type(self.cur_cs);
+        self.cur_chr = self.equiv(self.cur_cs);
         if self.cur_cmd >= OUTER_CALL {
             self.check_outer_validity()?;
         }
@@ -387,19 +427,19 @@ impl Global {
     // Section 357
     fn sec357_input_from_token_list(&mut self) -> TeXResult<Goto> {
         if self.loc() != NULL {
-            let t = info(self.loc());
-            *self.loc_mut() = link(self.loc());
+            let t = self.info(self.loc());
+            *self.loc_mut() = self.link(self.loc());
             if t >= CS_TOKEN_FLAG {
                 self.cur_cs = t - CS_TOKEN_FLAG;
-                self.cur_cmd = eq_type(self.cur_cs);
-                self.cur_chr = equiv(self.cur_cs);
+                self.cur_cmd = self.eq_type(self.cur_cs);
+                self.cur_chr = self.equiv(self.cur_cs);
                 if self.cur_cmd >= OUTER_CALL {
                     if self.cur_cmd == DONT_EXPAND {
                         // Section 358
-                        self.cur_cs = info(self.loc()) - CS_TOKEN_FLAG;
+                        self.cur_cs = self.info(self.loc()) - CS_TOKEN_FLAG;
                         *self.loc_mut() = NULL;
-                        self.cur_cmd = eq_type(self.cur_cs);
-                        self.cur_chr = equiv(self.cur_cs);
+                        self.cur_cmd = self.eq_type(self.cur_cs);
+                        self.cur_chr = self.equiv(self.cur_cs);
                         if self.cur_cmd > MAX_COMMAND {
                             self.cur_cmd = RELAX;
                             self.cur_chr = NO_EXPAND_FLAG;
@@ -451,17 +491,18 @@ impl Global {
             if self.force_eof {
                 self.print_char(b')');
                 self.open_parens -= 1;
-                update_terminal!();
+                update_terminal!(self);
                 self.force_eof = false;
                 self.end_file_reading();
                 self.check_outer_validity()?;
                 return Ok(Goto::Restart);
             }
-            if end_line_char_inactive!() {
+            if end_line_char_inactive!(self) {
                 *self.limit_mut() -= 1;
             }
             else {
-                self.buffer[self.limit() as usize] = end_line_char() as u8;
+                let limit = self.limit() as usize;
+                self.buffer[limit] = self.end_line_char() as u8;
             }
             self.first = self.limit() + 1;
             *self.loc_mut() = self.start();
@@ -481,8 +522,8 @@ impl Global {
             if self.selector < LOG_ONLY {
                 self.open_log_file()?;
             }
-            if self.interaction > BATCH_MODE {
-                if end_line_char_inactive!() {
+            if self.interaction > NONSTOP_MODE {
+                if end_line_char_inactive!(self) {
                     *self.limit_mut() += 1;
                 }
                 if self.limit() == self.start() {
@@ -492,11 +533,12 @@ impl Global {
                 self.first = self.start();
                 self.prompt_input("*")?;
                 *self.limit_mut() = self.last;
-                if end_line_char_inactive!() {
+                if end_line_char_inactive!(self) {
                     *self.limit_mut() -= 1;
                 }
                 else {
-                    self.buffer[self.limit() as usize] = end_line_char() as u8;
+                    let limit = self.limit() as usize;
+                    self.buffer[limit] = self.end_line_char() as u8;
                 }
                 self.first = self.limit() + 1;
                 *self.loc_mut() = self.start();
@@ -511,7 +553,7 @@ impl Global {
     // Section 363
     pub(crate) fn firm_up_the_line(&mut self) -> TeXResult<()> {
         *self.limit_mut() = self.last;
-        if pausing() > 0 && self.interaction > BATCH_MODE {
+        if self.pausing() > 0 && self.interaction > NONSTOP_MODE {
             self.print_ln();
             if self.start() < self.limit() {
                 for k in self.start()..self.limit() {
@@ -522,7 +564,8 @@ impl Global {
             self.prompt_input("=>")?;
             if self.last > self.first {
                 for k in self.first..self.last {
-                    self.buffer[(k + self.start() - self.first) as usize] = self.buffer[k as usize];
+                    let j = (k + self.start() - self.first) as usize;
+                    self.buffer[j] = self.buffer[k as usize];
                 }
                 *self.limit_mut() = self.start() + self.last - self.first;
             }
@@ -1,11 +1,6 @@
 use crate::constants::*;
-use crate::datastructures::{
-    MemoryWord, info_mut, language, lc_code, link_mut
-};
+use crate::datastructures::MemoryWord;
 use crate::error::{TeXError, TeXResult};
-use crate::strings::{
-    str_pool, append_char, length, make_string, str_room, str_start
-};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, SmallNumber
 };
@@ -44,11 +39,11 @@ impl Global {
 
     // Section 934
     pub(crate) fn set_cur_lang(&mut self) {
-        self.cur_lang = if language() <= 0 || language() > 255 {
+        self.cur_lang = if self.language() <= 0 || self.language() > 255 {
             0
         }
         else {
-            language() as u8
+            self.language() as u8
         };
     }
 
@@ -70,19 +65,19 @@ impl Global {
                             // Section 938
                             if n < 63 {
                                 let q = self.get_avail()?;
-                                *link_mut(q) = p;
-                                *info_mut(q) = n;
+                                *self.link_mut(q) = p;
+                                *self.info_mut(q) = n;
                                 p = q;
                             }
                             // End section 938
                         }
                         else {
-                            if lc_code(self.cur_chr) == 0 {
-                                return Err(TeXError::NotALetter);
+                            if self.lc_code(self.cur_chr) == 0 {
+                                self.error(TeXError::NotALetter)?;
                             }
-                            if n < 63 {
+                            else if n < 63 {
                                 n += 1;
-                                self.hc[n as usize] = lc_code(self.cur_chr) as QuarterWord;
+                                self.hc[n as usize] = self.lc_code(self.cur_chr) as QuarterWord;
                             }
                         }
                         // End section 937
@@ -102,13 +97,13 @@ impl Global {
                             // Section 939
                             n += 1;
                             self.hc[n as usize] = self.cur_lang as QuarterWord;
-                            str_room(n as usize)?;
+                            self.str_room(n as usize)?;
                             let mut h = 0;
                             for j in 1..=(n as usize) {
                                 h = (h + h + self.hc[j]) % HYPH_SIZE as QuarterWord;
-                                append_char(self.hc[j] as u8);
+                                self.append_char(self.hc[j] as u8);
                             }
-                            let mut s = make_string()?;
+                            let mut s = self.make_string()?;
                             // Section 940
                             if self.hyph_count == HYPH_SIZE {
                                 return Err(TeXError::Overflow("exception dictionary", HYPH_SIZE));
@@ -117,21 +112,21 @@ impl Global {
                             while self.hyph_word[h as usize] != 0 {
                                 // Section 941
                                 let k = self.hyph_word[h as usize];
-                                let found = match length(k).cmp(&length(s)) {
+                                let found = match self.length(k).cmp(&self.length(s)) {
                                     Less => true,
                                     Greater => false,
                                     Equal => {
-                                        let (mut u, mut v) = (str_start(k), str_start(s));
+                                        let (mut u, mut v) = (self.str_start(k), self.str_start(s));
                                         loop {
-                                            if str_pool(u) < str_pool(v) {
+                                            if self.str_pool(u) < self.str_pool(v) {
                                                 break true; // Goto found
                                             }
-                                            if str_pool(u) > str_pool(v) {
+                                            if self.str_pool(u) > self.str_pool(v) {
                                                 break false; // Goto not_found
                                             }
                                             u += 1;
                                             v += 1;
-                                            if u == str_start(k + 1) {
+                                            if u == self.str_start(k + 1) {
                                                 break true;
                                             }
                                         }
@@ -164,7 +159,11 @@ impl Global {
                         break 'reswitch;
                     },
 
-                    _ => return Err(TeXError::ImproperHyphenation)
+                    _ => {
+                        // Section 936
+                        self.error(TeXError::ImproperHyphenation)?;
+                        break 'reswitch;
+                    }
                 }
             }
         }
@@ -246,7 +245,7 @@ impl Global {
             + 1009*(self.trie_o[p] as Integer)
             + 2718*(self.trie_l[p] as Integer)
             + 3142*(self.trie_r[p] as Integer)
-        ).abs() % TRIE_SIZE;
+        ).abs() % self.trie_size;
 
         loop {
             let q = self.trie_hash[h as usize];
@@ -265,7 +264,7 @@ impl Global {
                 h - 1
             }
             else {
-                TRIE_SIZE
+                self.trie_size
             };
         }
     }
@@ -302,11 +301,11 @@ macro_rules! trie_back_mut {
     };
 }
 
-pub(crate) struct TrieTaken([bool; TRIE_SIZE as usize]);
+pub(crate) struct TrieTaken(Vec<bool>);
 
-impl Default for TrieTaken {
-    fn default() -> Self {
-        Self([false; TRIE_SIZE as usize])
+impl TrieTaken {
+    pub(crate) fn new(trie_size: Integer) -> Self {
+        Self(vec![false; trie_size as usize])
     }
 }
 
@@ -332,8 +331,8 @@ impl Global {
             let h = z - c;
             // Section 954
             if self.trie_max < h + 256 {
-                if TRIE_SIZE as usize <= h + 256 {
-                    return Err(TeXError::Overflow("pattern memory", TRIE_SIZE));
+                if self.trie_size as usize <= h + 256 {
+                    return Err(TeXError::Overflow("pattern memory", self.trie_size));
                 }
                 'inner: loop {
                     self.trie_max += 1;
@@ -449,7 +448,14 @@ impl Global {
             self.sec961_enter_all_of_the_patterns()
         }
         else {
-            Err(TeXError::TooLateForPatterns)
+            self.error(TeXError::TooLateForPatterns)?;
+            loop {
+                self.get_token()?;
+                if self.cur_cmd == RIGHT_BRACE {
+                    break;
+                }
+            }
+            Ok(())
         }
     }
 
@@ -472,9 +478,9 @@ impl Global {
                             self.cur_chr = 0;
                         }
                         else {
-                            self.cur_chr = lc_code(self.cur_chr);
+                            self.cur_chr = self.lc_code(self.cur_chr);
                             if self.cur_chr == 0 {
-                                return Err(TeXError::Nonletter);
+                                self.error(TeXError::Nonletter)?;
                             }
                         }
                         if k < 63 {
@@ -532,8 +538,8 @@ impl Global {
                             }
                             if p == 0 || c < self.trie_c[p] as QuarterWord {
                                 // Section 964
-                                if self.trie_ptr == TRIE_SIZE as usize {
-                                    return Err(TeXError::Overflow("pattern memory", TRIE_SIZE));
+                                if self.trie_ptr == self.trie_size as usize {
+                                    return Err(TeXError::Overflow("pattern memory", self.trie_size));
                                 }
                                 self.trie_ptr += 1;
                                 self.trie_r[self.trie_ptr] = p;
@@ -552,7 +558,7 @@ impl Global {
                             q = p;
                         }
                         if self.trie_o[q] != MIN_QUARTERWORD {
-                            return Err(TeXError::DuplicatePattern);
+                            self.error(TeXError::DuplicatePattern)?;
                         }
                         self.trie_o[q] = v;
                         // End section 963
@@ -565,7 +571,7 @@ impl Global {
                     digit_sensed = false;
                 },
 
-                _ => return Err(TeXError::BadPatterns)
+                _ => self.error(TeXError::BadPatterns)?
             }
         }
         // done:
@@ -612,7 +618,7 @@ impl Global {
         }
         // End section 945
 
-        self.trie_hash[0..=(TRIE_SIZE as usize)].fill(0);
+        self.trie_hash[0..=(self.trie_size as usize)].fill(0);
         trie_root!() = self.compress_trie(trie_root!());
         for p in 0..=self.trie_ptr {
             trie_ref![self, p] = 0;
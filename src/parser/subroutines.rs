@@ -1,12 +1,4 @@
-use crate::arithmetic::{mult_and_add, xn_over_d};
 use crate::constants::*;
-use crate::datastructures::{
-    eqtb, mem, count, cur_font, depth_mut, dimen, equiv, glue_ptr,
-    glue_ref_count_mut, height_mut, info, info_mut, link, link_mut, mag,
-    math_code, mu_skip, par_shape_ptr, penalty, r#box, r#type, shrink,
-    shrink_mut, shrink_order_mut, skip, stretch, stretch_mut,
-    stretch_order_mut, subtype, width, width_mut
-};
 use crate::error::{TeXError, TeXResult};
 use crate::{
     Global, HalfWord, Integer, QuarterWord, Scaled, SmallNumber,
@@ -68,10 +60,14 @@ impl Global {
     // Section 403
     pub(crate) fn scan_left_brace(&mut self) -> TeXResult<()> {
         sec404_get_next_nonblank_nonrelax_noncall_token!(self);
-        match self.cur_cmd {
-            LEFT_BRACE => Ok(()),
-            _ => Err(TeXError::MissingLeftBrace)
+        if self.cur_cmd != LEFT_BRACE {
+            self.back_error(TeXError::MissingLeftBrace)?;
+            self.cur_tok = LEFT_BRACE_TOKEN + b'{' as HalfWord;
+            self.cur_cmd = LEFT_BRACE;
+            self.cur_chr = b'{' as HalfWord;
+            self.align_state += 1;
         }
+        Ok(())
     }
     
     // Section 405
@@ -85,8 +81,8 @@ impl Global {
 
     // Section 407
     pub(crate) fn scan_keyword(&mut self, s: &[u8]) -> TeXResult<bool> {
-        let mut p = BACKUP_HEAD;
-        *link_mut(p) = NULL;
+        let mut p = self.backup_head();
+        *self.link_mut(p) = NULL;
         let mut k = 0;
         while k < s.len() {
             self.get_x_token()?;
@@ -94,15 +90,15 @@ impl Global {
                 self.store_new_token(&mut p, self.cur_tok)?;
                 k += 1;
             }
-            else if self.cur_cmd != SPACER || p != BACKUP_HEAD {
+            else if self.cur_cmd != SPACER || p != self.backup_head() {
                 self.back_input()?;
-                if p != BACKUP_HEAD {
-                    back_list!(self, link(BACKUP_HEAD));
+                if p != self.backup_head() {
+                    back_list!(self, self.link(self.backup_head()));
                 }
                 return Ok(false);
             }
         }
-        self.flush_list(link(BACKUP_HEAD));
+        self.flush_list(self.link(self.backup_head()));
         Ok(true)
     }
 
@@ -114,9 +110,9 @@ impl Global {
                 // Section 414
                 self.scan_char_num()?;
                 match m.cmp(&MATH_CODE_BASE) {
-                    Equal => scanned_result!(self, ho!(math_code(self.cur_val)), INT_VAL),
-                    Less => scanned_result!(self, equiv(m + self.cur_val), INT_VAL),
-                    Greater => scanned_result!(self, eqtb((m + self.cur_val) as usize).int(), INT_VAL)
+                    Equal => scanned_result!(self, ho!(self.math_code(self.cur_val)), INT_VAL),
+                    Less => scanned_result!(self, self.equiv(m + self.cur_val), INT_VAL),
+                    Greater => scanned_result!(self, self.eqtb((m + self.cur_val) as usize).int(), INT_VAL)
                 }
                 // End section 414
             },
@@ -128,14 +124,15 @@ impl Global {
             | DEF_FONT => {
                 // Section 415
                 if (level as Integer) != TOK_VAL {
-                    return Err(TeXError::MissingNumber);
+                    self.back_error(TeXError::MissingNumber)?;
+                    scanned_result!(self, 0, DIMEN_VAL);
                 }
-                if self.cur_cmd <= ASSIGN_TOKS {
+                else if self.cur_cmd <= ASSIGN_TOKS {
                     if self.cur_cmd < ASSIGN_TOKS {
                         self.scan_eight_bit_int()?;
                         m = TOKS_BASE + self.cur_val;
                     }
-                    scanned_result!(self, equiv(m), TOK_VAL);
+                    scanned_result!(self, self.equiv(m), TOK_VAL);
                 }
                 else {
                     self.back_input()?;
@@ -145,20 +142,26 @@ impl Global {
                 // End section 415
             },
 
-            ASSIGN_INT => scanned_result!(self, eqtb(m as usize).int(), INT_VAL),
+            ASSIGN_INT => scanned_result!(self, self.eqtb(m as usize).int(), INT_VAL),
 
-            ASSIGN_DIMEN => scanned_result!(self, eqtb(m as usize).sc(), DIMEN_VAL),
+            ASSIGN_DIMEN => scanned_result!(self, self.eqtb(m as usize).sc(), DIMEN_VAL),
 
-            ASSIGN_GLUE => scanned_result!(self, equiv(m), GLUE_VAL),
+            ASSIGN_GLUE => scanned_result!(self, self.equiv(m), GLUE_VAL),
 
-            ASSIGN_MU_GLUE => scanned_result!(self, equiv(m), MU_VAL),
+            ASSIGN_MU_GLUE => scanned_result!(self, self.equiv(m), MU_VAL),
 
             SET_AUX => {
                 // Section 418
                 if self.mode().abs() != m {
-                    return Err(TeXError::ImproperMode(m ));
+                    self.error(TeXError::ImproperMode(m))?;
+                    if (level as Integer) != TOK_VAL {
+                        scanned_result!(self, 0, DIMEN_VAL);
+                    }
+                    else {
+                        scanned_result!(self, 0, INT_VAL);
+                    }
                 }
-                if m == VMODE {
+                else if m == VMODE {
                     scanned_result!(self, self.prev_depth(), DIMEN_VAL);
                 }
                 else {
@@ -211,9 +214,9 @@ impl Global {
 
             SET_SHAPE => {
                 // Section 423
-                self.cur_val = match par_shape_ptr() {
+                self.cur_val = match self.par_shape_ptr() {
                     NULL => 0,
-                    _ => info(par_shape_ptr()),
+                    _ => self.info(self.par_shape_ptr()),
                 };
                 self.cur_val_level = INT_VAL;
                 // End section 423
@@ -222,9 +225,9 @@ impl Global {
             SET_BOX_DIMEN => {
                 // Section 420
                 self.scan_eight_bit_int()?;
-                self.cur_val = match r#box(self.cur_val) {
+                self.cur_val = match self.r#box(self.cur_val) {
                     NULL => 0,
-                    _ => mem((r#box(self.cur_val) + m) as usize).sc(),
+                    _ => self.mem((self.r#box(self.cur_val) + m) as usize).sc(),
                 };
                 self.cur_val_level = DIMEN_VAL;
                 // End section 420
@@ -257,10 +260,10 @@ impl Global {
                 // Section 427
                 self.scan_eight_bit_int()?;
                 match m {
-                    INT_VAL => self.cur_val = count(self.cur_val),
-                    DIMEN_VAL => self.cur_val = dimen(self.cur_val),
-                    GLUE_VAL => self.cur_val = skip(self.cur_val),
-                    MU_VAL => self.cur_val = mu_skip(self.cur_val),
+                    INT_VAL => self.cur_val = self.count(self.cur_val),
+                    DIMEN_VAL => self.cur_val = self.dimen(self.cur_val),
+                    GLUE_VAL => self.cur_val = self.skip(self.cur_val),
+                    MU_VAL => self.cur_val = self.mu_skip(self.cur_val),
                     _ => () // There are no other cases
                 }
                 self.cur_val_level = m;
@@ -269,16 +272,25 @@ impl Global {
 
             LAST_ITEM => self.sec424_fetch_item_in_current_node(),
 
-            _ => return Err(TeXError::CantUseAfterThe),
+            _ => {
+                // Section 428
+                self.error(TeXError::CantUseAfterThe)?;
+                if (level as Integer) != TOK_VAL {
+                    scanned_result!(self, 0, DIMEN_VAL);
+                }
+                else {
+                    scanned_result!(self, 0, INT_VAL);
+                }
+            }
         }
 
         while self.cur_val_level > level as Integer {
             // Section 429
             if self.cur_val_level == GLUE_VAL {
-                self.cur_val = width(self.cur_val);
+                self.cur_val = self.width(self.cur_val);
             }
             else if self.cur_val_level == MU_VAL {
-                return Err(TeXError::IncompatibleGlueUnits);
+                self.error(TeXError::IncompatibleGlueUnits)?; // mu_error
             }
             self.cur_val_level -= 1;
             // End section 429
@@ -289,9 +301,9 @@ impl Global {
             if self.cur_val_level >= GLUE_VAL {
                 self.cur_val = self.new_spec(self.cur_val)?;
                 // Section 431 
-                *width_mut(self.cur_val) = -width(self.cur_val);
-                *stretch_mut(self.cur_val) = -stretch(self.cur_val);
-                *shrink_mut(self.cur_val) = -shrink(self.cur_val);
+                *self.width_mut(self.cur_val) = -self.width(self.cur_val);
+                *self.stretch_mut(self.cur_val) = -self.stretch(self.cur_val);
+                *self.shrink_mut(self.cur_val) = -self.shrink(self.cur_val);
                 // End section 431
             }
             else {
@@ -299,7 +311,7 @@ impl Global {
             }
         }
         else if self.cur_val_level >= GLUE_VAL && self.cur_val_level <= MU_VAL {
-            add_glue_ref!(self.cur_val);
+            add_glue_ref!(self, self.cur_val);
         }
         // End section 430
 
@@ -325,21 +337,21 @@ impl Global {
             if !self.is_char_node(self.tail()) && self.mode() != 0 {
                 match self.cur_chr {
                     INT_VAL => {
-                        if r#type(self.tail()) == PENALTY_NODE {
-                            self.cur_val = penalty(self.tail());
+                        if self.r#type(self.tail()) == PENALTY_NODE {
+                            self.cur_val = self.penalty(self.tail());
                         }
                     },
 
                     DIMEN_VAL => {
-                        if r#type(self.tail()) == KERN_NODE {
-                            self.cur_val = width(self.tail());
+                        if self.r#type(self.tail()) == KERN_NODE {
+                            self.cur_val = self.width(self.tail());
                         }
                     },
 
                     GLUE_VAL => {
-                        if r#type(self.tail()) == GLUE_NODE {
-                            self.cur_val = glue_ptr(self.tail());
-                            if subtype(self.tail()) == MU_GLUE {
+                        if self.r#type(self.tail()) == GLUE_NODE {
+                            self.cur_val = self.glue_ptr(self.tail());
+                            if self.subtype(self.tail()) == MU_GLUE {
                                 self.cur_val_level = MU_VAL;
                             }
                         }
@@ -369,56 +381,51 @@ impl Global {
     // Section 433
     pub(crate) fn scan_eight_bit_int(&mut self) -> TeXResult<()> {
         self.scan_int()?;
-        if (0..=255).contains(&self.cur_val) {
-            Ok(())
-        }
-        else {
-            Err(TeXError::BadRegisterCode)
+        if !(0..=255).contains(&self.cur_val) {
+            self.error(TeXError::BadRegisterCode)?;
+            self.cur_val = 0;
         }
+        Ok(())
     }
 
     // Section 434
     pub(crate) fn scan_char_num(&mut self) -> TeXResult<()> {
         self.scan_int()?;
-        if (0..=255).contains(&self.cur_val) {
-            Ok(())
-        }
-        else {
-            Err(TeXError::BadCharacterCode)
+        if !(0..=255).contains(&self.cur_val) {
+            self.error(TeXError::BadCharacterCode)?;
+            self.cur_val = 0;
         }
+        Ok(())
     }
 
     // Section 435
     pub(crate) fn scan_four_bit_int(&mut self) -> TeXResult<()> {
         self.scan_int()?;
-        if (0..=15).contains(&self.cur_val) {
-            Ok(())
-        }
-        else {
-            Err(TeXError::BadNumber)
+        if !(0..=15).contains(&self.cur_val) {
+            self.error(TeXError::BadNumber)?;
+            self.cur_val = 0;
         }
+        Ok(())
     }
 
     // Section 436
     pub(crate) fn scan_fifteen_bit_int(&mut self) -> TeXResult<()> {
         self.scan_int()?;
-        if (0..=0x7fff).contains(&self.cur_val) {
-            Ok(())
-        }
-        else {
-            Err(TeXError::BadMathChar)
+        if !(0..=0x7fff).contains(&self.cur_val) {
+            self.error(TeXError::BadMathChar)?;
+            self.cur_val = 0;
         }
+        Ok(())
     }
 
     // Section 437
     pub(crate) fn scan_twenty_seven_bit_int(&mut self) -> TeXResult<()> {
         self.scan_int()?;
-        if (0..=0x7ff_ffff).contains(&self.cur_val) {
-            Ok(())
-        }
-        else {
-            Err(TeXError::BadDelimiterCode)
+        if !(0..=0x7ff_ffff).contains(&self.cur_val) {
+            self.error(TeXError::BadDelimiterCode)?;
+            self.cur_val = 0;
         }
+        Ok(())
     }
 
     // Section 440
@@ -464,9 +471,10 @@ impl Global {
             self.sec445_accumulate_constant_until(&mut vacuous, m)?;
             
             if vacuous {
-                return Err(TeXError::MissingNumber);
+                // Section 446
+                self.back_error(TeXError::MissingNumber)?;
             }
-            if self.cur_cmd != SPACER {
+            else if self.cur_cmd != SPACER {
                 self.back_input()?;
             }
             // End section 444
@@ -499,16 +507,18 @@ impl Global {
         };
 
         if self.cur_val > 255 {
-            Err(TeXError::ImproperAlphabeticConstant)
+            self.cur_val = b'0' as Integer;
+            self.back_error(TeXError::ImproperAlphabeticConstant)?;
         }
         else {
             sec443_scan_an_optional_space!(self);
-            Ok(())
         }
+        Ok(())
     }
 
     // Section 445
     fn sec445_accumulate_constant_until(&mut self, vacuous: &mut bool, m: Integer) -> TeXResult<()> {
+        let mut ok_so_far = true;
         loop {
             let d = if self.cur_tok < ZERO_TOKEN + self.radix && self.cur_tok >= ZERO_TOKEN && self.cur_tok <= ZERO_TOKEN + 9 {
                 self.cur_tok - ZERO_TOKEN
@@ -532,9 +542,15 @@ impl Global {
             if self.cur_val >= m
                 && (self.cur_val > m || d > 7 || self.radix != 10)
             {
-                return Err(TeXError::NumberTooBig);
+                if ok_so_far {
+                    self.error(TeXError::NumberTooBig)?;
+                    self.cur_val = INFINITY;
+                    ok_so_far = false;
+                }
+            }
+            else {
+                self.cur_val = self.cur_val * self.radix + d;
             }
-            self.cur_val = self.cur_val * self.radix + d;
             self.get_x_token()?;
         }
         // done:
@@ -544,6 +560,7 @@ impl Global {
     // Section 448
     pub(crate) fn scan_dimen(&mut self, mu: bool, inf: bool, shortcut: bool) -> TeXResult<()> {
         let mut f = 0;
+        self.arith_error = false;
         self.cur_order = NORMAL;
         let mut negative = false;
 
@@ -570,7 +587,7 @@ impl Global {
 
                         // Section 451
                         if self.cur_val_level >= GLUE_VAL {
-                            let v = width(self.cur_val);
+                            let v = self.width(self.cur_val);
                             self.delete_glue_ref(self.cur_val);
                             self.cur_val = v;
                         }
@@ -581,7 +598,7 @@ impl Global {
                         }
 
                         if self.cur_val_level != INT_VAL {
-                            return Err(TeXError::IncompatibleGlueUnits);
+                            self.error(TeXError::IncompatibleGlueUnits)?; // mu_error
                         }
                     }
                     else {
@@ -624,8 +641,11 @@ impl Global {
         }
 
         // attach_sign:
-        if self.cur_val.abs() >= 0x4000_0000 {
-            return Err(TeXError::DimensionTooLarge);
+        if self.arith_error || self.cur_val.abs() >= 0x4000_0000 {
+            // Section 460
+            self.error(TeXError::DimensionTooLarge)?;
+            self.cur_val = MAX_DIMEN;
+            self.arith_error = false;
         }
         if negative {
             self.cur_val = -self.cur_val
@@ -645,8 +665,8 @@ impl Global {
             }
             if k < 17 {
                 let q = self.get_avail()?;
-                *link_mut(q) = p;
-                *info_mut(q) = self.cur_tok - ZERO_TOKEN;
+                *self.link_mut(q) = p;
+                *self.info_mut(q) = self.cur_tok - ZERO_TOKEN;
                 p = q;
                 k += 1;
             }
@@ -654,9 +674,9 @@ impl Global {
 
         // done1:
         for kk in (1..=k).rev() {
-            self.dig[kk - 1] = info(p) as u8;
+            self.dig[kk - 1] = self.info(p) as u8;
             let q = p;
-            p = link(p);
+            p = self.link(p);
             free_avail!(self, q);
         }
         let f = self.round_decimals(k);
@@ -676,9 +696,11 @@ impl Global {
                     self.cur_order = FIL;
                     while self.scan_keyword(b"l")? {
                         if self.cur_order == FILLL {
-                            return Err(TeXError::IllegalUnitOfMeasureFilll);
+                            self.error(TeXError::IllegalUnitOfMeasureFilll)?;
+                        }
+                        else {
+                            self.cur_order += 1;
                         }
-                        self.cur_order += 1;
                     }
                     break 'block; // Goto attach_fraction
                 }
@@ -691,20 +713,20 @@ impl Global {
 
             if mu {
                 // Section 456
-                match self.scan_keyword(b"mu")? {
-                    true => break 'block, // Goto attach_fraction
-                    false => return Err(TeXError::IllegalUnitOfMeasureMu),
+                if !self.scan_keyword(b"mu")? {
+                    self.error(TeXError::IllegalUnitOfMeasureMu)?;
                 }
+                break 'block; // Goto attach_fraction
                 // End section 456
             }
 
             if self.scan_keyword(b"true")? {
                 // Section 457
                 self.prepare_mag()?;
-                if mag() != 1000 {
+                if self.mag() != 1000 {
                     let rem: Scaled;
-                    (self.cur_val, rem) = xn_over_d(self.cur_val, 1000, mag())?;
-                    f = (1000 * f + 65536 * rem) / mag();
+                    (self.cur_val, rem) = self.xn_over_d(self.cur_val, 1000, self.mag());
+                    f = (1000 * f + 65536 * rem) / self.mag();
                     self.cur_val += f / 65536;
                     f %= 65536;
                 }
@@ -721,13 +743,13 @@ impl Global {
         }
 
         // attach_fraction:
-        match self.cur_val.cmp(&16384) {
-            Less => {
-                self.cur_val = self.cur_val * UNITY + f;
-                Ok(false)
-            },
-            _ => Err(TeXError::Arith)
+        if self.cur_val >= 16384 {
+            self.arith_error = true;
+        }
+        else {
+            self.cur_val = self.cur_val * UNITY + f;
         }
+        Ok(false)
     }
 
     // Section 455
@@ -744,13 +766,13 @@ impl Global {
                     self.scan_something_internal(MU_VAL as QuarterWord, false)?;
                     // Section 451
                     if self.cur_val_level >= GLUE_VAL {
-                        let v = width(self.cur_val);
+                        let v = self.width(self.cur_val);
                         self.delete_glue_ref(self.cur_val);
                         self.cur_val = v;
                     }
                     // End section 451
                     if self.cur_val_level != MU_VAL {
-                        return Err(TeXError::IncompatibleGlueUnits);
+                        self.error(TeXError::IncompatibleGlueUnits)?; // mu_error
                     }
                 }
                 else {
@@ -765,12 +787,12 @@ impl Global {
 
             let v = if self.scan_keyword(b"em")? {
                 // Section 558
-                self.quad(cur_font() as QuarterWord)
+                self.quad(self.cur_font() as QuarterWord)
                 // End section 558
             }
             else if self.scan_keyword(b"ex")? {
                 // Section 559
-                self.x_height(cur_font() as QuarterWord)
+                self.x_height(self.cur_font() as QuarterWord)
                 // End section 559
             }
             else {
@@ -781,8 +803,8 @@ impl Global {
         };
 
         // found:
-        let (quo, _) = xn_over_d(v, f, 65536)?;
-        self.cur_val = nx_plus_y!(save_cur_val, v, quo);
+        let (quo, _) = self.xn_over_d(v, f, 65536);
+        self.cur_val = nx_plus_y!(self, save_cur_val, v, quo);
         Ok(true)
     }
 
@@ -814,11 +836,13 @@ impl Global {
             return Ok(true); // Goto done in section 453
         }
         else {
-            return Err(TeXError::IllegalUnitOfMeasurePt);
+            // Section 459
+            self.error(TeXError::IllegalUnitOfMeasurePt)?;
+            return Ok(false); // Goto attach_fraction
         };
 
         let rem: Scaled;
-        (self.cur_val, rem) = xn_over_d(self.cur_val, num, denom)?;
+        (self.cur_val, rem) = self.xn_over_d(self.cur_val, num, denom);
         *f = (num * (*f) + 65536 * rem) / denom;
         self.cur_val += *f / 65536;
         *f %= 65536;
@@ -847,7 +871,7 @@ impl Global {
             self.scan_something_internal(level, negative)?;
             if self.cur_val_level >= GLUE_VAL {
                 if self.cur_val_level != (level as Integer) {
-                    return Err(TeXError::IncompatibleGlueUnits);
+                    self.error(TeXError::IncompatibleGlueUnits)?; // mu_error
                 }
                 return Ok(());
             }
@@ -855,7 +879,7 @@ impl Global {
                 self.scan_dimen(mu, false, true)?;
             }
             else if level == (MU_VAL as QuarterWord) {
-                return Err(TeXError::IncompatibleGlueUnits);
+                self.error(TeXError::IncompatibleGlueUnits)?; // mu_error
             }
         }
         else {
@@ -868,16 +892,16 @@ impl Global {
 
         // Section 462
         let q = self.new_spec(ZERO_GLUE)?;
-        *width_mut(q) = self.cur_val;
+        *self.width_mut(q) = self.cur_val;
         if self.scan_keyword(b"plus")? {
             self.scan_dimen(mu, true, false)?;
-            *stretch_mut(q) = self.cur_val;
-            *stretch_order_mut(q) = self.cur_order;
+            *self.stretch_mut(q) = self.cur_val;
+            *self.stretch_order_mut(q) = self.cur_order;
         }
         if self.scan_keyword(b"minus")? {
             self.scan_dimen(mu, true, false)?;
-            *shrink_mut(q) = self.cur_val;
-            *shrink_order_mut(q) = self.cur_order;
+            *self.shrink_mut(q) = self.cur_val;
+            *self.shrink_order_mut(q) = self.cur_order;
         }
         self.cur_val = q;
         // End section 462
@@ -889,28 +913,28 @@ impl Global {
     pub(crate) fn scan_rule_spec(&mut self) -> TeXResult<HalfWord> {
         let q = self.new_rule()?;
         if self.cur_cmd == VRULE {
-            *width_mut(q) = DEFAULT_RULE;
+            *self.width_mut(q) = DEFAULT_RULE;
         }
         else {
-            *height_mut(q) = DEFAULT_RULE;
-            *depth_mut(q) = 0;
+            *self.height_mut(q) = DEFAULT_RULE;
+            *self.depth_mut(q) = 0;
         }
 
         // reswitch:
         loop {
             if self.scan_keyword(b"width")? {
                 self.scan_dimen(false, false, false)?;
-                *width_mut(q) = self.cur_val;
+                *self.width_mut(q) = self.cur_val;
                 continue;
             }
             if self.scan_keyword(b"height")? {
                 self.scan_dimen(false, false, false)?;
-                *height_mut(q) = self.cur_val;
+                *self.height_mut(q) = self.cur_val;
                 continue;
             }
             if self.scan_keyword(b"depth")? {
                 self.scan_dimen(false, false, false)?;
-                *depth_mut(q) = self.cur_val;
+                *self.depth_mut(q) = self.cur_val;
                 continue;
             }
             break;
@@ -926,17 +950,20 @@ impl Global {
     pub(crate) fn scan_font_ident(&mut self) -> TeXResult<()> {
         sec406_get_next_nonblank_noncall_token!(self);
         self.cur_val = match self.cur_cmd {
-            DEF_FONT => cur_font(),
+            DEF_FONT => self.cur_font(),
 
             SET_FONT => self.cur_chr,
 
             DEF_FAMILY => {
                 let m = self.cur_chr;
                 self.scan_four_bit_int()?;
-                equiv(m + self.cur_val)
+                self.equiv(m + self.cur_val)
             },
 
-            _ => return Err(TeXError::MissingFontIdentifier)
+            _ => {
+                self.back_error(TeXError::MissingFontIdentifier)?;
+                NULL_FONT
+            }
         };
         Ok(())
     }
@@ -962,8 +989,8 @@ impl Global {
                 else {
                     // Section 580
                     loop {
-                        if self.fmem_ptr == FONT_MEM_SIZE as usize {
-                            return Err(TeXError::Overflow("font memory", FONT_MEM_SIZE));
+                        if self.fmem_ptr == self.font_mem_size as usize {
+                            return Err(TeXError::Overflow("font memory", self.font_mem_size));
                         }
                         *self.font_info[self.fmem_ptr].sc_mut() = 0;
                         self.fmem_ptr += 1;
@@ -983,11 +1010,9 @@ impl Global {
 
         // Section 579
         if self.cur_val == (self.fmem_ptr as Integer) {
-            Err(TeXError::FontHasOnly(f as QuarterWord))
-        }
-        else {
-            Ok(())
+            self.error(TeXError::FontHasOnly(f as QuarterWord))?;
         }
+        Ok(())
     }
 
     // Section 645
@@ -0,0 +1,5 @@
+mod font_map;
+mod pdf_file;
+mod type1;
+
+pub(crate) use pdf_file::PdfFile;
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+// Map files in the style of pdftex.map tell which Type 1 font
+// and which encoding go with a TFM file, one font per line:
+//   tfmname [psname] ["special instructions"] [<encoding.enc] [<fontfile.pfb]
+// Lines starting with '%', '#', ';' or '*' are comments.
+// The special instructions (such as "SlantFont") are not supported.
+#[derive(Default)]
+pub(crate) struct FontMapEntry {
+    pub(crate) ps_name: Option<String>,
+    pub(crate) encoding: Option<String>,
+    pub(crate) font_file: Option<String>,
+}
+
+// Entries of a map file, by TFM name. The first line for a name wins.
+pub(crate) fn parse_font_map(text: &str) -> BTreeMap<String, FontMapEntry> {
+    let mut map = BTreeMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['%', '#', ';', '*']) {
+            continue;
+        }
+        let mut tokens = tokens(line).into_iter();
+        let mut tfm_name = None;
+        let mut entry = FontMapEntry::default();
+        while let Some(token) = tokens.next() {
+            if token.starts_with('"') {
+                continue;
+            }
+            if token.starts_with('<') {
+                // The file name may be separated from '<' by spaces.
+                let mut file = token.trim_start_matches(['<', '[']);
+                if file.is_empty() {
+                    file = tokens.next().unwrap_or_default();
+                }
+                if file.ends_with(".enc") {
+                    entry.encoding = Some(file.to_string());
+                }
+                else {
+                    entry.font_file = Some(file.to_string());
+                }
+            }
+            else if tfm_name.is_none() {
+                tfm_name = Some(token.to_string());
+            }
+            else if entry.ps_name.is_none() {
+                entry.ps_name = Some(token.to_string());
+            }
+        }
+        if let Some(tfm_name) = tfm_name {
+            map.entry(tfm_name).or_insert(entry);
+        }
+    }
+    map
+}
+
+// Words are separated by spaces, quoted strings are kept whole.
+fn tokens(line: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let end = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.find('"').map_or(rest.len(), |k| k + 2),
+            None => rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    tokens
+}
+
+// An encoding file holds a PostScript array of 256 glyph names:
+//   /EncodingName [ /name0 /name1 ... /name255 ] def
+pub(crate) fn parse_encoding(text: &str) -> Option<Vec<String>> {
+    let mut names = vec![];
+    let mut in_array = false;
+    for line in text.lines() {
+        let line = match line.find('%') {
+            Some(k) => &line[..k],
+            None => line
+        };
+        let mut line = line;
+        if !in_array {
+            match line.find('[') {
+                Some(k) => {
+                    in_array = true;
+                    line = &line[k + 1..];
+                },
+                None => continue
+            }
+        }
+        let (line, done) = match line.find(']') {
+            Some(k) => (&line[..k], true),
+            None => (line, false)
+        };
+        for word in line.split_whitespace() {
+            names.push(word.strip_prefix('/')?.to_string());
+        }
+        if done {
+            break;
+        }
+    }
+    if names.len() == 256 {
+        Some(names)
+    }
+    else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "\
+% pdftex.map
+cmr10 CMR10 <cmr10.pfb
+cmsy10 CMSY10 \" TeXBase1Encoding ReEncodeFont \" <8r.enc <cmsy10.pfb
+ptmr8r Times-Roman \"TeXBase1Encoding ReEncodeFont\" <8r.enc <<ptmr8a.pfa
+cmmi10 CMMI10 <[cmmi.enc < cmmi10.pfb
+cmex10
+  # comment
+cmr10 Other <other.pfb
+cmtt10 CMTT10 \"unterminated <cmtt10.pfb
+";
+
+    #[test]
+    fn map_lines() {
+        let map = parse_font_map(MAP);
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["cmex10", "cmmi10", "cmr10", "cmsy10", "cmtt10", "ptmr8r"]);
+
+        let entry = |name| {
+            let entry: &FontMapEntry = &map[name];
+            (entry.ps_name.as_deref(), entry.encoding.as_deref(), entry.font_file.as_deref())
+        };
+        // The first line for a name wins.
+        assert_eq!(entry("cmr10"), (Some("CMR10"), None, Some("cmr10.pfb")));
+        // Quoted instructions are skipped, even with spaces inside.
+        assert_eq!(entry("cmsy10"), (Some("CMSY10"), Some("8r.enc"), Some("cmsy10.pfb")));
+        assert_eq!(entry("ptmr8r"), (Some("Times-Roman"), Some("8r.enc"), Some("ptmr8a.pfa")));
+        assert_eq!(entry("cmmi10"), (Some("CMMI10"), Some("cmmi.enc"), Some("cmmi10.pfb")));
+        assert_eq!(entry("cmex10"), (None, None, None));
+        assert_eq!(entry("cmtt10"), (Some("CMTT10"), None, None));
+    }
+
+    #[test]
+    fn encoding_files() {
+        let names: Vec<String> = (0..256).map(|k| format!("/g{k}")).collect();
+        let text = format!(
+            "% an encoding\n/TestEncoding [ % the glyphs\n{}\n{} ] def\n",
+            names[..100].join(" "),
+            names[100..].join(" ")
+        );
+        let encoding = parse_encoding(&text).unwrap();
+        assert_eq!(encoding.len(), 256);
+        assert_eq!(encoding[0], "g0");
+        assert_eq!(encoding[255], "g255");
+
+        // Too few names, or a word that is not a name.
+        assert!(parse_encoding(&format!("/E [ {} ] def", names[1..].join(" "))).is_none());
+        assert!(parse_encoding(&format!("/E [ {} 0 ] def", names[1..].join(" "))).is_none());
+    }
+}
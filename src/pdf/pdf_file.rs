@@ -0,0 +1,558 @@
+use crate::constants::*;
+use crate::arithmetic::real;
+use crate::error::TeXResult;
+use crate::io::{ByteFileOut, ByteFileOutSelector};
+use crate::{Global, HalfWord, Integer, QuarterWord, Scaled};
+
+use super::font_map::{FontMapEntry, parse_encoding, parse_font_map};
+use super::type1::Type1Font;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+
+// PDF output
+// Pages are shipped out by `hlist_out` and `vlist_out` as for DVI,
+// they call the functions below to draw characters and rules.
+// Characters are placed with the widths found in the TFM files.
+// Fonts are embedded when a Type 1 font file is found for them
+// (see `pdf_font`), otherwise they are only referred to by name.
+//
+// Positions follow the DVI conventions: the origin is one inch from
+// the top and left edges of the page, and `\mag` scales everything
+// else. Pages are US letter, or larger if the box does not fit.
+
+// Objects written once all pages are shipped out.
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+const RESOURCES: usize = 3;
+
+const LETTER_WIDTH: f64 = 612.0;
+const LETTER_HEIGHT: f64 = 792.0;
+const ONE_INCH: f64 = 72.0;
+
+pub(crate) struct PdfFile {
+    pub(crate) file: ByteFileOut,
+    // Number of bytes written so far.
+    offset: usize,
+    // Offset of each object in the file, indexed by object number.
+    objects: Vec<usize>,
+    pages: Vec<usize>,
+    // Object of each font in use, 0 if the font is not used.
+    fonts: Vec<usize>,
+    // Characters of each font set on the pages, to subset the fonts.
+    chars_used: Vec<[bool; 256]>,
+    // Type 1 fonts and encodings of the TFM files, read from the map file.
+    font_map: BTreeMap<String, FontMapEntry>,
+    // From scaled points to big points, including `\mag`.
+    scale: f64,
+    page_width: f64,
+    page_height: f64,
+    // Content stream of the current page.
+    content: Vec<u8>,
+    in_text: bool,
+    in_array: bool,
+    in_string: bool,
+    text_font: QuarterWord,
+    // Where the next character goes if it follows the previous one.
+    text_h: Scaled,
+    text_v: Scaled,
+}
+
+impl PdfFile {
+    pub(crate) fn new(font_max: Integer) -> Self {
+        Self {
+            file: ByteFileOut::new(),
+            offset: 0,
+            objects: vec![0; RESOURCES + 1],
+            pages: vec![],
+            fonts: vec![0; (font_max - FONT_BASE + 1) as usize],
+            chars_used: vec![[false; 256]; (font_max - FONT_BASE + 1) as usize],
+            font_map: BTreeMap::new(),
+            scale: 0.0,
+            page_width: LETTER_WIDTH,
+            page_height: LETTER_HEIGHT,
+            content: vec![],
+            in_text: false,
+            in_array: false,
+            in_string: false,
+            text_font: NULL_FONT as QuarterWord,
+            text_h: 0,
+            text_v: 0,
+        }
+    }
+
+    fn out(&mut self, bytes: &[u8]) {
+        self.file.write(bytes);
+        self.offset += bytes.len();
+    }
+
+    fn new_object(&mut self) -> usize {
+        self.objects.push(0);
+        self.objects.len() - 1
+    }
+
+    fn begin_object(&mut self, n: usize) {
+        self.objects[n] = self.offset;
+        self.out(format!("{n} 0 obj\n").as_bytes());
+    }
+
+    fn write_object(&mut self, n: usize, dict: &str) {
+        self.begin_object(n);
+        self.out(dict.as_bytes());
+        self.out(b"\nendobj\n");
+    }
+
+    // `entries` are added to the stream dictionary after `/Length`.
+    fn write_stream(&mut self, n: usize, entries: &str, data: &[u8]) {
+        self.begin_object(n);
+        self.out(format!("<< /Length {}{entries} >>\nstream\n", data.len()).as_bytes());
+        self.out(data);
+        self.out(b"\nendstream\nendobj\n");
+    }
+
+    fn x(&self, h: Scaled) -> String {
+        real(ONE_INCH + h as f64 * self.scale, DECIMALS)
+    }
+
+    fn y(&self, v: Scaled) -> String {
+        real(self.page_height - ONE_INCH - v as f64 * self.scale, DECIMALS)
+    }
+
+    // Closes the `TJ` array of the characters set so far.
+    fn end_array(&mut self) {
+        if self.in_string {
+            self.content.push(b')');
+            self.in_string = false;
+        }
+        if self.in_array {
+            self.content.extend_from_slice(b"]TJ\n");
+            self.in_array = false;
+        }
+    }
+
+    fn end_text(&mut self) {
+        self.end_array();
+        if self.in_text {
+            self.content.extend_from_slice(b"ET\n");
+            self.in_text = false;
+        }
+    }
+}
+
+// Reals are written with at most four decimals.
+const DECIMALS: usize = 4;
+
+// PDF names cannot contain delimiters nor spaces.
+fn name(s: &[u8]) -> String {
+    let mut name = String::from("/");
+    for &c in s {
+        if c.is_ascii_alphanumeric() || b"+-_.".contains(&c) {
+            name.push(c as char);
+        }
+        else {
+            name.push_str(&format!("#{c:02X}"));
+        }
+    }
+    name
+}
+
+impl Global {
+    // Called by `ship_out` before the box `p` is output.
+    pub(crate) fn pdf_begin_page(&mut self, p: HalfWord) -> TeXResult<()> {
+        if self.output_file_name == 0 {
+            if self.job_name == 0 {
+                self.open_log_file()?;
+            }
+            self.pack_job_name(EXT_PDF);
+            self.b_open_out(ByteFileOutSelector::PdfFile)?;
+            self.output_file_name = self.make_name_string()?;
+        }
+        if self.total_pages == 0 {
+            self.pdf.font_map = self.read_pdf_file(&self.font_map_name())
+                .map(|bytes| parse_font_map(&String::from_utf8_lossy(&bytes)))
+                .unwrap_or_default();
+            self.prepare_mag()?;
+            self.pdf.scale = (self.mag() as f64 / 1000.0) * (72.0 / 72.27) / 65536.0;
+            self.pdf.out(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n");
+        }
+        let width = 2.0*ONE_INCH + (self.width(p) + self.h_offset()) as f64 * self.pdf.scale;
+        let height = 2.0*ONE_INCH
+            + (self.height(p) + self.depth(p) + self.v_offset()) as f64 * self.pdf.scale;
+        self.pdf.page_width = width.max(LETTER_WIDTH);
+        self.pdf.page_height = height.max(LETTER_HEIGHT);
+        self.pdf.content.clear();
+        self.pdf.text_font = NULL_FONT as QuarterWord;
+        Ok(())
+    }
+
+    // Character `c` of font `f` at (`cur_h`, `cur_v`).
+    pub(crate) fn pdf_char(&mut self) {
+        let f = self.f;
+        let c = self.c;
+        if self.pdf.fonts[f as usize] == 0 {
+            self.pdf.fonts[f as usize] = self.pdf.new_object();
+        }
+        let size = self.font_size[f as usize] as f64 * self.pdf.scale;
+        if !self.pdf.in_text {
+            self.pdf.content.extend_from_slice(b"BT\n");
+            self.pdf.in_text = true;
+        }
+        if f != self.pdf.text_font {
+            self.pdf.end_array();
+            _ = writeln!(self.pdf.content, "/F{} {} Tf", f, real(size, DECIMALS));
+            self.pdf.text_font = f;
+        }
+        if self.pdf.in_array && self.cur_v == self.pdf.text_v {
+            if self.cur_h != self.pdf.text_h {
+                // The gap is given in thousandths of the font size.
+                let adjust = (self.pdf.text_h - self.cur_h) as f64 * self.pdf.scale / size * 1000.0;
+                if self.pdf.in_string {
+                    self.pdf.content.push(b')');
+                    self.pdf.in_string = false;
+                }
+                _ = write!(self.pdf.content, "{}", real(adjust, DECIMALS));
+            }
+        }
+        else {
+            self.pdf.end_array();
+            let (x, y) = (self.pdf.x(self.cur_h), self.pdf.y(self.cur_v));
+            _ = write!(self.pdf.content, "1 0 0 1 {x} {y} Tm\n[");
+            self.pdf.in_array = true;
+        }
+        if !self.pdf.in_string {
+            self.pdf.content.push(b'(');
+            self.pdf.in_string = true;
+        }
+        let c = c as u8;
+        self.pdf.chars_used[f as usize][c as usize] = true;
+        match c {
+            b'(' | b')' | b'\\' => self.pdf.content.extend_from_slice(&[b'\\', c]),
+            32..=126 => self.pdf.content.push(c),
+            _ => _ = write!(self.pdf.content, "\\{c:03o}")
+        }
+        self.pdf.text_h = self.cur_h + self.char_width(f, self.char_info(f, c as QuarterWord));
+        self.pdf.text_v = self.cur_v;
+    }
+
+    // Rule of size `rule_wd` by `rule_ht`, whose bottom left corner
+    // is at (`cur_h`, `cur_v`).
+    pub(crate) fn pdf_rule(&mut self) {
+        self.pdf.end_text();
+        let (x, y) = (self.pdf.x(self.cur_h), self.pdf.y(self.cur_v));
+        let w = real(self.rule_wd as f64 * self.pdf.scale, DECIMALS);
+        let h = real(self.rule_ht as f64 * self.pdf.scale, DECIMALS);
+        _ = writeln!(self.pdf.content, "{x} {y} {w} {h} re f");
+    }
+
+    // Called by `ship_out` once the box is output.
+    pub(crate) fn pdf_end_page(&mut self) {
+        self.pdf.end_text();
+        let contents = self.pdf.new_object();
+        let content = std::mem::take(&mut self.pdf.content);
+        self.pdf.write_stream(contents, "", &content);
+
+        let page = self.pdf.new_object();
+        let dict = format!(
+            "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {} {}] /Resources {RESOURCES} 0 R /Contents {contents} 0 R >>",
+            real(self.pdf.page_width, DECIMALS),
+            real(self.pdf.page_height, DECIMALS)
+        );
+        self.pdf.write_object(page, &dict);
+        self.pdf.pages.push(page);
+    }
+
+    // The map file, in the Type 1 directory unless a name is given.
+    fn font_map_name(&self) -> String {
+        match &self.font_map_file {
+            Some(name) => name.clone(),
+            None => format!("{}{}", self.type1_directory, FONT_MAP_DEFAULT)
+        }
+    }
+
+    // Content of a file for the PDF backend, if it can be read.
+    fn read_pdf_file(&mut self, name: &str) -> Option<Vec<u8>> {
+        let mut bytes = vec![];
+        self.file_system.open_bytes(name).ok()?.read_to_end(&mut bytes).ok()?;
+        Some(bytes)
+    }
+
+    // The font `f` as a Type 1 font.
+    // The map file gives its PostScript name, its encoding and its font file,
+    // found in the Type 1 directory. Without an entry in the map file,
+    // the font file is `tfmname.pfb`. The font is embedded if its font file
+    // is found, with only the characters used in the pages.
+    fn pdf_font(&mut self, f: QuarterWord) {
+        let fu = f as usize;
+        let tfm_name = String::from_utf8_lossy(self.str_pool_slice(self.font_name[fu])).into_owned();
+        let (ps_name, encoding, font_file) = match self.pdf.font_map.get(&tfm_name) {
+            Some(entry) => (entry.ps_name.clone(), entry.encoding.clone(), entry.font_file.clone()),
+            None => (None, None, Some(format!("{tfm_name}.pfb")))
+        };
+        let encoding = encoding
+            .and_then(|file| self.read_pdf_file(&format!("{}{file}", self.type1_directory)))
+            .and_then(|bytes| parse_encoding(&String::from_utf8_lossy(&bytes)));
+        let program = font_file
+            .and_then(|file| self.read_pdf_file(&format!("{}{file}", self.type1_directory)))
+            .and_then(|bytes| Type1Font::from_pfb(&bytes));
+        // PostScript names of TeX fonts are usually their names in uppercase.
+        let ps_name = ps_name
+            .or_else(|| program.as_ref().and_then(|program| program.font_name()))
+            .unwrap_or_else(|| tfm_name.to_ascii_uppercase());
+
+        let size = self.font_size[fu] as f64;
+        let to_glyph = |x: Scaled| x as f64 / size * 1000.0;
+        let mut widths = String::new();
+        let (mut max_wd, mut max_ht, mut max_dp) = (0, 0, 0);
+        for c in self.font_bc[fu]..=self.font_ec[fu] {
+            let info = self.char_info(f, c as QuarterWord);
+            let mut wd = 0;
+            if info.char_exists() {
+                wd = self.char_width(f, info);
+                let hd = info.height_depth();
+                max_wd = max_wd.max(wd);
+                max_ht = max_ht.max(self.char_height(f, hd));
+                max_dp = max_dp.max(self.char_depth(f, hd));
+            }
+            if !widths.is_empty() {
+                widths.push(' ');
+            }
+            widths.push_str(&real(to_glyph(wd), DECIMALS));
+        }
+        let italic_angle = -(self.slant(f) as f64 / 65536.0).atan().to_degrees();
+        let mut bbox = [0.0, -to_glyph(max_dp), to_glyph(max_wd), to_glyph(max_ht)];
+
+        let mut base_font = ps_name.clone();
+        let mut font_file = String::new();
+        if let Some(program) = program {
+            // Names of the glyphs used, unless the codes cannot be mapped to names.
+            let glyphs: Option<BTreeSet<String>> = encoding.clone()
+                .or_else(|| program.encoding())
+                .map(|names| {
+                    (0..256)
+                        .filter(|&c| self.pdf.chars_used[fu][c])
+                        .map(|c| names[c].clone())
+                        .collect()
+                });
+            let (font_name, [clear, private, trailer]) = program.embed(glyphs.as_ref(), &ps_name);
+            base_font = font_name;
+            if let Some(font_bbox) = program.font_bbox() {
+                bbox = font_bbox.map(|x| x as f64);
+            }
+            let stream = self.pdf.new_object();
+            let entries = format!(" /Length1 {} /Length2 {} /Length3 {}", clear.len(), private.len(), trailer.len());
+            self.pdf.write_stream(stream, &entries, &[clear, private, trailer].concat());
+            font_file = format!(" /FontFile {stream} 0 R");
+        }
+        let base_font = name(base_font.as_bytes());
+
+        let descriptor = self.pdf.new_object();
+        let dict = format!(
+            "<< /Type /FontDescriptor /FontName {base_font} /Flags 4 /FontBBox [{}] /ItalicAngle {} /Ascent {} /Descent {} /CapHeight {} /StemV 80{font_file} >>",
+            bbox.map(|x| real(x, DECIMALS)).join(" "),
+            real(italic_angle, DECIMALS),
+            real(to_glyph(max_ht), DECIMALS),
+            real(-to_glyph(max_dp), DECIMALS),
+            real(to_glyph(max_ht), DECIMALS)
+        );
+        self.pdf.write_object(descriptor, &dict);
+
+        // The codes are mapped to the glyphs of the encoding file
+        // instead of the encoding of the font.
+        let mut differences = String::new();
+        if let Some(names) = &encoding {
+            differences = format!(" /Encoding << /Type /Encoding /Differences [{}", self.font_bc[fu]);
+            for c in self.font_bc[fu]..=self.font_ec[fu] {
+                differences.push(' ');
+                differences.push_str(&name(names[c as usize].as_bytes()));
+            }
+            differences.push_str("] >>");
+        }
+        let dict = format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont {base_font} /FirstChar {} /LastChar {} /Widths [{widths}]{differences} /FontDescriptor {descriptor} 0 R >>",
+            self.font_bc[fu],
+            self.font_ec[fu]
+        );
+        self.pdf.write_object(self.pdf.fonts[fu], &dict);
+    }
+
+    // Section 642, for PDF
+    pub(crate) fn pdf_finish_file(&mut self) -> TeXResult<()> {
+        if self.cur_s > -1 {
+            self.pdf_end_page();
+            self.total_pages += 1;
+            self.cur_s = -1;
+        }
+        if self.total_pages == 0 {
+            self.print_nl("No pages of output.");
+            return Ok(());
+        }
+
+        let mut fonts = String::new();
+        for f in (FONT_BASE + 1)..=(self.font_ptr as Integer) {
+            let f = f as QuarterWord;
+            if self.pdf.fonts[f as usize] != 0 {
+                self.pdf_font(f);
+                fonts.push_str(&format!(" /F{f} {} 0 R", self.pdf.fonts[f as usize]));
+            }
+        }
+        self.pdf.write_object(RESOURCES, &format!("<< /Font <<{fonts} >> /ProcSet [/PDF /Text] >>"));
+        let kids: Vec<String> = self.pdf.pages.iter().map(|page| format!("{page} 0 R")).collect();
+        let dict = format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), kids.len());
+        self.pdf.write_object(PAGES, &dict);
+        self.pdf.write_object(CATALOG, &format!("<< /Type /Catalog /Pages {PAGES} 0 R >>"));
+
+        let startxref = self.pdf.offset;
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.pdf.objects.len());
+        for offset in self.pdf.objects.iter().skip(1) {
+            xref.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        xref.push_str(&format!(
+            "trailer\n<< /Size {} /Root {CATALOG} 0 R >>\nstartxref\n{startxref}\n%%EOF\n",
+            self.pdf.objects.len()
+        ));
+        self.pdf.out(xref.as_bytes());
+
+        self.print_nl("Output written on ");
+        self.slow_print(self.output_file_name);
+        self.print(" (");
+        self.print_int(self.total_pages);
+        self.print(" page");
+        if self.total_pages != 1 {
+            self.print_char(b's');
+        }
+        self.print(", ");
+        self.print_int(self.pdf.offset as Integer);
+        self.print(" bytes).");
+        self.pdf.file.close();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OutputFormat;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, compile};
+    use crate::pdf::type1::tests::{embedded_glyphs, test_font};
+
+    const CMR10: &[u8] = include_bytes!("../../TeXfonts/cmr10.tfm");
+
+    // The PDF file of `pages` typeset with cmr10, and other input files.
+    fn pdf_file(pages: &str, inputs: &[(&str, &[u8])]) -> Vec<u8> {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let mut all_inputs = vec![("TeXfonts/cmr10.tfm", CMR10)];
+        all_inputs.extend_from_slice(inputs);
+        let options = CompileOptions {
+            format: &format,
+            output_format: OutputFormat::Pdf,
+            inputs: &all_inputs,
+            ..CompileOptions::default()
+        };
+        let output = compile(&format!("\\font\\x=cmr10 \\x\n{pages}\\end\n"), &options);
+        assert!(output.errors.is_empty(), "{}", output.log);
+        assert!(output.dvi.is_none());
+        output.pdf.expect("no PDF file")
+    }
+
+    fn find(bytes: &[u8], pattern: &str, from: usize) -> Option<usize> {
+        bytes[from..].windows(pattern.len())
+            .position(|window| window == pattern.as_bytes())
+            .map(|k| from + k)
+    }
+
+    // The text of each object, found with the cross-reference table.
+    fn objects(pdf: &[u8]) -> Vec<String> {
+        let k = pdf.windows(10).rposition(|window| window == b"startxref\n").unwrap();
+        let startxref: usize = String::from_utf8_lossy(&pdf[k + 10..]).lines().next().unwrap().parse().unwrap();
+        let xref = String::from_utf8_lossy(&pdf[startxref..]).into_owned();
+        let mut lines = xref.lines();
+        assert_eq!(lines.next(), Some("xref"));
+        let (first, size) = lines.next().unwrap().split_once(' ').unwrap();
+        assert_eq!(first, "0");
+        let size: usize = size.parse().unwrap();
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+        let mut objects = vec![String::new()];
+        for n in 1..size {
+            let entry = lines.next().unwrap();
+            assert_eq!(entry.len(), 19, "{entry}");
+            assert!(entry.ends_with(" 00000 n "), "{entry}");
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{n} 0 obj\n").as_bytes()), "object {n} is not at {offset}");
+            let end = find(pdf, "endobj\n", offset).unwrap();
+            objects.push(String::from_utf8_lossy(&pdf[offset..end]).into_owned());
+        }
+        assert_eq!(lines.next(), Some("trailer"));
+        assert_eq!(lines.next(), Some(format!("<< /Size {size} /Root 1 0 R >>").as_str()));
+        objects
+    }
+
+    // The value of `/key` in `dict`, up to the next key or the end of the dictionary.
+    fn value<'a>(dict: &'a str, key: &str) -> &'a str {
+        let start = dict.find(&format!("/{key} ")).unwrap_or_else(|| panic!("no /{key} in {dict}")) + key.len() + 2;
+        let rest = &dict[start..];
+        if rest.starts_with('[') {
+            &rest[..=rest.find(']').unwrap()]
+        }
+        else {
+            rest.split(' ').next().unwrap()
+        }
+    }
+
+    #[test]
+    fn cross_reference_table() {
+        let pdf = pdf_file("\\shipout\\hbox{AB}\n\\shipout\\hbox{C\\vrule width 1pt}\n", &[]);
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        let objects = objects(&pdf);
+        assert!(objects[1].contains("<< /Type /Catalog /Pages 2 0 R >>"));
+        assert_eq!(value(&objects[2], "Count"), "2");
+        let pages: Vec<&String> = objects.iter().filter(|object| object.contains("/Type /Page ")).collect();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(value(pages[0], "MediaBox"), "[0 0 612 792]");
+
+        // cmr10 has the characters 0 to 127.
+        let font = objects.iter().find(|object| object.contains("/Type /Font ")).unwrap();
+        assert_eq!(value(font, "BaseFont"), "/CMR10");
+        assert_eq!((value(font, "FirstChar"), value(font, "LastChar")), ("0", "127"));
+        let widths = value(font, "Widths");
+        assert_eq!(widths.trim_matches(['[', ']']).split(' ').count(), 128);
+        // Without a font file, the font is not embedded.
+        assert!(!objects.iter().any(|object| object.contains("/FontFile")));
+    }
+
+    // The Type 1 font of cmr10 is the test font, with the glyphs
+    // `.notdef`, `A`, `B` and `Aacute`.
+    #[test]
+    fn embedded_font() {
+        let pfb = test_font();
+        let map: &[u8] = b"% fonts\ncmr10 Test <test.pfb\n";
+        let pdf = pdf_file("\\shipout\\hbox{AAA}\n", &[("TeXtype1/pdftex.map", map), ("TeXtype1/test.pfb", &pfb)]);
+        let objects = objects(&pdf);
+        let font = objects.iter().find(|object| object.contains("/Type /Font ")).unwrap();
+        let descriptor = objects.iter().find(|object| object.contains("/Type /FontDescriptor ")).unwrap();
+        let base_font = value(font, "BaseFont");
+        assert!(base_font.ends_with("+Test") && base_font.len() == 12, "{base_font}");
+        assert_eq!(value(descriptor, "FontName"), base_font);
+        assert_eq!(value(descriptor, "FontBBox"), "[-40 -250 1009 750]");
+
+        let n = value(descriptor, "FontFile");
+        let start = find(&pdf, &format!("{n} 0 obj\n<< /Length "), 0).unwrap();
+        let dict_end = find(&pdf, " >>\nstream\n", start).unwrap();
+        let dict = String::from_utf8_lossy(&pdf[start..dict_end + 3]).into_owned();
+        let lengths: Vec<usize> = ["Length", "Length1", "Length2", "Length3"].iter()
+            .map(|key| value(&dict, key).parse().unwrap())
+            .collect();
+        assert_eq!(lengths[0], lengths[1] + lengths[2] + lengths[3]);
+        let data = &pdf[dict_end + 11..dict_end + 11 + lengths[0]];
+        assert!(pdf[dict_end + 11 + lengths[0]..].starts_with(b"\nendstream\nendobj\n"));
+        let (clear, rest) = data.split_at(lengths[1]);
+        let (private, trailer) = rest.split_at(lengths[2]);
+        assert!(clear.starts_with(b"%!PS-AdobeFont-1.0: Test\n"));
+        assert!(clear.ends_with(b"currentfile eexec\n"));
+        assert!(find(clear, &format!("/FontName {base_font} def"), 0).is_some());
+        assert!(trailer.ends_with(b"cleartomark\n"));
+        // Only the glyph that is used is kept, with `.notdef`.
+        assert_eq!(embedded_glyphs(clear, private), [".notdef", "A"]);
+    }
+}
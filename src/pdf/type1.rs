@@ -0,0 +1,415 @@
+use std::collections::BTreeSet;
+
+// Type 1 fonts, read from PFB files.
+// A PFB file has three parts: the clear text (font dictionary and
+// encoding), the private part encrypted with eexec (subroutines and
+// glyph programs), and a trailer of zeros ending with `cleartomark`.
+pub(crate) struct Type1Font {
+    clear: Vec<u8>,
+    // Decrypted, it starts with four random bytes.
+    private: Vec<u8>,
+    trailer: Vec<u8>,
+}
+
+const EEXEC_KEY: u16 = 55665;
+const CHARSTRING_KEY: u16 = 4330;
+
+fn decrypt(data: &[u8], key: u16) -> Vec<u8> {
+    let mut r = key;
+    data.iter()
+        .map(|&c| {
+            let p = c ^ (r >> 8) as u8;
+            r = (c as u16).wrapping_add(r).wrapping_mul(52845).wrapping_add(22719);
+            p
+        })
+        .collect()
+}
+
+fn encrypt(data: &[u8], key: u16) -> Vec<u8> {
+    let mut r = key;
+    data.iter()
+        .map(|&p| {
+            let c = p ^ (r >> 8) as u8;
+            r = (c as u16).wrapping_add(r).wrapping_mul(52845).wrapping_add(22719);
+            c
+        })
+        .collect()
+}
+
+fn find(data: &[u8], pattern: &[u8], from: usize) -> Option<usize> {
+    data.get(from..)?
+        .windows(pattern.len())
+        .position(|w| w == pattern)
+        .map(|k| k + from)
+}
+
+fn skip_spaces(data: &[u8], mut k: usize) -> usize {
+    while k < data.len() && data[k].is_ascii_whitespace() {
+        k += 1;
+    }
+    k
+}
+
+// End of the word starting at `k`.
+fn word_end(data: &[u8], mut k: usize) -> usize {
+    while k < data.len() && !data[k].is_ascii_whitespace() {
+        k += 1;
+    }
+    k
+}
+
+// PostScript words of `data`: names and brackets start
+// a new word even without space.
+fn words(data: &[u8]) -> Vec<&[u8]> {
+    let mut words = vec![];
+    let mut k = skip_spaces(data, 0);
+    while k < data.len() {
+        let start = k;
+        k += 1;
+        if !b"[]{}".contains(&data[start]) {
+            while k < data.len()
+                && !data[k].is_ascii_whitespace()
+                && !b"/[]{}".contains(&data[k])
+            {
+                k += 1;
+            }
+        }
+        words.push(&data[start..k]);
+        k = skip_spaces(data, k);
+    }
+    words
+}
+
+fn number(word: &[u8]) -> Option<i32> {
+    std::str::from_utf8(word).ok()?.parse().ok()
+}
+
+// Subsets are named with a tag of six uppercase letters,
+// which depends on the glyphs they contain.
+fn subset_tag(ps_name: &str, glyphs: &BTreeSet<String>) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for name in std::iter::once(ps_name).chain(glyphs.iter().map(|s| s.as_str())) {
+        for b in name.bytes().chain(std::iter::once(0)) {
+            h = (h ^ b as u64).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+    (0..6)
+        .map(|_| {
+            let letter = (b'A' + (h % 26) as u8) as char;
+            h /= 26;
+            letter
+        })
+        .collect()
+}
+
+impl Type1Font {
+    pub(crate) fn from_pfb(bytes: &[u8]) -> Option<Self> {
+        let mut font = Self {
+            clear: vec![],
+            private: vec![],
+            trailer: vec![],
+        };
+        let mut encrypted = vec![];
+        let mut k = 0;
+        while k + 1 < bytes.len() && bytes[k] == 128 {
+            let segment_type = bytes[k + 1];
+            if segment_type == 3 {
+                break;
+            }
+            let length = u32::from_le_bytes(bytes.get(k + 2..k + 6)?.try_into().ok()?) as usize;
+            let data = bytes.get(k + 6..k + 6 + length)?;
+            match segment_type {
+                1 if encrypted.is_empty() => font.clear.extend_from_slice(data),
+                1 => font.trailer.extend_from_slice(data),
+                2 => encrypted.extend_from_slice(data),
+                _ => return None
+            }
+            k += 6 + length;
+        }
+        if font.clear.is_empty() || encrypted.is_empty() {
+            return None;
+        }
+        font.private = decrypt(&encrypted, EEXEC_KEY);
+        Some(font)
+    }
+
+    // The name following `key` in the clear text, such as `/FontName /CMR10`.
+    fn clear_name(&self, key: &[u8]) -> Option<(usize, usize)> {
+        let k = find(&self.clear, key, 0)? + key.len();
+        let start = skip_spaces(&self.clear, k);
+        if self.clear.get(start) != Some(&b'/') {
+            return None;
+        }
+        Some((start + 1, word_end(&self.clear, start + 1)))
+    }
+
+    pub(crate) fn font_name(&self) -> Option<String> {
+        let (start, end) = self.clear_name(b"/FontName")?;
+        Some(String::from_utf8_lossy(&self.clear[start..end]).into_owned())
+    }
+
+    pub(crate) fn font_bbox(&self) -> Option<[i32; 4]> {
+        let k = find(&self.clear, b"/FontBBox", 0)? + 9;
+        let end = find(&self.clear, b"def", k)?;
+        let numbers: Vec<i32> = words(&self.clear[k..end])
+            .into_iter()
+            .filter_map(number)
+            .collect();
+        numbers.try_into().ok()
+    }
+
+    // The built-in encoding, `None` if it is `StandardEncoding`.
+    pub(crate) fn encoding(&self) -> Option<Vec<String>> {
+        let k = find(&self.clear, b"/Encoding", 0)? + 9;
+        let mut encoding = vec![String::from(".notdef"); 256];
+        let words = words(&self.clear[k..]);
+        let mut n = 0;
+        while n < words.len() {
+            match words[n] {
+                b"StandardEncoding" => return None,
+                b"def" | b"readonly" => break,
+                b"dup" if n + 3 < words.len() && words[n + 3] == b"put" => {
+                    let code = number(words[n + 1])?;
+                    let name = words[n + 2].strip_prefix(b"/")?;
+                    *encoding.get_mut(code as usize)? = String::from_utf8_lossy(name).into_owned();
+                    n += 4;
+                },
+                _ => n += 1
+            }
+        }
+        Some(encoding)
+    }
+
+    // Number of random bytes at the start of glyph programs.
+    fn len_iv(&self) -> i32 {
+        find(&self.private, b"/lenIV", 0)
+            .and_then(|k| words(&self.private[k + 6..]).first().copied().and_then(number))
+            .unwrap_or(4)
+    }
+
+    // Glyph programs, as the range of each entry
+    // `/name len RD <len bytes> ND` in `private`,
+    // with the name and the range of the program.
+    fn char_strings(&self) -> Option<Vec<CharString>> {
+        let data = &self.private;
+        let k = find(data, b"/CharStrings", 0)?;
+        let mut k = find(data, b"begin", k)? + 5;
+        let mut char_strings = vec![];
+        loop {
+            let start = k;
+            k = skip_spaces(data, k);
+            if data.get(k) != Some(&b'/') {
+                break;
+            }
+            let name_end = word_end(data, k);
+            let name = String::from_utf8_lossy(&data[k + 1..name_end]).into_owned();
+            let len_start = skip_spaces(data, name_end);
+            let len_end = word_end(data, len_start);
+            let len = number(&data[len_start..len_end])? as usize;
+            let rd_end = word_end(data, skip_spaces(data, len_end));
+            let program = rd_end + 1..rd_end + 1 + len;
+            let nd_end = word_end(data, skip_spaces(data, program.end));
+            if nd_end > data.len() {
+                return None;
+            }
+            char_strings.push(CharString {
+                name,
+                entry: start..nd_end,
+                program
+            });
+            k = nd_end;
+        }
+        Some(char_strings)
+    }
+
+    // Accented characters built with `seac` need two other glyphs,
+    // given by their code in the standard encoding.
+    fn uses_seac(&self, program: &[u8]) -> bool {
+        let len_iv = self.len_iv();
+        let program = if len_iv < 0 {
+            program.to_vec()
+        }
+        else {
+            let decrypted = decrypt(program, CHARSTRING_KEY);
+            decrypted.get(len_iv as usize..).unwrap_or_default().to_vec()
+        };
+        let mut k = 0;
+        while k < program.len() {
+            match program[k] {
+                12 => {
+                    if program.get(k + 1) == Some(&6) {
+                        return true;
+                    }
+                    k += 2;
+                },
+                0..=246 => k += 1,
+                247..=254 => k += 2,
+                255 => k += 5
+            }
+        }
+        false
+    }
+
+    // The font with only the glyphs in `glyphs` (and `.notdef`),
+    // as the three parts to embed in PDF, with its name.
+    // A subset is renamed `ABCDEF+ps_name`, with a tag of six letters.
+    // All glyphs are kept if `glyphs` is `None`, if one of them is
+    // an accented character made with `seac`, or if the font cannot be parsed.
+    pub(crate) fn embed(&self, glyphs: Option<&BTreeSet<String>>, ps_name: &str) -> (String, [Vec<u8>; 3]) {
+        let subset = glyphs.and_then(|glyphs| Some((glyphs, self.subset_private(glyphs)?)));
+        let (font_name, private) = match subset {
+            Some((glyphs, private)) => (format!("{}+{ps_name}", subset_tag(ps_name, glyphs)), private),
+            None => (ps_name.to_string(), self.private.clone())
+        };
+        let mut clear = self.clear.clone();
+        if let Some((start, end)) = self.clear_name(b"/FontName") {
+            clear.splice(start..end, font_name.bytes());
+        }
+        (font_name, [clear, encrypt(&private, EEXEC_KEY), self.trailer.clone()])
+    }
+
+    fn subset_private(&self, glyphs: &BTreeSet<String>) -> Option<Vec<u8>> {
+        let char_strings = self.char_strings()?;
+        let kept: Vec<&CharString> = char_strings.iter()
+            .filter(|char_string| char_string.name == ".notdef" || glyphs.contains(&char_string.name))
+            .collect();
+        if kept.iter().any(|char_string| self.uses_seac(&self.private[char_string.program.clone()])) {
+            return None;
+        }
+        let first = char_strings.first()?.entry.start;
+        let last = char_strings.last()?.entry.end;
+        let mut private = self.private[..first].to_vec();
+        for char_string in kept {
+            private.extend_from_slice(&self.private[char_string.entry.clone()]);
+        }
+        private.extend_from_slice(&self.private[last..]);
+        Some(private)
+    }
+}
+
+struct CharString {
+    name: String,
+    entry: std::ops::Range<usize>,
+    program: std::ops::Range<usize>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // A PFB segment: 128, its type and its length, then its data.
+    fn segment(segment_type: u8, data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![128, segment_type];
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    // `/name len RD <program> ND` with four random bytes before the program.
+    fn char_string(name: &str, program: &[u8]) -> Vec<u8> {
+        let encrypted = encrypt(&[[1, 2, 3, 4].as_slice(), program].concat(), CHARSTRING_KEY);
+        let mut entry = format!("/{name} {} RD ", encrypted.len()).into_bytes();
+        entry.extend_from_slice(&encrypted);
+        entry.extend_from_slice(b" ND\n");
+        entry
+    }
+
+    // A font with the glyphs `.notdef`, `A`, `B` and `Aacute`,
+    // the last one built with `seac`.
+    pub(crate) fn test_font() -> Vec<u8> {
+        let clear = b"%!PS-AdobeFont-1.0: Test\n/FontName /Test def\n\
+            /FontBBox {-40 -250 1009 750} readonly def\n\
+            /Encoding 256 array\n0 1 255 {1 index exch /.notdef put} for\n\
+            dup 65 /A put\ndup 66 /B put\ndup 193 /Aacute put\nreadonly def\n\
+            currentfile eexec\n";
+        let mut private = b"\x10\x20\x30\x40dup /Private 8 dict dup begin\n\
+            /CharStrings 4 dict dup begin\n".to_vec();
+        private.extend(char_string(".notdef", &[139, 139, 13, 14]));
+        private.extend(char_string("A", &[139, 239, 13, 14]));
+        private.extend(char_string("B", &[139, 239, 13, 14]));
+        private.extend(char_string("Aacute", &[139, 139, 139, 204, 150, 12, 6]));
+        private.extend_from_slice(b"end\nend\nmark currentfile closefile\n");
+        let mut trailer = vec![b'0'; 64];
+        trailer.extend_from_slice(b"\ncleartomark\n");
+        [
+            segment(1, clear),
+            segment(2, &encrypt(&private, EEXEC_KEY)),
+            segment(1, &trailer),
+            vec![128, 3]
+        ].concat()
+    }
+
+    // The names of the CharStrings of an embedded font,
+    // from its clear text and its encrypted part.
+    pub(crate) fn embedded_glyphs(clear: &[u8], private: &[u8]) -> Vec<String> {
+        let font = Type1Font {
+            clear: clear.to_vec(),
+            private: decrypt(private, EEXEC_KEY),
+            trailer: vec![]
+        };
+        font.char_strings().unwrap().into_iter().map(|c| c.name).collect()
+    }
+
+    fn glyphs(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn eexec_encryption() {
+        // The first bytes of eexec-encrypted zeros.
+        assert_eq!(encrypt(&[0; 4], EEXEC_KEY), [0xd9, 0xd6, 0x6f, 0x63]);
+        assert_eq!(decrypt(&[0xd9, 0xd6, 0x6f, 0x63], EEXEC_KEY), [0; 4]);
+        assert_eq!(encrypt(b"abcd", CHARSTRING_KEY), [0x71, 0x94, 0x64, 0xc5]);
+        let data: Vec<u8> = (0..=255).collect();
+        for key in [EEXEC_KEY, CHARSTRING_KEY] {
+            assert_eq!(decrypt(&encrypt(&data, key), key), data);
+        }
+    }
+
+    #[test]
+    fn subset_tag_depends_on_the_glyphs() {
+        let tag = subset_tag("CMR10", &glyphs(&["A", "B"]));
+        assert_eq!(tag.len(), 6);
+        assert!(tag.bytes().all(|b| b.is_ascii_uppercase()));
+        assert_eq!(tag, subset_tag("CMR10", &glyphs(&["B", "A"])));
+        assert_ne!(tag, subset_tag("CMR10", &glyphs(&["A"])));
+        assert_ne!(tag, subset_tag("CMR12", &glyphs(&["A", "B"])));
+    }
+
+    #[test]
+    fn pfb_dictionary() {
+        let font = Type1Font::from_pfb(&test_font()).unwrap();
+        assert_eq!(font.font_name().as_deref(), Some("Test"));
+        assert_eq!(font.font_bbox(), Some([-40, -250, 1009, 750]));
+        let encoding = font.encoding().unwrap();
+        assert_eq!(encoding[65], "A");
+        assert_eq!(encoding[193], "Aacute");
+        assert_eq!(encoding[0], ".notdef");
+        let names: Vec<String> = font.char_strings().unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, [".notdef", "A", "B", "Aacute"]);
+
+        assert!(Type1Font::from_pfb(b"%!PS-AdobeFont").is_none());
+        assert!(Type1Font::from_pfb(&test_font()[..100]).is_none());
+    }
+
+    #[test]
+    fn subsets() {
+        let font = Type1Font::from_pfb(&test_font()).unwrap();
+        let (name, [clear, private, trailer]) = font.embed(Some(&glyphs(&["A"])), "Test");
+        assert_eq!(name, format!("{}+Test", subset_tag("Test", &glyphs(&["A"]))));
+        assert!(find(&clear, format!("/FontName /{name} def").as_bytes(), 0).is_some());
+        let private = decrypt(&private, EEXEC_KEY);
+        assert!(find(&private, b"/.notdef ", 0).is_some());
+        assert!(find(&private, b"/A ", 0).is_some());
+        assert!(find(&private, b"/B ", 0).is_none());
+        assert!(private.ends_with(b"end\nend\nmark currentfile closefile\n"));
+        assert!(trailer.ends_with(b"cleartomark\n"));
+
+        // Glyphs made with `seac` need the whole font.
+        let (name, [_, private, _]) = font.embed(Some(&glyphs(&["Aacute"])), "Test");
+        assert_eq!(name, "Test");
+        assert_eq!(decrypt(&private, EEXEC_KEY), font.private);
+        let (name, _) = font.embed(None, "Test");
+        assert_eq!(name, "Test");
+    }
+}
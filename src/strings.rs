@@ -1,15 +1,13 @@
+use crate::capacities::grown_size;
 use crate::constants::{
-    POOL_SIZE, MAX_STRINGS, TEX_AREA_STRING, TEX_FONT_AREA_STRING
+    SUP_POOL_SIZE, TEX_AREA_STRING, TEX_FONT_AREA_STRING
 };
 use crate::error::{TeXError, TeXResult};
 use crate::{
     ASCIICode, Global, Integer, StrNum
 };
 
-use std::{
-    cmp::Ordering,
-    ops::{Index, IndexMut, Range}
-};
+use std::ops::{Index, IndexMut, Range};
 
 // Part 2: The character set
 // Part 4: String Handling
@@ -52,22 +50,26 @@ pub(crate) const XCHR: [char; 256] = [
 
 // Section 39
 pub(crate) struct StrPool {
-    pool: [ASCIICode; POOL_SIZE as usize],
-    str_start: [StrNum; (MAX_STRINGS + 1) as usize],
+    pool: Vec<ASCIICode>,
+    str_start: Vec<StrNum>,
     pool_ptr: usize,
     str_ptr: StrNum,
     init_pool_ptr: usize,
     init_str_ptr: StrNum,
 }
 
-pub(crate) static mut POOL: StrPool = StrPool {
-    pool: [0; POOL_SIZE as usize],
-    str_start: [0; (MAX_STRINGS + 1) as usize],
-    pool_ptr: 0,
-    str_ptr: 0,
-    init_pool_ptr: 0,
-    init_str_ptr: 0,
-};
+impl StrPool {
+    pub(crate) fn new(pool_size: Integer, max_strings: Integer) -> Self {
+        Self {
+            pool: vec![0; pool_size as usize],
+            str_start: vec![0; (max_strings + 1) as usize],
+            pool_ptr: 0,
+            str_ptr: 0,
+            init_pool_ptr: 0,
+            init_str_ptr: 0,
+        }
+    }
+}
 
 impl Index<usize> for StrPool {
     type Output = ASCIICode;
@@ -95,255 +97,263 @@ impl IndexMut<Range<usize>> for StrPool {
     }
 }
 
-pub(crate) fn str_pool(p: StrNum) -> ASCIICode {
-    unsafe { POOL[p] }
-}
+impl Global {
+    pub(crate) fn str_pool(&self, p: StrNum) -> ASCIICode {
+        self.pool[p]
+    }
 
-pub(crate) fn str_pool_mut(p: StrNum) -> &'static mut ASCIICode {
-    unsafe { &mut POOL[p] }
-}
+    pub(crate) fn str_pool_mut(&mut self, p: StrNum) -> &mut ASCIICode {
+        &mut self.pool[p]
+    }
 
-pub(crate) fn str_start(p: usize) -> StrNum {
-    unsafe { POOL.str_start[p] }
-}
+    pub(crate) fn str_start(&self, p: usize) -> StrNum {
+        self.pool.str_start[p]
+    }
 
-pub(crate) fn str_start_mut(p: usize) -> &'static mut StrNum {
-    unsafe { &mut POOL.str_start[p] }
-}
+    pub(crate) fn str_start_mut(&mut self, p: usize) -> &mut StrNum {
+        &mut self.pool.str_start[p]
+    }
 
-pub fn pool_ptr() -> usize {
-    unsafe { POOL.pool_ptr }
-}
+    pub fn pool_ptr(&self) -> usize {
+        self.pool.pool_ptr
+    }
 
-pub(crate) fn pool_ptr_set(value: usize) {
-    unsafe { POOL.pool_ptr = value };
-}
+    pub(crate) fn pool_ptr_set(&mut self, value: usize) {
+        self.pool.pool_ptr = value;
+    }
 
-pub fn str_ptr() -> StrNum {
-    unsafe { POOL.str_ptr }
-}
+    pub fn str_ptr(&self) -> StrNum {
+        self.pool.str_ptr
+    }
 
-pub(crate) fn str_ptr_set(value: StrNum) {
-    unsafe { POOL.str_ptr = value };
-}
+    pub(crate) fn str_ptr_set(&mut self, value: StrNum) {
+        self.pool.str_ptr = value;
+    }
 
-#[cfg(feature = "stat")]
-pub(crate) fn init_pool_ptr() -> usize {
-    unsafe { POOL.init_pool_ptr }
-}
+    #[cfg(feature = "stat")]
+    pub(crate) fn init_pool_ptr(&self) -> usize {
+        self.pool.init_pool_ptr
+    }
 
-pub fn init_pool_ptr_set(value: usize) {
-    unsafe { POOL.init_pool_ptr = value };
-}
+    pub fn init_pool_ptr_set(&mut self, value: usize) {
+        self.pool.init_pool_ptr = value;
+    }
 
-pub(crate) fn init_str_ptr() -> StrNum {
-    unsafe { POOL.init_str_ptr }
-}
+    pub(crate) fn init_str_ptr(&self) -> StrNum {
+        self.pool.init_str_ptr
+    }
 
-pub fn init_str_ptr_set(value: StrNum) {
-    unsafe { POOL.init_str_ptr = value };
-}
+    pub fn init_str_ptr_set(&mut self, value: StrNum) {
+        self.pool.init_str_ptr = value;
+    }
 
-// Section 40
-pub(crate) fn length(p: StrNum) -> usize {
-    unsafe { POOL.str_start[p + 1] - POOL.str_start[p] }
-}
+    // Section 40
+    pub(crate) fn length(&self, p: StrNum) -> usize {
+        self.pool.str_start[p + 1] - self.pool.str_start[p]
+    }
 
-// Section 41
-pub(crate) fn cur_length() -> usize {
-    unsafe { POOL.pool_ptr - POOL.str_start[POOL.str_ptr] }
-}
+    // Section 41
+    pub(crate) fn cur_length(&self) -> usize {
+        self.pool.pool_ptr - self.pool.str_start[self.pool.str_ptr]
+    }
 
-// Section 42
-pub(crate) fn append_char(c: ASCIICode) {
-    unsafe {
-        POOL.pool[POOL.pool_ptr] = c;
-        POOL.pool_ptr += 1;
+    // Section 42
+    pub(crate) fn append_char(&mut self, c: ASCIICode) {
+        self.pool.pool[self.pool.pool_ptr] = c;
+        self.pool.pool_ptr += 1;
     }
-}
 
-pub(crate) fn flush_char() {
-    unsafe { POOL.pool_ptr -= 1 };
-}
+    pub(crate) fn flush_char(&mut self) {
+        self.pool.pool_ptr -= 1;
+    }
 
-pub(crate) fn str_room(p: usize) -> TeXResult<()> {
-    unsafe {
-        match (POOL.pool_ptr + p).cmp(&(POOL_SIZE as usize)) {
-            Ordering::Less => Ok(()),
-            _ => Err(TeXError::Overflow("pool size", POOL_SIZE - POOL.init_pool_ptr as Integer))
+    // The most characters the pool can hold: `pool_size` when nothing
+    // grows, as with the `trip` feature, `SUP_POOL_SIZE` otherwise.
+    pub(crate) fn sup_pool_size(&self) -> Integer {
+        if cfg!(feature = "trip") { self.pool_size } else { SUP_POOL_SIZE }
+    }
+
+    // The pool grows up to `sup_pool_size` characters.
+    pub(crate) fn str_room(&mut self, p: usize) -> TeXResult<()> {
+        let needed = (self.pool.pool_ptr + p + 1) as Integer;
+        if needed > self.pool_size {
+            match grown_size(self.pool_size, needed, self.sup_pool_size()) {
+                Some(size) => {
+                    self.pool_size = size;
+                    self.pool.pool.resize(size as usize, 0);
+                },
+                None => return Err(TeXError::Overflow("pool size", self.pool_size - self.pool.init_pool_ptr as Integer))
+            }
         }
+        Ok(())
     }
-}
 
-// Section 43
-pub(crate) fn make_string() -> TeXResult<StrNum> {
-    unsafe {
-        match POOL.str_ptr as Integer {
-            MAX_STRINGS => Err(TeXError::Overflow("number of strings", MAX_STRINGS - POOL.init_str_ptr as Integer)),
+    // Section 43
+    pub(crate) fn make_string(&mut self) -> TeXResult<StrNum> {
+        match self.pool.str_ptr as Integer {
+            n if n == self.max_strings => Err(TeXError::Overflow("number of strings", self.max_strings - self.pool.init_str_ptr as Integer)),
             _ => {
-                POOL.str_ptr += 1;
-                POOL.str_start[POOL.str_ptr] = POOL.pool_ptr;
-                Ok(POOL.str_ptr - 1)
+                self.pool.str_ptr += 1;
+                self.pool.str_start[self.pool.str_ptr] = self.pool.pool_ptr;
+                Ok(self.pool.str_ptr - 1)
             }
         }
     }
-}
 
-// Add a string in the pool
-pub(crate) fn put_string(s: &[u8]) -> TeXResult<StrNum> {
-    unsafe {
-        str_room(s.len())?;
-        POOL.pool[POOL.pool_ptr..(POOL.pool_ptr + s.len())].copy_from_slice(s);
-        POOL.pool_ptr += s.len();
-        make_string()
+    // Add a string in the pool
+    pub(crate) fn put_string(&mut self, s: &[u8]) -> TeXResult<StrNum> {
+        self.str_room(s.len())?;
+        self.pool.pool[self.pool.pool_ptr..(self.pool.pool_ptr + s.len())].copy_from_slice(s);
+        self.pool.pool_ptr += s.len();
+        self.make_string()
     }
-}
 
-// Get a String from a slice of str_pool.
-// Valid utf8 bytes are expected.
-#[cfg(feature = "debug")]
-pub(crate) fn get_string(n: StrNum) -> String {
-    unsafe {
-        String::from_utf8_unchecked(
-            POOL.pool[POOL.str_start[n]..POOL.str_start[n + 1]].into()
-        )
+    // Get a String from a slice of str_pool.
+    // Valid utf8 bytes are expected.
+    #[cfg(feature = "debug")]
+    pub(crate) fn get_string(&self, n: StrNum) -> String {
+        unsafe {
+            String::from_utf8_unchecked(
+                self.pool.pool[self.pool.str_start[n]..self.pool.str_start[n + 1]].into()
+            )
+        }
     }
-}
 
-#[cfg(feature = "debug")]
-pub fn dump_pool() {
-    unsafe {
-        for s in 0..POOL.str_ptr {
-            let chaine = get_string(s);
+    #[cfg(feature = "debug")]
+    pub fn dump_pool(&self) {
+        for s in 0..self.pool.str_ptr {
+            let chaine = self.get_string(s);
             println!("{chaine}");
         }
     }
-}
 
-// Section 44
-pub(crate) fn flush_string() {
-    unsafe {
-        POOL.str_ptr -= 1;
-        POOL.pool_ptr = POOL.str_start[POOL.str_ptr];
+    // Section 44
+    pub(crate) fn flush_string(&mut self) {
+        self.pool.str_ptr -= 1;
+        self.pool.pool_ptr = self.pool.str_start[self.pool.str_ptr];
     }
-}
 
-pub(crate) fn str_pool_slice(s: StrNum) -> &'static [ASCIICode] {
-    unsafe {
-        &POOL.pool[POOL.str_start[s]..POOL.str_start[s + 1]]
+    pub(crate) fn str_pool_slice(&self, s: StrNum) -> &[ASCIICode] {
+        &self.pool.pool[self.pool.str_start[s]..self.pool.str_start[s + 1]]
     }
-}
 
-// Section 46
-// Same as above, we compare with Rust slices
-pub(crate) fn str_eq_str(s: StrNum, t: StrNum) -> bool {
-    if length(s) == length(t) {
-        str_pool_slice(s) == str_pool_slice(t)
-    }
-    else {
-        false
+    // Section 46
+    // Same as above, we compare with Rust slices
+    pub(crate) fn str_eq_str(&self, s: StrNum, t: StrNum) -> bool {
+        if self.length(s) == self.length(t) {
+            self.str_pool_slice(s) == self.str_pool_slice(t)
+        }
+        else {
+            false
+        }
     }
-}
 
-pub fn get_strings_started() -> TeXResult<()> {
+    pub fn get_strings_started(&mut self) -> TeXResult<()> {
+
+        macro_rules! app_lc_hex {
+            ($c:expr) => {
+                let l = $c;
+                if l < 10 {
+                    self.append_char(l + b'0');
+                }
+                else {
+                    self.append_char(l - 10 + b'a');
+                }
+            };
+        }
 
-    macro_rules! app_lc_hex {
-        ($c:expr) => {
-            let l = $c;
-            if l < 10 {
-                append_char(l + b'0');
+        self.pool_ptr_set(0);
+        self.str_ptr_set(0);
+        *self.str_start_mut(0) = 0;
+
+        // Section 48
+        for k in 0..=255 {
+            // Section 49 (condition)
+            if !(32..=126).contains(&k) {
+                self.append_char(b'^');
+                self.append_char(b'^');
+                if k < 64 {
+                    self.append_char(k + 64);
+                }
+                else if k < 128 {
+                    self.append_char(k - 64);
+                }
+                else {
+                    app_lc_hex!(k / 16);
+                    app_lc_hex!(k % 16);
+                }
             }
             else {
-                append_char(l - 10 + b'a');
+                self.append_char(k);
             }
-        };
-    }
+            _ = self.make_string()?
+        }
 
-    pool_ptr_set(0);
-    str_ptr_set(0);
-    *str_start_mut(0) = 0;
+        // Section 51
+        // We don't use TEX.POOL, but we add some strings below.
 
-    // Section 48
-    for k in 0..=255 {
-        // Section 49 (condition)
-        if !(32..=126).contains(&k) {
-            append_char(b'^');
-            append_char(b'^');
-            if k < 64 {
-                append_char(k + 64);
-            }
-            else if k < 128 {
-                append_char(k - 64);
-            }
-            else {
-                app_lc_hex!(k / 16);
-                app_lc_hex!(k % 16);
-            }
-        }
-        else {
-            append_char(k);
-        }
-        _ = make_string()?
-    }
+        // Empty string "", its number should be 256.
+        _ = self.make_string()?;
 
-    // Section 51
-    // We don't use TEX.POOL, but we add some strings below.
+        // File extensions, we put it once.
+        // ".tex": 257
 
-    // Empty string "", its number should be 256.
-    _ = make_string()?;
+        _ = self.put_string(b".tex")?;
 
-    // File extensions, we put it once.
-    // ".tex": 257
+        // ".log": 258
+        _ = self.put_string(b".log")?;
 
-    _ = put_string(b".tex")?;
+        // ".dvi": 259
+        _ = self.put_string(b".dvi")?;
 
-    // ".log": 258
-    _ = put_string(b".log")?;
+        // ".fmt": 260
+        _ = self.put_string(b".fmt")?;
 
-    // ".dvi": 259
-    _ = put_string(b".dvi")?;
+        // ".tfm": 261
+        _ = self.put_string(b".tfm")?;
 
-    // ".fmt": 260
-    _ = put_string(b".fmt")?;
+        // TEX_AREA: 262
+        _ = self.put_string(TEX_AREA_STRING.as_bytes())?;
 
-    // ".tfm": 261
-    _ = put_string(b".tfm")?;
+        // TEX_FONT_AREA: 263
+        _ = self.put_string(TEX_FONT_AREA_STRING.as_bytes())?;
 
-    // TEX_AREA: 262
-    _ = put_string(TEX_AREA_STRING.as_bytes())?;
+        // FONT_STRING: 264
+        _ = self.put_string(b"FONT")?;
 
-    // TEX_FONT_AREA: 263
-    _ = put_string(TEX_FONT_AREA_STRING.as_bytes())?;
+        // NOTEXPANDED_STRING: 265
+        _ = self.put_string(b"notexpanded:")?;
 
-    // FONT_STRING: 264
-    _ = put_string(b"FONT")?;
+        // NULLFONT_STRING: 266
+        _ = self.put_string(b"nullfont")?;
 
-    // NOTEXPANDED_STRING: 265
-    _ = put_string(b"notexpanded:")?;
+        // INACCESSIBLE_STRING: 267
+        _ = self.put_string(b"inaccessible")?;
 
-    // NULLFONT_STRING: 266
-    _ = put_string(b"nullfont")?;
+        // INITEX_IDENT_STRING: 268
+        _ = self.put_string(b" (INITEX)")?;
 
-    // INACCESSIBLE_STRING: 267
-    _ = put_string(b"inaccessible")?;
+        // ENDWRITE_STRING: 269
+        _ = self.put_string(b"endwrite")?;
 
-    // INITEX_IDENT_STRING: 268
-    _ = put_string(b" (INITEX)")?;
+        // ENDTEMPLATE_STRING: 270
+        _ = self.put_string(b"endtemplate")?;
 
-    // ENDWRITE_STRING: 269
-    _ = put_string(b"endwrite")?;
+        // ".pdf": 271
+        _ = self.put_string(b".pdf")?;
 
-    // ENDTEMPLATE_STRING: 270
-    _ = put_string(b"endtemplate")?;
+        // ".svg": 272
+        _ = self.put_string(b".svg")?;
     
-    Ok(())
+        Ok(())
+    }
 }
 
 impl Global {
     // Section 45
     // We use slices comparison directly
     pub(crate) fn str_eq_buf(&self, s: StrNum, k: usize) -> bool {
-        let a = str_pool_slice(s);
+        let a = self.str_pool_slice(s);
         let b = &self.buffer[k..(k + a.len())];
         a == b
     }
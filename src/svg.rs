@@ -0,0 +1,293 @@
+use crate::constants::*;
+use crate::arithmetic::real;
+use crate::error::TeXResult;
+use crate::io::{ByteFileOut, ByteFileOutSelector};
+use crate::{Global, HalfWord, Integer, QuarterWord, Scaled};
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+// SVG output
+// Each page is written in its own file, `job-1.svg`, `job-2.svg`, ...
+// Pages are shipped out by `hlist_out` and `vlist_out` as for DVI,
+// rules become `<rect>` elements and characters are `<text>` elements
+// with the position of each character, computed with the TFM widths.
+//
+// The picture is the box that is shipped out, moved by `\hoffset`
+// and `\voffset`, without the one inch margins of DVI.
+// Its unit is the big point (1/72 inch), and `\mag` scales everything.
+
+pub(crate) struct SvgFile {
+    pub(crate) file: ByteFileOut,
+    // Number of bytes written in all files.
+    bytes: usize,
+    // Name of the last file written.
+    last_name: String,
+    // From scaled points to big points, including `\mag`.
+    scale: f64,
+    width: f64,
+    height: f64,
+    // Elements of the current page.
+    content: String,
+    fonts: BTreeSet<QuarterWord>,
+    // The `<text>` element being built: its font, baseline,
+    // the position of each character, and the characters.
+    text_font: QuarterWord,
+    text_v: Scaled,
+    text_x: Vec<String>,
+    text: String,
+}
+
+impl SvgFile {
+    pub(crate) fn new() -> Self {
+        Self {
+            file: ByteFileOut::new(),
+            bytes: 0,
+            last_name: String::new(),
+            scale: 0.0,
+            width: 0.0,
+            height: 0.0,
+            content: String::new(),
+            fonts: BTreeSet::new(),
+            text_font: NULL_FONT as QuarterWord,
+            text_v: 0,
+            text_x: vec![],
+            text: String::new(),
+        }
+    }
+
+    fn end_text(&mut self) {
+        if self.text_x.is_empty() {
+            return;
+        }
+        let y = real(self.text_v as f64 * self.scale, DECIMALS);
+        _ = writeln!(
+            self.content,
+            "<text class=\"f{}\" x=\"{}\" y=\"{y}\">{}</text>",
+            self.text_font,
+            self.text_x.join(" "),
+            self.text
+        );
+        self.text_x.clear();
+        self.text.clear();
+    }
+}
+
+// Reals are written with at most three decimals.
+const DECIMALS: usize = 3;
+
+// Printable ASCII characters stand for themselves, the other codes
+// of the font are put in the Unicode private use area.
+fn push_char(text: &mut String, c: u8) {
+    match c {
+        b'<' => text.push_str("&lt;"),
+        b'>' => text.push_str("&gt;"),
+        b'&' => text.push_str("&amp;"),
+        32..=126 => text.push(c as char),
+        _ => text.push(char::from_u32(0xE000 + c as u32).unwrap())
+    }
+}
+
+impl Global {
+    // Called by `ship_out` before the box `p` is output.
+    pub(crate) fn svg_begin_page(&mut self, p: HalfWord) -> TeXResult<()> {
+        if self.job_name == 0 {
+            self.open_log_file()?;
+        }
+        if self.total_pages == 0 {
+            self.prepare_mag()?;
+            self.svg.scale = (self.mag() as f64 / 1000.0) * (72.0 / 72.27) / 65536.0;
+        }
+        // The file `job-n.svg` for page n.
+        self.pack_job_name(EXT_SVG);
+        self.name_of_file.truncate(self.name_of_file.len() - 4);
+        _ = write!(self.name_of_file, "-{}.svg", self.total_pages + 1);
+        self.b_open_out(ByteFileOutSelector::SvgFile)?;
+        if self.output_file_name == 0 {
+            self.output_file_name = self.make_name_string()?;
+        }
+        self.svg.last_name = self.name_of_file.clone();
+
+        self.svg.width = (self.width(p) + self.h_offset()) as f64 * self.svg.scale;
+        self.svg.height = (self.height(p) + self.depth(p) + self.v_offset()) as f64 * self.svg.scale;
+        self.svg.content.clear();
+        self.svg.fonts.clear();
+        Ok(())
+    }
+
+    // Character `c` of font `f` at (`cur_h`, `cur_v`).
+    pub(crate) fn svg_char(&mut self) {
+        if self.f != self.svg.text_font || self.cur_v != self.svg.text_v {
+            self.svg.end_text();
+            self.svg.text_font = self.f;
+            self.svg.text_v = self.cur_v;
+            self.svg.fonts.insert(self.f);
+        }
+        let x = real(self.cur_h as f64 * self.svg.scale, DECIMALS);
+        self.svg.text_x.push(x);
+        push_char(&mut self.svg.text, self.c as u8);
+    }
+
+    // Rule of size `rule_wd` by `rule_ht`, whose bottom left corner
+    // is at (`cur_h`, `cur_v`).
+    pub(crate) fn svg_rule(&mut self) {
+        self.svg.end_text();
+        let scale = self.svg.scale;
+        _ = writeln!(
+            self.svg.content,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            real(self.cur_h as f64 * scale, DECIMALS),
+            real((self.cur_v - self.rule_ht) as f64 * scale, DECIMALS),
+            real(self.rule_wd as f64 * scale, DECIMALS),
+            real(self.rule_ht as f64 * scale, DECIMALS)
+        );
+    }
+
+    // Called by `ship_out` once the box is output: the file is written.
+    pub(crate) fn svg_end_page(&mut self) {
+        self.svg.end_text();
+        self.svg.text_font = NULL_FONT as QuarterWord;
+        let (width, height) = (real(self.svg.width, DECIMALS), real(self.svg.height, DECIMALS));
+        let mut svg = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}pt\" height=\"{height}pt\" viewBox=\"0 0 {width} {height}\">\n\
+            <style>\n\
+            text {{ white-space: pre; }}\n"
+        );
+        // Fonts are named after their TFM files.
+        for &f in &self.svg.fonts {
+            let size = self.font_size[f as usize] as f64 * self.svg.scale;
+            _ = writeln!(
+                svg,
+                ".f{f} {{ font-family: {}; font-size: {}px; }}",
+                String::from_utf8_lossy(self.str_pool_slice(self.font_name[f as usize])),
+                real(size, DECIMALS)
+            );
+        }
+        svg.push_str("</style>\n");
+        svg.push_str(&self.svg.content);
+        svg.push_str("</svg>\n");
+        self.svg.file.write(svg.as_bytes());
+        self.svg.file.close();
+        self.svg.bytes += svg.len();
+    }
+
+    // Section 642, for SVG
+    pub(crate) fn svg_finish_file(&mut self) {
+        if self.cur_s > -1 {
+            self.svg_end_page();
+            self.total_pages += 1;
+            self.cur_s = -1;
+        }
+        if self.total_pages == 0 {
+            self.print_nl("No pages of output.");
+            return;
+        }
+        self.print_nl("Output written on ");
+        self.slow_print(self.output_file_name);
+        if self.total_pages > 1 {
+            self.print(" to ");
+            let last_name = self.svg.last_name.clone();
+            self.print(&last_name);
+        }
+        self.print(" (");
+        self.print_int(self.total_pages);
+        self.print(" page");
+        if self.total_pages != 1 {
+            self.print_char(b's');
+        }
+        self.print(", ");
+        self.print_int(self.svg.bytes as Integer);
+        self.print(" bytes).");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OutputFormat;
+    use crate::capacities::Capacities;
+    use crate::compile::tests::{MINIMAL_FORMAT, dump_format};
+    use crate::compile::{CompileOptions, CompileOutput, compile};
+
+    const CMR10: &[u8] = include_bytes!("../TeXfonts/cmr10.tfm");
+
+    fn compile_svg(source: &str) -> CompileOutput {
+        let format = dump_format(MINIMAL_FORMAT, &Capacities::default());
+        let options = CompileOptions {
+            format: &format,
+            job_name: "job",
+            output_format: OutputFormat::Svg,
+            inputs: &[("TeXfonts/cmr10.tfm", CMR10)],
+            ..CompileOptions::default()
+        };
+        compile(source, &options)
+    }
+
+    // The elements are properly nested in a single `<svg>` element,
+    // and the text has no markup characters left.
+    fn assert_well_formed(svg: &str) {
+        let body = svg.strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n").expect("no XML declaration");
+        let mut open: Vec<&str> = vec![];
+        let mut roots = 0;
+        let mut rest = body;
+        while let Some(start) = rest.find(['<', '>', '&']) {
+            assert!(rest[..start].trim().is_empty() || !open.is_empty(), "text outside of <svg>");
+            rest = &rest[start..];
+            if rest.starts_with('&') {
+                let end = rest.find(';').expect("unterminated entity");
+                assert!(["&lt;", "&gt;", "&amp;"].contains(&&rest[..=end]), "{}", &rest[..=end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            assert!(rest.starts_with('<'), "'>' in the text");
+            let end = rest.find('>').expect("unterminated tag");
+            let tag = &rest[1..end];
+            assert!(!tag.contains('<'), "'<' in a tag: {tag}");
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name), "</{name}> does not match");
+            }
+            else if !tag.ends_with('/') {
+                let name = tag.split(' ').next().unwrap();
+                if open.is_empty() {
+                    roots += 1;
+                }
+                open.push(name);
+            }
+            rest = &rest[end + 1..];
+        }
+        assert!(open.is_empty(), "unclosed elements: {open:?}");
+        assert!(rest.trim().is_empty());
+        assert_eq!(roots, 1);
+        assert!(body.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" "));
+    }
+
+    #[test]
+    fn one_file_per_page() {
+        // One inch is 72 big points, the unit of the pictures.
+        let output = compile_svg(
+            "\\font\\x=cmr10 \\x\n\
+            \\shipout\\hbox{\\vrule width 72.27pt height 72.27pt depth 0pt}\n\
+            \\hoffset=72.27pt \\voffset=36.135pt\n\
+            \\shipout\\hbox{A&<\\vrule width 72.27pt height 72.27pt depth 0pt}\n\
+            \\end\n"
+        );
+        assert!(output.errors.is_empty(), "{}", output.log);
+        assert!(output.log.contains("Output written on job-1.svg to job-2.svg (2 pages, "), "{}", output.log);
+        assert_eq!(output.svg.len(), 2);
+        let pages: Vec<String> = output.svg.iter().map(|svg| String::from_utf8(svg.clone()).unwrap()).collect();
+        for page in &pages {
+            assert_well_formed(page);
+        }
+
+        assert!(pages[0].contains("width=\"72pt\" height=\"72pt\" viewBox=\"0 0 72 72\""), "{}", pages[0]);
+        assert!(pages[0].contains("<rect x=\"0\" y=\"0\" width=\"72\" height=\"72\"/>\n"), "{}", pages[0]);
+        assert!(!pages[0].contains("<text"));
+
+        // The box is moved by `\hoffset` and `\voffset`: one inch to the right
+        // and half an inch down, the `<` of cmr10 (an inverted `!`) has a depth.
+        assert!(pages[1].contains("width=\"161.988pt\" height=\"109.937pt\" viewBox=\"0 0 161.988 109.937\""), "{}", pages[1]);
+        assert!(pages[1].contains("<rect x=\"89.988\" y=\"36\" width=\"72\" height=\"72\"/>\n"), "{}", pages[1]);
+        assert!(pages[1].contains(".f1 { font-family: cmr10; font-size: 9.963px; }\n"), "{}", pages[1]);
+        assert!(pages[1].contains("<text class=\"f1\" x=\"72 79.472 87.221\" y=\"108\">A&amp;&lt;</text>\n"), "{}", pages[1]);
+    }
+}
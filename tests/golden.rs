@@ -0,0 +1,180 @@
+#![cfg(not(feature = "trip"))]
+
+use tex_rust::dvi::{DviDiffOptions, dvi_diff};
+use tex_rust::DiskFileSystem;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+// Golden regression tests
+// The plain format is dumped in INITEX mode from `TeXinputs/plain.tex`,
+// then each file of `examples/` (and `TeXinputs/story.tex`) is typeset.
+// The DVI file and the log file are compared to the ones in `tests/golden/`,
+// once the dates they contain are removed.
+//
+// When the output changes on purpose, the golden files are written again with
+//     UPDATE_GOLDEN=1 cargo test --test golden
+
+const TEX: &str = env!("CARGO_BIN_EXE_tex-rust");
+
+// The format is dumped under a name that is never embedded, and loaded
+// by its path: an embedded `plain.fmt`, which comes before the files and
+// may be stale, is not used.
+const FORMAT: &str = "golden-plain";
+
+fn crate_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn copy_files(from: &Path, to: &Path, extension: &str) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == extension) {
+            fs::copy(&path, to.join(path.file_name().unwrap())).unwrap();
+        }
+    }
+}
+
+fn run_tex(dir: &Path, args: &[&str]) {
+    let output = Command::new(TEX)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "tex-rust {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+// The directory where the documents are typeset, with the format
+// dumped once for all tests.
+fn work_dir() -> &'static Path {
+    static WORK_DIR: OnceLock<PathBuf> = OnceLock::new();
+    WORK_DIR.get_or_init(|| {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        _ = fs::remove_dir_all(&dir);
+        copy_files(&crate_dir().join("TeXinputs"), &dir.join("TeXinputs"), "tex");
+        copy_files(&crate_dir().join("TeXfonts"), &dir.join("TeXfonts"), "tfm");
+        copy_files(&crate_dir().join("examples"), &dir, "tex");
+        run_tex(&dir, &["-ini", "-interaction=nonstopmode", &format!("-jobname={FORMAT}"), "plain", "\\dump"]);
+        dir
+    })
+}
+
+// The comment of the preamble, `TeX output YYYY.MM.DD:HHMM`, has its digits
+// replaced by zeros. Its length does not change, nor the rest of the file.
+fn normalize_dvi(mut dvi: Vec<u8>) -> Vec<u8> {
+    if dvi.len() > 15 {
+        let k = dvi[14] as usize;
+        for b in dvi.iter_mut().skip(15).take(k) {
+            if b.is_ascii_digit() {
+                *b = b'0';
+            }
+        }
+    }
+    dvi
+}
+
+// The first line of the log holds the date of the format and the date of the run.
+fn normalize_log(log: Vec<u8>) -> Vec<u8> {
+    let log = String::from_utf8_lossy(&log);
+    let (first_line, rest) = log.split_once('\n').unwrap_or((&log, ""));
+    let banner = first_line.split(" (").next().unwrap();
+    format!("{banner}\n{rest}").into_bytes()
+}
+
+fn golden(name: &str, first_line: &[&str]) {
+    let dir = work_dir();
+    let fmt = format!("-fmt=./{FORMAT}.fmt");
+    let mut args = vec![fmt.as_str(), "-interaction=nonstopmode"];
+    args.extend_from_slice(first_line);
+    run_tex(dir, &args);
+
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let golden_dir = crate_dir().join("tests").join("golden");
+    let dvi = normalize_dvi(fs::read(dir.join(format!("{name}.dvi"))).unwrap());
+    let log = normalize_log(fs::read(dir.join(format!("{name}.log"))).unwrap());
+    let dvi_golden = golden_dir.join(format!("{name}.dvi"));
+    let log_golden = golden_dir.join(format!("{name}.log"));
+    if update {
+        fs::create_dir_all(&golden_dir).unwrap();
+        fs::write(&dvi_golden, &dvi).unwrap();
+        fs::write(&log_golden, &log).unwrap();
+        return;
+    }
+
+    let expected = fs::read(&dvi_golden).unwrap_or_else(|_| panic!("no golden file {}", dvi_golden.display()));
+    if dvi != expected {
+        // The fonts are read from `TeXfonts/` of the crate.
+        let options = DviDiffOptions { ignore_comment: true, ignore_specials: false };
+        let report = match dvi_diff(&expected, &dvi, &options, &mut DiskFileSystem) {
+            Ok(Some(difference)) => difference.report(["golden", "output"]),
+            Ok(None) => String::from("the files typeset the same pages but are not identical\n"),
+            Err(message) => format!("{message}\n")
+        };
+        panic!("{name}.dvi differs from the golden file:\n{report}");
+    }
+    let expected = String::from_utf8_lossy(&fs::read(&log_golden).unwrap()).into_owned();
+    let log = String::from_utf8_lossy(&log).into_owned();
+    if log != expected {
+        let line = expected.lines().zip(log.lines()).position(|(a, b)| a != b)
+            .unwrap_or_else(|| expected.lines().count().min(log.lines().count()));
+        panic!(
+            "{name}.log differs from the golden file at line {}:\n  golden: {}\n  output: {}",
+            line + 1,
+            expected.lines().nth(line).unwrap_or("(end of file)"),
+            log.lines().nth(line).unwrap_or("(end of file)")
+        );
+    }
+}
+
+// One test per document, named after its job. The names are also listed
+// in `GOLDEN_TESTS`, to check that every example is tested.
+macro_rules! golden_tests {
+    ($($test:ident: $($arg:literal)+;)*) => {
+        $(
+            #[test]
+            fn $test() {
+                golden(stringify!($test), &[$($arg),+]);
+            }
+        )*
+
+        const GOLDEN_TESTS: &[&str] = &[$(stringify!($test)),*];
+    };
+}
+
+golden_tests! {
+    alignment: "alignment";
+    formulas: "formulas";
+    lipsum: "lipsum";
+    misc: "misc";
+    parshape: "parshape";
+    primes: "primes";
+    stories: "stories";
+    // `story.tex` does not end with `\end`.
+    story: "story" "\\end";
+}
+
+// A new file in `examples/` needs its line above and its golden files.
+#[test]
+fn every_example_is_tested() {
+    for entry in fs::read_dir(crate_dir().join("examples")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "tex") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        assert!(GOLDEN_TESTS.contains(&name.as_str()), "examples/{name}.tex has no golden test");
+        for ext in ["dvi", "log"] {
+            let golden = crate_dir().join("tests").join("golden").join(format!("{name}.{ext}"));
+            assert!(golden.exists(), "no golden file {}", golden.display());
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// The TRIP test
+// It needs the constants of `tripman.tex`, given by the `trip` feature:
+//     cargo test --features trip --test trip
+// Without it, the test is ignored.
+// The files of the test are not part of the repository, they are read
+// from `tests/trip/`, where `make trip` downloads them before running
+// the test: the input files `trip.tex`, `trip1.in`, `trip2.in` and
+// `trip.tfm`, and the canonical results `tripin.log`, `trip.log`,
+// `trip.fot` and `trip.typ`.
+//
+// As in `tripman.tex`, INITEX reads `trip1.in` to dump `trip.fmt`,
+// then `trip2.in` to load it and run `trip.tex` again, and the DVI file
+// is listed by DVItype.
+
+const TEX: &str = env!("CARGO_BIN_EXE_tex-rust");
+
+const INPUTS: [&str; 4] = ["trip.tex", "trip1.in", "trip2.in", "trip.tfm"];
+const RESULTS: [&str; 4] = ["tripin.log", "trip.log", "trip.fot", "trip.typ"];
+
+fn trip_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("trip")
+}
+
+// INITEX with `input` as terminal, the terminal output is returned.
+fn run_initex(dir: &Path, input: &str) -> Vec<u8> {
+    let input = fs::File::open(trip_dir().join(input)).unwrap();
+    Command::new(TEX)
+        .arg("-ini")
+        .current_dir(dir)
+        .stdin(input)
+        .output()
+        .unwrap()
+        .stdout
+}
+
+fn run_dvitype(dir: &Path) -> Vec<u8> {
+    Command::new(TEX)
+        .args([
+            "dvitype",
+            "-output-level=2",
+            "-page-start=*.*.*.*.*.*.*.*.*.*",
+            "-max-pages=1000000",
+            "-dpi=72.27",
+            "-magnification=2000",
+            "trip.dvi"
+        ])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+        .stdout
+}
+
+// `tripman.tex` allows the statistics about strings and the capacities
+// (the numbers after `out of`) to differ from the canonical results.
+fn normalize(text: &str) -> Vec<String> {
+    let hide_digits = |s: &str| s.chars().map(|c| if c.is_ascii_digit() { '#' } else { c }).collect::<String>();
+    text.lines()
+        .map(|line| {
+            if line.contains(" strings out of ") || line.contains(" string characters out of ") {
+                hide_digits(line)
+            }
+            else if let Some((used, capacity)) = line.split_once(" out of ") {
+                format!("{used} out of {}", hide_digits(capacity))
+            }
+            else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+// The first line that differs, if any.
+fn compare(name: &str, dir: &Path) -> Option<String> {
+    let expected = fs::read(trip_dir().join(name)).unwrap();
+    let Ok(found) = fs::read(dir.join(name)) else {
+        return Some(format!("{name}: the file was not written"));
+    };
+    let expected = normalize(&String::from_utf8_lossy(&expected));
+    let found = normalize(&String::from_utf8_lossy(&found));
+    let end = String::from("(end of file)");
+    (0..expected.len().max(found.len()))
+        .find(|&k| expected.get(k) != found.get(k))
+        .map(|k| format!(
+            "{name}, line {}:\n  canonical: {}\n  found:     {}",
+            k + 1,
+            expected.get(k).unwrap_or(&end),
+            found.get(k).unwrap_or(&end)
+        ))
+}
+
+#[test]
+#[cfg_attr(not(feature = "trip"), ignore = "the TRIP test needs the trip feature")]
+fn trip() {
+    let missing: Vec<&str> = INPUTS.iter().chain(&RESULTS)
+        .filter(|name| !trip_dir().join(name).exists())
+        .copied()
+        .collect();
+    let pltotf = if missing.contains(&"trip.tfm") {
+        "; trip.tfm is made from trip.pl with `pltotf trip.pl trip.tfm`"
+    }
+    else {
+        ""
+    };
+    assert!(
+        missing.is_empty(),
+        "the TRIP test needs these files in {}: {}\n\
+        `make trip` downloads them and runs the test. \
+        They are in https://ctan.org/tex-archive/systems/knuth/dist/tex{pltotf}.",
+        trip_dir().display(),
+        missing.join(", ")
+    );
+
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("trip");
+    _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("TeXfonts")).unwrap();
+    fs::copy(trip_dir().join("trip.tex"), dir.join("trip.tex")).unwrap();
+    fs::copy(trip_dir().join("trip.tfm"), dir.join("TeXfonts").join("trip.tfm")).unwrap();
+
+    // First run: `tripin.log` and `trip.fmt`.
+    run_initex(&dir, "trip1.in");
+    fs::rename(dir.join("trip.log"), dir.join("tripin.log")).unwrap();
+    assert!(dir.join("trip.fmt").exists(), "trip.fmt was not dumped, see {}", dir.join("tripin.log").display());
+
+    // Second run: `trip.log`, `trip.fot` and `trip.dvi`.
+    let fot = run_initex(&dir, "trip2.in");
+    fs::write(dir.join("trip.fot"), fot).unwrap();
+    let typ = run_dvitype(&dir);
+    fs::write(dir.join("trip.typ"), typ).unwrap();
+
+    let differences: Vec<String> = RESULTS.iter().filter_map(|name| compare(name, &dir)).collect();
+    assert!(
+        differences.is_empty(),
+        "the TRIP test fails (the files are in {}):\n{}",
+        dir.display(),
+        differences.join("\n")
+    );
+}